@@ -0,0 +1,2 @@
+-- Add version column for optimistic concurrency control on item toggles
+ALTER TABLE shopping_list_items ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,27 @@
+-- Weekly activity summaries generated by the scheduler
+CREATE TABLE IF NOT EXISTS group_summaries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    period_start TIMESTAMP NOT NULL,
+    period_end TIMESTAMP NOT NULL,
+    debt_count INTEGER NOT NULL,
+    total_amount TEXT NOT NULL,  -- Stored as TEXT to maintain DECIMAL precision
+    largest_expense_name TEXT,
+    largest_expense_amount TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE
+);
+
+-- Net balance movement of each member during the summary period
+CREATE TABLE IF NOT EXISTS group_summary_members (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    summary_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    net_change TEXT NOT NULL,  -- Positive: member is owed more, negative: member owes more
+    FOREIGN KEY (summary_id) REFERENCES group_summaries(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(summary_id, user_id)
+);
+
+CREATE INDEX idx_group_summaries_group_id ON group_summaries(group_id, created_at);
+CREATE INDEX idx_group_summary_members_summary_id ON group_summary_members(summary_id);
@@ -0,0 +1,13 @@
+-- Per-group feature flags for gradual rollout of experimental features
+-- Keys without a row are treated as disabled
+CREATE TABLE IF NOT EXISTS feature_flags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    key TEXT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    UNIQUE(group_id, key)
+);
+
+CREATE INDEX idx_feature_flags_group_id ON feature_flags(group_id);
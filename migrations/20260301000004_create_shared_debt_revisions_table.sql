@@ -0,0 +1,18 @@
+-- Edit history for shared debts, written by update_shared_debt
+-- Revisions live as long as the debt row itself
+CREATE TABLE IF NOT EXISTS shared_debt_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    shared_debt_id INTEGER NOT NULL,
+    edited_by INTEGER,
+    old_name TEXT NOT NULL,
+    new_name TEXT NOT NULL,
+    old_amount TEXT NOT NULL,
+    new_amount TEXT NOT NULL,
+    old_member_ids TEXT NOT NULL,  -- JSON array of user ids
+    new_member_ids TEXT NOT NULL,  -- JSON array of user ids
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (shared_debt_id) REFERENCES shared_debts(id) ON DELETE CASCADE,
+    FOREIGN KEY (edited_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_shared_debt_revisions_debt_id ON shared_debt_revisions(shared_debt_id, created_at);
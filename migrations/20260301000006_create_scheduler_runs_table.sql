@@ -0,0 +1,11 @@
+-- Outcome of each recurring debts scheduler run, pruned to the most recent rows
+CREATE TABLE IF NOT EXISTS scheduler_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    started_at TIMESTAMP NOT NULL,
+    finished_at TIMESTAMP,  -- NULL while the run is in progress (or if it crashed)
+    generated_count INTEGER NOT NULL DEFAULT 0,
+    error TEXT,  -- NULL on success
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_scheduler_runs_started_at ON scheduler_runs(started_at);
@@ -0,0 +1,5 @@
+-- Last time each periodic maintenance task ran
+CREATE TABLE IF NOT EXISTS maintenance_state (
+    task TEXT PRIMARY KEY NOT NULL,
+    last_run_at TIMESTAMP NOT NULL
+);
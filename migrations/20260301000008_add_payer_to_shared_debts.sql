@@ -0,0 +1,6 @@
+-- Member who actually paid a shared debt; created_by stays the member who entered it
+ALTER TABLE shared_debts ADD COLUMN payer_id INTEGER REFERENCES users(id) ON DELETE CASCADE;
+
+UPDATE shared_debts SET payer_id = created_by WHERE payer_id IS NULL;
+
+CREATE INDEX idx_shared_debts_payer_id ON shared_debts(payer_id);
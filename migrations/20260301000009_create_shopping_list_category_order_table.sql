@@ -0,0 +1,8 @@
+-- Per-list aisle order for item categories
+CREATE TABLE IF NOT EXISTS shopping_list_category_order (
+    shopping_list_id INTEGER NOT NULL,
+    category TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (shopping_list_id, category),
+    FOREIGN KEY (shopping_list_id) REFERENCES shopping_lists(id) ON DELETE CASCADE
+);
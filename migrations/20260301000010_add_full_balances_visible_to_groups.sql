@@ -0,0 +1,2 @@
+-- Whether non-creator members may load the full group balance matrix
+ALTER TABLE groups ADD COLUMN full_balances_visible BOOLEAN NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+-- Explicit share of a member in a shared debt; NULL means the debt is split
+-- equally between its members
+ALTER TABLE shared_debt_user ADD COLUMN share_amount TEXT;
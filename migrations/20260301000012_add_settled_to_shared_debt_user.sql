@@ -0,0 +1,4 @@
+-- A member's share of a shared debt can be settled on its own; settled shares
+-- no longer count towards balances
+ALTER TABLE shared_debt_user ADD COLUMN is_settled BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE shared_debt_user ADD COLUMN settled_at TIMESTAMP;
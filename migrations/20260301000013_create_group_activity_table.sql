@@ -0,0 +1,19 @@
+-- Audit trail of changes to a group's debts and transactions
+--
+-- Names are stored denormalized and entity_id has no foreign key, so the
+-- history stays readable after the entity or the acting user is deleted.
+CREATE TABLE group_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    actor_id INTEGER,
+    actor_username TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (actor_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_group_activity_group_id ON group_activity(group_id, created_at);
@@ -0,0 +1,3 @@
+-- Relative weight of a member when splitting a recurring debt; a member with
+-- weight 2 owes twice as much as a member with weight 1
+ALTER TABLE recurring_debt_user ADD COLUMN weight INTEGER NOT NULL DEFAULT 1;
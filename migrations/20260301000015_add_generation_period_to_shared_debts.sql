@@ -0,0 +1,7 @@
+-- Date a generated shared debt covers. Unique per recurring debt so the
+-- scheduler and "Generate Now" can't both create the same instance; NULL for
+-- debts created by hand.
+ALTER TABLE shared_debts ADD COLUMN generation_period TEXT;
+
+CREATE UNIQUE INDEX idx_shared_debts_generation_period
+    ON shared_debts(recurring_debt_id, generation_period);
@@ -0,0 +1,8 @@
+-- Role of a member within a group; admins can manage the group like its
+-- creator, who is always an admin
+ALTER TABLE group_members ADD COLUMN role TEXT NOT NULL DEFAULT 'member'
+    CHECK (role IN ('member', 'admin'));
+
+UPDATE group_members
+SET role = 'admin'
+WHERE user_id = (SELECT created_by FROM groups WHERE groups.id = group_members.group_id);
@@ -0,0 +1,4 @@
+-- Custom "every N days/weeks" schedules keep the daily or weekly frequency
+-- and store N here; every other schedule uses 1
+ALTER TABLE recurring_debts
+    ADD COLUMN frequency_interval INTEGER NOT NULL DEFAULT 1 CHECK (frequency_interval >= 1);
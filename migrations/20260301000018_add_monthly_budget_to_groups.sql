@@ -0,0 +1,3 @@
+-- Monthly spending budget of a group in euros, stored as TEXT for rust_decimal
+-- precision; NULL means the group has no budget
+ALTER TABLE groups ADD COLUMN monthly_budget TEXT;
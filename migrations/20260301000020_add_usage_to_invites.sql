@@ -0,0 +1,17 @@
+-- Optional cap on how often an invite can be accepted, and how often it was
+ALTER TABLE invites ADD COLUMN max_uses INTEGER CHECK (max_uses IS NULL OR max_uses >= 1);
+ALTER TABLE invites ADD COLUMN use_count INTEGER NOT NULL DEFAULT 0;
+
+-- Who joined through which invite; invite_uuid has no foreign key so the
+-- record survives the invite being deleted
+CREATE TABLE invite_uses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    invite_uuid TEXT NOT NULL,
+    group_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    used_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_invite_uses_invite_uuid ON invite_uses(invite_uuid);
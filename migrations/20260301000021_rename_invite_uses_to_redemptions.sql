@@ -0,0 +1,10 @@
+-- Invite uses are shown to admins as redemptions
+ALTER TABLE invite_uses RENAME TO invite_redemptions;
+ALTER TABLE invite_redemptions RENAME COLUMN used_at TO redeemed_at;
+
+DROP INDEX idx_invite_uses_invite_uuid;
+CREATE INDEX idx_invite_redemptions_group_id ON invite_redemptions(group_id, invite_uuid);
+
+-- Single-use invites are now kept once used so their redemption stays
+-- visible; cap the older ones that predate use limits
+UPDATE invites SET max_uses = 1 WHERE is_reusable = 0 AND max_uses IS NULL;
@@ -0,0 +1,4 @@
+-- Member responsible for buying an item; cleared when that user is deleted
+ALTER TABLE shopping_list_items ADD COLUMN assigned_to INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_shopping_list_items_assigned_to ON shopping_list_items(assigned_to);
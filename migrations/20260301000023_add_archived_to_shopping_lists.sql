@@ -0,0 +1,5 @@
+-- Archived lists are hidden from the group but keep their items and history
+ALTER TABLE shopping_lists ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE shopping_lists ADD COLUMN archived_at TIMESTAMP;
+
+CREATE INDEX idx_shopping_lists_group_archived ON shopping_lists(group_id, is_archived);
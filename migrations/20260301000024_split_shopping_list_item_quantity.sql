@@ -0,0 +1,31 @@
+-- Structured quantity: a decimal amount (stored as TEXT) and an optional unit.
+-- No unit means a plain count.
+ALTER TABLE shopping_list_items ADD COLUMN quantity_value TEXT;
+ALTER TABLE shopping_list_items ADD COLUMN quantity_unit TEXT;
+
+-- Best-effort split of the old free text: a leading number ("2", "1,5", "2.5")
+-- becomes the amount and whatever follows it ("kg", " cans") the unit
+UPDATE shopping_list_items
+SET quantity_value = REPLACE(
+        SUBSTR(TRIM(quantity), 1, LENGTH(TRIM(quantity)) - LENGTH(LTRIM(TRIM(quantity), '0123456789.,'))),
+        ',', '.'
+    ),
+    quantity_unit = CASE LOWER(TRIM(LTRIM(TRIM(quantity), '0123456789.,')))
+        WHEN '' THEN NULL
+        WHEN 'x' THEN NULL
+        WHEN 'pc' THEN NULL
+        WHEN 'pcs' THEN NULL
+        WHEN 'packs' THEN 'pack'
+        WHEN 'cans' THEN 'can'
+        WHEN 'bottles' THEN 'bottle'
+        ELSE LOWER(TRIM(LTRIM(TRIM(quantity), '0123456789.,')))
+    END
+WHERE TRIM(quantity) GLOB '[0-9]*';
+
+-- Quantities without a leading number ("two kilos") can't be split; keep them
+-- visible by moving them into the name
+UPDATE shopping_list_items
+SET name = name || ' (' || TRIM(quantity) || ')'
+WHERE TRIM(quantity) <> '' AND NOT TRIM(quantity) GLOB '[0-9]*';
+
+ALTER TABLE shopping_list_items DROP COLUMN quantity;
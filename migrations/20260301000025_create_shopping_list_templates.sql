@@ -0,0 +1,25 @@
+-- Reusable item sets a group can start new shopping lists from
+CREATE TABLE shopping_list_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    created_by INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_shopping_list_templates_group_id ON shopping_list_templates(group_id);
+
+CREATE TABLE template_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    template_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    quantity_value TEXT,
+    quantity_unit TEXT,
+    category TEXT,
+    position INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (template_id) REFERENCES shopping_list_templates(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_template_items_template_id ON template_items(template_id, position);
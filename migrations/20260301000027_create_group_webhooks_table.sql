@@ -0,0 +1,21 @@
+-- Outgoing webhooks that notify external services about group events
+--
+-- events holds the subscribed event names separated by commas. The last_*
+-- columns describe the most recent delivery attempt so admins can spot
+-- failing endpoints.
+CREATE TABLE group_webhooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT NOT NULL,
+    created_by INTEGER,
+    last_delivery_at TIMESTAMP,
+    last_status_code INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_group_webhooks_group_id ON group_webhooks(group_id);
@@ -0,0 +1,8 @@
+-- Optional category of a debt, one of a fixed set of names such as
+-- "Groceries"; recurring debts pass theirs on to generated debts
+ALTER TABLE shared_debts ADD COLUMN category TEXT
+    CHECK (category IN ('Groceries', 'Rent', 'Utilities', 'Travel', 'Entertainment', 'Other'));
+ALTER TABLE recurring_debts ADD COLUMN category TEXT
+    CHECK (category IN ('Groceries', 'Rent', 'Utilities', 'Travel', 'Entertainment', 'Other'));
+
+CREATE INDEX idx_shared_debts_group_category ON shared_debts(group_id, category);
@@ -0,0 +1,6 @@
+-- Version counters for optimistic concurrency control: an edit passes the
+-- version it was loaded with and only applies if nobody saved in between
+ALTER TABLE groups ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE shared_debts ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE recurring_debts ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE transactions ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,17 @@
+-- Pairwise balances per group, kept up to date by the handlers that change
+-- debts, shares and payments so balances don't need a full recompute.
+-- amount is what user_a owes user_b, negative when user_b owes user_a.
+CREATE TABLE group_balances (
+    group_id INTEGER NOT NULL,
+    user_a INTEGER NOT NULL,
+    user_b INTEGER NOT NULL,
+    amount TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, user_a, user_b),
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    CHECK (user_a < user_b)
+);
+
+-- NULL until the group's cached balances have been built, or after they were
+-- invalidated; the next balance read rebuilds them
+ALTER TABLE groups ADD COLUMN balances_cached_at TIMESTAMP;
@@ -0,0 +1,5 @@
+-- Optional deadline (YYYY-MM-DD) by which a shared debt should be paid back;
+-- debts past it with unsettled shares are shown as overdue
+ALTER TABLE shared_debts ADD COLUMN due_date DATE;
+
+CREATE INDEX idx_shared_debts_group_due_date ON shared_debts(group_id, due_date);
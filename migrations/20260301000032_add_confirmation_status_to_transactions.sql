@@ -0,0 +1,5 @@
+-- Recipients confirm that a payment arrived or dispute it; disputed payments
+-- don't count towards balances until they are confirmed
+ALTER TABLE transactions ADD COLUMN confirmation_status TEXT NOT NULL DEFAULT 'pending'
+    CHECK (confirmation_status IN ('pending', 'confirmed', 'disputed'));
+ALTER TABLE transactions ADD COLUMN dispute_reason TEXT;
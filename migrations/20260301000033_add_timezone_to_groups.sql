@@ -0,0 +1,3 @@
+-- IANA timezone (e.g. Europe/Berlin) whose calendar days recurring debts are
+-- generated on; NULL keeps the server's APP_TIMEZONE
+ALTER TABLE groups ADD COLUMN timezone TEXT;
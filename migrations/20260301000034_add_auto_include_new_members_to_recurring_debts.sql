@@ -0,0 +1,3 @@
+-- Recurring debts with this flag get everyone who joins the group later added
+-- to their members
+ALTER TABLE recurring_debts ADD COLUMN auto_include_new_members BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Group a user lands on after logging in; NULL sends them to the group list
+ALTER TABLE users ADD COLUMN default_group_id INTEGER REFERENCES groups(id) ON DELETE SET NULL;
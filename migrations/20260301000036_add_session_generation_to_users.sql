@@ -0,0 +1,3 @@
+-- Bumped to log out every session of a user; sessions storing an older value
+-- count as logged out
+ALTER TABLE users ADD COLUMN session_generation INTEGER NOT NULL DEFAULT 0;
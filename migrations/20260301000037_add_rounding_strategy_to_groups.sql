@@ -0,0 +1,2 @@
+-- Who gets the cents left over when a debt does not split evenly
+ALTER TABLE groups ADD COLUMN rounding_strategy TEXT NOT NULL DEFAULT 'distribute_cents';
@@ -0,0 +1,26 @@
+-- Viewers can see a group but not change anything in it. SQLite cannot
+-- change a CHECK constraint, so group_members is rebuilt with the new role.
+CREATE TABLE group_members_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    role TEXT NOT NULL DEFAULT 'member' CHECK (role IN ('member', 'admin', 'viewer')),
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(group_id, user_id)
+);
+
+INSERT INTO group_members_new (id, group_id, user_id, created_at, role)
+SELECT id, group_id, user_id, created_at, role FROM group_members;
+
+DROP TABLE group_members;
+ALTER TABLE group_members_new RENAME TO group_members;
+
+CREATE INDEX idx_group_members_group_id ON group_members(group_id);
+CREATE INDEX idx_group_members_user_id ON group_members(user_id);
+CREATE INDEX idx_group_members_lookup ON group_members(group_id, user_id);
+
+-- Role given to whoever accepts an invite
+ALTER TABLE invites ADD COLUMN grants_role TEXT NOT NULL DEFAULT 'member'
+    CHECK (grants_role IN ('member', 'viewer'));
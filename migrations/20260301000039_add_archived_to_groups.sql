@@ -0,0 +1,2 @@
+-- Archived groups stay viewable but can no longer be changed
+ALTER TABLE groups ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- How amounts are written for the user: 'dot' for 1,234.50, 'comma' for 1.234,50
+ALTER TABLE users ADD COLUMN number_format TEXT NOT NULL DEFAULT 'dot';
@@ -0,0 +1,19 @@
+-- Short code users can enter to ask an admin to let them into a group; NULL
+-- while joining by code is turned off
+ALTER TABLE groups ADD COLUMN join_code TEXT;
+CREATE UNIQUE INDEX idx_groups_join_code ON groups(join_code) WHERE join_code IS NOT NULL;
+
+CREATE TABLE group_join_requests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    group_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    decided_at TIMESTAMP,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- One open request per user and group; decided ones are kept as history
+CREATE UNIQUE INDEX idx_group_join_requests_pending
+    ON group_join_requests(group_id, user_id) WHERE status = 'pending';
@@ -0,0 +1,6 @@
+-- Debts paid in another currency keep what was paid and the hand-entered
+-- rate to euros; `amount` holds the converted value. All three are set
+-- together or not at all.
+ALTER TABLE shared_debts ADD COLUMN original_amount TEXT;
+ALTER TABLE shared_debts ADD COLUMN original_currency TEXT;
+ALTER TABLE shared_debts ADD COLUMN exchange_rate TEXT;
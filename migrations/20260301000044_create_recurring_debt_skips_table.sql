@@ -0,0 +1,14 @@
+-- Generations of a recurring debt skipped on purpose, e.g. while the cleaner
+-- is on vacation. Undoing a skip deletes its row.
+CREATE TABLE recurring_debt_skips (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    recurring_debt_id INTEGER NOT NULL,
+    skipped_date TEXT NOT NULL,
+    next_date TEXT NOT NULL,
+    skipped_by INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (recurring_debt_id) REFERENCES recurring_debts(id) ON DELETE CASCADE,
+    FOREIGN KEY (skipped_by) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_recurring_debt_skips_recurring_debt_id ON recurring_debt_skips(recurring_debt_id);
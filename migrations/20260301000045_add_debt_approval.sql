@@ -0,0 +1,8 @@
+-- Groups can require an admin to approve debts proposed by other members
+-- before they count towards balances. Rejected debts go back to draft with
+-- the admin's reason until their creator edits them.
+ALTER TABLE groups ADD COLUMN require_debt_approval BOOLEAN NOT NULL DEFAULT 0;
+
+ALTER TABLE shared_debts ADD COLUMN status TEXT NOT NULL DEFAULT 'approved'
+    CHECK (status IN ('draft', 'pending', 'approved'));
+ALTER TABLE shared_debts ADD COLUMN rejection_reason TEXT;
@@ -0,0 +1,15 @@
+-- The day a debt was incurred, which can lie before the day it was entered.
+-- Month-based statistics and the CSV export go by this date. Existing debts
+-- keep the day they were entered, generated ones the day they were due.
+ALTER TABLE shared_debts ADD COLUMN incurred_on DATE;
+
+UPDATE shared_debts SET incurred_on = COALESCE(generation_period, date(created_at));
+
+-- SQLite can't give added columns a default of the current date, so debts
+-- inserted without one are filled in here
+CREATE TRIGGER shared_debts_default_incurred_on
+AFTER INSERT ON shared_debts
+WHEN NEW.incurred_on IS NULL
+BEGIN
+    UPDATE shared_debts SET incurred_on = date(NEW.created_at) WHERE id = NEW.id;
+END;
@@ -0,0 +1,3 @@
+-- Who created an invite, shown on its preview. Existing invites and those
+-- of deleted users have none.
+ALTER TABLE invites ADD COLUMN created_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+-- ISO 4217 code of the currency a group keeps its books in; existing groups
+-- have always used euros
+ALTER TABLE groups ADD COLUMN currency TEXT NOT NULL DEFAULT 'EUR';
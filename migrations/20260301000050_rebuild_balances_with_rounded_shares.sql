@@ -0,0 +1,3 @@
+-- Cached balances charged equal splits unrounded; rebuild them on next read
+-- so they charge the shares shown on each debt
+UPDATE groups SET balances_cached_at = NULL;
@@ -7,12 +7,13 @@ use leptos_router::{
 };
 
 use crate::{
-    features::auth::get_user,
+    features::auth::{get_number_format, get_user},
     pages::{
-        GroupsCreate, GroupsEdit, GroupsIndex, GroupsInvites, GroupsShow, HomePage, InviteAccept,
-        LoginPage, RecurringDebtsCreate, RecurringDebtsEdit, RecurringDebtsShow, RegisterPage,
-        SharedDebtsCreate, SharedDebtsEdit, ShoppingListCreate, ShoppingListEdit, ShoppingListShow,
-        TransactionsCreate, TransactionsEdit,
+        GroupScope, GroupsCreate, GroupsDigest, GroupsEdit, GroupsIndex, GroupsInvites, GroupsShow,
+        GroupsStats, HomePage, InviteAccept, JoinGroup, LoginPage, ProfilePage, RecoveryCodesPage,
+        RecurringDebtsCreate, RecurringDebtsEdit, RecurringDebtsShow, RegisterPage,
+        ResetPasswordPage, SharedDebtsCreate, SharedDebtsEdit, ShoppingListCreate,
+        ShoppingListEdit, ShoppingListShow, TransactionsCreate, TransactionsEdit,
     },
 };
 
@@ -48,6 +49,13 @@ pub fn App() -> impl IntoView {
     // Provide user context globally
     provide_context(user_resource);
 
+    // How the user wants amounts written, reloaded for whoever logs in
+    let number_format_resource = LocalResource::new(move || {
+        user_resource.track();
+        async move { get_number_format().await }
+    });
+    provide_context(number_format_resource);
+
     view! {
         // Meta tags for better SEO and appearance
         <Meta name="description" content="Splitify - Split expenses with friends, the Rust way. Fast, secure, and reliable expense tracking."/>
@@ -73,40 +81,49 @@ pub fn App() -> impl IntoView {
         // Main router and content
         <Router>
             <main>
-                <Routes fallback=|| view! {
-                    <div class="min-h-screen flex items-center justify-center bg-gray-100 dark:bg-gray-900">
-                        <div class="text-center">
-                            <h1 class="text-6xl font-bold text-gray-900 dark:text-white mb-4">"404"</h1>
-                            <p class="text-xl text-gray-600 dark:text-gray-400 mb-8">"Page not found"</p>
-                            <a
-                                href="/"
-                                class="inline-flex items-center px-6 py-3 bg-indigo-600 hover:bg-indigo-700 text-white font-semibold rounded-lg shadow-lg transition-all duration-200"
-                            >
-                                "Go Home"
-                            </a>
+                // Group pages show amounts in the group's currency
+                <GroupScope>
+                    <Routes fallback=|| view! {
+                        <div class="min-h-screen flex items-center justify-center bg-gray-100 dark:bg-gray-900">
+                            <div class="text-center">
+                                <h1 class="text-6xl font-bold text-gray-900 dark:text-white mb-4">"404"</h1>
+                                <p class="text-xl text-gray-600 dark:text-gray-400 mb-8">"Page not found"</p>
+                                <a
+                                    href="/"
+                                    class="inline-flex items-center px-6 py-3 bg-indigo-600 hover:bg-indigo-700 text-white font-semibold rounded-lg shadow-lg transition-all duration-200"
+                                >
+                                    "Go Home"
+                                </a>
+                            </div>
                         </div>
-                    </div>
-                }.into_view()>
-                    <Route path=StaticSegment("") view=HomePage/>
-                    <Route path=StaticSegment("login") view=LoginPage/>
-                    <Route path=StaticSegment("register") view=RegisterPage/>
-                    <Route path=StaticSegment("groups") view=GroupsIndex/>
-                    <Route path=path!("/groups/create") view=GroupsCreate/>
-                    <Route path=path!("/groups/:id") view=GroupsShow/>
-                    <Route path=path!("/groups/:id/edit") view=GroupsEdit/>
-                    <Route path=path!("/groups/:id/invites") view=GroupsInvites/>
-                    <Route path=path!("/groups/:id/debts/create") view=SharedDebtsCreate/>
-                    <Route path=path!("/groups/:id/debts/:debt_id/edit") view=SharedDebtsEdit/>
-                    <Route path=path!("/groups/:group_id/shopping-lists/create") view=ShoppingListCreate/>
-                    <Route path=path!("/groups/:group_id/shopping-lists/:list_id") view=ShoppingListShow/>
-                    <Route path=path!("/groups/:group_id/shopping-lists/:list_id/edit") view=ShoppingListEdit/>
-                    <Route path=path!("/groups/:id/recurring-debts/create") view=RecurringDebtsCreate/>
-                    <Route path=path!("/groups/:id/recurring-debts/:recurring_id") view=RecurringDebtsShow/>
-                    <Route path=path!("/groups/:id/recurring-debts/:recurring_id/edit") view=RecurringDebtsEdit/>
-                    <Route path=path!("/groups/:id/transactions/create") view=TransactionsCreate/>
-                    <Route path=path!("/groups/:id/transactions/:transaction_id/edit") view=TransactionsEdit/>
-                    <Route path=path!("/invite/:uuid") view=InviteAccept/>
-                </Routes>
+                    }.into_view()>
+                        <Route path=StaticSegment("") view=HomePage/>
+                        <Route path=StaticSegment("login") view=LoginPage/>
+                        <Route path=StaticSegment("register") view=RegisterPage/>
+                        <Route path=StaticSegment("reset-password") view=ResetPasswordPage/>
+                        <Route path=StaticSegment("recovery-codes") view=RecoveryCodesPage/>
+                        <Route path=StaticSegment("profile") view=ProfilePage/>
+                        <Route path=StaticSegment("groups") view=GroupsIndex/>
+                        <Route path=path!("/groups/create") view=GroupsCreate/>
+                        <Route path=path!("/groups/:id") view=GroupsShow/>
+                        <Route path=path!("/groups/:id/edit") view=GroupsEdit/>
+                        <Route path=path!("/groups/:id/invites") view=GroupsInvites/>
+                        <Route path=path!("/groups/:id/stats") view=GroupsStats/>
+                        <Route path=path!("/groups/:id/digest") view=GroupsDigest/>
+                        <Route path=path!("/groups/:id/debts/create") view=SharedDebtsCreate/>
+                        <Route path=path!("/groups/:id/debts/:debt_id/edit") view=SharedDebtsEdit/>
+                        <Route path=path!("/groups/:group_id/shopping-lists/create") view=ShoppingListCreate/>
+                        <Route path=path!("/groups/:group_id/shopping-lists/:list_id") view=ShoppingListShow/>
+                        <Route path=path!("/groups/:group_id/shopping-lists/:list_id/edit") view=ShoppingListEdit/>
+                        <Route path=path!("/groups/:id/recurring-debts/create") view=RecurringDebtsCreate/>
+                        <Route path=path!("/groups/:id/recurring-debts/:recurring_id") view=RecurringDebtsShow/>
+                        <Route path=path!("/groups/:id/recurring-debts/:recurring_id/edit") view=RecurringDebtsEdit/>
+                        <Route path=path!("/groups/:id/transactions/create") view=TransactionsCreate/>
+                        <Route path=path!("/groups/:id/transactions/:transaction_id/edit") view=TransactionsEdit/>
+                        <Route path=path!("/invite/:uuid") view=InviteAccept/>
+                        <Route path=StaticSegment("join") view=JoinGroup/>
+                    </Routes>
+                </GroupScope>
             </main>
         </Router>
     }
@@ -0,0 +1,149 @@
+use std::{any::Any, cell::RefCell, collections::HashMap, future::Future, pin::Pin};
+
+use leptos::prelude::*;
+
+/// Cached values keyed by resource name and group id
+type ResourceCache = HashMap<(&'static str, i64), Box<dyn Any>>;
+
+thread_local! {
+    /// Last loaded value per resource name and group id
+    ///
+    /// Lives in browser memory only, so a hard refresh always starts empty.
+    /// Entries are only written from effects, which never run on the server.
+    static CACHE: RefCell<ResourceCache> = RefCell::new(HashMap::new());
+}
+
+fn load<T: Clone + 'static>(name: &'static str, group_id: i64) -> Option<T> {
+    CACHE.with_borrow(|cache| {
+        cache
+            .get(&(name, group_id))
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    })
+}
+
+fn store<T: 'static>(name: &'static str, group_id: i64, value: T) {
+    CACHE.with_borrow_mut(|cache| {
+        cache.insert((name, group_id), Box::new(value));
+    });
+}
+
+fn evict(name: &'static str, group_id: i64) {
+    CACHE.with_borrow_mut(|cache| {
+        cache.remove(&(name, group_id));
+    });
+}
+
+/// Drop every cached group resource, e.g. when the user logs out
+pub fn clear_resource_cache() {
+    CACHE.with_borrow_mut(HashMap::clear);
+}
+
+/// A `LocalResource` for one group that serves its last known value while it
+/// reloads
+///
+/// Revisiting a group renders the value cached from the previous visit right
+/// away while the fetcher runs again in the background, so sections don't
+/// flash their loading state. Only the very first load suspends.
+pub struct CachedResource<T: 'static> {
+    name: &'static str,
+    group_id: Memo<i64>,
+    resource: LocalResource<T>,
+    revision: Trigger,
+    /// Group whose value was refetched, so it no longer comes from `initial`
+    refetched: StoredValue<Option<i64>>,
+}
+
+impl<T: 'static> Clone for CachedResource<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: 'static> Copy for CachedResource<T> {}
+
+impl<T: Clone + 'static> CachedResource<T> {
+    /// Load `fetcher(group_id)`, caching the result under `name` and the id
+    pub fn new<Fut>(
+        name: &'static str,
+        group_id: Memo<i64>,
+        fetcher: impl Fn(i64) -> Fut + 'static,
+    ) -> Self
+    where
+        Fut: Future<Output = T> + 'static,
+    {
+        Self::build(name, group_id, move |group_id, _| {
+            Box::pin(fetcher(group_id))
+        })
+    }
+
+    /// Like [`CachedResource::new`], but the first load of a group comes from
+    /// `initial`, e.g. a share of a request made for several sections; only
+    /// [`CachedResource::refetch`] calls `fetcher`
+    pub fn with_initial<Init, Fut>(
+        name: &'static str,
+        group_id: Memo<i64>,
+        initial: impl Fn(i64) -> Init + 'static,
+        fetcher: impl Fn(i64) -> Fut + 'static,
+    ) -> Self
+    where
+        Init: Future<Output = T> + 'static,
+        Fut: Future<Output = T> + 'static,
+    {
+        Self::build(name, group_id, move |group_id, refetched| {
+            if refetched {
+                Box::pin(fetcher(group_id))
+            } else {
+                Box::pin(initial(group_id))
+            }
+        })
+    }
+
+    fn build(
+        name: &'static str,
+        group_id: Memo<i64>,
+        load: impl Fn(i64, bool) -> Pin<Box<dyn Future<Output = T>>> + 'static,
+    ) -> Self {
+        let refetched = StoredValue::new(None::<i64>);
+        let resource = LocalResource::new(move || {
+            let group_id = group_id.get();
+            load(group_id, refetched.get_value() == Some(group_id))
+        });
+        let revision = Trigger::new();
+
+        // Remember every fresh value for the next visit
+        Effect::new(move |_| {
+            if let Some(value) = resource.get() {
+                store(name, group_id.get_untracked(), value);
+                revision.notify();
+            }
+        });
+
+        Self {
+            name,
+            group_id,
+            resource,
+            revision,
+            refetched,
+        }
+    }
+
+    /// The freshest value available, cached or loaded
+    ///
+    /// Returns `None` (and suspends) only when nothing was cached yet.
+    pub fn get(&self) -> Option<T> {
+        let group_id = self.group_id.get();
+        self.revision.track();
+        load(self.name, group_id).or_else(|| self.resource.get())
+    }
+
+    /// Invalidate the cached value and load it again
+    ///
+    /// The current value stays on screen until the new one arrives.
+    pub fn refetch(&self) {
+        let group_id = self.group_id.get_untracked();
+        evict(self.name, group_id);
+        self.refetched.set_value(Some(group_id));
+        self.resource.refetch();
+    }
+}
@@ -0,0 +1,53 @@
+use leptos::prelude::*;
+
+use crate::{components::FormSelect, features::shared_debts::models::DebtCategory};
+
+/// Small colored label with a debt's category
+#[must_use]
+#[component]
+pub fn CategoryBadge(category: DebtCategory) -> impl IntoView {
+    view! {
+        <span class=format!(
+            "inline-flex items-center px-2 py-0.5 rounded-full text-xs font-medium {}",
+            category.badge_class()
+        )>
+            {category.as_str()}
+        </span>
+    }
+}
+
+/// Dropdown of the predefined categories with an empty first option
+///
+/// The value is the category name, or an empty string for none.
+#[must_use]
+#[component]
+pub fn CategorySelect(
+    /// Input ID
+    #[prop(optional)]
+    id: &'static str,
+    /// Text of the empty option
+    #[prop(default = "No category")]
+    empty_label: &'static str,
+    /// Signal for the value
+    #[prop(into)]
+    value: Signal<String>,
+    /// Setter for the value
+    #[prop(into)]
+    on_change: Callback<String>,
+) -> impl IntoView {
+    view! {
+        <FormSelect id=id value=value on_change=on_change>
+            // `selected` as well, since the value may be set before the
+            // options exist
+            <option value="" selected=move || value.get().is_empty()>{empty_label}</option>
+            {DebtCategory::ALL
+                .into_iter()
+                .map(|category| view! {
+                    <option value=category.as_str() selected=move || value.get() == category.as_str()>
+                        {category.as_str()}
+                    </option>
+                })
+                .collect_view()}
+        </FormSelect>
+    }
+}
@@ -0,0 +1,210 @@
+use leptos::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::{
+    error::AppError,
+    features::{auth::NumberFormat, shared_debts::models::ForeignAmount},
+};
+
+/// Symbol of the default currency, euros
+pub const EURO: &str = "€";
+
+/// Symbol of the currency the group on screen keeps its books in, provided
+/// around every group page so amounts inside them show it
+#[derive(Clone, Copy)]
+pub struct GroupCurrency(pub Signal<&'static str>);
+
+/// The symbol amounts are shown with: the group's currency inside a group
+/// page, euros elsewhere
+pub fn use_currency_symbol() -> Signal<&'static str> {
+    use_context::<GroupCurrency>()
+        .map(|GroupCurrency(symbol)| symbol)
+        .unwrap_or_else(|| Signal::stored(EURO))
+}
+
+/// Write an amount with two decimals and thousands separators, e.g.
+/// "€1,234.50" or "-€1.234,50"
+pub fn format_money(amount: Decimal, currency: &str, format: NumberFormat) -> String {
+    let amount = amount.round_dp(2);
+    let sign = if amount.is_sign_negative() && !amount.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+    let digits = format!("{:.2}", amount.abs());
+    let (whole, cents) = digits.split_once('.').unwrap_or((&digits, "00"));
+    let (thousands, decimal) = format.separators();
+
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+
+    format!("{sign}{currency}{grouped}{decimal}{cents}")
+}
+
+/// Write what was paid in another currency and its conversion to the
+/// group's, e.g. "THB 1,200 @ 0.026 = €31.20"
+///
+/// Whole original amounts are shown without cents.
+pub fn format_foreign_amount(
+    original: &ForeignAmount,
+    currency: &str,
+    format: NumberFormat,
+) -> String {
+    let (_, decimal) = format.separators();
+    let mut amount = format_money(original.amount, &format!("{} ", original.currency), format);
+    if original.amount.fract().is_zero() {
+        // Drop the decimal separator and "00"
+        amount.truncate(amount.len() - 3);
+    }
+    let rate = original
+        .exchange_rate
+        .normalize()
+        .to_string()
+        .replace('.', &decimal.to_string());
+
+    format!(
+        "{} @ {} = {}",
+        amount,
+        rate,
+        format_money(original.converted(), currency, format)
+    )
+}
+
+/// The current user's number format, the default until it has loaded or
+/// when nobody is logged in
+pub fn use_number_format() -> Signal<NumberFormat> {
+    let resource = use_context::<LocalResource<Result<NumberFormat, AppError>>>();
+    Signal::derive(move || {
+        resource
+            .and_then(|resource| resource.get())
+            .and_then(Result::ok)
+            .unwrap_or_default()
+    })
+}
+
+/// An amount written the way the current user prefers
+#[must_use]
+#[component]
+pub fn Money(
+    amount: Decimal,
+    /// Currency symbol; the group's, see [`use_currency_symbol`], when
+    /// omitted
+    #[prop(optional)]
+    currency: Option<&'static str>,
+    /// Prefix positive amounts with "+", e.g. for balances
+    #[prop(optional)]
+    signed: bool,
+) -> impl IntoView {
+    let format = use_number_format();
+    let symbol = use_currency_symbol();
+    let plus = if signed && amount.round_dp(2) > Decimal::ZERO {
+        "+"
+    } else {
+        ""
+    };
+
+    view! {
+        {move || {
+            let currency = currency.unwrap_or_else(|| symbol.get());
+            format!("{}{}", plus, format_money(amount, currency, format.get()))
+        }}
+    }
+}
+
+/// An amount paid in another currency and its conversion to the group's,
+/// written the way the current user prefers
+#[must_use]
+#[component]
+pub fn ForeignMoney(original: ForeignAmount) -> impl IntoView {
+    let format = use_number_format();
+    let symbol = use_currency_symbol();
+
+    view! { {move || format_foreign_amount(&original, symbol.get(), format.get())} }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_money() {
+        let dot = |amount| format_money(amount, EURO, NumberFormat::Dot);
+        let comma = |amount| format_money(amount, EURO, NumberFormat::Comma);
+
+        assert_eq!(dot(Decimal::ZERO), "€0.00");
+        assert_eq!(dot(Decimal::new(-1, 3)), "€0.00");
+        assert_eq!(dot(Decimal::new(125, 1)), "€12.50");
+        assert_eq!(dot(Decimal::new(3, 0)), "€3.00");
+        assert_eq!(dot(Decimal::new(99999, 2)), "€999.99");
+        assert_eq!(dot(Decimal::new(12345, 1)), "€1,234.50");
+        assert_eq!(dot(Decimal::new(-12345, 1)), "-€1,234.50");
+        assert_eq!(dot(Decimal::new(1_000_000, 0)), "€1,000,000.00");
+        assert_eq!(dot(Decimal::new(12_345_678_901, 2)), "€123,456,789.01");
+
+        assert_eq!(comma(Decimal::ZERO), "€0,00");
+        assert_eq!(comma(Decimal::new(12345, 1)), "€1.234,50");
+        assert_eq!(comma(Decimal::new(-1_000_000, 0)), "-€1.000.000,00");
+    }
+
+    #[test]
+    fn test_format_foreign_amount() {
+        let hotel = ForeignAmount {
+            amount: Decimal::from(1200),
+            currency: "THB".to_string(),
+            exchange_rate: Decimal::new(260, 4),
+        };
+        assert_eq!(
+            format_foreign_amount(&hotel, EURO, NumberFormat::Dot),
+            "THB 1,200 @ 0.026 = €31.20"
+        );
+        assert_eq!(
+            format_foreign_amount(&hotel, EURO, NumberFormat::Comma),
+            "THB 1.200 @ 0,026 = €31,20"
+        );
+
+        let taxi = ForeignAmount {
+            amount: Decimal::new(4550, 2),
+            currency: "USD".to_string(),
+            exchange_rate: Decimal::new(92, 2),
+        };
+        assert_eq!(
+            format_foreign_amount(&taxi, "CHF ", NumberFormat::Dot),
+            "USD 45.50 @ 0.92 = CHF 41.86"
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_money_renders_signed_amounts() {
+        let render = |amount, signed| {
+            Owner::new().with(|| view! { <Money amount=amount signed=signed /> }.to_html())
+        };
+
+        let html = render(Decimal::new(12345, 1), false);
+        assert!(html.contains("€1,234.50") && !html.contains('+'));
+        assert!(render(Decimal::new(12345, 1), true).contains("+€1,234.50"));
+        assert!(render(Decimal::new(-25, 0), true).contains("-€25.00"));
+        let html = render(Decimal::ZERO, true);
+        assert!(html.contains("€0.00") && !html.contains('+'));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_money_uses_group_currency() {
+        let html = Owner::new().with(|| {
+            provide_context(GroupCurrency(Signal::stored("£")));
+            view! {
+                <Money amount=Decimal::new(5, 0) />
+                " "
+                <Money amount=Decimal::new(5, 0) currency=EURO />
+            }
+            .to_html()
+        });
+        assert!(html.contains("£5.00") && html.contains("€5.00"));
+    }
+}
@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+
+/// Client-side validation state of one form field
+///
+/// The message is only shown once the field was left (blurred), while
+/// [`FieldCheck::is_invalid`] reflects the current value right away so submit
+/// buttons can be disabled before the user has touched every field.
+#[derive(Clone, Copy)]
+pub struct FieldCheck {
+    touched: RwSignal<bool>,
+    error: Memo<Option<String>>,
+}
+
+impl FieldCheck {
+    /// Track a field; `check` returns the message of the first failing rule
+    pub fn new(check: impl Fn() -> Option<String> + Send + Sync + 'static) -> Self {
+        Self {
+            touched: RwSignal::new(false),
+            error: Memo::new(move |_| check()),
+        }
+    }
+
+    /// Mark the field as visited so its message is shown
+    pub fn touch(&self) {
+        self.touched.set(true);
+    }
+
+    /// Blur handler for the form input components
+    pub fn on_blur(self) -> Callback<()> {
+        Callback::new(move |_| self.touch())
+    }
+
+    /// Whether any rule currently fails
+    pub fn is_invalid(&self) -> bool {
+        self.error.with(Option::is_some)
+    }
+
+    /// Message to render below the field
+    pub fn message(self) -> Signal<Option<String>> {
+        Signal::derive(move || {
+            if self.touched.get() {
+                self.error.get()
+            } else {
+                None
+            }
+        })
+    }
+}
@@ -0,0 +1,123 @@
+use leptos::prelude::*;
+
+use crate::{
+    components::{
+        FormInput, FormNumberInput, format_money, use_currency_symbol, use_number_format,
+    },
+    features::shared_debts::{models::ForeignAmount, utils::resolve_foreign_amount},
+    validation::rules::check_amount,
+};
+
+/// Original amount, currency and exchange rate as typed into a debt form
+#[derive(Clone, Copy)]
+pub struct ForeignAmountEntry {
+    pub amount: RwSignal<String>,
+    pub currency: RwSignal<String>,
+    pub exchange_rate: RwSignal<String>,
+}
+
+impl ForeignAmountEntry {
+    pub fn new() -> Self {
+        Self {
+            amount: RwSignal::new(String::new()),
+            currency: RwSignal::new(String::new()),
+            exchange_rate: RwSignal::new(String::new()),
+        }
+    }
+
+    /// Fill in a debt's stored original amount
+    pub fn set(&self, original: &ForeignAmount) {
+        self.amount.set(original.amount.to_string());
+        self.currency.set(original.currency.clone());
+        self.exchange_rate
+            .set(original.exchange_rate.normalize().to_string());
+    }
+
+    /// Check the entry like the server does, including the converted amount
+    pub fn check(&self) -> Result<ForeignAmount, String> {
+        let original = resolve_foreign_amount(
+            Some(&self.amount.get()),
+            Some(&self.currency.get()),
+            Some(&self.exchange_rate.get()),
+        )?
+        .ok_or_else(|| {
+            "Enter the original amount, its currency and the exchange rate".to_string()
+        })?;
+        check_amount(&original.converted().to_string())?;
+        Ok(original)
+    }
+}
+
+impl Default for ForeignAmountEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Inputs for a debt paid in another currency, with the amount in the
+/// group's currency it converts to
+#[must_use]
+#[component]
+pub fn ForeignAmountInputs(
+    entry: ForeignAmountEntry,
+    /// Called when one of the inputs loses focus
+    #[prop(into)]
+    on_blur: Callback<()>,
+) -> impl IntoView {
+    let format = use_number_format();
+    let currency = use_currency_symbol();
+
+    view! {
+        <div class="space-y-2">
+            <div class="grid grid-cols-1 sm:grid-cols-3 gap-3">
+                <div>
+                    <label for="original_currency" class="block text-xs text-gray-600 dark:text-gray-400 mb-1">
+                        "Currency"
+                    </label>
+                    <FormInput
+                        id="original_currency"
+                        placeholder="e.g., THB"
+                        value=entry.currency
+                        on_input=Callback::new(move |val| entry.currency.set(val))
+                        on_blur=on_blur
+                    />
+                </div>
+                <div>
+                    <label for="original_amount" class="block text-xs text-gray-600 dark:text-gray-400 mb-1">
+                        "Amount paid"
+                    </label>
+                    <FormNumberInput
+                        id="original_amount"
+                        placeholder="0.00"
+                        min="0.01"
+                        value=entry.amount
+                        on_input=Callback::new(move |val| entry.amount.set(val))
+                        on_blur=on_blur
+                    />
+                </div>
+                <div>
+                    <label for="exchange_rate" class="block text-xs text-gray-600 dark:text-gray-400 mb-1">
+                        {move || format!("Rate ({} per unit)", currency.get())}
+                    </label>
+                    <FormNumberInput
+                        id="exchange_rate"
+                        placeholder="e.g., 0.026"
+                        step="any"
+                        value=entry.exchange_rate
+                        on_input=Callback::new(move |val| entry.exchange_rate.set(val))
+                        on_blur=on_blur
+                    />
+                </div>
+            </div>
+            <p class="text-sm text-gray-600 dark:text-gray-400">
+                {move || match entry.check() {
+                    Ok(original) => format!(
+                        "Booked as {}",
+                        format_money(original.converted(), currency.get(), format.get())
+                    ),
+                    Err(_) => "Enter the amount paid and the rate to see the converted amount".to_string(),
+                }}
+            </p>
+        </div>
+    }
+}
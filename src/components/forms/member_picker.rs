@@ -0,0 +1,280 @@
+use leptos::prelude::*;
+
+use crate::{features::groups::models::GroupMemberInfo, validation::rules::MAX_SPLIT_WEIGHT};
+
+/// Members matching a case-insensitive username filter, without duplicates
+fn visible_members(members: &[GroupMemberInfo], filter: &str) -> Vec<GroupMemberInfo> {
+    let filter = filter.trim().to_lowercase();
+    let mut seen = Vec::with_capacity(members.len());
+    members
+        .iter()
+        .filter(|m| {
+            if seen.contains(&m.id) {
+                return false;
+            }
+            seen.push(m.id);
+            filter.is_empty() || m.username.to_lowercase().contains(&filter)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Searchable multi-select for group members
+///
+/// Select all/none only affect the currently visible members and never change
+/// the state of disabled ones.
+#[must_use]
+#[component]
+pub fn MemberPicker(
+    /// Members to choose from; duplicate ids are shown once
+    members: Vec<GroupMemberInfo>,
+    /// Selected member ids
+    selected: RwSignal<Vec<i64>>,
+    /// Members whose checkbox cannot be toggled
+    #[prop(optional)]
+    disabled_ids: Vec<i64>,
+    /// Current user, labelled "(You)"
+    #[prop(optional)]
+    current_user_id: Option<i64>,
+    /// Members showing a "Remove" button
+    #[prop(optional)]
+    removable_ids: Vec<i64>,
+    /// Called when a member's "Remove" button is clicked
+    #[prop(optional, into)]
+    on_remove: Option<Callback<GroupMemberInfo>>,
+    /// Filter text; owned by the picker when not provided
+    #[prop(optional)]
+    filter: Option<RwSignal<String>>,
+    /// Split weight entry per member id; selected members get a weight input
+    /// next to their checkbox when provided
+    #[prop(optional)]
+    weights: Option<RwSignal<Vec<(i64, String)>>>,
+) -> impl IntoView {
+    let filter = filter.unwrap_or_else(|| RwSignal::new(String::new()));
+    let members = StoredValue::new(members);
+    let disabled_ids = StoredValue::new(disabled_ids);
+    let removable_ids = StoredValue::new(removable_ids);
+
+    let visible = Memo::new(move |_| members.with_value(|m| visible_members(m, &filter.get())));
+
+    let set_weight = move |user_id: i64, entry: String| {
+        if let Some(weights) = weights {
+            weights.update(
+                |weights| match weights.iter_mut().find(|(id, _)| *id == user_id) {
+                    Some((_, existing)) => *existing = entry,
+                    None => weights.push((user_id, entry)),
+                },
+            );
+        }
+    };
+
+    let set_visible = move |checked: bool| {
+        let ids: Vec<i64> = visible
+            .get_untracked()
+            .into_iter()
+            .map(|m| m.id)
+            .filter(|id| !disabled_ids.with_value(|d| d.contains(id)))
+            .collect();
+        selected.update(|selected| {
+            if checked {
+                for id in ids {
+                    if !selected.contains(&id) {
+                        selected.push(id);
+                    }
+                }
+            } else {
+                selected.retain(|id| !ids.contains(id));
+            }
+        });
+    };
+
+    view! {
+        <div class="space-y-2">
+            <div class="flex flex-col sm:flex-row gap-2">
+                <input
+                    type="search"
+                    placeholder="Filter members..."
+                    aria-label="Filter members"
+                    prop:value=move || filter.get()
+                    on:input=move |ev| filter.set(event_target_value(&ev))
+                    class="flex-1 px-3 py-1.5 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white placeholder-gray-500 dark:placeholder-gray-400"
+                />
+                <div class="flex gap-2">
+                    <button
+                        type="button"
+                        on:click=move |_| set_visible(true)
+                        class="px-3 py-1.5 text-sm bg-gray-100 hover:bg-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-700 dark:text-gray-300 rounded-lg"
+                    >
+                        "Select all"
+                    </button>
+                    <button
+                        type="button"
+                        on:click=move |_| set_visible(false)
+                        class="px-3 py-1.5 text-sm bg-gray-100 hover:bg-gray-200 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-700 dark:text-gray-300 rounded-lg"
+                    >
+                        "Select none"
+                    </button>
+                </div>
+            </div>
+            <div class="border border-gray-200 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 max-h-64 overflow-y-auto">
+                {move || {
+                    let visible = visible.get();
+                    if visible.is_empty() {
+                        return view! {
+                            <p class="px-4 py-3 text-sm text-gray-500 dark:text-gray-400">"No members match the filter."</p>
+                        }.into_any();
+                    }
+                    visible.into_iter().map(|member| {
+                        let member_id = member.id;
+                        let disabled = disabled_ids.with_value(|d| d.contains(&member_id));
+                        let removable = on_remove.is_some()
+                            && removable_ids.with_value(|r| r.contains(&member_id));
+                        let is_current_user = current_user_id == Some(member_id);
+                        let is_creator = member.is_creator;
+                        let username = member.username.clone();
+                        let weight_label = format!("Weight for {}", member.username);
+                        view! {
+                            <div class="flex items-center hover:bg-gray-50 dark:hover:bg-gray-600 border-b border-gray-200 dark:border-gray-600 last:border-0">
+                                <label class="flex flex-1 items-center px-4 py-3 cursor-pointer">
+                                    <input
+                                        type="checkbox"
+                                        id=format!("member-{}", member_id)
+                                        class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                        disabled=disabled
+                                        prop:checked=move || selected.get().contains(&member_id)
+                                        on:change=move |ev| {
+                                            let checked = event_target_checked(&ev);
+                                            selected.update(|members| {
+                                                if checked {
+                                                    if !members.contains(&member_id) {
+                                                        members.push(member_id);
+                                                    }
+                                                } else {
+                                                    members.retain(|&id| id != member_id);
+                                                }
+                                            });
+                                        }
+                                    />
+                                    <span class="ml-3 text-gray-900 dark:text-white">
+                                        {username}
+                                        {is_current_user.then_some(" (You)")}
+                                        {is_creator.then_some(" - Creator")}
+                                    </span>
+                                </label>
+                                {move || weights.filter(|_| selected.get().contains(&member_id)).map(|weights| view! {
+                                    <input
+                                        type="number"
+                                        id=format!("weight-{}", member_id)
+                                        min="1"
+                                        max=MAX_SPLIT_WEIGHT.to_string()
+                                        step="1"
+                                        aria-label=weight_label.clone()
+                                        title="Weight"
+                                        prop:value=move || {
+                                            weights.with(|weights| {
+                                                weights
+                                                    .iter()
+                                                    .find(|(id, _)| *id == member_id)
+                                                    .map_or_else(|| "1".to_string(), |(_, entry)| entry.clone())
+                                            })
+                                        }
+                                        on:input=move |ev| set_weight(member_id, event_target_value(&ev))
+                                        class="w-16 mr-4 px-2 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                                    />
+                                })}
+                                {removable.then(|| view! {
+                                    <button
+                                        type="button"
+                                        on:click=move |_| {
+                                            if let Some(on_remove) = on_remove {
+                                                on_remove.run(member.clone());
+                                            }
+                                        }
+                                        class="mr-4 text-sm text-red-600 dark:text-red-400 hover:text-red-800 dark:hover:text-red-300"
+                                    >
+                                        "Remove"
+                                    </button>
+                                })}
+                            </div>
+                        }
+                    }).collect_view().into_any()
+                }}
+            </div>
+        </div>
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn member(id: i64, username: &str) -> GroupMemberInfo {
+        GroupMemberInfo {
+            id,
+            username: username.to_string(),
+            is_creator: false,
+            is_admin: false,
+            is_viewer: false,
+            joined_at: None,
+        }
+    }
+
+    fn render(disabled_ids: Vec<i64>, filter: &str) -> String {
+        let owner = Owner::new();
+        owner.with(|| {
+            let members = vec![member(1, "alice"), member(2, "bob"), member(2, "bob")];
+            let selected = RwSignal::new(vec![1]);
+            let filter = RwSignal::new(filter.to_string());
+            view! {
+                <MemberPicker
+                    members=members
+                    selected=selected
+                    disabled_ids=disabled_ids
+                    filter=filter
+                />
+            }
+            .to_html()
+        })
+    }
+
+    fn checkbox_for(html: &str, id: i64) -> Option<&str> {
+        let start = html.find(&format!("id=\"member-{}\"", id))?;
+        let end = start + html[start..].find('>')?;
+        Some(&html[start..end])
+    }
+
+    #[test]
+    fn test_member_picker_disabled_state() {
+        let html = render(vec![1], "");
+
+        assert!(checkbox_for(&html, 1).unwrap().contains("disabled"));
+        assert!(!checkbox_for(&html, 2).unwrap().contains("disabled"));
+        assert_eq!(html.matches("id=\"member-2\"").count(), 1);
+    }
+
+    #[test]
+    fn test_member_picker_weight_inputs() {
+        let owner = Owner::new();
+        let html = owner.with(|| {
+            let members = vec![member(1, "alice"), member(2, "bob")];
+            let selected = RwSignal::new(vec![1]);
+            let weights = RwSignal::new(vec![(1, "2".to_string())]);
+            view! { <MemberPicker members=members selected=selected weights=weights /> }.to_html()
+        });
+
+        assert!(html.contains("id=\"weight-1\""));
+        assert!(!html.contains("id=\"weight-2\""));
+        assert!(!render(Vec::new(), "").contains("id=\"weight-"));
+    }
+
+    #[test]
+    fn test_member_picker_filtered_state() {
+        let html = render(Vec::new(), "BO");
+
+        assert!(checkbox_for(&html, 1).is_none());
+        assert!(checkbox_for(&html, 2).is_some());
+
+        let html = render(Vec::new(), "carol");
+        assert!(html.contains("No members match the filter."));
+    }
+}
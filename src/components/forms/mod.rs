@@ -1,17 +1,35 @@
 use leptos::prelude::*;
 
+mod field_check;
+mod foreign_amount_inputs;
+mod member_picker;
+mod share_inputs;
+mod suggest_input;
+
+pub use field_check::FieldCheck;
+pub use foreign_amount_inputs::{ForeignAmountEntry, ForeignAmountInputs};
+pub use member_picker::MemberPicker;
+pub use share_inputs::{ShareInputs, share_entries};
+pub use suggest_input::SuggestInput;
+
+use crate::validation::rules::is_edit_conflict;
+
 /// Form field component with label
 #[must_use]
 #[component]
 pub fn FormField(
-    /// Label text
-    label: &'static str,
+    /// Label text, e.g. "Amount (€)" with the group's currency
+    #[prop(into)]
+    label: TextProp,
     /// HTML id for the input
     #[prop(optional)]
     for_id: &'static str,
     /// Optional helper text below the input
     #[prop(optional)]
     helper_text: Option<&'static str>,
+    /// Inline validation message shown below the input
+    #[prop(optional, into)]
+    error: Option<Signal<Option<String>>>,
     children: Children,
 ) -> impl IntoView {
     view! {
@@ -20,12 +38,15 @@ pub fn FormField(
                 for=for_id
                 class="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2"
             >
-                {label}
+                {move || label.get().to_string()}
             </label>
             {children()}
             {helper_text.map(|text| view! {
                 <p class="mt-1 text-xs text-gray-500 dark:text-gray-400">{text}</p>
             })}
+            {move || error.and_then(|error| error.get()).map(|message| view! {
+                <p class="mt-1 text-sm text-red-600 dark:text-red-400">{message}</p>
+            })}
         </div>
     }
 }
@@ -55,6 +76,9 @@ pub fn FormInput(
     /// Setter for the value
     #[prop(into)]
     on_input: Callback<String>,
+    /// Called when the input loses focus
+    #[prop(optional, into)]
+    on_blur: Option<Callback<()>>,
 ) -> impl IntoView {
     view! {
         <input
@@ -66,6 +90,11 @@ pub fn FormInput(
             class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white disabled:bg-gray-100 dark:disabled:bg-gray-800 disabled:cursor-not-allowed disabled:text-gray-600 dark:disabled:text-gray-400"
             value=value.get_untracked()
             on:input=move |ev| on_input.run(event_target_value(&ev))
+            on:blur=move |_| {
+                if let Some(on_blur) = on_blur {
+                    on_blur.run(());
+                }
+            }
         />
     }
 }
@@ -98,6 +127,9 @@ pub fn FormNumberInput(
     /// Setter for the value
     #[prop(into)]
     on_input: Callback<String>,
+    /// Called when the input loses focus
+    #[prop(optional, into)]
+    on_blur: Option<Callback<()>>,
 ) -> impl IntoView {
     view! {
         <input
@@ -110,7 +142,13 @@ pub fn FormNumberInput(
             placeholder=placeholder
             class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white disabled:bg-gray-100 dark:disabled:bg-gray-800 disabled:cursor-not-allowed disabled:text-gray-600 dark:disabled:text-gray-400"
             value=value.get_untracked()
+            prop:value=move || value.get()
             on:input=move |ev| on_input.run(event_target_value(&ev))
+            on:blur=move |_| {
+                if let Some(on_blur) = on_blur {
+                    on_blur.run(());
+                }
+            }
         />
     }
 }
@@ -134,6 +172,9 @@ pub fn FormDateInput(
     /// Setter for the value
     #[prop(into)]
     on_input: Callback<String>,
+    /// Called when the input loses focus
+    #[prop(optional, into)]
+    on_blur: Option<Callback<()>>,
 ) -> impl IntoView {
     view! {
         <input
@@ -144,6 +185,11 @@ pub fn FormDateInput(
             class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white disabled:bg-gray-100 dark:disabled:bg-gray-800 disabled:cursor-not-allowed disabled:text-gray-600 dark:disabled:text-gray-400"
             value=value.get_untracked()
             on:input=move |ev| on_input.run(event_target_value(&ev))
+            on:blur=move |_| {
+                if let Some(on_blur) = on_blur {
+                    on_blur.run(());
+                }
+            }
         />
     }
 }
@@ -233,55 +279,60 @@ where
     }
 }
 
-/// Simple checkbox list item for member selection (most common use case)
+/// Error alert component
 #[must_use]
 #[component]
-pub fn MemberCheckboxItem(
-    /// Member ID
-    member_id: i64,
-    /// Member username
-    username: String,
-    /// Whether this checkbox is checked
-    is_checked: bool,
-    /// Callback when checkbox state changes
+pub fn ErrorAlert(
+    /// Error message to display
     #[prop(into)]
-    on_change: Callback<bool>,
+    message: Signal<Option<String>>,
 ) -> impl IntoView {
     view! {
-        <div class="flex items-center">
-            <input
-                type="checkbox"
-                id=format!("member-{}", member_id)
-                checked=is_checked
-                class="h-4 w-4 text-indigo-600 focus:ring-indigo-500 border-gray-300 dark:border-gray-700 rounded bg-white dark:bg-gray-700"
-                on:change=move |ev| {
-                    let checked = event_target_checked(&ev);
-                    on_change.run(checked);
-                }
-            />
-            <label
-                for=format!("member-{}", member_id)
-                class="ml-2 text-gray-700 dark:text-gray-300"
-            >
-                {username}
-            </label>
-        </div>
+        {move || message.get().map(|msg| view! {
+            <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
+                <p class="text-sm text-red-700 dark:text-red-300">{msg}</p>
+            </div>
+        })}
     }
 }
 
-/// Error alert component
+/// Error alert for saving an edit form
+///
+/// An edit conflict gets its own warning with a button to reload the record;
+/// other errors look like [`ErrorAlert`].
 #[must_use]
 #[component]
-pub fn ErrorAlert(
+pub fn EditErrorAlert(
     /// Error message to display
     #[prop(into)]
     message: Signal<Option<String>>,
+    /// Reloads the record being edited
+    on_reload: Callback<()>,
 ) -> impl IntoView {
     view! {
-        {move || message.get().map(|msg| view! {
-            <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
-                <p class="text-sm text-red-700 dark:text-red-300">{msg}</p>
-            </div>
+        {move || message.get().map(|msg| {
+            if is_edit_conflict(&msg) {
+                view! {
+                    <div class="rounded-md bg-amber-50 dark:bg-amber-900/30 p-4 flex flex-col sm:flex-row sm:items-center gap-3">
+                        <p class="flex-1 text-sm text-amber-800 dark:text-amber-300">
+                            "Someone else saved changes to this record while you were editing. Reload to see them; your unsaved changes will be lost."
+                        </p>
+                        <button
+                            type="button"
+                            on:click=move |_| on_reload.run(())
+                            class="px-3 py-1.5 bg-amber-600 hover:bg-amber-700 text-white rounded-lg text-sm font-medium transition-colors"
+                        >
+                            "Reload"
+                        </button>
+                    </div>
+                }.into_any()
+            } else {
+                view! {
+                    <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
+                        <p class="text-sm text-red-700 dark:text-red-300">{msg}</p>
+                    </div>
+                }.into_any()
+            }
         })}
     }
 }
@@ -326,6 +377,9 @@ pub fn SubmitButton(
     /// Whether the button is in loading state
     #[prop(into)]
     loading: Signal<bool>,
+    /// Disables the button without showing the loading text
+    #[prop(optional, into)]
+    disabled: Option<Signal<bool>>,
 ) -> impl IntoView {
     let loading_text = if loading_text.is_empty() {
         "Loading..."
@@ -336,7 +390,7 @@ pub fn SubmitButton(
     view! {
         <button
             type="submit"
-            disabled=move || loading.get()
+            disabled=move || loading.get() || disabled.is_some_and(|disabled| disabled.get())
             class="flex-1 px-4 py-2 bg-indigo-600 hover:bg-indigo-700 disabled:bg-gray-400 text-white rounded-lg font-medium transition-colors"
         >
             {move || if loading.get() { loading_text } else { text }}
@@ -373,12 +427,16 @@ pub fn FormActions(
     /// Whether the submit button is in loading state
     #[prop(into)]
     loading: Signal<bool>,
+    /// Whether the submit button is disabled, e.g. while a field is invalid
+    #[prop(optional, into)]
+    disabled: Option<Signal<bool>>,
     /// Cancel button URL
     cancel_href: String,
 ) -> impl IntoView {
+    let disabled = Signal::derive(move || disabled.is_some_and(|disabled| disabled.get()));
     view! {
         <div class="flex gap-3">
-            <SubmitButton text=submit_text loading_text=loading_text loading=loading />
+            <SubmitButton text=submit_text loading_text=loading_text loading=loading disabled=disabled />
             <CancelButton href=cancel_href />
         </div>
     }
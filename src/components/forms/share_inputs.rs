@@ -0,0 +1,194 @@
+use leptos::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::{
+    components::use_currency_symbol,
+    features::groups::models::GroupMemberInfo,
+    validation::rules::{check_amount, check_share_entry},
+};
+
+/// Share entries of the selected members, in selection order
+///
+/// Members without an entry get an empty one, so they take an equal part of
+/// whatever the other entries leave over.
+pub fn share_entries(selected: &[i64], shares: &[(i64, String)]) -> Vec<(i64, String)> {
+    selected
+        .iter()
+        .map(|user_id| {
+            let entry = shares
+                .iter()
+                .find(|(id, _)| id == user_id)
+                .map(|(_, entry)| entry.clone())
+                .unwrap_or_default();
+            (*user_id, entry)
+        })
+        .collect()
+}
+
+/// Live summary of how much of the total the entries leave unassigned
+///
+/// Invalid entries are ignored here; their error is shown by the form's
+/// validation instead.
+fn remaining_label(total: &str, entries: &[(i64, String)], currency: &str) -> String {
+    let Ok(total) = check_amount(total) else {
+        return "Enter the total amount to split it".to_string();
+    };
+
+    let mut assigned = Decimal::ZERO;
+    let mut open = 0;
+    for (_, entry) in entries {
+        match check_share_entry(total, entry) {
+            Ok(Some(share)) => assigned += share,
+            Ok(None) => open += 1,
+            Err(_) => {}
+        }
+    }
+
+    let remaining = total - assigned;
+    if remaining < Decimal::ZERO {
+        format!("{}{:.2} over the total", currency, remaining.abs())
+    } else if remaining == Decimal::ZERO {
+        "Fully assigned".to_string()
+    } else if open == 0 {
+        format!("{}{:.2} left to assign", currency, remaining)
+    } else if open == 1 {
+        format!(
+            "{}{:.2} left for the member without an amount",
+            currency, remaining
+        )
+    } else {
+        format!(
+            "{}{:.2} left, split between {} members without an amount",
+            currency, remaining, open
+        )
+    }
+}
+
+/// Per-member share inputs for splitting a debt unequally
+///
+/// Each selected member gets an input taking an exact amount or a percentage
+/// of the total. Members left empty split the rest equally.
+#[must_use]
+#[component]
+pub fn ShareInputs(
+    /// Group members; only the selected ones get an input
+    members: Vec<GroupMemberInfo>,
+    /// Selected member ids
+    #[prop(into)]
+    selected: Signal<Vec<i64>>,
+    /// Entered share per member id
+    shares: RwSignal<Vec<(i64, String)>>,
+    /// Total amount of the debt as entered
+    #[prop(into)]
+    total: Signal<String>,
+) -> impl IntoView {
+    let members = StoredValue::new(members);
+    let currency = use_currency_symbol();
+
+    let set_share = move |user_id: i64, entry: String| {
+        shares.update(|shares| match shares.iter_mut().find(|(id, _)| *id == user_id) {
+            Some((_, existing)) => *existing = entry,
+            None => shares.push((user_id, entry)),
+        });
+    };
+
+    view! {
+        <div class="space-y-2">
+            <div class="border border-gray-200 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700">
+                {move || {
+                    let selected = selected.get();
+                    members.with_value(|members| {
+                        members
+                            .iter()
+                            .filter(|m| selected.contains(&m.id))
+                            .map(|member| {
+                                let member_id = member.id;
+                                let username = member.username.clone();
+                                view! {
+                                    <div class="flex items-center gap-3 px-4 py-2 border-b border-gray-200 dark:border-gray-600 last:border-0">
+                                        <label
+                                            for=format!("share-{}", member_id)
+                                            class="flex-1 text-gray-900 dark:text-white"
+                                        >
+                                            {username}
+                                        </label>
+                                        <input
+                                            type="text"
+                                            id=format!("share-{}", member_id)
+                                            inputmode="decimal"
+                                            placeholder="e.g. 12.50 or 70%"
+                                            prop:value=move || {
+                                                shares.with(|shares| {
+                                                    shares
+                                                        .iter()
+                                                        .find(|(id, _)| *id == member_id)
+                                                        .map(|(_, entry)| entry.clone())
+                                                        .unwrap_or_default()
+                                                })
+                                            }
+                                            on:input=move |ev| set_share(member_id, event_target_value(&ev))
+                                            class="w-36 px-3 py-1.5 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white placeholder-gray-500 dark:placeholder-gray-400"
+                                        />
+                                    </div>
+                                }
+                            })
+                            .collect_view()
+                    })
+                }}
+            </div>
+            <p class="text-sm text-gray-600 dark:text-gray-400">
+                {move || {
+                    let entries = shares.with(|shares| share_entries(&selected.get(), shares));
+                    remaining_label(&total.get(), &entries, currency.get())
+                }}
+            </p>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_entries_follow_selection() {
+        let shares = vec![(2, "5".to_string()), (3, "70%".to_string())];
+
+        assert_eq!(
+            share_entries(&[1, 2], &shares),
+            vec![(1, String::new()), (2, "5".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_remaining_label() {
+        let entries = |values: &[&str]| -> Vec<(i64, String)> {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i as i64, v.to_string()))
+                .collect()
+        };
+
+        assert_eq!(
+            remaining_label("", &entries(&["5"]), "€"),
+            "Enter the total amount to split it"
+        );
+        assert_eq!(
+            remaining_label("30", &entries(&["70%", "9"]), "€"),
+            "Fully assigned"
+        );
+        assert_eq!(
+            remaining_label("30", &entries(&["10", "5"]), "€"),
+            "€15.00 left to assign"
+        );
+        assert_eq!(
+            remaining_label("30", &entries(&["10", "", ""]), "€"),
+            "€20.00 left, split between 2 members without an amount"
+        );
+        assert_eq!(
+            remaining_label("30", &entries(&["25", "10"]), "£"),
+            "£5.00 over the total"
+        );
+    }
+}
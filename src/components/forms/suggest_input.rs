@@ -0,0 +1,167 @@
+use leptos::prelude::*;
+
+/// Most suggestions shown at once
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Suggestions containing the typed text, case-insensitively, those starting
+/// with it first
+///
+/// The text itself is left out once it is typed in full.
+fn matching_suggestions(suggestions: &[String], typed: &str) -> Vec<String> {
+    let typed = typed.trim().to_lowercase();
+    let mut matches: Vec<&String> = suggestions
+        .iter()
+        .filter(|suggestion| {
+            let suggestion = suggestion.to_lowercase();
+            suggestion != typed && suggestion.contains(&typed)
+        })
+        .collect();
+    matches.sort_by_key(|suggestion| !suggestion.to_lowercase().starts_with(&typed));
+    matches.into_iter().take(MAX_SUGGESTIONS).cloned().collect()
+}
+
+/// Text input with a dropdown of earlier values
+///
+/// Arrow keys move through the suggestions and Enter picks the highlighted
+/// one. Without a highlighted suggestion, Enter submits the surrounding form
+/// as usual.
+#[must_use]
+#[component]
+pub fn SuggestInput(
+    /// Input value
+    value: RwSignal<String>,
+    /// Values to suggest
+    #[prop(into)]
+    suggestions: Signal<Vec<String>>,
+    /// HTML id for the input
+    #[prop(optional)]
+    id: &'static str,
+    #[prop(optional)] placeholder: &'static str,
+    /// Extra classes for the wrapper
+    #[prop(optional)]
+    class: &'static str,
+) -> impl IntoView {
+    let open = RwSignal::new(false);
+    let highlighted = RwSignal::new(None::<usize>);
+    let matches = Memo::new(move |_| matching_suggestions(&suggestions.get(), &value.get()));
+    let visible = move || open.get() && matches.with(|matches| !matches.is_empty());
+
+    let pick = move |suggestion: String| {
+        value.set(suggestion);
+        open.set(false);
+        highlighted.set(None);
+    };
+
+    let on_keydown = move |ev: leptos::ev::KeyboardEvent| {
+        let count = matches.with(Vec::len);
+        match ev.key().as_str() {
+            "ArrowDown" if count > 0 => {
+                ev.prevent_default();
+                open.set(true);
+                highlighted.update(|index| *index = Some(index.map_or(0, |i| (i + 1) % count)));
+            }
+            "ArrowUp" if count > 0 => {
+                ev.prevent_default();
+                open.set(true);
+                highlighted.update(|index| {
+                    *index = Some(index.map_or(count - 1, |i| (i + count - 1) % count));
+                });
+            }
+            "Enter" if visible() => {
+                let choice = highlighted
+                    .get()
+                    .and_then(|index| matches.with(|matches| matches.get(index).cloned()));
+                if let Some(choice) = choice {
+                    ev.prevent_default();
+                    pick(choice);
+                }
+            }
+            "Escape" => {
+                open.set(false);
+                highlighted.set(None);
+            }
+            _ => {}
+        }
+    };
+
+    view! {
+        <div class=format!("relative {}", class)>
+            <input
+                type="text"
+                id=id
+                placeholder=placeholder
+                autocomplete="off"
+                role="combobox"
+                aria-expanded=move || visible().to_string()
+                class="w-full px-3 py-2 border border-gray-300 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-300 focus:border-indigo-500 dark:focus:border-indigo-600 focus:ring-indigo-500 dark:focus:ring-indigo-600 rounded-md shadow-sm"
+                prop:value=move || value.get()
+                on:input=move |ev| {
+                    value.set(event_target_value(&ev));
+                    open.set(true);
+                    highlighted.set(None);
+                }
+                on:focus=move |_| open.set(true)
+                on:blur=move |_| {
+                    open.set(false);
+                    highlighted.set(None);
+                }
+                on:keydown=on_keydown
+            />
+            <Show when=visible>
+                <ul
+                    role="listbox"
+                    class="absolute z-10 mt-1 w-full max-h-48 overflow-auto rounded-md border border-gray-200 dark:border-gray-700 bg-white dark:bg-gray-800 shadow-lg text-sm"
+                >
+                    {move || matches.get().into_iter().enumerate().map(|(index, suggestion)| {
+                        let is_highlighted = move || highlighted.get() == Some(index);
+                        let label = suggestion.clone();
+                        view! {
+                            <li
+                                role="option"
+                                aria-selected=move || is_highlighted().to_string()
+                                class=move || if is_highlighted() {
+                                    "px-3 py-2 cursor-pointer bg-indigo-600 text-white"
+                                } else {
+                                    "px-3 py-2 cursor-pointer text-gray-900 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-700"
+                                }
+                                // mousedown fires before the input's blur closes the list
+                                on:mousedown=move |ev| {
+                                    ev.prevent_default();
+                                    pick(suggestion.clone());
+                                }
+                            >
+                                {label}
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+            </Show>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matching_suggestions() {
+        let suggestions = strings(&["Bakery", "Dairy", "Drinks", "Frozen", "Produce"]);
+
+        assert_eq!(
+            matching_suggestions(&suggestions, "d"),
+            strings(&["Dairy", "Drinks", "Produce"])
+        );
+        assert_eq!(
+            matching_suggestions(&suggestions, " RY "),
+            strings(&["Bakery", "Dairy"])
+        );
+        // A fully typed suggestion is not offered again
+        assert!(matching_suggestions(&suggestions, "frozen").is_empty());
+        assert_eq!(matching_suggestions(&suggestions, "").len(), 5);
+    }
+}
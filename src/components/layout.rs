@@ -1,5 +1,11 @@
 use leptos::prelude::*;
 
+use super::notifications::NotificationBell;
+use crate::{
+    error::{AppError, ErrorAction, summarize_page_error},
+    features::auth::use_logout,
+};
+
 /// Splitify pie chart icon component
 #[must_use]
 #[component]
@@ -66,10 +72,14 @@ pub fn TextInput(
     #[prop(optional)] required: bool,
     #[prop(optional)] disabled: bool,
     #[prop(optional)] class: &'static str,
+    /// Reference to the input element, e.g. to focus it
+    #[prop(optional)]
+    node_ref: NodeRef<leptos::html::Input>,
     value: RwSignal<String>,
 ) -> impl IntoView {
     view! {
         <input
+            node_ref=node_ref
             type=input_type
             placeholder=placeholder
             required=required
@@ -147,55 +157,65 @@ pub fn Navigation(username: String, #[prop(into)] on_logout: Callback<()>) -> im
                         </div>
                     </div>
 
-                    // Settings Dropdown (Desktop)
-                    <div class="hidden sm:flex sm:items-center sm:ms-6">
-                        <div class="relative">
+                    <div class="flex items-center gap-2">
+                        <NotificationBell />
+
+                        // Settings Dropdown (Desktop)
+                        <div class="hidden sm:flex sm:items-center sm:ms-4">
+                            <div class="relative">
+                                <button
+                                    on:click=move |_| set_open.set(!open.get())
+                                    class="inline-flex items-center px-3 py-2 border border-transparent text-sm leading-4 font-medium rounded-md text-gray-500 dark:text-gray-400 bg-white dark:bg-gray-800 hover:text-gray-700 dark:hover:text-gray-300 focus:outline-none transition ease-in-out duration-150"
+                                >
+                                    <div>{username.clone()}</div>
+                                    <div class="ms-1">
+                                        <svg class="fill-current h-4 w-4" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
+                                            <path fill-rule="evenodd" d="M5.293 7.293a1 1 0 011.414 0L10 10.586l3.293-3.293a1 1 0 111.414 1.414l-4 4a1 1 0 01-1.414 0l-4-4a1 1 0 010-1.414z" clip-rule="evenodd" />
+                                        </svg>
+                                    </div>
+                                </button>
+
+                                <Show when=move || open.get()>
+                                    <div class="absolute right-0 mt-2 w-48 rounded-md shadow-lg py-1 bg-white dark:bg-gray-800 ring-1 ring-black ring-opacity-5">
+                                        <a
+                                            href="/profile"
+                                            class="block w-full text-left px-4 py-2 text-sm text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-900"
+                                        >
+                                            "Profile"
+                                        </a>
+                                        <button
+                                        on:click=move |_| {
+                                            on_logout.run(());
+                                            set_open.set(false);
+                                        }
+                                            class="block w-full text-left px-4 py-2 text-sm text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-900"
+                                        >
+                                            "Log Out"
+                                        </button>
+                                    </div>
+                                </Show>
+                            </div>
+                        </div>
+
+                        // Hamburger (Mobile)
+                        <div class="-me-2 flex items-center sm:hidden">
                             <button
                                 on:click=move |_| set_open.set(!open.get())
-                                class="inline-flex items-center px-3 py-2 border border-transparent text-sm leading-4 font-medium rounded-md text-gray-500 dark:text-gray-400 bg-white dark:bg-gray-800 hover:text-gray-700 dark:hover:text-gray-300 focus:outline-none transition ease-in-out duration-150"
+                                class="inline-flex items-center justify-center p-2 rounded-md text-gray-400 dark:text-gray-500 hover:text-gray-500 dark:hover:text-gray-400 hover:bg-gray-100 dark:hover:bg-gray-900 focus:outline-none focus:bg-gray-100 dark:focus:bg-gray-900 focus:text-gray-500 dark:focus:text-gray-400 transition duration-150 ease-in-out"
                             >
-                                <div>{username.clone()}</div>
-                                <div class="ms-1">
-                                    <svg class="fill-current h-4 w-4" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 20">
-                                        <path fill-rule="evenodd" d="M5.293 7.293a1 1 0 011.414 0L10 10.586l3.293-3.293a1 1 0 111.414 1.414l-4 4a1 1 0 01-1.414 0l-4-4a1 1 0 010-1.414z" clip-rule="evenodd" />
-                                    </svg>
-                                </div>
-                            </button>
-
-                            <Show when=move || open.get()>
-                                <div class="absolute right-0 mt-2 w-48 rounded-md shadow-lg py-1 bg-white dark:bg-gray-800 ring-1 ring-black ring-opacity-5">
-                                    <button
-                                    on:click=move |_| {
-                                        on_logout.run(());
-                                        set_open.set(false);
-                                    }
-                                        class="block w-full text-left px-4 py-2 text-sm text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-900"
+                                <svg class="h-6 w-6" stroke="currentColor" fill="none" viewBox="0 0 24 24">
+                                    <Show
+                                        when=move || open.get()
+                                        fallback=move || view! {
+                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 6h16M4 12h16M4 18h16" />
+                                        }
                                     >
-                                        "Log Out"
-                                    </button>
-                                </div>
-                            </Show>
+                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12" />
+                                    </Show>
+                                </svg>
+                            </button>
                         </div>
                     </div>
-
-                    // Hamburger (Mobile)
-                    <div class="-me-2 flex items-center sm:hidden">
-                        <button
-                            on:click=move |_| set_open.set(!open.get())
-                            class="inline-flex items-center justify-center p-2 rounded-md text-gray-400 dark:text-gray-500 hover:text-gray-500 dark:hover:text-gray-400 hover:bg-gray-100 dark:hover:bg-gray-900 focus:outline-none focus:bg-gray-100 dark:focus:bg-gray-900 focus:text-gray-500 dark:focus:text-gray-400 transition duration-150 ease-in-out"
-                        >
-                            <svg class="h-6 w-6" stroke="currentColor" fill="none" viewBox="0 0 24 24">
-                                <Show
-                                    when=move || open.get()
-                                    fallback=move || view! {
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 6h16M4 12h16M4 18h16" />
-                                    }
-                                >
-                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12" />
-                                </Show>
-                            </svg>
-                        </button>
-                    </div>
                 </div>
             </div>
 
@@ -217,6 +237,12 @@ pub fn Navigation(username: String, #[prop(into)] on_logout: Callback<()>) -> im
                         </div>
 
                         <div class="mt-3 space-y-1">
+                            <a
+                                href="/profile"
+                                class="block w-full text-left ps-3 pe-4 py-2 border-l-4 border-transparent text-base font-medium text-gray-600 dark:text-gray-400 hover:text-gray-800 dark:hover:text-gray-200 hover:bg-gray-50 dark:hover:bg-gray-700 hover:border-gray-300 dark:hover:border-gray-600 focus:outline-none focus:text-gray-800 dark:focus:text-gray-200 focus:bg-gray-50 dark:focus:bg-gray-700 focus:border-gray-300 dark:focus:border-gray-600 transition duration-150 ease-in-out"
+                            >
+                                "Profile"
+                            </a>
                             <button
                                 on:click=move |_| {
                                     on_logout.run(());
@@ -261,3 +287,100 @@ pub fn AppLayout(
         </div>
     }
 }
+
+/// Card shown in place of a page whose subject doesn't exist
+#[must_use]
+#[component]
+pub fn NotFoundCard(
+    #[prop(into)] title: String,
+    #[prop(into)] message: String,
+    /// Text of the link back, e.g. "Back to groups"
+    #[prop(into)]
+    back_label: String,
+    #[prop(into)] back_href: String,
+) -> impl IntoView {
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-12 text-center">
+            <div class="w-16 h-16 mx-auto mb-4 bg-gray-100 dark:bg-gray-700 rounded-full flex items-center justify-center">
+                <svg class="w-8 h-8 text-gray-400" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9.172 16.172a4 4 0 015.656 0M9 10h.01M15 10h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z"/>
+                </svg>
+            </div>
+            <h2 class="text-lg font-semibold text-gray-900 dark:text-white">{title}</h2>
+            <p class="mt-1 text-sm text-gray-500 dark:text-gray-400">{message}</p>
+            <a
+                href=back_href
+                class="mt-6 inline-flex px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white rounded-lg font-medium transition-colors"
+            >
+                {back_label}
+            </a>
+        </div>
+    }
+}
+
+/// Card shown in place of a page that failed to load, with a next step
+/// instead of the raw error
+#[must_use]
+#[component]
+pub fn ErrorCard(
+    error: AppError,
+    /// Where to go when the page's subject doesn't exist
+    #[prop(into)]
+    back_label: String,
+    #[prop(into)] back_href: String,
+) -> impl IntoView {
+    let summary = summarize_page_error(&error, &back_label, &back_href);
+    if summary.not_found {
+        return view! {
+            <NotFoundCard
+                title=summary.title
+                message=summary.message
+                back_label=back_label
+                back_href=back_href
+            />
+        }
+        .into_any();
+    }
+
+    let button_class = "mt-4 inline-flex px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded-lg text-sm font-medium transition-colors";
+    let action = match summary.action {
+        ErrorAction::Back { label, href } => view! {
+            <a href=href class=button_class>{label}</a>
+        }
+        .into_any(),
+        ErrorAction::SwitchAccount => {
+            let on_logout = use_logout();
+            view! {
+                <button type="button" on:click=move |_| on_logout.run(()) class=button_class>
+                    "Switch account"
+                </button>
+            }
+            .into_any()
+        }
+        ErrorAction::LogIn => view! {
+            <a href="/login" class=button_class>"Log in"</a>
+        }
+        .into_any(),
+        ErrorAction::Retry => view! {
+            <button
+                type="button"
+                on:click=move |_| {
+                    let _ = window().location().reload();
+                }
+                class=button_class
+            >
+                "Try again"
+            </button>
+        }
+        .into_any(),
+    };
+
+    view! {
+        <div class="rounded-xl bg-red-50 dark:bg-red-900/30 border border-red-200 dark:border-red-800 p-6">
+            <h2 class="text-lg font-semibold text-red-800 dark:text-red-300">{summary.title}</h2>
+            <p class="mt-1 text-sm text-red-700 dark:text-red-400">{summary.message}</p>
+            {action}
+        </div>
+    }
+    .into_any()
+}
@@ -1,6 +1,14 @@
+pub mod cached_resource;
+pub mod category;
+pub mod display;
 pub mod forms;
 pub mod layout;
+pub mod notifications;
 
 // Re-export components for easy imports
+pub use cached_resource::*;
+pub use category::*;
+pub use display::*;
 pub use forms::*;
 pub use layout::*;
+pub use notifications::*;
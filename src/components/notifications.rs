@@ -0,0 +1,133 @@
+use leptos::prelude::*;
+
+use crate::features::notifications::{MarkAllRead, MarkNotificationRead, get_notifications};
+
+/// Number of notifications listed in the dropdown
+const NOTIFICATION_LIMIT: i64 = 20;
+
+/// Bell with the number of unread notifications, opening a list of the latest
+#[must_use]
+#[component]
+pub fn NotificationBell() -> impl IntoView {
+    let (open, set_open) = signal(false);
+    let mark_read_action = ServerAction::<MarkNotificationRead>::new();
+    let mark_all_action = ServerAction::<MarkAllRead>::new();
+
+    let feed_resource = LocalResource::new(move || {
+        let _ = mark_read_action.version().get();
+        let _ = mark_all_action.version().get();
+        get_notifications(NOTIFICATION_LIMIT)
+    });
+
+    let unread_count = move || {
+        feed_resource
+            .get()
+            .and_then(Result::ok)
+            .map(|feed| feed.unread_count)
+            .unwrap_or(0)
+    };
+
+    view! {
+        <div class="relative">
+            <button
+                type="button"
+                aria-label="Notifications"
+                on:click=move |_| {
+                    // Pick up notifications that arrived since the page loaded
+                    if !open.get() {
+                        feed_resource.refetch();
+                    }
+                    set_open.set(!open.get());
+                }
+                class="relative p-2 rounded-md text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-300 focus:outline-none transition ease-in-out duration-150"
+            >
+                <svg class="h-6 w-6" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15 17h5l-1.405-1.405A2.032 2.032 0 0118 14.158V11a6.002 6.002 0 00-4-5.659V5a2 2 0 10-4 0v.341C7.67 6.165 6 8.388 6 11v3.159c0 .538-.214 1.055-.595 1.436L4 17h5m6 0v1a3 3 0 11-6 0v-1m6 0H9" />
+                </svg>
+                <Show when=move || { unread_count() > 0 }>
+                    <span class="absolute -top-0.5 -right-0.5 min-w-[1.25rem] h-5 px-1 rounded-full bg-red-600 text-white text-xs font-semibold flex items-center justify-center">
+                        {move || {
+                            let count = unread_count();
+                            if count > 99 { "99+".to_string() } else { count.to_string() }
+                        }}
+                    </span>
+                </Show>
+            </button>
+
+            <Show when=move || open.get()>
+                <div class="absolute right-0 mt-2 w-80 max-h-96 overflow-y-auto rounded-md shadow-lg bg-white dark:bg-gray-800 ring-1 ring-black ring-opacity-5 z-30">
+                    <div class="flex justify-between items-center px-4 py-2 border-b border-gray-200 dark:border-gray-700">
+                        <span class="text-sm font-semibold text-gray-900 dark:text-white">"Notifications"</span>
+                        <button
+                            type="button"
+                            on:click=move |_| {
+                                mark_all_action.dispatch(MarkAllRead {});
+                            }
+                            disabled=move || unread_count() == 0
+                            class="text-xs font-medium text-indigo-600 hover:text-indigo-500 dark:text-indigo-400 dark:hover:text-indigo-300 disabled:opacity-50"
+                        >
+                            "Mark all read"
+                        </button>
+                    </div>
+                    <Suspense fallback=move || view! {
+                        <p class="px-4 py-3 text-sm text-gray-500 dark:text-gray-400">"Loading..."</p>
+                    }>
+                        {move || {
+                            match feed_resource.get() {
+                                Some(Ok(feed)) if feed.notifications.is_empty() => view! {
+                                    <p class="px-4 py-3 text-sm text-gray-500 dark:text-gray-400">"No notifications yet."</p>
+                                }.into_any(),
+                                Some(Ok(feed)) => view! {
+                                    <ul class="divide-y divide-gray-200 dark:divide-gray-700">
+                                        {feed.notifications.into_iter().map(|notification| {
+                                            let id = notification.id;
+                                            let is_read = notification.is_read();
+                                            let item_class = if is_read {
+                                                "block px-4 py-2 hover:bg-gray-100 dark:hover:bg-gray-900"
+                                            } else {
+                                                "block px-4 py-2 bg-indigo-50 dark:bg-indigo-900/20 hover:bg-gray-100 dark:hover:bg-gray-900"
+                                            };
+                                            view! {
+                                                <li>
+                                                    <a
+                                                        href=notification.href()
+                                                        on:click=move |_| {
+                                                            if !is_read {
+                                                                mark_read_action.dispatch(MarkNotificationRead {
+                                                                    notification_id: id,
+                                                                });
+                                                            }
+                                                            set_open.set(false);
+                                                        }
+                                                        class=item_class
+                                                    >
+                                                        <p class="text-sm text-gray-900 dark:text-white">{notification.message()}</p>
+                                                        <p class="text-xs text-gray-500 dark:text-gray-400">
+                                                            {format!(
+                                                                "{} · {} {:02}:{:02}",
+                                                                notification.group_name,
+                                                                notification.created_at.date(),
+                                                                notification.created_at.hour(),
+                                                                notification.created_at.minute()
+                                                            )}
+                                                        </p>
+                                                    </a>
+                                                </li>
+                                            }
+                                        }).collect_view()}
+                                    </ul>
+                                }.into_any(),
+                                Some(Err(e)) => view! {
+                                    <p class="px-4 py-3 text-sm text-red-600 dark:text-red-400">"Error: " {e.to_string()}</p>
+                                }.into_any(),
+                                None => view! {
+                                    <p class="px-4 py-3 text-sm text-gray-500 dark:text-gray-400">"Loading..."</p>
+                                }.into_any(),
+                            }
+                        }}
+                    </Suspense>
+                </div>
+            </Show>
+        </div>
+    }
+}
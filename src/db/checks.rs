@@ -0,0 +1,158 @@
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use sqlx::SqlitePool;
+
+/// Log every stored amount that does not parse as a Decimal
+///
+/// Run once at startup so malformed rows show up in the logs instead of
+/// surfacing later as confusing balance errors. Returns the number of bad rows.
+#[cfg(feature = "ssr")]
+pub async fn scan_invalid_amounts(pool: &SqlitePool) -> Result<usize, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT 'transactions' as "table_name!: String", id as "id!", amount FROM transactions
+        UNION ALL
+        SELECT 'shared_debts', id, amount FROM shared_debts
+        UNION ALL
+        SELECT 'recurring_debts', id, amount FROM recurring_debts
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut invalid = 0;
+    for row in rows {
+        if let Err(e) = row.amount.parse::<Decimal>() {
+            invalid += 1;
+            tracing::warn!(
+                table = %row.table_name,
+                id = row.id,
+                amount = %row.amount,
+                error = %e,
+                "Stored amount is not a valid decimal"
+            );
+        }
+    }
+
+    Ok(invalid)
+}
+
+/// Clean up a stored amount with stray whitespace or commas
+///
+/// A single comma followed by one or two digits is read as a decimal comma
+/// ("12,50"); any other comma is a thousands separator ("1,000.50"). Returns
+/// the normalized amount, or `None` if the amount is already clean or still
+/// isn't a number after cleanup.
+#[cfg(feature = "ssr")]
+pub fn normalize_stored_amount(amount: &str) -> Option<String> {
+    let trimmed = amount.trim();
+    let cleaned = match trimmed.split_once(',') {
+        Some((whole, fraction))
+            if !fraction.contains([',', '.']) && (1..=2).contains(&fraction.len()) =>
+        {
+            format!("{}.{}", whole, fraction)
+        }
+        Some(_) => trimmed.replace(',', ""),
+        None => trimmed.to_string(),
+    };
+
+    let normalized = cleaned.parse::<Decimal>().ok()?.to_string();
+    (normalized != amount).then_some(normalized)
+}
+
+/// Rewrite transaction amounts stored with stray whitespace or commas
+///
+/// Run once at startup, before [`scan_invalid_amounts`], so rows written by
+/// older versions parse again. Returns the number of rows rewritten.
+#[cfg(feature = "ssr")]
+pub async fn normalize_transaction_amounts(pool: &SqlitePool) -> Result<usize, sqlx::Error> {
+    let rows = sqlx::query!(r#"SELECT id as "id!", amount FROM transactions"#)
+        .fetch_all(pool)
+        .await?;
+
+    let mut normalized = 0;
+    for row in rows {
+        let Some(amount) = normalize_stored_amount(&row.amount) else {
+            continue;
+        };
+        sqlx::query!(
+            "UPDATE transactions SET amount = ? WHERE id = ?",
+            amount,
+            row.id
+        )
+        .execute(pool)
+        .await?;
+        // The group's cached balances may have been built from the old value
+        sqlx::query!(
+            "UPDATE groups SET balances_cached_at = NULL WHERE id = (SELECT group_id FROM transactions WHERE id = ?)",
+            row.id
+        )
+        .execute(pool)
+        .await?;
+        tracing::info!(
+            id = row.id,
+            old = %row.amount,
+            new = %amount,
+            "Normalized stored transaction amount"
+        );
+        normalized += 1;
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_stored_amount() {
+        assert_eq!(normalize_stored_amount("12,50").as_deref(), Some("12.50"));
+        assert_eq!(normalize_stored_amount(" 7.5 ").as_deref(), Some("7.5"));
+        assert_eq!(normalize_stored_amount("1,5").as_deref(), Some("1.5"));
+        assert_eq!(
+            normalize_stored_amount("1,000.50").as_deref(),
+            Some("1000.50")
+        );
+        assert_eq!(normalize_stored_amount("1,000").as_deref(), Some("1000"));
+
+        // Clean or unsalvageable amounts are left alone
+        assert_eq!(normalize_stored_amount("12.50"), None);
+        assert_eq!(normalize_stored_amount("abc"), None);
+        assert_eq!(normalize_stored_amount("12,50 EUR"), None);
+    }
+
+    #[tokio::test]
+    async fn test_normalize_transaction_amounts() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 1, 2, '1,50'), (1, 1, 2, '20.00 '), (1, 1, 2, '5')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        assert_eq!(normalize_transaction_amounts(&pool).await.unwrap(), 2);
+        assert_eq!(scan_invalid_amounts(&pool).await.unwrap(), 0);
+
+        let amounts: Vec<String> =
+            sqlx::query_scalar("SELECT amount FROM transactions ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(amounts, ["1.50", "20.00", "5"]);
+
+        // A second run has nothing left to do
+        assert_eq!(normalize_transaction_amounts(&pool).await.unwrap(), 0);
+    }
+}
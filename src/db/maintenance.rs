@@ -0,0 +1,208 @@
+#[cfg(feature = "ssr")]
+use sqlx::SqlitePool;
+
+/// Default days an invite is kept after it expired
+#[cfg(feature = "ssr")]
+pub const DEFAULT_INVITE_RETENTION_DAYS: u32 = 30;
+
+/// Default number of activity entries kept per shopping list
+#[cfg(feature = "ssr")]
+pub const DEFAULT_SHOPPING_ACTIVITY_RETENTION: u32 = 500;
+
+/// Default days between VACUUM runs
+#[cfg(feature = "ssr")]
+pub const DEFAULT_VACUUM_INTERVAL_DAYS: u32 = 7;
+
+/// Retention settings for the maintenance job
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaintenanceConfig {
+    pub invite_retention_days: u32,
+    pub shopping_activity_retention: u32,
+    pub vacuum_interval_days: u32,
+}
+
+#[cfg(feature = "ssr")]
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            invite_retention_days: DEFAULT_INVITE_RETENTION_DAYS,
+            shopping_activity_retention: DEFAULT_SHOPPING_ACTIVITY_RETENTION,
+            vacuum_interval_days: DEFAULT_VACUUM_INTERVAL_DAYS,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl MaintenanceConfig {
+    /// Read INVITE_RETENTION_DAYS, SHOPPING_ACTIVITY_RETENTION and
+    /// VACUUM_INTERVAL_DAYS, rejecting values that aren't in range
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        Ok(Self {
+            invite_retention_days: parse_setting(
+                "INVITE_RETENTION_DAYS",
+                lookup("INVITE_RETENTION_DAYS"),
+                DEFAULT_INVITE_RETENTION_DAYS,
+                0..=3650,
+            )?,
+            shopping_activity_retention: parse_setting(
+                "SHOPPING_ACTIVITY_RETENTION",
+                lookup("SHOPPING_ACTIVITY_RETENTION"),
+                DEFAULT_SHOPPING_ACTIVITY_RETENTION,
+                1..=1_000_000,
+            )?,
+            vacuum_interval_days: parse_setting(
+                "VACUUM_INTERVAL_DAYS",
+                lookup("VACUUM_INTERVAL_DAYS"),
+                DEFAULT_VACUUM_INTERVAL_DAYS,
+                1..=365,
+            )?,
+        })
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn parse_setting(
+    name: &str,
+    value: Option<String>,
+    default: u32,
+    range: std::ops::RangeInclusive<u32>,
+) -> Result<u32, String> {
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    let parsed = value
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("{} must be a whole number, got {:?}", name, value))?;
+    if !range.contains(&parsed) {
+        return Err(format!(
+            "{} must be between {} and {}, got {}",
+            name,
+            range.start(),
+            range.end(),
+            parsed
+        ));
+    }
+    Ok(parsed)
+}
+
+/// What a maintenance run removed
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MaintenanceReport {
+    pub expired_invites: u64,
+    pub shopping_activity: u64,
+    pub vacuumed: bool,
+}
+
+/// Purge expired invites and old shopping list activity, then optimize the
+/// database
+///
+/// ANALYZE runs every time; VACUUM only when the configured interval has
+/// passed since the last one.
+#[cfg(feature = "ssr")]
+pub async fn run_maintenance(
+    pool: &SqlitePool,
+    config: &MaintenanceConfig,
+) -> Result<MaintenanceReport, sqlx::Error> {
+    let invite_cutoff = format!("-{} days", config.invite_retention_days);
+    let expired_invites = sqlx::query!(
+        r#"
+        DELETE FROM invites
+        WHERE datetime(created_at, '+' || duration_days || ' days') < datetime('now', ?)
+        "#,
+        invite_cutoff
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    let keep = i64::from(config.shopping_activity_retention);
+    let shopping_activity = sqlx::query!(
+        r#"
+        DELETE FROM shopping_list_activity
+        WHERE id IN (
+            SELECT id FROM (
+                SELECT id, ROW_NUMBER() OVER (
+                    PARTITION BY shopping_list_id ORDER BY created_at DESC, id DESC
+                ) AS position
+                FROM shopping_list_activity
+            )
+            WHERE position > ?
+        )
+        "#,
+        keep
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    sqlx::query("ANALYZE").execute(pool).await?;
+
+    let vacuum_cutoff = format!("-{} days", config.vacuum_interval_days);
+    let vacuum_due = sqlx::query_scalar!(
+        r#"
+        SELECT NOT EXISTS(
+            SELECT 1 FROM maintenance_state
+            WHERE task = 'vacuum' AND last_run_at > datetime('now', ?)
+        ) as "due!: bool"
+        "#,
+        vacuum_cutoff
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if vacuum_due {
+        sqlx::query("VACUUM").execute(pool).await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO maintenance_state (task, last_run_at)
+            VALUES ('vacuum', datetime('now'))
+            ON CONFLICT(task) DO UPDATE SET last_run_at = excluded.last_run_at
+            "#
+        )
+        .execute(pool)
+        .await?;
+    }
+
+    Ok(MaintenanceReport {
+        expired_invites,
+        shopping_activity,
+        vacuumed: vacuum_due,
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_defaults_and_validation() {
+        let config = MaintenanceConfig::from_lookup(|_| None).unwrap();
+        assert_eq!(config, MaintenanceConfig::default());
+
+        let config = MaintenanceConfig::from_lookup(|name| {
+            (name == "INVITE_RETENTION_DAYS").then(|| " 7 ".to_string())
+        })
+        .unwrap();
+        assert_eq!(config.invite_retention_days, 7);
+
+        assert!(
+            MaintenanceConfig::from_lookup(|name| {
+                (name == "SHOPPING_ACTIVITY_RETENTION").then(|| "0".to_string())
+            })
+            .is_err()
+        );
+        assert!(
+            MaintenanceConfig::from_lookup(|name| {
+                (name == "VACUUM_INTERVAL_DAYS").then(|| "weekly".to_string())
+            })
+            .is_err()
+        );
+    }
+}
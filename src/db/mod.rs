@@ -1,4 +1,12 @@
+pub mod checks;
+pub mod maintenance;
 pub mod pool;
+pub mod retry;
+pub mod seed;
 
 // Re-export database functions
+pub use checks::*;
+pub use maintenance::*;
 pub use pool::*;
+pub use retry::*;
+pub use seed::*;
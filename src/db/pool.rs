@@ -1,44 +1,145 @@
 #[cfg(feature = "ssr")]
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use std::{str::FromStr, time::Duration};
 
-/// Initialize database connection pool and run migrations
 #[cfg(feature = "ssr")]
-pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
-    let database_url =
-        std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:splitify.db".to_string());
+use sqlx::{
+    SqlitePool,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+};
+
+/// Default busy timeout when DATABASE_BUSY_TIMEOUT_MS is not set
+#[cfg(feature = "ssr")]
+pub const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
+
+/// Database settings read from the environment
+#[cfg(feature = "ssr")]
+#[derive(Clone, Debug)]
+pub struct DbConfig {
+    pub database_url: String,
+    pub max_connections: u32,
+    pub busy_timeout: Duration,
+}
+
+#[cfg(feature = "ssr")]
+impl DbConfig {
+    /// Read DATABASE_URL, DATABASE_MAX_CONNECTIONS and DATABASE_BUSY_TIMEOUT_MS
+    pub fn from_env() -> Self {
+        let database_url =
+            std::env::var("DATABASE_URL").unwrap_or_else(|_| "sqlite:splitify.db".to_string());
+
+        let max_connections = std::env::var("DATABASE_MAX_CONNECTIONS")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or_else(default_max_connections);
+
+        let busy_timeout_ms = std::env::var("DATABASE_BUSY_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_BUSY_TIMEOUT_MS);
+
+        Self {
+            database_url,
+            max_connections,
+            busy_timeout: Duration::from_millis(busy_timeout_ms),
+        }
+    }
+}
 
-    // Calculate connection pool size based on CPU cores
-    // Formula: (cores * 2) + effective_spindle_count
-    // For SQLite (single spindle), we use: cores * 2 + 1
+/// Pool size based on CPU cores
+///
+/// Formula: (cores * 2) + effective_spindle_count
+/// For SQLite (single spindle), we use: cores * 2 + 1
+#[cfg(feature = "ssr")]
+fn default_max_connections() -> u32 {
     let cores = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(4); // Default to 4 cores if detection fails
-    let max_connections = (cores * 2 + 1).clamp(5, 20); // Between 5 and 20
-
-    let pool = SqlitePoolOptions::new()
-        .max_connections(max_connections as u32)
-        .connect(&database_url)
-        .await?;
-
-    // Enable SQLite performance optimizations
-    sqlx::query("PRAGMA journal_mode = WAL")
-        .execute(&pool)
-        .await?;
-    sqlx::query("PRAGMA synchronous = NORMAL")
-        .execute(&pool)
-        .await?;
-    sqlx::query("PRAGMA cache_size = -64000") // 64MB cache
-        .execute(&pool)
-        .await?;
-    sqlx::query("PRAGMA busy_timeout = 5000") // 5 second timeout
-        .execute(&pool)
-        .await?;
-    sqlx::query("PRAGMA foreign_keys = ON")
-        .execute(&pool)
-        .await?;
+    (cores * 2 + 1).clamp(5, 20) as u32 // Between 5 and 20
+}
+
+/// Open a connection pool whose connections all carry the app's PRAGMAs
+///
+/// The options are applied by sqlx on every new connection, so settings like
+/// `foreign_keys` and `busy_timeout` hold for the whole pool rather than only
+/// the first connection.
+#[cfg(feature = "ssr")]
+pub async fn connect_pool(config: &DbConfig) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::from_str(&config.database_url)?
+        .journal_mode(SqliteJournalMode::Wal)
+        .synchronous(SqliteSynchronous::Normal)
+        .busy_timeout(config.busy_timeout)
+        .foreign_keys(true)
+        .pragma("cache_size", "-64000"); // 64MB cache
+
+    SqlitePoolOptions::new()
+        .max_connections(config.max_connections)
+        .connect_with(options)
+        .await
+}
+
+/// Initialize database connection pool and run migrations
+#[cfg(feature = "ssr")]
+pub async fn init_db() -> Result<SqlitePool, sqlx::Error> {
+    let config = DbConfig::from_env();
+
+    tracing::info!(
+        max_connections = config.max_connections,
+        busy_timeout_ms = config.busy_timeout.as_millis() as u64,
+        "Connecting to database"
+    );
+
+    let pool = connect_pool(&config).await?;
 
     // Run migrations
     sqlx::migrate!("./migrations").run(&pool).await?;
 
     Ok(pool)
 }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pragmas_applied_to_every_connection() {
+        let path =
+            std::env::temp_dir().join(format!("splitify-pool-test-{}.db", uuid::Uuid::new_v4()));
+        let config = DbConfig {
+            database_url: format!("sqlite:{}?mode=rwc", path.display()),
+            max_connections: 2,
+            busy_timeout: Duration::from_millis(1234),
+        };
+        let pool = connect_pool(&config).await.unwrap();
+
+        // Hold two connections at once so both are freshly opened
+        let mut first = pool.acquire().await.unwrap();
+        let mut second = pool.acquire().await.unwrap();
+
+        for conn in [&mut first, &mut second] {
+            let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+                .fetch_one(&mut **conn)
+                .await
+                .unwrap();
+            let busy_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+                .fetch_one(&mut **conn)
+                .await
+                .unwrap();
+            let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+                .fetch_one(&mut **conn)
+                .await
+                .unwrap();
+
+            assert_eq!(journal_mode, "wal");
+            assert_eq!(busy_timeout, 1234);
+            assert_eq!(foreign_keys, 1);
+        }
+
+        drop(first);
+        drop(second);
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(feature = "ssr")]
+use std::{future::Future, time::Duration};
+
+/// Number of attempts made by [`with_busy_retry`] before giving up
+#[cfg(feature = "ssr")]
+pub const BUSY_RETRY_ATTEMPTS: u32 = 3;
+
+/// Whether an error is SQLite reporting a locked database (SQLITE_BUSY or
+/// SQLITE_LOCKED, including their extended codes)
+#[cfg(feature = "ssr")]
+pub fn is_busy_error(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Database(db_error) => db_error
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, 5 | 6)),
+        _ => false,
+    }
+}
+
+/// Run a write operation, retrying with backoff while the database is busy
+///
+/// The busy_timeout already waits inside SQLite; this covers the cases where
+/// the timeout expires anyway (e.g. a long write from another connection) so
+/// background jobs don't skip work over a transient lock.
+#[cfg(feature = "ssr")]
+pub async fn with_busy_retry<T, F, Fut>(mut operation: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(e) if attempt < BUSY_RETRY_ATTEMPTS && is_busy_error(&e) => {
+                let backoff = Duration::from_millis(200 * 2u64.pow(attempt - 1));
+                tracing::warn!(
+                    attempt = attempt,
+                    backoff_ms = backoff.as_millis() as u64,
+                    "Database busy, retrying"
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
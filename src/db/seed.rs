@@ -0,0 +1,462 @@
+//! Demo data for local development, created by `cargo run -- --seed`
+//!
+//! Every row is looked up by a fixed natural key (username, group name, debt
+//! name, ...) before it is inserted, so seeding an existing database again
+//! adds nothing.
+
+#[cfg(feature = "ssr")]
+use sqlx::{SqliteConnection, SqlitePool};
+#[cfg(feature = "ssr")]
+use time::{Date, Duration, OffsetDateTime};
+
+#[cfg(feature = "ssr")]
+use crate::{
+    error::AppError,
+    features::{auth::utils::hash_password, transactions::balances::rebuild_group_balances},
+};
+
+/// Password of every demo user
+#[cfg(feature = "ssr")]
+pub const DEMO_PASSWORD: &str = "demo-password";
+
+/// Demo users, the first of which creates and administers the group
+#[cfg(feature = "ssr")]
+pub const DEMO_USERNAMES: [&str; 2] = ["demo_alice", "demo_bob"];
+
+/// Name of the demo group
+#[cfg(feature = "ssr")]
+pub const DEMO_GROUP_NAME: &str = "Demo Flat";
+
+/// A demo shared debt and the shares of its participants
+#[cfg(feature = "ssr")]
+struct SeedDebt<'a> {
+    name: &'a str,
+    amount: &'a str,
+    payer_id: i64,
+    category: &'a str,
+    days_ago: i64,
+    /// Participants with their fixed share, `None` for an even split
+    shares: &'a [(i64, Option<&'a str>)],
+}
+
+/// Whether demo data was asked for with `--seed` or `SEED_DEMO_DATA=1`
+#[cfg(feature = "ssr")]
+pub fn seed_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--seed")
+        || std::env::var("SEED_DEMO_DATA").is_ok_and(|value| value.trim() == "1")
+}
+
+/// Create the demo users, their group and its debts, payments and shopping
+/// list where missing, and return the group's id
+#[cfg(feature = "ssr")]
+pub async fn seed_demo_data(pool: &SqlitePool) -> Result<i64, AppError> {
+    let password_hash = hash_password(DEMO_PASSWORD).map_err(AppError::internal)?;
+    let today = OffsetDateTime::now_utc().date();
+
+    let mut tx = pool.begin().await?;
+
+    let alice = seed_user(&mut tx, DEMO_USERNAMES[0], &password_hash).await?;
+    let bob = seed_user(&mut tx, DEMO_USERNAMES[1], &password_hash).await?;
+
+    let group_id = seed_group(&mut tx, alice).await?;
+    for (user_id, role) in [(alice, "admin"), (bob, "member")] {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO group_members (group_id, user_id, role) VALUES (?, ?, ?)",
+            group_id,
+            user_id,
+            role
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Split evenly, unevenly and with a single participant
+    let even = [(alice, None), (bob, None)];
+    let uneven = [(alice, Some("21.00")), (bob, Some("49.00"))];
+    let only_bob = [(bob, None)];
+    let debts = [
+        SeedDebt {
+            name: "Groceries",
+            amount: "64.20",
+            payer_id: alice,
+            category: "Groceries",
+            days_ago: 12,
+            shares: &even,
+        },
+        SeedDebt {
+            name: "Electricity bill",
+            amount: "90.00",
+            payer_id: bob,
+            category: "Utilities",
+            days_ago: 9,
+            shares: &even,
+        },
+        SeedDebt {
+            name: "Concert tickets",
+            amount: "70.00",
+            payer_id: alice,
+            category: "Entertainment",
+            days_ago: 5,
+            shares: &uneven,
+        },
+        SeedDebt {
+            name: "Train ticket",
+            amount: "38.50",
+            payer_id: alice,
+            category: "Travel",
+            days_ago: 2,
+            shares: &only_bob,
+        },
+    ];
+    for debt in debts {
+        let debt_id = seed_shared_debt(
+            &mut tx,
+            group_id,
+            debt.name,
+            debt.amount,
+            debt.payer_id,
+            debt.category,
+            today - Duration::days(debt.days_ago),
+        )
+        .await?;
+        for &(user_id, share_amount) in debt.shares {
+            sqlx::query!(
+                "INSERT OR IGNORE INTO shared_debt_user (shared_debt_id, user_id, share_amount) VALUES (?, ?, ?)",
+                debt_id,
+                user_id,
+                share_amount
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    // Due yesterday, so the next scheduler run generates its first debt
+    let yesterday = today - Duration::days(1);
+    let recurring_debt_id = seed_recurring_debt(&mut tx, group_id, alice, yesterday).await?;
+    for user_id in [alice, bob] {
+        sqlx::query!(
+            "INSERT OR IGNORE INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (?, ?)",
+            recurring_debt_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    seed_transaction(
+        &mut tx,
+        group_id,
+        bob,
+        alice,
+        "20.00",
+        "Groceries, part one",
+    )
+    .await?;
+    seed_transaction(
+        &mut tx,
+        group_id,
+        alice,
+        bob,
+        "15.00",
+        "Half the electricity",
+    )
+    .await?;
+
+    let list_id = seed_shopping_list(&mut tx, group_id, alice).await?;
+    let items: [(&str, &str, Option<i64>); 5] = [
+        ("Milk", "Dairy", None),
+        ("Eggs", "Dairy", Some(bob)),
+        ("Bread", "Bakery", Some(alice)),
+        ("Apples", "Produce", None),
+        ("Coffee", "Pantry", None),
+    ];
+    for (position, (name, category, completed_by)) in (0_i64..).zip(items) {
+        seed_shopping_item(&mut tx, list_id, name, category, position, completed_by).await?;
+    }
+
+    // The rows above bypass the handlers that keep the cache up to date
+    rebuild_group_balances(&mut tx, group_id).await?;
+
+    tx.commit().await?;
+
+    Ok(group_id)
+}
+
+#[cfg(feature = "ssr")]
+async fn seed_user(
+    conn: &mut SqliteConnection,
+    username: &str,
+    password_hash: &str,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query!(
+        "INSERT OR IGNORE INTO users (username, password_hash) VALUES (?, ?)",
+        username,
+        password_hash
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM users WHERE username = ?"#,
+        username
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+async fn seed_group(conn: &mut SqliteConnection, created_by: i64) -> Result<i64, sqlx::Error> {
+    if let Some(id) = sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM groups WHERE name = ? AND created_by = ?"#,
+        DEMO_GROUP_NAME,
+        created_by
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    {
+        return Ok(id);
+    }
+
+    sqlx::query_scalar!(
+        r#"INSERT INTO groups (name, created_by, monthly_budget) VALUES (?, ?, '400') RETURNING id as "id!""#,
+        DEMO_GROUP_NAME,
+        created_by
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+async fn seed_shared_debt(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    name: &str,
+    amount: &str,
+    payer_id: i64,
+    category: &str,
+    incurred_on: Date,
+) -> Result<i64, sqlx::Error> {
+    if let Some(id) = sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM shared_debts WHERE group_id = ? AND name = ?"#,
+        group_id,
+        name
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    {
+        return Ok(id);
+    }
+
+    let incurred_on = incurred_on.to_string();
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, category, incurred_on)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        RETURNING id as "id!"
+        "#,
+        group_id,
+        payer_id,
+        payer_id,
+        name,
+        amount,
+        category,
+        incurred_on
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+async fn seed_recurring_debt(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    created_by: i64,
+    due: Date,
+) -> Result<i64, sqlx::Error> {
+    const NAME: &str = "Internet";
+
+    if let Some(id) = sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM recurring_debts WHERE group_id = ? AND name = ?"#,
+        group_id,
+        NAME
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    {
+        return Ok(id);
+    }
+
+    let due = due.to_string();
+    sqlx::query_scalar!(
+        r#"
+        INSERT INTO recurring_debts (
+            group_id, created_by, name, amount, frequency, start_date, next_generation_date, category
+        )
+        VALUES (?, ?, ?, '39.99', 'monthly', ?, ?, 'Utilities')
+        RETURNING id as "id!"
+        "#,
+        group_id,
+        created_by,
+        NAME,
+        due,
+        due
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+#[cfg(feature = "ssr")]
+async fn seed_transaction(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    payer_id: i64,
+    recipient_id: i64,
+    amount: &str,
+    description: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO transactions (group_id, payer_id, recipient_id, amount, description, confirmation_status)
+        SELECT ?, ?, ?, ?, ?, 'confirmed'
+        WHERE NOT EXISTS (SELECT 1 FROM transactions WHERE group_id = ? AND description = ?)
+        "#,
+        group_id,
+        payer_id,
+        recipient_id,
+        amount,
+        description,
+        group_id,
+        description
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+async fn seed_shopping_list(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    created_by: i64,
+) -> Result<i64, sqlx::Error> {
+    const NAME: &str = "Weekly shop";
+
+    if let Some(id) = sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM shopping_lists WHERE group_id = ? AND name = ?"#,
+        group_id,
+        NAME
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    {
+        return Ok(id);
+    }
+
+    sqlx::query_scalar!(
+        r#"INSERT INTO shopping_lists (group_id, created_by, name) VALUES (?, ?, ?) RETURNING id as "id!""#,
+        group_id,
+        created_by,
+        NAME
+    )
+    .fetch_one(&mut *conn)
+    .await
+}
+
+/// Add an item, completed by `completed_by` if given
+#[cfg(feature = "ssr")]
+async fn seed_shopping_item(
+    conn: &mut SqliteConnection,
+    list_id: i64,
+    name: &str,
+    category: &str,
+    position: i64,
+    completed_by: Option<i64>,
+) -> Result<(), sqlx::Error> {
+    let is_completed = completed_by.is_some();
+    sqlx::query!(
+        r#"
+        INSERT INTO shopping_list_items (
+            shopping_list_id, name, category, position, is_completed, completed_by, completed_at
+        )
+        SELECT ?, ?, ?, ?, ?, ?, CASE WHEN ? THEN CURRENT_TIMESTAMP END
+        WHERE NOT EXISTS (SELECT 1 FROM shopping_list_items WHERE shopping_list_id = ? AND name = ?)
+        "#,
+        list_id,
+        name,
+        category,
+        position,
+        is_completed,
+        completed_by,
+        is_completed,
+        list_id,
+        name
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::features::{
+        auth::utils::verify_password, transactions::balances::cached_pair_balances,
+    };
+
+    #[tokio::test]
+    async fn test_seeding_twice_adds_nothing() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        async fn counts(pool: &SqlitePool) -> Vec<i64> {
+            let mut counts = Vec::new();
+            for table in [
+                "users",
+                "groups",
+                "group_members",
+                "shared_debts",
+                "shared_debt_user",
+                "recurring_debts",
+                "recurring_debt_user",
+                "transactions",
+                "shopping_lists",
+                "shopping_list_items",
+            ] {
+                let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+                    .fetch_one(pool)
+                    .await
+                    .unwrap();
+                counts.push(count);
+            }
+            counts
+        }
+
+        let group_id = seed_demo_data(&pool).await.unwrap();
+        let first = counts(&pool).await;
+        assert_eq!(first, vec![2, 1, 2, 4, 7, 1, 2, 2, 1, 5]);
+
+        assert_eq!(seed_demo_data(&pool).await.unwrap(), group_id);
+        assert_eq!(counts(&pool).await, first);
+
+        let hash: String = sqlx::query_scalar("SELECT password_hash FROM users LIMIT 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(verify_password(DEMO_PASSWORD, &hash).unwrap());
+        assert!(
+            !cached_pair_balances(&pool, group_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,87 +1,299 @@
-use leptos::prelude::ServerFnError;
+//! Errors returned by server functions
+//!
+//! [`AppError`] travels to the client as JSON, so pages can tell a missing
+//! login from a rejected form. Database and other internal failures are
+//! logged on the server and reach the client only as
+//! [`AppError::Internal`], keeping SQL out of the UI.
 
-/// Custom application error type with better conversion handling
-#[cfg(feature = "ssr")]
-#[derive(Debug)]
+use std::fmt;
+
+use leptos::prelude::*;
+use leptos::server_fn::codec::JsonEncoding;
+use serde::{Deserialize, Serialize};
+
+/// Error of a server function, with a message safe to show to users
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AppError {
-    Database(sqlx::Error),
-    Authentication(String),
-    Authorization(String),
-    Validation(String),
+    /// Nobody is logged in
+    Unauthorized,
+    /// The logged in user may not do this
+    Forbidden(String),
+    /// What was asked for does not exist
     NotFound(String),
-    Internal(String),
+    /// The input was rejected
+    Validation(String),
+    /// The request clashes with the current state, e.g. a stale edit
+    Conflict(String),
+    /// Something failed on the server; the details are only logged
+    Internal,
+    /// Calling the server function itself failed, e.g. while offline
+    ServerFn(ServerFnErrorErr),
 }
 
-#[cfg(feature = "ssr")]
-impl std::fmt::Display for AppError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl AppError {
+    pub fn forbidden<S: Into<String>>(msg: S) -> Self {
+        Self::Forbidden(msg.into())
+    }
+
+    pub fn not_found<S: Into<String>>(msg: S) -> Self {
+        Self::NotFound(msg.into())
+    }
+
+    pub fn validation<S: Into<String>>(msg: S) -> Self {
+        Self::Validation(msg.into())
+    }
+
+    pub fn conflict<S: Into<String>>(msg: S) -> Self {
+        Self::Conflict(msg.into())
+    }
+
+    /// Log an unexpected failure and hide it behind [`AppError::Internal`]
+    pub fn internal(error: impl fmt::Display) -> Self {
+        #[cfg(feature = "ssr")]
+        tracing::error!(error = %error, "Server function failed");
+        #[cfg(not(feature = "ssr"))]
+        let _ = error;
+        Self::Internal
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Database(e) => write!(f, "Database error: {}", e),
-            Self::Authentication(msg) => write!(f, "Authentication error: {}", msg),
-            Self::Authorization(msg) => write!(f, "Authorization error: {}", msg),
-            Self::Validation(msg) => write!(f, "Validation error: {}", msg),
-            Self::NotFound(msg) => write!(f, "Not found: {}", msg),
-            Self::Internal(msg) => write!(f, "Internal error: {}", msg),
+            Self::Unauthorized => write!(f, "Please log in to continue"),
+            Self::Forbidden(msg)
+            | Self::NotFound(msg)
+            | Self::Validation(msg)
+            | Self::Conflict(msg) => write!(f, "{}", msg),
+            Self::Internal => write!(f, "Something went wrong, please try again"),
+            Self::ServerFn(e) => write!(f, "{}", e),
         }
     }
 }
 
-#[cfg(feature = "ssr")]
 impl std::error::Error for AppError {}
 
-#[cfg(feature = "ssr")]
-impl From<sqlx::Error> for AppError {
-    fn from(error: sqlx::Error) -> Self {
-        Self::Database(error)
+impl FromServerFnError for AppError {
+    type Encoder = JsonEncoding;
+
+    fn from_server_fn_error(value: ServerFnErrorErr) -> Self {
+        Self::ServerFn(value)
     }
 }
 
-#[cfg(feature = "ssr")]
-impl AppError {
-    /// Convert to ServerFnError
-    pub fn into_server_error(self) -> ServerFnError {
-        // Convert AppError to ServerFnError
-        // For production, you might want to log detailed errors but return generic
-        // messages
-        match self {
-            Self::Database(e) => {
-                // Log the actual database error
-                eprintln!("Database error: {:?}", e);
-                ServerFnError::new("A database error occurred")
+/// Errors of helpers that still return [`ServerFnError`], such as the
+/// validation rules
+///
+/// Their messages are written for users, so they pass as
+/// [`AppError::Validation`].
+impl From<ServerFnError> for AppError {
+    fn from(error: ServerFnError) -> Self {
+        match error {
+            ServerFnError::ServerError(msg) => Self::Validation(msg),
+            ServerFnError::Registration(msg) => Self::ServerFn(ServerFnErrorErr::Registration(msg)),
+            ServerFnError::Request(msg) => Self::ServerFn(ServerFnErrorErr::Request(msg)),
+            ServerFnError::Response(msg) => Self::ServerFn(ServerFnErrorErr::Response(msg)),
+            ServerFnError::MiddlewareError(msg) => {
+                Self::ServerFn(ServerFnErrorErr::MiddlewareError(msg))
             }
-            Self::Authentication(msg) => ServerFnError::new(msg),
-            Self::Authorization(msg) => ServerFnError::new(msg),
-            Self::Validation(msg) => ServerFnError::new(msg),
-            Self::NotFound(msg) => ServerFnError::new(msg),
-            Self::Internal(e) => {
-                eprintln!("Internal error: {}", e);
-                ServerFnError::new("An internal error occurred")
+            ServerFnError::Deserialization(msg) => {
+                Self::ServerFn(ServerFnErrorErr::Deserialization(msg))
             }
+            ServerFnError::Serialization(msg) => {
+                Self::ServerFn(ServerFnErrorErr::Serialization(msg))
+            }
+            ServerFnError::Args(msg) => Self::ServerFn(ServerFnErrorErr::Args(msg)),
+            ServerFnError::MissingArg(msg) => Self::ServerFn(ServerFnErrorErr::MissingArg(msg)),
+            #[allow(deprecated)]
+            ServerFnError::WrappedServerError(_) => Self::Internal,
         }
     }
+}
 
-    /// Create authentication error
-    pub fn auth<S: Into<String>>(msg: S) -> Self {
-        Self::Authentication(msg.into())
+#[cfg(feature = "ssr")]
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::internal(error)
     }
+}
 
-    /// Create authorization error
-    pub fn unauthorized<S: Into<String>>(msg: S) -> Self {
-        Self::Authorization(msg.into())
+/// Message to show for a failed server function, or `None` after sending
+/// the user to the login page because they are logged out
+///
+/// Pages render the message where they show form errors, so validation
+/// failures appear next to the form that caused them.
+pub fn handle_app_error(error: &AppError, redirect_to_login: impl Fn()) -> Option<String> {
+    match error {
+        AppError::Unauthorized => {
+            redirect_to_login();
+            None
+        }
+        error => Some(error.to_string()),
     }
+}
 
-    /// Create validation error
-    pub fn validation<S: Into<String>>(msg: S) -> Self {
-        Self::Validation(msg.into())
+/// Next step offered on a page that failed to load
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Link to a page that still works, e.g. the list of groups
+    Back {
+        label: String,
+        href: String,
+    },
+    /// Log out so someone with access can log in
+    SwitchAccount,
+    LogIn,
+    /// Load the page again
+    Retry,
+}
+
+/// What a page shows in place of its content when loading it failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorSummary {
+    pub title: String,
+    pub message: String,
+    pub action: ErrorAction,
+    /// Whether what the page shows is gone or never existed
+    pub not_found: bool,
+}
+
+/// Friendly title, explanation and next step for a page that failed to load
+///
+/// `back_label` and `back_href` point to the page to return to when the
+/// thing shown does not exist. Handlers that still return
+/// [`ServerFnError`] arrive as [`AppError::Validation`], so their known
+/// messages are recognized by their wording.
+pub fn summarize_page_error(error: &AppError, back_label: &str, back_href: &str) -> ErrorSummary {
+    let back = || ErrorAction::Back {
+        label: back_label.to_string(),
+        href: back_href.to_string(),
+    };
+    let summary = |title: &str, message: &str, action: ErrorAction| ErrorSummary {
+        title: title.to_string(),
+        message: message.to_string(),
+        action,
+        not_found: false,
+    };
+    let not_found = |title: &str| ErrorSummary {
+        title: title.to_string(),
+        message: "It may have been deleted, or the link is out of date.".to_string(),
+        action: back(),
+        not_found: true,
+    };
+    let forbidden =
+        |message: &str| summary("You don't have access", message, ErrorAction::SwitchAccount);
+    let logged_out = || {
+        summary(
+            "You're logged out",
+            "Please log in to continue.",
+            ErrorAction::LogIn,
+        )
+    };
+
+    match error {
+        AppError::Unauthorized => logged_out(),
+        AppError::NotFound(msg) => not_found(msg),
+        AppError::Forbidden(msg) => forbidden(msg),
+        AppError::Validation(msg) | AppError::Conflict(msg) => {
+            let lower = msg.to_lowercase();
+            if lower == "not authenticated" {
+                logged_out()
+            } else if lower.contains("not found") {
+                not_found("Not found")
+            } else if lower.contains("access denied")
+                || lower.contains("not a member")
+                || lower.starts_with("unauthorized")
+            {
+                forbidden("You need to be a member of the group to see this.")
+            } else {
+                summary("This page couldn't be loaded", msg, back())
+            }
+        }
+        AppError::Internal => summary(
+            "Something went wrong",
+            "Please try again in a moment.",
+            ErrorAction::Retry,
+        ),
+        AppError::ServerFn(_) => summary(
+            "Couldn't reach Splitify",
+            "Check your connection and try again.",
+            ErrorAction::Retry,
+        ),
     }
+}
 
-    /// Create not found error
-    pub fn not_found<S: Into<String>>(msg: S) -> Self {
-        Self::NotFound(msg.into())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_are_user_safe() {
+        assert_eq!(
+            AppError::validation("Name is required").to_string(),
+            "Name is required"
+        );
+        assert_eq!(
+            AppError::internal("no such table: groups").to_string(),
+            "Something went wrong, please try again"
+        );
+        // Helper messages keep their text instead of gaining a prefix
+        assert_eq!(
+            AppError::from(ServerFnError::new("Amount is too large")),
+            AppError::validation("Amount is too large")
+        );
     }
 
-    /// Create internal error
-    pub fn internal<S: Into<String>>(msg: S) -> Self {
-        Self::Internal(msg.into())
+    #[test]
+    fn test_handle_app_error() {
+        use std::cell::Cell;
+
+        let redirected = Cell::new(false);
+        let redirect = || redirected.set(true);
+
+        assert_eq!(
+            handle_app_error(&AppError::conflict("Changed meanwhile"), redirect),
+            Some("Changed meanwhile".to_string())
+        );
+        assert!(!redirected.get());
+        assert_eq!(handle_app_error(&AppError::Unauthorized, redirect), None);
+        assert!(redirected.get());
+    }
+
+    #[test]
+    fn test_summarize_page_error() {
+        let summarize = |error: AppError| summarize_page_error(&error, "Back to groups", "/groups");
+
+        let summary = summarize(AppError::not_found("Group not found"));
+        assert_eq!(summary.title, "Group not found");
+        assert!(summary.not_found);
+        assert_eq!(
+            summary.action,
+            ErrorAction::Back {
+                label: "Back to groups".to_string(),
+                href: "/groups".to_string(),
+            }
+        );
+
+        let summary = summarize(AppError::forbidden("Not a member of this group"));
+        assert_eq!(summary.title, "You don't have access");
+        assert_eq!(summary.action, ErrorAction::SwitchAccount);
+
+        // Messages of handlers returning `ServerFnError`
+        let summary =
+            summarize(ServerFnError::new("Unauthorized: Not a member of this list's group").into());
+        assert_eq!(summary.action, ErrorAction::SwitchAccount);
+        assert!(
+            summarize(ServerFnError::new("Recurring debt not found or access denied").into())
+                .not_found
+        );
+        assert_eq!(
+            summarize(ServerFnError::new("Not authenticated").into()).action,
+            ErrorAction::LogIn
+        );
+
+        // Nothing about the failure itself reaches the page
+        let summary = summarize(AppError::internal("no such column: sd.status"));
+        assert_eq!(summary.action, ErrorAction::Retry);
+        assert!(!summary.message.contains("column"));
     }
 }
@@ -0,0 +1,12 @@
+//! JSON API for scripts and other third-party clients
+//!
+//! Everything lives under `/api/v1` and is authenticated with personal API
+//! tokens (see [`auth::api_tokens`](crate::features::auth::api_tokens)) sent
+//! as `Authorization: Bearer <token>`. Responses use the same JSON shapes as
+//! the server functions; errors come back as `{"error": "<message>"}`.
+
+#[cfg(feature = "ssr")]
+mod routes;
+
+#[cfg(feature = "ssr")]
+pub use routes::{ApiError, ApiState, ApiUser, api_router};
@@ -0,0 +1,413 @@
+//! Routes of the `/api/v1` JSON API
+
+use axum::{
+    Json, Router,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Deserialize;
+use sqlx::SqlitePool;
+
+use crate::error::AppError;
+use crate::features::{
+    auth::{api_tokens::authenticate_api_token, models::UserSession},
+    groups::{
+        GroupEventBroadcaster,
+        handlers::{load_member_group, load_user_groups},
+        models::{Group, GroupWithMembers},
+    },
+    shared_debts::{
+        handlers::{check_new_shared_debt, create_shared_debt_internal, load_shared_debt_page},
+        models::{NewSharedDebt, SharedDebtPage},
+    },
+    transactions::{
+        handlers::{
+            check_new_transaction, compute_group_balances, create_transaction_internal,
+            load_transaction_page,
+        },
+        models::{NewTransaction, TransactionPage, UserBalance},
+    },
+    webhooks::WebhookDispatcher,
+};
+use crate::validation::rules::check_page;
+
+/// What the API handlers share
+#[derive(Clone)]
+pub struct ApiState {
+    pub pool: SqlitePool,
+    pub group_broadcaster: GroupEventBroadcaster,
+    pub webhooks: WebhookDispatcher,
+}
+
+/// Routes of the JSON API, ready to be merged into the app
+pub fn api_router<S: Clone + Send + Sync + 'static>(state: ApiState) -> Router<S> {
+    Router::new()
+        .route("/api/v1/groups", get(list_groups))
+        .route("/api/v1/groups/{group_id}", get(show_group))
+        .route("/api/v1/groups/{group_id}/balances", get(list_balances))
+        .route(
+            "/api/v1/groups/{group_id}/shared-debts",
+            get(list_shared_debts).post(create_shared_debt),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/transactions",
+            get(list_transactions).post(create_transaction),
+        )
+        .with_state(state)
+}
+
+/// An [`AppError`] answered with a matching status code and a JSON body
+#[derive(Debug)]
+pub struct ApiError(pub AppError);
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        Self(error)
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(error: sqlx::Error) -> Self {
+        Self(error.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match &self.0 {
+            AppError::Unauthorized => {
+                // Scripts have no login page to go to
+                let body = Json(serde_json::json!({ "error": "Missing or invalid API token" }));
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    [(header::WWW_AUTHENTICATE, "Bearer")],
+                    body,
+                )
+                    .into_response();
+            }
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Internal | AppError::ServerFn(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (
+            status,
+            Json(serde_json::json!({ "error": self.0.to_string() })),
+        )
+            .into_response()
+    }
+}
+
+/// The user whose `Authorization: Bearer` token came with the request
+pub struct ApiUser(pub UserSession);
+
+impl FromRequestParts<ApiState> for ApiUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ApiState,
+    ) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AppError::Unauthorized)?;
+
+        let user = authenticate_api_token(&state.pool, token)
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+
+        Ok(Self(user))
+    }
+}
+
+#[derive(Deserialize)]
+struct PageQuery {
+    /// Rows per page; 25 when omitted
+    limit: Option<i64>,
+    /// Rows to skip, counted from the newest
+    offset: Option<i64>,
+}
+
+/// `GET /api/v1/groups`: The caller's groups
+async fn list_groups(
+    State(state): State<ApiState>,
+    ApiUser(user): ApiUser,
+) -> Result<Json<Vec<GroupWithMembers>>, ApiError> {
+    let groups = load_user_groups(&state.pool, user.id).await?;
+
+    Ok(Json(groups))
+}
+
+/// `GET /api/v1/groups/{group_id}`
+async fn show_group(
+    State(state): State<ApiState>,
+    ApiUser(user): ApiUser,
+    Path(group_id): Path<i64>,
+) -> Result<Json<Group>, ApiError> {
+    let group = load_member_group(&state.pool, group_id, user.id).await?;
+
+    Ok(Json(group))
+}
+
+/// `GET /api/v1/groups/{group_id}/balances`: Every member's balance, or only
+/// the caller's when the group hides the others from members
+async fn list_balances(
+    State(state): State<ApiState>,
+    ApiUser(user): ApiUser,
+    Path(group_id): Path<i64>,
+) -> Result<Json<Vec<UserBalance>>, ApiError> {
+    let group = load_member_group(&state.pool, group_id, user.id).await?;
+
+    let mut balances = compute_group_balances(&state.pool, group_id).await?;
+    if !group.full_balances_visible && !group.is_admin {
+        balances.retain(|balance| balance.user_id == user.id);
+    }
+
+    Ok(Json(balances))
+}
+
+/// `GET /api/v1/groups/{group_id}/shared-debts?limit=&offset=`
+async fn list_shared_debts(
+    State(state): State<ApiState>,
+    ApiUser(user): ApiUser,
+    Path(group_id): Path<i64>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<SharedDebtPage>, ApiError> {
+    let (limit, offset) = check_page(query.limit, query.offset).map_err(AppError::Validation)?;
+    load_member_group(&state.pool, group_id, user.id).await?;
+
+    let page = load_shared_debt_page(
+        &state.pool,
+        group_id,
+        user.id,
+        limit,
+        offset,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await?;
+
+    Ok(Json(page))
+}
+
+/// `POST /api/v1/groups/{group_id}/shared-debts`: Add a debt paid by the
+/// caller unless `payer_id` says otherwise
+async fn create_shared_debt(
+    State(state): State<ApiState>,
+    ApiUser(user): ApiUser,
+    Path(group_id): Path<i64>,
+    Json(debt): Json<NewSharedDebt>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let debt = check_new_shared_debt(debt)?;
+    let id = create_shared_debt_internal(
+        &state.pool,
+        &state.group_broadcaster,
+        &state.webhooks,
+        &user,
+        group_id,
+        debt,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(serde_json::json!({ "id": id }))))
+}
+
+/// `GET /api/v1/groups/{group_id}/transactions?limit=&offset=`
+async fn list_transactions(
+    State(state): State<ApiState>,
+    ApiUser(user): ApiUser,
+    Path(group_id): Path<i64>,
+    Query(query): Query<PageQuery>,
+) -> Result<Json<TransactionPage>, ApiError> {
+    let (limit, offset) = check_page(query.limit, query.offset).map_err(AppError::Validation)?;
+    load_member_group(&state.pool, group_id, user.id).await?;
+
+    let page = load_transaction_page(&state.pool, group_id, limit, offset, None, None).await?;
+
+    Ok(Json(page))
+}
+
+/// `POST /api/v1/groups/{group_id}/transactions`: Record a payment from the
+/// caller
+async fn create_transaction(
+    State(state): State<ApiState>,
+    ApiUser(user): ApiUser,
+    Path(group_id): Path<i64>,
+    Json(payment): Json<NewTransaction>,
+) -> Result<(StatusCode, Json<serde_json::Value>), ApiError> {
+    let payment = check_new_transaction(payment)?;
+    let id = create_transaction_internal(
+        &state.pool,
+        &state.group_broadcaster,
+        &state.webhooks,
+        &user,
+        group_id,
+        payment,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(serde_json::json!({ "id": id }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{Body, to_bytes};
+    use axum::http::Request;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::features::{
+        auth::api_tokens::insert_api_token, groups::create_group_broadcaster,
+        webhooks::create_webhook_dispatcher,
+    };
+
+    async fn send(
+        router: &Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_token_holders_read_and_add_records() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1), ('Elsewhere', 2)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (2, 2)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let token = insert_api_token(&pool, 1, "Bank import")
+            .await
+            .unwrap()
+            .secret;
+
+        let router = api_router(ApiState {
+            pool: pool.clone(),
+            group_broadcaster: create_group_broadcaster(),
+            webhooks: create_webhook_dispatcher(pool.clone()),
+        });
+
+        let (status, _) = send(&router, "GET", "/api/v1/groups", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(&router, "GET", "/api/v1/groups", Some("spl_guess"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, groups) = send(&router, "GET", "/api/v1/groups", Some(&token), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(groups[0]["name"], "Flat");
+        assert_eq!(groups.as_array().unwrap().len(), 1);
+
+        let (status, _) = send(&router, "GET", "/api/v1/groups/2", Some(&token), None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, error) = send(
+            &router,
+            "POST",
+            "/api/v1/groups/1/shared-debts",
+            Some(&token),
+            Some(serde_json::json!({ "name": "Groceries", "amount": "30", "member_ids": [] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(
+            error["error"]
+                .as_str()
+                .unwrap()
+                .contains("At least one member")
+        );
+
+        let (status, created) = send(
+            &router,
+            "POST",
+            "/api/v1/groups/1/shared-debts",
+            Some(&token),
+            Some(serde_json::json!({
+                "name": "Groceries",
+                "amount": "30",
+                "member_ids": [1, 2],
+                "incurred_on": "2026-03-01",
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(created["id"].is_i64());
+
+        let (status, created) = send(
+            &router,
+            "POST",
+            "/api/v1/groups/1/transactions",
+            Some(&token),
+            Some(serde_json::json!({ "recipient_id": 2, "amount": "5" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(created["id"].is_i64());
+
+        let (_, debts) = send(
+            &router,
+            "GET",
+            "/api/v1/groups/1/shared-debts",
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(debts["total_count"], 1);
+        assert_eq!(debts["debts"][0]["name"], "Groceries");
+
+        let (_, transactions) = send(
+            &router,
+            "GET",
+            "/api/v1/groups/1/transactions?limit=10",
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(transactions["total_count"], 1);
+
+        let (status, balances) = send(
+            &router,
+            "GET",
+            "/api/v1/groups/1/balances",
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(balances.as_array().unwrap().len(), 2);
+    }
+}
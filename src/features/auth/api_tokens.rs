@@ -0,0 +1,272 @@
+//! Personal tokens for the JSON API
+//!
+//! Scripts send a token as `Authorization: Bearer <token>` instead of logging
+//! in. Tokens are shown once when created and stored as SHA-256 hashes, which
+//! is enough for random values of this length and lets each request find its
+//! token with a single lookup.
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use super::models::{ApiToken, CreatedApiToken};
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::auth::{models::UserSession, utils::get_user_from_session};
+#[cfg(feature = "ssr")]
+use crate::validation::{rules::MAX_NAME_LENGTH, validate_name};
+
+/// Start of every token, so leaked tokens are easy to recognize
+#[cfg(feature = "ssr")]
+const TOKEN_PREFIX: &str = "spl_";
+
+/// Server function: The current user's API tokens, newest first
+#[server(GetApiTokens)]
+pub async fn get_api_tokens() -> Result<Vec<ApiToken>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let tokens = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!",
+            name,
+            created_at as "created_at!: time::OffsetDateTime",
+            last_used_at as "last_used_at: time::OffsetDateTime"
+        FROM api_tokens
+        WHERE user_id = ?
+        ORDER BY created_at DESC, id DESC
+        "#,
+        user.id
+    )
+    .fetch_all(&pool)
+    .await?
+    .into_iter()
+    .map(|row| ApiToken {
+        id: row.id,
+        name: row.name,
+        created_at: row.created_at,
+        last_used_at: row.last_used_at,
+    })
+    .collect();
+
+    Ok(tokens)
+}
+
+/// Server function: Create an API token for the current user
+#[server(CreateApiToken)]
+pub async fn create_api_token(name: String) -> Result<CreatedApiToken, AppError> {
+    use sqlx::SqlitePool;
+
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let created = insert_api_token(&pool, user.id, &name).await?;
+
+    tracing::info!(
+        user_id = user.id,
+        token_id = created.token.id,
+        "Created API token"
+    );
+
+    Ok(created)
+}
+
+/// Server function: Revoke one of the current user's API tokens
+#[server(RevokeApiToken)]
+pub async fn revoke_api_token(token_id: i64) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let deleted = sqlx::query!(
+        "DELETE FROM api_tokens WHERE id = ? AND user_id = ?",
+        token_id,
+        user.id
+    )
+    .execute(&pool)
+    .await?
+    .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::not_found("API token not found"));
+    }
+
+    tracing::info!(user_id = user.id, token_id, "Revoked API token");
+
+    Ok(())
+}
+
+/// Generate a random token, `spl_` followed by 64 hex digits
+#[cfg(feature = "ssr")]
+fn generate_api_token() -> String {
+    format!(
+        "{}{}{}",
+        TOKEN_PREFIX,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Hash stored for a token
+#[cfg(feature = "ssr")]
+fn hash_api_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Store a new token for `user_id` and return it with its plain text
+#[cfg(feature = "ssr")]
+pub async fn insert_api_token(
+    pool: &sqlx::SqlitePool,
+    user_id: i64,
+    name: &str,
+) -> Result<CreatedApiToken, sqlx::Error> {
+    let secret = generate_api_token();
+    let token_hash = hash_api_token(&secret);
+
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO api_tokens (user_id, name, token_hash)
+        VALUES (?, ?, ?)
+        RETURNING id as "id!", created_at as "created_at!: time::OffsetDateTime"
+        "#,
+        user_id,
+        name,
+        token_hash
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(CreatedApiToken {
+        token: ApiToken {
+            id: row.id,
+            name: name.to_string(),
+            created_at: row.created_at,
+            last_used_at: None,
+        },
+        secret,
+    })
+}
+
+/// The user a token belongs to, or `None` for unknown and revoked tokens
+///
+/// Marks the token as used.
+#[cfg(feature = "ssr")]
+pub async fn authenticate_api_token(
+    pool: &sqlx::SqlitePool,
+    token: &str,
+) -> Result<Option<UserSession>, sqlx::Error> {
+    let token_hash = hash_api_token(token.trim());
+
+    let Some(user) = sqlx::query!(
+        r#"
+        SELECT u.id as "id!", u.username, u.session_generation
+        FROM api_tokens t
+        INNER JOIN users u ON t.user_id = u.id
+        WHERE t.token_hash = ?
+        "#,
+        token_hash
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query!(
+        "UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE token_hash = ?",
+        token_hash
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(Some(UserSession {
+        id: user.id,
+        username: user.username,
+        session_generation: user.session_generation,
+    }))
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tokens_authenticate_until_revoked() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('alice', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let created = insert_api_token(&pool, 1, "Bank import").await.unwrap();
+        assert!(created.secret.starts_with(TOKEN_PREFIX));
+        assert_eq!(created.secret.len(), TOKEN_PREFIX.len() + 64);
+
+        // Only the hash is stored
+        let stored: String = sqlx::query_scalar("SELECT token_hash FROM api_tokens")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, created.secret);
+
+        let user = authenticate_api_token(&pool, &created.secret)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.username, "alice");
+        let used: Option<String> = sqlx::query_scalar("SELECT last_used_at FROM api_tokens")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(used.is_some());
+
+        assert!(
+            authenticate_api_token(&pool, "spl_guess")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        sqlx::query("DELETE FROM api_tokens")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(
+            authenticate_api_token(&pool, &created.secret)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -6,10 +6,16 @@ use tower_sessions::Session;
 
 #[cfg(feature = "ssr")]
 use super::models::User;
-use super::models::UserSession;
+use super::models::{Registration, UserSession};
+#[cfg(feature = "ssr")]
+use super::recovery::{
+    RESET_ATTEMPT_WINDOW_MINUTES, consume_recovery_code, generate_recovery_codes,
+    record_failed_reset_attempt, replace_recovery_codes, reset_attempts_exhausted,
+};
 #[cfg(feature = "ssr")]
 use super::utils::{
-    clear_session, get_user_from_session, hash_password, set_user_in_session, verify_password,
+    bump_session_generation, clear_session, get_user_from_session, hash_password, require_auth,
+    set_user_in_session, verify_password,
 };
 #[cfg(feature = "ssr")]
 use crate::validation::{validate_email, validate_password, validate_username};
@@ -20,7 +26,7 @@ pub async fn register_user(
     username: String,
     password: String,
     email: Option<String>,
-) -> Result<UserSession, ServerFnError> {
+) -> Result<Registration, ServerFnError> {
     use sqlx::SqlitePool;
 
     // Validate username
@@ -51,6 +57,11 @@ pub async fn register_user(
         ));
     }
 
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
     // Insert new user
     let result = sqlx::query!(
         "INSERT INTO users (username, password_hash, email) VALUES (?, ?, ?)",
@@ -58,12 +69,21 @@ pub async fn register_user(
         password_hash,
         email
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
     .map_err(|e| ServerFnError::new(e.to_string()))?;
 
     let user_id = result.last_insert_rowid();
 
+    let recovery_codes = generate_recovery_codes();
+    replace_recovery_codes(&mut tx, user_id, &recovery_codes)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
     // Extract session using Axum extractor pattern
     let session = extract::<Session>()
         .await
@@ -72,13 +92,17 @@ pub async fn register_user(
     let user_session = UserSession {
         id: user_id,
         username: username.clone(),
+        session_generation: 0,
     };
 
     set_user_in_session(&session, &user_session)
         .await
         .map_err(|_| ServerFnError::new("Authentication error"))?;
 
-    Ok(user_session)
+    Ok(Registration {
+        user: user_session,
+        recovery_codes,
+    })
 }
 
 /// Server function: Login an existing user
@@ -99,7 +123,7 @@ pub async fn login_user(username: String, password: String) -> Result<UserSessio
 
     // Fetch user from database
     let user = sqlx::query_as::<_, User>(
-        "SELECT id, username, password_hash, email FROM users WHERE username = ?",
+        "SELECT id, username, password_hash, email, session_generation FROM users WHERE username = ?",
     )
     .bind(&username)
     .fetch_optional(&pool)
@@ -115,6 +139,7 @@ pub async fn login_user(username: String, password: String) -> Result<UserSessio
             let session = UserSession {
                 id: user.id,
                 username: user.username,
+                session_generation: user.session_generation,
             };
             (valid, Some(session))
         }
@@ -171,3 +196,333 @@ pub async fn get_user() -> Result<Option<UserSession>, ServerFnError> {
 
     Ok(get_user_from_session(&session).await)
 }
+
+/// Server function: Log the current user out on every other device
+///
+/// The current session is issued again so this device stays logged in.
+#[server(LogoutAllSessions)]
+pub async fn logout_all_sessions() -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let session_generation = bump_session_generation(&mut conn, user.id)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    reissue_session(&session, user, session_generation).await
+}
+
+/// Store `user` again under a new session id with the given generation
+#[cfg(feature = "ssr")]
+async fn reissue_session(
+    session: &Session,
+    user: UserSession,
+    session_generation: i64,
+) -> Result<(), ServerFnError> {
+    session
+        .cycle_id()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    set_user_in_session(
+        session,
+        &UserSession {
+            session_generation,
+            ..user
+        },
+    )
+    .await
+    .map_err(|_| ServerFnError::new("Authentication error"))
+}
+
+/// Server function: Replace the current user's recovery codes with a new set
+///
+/// Earlier codes stop working. The new codes are returned in plain text and
+/// cannot be retrieved again.
+#[server(RegenerateRecoveryCodes)]
+pub async fn regenerate_recovery_codes() -> Result<Vec<String>, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let user = require_auth().await?;
+    let pool = expect_context::<SqlitePool>();
+
+    let recovery_codes = generate_recovery_codes();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    replace_recovery_codes(&mut tx, user.id, &recovery_codes)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    Ok(recovery_codes)
+}
+
+/// Server function: Set a new password using one of the user's recovery codes
+///
+/// The code is used up on success. Failed attempts are rate limited per
+/// username.
+#[server(ResetPasswordWithCode)]
+pub async fn reset_password_with_code(
+    username: String,
+    code: String,
+    new_password: String,
+) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use crate::validation::sanitize_string;
+
+    let username = sanitize_string(&username);
+    if username.is_empty() || code.trim().is_empty() {
+        return Err(ServerFnError::new(
+            "Username and recovery code are required",
+        ));
+    }
+    validate_password(&new_password)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if reset_attempts_exhausted(&pool, &username)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+    {
+        return Err(ServerFnError::new(format!(
+            "Too many failed attempts. Please try again in {} minutes.",
+            RESET_ATTEMPT_WINDOW_MINUTES
+        )));
+    }
+
+    let user_id = sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM users WHERE username = ?"#,
+        username
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let valid = match user_id {
+        Some(user_id) => consume_recovery_code(&pool, user_id, &code)
+            .await
+            .map_err(|e| ServerFnError::new(e.to_string()))?,
+        None => false,
+    };
+
+    let Some(user_id) = user_id.filter(|_| valid) else {
+        record_failed_reset_attempt(&pool, &username)
+            .await
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+        return Err(ServerFnError::new("Invalid username or recovery code"));
+    };
+
+    let password_hash =
+        hash_password(&new_password).map_err(|e| ServerFnError::new(e.to_string()))?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    sqlx::query!(
+        "UPDATE users SET password_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        password_hash,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    // Whoever knew the old password may still be logged in somewhere
+    bump_session_generation(&mut tx, user_id)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Server function: Change the current user's password
+#[server(ChangePassword)]
+pub async fn change_password(
+    current_password: String,
+    new_password: String,
+) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+    validate_password(&new_password)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let current_hash = sqlx::query_scalar!("SELECT password_hash FROM users WHERE id = ?", user.id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .ok_or_else(|| ServerFnError::new("User not found"))?;
+
+    if !verify_password(&current_password, &current_hash).unwrap_or(false) {
+        return Err(ServerFnError::new("Current password is incorrect"));
+    }
+
+    let password_hash =
+        hash_password(&new_password).map_err(|e| ServerFnError::new(e.to_string()))?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    sqlx::query!(
+        "UPDATE users SET password_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        password_hash,
+        user.id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    // Other devices have to log in with the new password
+    let session_generation = bump_session_generation(&mut tx, user.id)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    reissue_session(&session, user, session_generation).await
+}
+
+/// Server function: Permanently delete the current user's account
+///
+/// Refused while the user created a group or has an unsettled balance in any
+/// group. Debts and payments involving the user are removed along with the
+/// account.
+#[server(DeleteAccount)]
+pub async fn delete_account(password: String) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use crate::features::transactions::{handlers::compute_group_balances, models::NetType};
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let password_hash =
+        sqlx::query_scalar!("SELECT password_hash FROM users WHERE id = ?", user.id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| ServerFnError::new(e.to_string()))?
+            .ok_or_else(|| ServerFnError::new("User not found"))?;
+
+    if !verify_password(&password, &password_hash).unwrap_or(false) {
+        return Err(ServerFnError::new("Password is incorrect"));
+    }
+
+    let created_groups =
+        sqlx::query_scalar!("SELECT name FROM groups WHERE created_by = ?", user.id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    if !created_groups.is_empty() {
+        return Err(ServerFnError::new(format!(
+            "You created {}. Delete your groups before deleting your account.",
+            created_groups.join(", ")
+        )));
+    }
+
+    let memberships = sqlx::query!(
+        r#"
+        SELECT g.id as "id!", g.name
+        FROM groups g
+        JOIN group_members gm ON gm.group_id = g.id
+        WHERE gm.user_id = ?
+        "#,
+        user.id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    for group in memberships {
+        let balances = compute_group_balances(&pool, group.id).await?;
+        let unsettled = balances
+            .into_iter()
+            .find(|b| b.user_id == user.id && b.net_type != NetType::Neutral);
+        if unsettled.is_some() {
+            return Err(ServerFnError::new(format!(
+                "You have an unsettled balance in {}. Settle up before deleting your account.",
+                group.name
+            )));
+        }
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    sqlx::query!("DELETE FROM group_members WHERE user_id = ?", user.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    // Their debts, shares and payments cascade away with the account, so the
+    // cached balances of every group they appear in are rebuilt on next read
+    sqlx::query!(
+        r#"
+        UPDATE groups SET balances_cached_at = NULL
+        WHERE id IN (
+            SELECT group_id FROM shared_debts
+            WHERE created_by = ? OR payer_id = ?
+                OR id IN (SELECT shared_debt_id FROM shared_debt_user WHERE user_id = ?)
+            UNION
+            SELECT group_id FROM transactions WHERE payer_id = ? OR recipient_id = ?
+        )
+        "#,
+        user.id,
+        user.id,
+        user.id,
+        user.id,
+        user.id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    sqlx::query!("DELETE FROM users WHERE id = ?", user.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    clear_session(&session)
+        .await
+        .map_err(|_| ServerFnError::new("Account deleted, but logging out failed"))?;
+
+    Ok(())
+}
@@ -1,10 +1,21 @@
+pub mod api_tokens;
 pub mod handlers;
 pub mod models;
+pub mod preferences;
+pub mod recovery;
 pub mod utils;
 
 // Re-export commonly used types and functions
+pub use api_tokens::{
+    CreateApiToken, GetApiTokens, RevokeApiToken, create_api_token, get_api_tokens,
+    revoke_api_token,
+};
 pub use handlers::{
-    GetUser, LoginUser, LogoutUser, RegisterUser, get_user, login_user, logout_user, register_user,
+    ChangePassword, DeleteAccount, GetUser, LoginUser, LogoutAllSessions, LogoutUser,
+    RegenerateRecoveryCodes, RegisterUser, ResetPasswordWithCode, change_password, delete_account,
+    get_user, login_user, logout_all_sessions, logout_user, regenerate_recovery_codes,
+    register_user, reset_password_with_code,
 };
-pub use models::{User, UserSession};
-pub use utils::use_logout;
+pub use models::{ApiToken, CreatedApiToken, NumberFormat, Registration, User, UserSession};
+pub use preferences::{GetNumberFormat, SetNumberFormat, get_number_format, set_number_format};
+pub use utils::{use_logout, use_redirect_target, use_redirect_to_login};
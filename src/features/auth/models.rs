@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "ssr")]
 use sqlx::FromRow;
+use time::OffsetDateTime;
 
 /// User model representing a database user record
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -11,6 +12,8 @@ pub struct User {
     #[serde(skip_serializing)]
     pub password_hash: String,
     pub email: Option<String>,
+    #[serde(skip_serializing)]
+    pub session_generation: i64,
 }
 
 /// UserSession model for session storage
@@ -19,4 +22,83 @@ pub struct User {
 pub struct UserSession {
     pub id: i64,
     pub username: String,
+    /// The user's `session_generation` when this session was issued; older
+    /// values mean the session was logged out
+    #[serde(default)]
+    #[cfg_attr(feature = "ssr", sqlx(default))]
+    pub session_generation: i64,
+}
+
+/// Outcome of a successful registration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Registration {
+    pub user: UserSession,
+    /// Plain-text recovery codes, only ever shown this once
+    pub recovery_codes: Vec<String>,
+}
+
+/// Token a user created for scripts using the JSON API
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: i64,
+    /// What the token is for, e.g. "Bank import"
+    pub name: String,
+    pub created_at: OffsetDateTime,
+    pub last_used_at: Option<OffsetDateTime>,
+}
+
+/// A freshly created API token
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    /// The token to send as `Authorization: Bearer`, only ever shown this once
+    pub secret: String,
+}
+
+/// How a user wants amounts written
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// Decimal point with comma thousands separators, e.g. 1,234.50
+    #[default]
+    Dot,
+    /// Decimal comma with dot thousands separators, e.g. 1.234,50
+    Comma,
+}
+
+impl NumberFormat {
+    pub const ALL: [NumberFormat; 2] = [NumberFormat::Dot, NumberFormat::Comma];
+
+    /// Value stored in the `number_format` column and submitted by forms
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NumberFormat::Dot => "dot",
+            NumberFormat::Comma => "comma",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NumberFormat::Dot => "1,234.50",
+            NumberFormat::Comma => "1.234,50",
+        }
+    }
+
+    /// Thousands separator and decimal separator
+    pub fn separators(self) -> (char, char) {
+        match self {
+            NumberFormat::Dot => (',', '.'),
+            NumberFormat::Comma => ('.', ','),
+        }
+    }
+}
+
+impl std::str::FromStr for NumberFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NumberFormat::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| format!("Unknown number format: {}", s))
+    }
 }
@@ -0,0 +1,124 @@
+//! Per-user display preferences
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use super::models::NumberFormat;
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+
+/// Server function: Choose how amounts are written for the current user
+#[server(SetNumberFormat)]
+pub async fn set_number_format(number_format: String) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let number_format = number_format
+        .parse::<NumberFormat>()
+        .map_err(AppError::Validation)?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    store_number_format(&pool, user.id, number_format).await
+}
+
+/// Server function: How amounts are written for the current user
+#[server(GetNumberFormat)]
+pub async fn get_number_format() -> Result<NumberFormat, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    load_number_format(&pool, user.id).await
+}
+
+#[cfg(feature = "ssr")]
+async fn store_number_format(
+    pool: &sqlx::SqlitePool,
+    user_id: i64,
+    number_format: NumberFormat,
+) -> Result<(), AppError> {
+    let value = number_format.as_str();
+    sqlx::query!(
+        "UPDATE users SET number_format = ? WHERE id = ?",
+        value,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+async fn load_number_format(
+    pool: &sqlx::SqlitePool,
+    user_id: i64,
+) -> Result<NumberFormat, AppError> {
+    let value = sqlx::query_scalar!("SELECT number_format FROM users WHERE id = ?", user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("User not found"))?;
+
+    // Fall back to the default for values written by a newer version
+    Ok(value.parse().unwrap_or_default())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_number_format_defaults_to_dot() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query(
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            load_number_format(&pool, 1).await.unwrap(),
+            NumberFormat::Dot
+        );
+
+        store_number_format(&pool, 1, NumberFormat::Comma)
+            .await
+            .unwrap();
+        assert_eq!(
+            load_number_format(&pool, 1).await.unwrap(),
+            NumberFormat::Comma
+        );
+        assert_eq!(
+            load_number_format(&pool, 2).await.unwrap(),
+            NumberFormat::Dot
+        );
+
+        let err = load_number_format(&pool, 3).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+    }
+}
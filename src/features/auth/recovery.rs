@@ -0,0 +1,286 @@
+//! Single-use recovery codes for resetting a forgotten password
+//!
+//! The app runs without SMTP, so these codes are the only self-service way
+//! back into an account. They are shown once in plain text and stored as
+//! bcrypt hashes. Passwords are already hashed with bcrypt in
+//! `auth::utils`, so the codes reuse it instead of pulling in argon2 as a
+//! second hashing dependency.
+
+#[cfg(feature = "ssr")]
+use bcrypt::{hash, verify};
+#[cfg(feature = "ssr")]
+use sqlx::{SqliteConnection, SqlitePool};
+
+/// Number of codes issued at a time
+#[cfg(feature = "ssr")]
+pub const RECOVERY_CODE_COUNT: usize = 8;
+
+/// Failed reset attempts allowed per username within the attempt window
+#[cfg(feature = "ssr")]
+pub const MAX_RESET_ATTEMPTS: i64 = 5;
+
+/// Minutes after which failed reset attempts stop counting
+#[cfg(feature = "ssr")]
+pub const RESET_ATTEMPT_WINDOW_MINUTES: i64 = 15;
+
+/// Characters a code is made of, without the easily confused 0/O and 1/I
+#[cfg(feature = "ssr")]
+const CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Characters per code, excluding the separator
+#[cfg(feature = "ssr")]
+const CODE_LENGTH: usize = 10;
+
+/// bcrypt cost for code hashes
+///
+/// Codes carry 50 random bits, so a lower cost than the `DEFAULT_COST`
+/// used for passwords keeps issuing and checking eight of them fast. The
+/// per-username attempt limit, not the hash cost, is what stops online
+/// guessing.
+#[cfg(feature = "ssr")]
+const CODE_HASH_COST: u32 = 10;
+
+/// Generate one random code, formatted as `XXXXX-XXXXX`
+#[cfg(feature = "ssr")]
+fn generate_code() -> String {
+    let uuid = uuid::Uuid::new_v4();
+    // Byte 6 holds the fixed version nibble; every other byte keeps at least
+    // five random low bits, and 256 is a multiple of the alphabet size
+    let chars: Vec<char> = uuid
+        .as_bytes()
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != 6)
+        .take(CODE_LENGTH)
+        .map(|(_, byte)| CODE_ALPHABET[usize::from(*byte) % CODE_ALPHABET.len()] as char)
+        .collect();
+
+    let (first, second) = chars.split_at(CODE_LENGTH / 2);
+    format!(
+        "{}-{}",
+        first.iter().collect::<String>(),
+        second.iter().collect::<String>()
+    )
+}
+
+/// Generate a fresh set of codes
+#[cfg(feature = "ssr")]
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT).map(|_| generate_code()).collect()
+}
+
+/// Canonical form of a typed code: uppercase, without separators or spaces
+#[cfg(feature = "ssr")]
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Replace all of a user's codes with the given ones
+#[cfg(feature = "ssr")]
+pub async fn replace_recovery_codes(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    codes: &[String],
+) -> Result<(), sqlx::Error> {
+    sqlx::query!("DELETE FROM recovery_codes WHERE user_id = ?", user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for code in codes {
+        let code_hash = hash(normalize_recovery_code(code), CODE_HASH_COST)
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+        sqlx::query!(
+            "INSERT INTO recovery_codes (user_id, code_hash) VALUES (?, ?)",
+            user_id,
+            code_hash
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Mark the matching unused code as used, returning whether one matched
+#[cfg(feature = "ssr")]
+pub async fn consume_recovery_code(
+    pool: &SqlitePool,
+    user_id: i64,
+    code: &str,
+) -> Result<bool, sqlx::Error> {
+    let code = normalize_recovery_code(code);
+    let candidates = sqlx::query!(
+        r#"SELECT id as "id!", code_hash FROM recovery_codes WHERE user_id = ? AND used_at IS NULL"#,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let Some(matched) = candidates
+        .into_iter()
+        .find(|candidate| verify(&code, &candidate.code_hash).unwrap_or(false))
+    else {
+        return Ok(false);
+    };
+
+    // Guard against two concurrent resets using the same code
+    let result = sqlx::query!(
+        "UPDATE recovery_codes SET used_at = CURRENT_TIMESTAMP WHERE id = ? AND used_at IS NULL",
+        matched.id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Whether a username has used up its failed reset attempts for now
+#[cfg(feature = "ssr")]
+pub async fn reset_attempts_exhausted(
+    pool: &SqlitePool,
+    username: &str,
+) -> Result<bool, sqlx::Error> {
+    let window = format!("-{} minutes", RESET_ATTEMPT_WINDOW_MINUTES);
+    let attempts = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM password_reset_attempts WHERE username = ? AND attempted_at > datetime('now', ?)",
+        username,
+        window
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(attempts >= MAX_RESET_ATTEMPTS)
+}
+
+/// Record a failed reset attempt, dropping attempts that no longer count
+#[cfg(feature = "ssr")]
+pub async fn record_failed_reset_attempt(
+    pool: &SqlitePool,
+    username: &str,
+) -> Result<(), sqlx::Error> {
+    let window = format!("-{} minutes", RESET_ATTEMPT_WINDOW_MINUTES);
+    sqlx::query!(
+        "DELETE FROM password_reset_attempts WHERE attempted_at <= datetime('now', ?)",
+        window
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query!(
+        "INSERT INTO password_reset_attempts (username) VALUES (?)",
+        username
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('alice', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    #[test]
+    fn test_generate_recovery_codes() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        for code in &codes {
+            assert_eq!(code.len(), CODE_LENGTH + 1);
+            assert_eq!(code.as_bytes()[5], b'-');
+            assert!(
+                normalize_recovery_code(code)
+                    .bytes()
+                    .all(|c| CODE_ALPHABET.contains(&c))
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_recovery_code() {
+        assert_eq!(normalize_recovery_code(" abcde-fgh23 "), "ABCDEFGH23");
+        assert_eq!(normalize_recovery_code("ABCDE FGH23"), "ABCDEFGH23");
+    }
+
+    #[tokio::test]
+    async fn test_recovery_code_is_single_use() {
+        let pool = setup().await;
+        let codes = vec!["ABCDE-FGH23".to_string(), "KLMNP-QRS45".to_string()];
+        let mut conn = pool.acquire().await.unwrap();
+        replace_recovery_codes(&mut conn, 1, &codes).await.unwrap();
+        drop(conn);
+
+        assert!(
+            !consume_recovery_code(&pool, 1, "WRONG-CODE2")
+                .await
+                .unwrap()
+        );
+        assert!(
+            consume_recovery_code(&pool, 1, "abcde fgh23")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !consume_recovery_code(&pool, 1, "ABCDE-FGH23")
+                .await
+                .unwrap()
+        );
+        assert!(
+            consume_recovery_code(&pool, 1, "KLMNP-QRS45")
+                .await
+                .unwrap()
+        );
+
+        // Regenerating invalidates the old codes
+        let mut conn = pool.acquire().await.unwrap();
+        replace_recovery_codes(&mut conn, 1, &["TUVWX-YZ678".to_string()])
+            .await
+            .unwrap();
+        drop(conn);
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recovery_codes")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reset_attempts_are_rate_limited_per_username() {
+        let pool = setup().await;
+
+        for _ in 0..MAX_RESET_ATTEMPTS {
+            assert!(!reset_attempts_exhausted(&pool, "alice").await.unwrap());
+            record_failed_reset_attempt(&pool, "alice").await.unwrap();
+        }
+        assert!(reset_attempts_exhausted(&pool, "alice").await.unwrap());
+        assert!(!reset_attempts_exhausted(&pool, "bob").await.unwrap());
+
+        // Attempts older than the window no longer count
+        sqlx::query("UPDATE password_reset_attempts SET attempted_at = datetime('now', '-1 hour')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(!reset_attempts_exhausted(&pool, "alice").await.unwrap());
+    }
+}
@@ -1,11 +1,12 @@
 #[cfg(feature = "ssr")]
 use bcrypt::{DEFAULT_COST, hash, verify};
 use leptos::prelude::*;
-use leptos_router::hooks::use_navigate;
+use leptos_router::hooks::{use_location, use_navigate, use_query_map};
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
 use super::models::UserSession;
+use crate::{components::clear_resource_cache, validation::safe_redirect_path};
 
 /// Hash a password using bcrypt with default cost
 #[cfg(feature = "ssr")]
@@ -24,9 +25,60 @@ pub fn verify_password(password: &str, hash: &str) -> Result<bool, bcrypt::Bcryp
 // instead
 
 /// Retrieve user session from tower-sessions
+///
+/// Sessions issued before the user's last "log out everywhere" count as
+/// logged out. Needs the pool from the server function context; plain Axum
+/// handlers use [`load_session_user`].
 #[cfg(feature = "ssr")]
 pub async fn get_user_from_session(session: &Session) -> Option<UserSession> {
-    session.get::<UserSession>("user").await.ok().flatten()
+    let pool = expect_context::<sqlx::SqlitePool>();
+    load_session_user(session, &pool).await
+}
+
+/// Retrieve user session from tower-sessions, checking its generation against
+/// the database
+#[cfg(feature = "ssr")]
+pub async fn load_session_user(session: &Session, pool: &sqlx::SqlitePool) -> Option<UserSession> {
+    let user = session.get::<UserSession>("user").await.ok().flatten()?;
+    match is_session_current(pool, &user).await {
+        Ok(true) => Some(user),
+        Ok(false) => None,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to check session generation");
+            None
+        }
+    }
+}
+
+/// Whether `user` was issued at the user's current session generation
+///
+/// Deleted users have no generation, so their sessions are never current.
+#[cfg(feature = "ssr")]
+pub async fn is_session_current(
+    pool: &sqlx::SqlitePool,
+    user: &UserSession,
+) -> Result<bool, sqlx::Error> {
+    let generation =
+        sqlx::query_scalar!("SELECT session_generation FROM users WHERE id = ?", user.id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(generation == Some(user.session_generation))
+}
+
+/// Invalidate every session of a user, returning the new generation
+///
+/// Sessions that should stay logged in must be stored again with it.
+#[cfg(feature = "ssr")]
+pub async fn bump_session_generation(
+    conn: &mut sqlx::SqliteConnection,
+    user_id: i64,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        "UPDATE users SET session_generation = session_generation + 1 WHERE id = ? RETURNING session_generation",
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await
 }
 
 /// Store user session in tower-sessions
@@ -91,6 +143,8 @@ pub fn use_logout() -> Callback<()> {
     // Effect to handle navigation after successful logout
     Effect::new(move |_| {
         if let Some(Ok(())) = logout_action.value().get() {
+            // Don't show the next user this user's cached group data
+            clear_resource_cache();
             // Refetch user resource to update the global user context
             user_resource.refetch();
             // Navigate to login page
@@ -103,3 +157,124 @@ pub fn use_logout() -> Callback<()> {
         logout_action.dispatch(LogoutUser {});
     })
 }
+
+/// Custom hook for sending unauthenticated users to the login page
+///
+/// The current path and query string are passed along as `?redirect=` so the
+/// login page can bring the user back once they are signed in.
+///
+/// # Example
+/// ```ignore
+/// let redirect_to_login = use_redirect_to_login();
+///
+/// Effect::new(move |_| {
+///     if let Some(Ok(None)) = user_resource.get() {
+///         redirect_to_login();
+///     }
+/// });
+/// ```
+pub fn use_redirect_to_login() -> impl Fn() + Clone + 'static {
+    let navigate = use_navigate();
+    let location = use_location();
+
+    move || {
+        let mut current = location.pathname.get_untracked();
+        let search = location.search.get_untracked();
+        let search = search.trim_start_matches('?');
+        if !search.is_empty() {
+            current.push('?');
+            current.push_str(search);
+        }
+
+        navigate(
+            &format!("/login?redirect={}", urlencoding::encode(&current)),
+            Default::default(),
+        );
+    }
+}
+
+/// Custom hook returning the validated post-login redirect target
+///
+/// Reads the `redirect` query parameter, falling back to the older
+/// `redirect_to` name so previously shared links keep working. Anything that
+/// is not a same-origin relative path is ignored.
+pub fn use_redirect_target() -> Memo<Option<String>> {
+    let query_map = use_query_map();
+
+    Memo::new(move |_| {
+        let query = query_map.read();
+        let raw = query.get("redirect").or_else(|| query.get("redirect_to"))?;
+        let decoded = urlencoding::decode(&raw).ok()?;
+        safe_redirect_path(&decoded).map(str::to_string)
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use std::sync::Arc;
+
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+    use tower_sessions::MemoryStore;
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('alice', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    fn new_session() -> Session {
+        Session::new(None, Arc::new(MemoryStore::default()), None)
+    }
+
+    #[tokio::test]
+    async fn test_bumped_generation_logs_out_other_sessions() {
+        let pool = setup().await;
+        let user = UserSession {
+            id: 1,
+            username: "alice".to_string(),
+            session_generation: 0,
+        };
+        let current = new_session();
+        let other = new_session();
+        set_user_in_session(&current, &user).await.unwrap();
+        set_user_in_session(&other, &user).await.unwrap();
+        assert!(load_session_user(&other, &pool).await.is_some());
+
+        let generation = {
+            let mut conn = pool.acquire().await.unwrap();
+            bump_session_generation(&mut conn, 1).await.unwrap()
+        };
+        assert_eq!(generation, 1);
+        assert!(load_session_user(&current, &pool).await.is_none());
+        assert!(load_session_user(&other, &pool).await.is_none());
+
+        // Re-issuing the current session, as change_password does, keeps it
+        set_user_in_session(
+            &current,
+            &UserSession {
+                session_generation: generation,
+                ..user
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            load_session_user(&current, &pool).await.map(|user| user.id),
+            Some(1)
+        );
+        assert!(load_session_user(&other, &pool).await.is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use super::models::FeatureFlags;
+#[cfg(feature = "ssr")]
+use crate::features::{auth::utils::get_user_from_session, groups::utils::is_group_admin};
+
+/// Server function: Get the feature flags of a group
+#[server(GetFeatureFlags)]
+pub async fn get_feature_flags(group_id: i64) -> Result<FeatureFlags, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query!(
+        "SELECT COUNT(*) as \"count!\" FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    if is_member.count == 0 {
+        return Err(ServerFnError::new(
+            "Unauthorized: Not a member of this group",
+        ));
+    }
+
+    load_feature_flags(&pool, group_id)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))
+}
+
+/// Load the feature flags of a group
+///
+/// Callers are responsible for verifying that the user is a member of the
+/// group.
+#[cfg(feature = "ssr")]
+pub async fn load_feature_flags(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+) -> Result<FeatureFlags, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT key, enabled as "enabled!: bool" FROM feature_flags WHERE group_id = ?"#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(FeatureFlags::from_rows(
+        rows.into_iter().map(|row| (row.key, row.enabled)),
+    ))
+}
+
+/// Server function: Enable or disable a feature flag (group admins only)
+#[server(SetFeatureFlag)]
+pub async fn set_feature_flag(
+    group_id: i64,
+    key: String,
+    enabled: bool,
+) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use super::models::FeatureFlag;
+
+    let flag = key.parse::<FeatureFlag>().map_err(ServerFnError::new)?;
+    let key = flag.as_str();
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    sqlx::query!("SELECT id FROM groups WHERE id = ?", group_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .ok_or_else(|| ServerFnError::new("Group not found"))?;
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(ServerFnError::new(
+            "Unauthorized: Only group admins can change feature flags",
+        ));
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO feature_flags (group_id, key, enabled)
+        VALUES (?, ?, ?)
+        ON CONFLICT(group_id, key) DO UPDATE SET
+            enabled = excluded.enabled,
+            updated_at = CURRENT_TIMESTAMP
+        "#,
+        group_id,
+        key,
+        enabled
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    Ok(())
+}
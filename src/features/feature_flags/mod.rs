@@ -0,0 +1,5 @@
+pub mod handlers;
+pub mod models;
+
+pub use handlers::{GetFeatureFlags, SetFeatureFlag, get_feature_flags, set_feature_flag};
+pub use models::{FeatureFlag, FeatureFlags, use_feature_flags};
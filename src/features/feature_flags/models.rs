@@ -0,0 +1,128 @@
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Experimental features that can be enabled per group
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureFlag {
+    CustomSplits,
+    Notifications,
+}
+
+impl FeatureFlag {
+    pub const ALL: [FeatureFlag; 2] = [FeatureFlag::CustomSplits, FeatureFlag::Notifications];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            FeatureFlag::CustomSplits => "custom_splits",
+            FeatureFlag::Notifications => "notifications",
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            FeatureFlag::CustomSplits => "Custom splits",
+            FeatureFlag::Notifications => "Notifications",
+        }
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            FeatureFlag::CustomSplits => "Split expenses by custom amounts instead of equally.",
+            FeatureFlag::Notifications => "Notify members about new debts and payments.",
+        }
+    }
+}
+
+impl std::str::FromStr for FeatureFlag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "custom_splits" => Ok(FeatureFlag::CustomSplits),
+            "notifications" => Ok(FeatureFlag::Notifications),
+            _ => Err(format!("Unknown feature flag: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for FeatureFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Feature flags of a group; anything not stored is off
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct FeatureFlags {
+    pub custom_splits: bool,
+    pub notifications: bool,
+}
+
+impl FeatureFlags {
+    /// Build flags from stored `(key, enabled)` rows, ignoring unknown keys
+    pub fn from_rows<I, K>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = (K, bool)>,
+        K: AsRef<str>,
+    {
+        let mut flags = Self::default();
+        for (key, enabled) in rows {
+            if let Ok(flag) = key.as_ref().parse::<FeatureFlag>() {
+                flags.set(flag, enabled);
+            }
+        }
+        flags
+    }
+
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        match flag {
+            FeatureFlag::CustomSplits => self.custom_splits,
+            FeatureFlag::Notifications => self.notifications,
+        }
+    }
+
+    pub fn set(&mut self, flag: FeatureFlag, enabled: bool) {
+        match flag {
+            FeatureFlag::CustomSplits => self.custom_splits = enabled,
+            FeatureFlag::Notifications => self.notifications = enabled,
+        }
+    }
+}
+
+/// Read the current group's feature flags from context
+///
+/// Falls back to all flags off when used outside the group show page.
+pub fn use_feature_flags() -> Signal<FeatureFlags> {
+    use_context::<Signal<FeatureFlags>>().unwrap_or_else(|| Signal::stored(FeatureFlags::default()))
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_keys_default_off() {
+        let flags = FeatureFlags::from_rows([("custom_splits", true), ("dark_mode", true)]);
+
+        assert!(flags.is_enabled(FeatureFlag::CustomSplits));
+        assert!(!flags.is_enabled(FeatureFlag::Notifications));
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let flags = FeatureFlags {
+            custom_splits: false,
+            notifications: true,
+        };
+        let json = serde_json::to_string(&flags).unwrap();
+        assert_eq!(serde_json::from_str::<FeatureFlags>(&json).unwrap(), flags);
+
+        // Missing fields deserialize as disabled
+        assert_eq!(
+            serde_json::from_str::<FeatureFlags>("{}").unwrap(),
+            FeatureFlags::default()
+        );
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+#[cfg(feature = "ssr")]
+use std::sync::Arc;
+
+#[cfg(feature = "ssr")]
+use parking_lot::RwLock;
+#[cfg(feature = "ssr")]
+use tokio::sync::broadcast;
+
+#[cfg(feature = "ssr")]
+use super::models::GroupEvent;
+
+/// Live update channels per group id, for the group page
+#[cfg(feature = "ssr")]
+pub type GroupEventBroadcaster = Arc<RwLock<HashMap<i64, broadcast::Sender<GroupEvent>>>>;
+
+#[cfg(feature = "ssr")]
+pub fn create_group_broadcaster() -> GroupEventBroadcaster {
+    Arc::new(RwLock::new(HashMap::new()))
+}
+
+#[cfg(feature = "ssr")]
+pub fn get_or_create_group_channel(
+    broadcaster: &GroupEventBroadcaster,
+    group_id: i64,
+) -> broadcast::Sender<GroupEvent> {
+    let mut map = broadcaster.write();
+    map.entry(group_id)
+        .or_insert_with(|| broadcast::channel(100).0)
+        .clone()
+}
+
+/// Send an event to everyone viewing the group; a no-op when nobody is
+#[cfg(feature = "ssr")]
+pub fn broadcast_group_event(
+    broadcaster: &GroupEventBroadcaster,
+    group_id: i64,
+    event: GroupEvent,
+) {
+    let map = broadcaster.read();
+    if let Some(tx) = map.get(&group_id) {
+        let _ = tx.send(event);
+    }
+}
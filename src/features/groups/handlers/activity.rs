@@ -0,0 +1,93 @@
+//! Recent activity of a group's debts and transactions
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::GroupActivity;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::models::{ActivityAction, ActivityEntity},
+};
+
+/// Most entries a single request may ask for
+#[cfg(feature = "ssr")]
+const MAX_ACTIVITY_LIMIT: i64 = 100;
+
+/// Number of entries shown in the group page's recent activity card
+pub const RECENT_ACTIVITY_LIMIT: i64 = 10;
+
+/// Server function: Get the latest activity of a group, newest first
+#[server(GetGroupActivity)]
+pub async fn get_group_activity(group_id: i64, limit: i64) -> Result<Vec<GroupActivity>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    load_group_activity(&pool, group_id, limit).await
+}
+
+/// Load the latest activity of a group, newest first
+///
+/// `limit` is clamped to [`MAX_ACTIVITY_LIMIT`]. Callers are responsible for
+/// verifying that the user is a member of the group.
+#[cfg(feature = "ssr")]
+pub async fn load_group_activity(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    limit: i64,
+) -> Result<Vec<GroupActivity>, AppError> {
+    let limit = limit.clamp(1, MAX_ACTIVITY_LIMIT);
+    let rows = sqlx::query!(
+        r#"
+        SELECT id as "id!", group_id, actor_username, entity_type, entity_id, action, summary, created_at
+        FROM group_activity
+        WHERE group_id = ?
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+        group_id,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Entries of unknown kinds, e.g. written by a newer version, are skipped
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(GroupActivity {
+                id: row.id,
+                group_id: row.group_id,
+                actor_username: row.actor_username,
+                entity_type: ActivityEntity::parse(&row.entity_type)?,
+                entity_id: row.entity_id,
+                action: ActivityAction::parse(&row.action)?,
+                summary: row.summary,
+                created_at: row.created_at,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,1081 @@
+//! JSON backup of a whole group, and restoring such a backup as a new group
+//!
+//! Users are referenced by username so a backup can move between servers.
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "ssr")]
+use sqlx::SqliteConnection;
+#[cfg(feature = "ssr")]
+use time::OffsetDateTime;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::GroupImportResult;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        models::GroupRole,
+        utils::{check_group_currency, is_group_admin},
+    },
+    recurring_debts::{
+        models::Frequency,
+        utils::{check_timezone, parse_form_date},
+    },
+    shared_debts::{
+        models::{DebtCategory, DebtStatus, ForeignAmount, RoundingStrategy},
+        utils::stored_foreign_amount,
+    },
+    transactions::models::ConfirmationStatus,
+};
+#[cfg(feature = "ssr")]
+use crate::validation::rules::{MAX_NAME_LENGTH, check_name};
+
+/// Version of the backup format
+///
+/// Bump it whenever the document changes shape; imports of any other version
+/// are refused.
+#[cfg(feature = "ssr")]
+pub const GROUP_BACKUP_VERSION: u32 = 1;
+
+/// Path of the download endpoint for a group backup
+pub fn backup_path(group_id: i64) -> String {
+    format!("/api/groups/{}/backup.json", group_id)
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GroupBackup {
+    version: u32,
+    #[serde(with = "time::serde::rfc3339")]
+    exported_at: OffsetDateTime,
+    group: BackupGroup,
+    members: Vec<BackupMember>,
+    shared_debts: Vec<BackupSharedDebt>,
+    recurring_debts: Vec<BackupRecurringDebt>,
+    transactions: Vec<BackupTransaction>,
+    shopping_lists: Vec<BackupShoppingList>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupGroup {
+    name: String,
+    monthly_budget: Option<Decimal>,
+    full_balances_visible: bool,
+    /// Missing in backups made before groups had timezones
+    #[serde(default)]
+    timezone: Option<String>,
+    /// ISO 4217 code; missing in backups made before groups had currencies,
+    /// which were all in euros
+    #[serde(default)]
+    currency: Option<String>,
+    /// Missing in backups made before groups chose a rounding strategy
+    #[serde(default)]
+    rounding_strategy: RoundingStrategy,
+    /// Missing in backups made before debts could need approval
+    #[serde(default)]
+    require_debt_approval: bool,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupMember {
+    username: String,
+    role: GroupRole,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupSharedDebt {
+    name: String,
+    amount: Decimal,
+    category: Option<DebtCategory>,
+    /// YYYY-MM-DD; missing in backups made before debts had due dates
+    #[serde(default)]
+    due_date: Option<String>,
+    /// YYYY-MM-DD; missing in backups made before debts had their own date
+    #[serde(default)]
+    incurred_on: Option<String>,
+    /// Missing in backups made before debts could be paid in other currencies
+    #[serde(default)]
+    original: Option<ForeignAmount>,
+    /// Missing in backups made before debts could need approval
+    #[serde(default)]
+    status: DebtStatus,
+    #[serde(default)]
+    rejection_reason: Option<String>,
+    created_by: String,
+    payer: String,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    shares: Vec<BackupShare>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupShare {
+    username: String,
+    /// Custom share; `None` for an equal split
+    share_amount: Option<Decimal>,
+    is_settled: bool,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupRecurringDebt {
+    name: String,
+    amount: Decimal,
+    category: Option<DebtCategory>,
+    frequency: Frequency,
+    /// YYYY-MM-DD
+    start_date: String,
+    end_date: Option<String>,
+    next_generation_date: String,
+    is_active: bool,
+    #[serde(default)]
+    auto_include_new_members: bool,
+    created_by: String,
+    members: Vec<BackupRecurringMember>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupRecurringMember {
+    username: String,
+    weight: i64,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupTransaction {
+    payer: String,
+    recipient: String,
+    amount: Decimal,
+    description: Option<String>,
+    /// Missing in backups made before recipients confirmed payments
+    #[serde(default)]
+    confirmation_status: ConfirmationStatus,
+    #[serde(default)]
+    dispute_reason: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupShoppingList {
+    name: String,
+    created_by: String,
+    is_archived: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    created_at: OffsetDateTime,
+    items: Vec<BackupShoppingItem>,
+}
+
+#[cfg(feature = "ssr")]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BackupShoppingItem {
+    name: String,
+    quantity_value: Option<Decimal>,
+    quantity_unit: Option<String>,
+    category: Option<String>,
+    is_completed: bool,
+    completed_by: Option<String>,
+    assigned_to: Option<String>,
+    position: i64,
+}
+
+/// Server function: Export everything in a group as a JSON backup
+///
+/// Group admins only, since the backup has every member's debts.
+#[server(ExportGroupJson)]
+pub async fn export_group_json(group_id: i64) -> Result<String, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    render_group_backup(&pool, group_id, user.id)
+        .await?
+        .ok_or_else(|| AppError::forbidden("Only group admins can export a backup"))
+}
+
+/// Server function: Create a new group from a JSON backup
+///
+/// Only an admin of the exported group may import it; they become the new
+/// group's creator. Users are matched by username, and those without an
+/// account here are left out and listed in the result.
+#[server(ImportGroupJson)]
+pub async fn import_group_json(json: String) -> Result<GroupImportResult, AppError> {
+    use sqlx::SqlitePool;
+
+    let backup = parse_backup(&json).map_err(AppError::Validation)?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let mut tx = pool.begin().await?;
+    let result = import_backup(&mut tx, user.id, &user.username, &backup).await?;
+    tx.commit().await?;
+
+    Ok(result)
+}
+
+/// Render a group backup for one of its admins
+///
+/// Returns `None` when the user is not an admin of the group.
+#[cfg(feature = "ssr")]
+pub async fn render_group_backup(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<Option<String>, AppError> {
+    if !is_group_admin(pool, group_id, user_id).await? {
+        return Ok(None);
+    }
+
+    let mut conn = pool.acquire().await?;
+    let backup = read_backup(&mut conn, group_id).await?;
+
+    serde_json::to_string_pretty(&backup)
+        .map(Some)
+        .map_err(AppError::internal)
+}
+
+#[cfg(feature = "ssr")]
+fn parse_decimal(value: &str) -> Result<Decimal, sqlx::Error> {
+    value
+        .parse::<Decimal>()
+        .map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+/// Collect everything in a group into a backup document
+#[cfg(feature = "ssr")]
+async fn read_backup(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+) -> Result<GroupBackup, sqlx::Error> {
+    let group = sqlx::query!(
+        r#"
+        SELECT name, monthly_budget, full_balances_visible as "full_balances_visible!: bool", timezone, currency, rounding_strategy,
+            require_debt_approval as "require_debt_approval!: bool"
+        FROM groups
+        WHERE id = ?
+        "#,
+        group_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let members = sqlx::query!(
+        r#"
+        SELECT
+            u.username,
+            CASE WHEN gm.role = 'admin' OR g.created_by = gm.user_id THEN 'admin' ELSE 'member' END
+                as "role!: String"
+        FROM group_members gm
+        INNER JOIN users u ON gm.user_id = u.id
+        INNER JOIN groups g ON g.id = gm.group_id
+        WHERE gm.group_id = ?
+        ORDER BY u.username ASC
+        "#,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| BackupMember {
+        username: row.username,
+        role: GroupRole::parse(&row.role).unwrap_or_default(),
+    })
+    .collect();
+
+    let debt_rows = sqlx::query!(
+        r#"
+        SELECT
+            sd.id as "id!",
+            sd.name,
+            sd.amount,
+            sd.category,
+            sd.due_date as "due_date: String",
+            sd.incurred_on as "incurred_on!: String",
+            sd.original_amount,
+            sd.original_currency,
+            sd.exchange_rate,
+            sd.status,
+            sd.rejection_reason,
+            sd.created_at,
+            c.username as creator_username,
+            p.username as payer_username
+        FROM shared_debts sd
+        INNER JOIN users c ON sd.created_by = c.id
+        INNER JOIN users p ON COALESCE(sd.payer_id, sd.created_by) = p.id
+        WHERE sd.group_id = ?
+        ORDER BY sd.created_at ASC, sd.id ASC
+        "#,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut shared_debts = Vec::with_capacity(debt_rows.len());
+    for row in debt_rows {
+        let shares = sqlx::query!(
+            r#"
+            SELECT u.username, sdu.share_amount, sdu.is_settled as "is_settled!: bool"
+            FROM shared_debt_user sdu
+            INNER JOIN users u ON sdu.user_id = u.id
+            WHERE sdu.shared_debt_id = ?
+            ORDER BY u.username ASC
+            "#,
+            row.id
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|share| {
+            Ok(BackupShare {
+                username: share.username,
+                share_amount: share
+                    .share_amount
+                    .as_deref()
+                    .map(parse_decimal)
+                    .transpose()?,
+                is_settled: share.is_settled,
+            })
+        })
+        .collect::<Result<_, sqlx::Error>>()?;
+
+        shared_debts.push(BackupSharedDebt {
+            name: row.name,
+            amount: parse_decimal(&row.amount)?,
+            category: row.category.and_then(|category| category.parse().ok()),
+            due_date: row.due_date,
+            incurred_on: Some(row.incurred_on),
+            original: stored_foreign_amount(
+                row.original_amount.as_deref(),
+                row.original_currency,
+                row.exchange_rate.as_deref(),
+            ),
+            status: row.status.parse().unwrap_or_default(),
+            rejection_reason: row.rejection_reason,
+            created_by: row.creator_username,
+            payer: row.payer_username,
+            created_at: row.created_at,
+            shares,
+        });
+    }
+
+    let recurring_rows = sqlx::query!(
+        r#"
+        SELECT
+            rd.id as "id!",
+            rd.name,
+            rd.amount,
+            rd.category,
+            rd.frequency,
+            rd.frequency_interval,
+            rd.start_date as "start_date!: String",
+            rd.end_date as "end_date?: String",
+            rd.next_generation_date as "next_generation_date!: String",
+            rd.is_active as "is_active!: bool",
+            rd.auto_include_new_members as "auto_include_new_members!: bool",
+            u.username as creator_username
+        FROM recurring_debts rd
+        INNER JOIN users u ON rd.created_by = u.id
+        WHERE rd.group_id = ?
+        ORDER BY rd.created_at ASC, rd.id ASC
+        "#,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut recurring_debts = Vec::with_capacity(recurring_rows.len());
+    for row in recurring_rows {
+        let members = sqlx::query!(
+            r#"
+            SELECT u.username, rdu.weight
+            FROM recurring_debt_user rdu
+            INNER JOIN users u ON rdu.user_id = u.id
+            WHERE rdu.recurring_debt_id = ?
+            ORDER BY u.username ASC
+            "#,
+            row.id
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|member| BackupRecurringMember {
+            username: member.username,
+            weight: member.weight,
+        })
+        .collect();
+
+        recurring_debts.push(BackupRecurringDebt {
+            name: row.name,
+            amount: parse_decimal(&row.amount)?,
+            category: row.category.and_then(|category| category.parse().ok()),
+            frequency: Frequency::from_columns(&row.frequency, row.frequency_interval)
+                .map_err(|e| sqlx::Error::Decode(e.into()))?,
+            start_date: row.start_date,
+            end_date: row.end_date,
+            next_generation_date: row.next_generation_date,
+            is_active: row.is_active,
+            auto_include_new_members: row.auto_include_new_members,
+            created_by: row.creator_username,
+            members,
+        });
+    }
+
+    let transactions = sqlx::query!(
+        r#"
+        SELECT
+            t.amount,
+            t.description,
+            t.confirmation_status,
+            t.dispute_reason,
+            t.created_at,
+            p.username as payer_username,
+            r.username as recipient_username
+        FROM transactions t
+        INNER JOIN users p ON t.payer_id = p.id
+        INNER JOIN users r ON t.recipient_id = r.id
+        WHERE t.group_id = ?
+        ORDER BY t.created_at ASC, t.id ASC
+        "#,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(BackupTransaction {
+            payer: row.payer_username,
+            recipient: row.recipient_username,
+            amount: parse_decimal(&row.amount)?,
+            description: row.description,
+            confirmation_status: row
+                .confirmation_status
+                .parse()
+                .map_err(|e: String| sqlx::Error::Decode(e.into()))?,
+            dispute_reason: row.dispute_reason,
+            created_at: row.created_at,
+        })
+    })
+    .collect::<Result<_, sqlx::Error>>()?;
+
+    let list_rows = sqlx::query!(
+        r#"
+        SELECT
+            sl.id as "id!",
+            sl.name,
+            sl.is_archived as "is_archived!: bool",
+            sl.created_at,
+            u.username as creator_username
+        FROM shopping_lists sl
+        INNER JOIN users u ON sl.created_by = u.id
+        WHERE sl.group_id = ?
+        ORDER BY sl.created_at ASC, sl.id ASC
+        "#,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut shopping_lists = Vec::with_capacity(list_rows.len());
+    for row in list_rows {
+        let items = sqlx::query!(
+            r#"
+            SELECT
+                i.name,
+                i.quantity_value,
+                i.quantity_unit,
+                i.category,
+                i.is_completed as "is_completed!: bool",
+                i.position,
+                c.username as "completed_by?",
+                a.username as "assigned_to?"
+            FROM shopping_list_items i
+            LEFT JOIN users c ON i.completed_by = c.id
+            LEFT JOIN users a ON i.assigned_to = a.id
+            WHERE i.shopping_list_id = ?
+            ORDER BY i.position ASC, i.id ASC
+            "#,
+            row.id
+        )
+        .fetch_all(&mut *conn)
+        .await?
+        .into_iter()
+        .map(|item| {
+            Ok(BackupShoppingItem {
+                name: item.name,
+                quantity_value: item
+                    .quantity_value
+                    .as_deref()
+                    .map(parse_decimal)
+                    .transpose()?,
+                quantity_unit: item.quantity_unit,
+                category: item.category,
+                is_completed: item.is_completed,
+                completed_by: item.completed_by,
+                assigned_to: item.assigned_to,
+                position: item.position,
+            })
+        })
+        .collect::<Result<_, sqlx::Error>>()?;
+
+        shopping_lists.push(BackupShoppingList {
+            name: row.name,
+            created_by: row.creator_username,
+            is_archived: row.is_archived,
+            created_at: row.created_at,
+            items,
+        });
+    }
+
+    Ok(GroupBackup {
+        version: GROUP_BACKUP_VERSION,
+        exported_at: OffsetDateTime::now_utc(),
+        group: BackupGroup {
+            name: group.name,
+            monthly_budget: group
+                .monthly_budget
+                .as_deref()
+                .map(parse_decimal)
+                .transpose()?,
+            full_balances_visible: group.full_balances_visible,
+            timezone: group.timezone,
+            currency: Some(group.currency),
+            rounding_strategy: group.rounding_strategy.parse().unwrap_or_default(),
+            require_debt_approval: group.require_debt_approval,
+        },
+        members,
+        shared_debts,
+        recurring_debts,
+        transactions,
+        shopping_lists,
+    })
+}
+
+/// Parse a backup document, refusing other format versions before reading
+/// anything else
+#[cfg(feature = "ssr")]
+fn parse_backup(json: &str) -> Result<GroupBackup, String> {
+    let document: serde_json::Value =
+        serde_json::from_str(json).map_err(|_| "The backup is not valid JSON".to_string())?;
+
+    match document.get("version").and_then(serde_json::Value::as_u64) {
+        Some(version) if version == u64::from(GROUP_BACKUP_VERSION) => {}
+        Some(version) => {
+            return Err(format!(
+                "Unsupported backup version {} (expected {})",
+                version, GROUP_BACKUP_VERSION
+            ));
+        }
+        None => return Err("Not a group backup: the version is missing".to_string()),
+    }
+
+    let backup: GroupBackup =
+        serde_json::from_value(document).map_err(|e| format!("Invalid group backup: {}", e))?;
+
+    check_name(&backup.group.name, 1, MAX_NAME_LENGTH, "Name")?;
+    check_timezone(backup.group.timezone.as_deref())
+        .map_err(|e| format!("Invalid group backup: {}", e))?;
+    check_group_currency(backup.group.currency.as_deref())
+        .map_err(|e| format!("Invalid group backup: {}", e))?;
+    let amounts = backup
+        .shared_debts
+        .iter()
+        .map(|debt| debt.amount)
+        .chain(backup.recurring_debts.iter().map(|debt| debt.amount))
+        .chain(
+            backup
+                .transactions
+                .iter()
+                .map(|transaction| transaction.amount),
+        )
+        .chain(
+            backup
+                .shared_debts
+                .iter()
+                .filter_map(|debt| debt.original.as_ref())
+                .flat_map(|original| [original.amount, original.exchange_rate]),
+        );
+    for amount in amounts {
+        if amount <= Decimal::ZERO {
+            return Err(format!(
+                "Invalid group backup: amount {} is not positive",
+                amount
+            ));
+        }
+    }
+    for debt in &backup.shared_debts {
+        for (date, field) in [
+            (&debt.due_date, "due date"),
+            (&debt.incurred_on, "debt date"),
+        ] {
+            if let Some(date) = date {
+                parse_form_date(date, field).map_err(|e| format!("Invalid group backup: {}", e))?;
+            }
+        }
+    }
+    for debt in &backup.recurring_debts {
+        for date in [&debt.start_date, &debt.next_generation_date]
+            .into_iter()
+            .chain(&debt.end_date)
+        {
+            parse_form_date(date, "recurring debt date")
+                .map_err(|e| format!("Invalid group backup: {}", e))?;
+        }
+    }
+
+    Ok(backup)
+}
+
+/// Create a new group from a parsed backup, owned by the importing user
+#[cfg(feature = "ssr")]
+async fn import_backup(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    username: &str,
+    backup: &GroupBackup,
+) -> Result<GroupImportResult, AppError> {
+    use std::collections::{BTreeSet, HashMap};
+
+    let is_admin = backup
+        .members
+        .iter()
+        .any(|member| member.username == username && member.role == GroupRole::Admin);
+    if !is_admin {
+        return Err(AppError::forbidden(
+            "Only an admin of the exported group can import it",
+        ));
+    }
+
+    // Every username the backup mentions, mapped to a local account if any
+    let mut usernames: BTreeSet<&str> = BTreeSet::new();
+    usernames.extend(backup.members.iter().map(|m| m.username.as_str()));
+    for debt in &backup.shared_debts {
+        usernames.extend([debt.created_by.as_str(), debt.payer.as_str()]);
+        usernames.extend(debt.shares.iter().map(|s| s.username.as_str()));
+    }
+    for debt in &backup.recurring_debts {
+        usernames.insert(debt.created_by.as_str());
+        usernames.extend(debt.members.iter().map(|m| m.username.as_str()));
+    }
+    for transaction in &backup.transactions {
+        usernames.extend([transaction.payer.as_str(), transaction.recipient.as_str()]);
+    }
+    for list in &backup.shopping_lists {
+        usernames.insert(list.created_by.as_str());
+        for item in &list.items {
+            usernames.extend(item.completed_by.as_deref());
+            usernames.extend(item.assigned_to.as_deref());
+        }
+    }
+
+    let mut user_ids: HashMap<&str, i64> = HashMap::new();
+    let mut skipped_users = Vec::new();
+    for name in usernames {
+        let id = sqlx::query_scalar!(r#"SELECT id as "id!" FROM users WHERE username = ?"#, name)
+            .fetch_optional(&mut *conn)
+            .await?;
+        match id {
+            Some(id) => {
+                user_ids.insert(name, id);
+            }
+            None => skipped_users.push(name.to_string()),
+        }
+    }
+    let lookup = |name: &str| user_ids.get(name).copied();
+    let mut skipped_records = 0;
+
+    let budget = backup.group.monthly_budget.map(|budget| budget.to_string());
+    let rounding_strategy = backup.group.rounding_strategy.as_str();
+    let currency =
+        check_group_currency(backup.group.currency.as_deref()).map_err(AppError::Validation)?;
+    let group_id = sqlx::query!(
+        "INSERT INTO groups (name, created_by, monthly_budget, full_balances_visible, timezone, currency, rounding_strategy, require_debt_approval) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        backup.group.name,
+        user_id,
+        budget,
+        backup.group.full_balances_visible,
+        backup.group.timezone,
+        currency,
+        rounding_strategy,
+        backup.group.require_debt_approval
+    )
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    for member in &backup.members {
+        let Some(member_id) = lookup(&member.username) else {
+            continue;
+        };
+        let role = member.role.as_str();
+        sqlx::query!(
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (?, ?, ?)",
+            group_id,
+            member_id,
+            role
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for debt in &backup.shared_debts {
+        let shares: Vec<(i64, &BackupShare)> = debt
+            .shares
+            .iter()
+            .filter_map(|share| Some((lookup(&share.username)?, share)))
+            .collect();
+        let Some(payer_id) = lookup(&debt.payer).filter(|_| !shares.is_empty()) else {
+            skipped_records += 1;
+            continue;
+        };
+        let created_by = lookup(&debt.created_by).unwrap_or(user_id);
+        let amount = debt.amount.to_string();
+        let category = debt.category.map(|category| category.as_str());
+        let original_amount = debt
+            .original
+            .as_ref()
+            .map(|original| original.amount.to_string());
+        let original_currency = debt
+            .original
+            .as_ref()
+            .map(|original| original.currency.as_str());
+        let exchange_rate = debt
+            .original
+            .as_ref()
+            .map(|original| original.exchange_rate.to_string());
+        let status = debt.status.as_str();
+
+        let debt_id = sqlx::query!(
+            r#"
+            INSERT INTO shared_debts
+                (group_id, created_by, payer_id, name, amount, category, due_date, incurred_on, original_amount, original_currency, exchange_rate, status, rejection_reason, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            group_id,
+            created_by,
+            payer_id,
+            debt.name,
+            amount,
+            category,
+            debt.due_date,
+            debt.incurred_on,
+            original_amount,
+            original_currency,
+            exchange_rate,
+            status,
+            debt.rejection_reason,
+            debt.created_at
+        )
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+
+        for (member_id, share) in shares {
+            let share_amount = share.share_amount.map(|amount| amount.to_string());
+            sqlx::query!(
+                r#"
+                INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount, is_settled, settled_at)
+                VALUES (?, ?, ?, ?, CASE WHEN ? THEN CURRENT_TIMESTAMP ELSE NULL END)
+                "#,
+                debt_id,
+                member_id,
+                share_amount,
+                share.is_settled,
+                share.is_settled
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    for debt in &backup.recurring_debts {
+        let members: Vec<(i64, i64)> = debt
+            .members
+            .iter()
+            .filter_map(|member| Some((lookup(&member.username)?, member.weight)))
+            .collect();
+        if members.is_empty() {
+            skipped_records += 1;
+            continue;
+        }
+        let created_by = lookup(&debt.created_by).unwrap_or(user_id);
+        let amount = debt.amount.to_string();
+        let category = debt.category.map(|category| category.as_str());
+        let (frequency, frequency_interval) = debt.frequency.to_columns();
+
+        let recurring_debt_id = sqlx::query!(
+            r#"
+            INSERT INTO recurring_debts (
+                group_id, created_by, name, amount, frequency, frequency_interval,
+                start_date, end_date, next_generation_date, is_active, category,
+                auto_include_new_members
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            group_id,
+            created_by,
+            debt.name,
+            amount,
+            frequency,
+            frequency_interval,
+            debt.start_date,
+            debt.end_date,
+            debt.next_generation_date,
+            debt.is_active,
+            category,
+            debt.auto_include_new_members
+        )
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+
+        for (member_id, weight) in members {
+            sqlx::query!(
+                "INSERT INTO recurring_debt_user (recurring_debt_id, user_id, weight) VALUES (?, ?, ?)",
+                recurring_debt_id,
+                member_id,
+                weight
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    for transaction in &backup.transactions {
+        let (Some(payer_id), Some(recipient_id)) =
+            (lookup(&transaction.payer), lookup(&transaction.recipient))
+        else {
+            skipped_records += 1;
+            continue;
+        };
+        let amount = transaction.amount.to_string();
+        let confirmation_status = transaction.confirmation_status.as_str();
+        sqlx::query!(
+            r#"
+            INSERT INTO transactions (group_id, payer_id, recipient_id, amount, description, confirmation_status, dispute_reason, created_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            group_id,
+            payer_id,
+            recipient_id,
+            amount,
+            transaction.description,
+            confirmation_status,
+            transaction.dispute_reason,
+            transaction.created_at
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    for list in &backup.shopping_lists {
+        let created_by = lookup(&list.created_by).unwrap_or(user_id);
+        let list_id = sqlx::query!(
+            r#"
+            INSERT INTO shopping_lists (group_id, created_by, name, is_archived, archived_at, created_at)
+            VALUES (?, ?, ?, ?, CASE WHEN ? THEN CURRENT_TIMESTAMP ELSE NULL END, ?)
+            "#,
+            group_id,
+            created_by,
+            list.name,
+            list.is_archived,
+            list.is_archived,
+            list.created_at
+        )
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+
+        for item in &list.items {
+            let quantity_value = item.quantity_value.map(|value| value.to_string());
+            let completed_by = item.completed_by.as_deref().and_then(lookup);
+            let assigned_to = item.assigned_to.as_deref().and_then(lookup);
+            sqlx::query!(
+                r#"
+                INSERT INTO shopping_list_items
+                    (shopping_list_id, name, quantity_value, quantity_unit, category,
+                     is_completed, completed_by, completed_at, assigned_to, position)
+                VALUES (?, ?, ?, ?, ?, ?, ?, CASE WHEN ? THEN CURRENT_TIMESTAMP ELSE NULL END, ?, ?)
+                "#,
+                list_id,
+                item.name,
+                quantity_value,
+                item.quantity_unit,
+                item.category,
+                item.is_completed,
+                completed_by,
+                item.is_completed,
+                assigned_to,
+                item.position
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(GroupImportResult {
+        group_id,
+        skipped_users,
+        skipped_records,
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    /// Group 1 "Flat" of alice (admin), bob and carol with one of everything
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let statements = [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO groups (name, created_by, monthly_budget) VALUES ('Flat', 1, '500')",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'member'), (1, 3, 'member')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, category) VALUES (1, 1, 1, 'Groceries', '30', 'Groceries')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, is_settled) VALUES (1, 1, 0), (1, 2, 1), (1, 3, 0)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, frequency_interval, start_date, next_generation_date) VALUES (1, 2, 'Internet', '40', 'weekly', 2, '2026-01-01', '2026-01-15')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id, weight) VALUES (1, 1, 1), (1, 2, 2)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount, description) VALUES (1, 2, 3, '5', 'Coffee')",
+            "INSERT INTO shopping_lists (group_id, created_by, name) VALUES (1, 3, 'Weekend')",
+            "INSERT INTO shopping_list_items (shopping_list_id, name, quantity_value, quantity_unit, is_completed, completed_by) VALUES (1, 'Milk', '1.5', 'l', 1, 3)",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    async fn import(pool: &SqlitePool, json: &str) -> Result<GroupImportResult, AppError> {
+        let backup = parse_backup(json).map_err(AppError::Validation)?;
+        let mut conn = pool.acquire().await.unwrap();
+        import_backup(&mut conn, 1, "alice", &backup).await
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_skips_missing_users() {
+        let pool = setup().await;
+        let json = render_group_backup(&pool, 1, 1).await.unwrap().unwrap();
+        assert_eq!(render_group_backup(&pool, 1, 2).await.unwrap(), None);
+
+        // carol has no account on the "new server"
+        sqlx::query("UPDATE users SET username = 'carla' WHERE id = 3")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let result = import(&pool, &json).await.unwrap();
+        assert_eq!(result.skipped_users, vec!["carol".to_string()]);
+        // The coffee bob paid carol back
+        assert_eq!(result.skipped_records, 1);
+
+        let mut conn = pool.acquire().await.unwrap();
+        let imported = read_backup(&mut conn, result.group_id).await.unwrap();
+        assert_eq!(imported.group.name, "Flat");
+        assert_eq!(imported.group.monthly_budget, Some(Decimal::from(500)));
+        assert_eq!(imported.members.len(), 2);
+        assert!(imported.transactions.is_empty());
+
+        let debt = &imported.shared_debts[0];
+        assert_eq!(debt.amount, Decimal::from(30));
+        assert_eq!(debt.category, Some(DebtCategory::Groceries));
+        let shares: Vec<_> = debt
+            .shares
+            .iter()
+            .map(|share| (share.username.as_str(), share.is_settled))
+            .collect();
+        assert_eq!(shares, vec![("alice", false), ("bob", true)]);
+
+        let recurring = &imported.recurring_debts[0];
+        assert_eq!(recurring.frequency, Frequency::EveryNWeeks(2));
+        assert_eq!(recurring.created_by, "bob");
+        assert_eq!(recurring.members.len(), 2);
+
+        // carol's list now belongs to the importer
+        let list = &imported.shopping_lists[0];
+        assert_eq!(list.created_by, "alice");
+        assert_eq!(list.items[0].quantity_value, Some(Decimal::new(15, 1)));
+        assert!(list.items[0].is_completed);
+        assert_eq!(list.items[0].completed_by, None);
+    }
+
+    #[tokio::test]
+    async fn test_other_versions_rejected() {
+        let pool = setup().await;
+        let json = render_group_backup(&pool, 1, 1).await.unwrap().unwrap();
+        let mut document: serde_json::Value = serde_json::from_str(&json).unwrap();
+        document["version"] = 2.into();
+
+        let err = import(&pool, &document.to_string()).await.unwrap_err();
+        assert!(err.to_string().contains("Unsupported backup version 2"));
+
+        document.as_object_mut().unwrap().remove("version");
+        let err = import(&pool, &document.to_string()).await.unwrap_err();
+        assert!(err.to_string().contains("version is missing"));
+
+        let err = import(&pool, "{\"version\": 1}").await.unwrap_err();
+        assert!(err.to_string().contains("Invalid group backup"));
+    }
+
+    #[tokio::test]
+    async fn test_import_requires_admin_of_exported_group() {
+        let pool = setup().await;
+        sqlx::query("UPDATE group_members SET role = 'member' WHERE user_id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE groups SET created_by = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE group_members SET role = 'admin' WHERE user_id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let json = render_group_backup(&pool, 1, 2).await.unwrap().unwrap();
+
+        let err = import(&pool, &json).await.unwrap_err();
+        assert!(err.to_string().contains("Only an admin"));
+    }
+}
@@ -0,0 +1,81 @@
+//! Monthly spending of a group, for comparison against its budget
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{auth::utils::get_user_from_session, groups::utils::month_bounds};
+
+/// Server function: Total of the shared debts created in a group during a
+/// calendar month
+///
+/// Months run on UTC dates. Debts generated by recurring debts count like any
+/// other shared debt.
+#[server(GetMonthlySpend)]
+pub async fn get_monthly_spend(group_id: i64, year: i32, month: u8) -> Result<Decimal, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    load_monthly_spend(&pool, group_id, year, month).await
+}
+
+/// Total of the shared debts created in a group during a calendar month
+///
+/// Callers are responsible for verifying that the user is a member of the
+/// group.
+#[cfg(feature = "ssr")]
+pub async fn load_monthly_spend(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    year: i32,
+    month: u8,
+) -> Result<Decimal, AppError> {
+    let (start, end) = month_bounds(year, month).map_err(AppError::Validation)?;
+
+    // Amounts are stored as text, so they are summed here rather than in SQL
+    let start = start.to_string();
+    let end = end.to_string();
+    let amounts = sqlx::query_scalar!(
+        r#"
+        SELECT amount
+        FROM shared_debts
+        WHERE group_id = ? AND incurred_on >= ? AND incurred_on < ?
+        "#,
+        group_id,
+        start,
+        end
+    )
+    .fetch_all(pool)
+    .await?;
+
+    amounts.iter().try_fold(Decimal::ZERO, |total, amount| {
+        amount
+            .parse::<Decimal>()
+            .map(|amount| total + amount)
+            .map_err(AppError::internal)
+    })
+}
@@ -0,0 +1,146 @@
+//! The group a user lands on after logging in
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+
+/// Server function: Make a group the current user's default, or clear the
+/// default with `None`
+#[server(SetDefaultGroup)]
+pub async fn set_default_group(group_id: Option<i64>) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    store_default_group(&pool, user.id, group_id).await
+}
+
+/// Server function: The current user's default group, if they are still a
+/// member of it
+#[server(GetDefaultGroup)]
+pub async fn get_default_group() -> Result<Option<i64>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    load_default_group(&pool, user.id).await
+}
+
+#[cfg(feature = "ssr")]
+async fn store_default_group(
+    pool: &sqlx::SqlitePool,
+    user_id: i64,
+    group_id: Option<i64>,
+) -> Result<(), AppError> {
+    if let Some(group_id) = group_id {
+        let is_member = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+            group_id,
+            user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if is_member == 0 {
+            return Err(AppError::forbidden("Not a member of this group"));
+        }
+    }
+
+    sqlx::query!(
+        "UPDATE users SET default_group_id = ? WHERE id = ?",
+        group_id,
+        user_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+async fn load_default_group(
+    pool: &sqlx::SqlitePool,
+    user_id: i64,
+) -> Result<Option<i64>, AppError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT u.default_group_id as "default_group_id!: i64"
+        FROM users u
+        INNER JOIN group_members gm
+            ON gm.group_id = u.default_group_id AND gm.user_id = u.id
+        WHERE u.id = ?
+        "#,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(AppError::from)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::features::groups::utils::clear_stale_default_groups;
+
+    #[tokio::test]
+    async fn test_default_group_needs_membership_and_goes_with_it() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1), ('Trip', 2)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (2, 2)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        let err = store_default_group(&pool, 1, Some(2)).await.unwrap_err();
+        assert!(err.to_string().contains("Not a member"));
+        assert_eq!(load_default_group(&pool, 1).await.unwrap(), None);
+
+        store_default_group(&pool, 1, Some(1)).await.unwrap();
+        store_default_group(&pool, 2, Some(1)).await.unwrap();
+        assert_eq!(load_default_group(&pool, 1).await.unwrap(), Some(1));
+
+        // Removing alice clears her setting but not bob's
+        sqlx::query("DELETE FROM group_members WHERE group_id = 1 AND user_id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        clear_stale_default_groups(&pool, 1).await.unwrap();
+        let defaults: Vec<Option<i64>> =
+            sqlx::query_scalar("SELECT default_group_id FROM users ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(defaults, vec![None, Some(1)]);
+
+        store_default_group(&pool, 2, None).await.unwrap();
+        assert_eq!(load_default_group(&pool, 2).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,309 @@
+//! Bookmarkable digest of a group's recent activity
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use time::{Date, Duration};
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::GroupDigest;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::models::{DigestBalance, DigestDebt, DigestRecurring, DigestTransaction},
+    recurring_debts::{models::Frequency, utils::upcoming_occurrences},
+};
+
+/// Longest period a digest can cover, in weeks
+pub const MAX_DIGEST_WEEKS: i64 = 12;
+
+/// Server function: New debts and payments of the last `weeks` weeks,
+/// current balances, and recurring debts coming up in as many weeks
+///
+/// Days run on the application timezone, like recurring debt generation.
+#[server(GetGroupDigest)]
+pub async fn get_group_digest(group_id: i64, weeks: i64) -> Result<GroupDigest, AppError> {
+    use sqlx::SqlitePool;
+
+    use crate::features::recurring_debts::utils::app_today;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    let weeks = weeks.clamp(1, MAX_DIGEST_WEEKS);
+    compute_group_digest(&pool, group_id, app_today(), weeks).await
+}
+
+/// First day of a `weeks` long period ending with `today`
+#[cfg(feature = "ssr")]
+fn period_start(today: Date, weeks: i64) -> Date {
+    today - Duration::days(weeks * 7 - 1)
+}
+
+#[cfg(feature = "ssr")]
+fn parse_amount(amount: &str) -> Result<Decimal, AppError> {
+    amount.parse::<Decimal>().map_err(AppError::internal)
+}
+
+#[cfg(feature = "ssr")]
+fn parse_date(date: &str) -> Result<Date, AppError> {
+    Date::parse(
+        date,
+        &time::format_description::well_known::Iso8601::DEFAULT,
+    )
+    .map_err(AppError::internal)
+}
+
+/// Digest of a group for the `weeks` weeks ending with `today`, without
+/// access checks
+#[cfg(feature = "ssr")]
+pub async fn compute_group_digest(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    today: Date,
+    weeks: i64,
+) -> Result<GroupDigest, AppError> {
+    use crate::features::transactions::handlers::compute_group_balances;
+
+    let from = period_start(today, weeks);
+    let upcoming_until = today + Duration::days(weeks * 7);
+    let from_str = from.to_string();
+    let today_str = today.to_string();
+
+    let group_name = sqlx::query_scalar!("SELECT name FROM groups WHERE id = ?", group_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("Group not found"))?;
+
+    let new_debts = sqlx::query!(
+        r#"
+        SELECT sd.name, sd.amount, p.username as payer_username,
+            date(sd.created_at) as "created_on!: String"
+        FROM shared_debts sd
+        INNER JOIN users p ON p.id = COALESCE(sd.payer_id, sd.created_by)
+        WHERE sd.group_id = ? AND date(sd.created_at) >= ? AND date(sd.created_at) <= ?
+        ORDER BY sd.created_at ASC, sd.id ASC
+        "#,
+        group_id,
+        from_str,
+        today_str
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(DigestDebt {
+            name: row.name,
+            amount: parse_amount(&row.amount)?,
+            payer_username: row.payer_username,
+            created_on: parse_date(&row.created_on)?,
+        })
+    })
+    .collect::<Result<Vec<_>, AppError>>()?;
+
+    let new_transactions = sqlx::query!(
+        r#"
+        SELECT p.username as payer_username, r.username as recipient_username, t.amount,
+            date(t.created_at) as "created_on!: String"
+        FROM transactions t
+        INNER JOIN users p ON p.id = t.payer_id
+        INNER JOIN users r ON r.id = t.recipient_id
+        WHERE t.group_id = ? AND date(t.created_at) >= ? AND date(t.created_at) <= ?
+        ORDER BY t.created_at ASC, t.id ASC
+        "#,
+        group_id,
+        from_str,
+        today_str
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(DigestTransaction {
+            payer_username: row.payer_username,
+            recipient_username: row.recipient_username,
+            amount: parse_amount(&row.amount)?,
+            created_on: parse_date(&row.created_on)?,
+        })
+    })
+    .collect::<Result<Vec<_>, AppError>>()?;
+
+    let mut balances = compute_group_balances(pool, group_id)
+        .await?
+        .into_iter()
+        .map(|balance| {
+            Ok(DigestBalance {
+                username: balance.username,
+                net_type: balance.net_type,
+                amount: parse_amount(&balance.net_amount)?,
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+    balances.sort_by(|a, b| a.username.cmp(&b.username));
+
+    let recurring = sqlx::query!(
+        r#"
+        SELECT name, amount, frequency, frequency_interval,
+            next_generation_date as "next_generation_date!: String",
+            end_date as "end_date: String"
+        FROM recurring_debts
+        WHERE group_id = ? AND is_active = 1
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut upcoming_recurring = Vec::new();
+    for debt in recurring {
+        let frequency = Frequency::from_columns(&debt.frequency, debt.frequency_interval)
+            .map_err(AppError::internal)?;
+        let amount = parse_amount(&debt.amount)?;
+        let next = parse_date(&debt.next_generation_date)?;
+        let end_date = debt.end_date.as_deref().map(parse_date).transpose()?;
+        upcoming_recurring.extend(
+            upcoming_occurrences(next, &frequency, end_date, upcoming_until)
+                .into_iter()
+                .map(|date| DigestRecurring {
+                    name: debt.name.clone(),
+                    amount,
+                    date,
+                }),
+        );
+    }
+    upcoming_recurring.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(GroupDigest {
+        group_name,
+        from,
+        to: today,
+        upcoming_until,
+        new_debts,
+        new_transactions,
+        balances,
+        upcoming_recurring,
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+    use time::Month;
+
+    use super::*;
+    use crate::features::transactions::models::NetType;
+
+    fn date(day: u8) -> Date {
+        Date::from_calendar_date(2026, Month::March, day).unwrap()
+    }
+
+    /// Flat of alice and bob with debts and payments on both sides of
+    /// 2026-03-08, a week before 2026-03-14
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let statements = [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'member')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, created_at) VALUES (1, 1, 1, 'Old pizza', '20', '2026-03-07 20:00:00'), (1, 1, 2, 'Groceries', '40', '2026-03-08 09:00:00'), (1, 2, 2, 'Tomorrow', '5', '2026-03-15 09:00:00')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1), (1, 2), (2, 1), (2, 2), (3, 1), (3, 2)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount, created_at) VALUES (1, 1, 2, '10', '2026-03-01 12:00:00'), (1, 1, 2, '7.50', '2026-03-14 12:00:00')",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Cleaning', '30', 'weekly', '2026-03-10', '2026-03-17'), (1, 1, 'Rent', '900', 'monthly', '2026-01-01', '2026-04-01')",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date, is_active) VALUES (1, 1, 'Paused', '5', 'daily', '2026-03-10', '2026-03-15', 0)",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    #[test]
+    fn test_period_start_includes_today() {
+        assert_eq!(period_start(date(14), 1), date(8));
+        assert_eq!(period_start(date(14), 2), date(1));
+    }
+
+    #[tokio::test]
+    async fn test_digest_covers_the_chosen_weeks() {
+        let pool = setup().await;
+        let digest = compute_group_digest(&pool, 1, date(14), 1).await.unwrap();
+
+        assert_eq!(digest.group_name, "Flat");
+        assert_eq!((digest.from, digest.upcoming_until), (date(8), date(21)));
+        assert_eq!(
+            digest.new_debts,
+            vec![DigestDebt {
+                name: "Groceries".to_string(),
+                amount: Decimal::from(40),
+                payer_username: "bob".to_string(),
+                created_on: date(8),
+            }]
+        );
+        assert_eq!(
+            digest.new_transactions,
+            vec![DigestTransaction {
+                payer_username: "alice".to_string(),
+                recipient_username: "bob".to_string(),
+                amount: Decimal::new(750, 2),
+                created_on: date(14),
+            }]
+        );
+        let upcoming: Vec<(&str, Date)> = digest
+            .upcoming_recurring
+            .iter()
+            .map(|recurring| (recurring.name.as_str(), recurring.date))
+            .collect();
+        assert_eq!(upcoming, vec![("Cleaning", date(17))]);
+        let balances: Vec<(&str, &NetType)> = digest
+            .balances
+            .iter()
+            .map(|balance| (balance.username.as_str(), &balance.net_type))
+            .collect();
+        assert_eq!(
+            balances,
+            vec![("alice", &NetType::Positive), ("bob", &NetType::Negative)]
+        );
+
+        // Two weeks reach back to the first payment and list the cleaning twice
+        let digest = compute_group_digest(&pool, 1, date(14), 2).await.unwrap();
+        assert_eq!(digest.new_debts.len(), 2);
+        assert_eq!(digest.new_transactions.len(), 2);
+        assert_eq!(
+            digest
+                .upcoming_recurring
+                .iter()
+                .map(|recurring| recurring.date)
+                .collect::<Vec<_>>(),
+            vec![date(17), date(24)]
+        );
+        assert_eq!(digest.new_debts_total(), Decimal::from(60));
+    }
+}
@@ -0,0 +1,360 @@
+//! Export of a group's shared debts, transactions and balances
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use time::{Date, OffsetDateTime};
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::ExportFormat;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::utils::is_group_admin,
+    shared_debts::{
+        handlers::parse_stored_date,
+        models::{RoundingStrategy, UserShare},
+        utils::stored_shares,
+    },
+    transactions::{
+        handlers::compute_group_balances,
+        models::{NetType, UserBalance},
+    },
+};
+
+/// Header row of the CSV export
+#[cfg(feature = "ssr")]
+const CSV_HEADER: &str = "type,date,name,amount,creator,payer,recipient,shares";
+
+/// Path of the download endpoint for a group export
+pub fn export_path(group_id: i64, format: ExportFormat) -> String {
+    format!("/api/groups/{}/export.{}", group_id, format.extension())
+}
+
+/// A shared debt as it appears in an export
+#[cfg(feature = "ssr")]
+struct ExportedDebt {
+    incurred_on: Date,
+    name: String,
+    amount: Decimal,
+    creator: String,
+    payer: String,
+    shares: Vec<UserShare>,
+}
+
+/// A transaction as it appears in an export
+#[cfg(feature = "ssr")]
+struct ExportedTransaction {
+    created_at: OffsetDateTime,
+    description: Option<String>,
+    amount: Decimal,
+    payer: String,
+    recipient: String,
+}
+
+/// Server function: Export a group's debts, transactions and balances
+#[server(ExportGroupData)]
+pub async fn export_group_data(group_id: i64, format: ExportFormat) -> Result<String, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    render_group_export(&pool, group_id, user.id, format)
+        .await?
+        .ok_or_else(|| AppError::forbidden("Not a member of this group"))
+}
+
+/// Render a group export for one of its members
+///
+/// Returns `None` when the user is not a member of the group. Balances of
+/// other members are left out when the group hides the full matrix from
+/// everyone but its creator.
+#[cfg(feature = "ssr")]
+pub async fn render_group_export(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+    format: ExportFormat,
+) -> Result<Option<String>, AppError> {
+    use std::str::FromStr;
+
+    let Some(group) = sqlx::query!(
+        r#"
+        SELECT g.full_balances_visible as "full_balances_visible!: bool", g.rounding_strategy
+        FROM groups g
+        INNER JOIN group_members gm ON g.id = gm.group_id
+        WHERE g.id = ? AND gm.user_id = ?
+        "#,
+        group_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+    let rounding = group
+        .rounding_strategy
+        .parse::<RoundingStrategy>()
+        .map_err(AppError::internal)?;
+
+    let debt_rows = sqlx::query!(
+        r#"
+        SELECT
+            sd.id as "id!",
+            sd.name,
+            sd.amount,
+            sd.incurred_on as "incurred_on!: String",
+            sd.created_by,
+            u.username as creator_username,
+            p.username as payer_username
+        FROM shared_debts sd
+        INNER JOIN users u ON sd.created_by = u.id
+        INNER JOIN users p ON COALESCE(sd.payer_id, sd.created_by) = p.id
+        WHERE sd.group_id = ?
+        ORDER BY sd.incurred_on ASC, sd.created_at ASC, sd.id ASC
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut debts = Vec::with_capacity(debt_rows.len());
+    for row in debt_rows {
+        let amount = Decimal::from_str(&row.amount).map_err(AppError::internal)?;
+
+        let participants: Vec<(i64, String, Option<String>)> = sqlx::query!(
+            r#"
+            SELECT u.id as "id!", u.username, sdu.share_amount
+            FROM shared_debt_user sdu
+            INNER JOIN users u ON sdu.user_id = u.id
+            WHERE sdu.shared_debt_id = ?
+            ORDER BY u.username ASC
+            "#,
+            row.id
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p.username, p.share_amount))
+        .collect();
+
+        debts.push(ExportedDebt {
+            incurred_on: parse_stored_date(&row.incurred_on)?,
+            name: row.name,
+            amount,
+            creator: row.creator_username,
+            payer: row.payer_username,
+            shares: stored_shares(amount, &participants, rounding, row.created_by),
+        });
+    }
+
+    let transactions = sqlx::query!(
+        r#"
+        SELECT
+            t.amount,
+            t.description,
+            t.created_at,
+            p.username as payer_username,
+            r.username as recipient_username
+        FROM transactions t
+        INNER JOIN users p ON t.payer_id = p.id
+        INNER JOIN users r ON t.recipient_id = r.id
+        WHERE t.group_id = ?
+        ORDER BY t.created_at ASC, t.id ASC
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|row| {
+        Ok(ExportedTransaction {
+            created_at: row.created_at,
+            description: row.description,
+            amount: Decimal::from_str(&row.amount).map_err(AppError::internal)?,
+            payer: row.payer_username,
+            recipient: row.recipient_username,
+        })
+    })
+    .collect::<Result<Vec<_>, AppError>>()?;
+
+    let mut balances = compute_group_balances(pool, group_id).await?;
+    if !group.full_balances_visible && !is_group_admin(pool, group_id, user_id).await? {
+        balances.retain(|balance| balance.user_id == user_id);
+    }
+    balances.sort_by(|a, b| a.username.cmp(&b.username));
+
+    let body = match format {
+        ExportFormat::Csv => build_csv(&debts, &transactions, &balances),
+    };
+
+    Ok(Some(body))
+}
+
+/// Build the CSV body, one row per debt, transaction and member balance
+#[cfg(feature = "ssr")]
+fn build_csv(
+    debts: &[ExportedDebt],
+    transactions: &[ExportedTransaction],
+    balances: &[UserBalance],
+) -> String {
+    let mut lines = vec![CSV_HEADER.to_string()];
+
+    for debt in debts {
+        let shares = debt
+            .shares
+            .iter()
+            .map(|share| format!("{}: {}", share.username, format_amount(share.share_amount)))
+            .collect::<Vec<_>>()
+            .join("; ");
+        lines.push(
+            [
+                "debt".to_string(),
+                debt.incurred_on.to_string(),
+                csv_field(&debt.name),
+                format_amount(debt.amount),
+                csv_field(&debt.creator),
+                csv_field(&debt.payer),
+                String::new(),
+                csv_field(&shares),
+            ]
+            .join(","),
+        );
+    }
+
+    for transaction in transactions {
+        lines.push(
+            [
+                "transaction".to_string(),
+                transaction.created_at.date().to_string(),
+                csv_field(transaction.description.as_deref().unwrap_or_default()),
+                format_amount(transaction.amount),
+                csv_field(&transaction.payer),
+                csv_field(&transaction.payer),
+                csv_field(&transaction.recipient),
+                String::new(),
+            ]
+            .join(","),
+        );
+    }
+
+    for balance in balances {
+        let net = balance.net_amount.parse::<Decimal>().unwrap_or_default();
+        let net = match balance.net_type {
+            NetType::Negative => -net,
+            NetType::Positive | NetType::Neutral => net,
+        };
+        lines.push(format!(
+            "balance,,{},{},,,,",
+            csv_field(&balance.username),
+            format_amount(net)
+        ));
+    }
+
+    lines.push(String::new());
+    lines.join("\r\n")
+}
+
+/// Amount with exactly two decimal places, e.g. "12.50"
+#[cfg(feature = "ssr")]
+fn format_amount(amount: Decimal) -> String {
+    format!("{:.2}", amount.round_dp(2))
+}
+
+/// Quote a user-entered field and keep spreadsheets from running it as a
+/// formula
+#[cfg(feature = "ssr")]
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use time::{Date, Month, Time};
+
+    use super::*;
+
+    fn at(month: Month, day: u8) -> OffsetDateTime {
+        Date::from_calendar_date(2024, month, day)
+            .unwrap()
+            .with_time(Time::MIDNIGHT)
+            .assume_utc()
+    }
+
+    #[test]
+    fn test_empty_group_has_header_row() {
+        assert_eq!(build_csv(&[], &[], &[]), format!("{}\r\n", CSV_HEADER));
+    }
+
+    #[test]
+    fn test_rows_for_debts_transactions_and_balances() {
+        let debts = [ExportedDebt {
+            incurred_on: Date::from_calendar_date(2024, Month::March, 5).unwrap(),
+            name: "Dinner, drinks".to_string(),
+            amount: Decimal::new(10, 0),
+            creator: "alice".to_string(),
+            payer: "bob".to_string(),
+            shares: stored_shares(
+                Decimal::new(10, 0),
+                &[(1, "alice".to_string(), None), (2, "bob".to_string(), None)],
+                RoundingStrategy::DistributeCents,
+                1,
+            ),
+        }];
+        let transactions = [ExportedTransaction {
+            created_at: at(Month::March, 6),
+            description: None,
+            amount: Decimal::new(55, 1),
+            payer: "alice".to_string(),
+            recipient: "bob".to_string(),
+        }];
+        let balances = [UserBalance {
+            user_id: 1,
+            username: "alice".to_string(),
+            relationships: Vec::new(),
+            total_owed: "0".to_string(),
+            total_owing: "0.5".to_string(),
+            net_amount: "0.5".to_string(),
+            net_type: NetType::Negative,
+        }];
+
+        let csv = build_csv(&debts, &transactions, &balances);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[1],
+            "debt,2024-03-05,\"Dinner, drinks\",10.00,alice,bob,,alice: 5.00; bob: 5.00"
+        );
+        assert_eq!(lines[2], "transaction,2024-03-06,,5.50,alice,alice,bob,");
+        assert_eq!(lines[3], "balance,,alice,-0.50,,,,");
+    }
+
+    #[test]
+    fn test_csv_field_escaping() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+    }
+}
@@ -0,0 +1,365 @@
+//! Checks that a group's debts and payments only involve its members, and
+//! repairs for what they find
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use sqlx::SqliteConnection;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::{IntegrityReport, RepairAction};
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        models::{IntegrityIssue, IntegrityIssueKind},
+        utils::{currency_symbol, is_group_admin},
+    },
+    transactions::balances::invalidate_group_balances,
+};
+
+/// Server function: Look for debts and payments involving people who are no
+/// longer in the group (admins only)
+#[server(AuditGroupIntegrity)]
+pub async fn audit_group_integrity(group_id: i64) -> Result<IntegrityReport, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can check data health",
+        ));
+    }
+
+    let mut conn = pool.acquire().await?;
+    Ok(audit(&mut conn, group_id).await?)
+}
+
+/// Server function: Apply repairs found by an integrity audit (admins only)
+///
+/// All repairs run in one transaction. Returns the number of rows changed.
+#[server(RepairGroupIntegrity)]
+pub async fn repair_group_integrity(
+    group_id: i64,
+    actions: Vec<RepairAction>,
+) -> Result<u64, AppError> {
+    use sqlx::SqlitePool;
+
+    if actions.is_empty() {
+        return Err(AppError::validation("No repairs selected"));
+    }
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can repair group data",
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    let changed = repair(&mut tx, group_id, &actions).await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        group_id = group_id,
+        user_id = user.id,
+        changed = changed,
+        "Repaired group data"
+    );
+
+    Ok(changed)
+}
+
+/// Find everything in a group that involves non-members
+#[cfg(feature = "ssr")]
+async fn audit(conn: &mut SqliteConnection, group_id: i64) -> Result<IntegrityReport, sqlx::Error> {
+    let mut issues = Vec::new();
+
+    let recurring_members = sqlx::query!(
+        r#"
+        SELECT rdu.id as "id!", rd.name, u.username
+        FROM recurring_debt_user rdu
+        INNER JOIN recurring_debts rd ON rd.id = rdu.recurring_debt_id
+        INNER JOIN users u ON u.id = rdu.user_id
+        WHERE rd.group_id = ?
+          AND rdu.user_id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+        ORDER BY rd.name ASC, u.username ASC
+        "#,
+        group_id,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    issues.extend(recurring_members.into_iter().map(|row| IntegrityIssue {
+        kind: IntegrityIssueKind::RecurringDebtNonMember,
+        record_id: row.id,
+        description: format!("{} is still part of \"{}\"", row.username, row.name),
+    }));
+
+    let shares = sqlx::query!(
+        r#"
+        SELECT sdu.id as "id!", sd.name, u.username
+        FROM shared_debt_user sdu
+        INNER JOIN shared_debts sd ON sd.id = sdu.shared_debt_id
+        INNER JOIN users u ON u.id = sdu.user_id
+        WHERE sd.group_id = ? AND sdu.is_settled = 0
+          AND sdu.user_id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+        ORDER BY sd.created_at ASC, u.username ASC
+        "#,
+        group_id,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    issues.extend(shares.into_iter().map(|row| IntegrityIssue {
+        kind: IntegrityIssueKind::SharedDebtShareNonMember,
+        record_id: row.id,
+        description: format!("{} has an open share of \"{}\"", row.username, row.name),
+    }));
+
+    let empty_debts = sqlx::query!(
+        r#"
+        SELECT rd.id as "id!", rd.name
+        FROM recurring_debts rd
+        WHERE rd.group_id = ? AND rd.is_active = 1
+          AND NOT EXISTS (SELECT 1 FROM recurring_debt_user rdu WHERE rdu.recurring_debt_id = rd.id)
+        ORDER BY rd.name ASC
+        "#,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    issues.extend(empty_debts.into_iter().map(|row| IntegrityIssue {
+        kind: IntegrityIssueKind::EmptyRecurringDebt,
+        record_id: row.id,
+        description: format!("\"{}\" has nobody to split it between", row.name),
+    }));
+
+    let transactions = sqlx::query!(
+        r#"
+        SELECT
+            t.id as "id!", t.amount, g.currency,
+            p.username as payer_username, r.username as recipient_username
+        FROM transactions t
+        INNER JOIN groups g ON g.id = t.group_id
+        INNER JOIN users p ON p.id = t.payer_id
+        INNER JOIN users r ON r.id = t.recipient_id
+        WHERE t.group_id = ?
+          AND t.payer_id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+          AND t.recipient_id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+        ORDER BY t.created_at ASC
+        "#,
+        group_id,
+        group_id,
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    issues.extend(transactions.into_iter().map(|row| IntegrityIssue {
+        kind: IntegrityIssueKind::TransactionBetweenNonMembers,
+        record_id: row.id,
+        description: format!(
+            "{} paid {} {}{}",
+            row.payer_username,
+            row.recipient_username,
+            currency_symbol(&row.currency),
+            row.amount
+        ),
+    }));
+
+    Ok(IntegrityReport { issues })
+}
+
+/// Apply repairs, returning the number of rows changed
+///
+/// Members are removed from recurring debts before empty ones are paused,
+/// so a debt left without members by the first is caught by the second.
+#[cfg(feature = "ssr")]
+async fn repair(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    actions: &[RepairAction],
+) -> Result<u64, sqlx::Error> {
+    let mut changed = 0;
+
+    if actions.contains(&RepairAction::RemoveRecurringDebtNonMembers) {
+        changed += sqlx::query!(
+            r#"
+            DELETE FROM recurring_debt_user
+            WHERE recurring_debt_id IN (SELECT id FROM recurring_debts WHERE group_id = ?)
+              AND user_id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+            "#,
+            group_id,
+            group_id
+        )
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    }
+
+    if actions.contains(&RepairAction::RemoveSharedDebtNonMembers) {
+        let removed = sqlx::query!(
+            r#"
+            DELETE FROM shared_debt_user
+            WHERE is_settled = 0
+              AND shared_debt_id IN (SELECT id FROM shared_debts WHERE group_id = ?)
+              AND user_id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+            "#,
+            group_id,
+            group_id
+        )
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+
+        // Dropping shares changes the remaining members' equal splits
+        if removed > 0 {
+            invalidate_group_balances(&mut *conn, group_id).await?;
+        }
+        changed += removed;
+    }
+
+    if actions.contains(&RepairAction::PauseEmptyRecurringDebts) {
+        changed += sqlx::query!(
+            r#"
+            UPDATE recurring_debts
+            SET is_active = 0, updated_at = CURRENT_TIMESTAMP, version = version + 1
+            WHERE group_id = ? AND is_active = 1
+              AND NOT EXISTS (SELECT 1 FROM recurring_debt_user rdu WHERE rdu.recurring_debt_id = recurring_debts.id)
+            "#,
+            group_id
+        )
+        .execute(&mut *conn)
+        .await?
+        .rows_affected();
+    }
+
+    Ok(changed)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+    use crate::features::transactions::balances::rebuild_group_balances;
+
+    /// Group 1 of alice and bob, after carol (3) and dave (4) left without
+    /// being cleaned up
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        let statements = [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x'), ('dave', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'member')",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-01-01', '2026-02-01'), (1, 1, 'Cleaning', '60', 'monthly', '2026-01-01', '2026-02-01'), (1, 1, 'Parking', '30', 'monthly', '2026-01-01', '2026-02-01')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (1, 2), (1, 3), (2, 3)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, 1, 'Pizza', '30')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, is_settled) VALUES (1, 1, 0), (1, 2, 0), (1, 3, 0), (1, 4, 1)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 3, 4, '5'), (1, 2, 3, '10')",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    fn kinds(report: &IntegrityReport) -> Vec<IntegrityIssueKind> {
+        report.issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    #[tokio::test]
+    async fn test_audit_reports_non_members() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let report = audit(&mut conn, 1).await.unwrap();
+
+        assert_eq!(
+            kinds(&report),
+            vec![
+                IntegrityIssueKind::RecurringDebtNonMember,
+                IntegrityIssueKind::RecurringDebtNonMember,
+                IntegrityIssueKind::SharedDebtShareNonMember,
+                IntegrityIssueKind::EmptyRecurringDebt,
+                IntegrityIssueKind::TransactionBetweenNonMembers,
+            ]
+        );
+        assert_eq!(
+            report.issues[0].description,
+            "carol is still part of \"Cleaning\""
+        );
+        // dave's settled share is history, not a problem
+        assert_eq!(
+            report.issues[2].description,
+            "carol has an open share of \"Pizza\""
+        );
+        assert_eq!(
+            report.issues[3].description,
+            "\"Parking\" has nobody to split it between"
+        );
+        assert_eq!(report.issues[4].description, "carol paid dave €5");
+        assert_eq!(
+            report.repairs(),
+            vec![
+                RepairAction::RemoveRecurringDebtNonMembers,
+                RepairAction::RemoveSharedDebtNonMembers,
+                RepairAction::PauseEmptyRecurringDebts,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repair_leaves_only_history() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+        rebuild_group_balances(&mut conn, 1).await.unwrap();
+        let actions = audit(&mut conn, 1).await.unwrap().repairs();
+
+        let changed = repair(&mut conn, 1, &actions).await.unwrap();
+        // Two recurring members, one share, and both Cleaning and Parking paused
+        assert_eq!(changed, 5);
+
+        let report = audit(&mut conn, 1).await.unwrap();
+        assert_eq!(
+            kinds(&report),
+            vec![IntegrityIssueKind::TransactionBetweenNonMembers]
+        );
+        assert!(report.repairs().is_empty());
+
+        let rent_active: bool =
+            sqlx::query_scalar("SELECT is_active FROM recurring_debts WHERE name = 'Rent'")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert!(rent_active);
+        let cached_at: Option<String> =
+            sqlx::query_scalar("SELECT balances_cached_at FROM groups WHERE id = 1")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(cached_at, None);
+    }
+}
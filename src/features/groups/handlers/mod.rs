@@ -0,0 +1,1427 @@
+//! Group server functions
+
+mod activity;
+mod backup;
+mod budget;
+mod default_group;
+mod digest;
+mod export;
+mod integrity;
+mod overview;
+mod search;
+mod statistics;
+
+pub use activity::*;
+pub use backup::*;
+pub use budget::*;
+pub use default_group::*;
+pub use digest::*;
+pub use export::*;
+pub use integrity::*;
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+pub use overview::*;
+pub use search::*;
+pub use statistics::*;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use super::models::{Group, GroupMemberInfo, GroupRole, GroupSummary, GroupWithMembers};
+#[cfg(feature = "ssr")]
+use super::utils::{
+    check_group_currency, clear_stale_default_groups, currency_symbol, group_currency,
+    is_group_admin,
+};
+use crate::error::AppError;
+use crate::features::auth::models::UserSession;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+#[cfg(feature = "ssr")]
+use crate::features::recurring_debts::{
+    handlers::include_new_member_in_recurring_debts, utils::check_timezone,
+};
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::RoundingStrategy;
+#[cfg(feature = "ssr")]
+use crate::features::transactions::balances::invalidate_group_balances;
+#[cfg(feature = "ssr")]
+use crate::validation::{
+    rules::{EDIT_CONFLICT_ERROR, MAX_NAME_LENGTH, MEMBER_REMOVAL_BLOCKED},
+    validate_money, validate_name,
+};
+
+/// Server function: Get all groups for the current user
+#[server(GetUserGroups)]
+pub async fn get_user_groups() -> Result<Vec<GroupWithMembers>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    load_user_groups(&pool, user.id).await
+}
+
+/// Load the groups `user_id` is a member of, most recently updated first
+#[cfg(feature = "ssr")]
+pub async fn load_user_groups(
+    pool: &sqlx::SqlitePool,
+    user_id: i64,
+) -> Result<Vec<GroupWithMembers>, AppError> {
+    // Fetch groups with member count
+    let groups = sqlx::query!(
+        r#"
+        SELECT
+            g.id as "id!",
+            g.name,
+            g.created_by,
+            g.created_at,
+            g.updated_at,
+            COALESCE(COUNT(gm.user_id), 0) as "member_count!: i64",
+            CASE WHEN g.created_by = ? OR me.role = 'admin' THEN 1 ELSE 0 END as "is_admin!: bool",
+            g.is_archived as "is_archived!: bool",
+            g.currency
+        FROM groups g
+        INNER JOIN group_members gm ON g.id = gm.group_id
+        INNER JOIN group_members me ON g.id = me.group_id AND me.user_id = ?
+        GROUP BY g.id
+        ORDER BY g.updated_at DESC
+        "#,
+        user_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let groups_with_members = groups
+        .into_iter()
+        .map(|row| GroupWithMembers {
+            id: row.id,
+            name: row.name,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            member_count: row.member_count,
+            is_admin: row.is_admin,
+            is_archived: row.is_archived,
+            currency: row.currency,
+        })
+        .collect();
+
+    Ok(groups_with_members)
+}
+
+/// Server function: Get a specific group with details
+#[server(GetGroup)]
+pub async fn get_group(group_id: i64) -> Result<Group, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    load_member_group(&pool, group_id, user.id).await
+}
+
+/// Load a group for `user_id`, who must be a member
+#[cfg(feature = "ssr")]
+pub async fn load_member_group(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<Group, AppError> {
+    // Check if user is a member of the group
+    let role = sqlx::query_scalar!(
+        "SELECT role FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::forbidden("Not a member of this group"))?;
+
+    load_group(pool, group_id, user_id, &role).await
+}
+
+/// Load a group as seen by `user_id`, a member with the given role
+#[cfg(feature = "ssr")]
+pub async fn load_group(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+    role: &str,
+) -> Result<Group, AppError> {
+    let group = sqlx::query!(
+        r#"SELECT id, name, created_by, full_balances_visible as "full_balances_visible!: bool", require_debt_approval as "require_debt_approval!: bool", monthly_budget, timezone, currency, rounding_strategy, is_archived as "is_archived!: bool", version, created_at, updated_at FROM groups WHERE id = ?"#,
+        group_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Group not found"))?;
+
+    let is_admin = is_group_admin(pool, group_id, user_id).await?;
+    let monthly_budget = group
+        .monthly_budget
+        .map(|budget| budget.parse::<rust_decimal::Decimal>())
+        .transpose()
+        .map_err(AppError::internal)?;
+    let rounding_strategy = group
+        .rounding_strategy
+        .parse::<RoundingStrategy>()
+        .map_err(AppError::internal)?;
+
+    Ok(Group {
+        id: group.id,
+        name: group.name,
+        created_by: group.created_by,
+        full_balances_visible: group.full_balances_visible,
+        require_debt_approval: group.require_debt_approval,
+        created_at: group.created_at,
+        updated_at: group.updated_at,
+        is_admin,
+        is_viewer: GroupRole::parse(role) == Some(GroupRole::Viewer),
+        monthly_budget,
+        timezone: group.timezone,
+        currency: group.currency,
+        rounding_strategy,
+        is_archived: group.is_archived,
+        version: group.version,
+    })
+}
+
+/// Server function: Get members of a group, creator first, then by join date
+#[server(GetGroupMembers)]
+pub async fn get_group_members(group_id: i64) -> Result<Vec<GroupMemberInfo>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    // Check if user is a member of the group
+    let is_member = sqlx::query!(
+        "SELECT COUNT(*) as \"count!\" FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member.count == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    load_group_members(&pool, group_id).await
+}
+
+/// Load a group's members, creator first, then by join date
+#[cfg(feature = "ssr")]
+pub async fn load_group_members(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+) -> Result<Vec<GroupMemberInfo>, AppError> {
+    // Get the group creator ID
+    let group = sqlx::query!("SELECT created_by FROM groups WHERE id = ?", group_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("Group not found"))?;
+
+    // Fetch all members with creator and admin flags
+    let members = sqlx::query!(
+        r#"
+        SELECT 
+            u.id as "id!",
+            u.username,
+            CASE WHEN u.id = ? THEN 1 ELSE 0 END as "is_creator!: bool",
+            CASE WHEN u.id = ? OR gm.role = 'admin' THEN 1 ELSE 0 END as "is_admin!: bool",
+            CASE WHEN gm.role = 'viewer' THEN 1 ELSE 0 END as "is_viewer!: bool",
+            gm.created_at as joined_at
+        FROM users u
+        INNER JOIN group_members gm ON u.id = gm.user_id
+        WHERE gm.group_id = ?
+        ORDER BY (CASE WHEN u.id = ? THEN 0 ELSE 1 END), gm.created_at ASC, u.username ASC
+        "#,
+        group.created_by,
+        group.created_by,
+        group_id,
+        group.created_by
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let member_infos = members
+        .into_iter()
+        .map(|row| GroupMemberInfo {
+            id: row.id,
+            username: row.username,
+            is_creator: row.is_creator,
+            is_admin: row.is_admin,
+            is_viewer: row.is_viewer,
+            joined_at: Some(row.joined_at),
+        })
+        .collect();
+
+    Ok(member_infos)
+}
+
+/// Server function: Create a new group
+#[server(CreateGroup)]
+pub async fn create_group(
+    name: String,
+    /// Monthly spending budget in the group's currency; no budget when omitted
+    /// or empty
+    #[server(default)]
+    monthly_budget: Option<String>,
+    /// IANA timezone such as `Europe/Berlin`; the server's when omitted or
+    /// empty
+    #[server(default)]
+    timezone: Option<String>,
+    /// ISO 4217 code such as `USD`; euros when omitted or empty
+    #[server(default)]
+    currency: Option<String>,
+) -> Result<i64, AppError> {
+    use sqlx::SqlitePool;
+
+    // Validate group name
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+    let monthly_budget = validate_budget(monthly_budget.as_deref())?;
+    let timezone = check_timezone(timezone.as_deref()).map_err(AppError::Validation)?;
+    let currency = check_group_currency(currency.as_deref()).map_err(AppError::Validation)?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    // Start a transaction
+    let mut tx = pool.begin().await?;
+
+    // Insert the group
+    let result = sqlx::query!(
+        "INSERT INTO groups (name, created_by, monthly_budget, timezone, currency) VALUES (?, ?, ?, ?, ?)",
+        name,
+        user.id,
+        monthly_budget,
+        timezone,
+        currency
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let group_id = result.last_insert_rowid();
+
+    // Add creator as the first member
+    sqlx::query!(
+        "INSERT INTO group_members (group_id, user_id, role) VALUES (?, ?, 'admin')",
+        group_id,
+        user.id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Commit transaction
+    tx.commit().await?;
+
+    Ok(group_id)
+}
+
+/// Server function: Update a group
+///
+/// `version` is the group version the editor loaded; the update is rejected
+/// with a conflict error if the group was saved since.
+#[server(UpdateGroup)]
+pub async fn update_group(
+    group_id: i64,
+    name: String,
+    member_ids: Vec<i64>,
+    version: i64,
+    /// Monthly spending budget in the group's currency; removes the budget
+    /// when omitted or empty
+    #[server(default)]
+    monthly_budget: Option<String>,
+    /// IANA timezone such as `Europe/Berlin`; the server's when omitted or
+    /// empty
+    #[server(default)]
+    timezone: Option<String>,
+    /// ISO 4217 code such as `USD`; euros when omitted or empty
+    #[server(default)]
+    currency: Option<String>,
+) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    // Validate group name
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+    let monthly_budget = validate_budget(monthly_budget.as_deref())?;
+    let timezone = check_timezone(timezone.as_deref()).map_err(AppError::Validation)?;
+    let currency = check_group_currency(currency.as_deref()).map_err(AppError::Validation)?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group = sqlx::query!("SELECT created_by FROM groups WHERE id = ?", group_id)
+        .fetch_optional(&pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("Group not found"))?;
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can update the group",
+        ));
+    }
+
+    // Prevent admins from removing themselves
+    if !member_ids.contains(&user.id) {
+        return Err(AppError::validation(
+            "You cannot remove yourself from the group",
+        ));
+    }
+
+    if !member_ids.contains(&group.created_by) {
+        return Err(AppError::validation(
+            "The group creator cannot be removed from the group",
+        ));
+    }
+
+    let current_ids = sqlx::query_scalar!(
+        "SELECT user_id FROM group_members WHERE group_id = ?",
+        group_id
+    )
+    .fetch_all(&pool)
+    .await?;
+    let removed_ids: Vec<i64> = current_ids
+        .into_iter()
+        .filter(|id| !member_ids.contains(id))
+        .collect();
+    check_member_removal(&pool, group_id, &removed_ids).await?;
+
+    // Start a transaction
+    let mut tx = pool.begin().await?;
+
+    update_group_row(
+        &mut tx,
+        group_id,
+        version,
+        &name,
+        monthly_budget.as_deref(),
+        timezone.as_deref(),
+        &currency,
+    )
+    .await?;
+
+    replace_members(&mut tx, group_id, &member_ids).await?;
+
+    // Commit transaction
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Make sure members about to be removed from a group have nothing open in
+/// it: no unsettled shares of debts someone else paid and a zero balance
+///
+/// The error names each blocking member's debts and balance so the admin
+/// knows what to settle first.
+#[cfg(feature = "ssr")]
+async fn check_member_removal(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    removed_ids: &[i64],
+) -> Result<(), AppError> {
+    use crate::features::transactions::{handlers::compute_group_balances, models::NetType};
+
+    if removed_ids.is_empty() {
+        return Ok(());
+    }
+
+    let balances = compute_group_balances(pool, group_id).await?;
+    let currency = currency_symbol(&group_currency(pool, group_id).await?);
+    let mut blocked = Vec::new();
+    for balance in balances
+        .iter()
+        .filter(|balance| removed_ids.contains(&balance.user_id))
+    {
+        let open_debts = sqlx::query_scalar!(
+            r#"
+            SELECT sd.name
+            FROM shared_debt_user sdu
+            INNER JOIN shared_debts sd ON sd.id = sdu.shared_debt_id
+            WHERE sd.group_id = ? AND sdu.user_id = ? AND sdu.is_settled = 0
+              AND COALESCE(sd.payer_id, sd.created_by) != sdu.user_id
+            ORDER BY sd.created_at ASC, sd.id ASC
+            "#,
+            group_id,
+            balance.user_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut reasons = Vec::new();
+        if !open_debts.is_empty() {
+            let names: Vec<String> = open_debts
+                .iter()
+                .map(|name| format!("\"{}\"", name))
+                .collect();
+            reasons.push(format!("unsettled shares of {}", names.join(", ")));
+        }
+        match balance.net_type {
+            NetType::Negative => reasons.push(format!("owes {}{}", currency, balance.net_amount)),
+            NetType::Positive => {
+                reasons.push(format!("is owed {}{}", currency, balance.net_amount))
+            }
+            NetType::Neutral => {}
+        }
+        if !reasons.is_empty() {
+            blocked.push(format!("{}: {}", balance.username, reasons.join("; ")));
+        }
+    }
+
+    if blocked.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::conflict(format!(
+            "{}. {}. Settle up first.",
+            MEMBER_REMOVAL_BLOCKED,
+            blocked.join(". ")
+        )))
+    }
+}
+
+/// Replace a group's members, keeping the role of everyone who stays, taking
+/// anyone removed off the group's recurring debts and default group setting,
+/// and adding anyone new to recurring debts that include new members
+#[cfg(feature = "ssr")]
+async fn replace_members(
+    conn: &mut sqlx::SqliteConnection,
+    group_id: i64,
+    member_ids: &[i64],
+) -> Result<(), AppError> {
+    // Remaining admins and viewers keep their role
+    let previous_roles: std::collections::HashMap<i64, GroupRole> = sqlx::query!(
+        "SELECT user_id, role FROM group_members WHERE group_id = ?",
+        group_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .map(|row| (row.user_id, GroupRole::parse(&row.role).unwrap_or_default()))
+    .collect();
+
+    // Remove all existing members
+    sqlx::query!("DELETE FROM group_members WHERE group_id = ?", group_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Add new members
+    for &member_id in member_ids {
+        let role = previous_roles
+            .get(&member_id)
+            .copied()
+            .unwrap_or_default()
+            .as_str();
+        sqlx::query!(
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (?, ?, ?)",
+            group_id,
+            member_id,
+            role
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        if !previous_roles.contains_key(&member_id) {
+            include_new_member_in_recurring_debts(&mut *conn, group_id, member_id).await?;
+        }
+    }
+
+    // Future recurring debt instances only go to members
+    sqlx::query!(
+        r#"
+        DELETE FROM recurring_debt_user
+        WHERE recurring_debt_id IN (SELECT id FROM recurring_debts WHERE group_id = ?)
+          AND user_id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+        "#,
+        group_id,
+        group_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    clear_stale_default_groups(&mut *conn, group_id).await?;
+
+    Ok(())
+}
+
+/// Write a group's name, budget, timezone and updated_at timestamp if it is
+/// still at `version`, bumping the version
+#[cfg(feature = "ssr")]
+async fn update_group_row(
+    conn: &mut sqlx::SqliteConnection,
+    group_id: i64,
+    version: i64,
+    name: &str,
+    monthly_budget: Option<&str>,
+    timezone: Option<&str>,
+    currency: &str,
+) -> Result<(), AppError> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE groups
+        SET name = ?, monthly_budget = ?, timezone = ?, currency = ?,
+            updated_at = CURRENT_TIMESTAMP, version = version + 1
+        WHERE id = ? AND version = ?
+        "#,
+        name,
+        monthly_budget,
+        timezone,
+        currency,
+        group_id,
+        version
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict(EDIT_CONFLICT_ERROR));
+    }
+
+    Ok(())
+}
+
+/// Server function: Allow or forbid members who aren't admins to load the full
+/// balance matrix (admins only)
+#[server(SetFullBalancesVisible)]
+pub async fn set_full_balances_visible(group_id: i64, visible: bool) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can change this setting",
+        ));
+    }
+
+    sqlx::query!(
+        "UPDATE groups SET full_balances_visible = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        visible,
+        group_id
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Server function: Require an admin's approval before debts by other
+/// members count towards balances (admins only)
+///
+/// Turning it off leaves debts already waiting for approval as they are.
+#[server(SetRequireDebtApproval)]
+pub async fn set_require_debt_approval(group_id: i64, required: bool) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can change this setting",
+        ));
+    }
+
+    sqlx::query!(
+        "UPDATE groups SET require_debt_approval = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        required,
+        group_id
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Server function: Choose who absorbs the cents left over when the group's
+/// debts are split (admins only)
+#[server(SetRoundingStrategy)]
+pub async fn set_rounding_strategy(group_id: i64, strategy: String) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let strategy = strategy
+        .parse::<RoundingStrategy>()
+        .map_err(AppError::Validation)?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can change this setting",
+        ));
+    }
+
+    let strategy = strategy.as_str();
+    let mut tx = pool.begin().await?;
+    sqlx::query!(
+        "UPDATE groups SET rounding_strategy = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        strategy,
+        group_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    // Equal splits are charged with the new leftover cents from now on
+    invalidate_group_balances(&mut tx, group_id).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Server function: Freeze a group so nothing in it can change anymore
+/// (admins only)
+///
+/// The group stays viewable and its recurring debts stop generating until it
+/// is unarchived.
+#[server(ArchiveGroup)]
+pub async fn archive_group(group_id: i64) -> Result<(), AppError> {
+    set_group_archived(group_id, true).await
+}
+
+/// Server function: Let an archived group be changed again (admins only)
+#[server(UnarchiveGroup)]
+pub async fn unarchive_group(group_id: i64) -> Result<(), AppError> {
+    set_group_archived(group_id, false).await
+}
+
+#[cfg(feature = "ssr")]
+async fn set_group_archived(group_id: i64, archived: bool) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can archive or unarchive a group",
+        ));
+    }
+
+    sqlx::query!(
+        "UPDATE groups SET is_archived = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        archived,
+        group_id
+    )
+    .execute(&pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Server function: Remove a single member from a group (admins only)
+///
+/// Members with an outstanding balance are only removed when `force` is set.
+/// The member is dropped from the group's recurring debts so no future instances
+/// include them; existing shared debt shares are left untouched.
+#[server(RemoveGroupMember)]
+pub async fn remove_group_member(group_id: i64, user_id: i64, force: bool) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group = sqlx::query!(
+        "SELECT created_by, currency FROM groups WHERE id = ?",
+        group_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Group not found"))?;
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden("Only group admins can remove members"));
+    }
+
+    if user_id == group.created_by {
+        return Err(AppError::validation(
+            "The group creator cannot be removed from the group",
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    remove_member(
+        &mut tx,
+        group_id,
+        user_id,
+        force,
+        currency_symbol(&group.currency),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Remove a member unless they still owe or are owed money, or `force` is
+/// set
+///
+/// The balance is read on the connection the member is deleted on, so within
+/// a transaction a debt added in between cannot slip past the check.
+/// `currency` is the symbol the balance is written with in the error.
+#[cfg(feature = "ssr")]
+async fn remove_member(
+    conn: &mut sqlx::SqliteConnection,
+    group_id: i64,
+    user_id: i64,
+    force: bool,
+    currency: &str,
+) -> Result<(), AppError> {
+    use rust_decimal::Decimal;
+
+    use crate::features::transactions::balances::compute_pair_balances;
+
+    let username = sqlx::query_scalar!(
+        r#"
+        SELECT u.username
+        FROM users u
+        INNER JOIN group_members gm ON gm.user_id = u.id
+        WHERE gm.group_id = ? AND gm.user_id = ?
+        "#,
+        group_id,
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("User is not a member of this group"))?;
+
+    if !force {
+        // Positive when the member owes others, like the pairwise balances
+        let owing: Decimal = compute_pair_balances(&mut *conn, group_id)
+            .await?
+            .into_iter()
+            .map(|((user_a, user_b), amount)| {
+                if user_a == user_id {
+                    amount
+                } else if user_b == user_id {
+                    -amount
+                } else {
+                    Decimal::ZERO
+                }
+            })
+            .sum::<Decimal>()
+            .round_dp(2);
+
+        if owing > Decimal::ZERO {
+            return Err(AppError::conflict(format!(
+                "{} still owes {}{} in this group. Settle up first or force the removal.",
+                username, currency, owing
+            )));
+        }
+        if owing < Decimal::ZERO {
+            return Err(AppError::conflict(format!(
+                "{} is still owed {}{} in this group. Settle up first or force the removal.",
+                username,
+                currency,
+                owing.abs()
+            )));
+        }
+    }
+
+    sqlx::query!(
+        "DELETE FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    // Drop them from future recurring debt instances; historical shares stay intact
+    sqlx::query!(
+        r#"
+        DELETE FROM recurring_debt_user
+        WHERE user_id = ?
+          AND recurring_debt_id IN (SELECT id FROM recurring_debts WHERE group_id = ?)
+        "#,
+        user_id,
+        group_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    clear_stale_default_groups(&mut *conn, group_id).await?;
+
+    sqlx::query!(
+        "UPDATE groups SET updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        group_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Server function: Leave a group as the current user
+///
+/// Only possible with a settled balance, and never for the group creator.
+/// The member's unsettled shares and recurring debt entries in the group are
+/// dropped along with their membership.
+#[server(LeaveGroup)]
+pub async fn leave_group(group_id: i64) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    use super::{GroupEventBroadcaster, broadcast_group_event, models::GroupEvent};
+    use crate::features::transactions::{handlers::compute_group_balances, models::NetType};
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group = sqlx::query!(
+        "SELECT created_by, currency FROM groups WHERE id = ?",
+        group_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Group not found"))?;
+    let currency = currency_symbol(&group.currency);
+
+    if user.id == group.created_by {
+        return Err(AppError::validation(
+            "The group creator cannot leave the group",
+        ));
+    }
+
+    let balances = compute_group_balances(&pool, group_id).await?;
+    let balance = balances
+        .into_iter()
+        .find(|b| b.user_id == user.id)
+        .ok_or_else(|| AppError::forbidden("You are not a member of this group"))?;
+
+    match balance.net_type {
+        NetType::Negative => {
+            return Err(AppError::conflict(format!(
+                "You still owe {}{} in this group. Settle up before leaving.",
+                currency, balance.net_amount
+            )));
+        }
+        NetType::Positive => {
+            return Err(AppError::conflict(format!(
+                "You are still owed {}{} in this group. Settle up before leaving.",
+                currency, balance.net_amount
+            )));
+        }
+        NetType::Neutral => {}
+    }
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "DELETE FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM shared_debt_user
+        WHERE user_id = ? AND is_settled = 0
+          AND shared_debt_id IN (SELECT id FROM shared_debts WHERE group_id = ?)
+        "#,
+        user.id,
+        group_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // Dropping shares changes the other members' equal splits
+    invalidate_group_balances(&mut tx, group_id).await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM recurring_debt_user
+        WHERE user_id = ?
+          AND recurring_debt_id IN (SELECT id FROM recurring_debts WHERE group_id = ?)
+        "#,
+        user.id,
+        group_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    clear_stale_default_groups(&mut *tx, group_id).await?;
+
+    sqlx::query!(
+        "UPDATE groups SET updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        group_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, group_id, GroupEvent::SharedDebtsChanged);
+
+    Ok(())
+}
+
+/// Server function: Promote a member to admin or demote them back (admins
+/// only)
+///
+/// The group creator is always an admin and cannot be demoted.
+#[server(SetMemberRole)]
+pub async fn set_member_role(group_id: i64, user_id: i64, role: GroupRole) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group = sqlx::query!("SELECT created_by FROM groups WHERE id = ?", group_id)
+        .fetch_optional(&pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("Group not found"))?;
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can change member roles",
+        ));
+    }
+
+    if user_id == group.created_by && role != GroupRole::Admin {
+        return Err(AppError::validation("The group creator cannot be demoted"));
+    }
+
+    let role = role.as_str();
+    let updated = sqlx::query!(
+        "UPDATE group_members SET role = ? WHERE group_id = ? AND user_id = ?",
+        role,
+        group_id,
+        user_id
+    )
+    .execute(&pool)
+    .await?
+    .rows_affected();
+
+    if updated == 0 {
+        return Err(AppError::not_found("User is not a member of this group"));
+    }
+
+    Ok(())
+}
+
+/// Server function: Delete a group
+#[server(DeleteGroup)]
+pub async fn delete_group(group_id: i64) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can delete the group",
+        ));
+    }
+
+    // Delete the group (CASCADE will handle related data)
+    sqlx::query!("DELETE FROM groups WHERE id = ?", group_id)
+        .execute(&pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Server function: Get all users for member selection
+#[server(GetAllUsers)]
+pub async fn get_all_users() -> Result<Vec<UserSession>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    // Fetch all users except current user
+    let users = sqlx::query_as::<_, UserSession>(
+        "SELECT id, username FROM users WHERE id != ? ORDER BY username",
+    )
+    .bind(user.id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(users)
+}
+
+/// Server function: Get the most recent weekly summaries for a group
+#[server(GetGroupSummaries)]
+pub async fn get_group_summaries(group_id: i64, limit: i64) -> Result<Vec<GroupSummary>, AppError> {
+    use sqlx::SqlitePool;
+
+    use super::models::GroupSummaryMember;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query!(
+        "SELECT COUNT(*) as \"count!\" FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member.count == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    let limit = limit.clamp(1, 52);
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!",
+            group_id as "group_id!",
+            period_start as "period_start!: time::OffsetDateTime",
+            period_end as "period_end!: time::OffsetDateTime",
+            debt_count,
+            total_amount,
+            largest_expense_name,
+            largest_expense_amount,
+            created_at as "created_at!: time::OffsetDateTime"
+        FROM group_summaries
+        WHERE group_id = ?
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+        group_id,
+        limit
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let mut summaries = Vec::with_capacity(rows.len());
+    for row in rows {
+        let members = sqlx::query_as!(
+            GroupSummaryMember,
+            r#"
+            SELECT gsm.user_id, u.username, gsm.net_change
+            FROM group_summary_members gsm
+            JOIN users u ON u.id = gsm.user_id
+            WHERE gsm.summary_id = ?
+            ORDER BY u.username
+            "#,
+            row.id
+        )
+        .fetch_all(&pool)
+        .await?;
+
+        summaries.push(GroupSummary {
+            id: row.id,
+            group_id: row.group_id,
+            period_start: row.period_start,
+            period_end: row.period_end,
+            debt_count: row.debt_count,
+            total_amount: row.total_amount,
+            largest_expense_name: row.largest_expense_name,
+            largest_expense_amount: row.largest_expense_amount,
+            members,
+            created_at: row.created_at,
+        });
+    }
+
+    Ok(summaries)
+}
+
+/// Check an optional budget entry, returning the amount to store
+///
+/// Empty entries mean no budget.
+#[cfg(feature = "ssr")]
+fn validate_budget(budget: Option<&str>) -> Result<Option<String>, AppError> {
+    budget
+        .map(str::trim)
+        .filter(|budget| !budget.is_empty())
+        .map(|budget| validate_money(budget).map(|amount| amount.to_string()))
+        .transpose()
+        .map_err(AppError::from)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+    use crate::validation::rules::is_member_removal_blocked;
+
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('alice', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Trip', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_update_group_row_rejects_stale_version() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        // Two admins opened the edit page at version 0; the first save wins
+        update_group_row(&mut conn, 1, 0, "Ski trip", Some("500"), None, "CHF")
+            .await
+            .unwrap();
+        let err = update_group_row(&mut conn, 1, 0, "Beach trip", None, None, "EUR")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(EDIT_CONFLICT_ERROR));
+
+        let (name, monthly_budget, currency, version): (String, Option<String>, String, i64) =
+            sqlx::query_as(
+                "SELECT name, monthly_budget, currency, version FROM groups WHERE id = 1",
+            )
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(name, "Ski trip");
+        assert_eq!(monthly_budget.as_deref(), Some("500"));
+        assert_eq!(currency, "CHF");
+        assert_eq!(version, 1);
+
+        // Saving again after a reload goes through
+        update_group_row(&mut conn, 1, 1, "Beach trip", None, None, "EUR")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_member_removal_waits_for_open_debts() {
+        let pool = setup().await;
+        let statements = [
+            "INSERT INTO users (username, password_hash) VALUES ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'member'), (1, 3, 'member')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, 1, 'Pizza', '30')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, is_settled) VALUES (1, 1, 1), (1, 2, 1), (1, 3, 0)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-01-01', '2026-02-01')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        let err = check_member_removal(&pool, 1, &[3]).await.unwrap_err();
+        let message = err.to_string();
+        assert!(is_member_removal_blocked(&message));
+        assert!(message.contains("carol: unsettled shares of \"Pizza\"; owes €10"));
+        // bob has nothing open
+        check_member_removal(&pool, 1, &[2]).await.unwrap();
+
+        sqlx::query("UPDATE shared_debt_user SET is_settled = 1 WHERE user_id = 3")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE groups SET balances_cached_at = NULL WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        check_member_removal(&pool, 1, &[3]).await.unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        replace_members(&mut conn, 1, &[1, 2]).await.unwrap();
+        let members: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT user_id, role FROM group_members WHERE group_id = 1 ORDER BY user_id",
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            members,
+            vec![(1, "admin".to_string()), (2, "member".to_string())]
+        );
+        let recurring_users: Vec<i64> = sqlx::query_scalar(
+            "SELECT user_id FROM recurring_debt_user WHERE recurring_debt_id = 1 ORDER BY user_id",
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(recurring_users, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_remove_member_waits_for_settled_balance() {
+        let pool = setup().await;
+        let statements = [
+            "INSERT INTO users (username, password_hash) VALUES ('bob', 'x')",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'member')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, 1, 'Pizza', '20')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, is_settled) VALUES (1, 1, 1), (1, 2, 0)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-01-01', '2026-02-01')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (1, 2)",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+
+        let err = remove_member(&mut conn, 1, 2, false, "€")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("bob still owes €10"));
+        let err = remove_member(&mut conn, 1, 1, false, "€")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("alice is still owed €10"));
+        let still_member: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = 1 AND user_id = 2)",
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        assert!(still_member);
+
+        // bob pays alice back
+        sqlx::query(
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 2, 1, '10')",
+        )
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+        remove_member(&mut conn, 1, 2, false, "€").await.unwrap();
+
+        let members: Vec<i64> =
+            sqlx::query_scalar("SELECT user_id FROM group_members WHERE group_id = 1")
+                .fetch_all(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(members, vec![1]);
+        let recurring_users: Vec<i64> = sqlx::query_scalar(
+            "SELECT user_id FROM recurring_debt_user WHERE recurring_debt_id = 1",
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(recurring_users, vec![1]);
+
+        let err = remove_member(&mut conn, 1, 2, false, "€")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a member"));
+    }
+
+    #[tokio::test]
+    async fn test_added_members_join_opted_in_recurring_debts() {
+        let pool = setup().await;
+        let statements = [
+            "INSERT INTO users (username, password_hash) VALUES ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'member')",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date, auto_include_new_members) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-01-01', '2026-02-01', 1), (1, 1, 'Gym', '40', 'monthly', '2026-01-01', '2026-02-01', 0)",
+            // bob left the rent on purpose and stays off it
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (2, 1), (2, 2)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, recurring_debt_id) VALUES (1, 1, 1, 'Rent', '900', 1)",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1)",
+        ];
+        for statement in statements {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            replace_members(&mut conn, 1, &[1, 2, 3]).await.unwrap();
+        }
+
+        let recurring_users: Vec<(i64, i64, i64)> = sqlx::query_as(
+            "SELECT recurring_debt_id, user_id, weight FROM recurring_debt_user ORDER BY recurring_debt_id, user_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            recurring_users,
+            vec![(1, 1, 1), (1, 3, 1), (2, 1, 1), (2, 2, 1)]
+        );
+        // The rent generated before carol joined is hers to ignore
+        let shared_users: Vec<i64> =
+            sqlx::query_scalar("SELECT user_id FROM shared_debt_user WHERE shared_debt_id = 1")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(shared_users, vec![1]);
+    }
+}
@@ -0,0 +1,172 @@
+//! Everything the group page shows first, in one request
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::GroupOverview;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    feature_flags::handlers::load_feature_flags,
+    groups::{
+        handlers::{
+            RECENT_ACTIVITY_LIMIT, load_group, load_group_activity, load_group_members,
+            load_monthly_spend,
+        },
+        utils::current_utc_month,
+    },
+    recurring_debts::handlers::load_recurring_debts,
+    shared_debts::handlers::load_shared_debt_page,
+    transactions::handlers::{compute_group_balances, load_transaction_page, visible_settlements},
+};
+#[cfg(feature = "ssr")]
+use crate::validation::rules::DEFAULT_PAGE_SIZE;
+
+/// Server function: Load the group page's sections at once
+///
+/// Returns what [`get_group`](super::get_group), the members, the first pages
+/// of shared debts and transactions, the recurring debts, the caller's
+/// balances and settlement plan, the recent activity, this month's spending
+/// and the feature flags would, checking the session and membership only
+/// once.
+#[server(GetGroupOverview)]
+pub async fn get_group_overview(group_id: i64) -> Result<GroupOverview, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    load_group_overview(&pool, group_id, user.id).await
+}
+
+/// Load the overview of a group for `user_id`, who must be a member
+#[cfg(feature = "ssr")]
+async fn load_group_overview(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<GroupOverview, AppError> {
+    let role = sqlx::query_scalar!(
+        "SELECT role FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::forbidden("Not a member of this group"))?;
+
+    let group = load_group(pool, group_id, user_id, &role).await?;
+    let members = load_group_members(pool, group_id).await?;
+    let shared_debts = load_shared_debt_page(
+        pool,
+        group_id,
+        user_id,
+        DEFAULT_PAGE_SIZE,
+        0,
+        None,
+        None,
+        None,
+        false,
+    )
+    .await?;
+    let recurring_debts = load_recurring_debts(pool, group_id, user_id)
+        .await
+        .map_err(AppError::internal)?;
+    let transactions =
+        load_transaction_page(pool, group_id, DEFAULT_PAGE_SIZE, 0, None, None).await?;
+
+    // The own balance and the settlement plan share one computation
+    let balances = compute_group_balances(pool, group_id).await?;
+    let settlement_plan = visible_settlements(
+        &balances,
+        user_id,
+        group.full_balances_visible || group.is_admin,
+    );
+    let my_balance = balances
+        .into_iter()
+        .find(|balance| balance.user_id == user_id)
+        .ok_or_else(|| AppError::not_found("Balance not found"))?;
+
+    let activity = load_group_activity(pool, group_id, RECENT_ACTIVITY_LIMIT).await?;
+    let (year, month) = current_utc_month();
+    let monthly_spend = load_monthly_spend(pool, group_id, year, month).await?;
+    let feature_flags = load_feature_flags(pool, group_id).await?;
+
+    Ok(GroupOverview {
+        group,
+        members,
+        shared_debts,
+        recurring_debts,
+        my_balance,
+        settlement_plan,
+        transactions,
+        activity,
+        monthly_spend,
+        feature_flags,
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use rust_decimal::Decimal;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::features::transactions::balances::add_shared_debt_to_balances;
+
+    #[tokio::test]
+    async fn test_overview_limits_settlements_to_members_view() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x'), ('dave', 'x')",
+            "INSERT INTO groups (name, created_by, full_balances_visible) VALUES ('Flat', 1, 0)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, 1, 'Rent', '90')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+        add_shared_debt_to_balances(&mut conn, 1).await.unwrap();
+        drop(conn);
+
+        // The creator sees the whole plan
+        let overview = load_group_overview(&pool, 1, 1).await.unwrap();
+        assert_eq!(overview.group.name, "Flat");
+        assert_eq!(overview.members.len(), 3);
+        assert_eq!(overview.shared_debts.total_count, 1);
+        assert_eq!(overview.transactions.total_count, 0);
+        assert_eq!(overview.my_balance.user_id, 1);
+        assert_eq!(overview.settlement_plan.len(), 2);
+        assert_eq!(overview.monthly_spend, Decimal::from(90));
+        assert!(overview.activity.is_empty());
+
+        // Other members only see their own payment
+        let overview = load_group_overview(&pool, 1, 2).await.unwrap();
+        assert_eq!(overview.my_balance.username, "bob");
+        let payments: Vec<(i64, i64, Decimal)> = overview
+            .settlement_plan
+            .iter()
+            .map(|s| (s.from_user_id, s.to_user_id, s.amount))
+            .collect();
+        assert_eq!(payments, vec![(2, 1, Decimal::from(30))]);
+
+        let err = load_group_overview(&pool, 1, 4).await.unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+    }
+}
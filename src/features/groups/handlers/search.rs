@@ -0,0 +1,207 @@
+//! Search over the names and descriptions of a group's entities
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::GroupSearchResult;
+#[cfg(feature = "ssr")]
+use crate::features::{auth::utils::get_user_from_session, groups::models::SearchEntity};
+
+/// Most results a search returns
+#[cfg(feature = "ssr")]
+const MAX_SEARCH_RESULTS: i64 = 50;
+
+/// Longest search term accepted
+#[cfg(feature = "ssr")]
+const MAX_QUERY_LENGTH: usize = 100;
+
+/// Server function: Find debts, payments, recurring debts and shopping items
+/// of a group whose name or description contains the query, newest first
+#[server(SearchGroup)]
+pub async fn search_group(
+    group_id: i64,
+    query: String,
+) -> Result<Vec<GroupSearchResult>, AppError> {
+    use sqlx::SqlitePool;
+
+    use crate::validation::sanitize_string;
+
+    let query = sanitize_string(&query);
+    if query.chars().count() > MAX_QUERY_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Search term must be {} characters or less",
+            MAX_QUERY_LENGTH
+        )));
+    }
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    Ok(find_group_entries(&pool, group_id, &query).await?)
+}
+
+/// Case-insensitive substring match over a group's entities, without access
+/// checks
+#[cfg(feature = "ssr")]
+pub async fn find_group_entries(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    query: &str,
+) -> Result<Vec<GroupSearchResult>, sqlx::Error> {
+    use crate::validation::escape_like;
+
+    let pattern = format!("%{}%", escape_like(query));
+    let limit = MAX_SEARCH_RESULTS;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            kind as "kind!: String",
+            id as "id!: i64",
+            list_id as "list_id?: i64",
+            text as "text!: String",
+            created_at as "created_at!: time::OffsetDateTime"
+        FROM (
+            SELECT 'shared_debt' as kind, id, NULL as list_id, name as text, created_at
+            FROM shared_debts
+            WHERE group_id = ? AND name LIKE ? ESCAPE '\'
+            UNION ALL
+            SELECT 'transaction', id, NULL, description, created_at
+            FROM transactions
+            WHERE group_id = ? AND description LIKE ? ESCAPE '\'
+            UNION ALL
+            SELECT 'recurring_debt', id, NULL, name, created_at
+            FROM recurring_debts
+            WHERE group_id = ? AND name LIKE ? ESCAPE '\'
+            UNION ALL
+            SELECT 'shopping_item', i.id, i.shopping_list_id, i.name, i.created_at
+            FROM shopping_list_items i
+            INNER JOIN shopping_lists l ON i.shopping_list_id = l.id
+            WHERE l.group_id = ? AND i.name LIKE ? ESCAPE '\'
+        )
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+        group_id,
+        pattern,
+        group_id,
+        pattern,
+        group_id,
+        pattern,
+        group_id,
+        pattern,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let entity = match (row.kind.as_str(), row.list_id) {
+                ("shared_debt", _) => SearchEntity::SharedDebt,
+                ("transaction", _) => SearchEntity::Transaction,
+                ("recurring_debt", _) => SearchEntity::RecurringDebt,
+                ("shopping_item", Some(list_id)) => SearchEntity::ShoppingItem { list_id },
+                _ => return None,
+            };
+            Some(GroupSearchResult {
+                entity,
+                id: row.id,
+                text: row.text,
+                created_at: row.created_at,
+            })
+        })
+        .collect())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1), ('Other', 1)",
+            "INSERT INTO shared_debts (group_id, created_by, name, amount, created_at) VALUES (1, 1, 'IKEA shelves', '120', '2026-03-02 10:00:00')",
+            "INSERT INTO shared_debts (group_id, created_by, name, amount, created_at) VALUES (2, 1, 'Ikea lamp', '20', '2026-03-03 10:00:00')",
+            "INSERT INTO shared_debts (group_id, created_by, name, amount, created_at) VALUES (1, 1, '100% juice', '4', '2026-03-04 10:00:00')",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount, description, created_at) VALUES (1, 2, 1, '60', 'Half of ikea', '2026-03-05 10:00:00')",
+            "INSERT INTO shopping_lists (group_id, created_by, name) VALUES (1, 1, 'Week')",
+            "INSERT INTO shopping_list_items (shopping_list_id, name, created_at) VALUES (1, 'Ikea meatballs', '2026-03-01 10:00:00')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_across_entities_in_group() {
+        let pool = setup().await;
+
+        let results: Vec<(SearchEntity, String)> = find_group_entries(&pool, 1, "ikea")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| (result.entity, result.text))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                (SearchEntity::Transaction, "Half of ikea".to_string()),
+                (SearchEntity::SharedDebt, "IKEA shelves".to_string()),
+                (
+                    SearchEntity::ShoppingItem { list_id: 1 },
+                    "Ikea meatballs".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_treats_wildcards_literally() {
+        let pool = setup().await;
+
+        let percent = find_group_entries(&pool, 1, "0%").await.unwrap();
+        assert_eq!(percent.len(), 1);
+        assert_eq!(percent[0].text, "100% juice");
+
+        assert!(find_group_entries(&pool, 1, "_").await.unwrap().is_empty());
+    }
+}
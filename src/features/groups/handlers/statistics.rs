@@ -0,0 +1,598 @@
+//! Monthly spending and member contributions of a group
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::groups::models::{GroupStatistics, MemberContributionSummary};
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::models::{CategorySpend, MemberContribution, MonthlySpend},
+    shared_debts::{
+        models::{DebtCategory, RoundingStrategy},
+        utils::member_share_amounts,
+    },
+};
+#[cfg(feature = "ssr")]
+use crate::validation::rules::check_date_range;
+
+/// Longest period the statistics can cover, in months
+pub const MAX_STATISTICS_MONTHS: i64 = 24;
+
+/// Server function: Spending per month, per paying member and per category
+/// over the last `months` calendar months, including the current one
+///
+/// Months run on UTC dates, like the monthly budget.
+#[server(GetGroupStatistics)]
+pub async fn get_group_statistics(group_id: i64, months: i64) -> Result<GroupStatistics, AppError> {
+    use sqlx::SqlitePool;
+
+    use crate::features::groups::utils::current_utc_month;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    let months = months.clamp(1, MAX_STATISTICS_MONTHS) as u32;
+    compute_group_statistics(&pool, group_id, current_utc_month(), months).await
+}
+
+/// Server function: What each member paid, was assigned and paid back in
+/// the group, optionally only counting debts incurred and payments made
+/// between two dates (YYYY-MM-DD, both inclusive)
+#[server(GetMemberContributionSummary)]
+pub async fn get_member_contribution_summary(
+    group_id: i64,
+    #[server(default)] from_date: Option<String>,
+    #[server(default)] to_date: Option<String>,
+) -> Result<Vec<MemberContributionSummary>, AppError> {
+    use sqlx::SqlitePool;
+
+    let (from_date, to_date) =
+        check_date_range(from_date.as_deref(), to_date.as_deref()).map_err(AppError::Validation)?;
+    let from_date = from_date.map(|d| d.to_string());
+    let to_date = to_date.map(|d| d.to_string());
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden("Not a member of this group"));
+    }
+
+    compute_member_contribution_summary(&pool, group_id, from_date.as_deref(), to_date.as_deref())
+        .await
+}
+
+/// Sum amounts concatenated with `group_concat(amount, ' ')`
+///
+/// Amounts are stored as text, so they are summed as decimals here rather
+/// than in SQL.
+#[cfg(feature = "ssr")]
+fn sum_amounts(amounts: &str) -> Result<Decimal, AppError> {
+    amounts
+        .split(' ')
+        .try_fold(Decimal::ZERO, |total, amount| {
+            amount.parse::<Decimal>().map(|amount| total + amount)
+        })
+        .map_err(AppError::internal)
+}
+
+/// The `count` calendar months ending with `last`, oldest first
+#[cfg(feature = "ssr")]
+fn months_ending(last: (i32, u8), count: u32) -> Vec<(i32, u8)> {
+    let (mut year, mut month) = last;
+    let mut months = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        months.push((year, month));
+        if month == 1 {
+            year -= 1;
+            month = 12;
+        } else {
+            month -= 1;
+        }
+    }
+    months.reverse();
+    months
+}
+
+/// Statistics of a group for the `months` calendar months ending with
+/// `last_month`, without access checks
+#[cfg(feature = "ssr")]
+pub async fn compute_group_statistics(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    last_month: (i32, u8),
+    months: u32,
+) -> Result<GroupStatistics, AppError> {
+    use std::collections::HashMap;
+
+    use crate::features::groups::utils::month_bounds;
+
+    let period = months_ending(last_month, months);
+    let (first_year, first_month) = period[0];
+    let (start, _) = month_bounds(first_year, first_month).map_err(AppError::Validation)?;
+    let (_, end) = month_bounds(last_month.0, last_month.1).map_err(AppError::Validation)?;
+    let start = start.to_string();
+    let end = end.to_string();
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            strftime('%Y-%m', sd.incurred_on) as "month!: String",
+            u.id as "payer_id!",
+            u.username,
+            sd.category,
+            group_concat(sd.amount, ' ') as "amounts!: String",
+            COUNT(*) as "debt_count!: i64"
+        FROM shared_debts sd
+        INNER JOIN users u ON u.id = COALESCE(sd.payer_id, sd.created_by)
+        WHERE sd.group_id = ? AND sd.incurred_on >= ? AND sd.incurred_on < ?
+        GROUP BY strftime('%Y-%m', sd.incurred_on), u.id, sd.category
+        "#,
+        group_id,
+        start,
+        end
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Current members show up in the ranking even when they paid nothing
+    let members = sqlx::query!(
+        r#"
+        SELECT u.id as "id!", u.username
+        FROM group_members gm
+        INNER JOIN users u ON u.id = gm.user_id
+        WHERE gm.group_id = ?
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_month: HashMap<String, (Decimal, i64)> = HashMap::new();
+    let mut by_category: HashMap<Option<DebtCategory>, (Decimal, i64)> = HashMap::new();
+    let mut by_member: HashMap<i64, MemberContribution> = members
+        .into_iter()
+        .map(|member| {
+            (
+                member.id,
+                MemberContribution {
+                    user_id: member.id,
+                    username: member.username,
+                    total: Decimal::ZERO,
+                    debt_count: 0,
+                },
+            )
+        })
+        .collect();
+
+    for row in rows {
+        let total = sum_amounts(&row.amounts)?;
+
+        let month = by_month.entry(row.month).or_default();
+        month.0 += total;
+        month.1 += row.debt_count;
+
+        let category =
+            DebtCategory::parse_optional(row.category.as_deref()).map_err(AppError::internal)?;
+        let category = by_category.entry(category).or_default();
+        category.0 += total;
+        category.1 += row.debt_count;
+
+        let member = by_member
+            .entry(row.payer_id)
+            .or_insert_with(|| MemberContribution {
+                user_id: row.payer_id,
+                username: row.username,
+                total: Decimal::ZERO,
+                debt_count: 0,
+            });
+        member.total += total;
+        member.debt_count += row.debt_count;
+    }
+
+    let months = period
+        .into_iter()
+        .map(|(year, month)| {
+            let (total, debt_count) = by_month
+                .get(&format!("{:04}-{:02}", year, month))
+                .copied()
+                .unwrap_or_default();
+            MonthlySpend {
+                year,
+                month,
+                total,
+                debt_count,
+            }
+        })
+        .collect();
+
+    let mut members: Vec<MemberContribution> = by_member.into_values().collect();
+    members.sort_by(|a, b| {
+        b.total
+            .cmp(&a.total)
+            .then_with(|| a.username.cmp(&b.username))
+    });
+
+    // Uncategorized debts come last among equal totals
+    let mut categories: Vec<CategorySpend> = by_category
+        .into_iter()
+        .map(|(category, (total, debt_count))| CategorySpend {
+            category,
+            total,
+            debt_count,
+        })
+        .collect();
+    categories.sort_by(|a, b| {
+        b.total
+            .cmp(&a.total)
+            .then_with(|| a.category.is_none().cmp(&b.category.is_none()))
+            .then_with(|| {
+                a.category
+                    .map(|c| c.as_str())
+                    .cmp(&b.category.map(|c| c.as_str()))
+            })
+    });
+
+    Ok(GroupStatistics {
+        months,
+        members,
+        categories,
+    })
+}
+
+/// Contribution summary of every current member for the approved debts
+/// incurred and undisputed payments made between two dates, without access
+/// checks
+///
+/// Members are ordered by the total they paid, highest first.
+#[cfg(feature = "ssr")]
+pub async fn compute_member_contribution_summary(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    from_date: Option<&str>,
+    to_date: Option<&str>,
+) -> Result<Vec<MemberContributionSummary>, AppError> {
+    use std::collections::HashMap;
+
+    let members = sqlx::query!(
+        r#"
+        SELECT u.id as "id!", u.username
+        FROM group_members gm
+        INNER JOIN users u ON u.id = gm.user_id
+        WHERE gm.group_id = ?
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_member: HashMap<i64, MemberContributionSummary> = members
+        .into_iter()
+        .map(|member| {
+            (
+                member.id,
+                MemberContributionSummary {
+                    user_id: member.id,
+                    username: member.username,
+                    debts_paid: 0,
+                    paid_total: Decimal::ZERO,
+                    share_total: Decimal::ZERO,
+                    payments_sent: Decimal::ZERO,
+                    payments_received: Decimal::ZERO,
+                },
+            )
+        })
+        .collect();
+
+    let paid = sqlx::query!(
+        r#"
+        SELECT
+            COALESCE(sd.payer_id, sd.created_by) as "payer_id!: i64",
+            group_concat(sd.amount, ' ') as "amounts!: String",
+            COUNT(*) as "debt_count!: i64"
+        FROM shared_debts sd
+        WHERE sd.group_id = ? AND sd.status = 'approved'
+          AND (? IS NULL OR sd.incurred_on >= ?)
+          AND (? IS NULL OR sd.incurred_on <= ?)
+        GROUP BY COALESCE(sd.payer_id, sd.created_by)
+        "#,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for row in paid {
+        if let Some(member) = by_member.get_mut(&row.payer_id) {
+            member.debts_paid += row.debt_count;
+            member.paid_total += sum_amounts(&row.amounts)?;
+        }
+    }
+
+    // Equal splits aren't stored, so shares are worked out per debt
+    let strategy = sqlx::query_scalar!(
+        "SELECT rounding_strategy FROM groups WHERE id = ?",
+        group_id
+    )
+    .fetch_one(pool)
+    .await?
+    .parse::<RoundingStrategy>()
+    .map_err(AppError::internal)?;
+    let shares = sqlx::query!(
+        r#"
+        SELECT sd.id as "debt_id!", sd.created_by, sd.amount, sdu.user_id, sdu.share_amount
+        FROM shared_debts sd
+        INNER JOIN shared_debt_user sdu ON sdu.shared_debt_id = sd.id
+        WHERE sd.group_id = ? AND sd.status = 'approved'
+          AND (? IS NULL OR sd.incurred_on >= ?)
+          AND (? IS NULL OR sd.incurred_on <= ?)
+        ORDER BY sd.id, sdu.id
+        "#,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for debt in shares.chunk_by(|a, b| a.debt_id == b.debt_id) {
+        let amount = debt[0]
+            .amount
+            .parse::<Decimal>()
+            .map_err(AppError::internal)?;
+        let participants: Vec<(i64, Option<String>)> = debt
+            .iter()
+            .map(|row| (row.user_id, row.share_amount.clone()))
+            .collect();
+        let shares = member_share_amounts(amount, &participants, strategy, debt[0].created_by);
+        for (user_id, share) in shares {
+            if let Some(member) = by_member.get_mut(&user_id) {
+                member.share_total += share;
+            }
+        }
+    }
+
+    let payments = sqlx::query!(
+        r#"
+        SELECT payer_id, recipient_id, group_concat(amount, ' ') as "amounts!: String"
+        FROM transactions
+        WHERE group_id = ? AND confirmation_status != 'disputed'
+          AND (? IS NULL OR date(created_at) >= ?)
+          AND (? IS NULL OR date(created_at) <= ?)
+        GROUP BY payer_id, recipient_id
+        "#,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for row in payments {
+        let total = sum_amounts(&row.amounts)?;
+        if let Some(member) = by_member.get_mut(&row.payer_id) {
+            member.payments_sent += total;
+        }
+        if let Some(member) = by_member.get_mut(&row.recipient_id) {
+            member.payments_received += total;
+        }
+    }
+
+    let mut summary: Vec<MemberContributionSummary> = by_member
+        .into_values()
+        .map(|mut member| {
+            member.share_total = member.share_total.round_dp(2);
+            member
+        })
+        .collect();
+    summary.sort_by(|a, b| {
+        b.paid_total
+            .cmp(&a.paid_total)
+            .then_with(|| a.username.cmp(&b.username))
+    });
+
+    Ok(summary)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1), ('Other', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, category, created_at) VALUES (1, 1, 1, 'Rent', '900', 'Rent', '2026-01-03 10:00:00')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, created_at) VALUES (1, 1, 2, 'Dinner', '30.10', '2026-01-20 10:00:00')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, category, created_at) VALUES (1, 2, 2, 'Groceries', '45.20', 'Groceries', '2026-03-31 23:59:00')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, created_at) VALUES (1, 1, 1, 'Too old', '5', '2025-12-31 10:00:00')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, created_at) VALUES (2, 1, 1, 'Elsewhere', '7', '2026-02-10 10:00:00')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_contribution_summary_counts_debts_shares_and_payments() {
+        let pool = setup().await;
+        for statement in [
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1), (1, 2), (1, 3), (2, 1), (2, 2)",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount) VALUES (3, 2, '40.20'), (3, 3, '5.00')",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, status, created_at) VALUES (1, 3, 3, 'Proposed', '80', 'pending', '2026-03-02 10:00:00')",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount, confirmation_status, created_at) VALUES (1, 2, 1, '300', 'confirmed', '2026-01-25 10:00:00'), (1, 3, 1, '100.50', 'pending', '2026-02-01 10:00:00'), (1, 3, 1, '20', 'disputed', '2026-02-02 10:00:00'), (1, 3, 2, '4', 'pending', '2026-04-01 10:00:00')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        let summary =
+            compute_member_contribution_summary(&pool, 1, Some("2026-01-01"), Some("2026-03-31"))
+                .await
+                .unwrap();
+        let rows: Vec<(&str, i64, Decimal, Decimal, Decimal, Decimal)> = summary
+            .iter()
+            .map(|member| {
+                (
+                    member.username.as_str(),
+                    member.debts_paid,
+                    member.paid_total,
+                    member.share_total,
+                    member.payments_sent,
+                    member.payments_received,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "alice",
+                    1,
+                    Decimal::from(900),
+                    Decimal::new(31505, 2),
+                    Decimal::ZERO,
+                    Decimal::new(40050, 2),
+                ),
+                (
+                    "bob",
+                    2,
+                    Decimal::new(7530, 2),
+                    Decimal::new(35525, 2),
+                    Decimal::from(300),
+                    Decimal::ZERO,
+                ),
+                (
+                    "carol",
+                    0,
+                    Decimal::ZERO,
+                    Decimal::from(305),
+                    Decimal::new(10050, 2),
+                    Decimal::ZERO,
+                ),
+            ]
+        );
+
+        // Without a range the older debt and the April payment count too
+        let all_time = compute_member_contribution_summary(&pool, 1, None, None)
+            .await
+            .unwrap();
+        assert_eq!(all_time[0].paid_total, Decimal::from(905));
+        assert_eq!(all_time[2].payments_sent, Decimal::new(10450, 2));
+    }
+
+    #[test]
+    fn test_months_ending_crosses_years() {
+        assert_eq!(
+            months_ending((2026, 2), 3),
+            vec![(2025, 12), (2026, 1), (2026, 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_statistics_include_empty_months() {
+        let pool = setup().await;
+
+        let stats = compute_group_statistics(&pool, 1, (2026, 3), 3)
+            .await
+            .unwrap();
+
+        let months: Vec<(u8, Decimal, i64)> = stats
+            .months
+            .iter()
+            .map(|month| (month.month, month.total, month.debt_count))
+            .collect();
+        assert_eq!(
+            months,
+            vec![
+                (1, Decimal::new(93010, 2), 2),
+                (2, Decimal::ZERO, 0),
+                (3, Decimal::new(4520, 2), 1),
+            ]
+        );
+
+        let members: Vec<(&str, Decimal)> = stats
+            .members
+            .iter()
+            .map(|member| (member.username.as_str(), member.total))
+            .collect();
+        assert_eq!(
+            members,
+            vec![
+                ("alice", Decimal::new(900, 0)),
+                ("bob", Decimal::new(7530, 2)),
+                ("carol", Decimal::ZERO),
+            ]
+        );
+        assert_eq!(stats.total(), Decimal::new(97530, 2));
+
+        let categories: Vec<(Option<DebtCategory>, Decimal)> = stats
+            .categories
+            .iter()
+            .map(|category| (category.category, category.total))
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                (Some(DebtCategory::Rent), Decimal::new(900, 0)),
+                (Some(DebtCategory::Groceries), Decimal::new(4520, 2)),
+                (None, Decimal::new(3010, 2)),
+            ]
+        );
+    }
+}
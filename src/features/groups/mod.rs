@@ -1,5 +1,16 @@
+pub mod events;
 pub mod handlers;
 pub mod models;
+pub mod scheduler;
+pub mod utils;
 
 // Re-export commonly used types
-pub use models::{Group, GroupMember, GroupMemberInfo, GroupWithMembers};
+#[cfg(feature = "ssr")]
+pub use events::{GroupEventBroadcaster, broadcast_group_event, create_group_broadcaster};
+pub use models::{
+    ActivityAction, ActivityEntity, CategorySpend, DigestBalance, DigestDebt, DigestRecurring,
+    DigestTransaction, ExportFormat, Group, GroupActivity, GroupDigest, GroupEvent,
+    GroupImportResult, GroupMember, GroupMemberInfo, GroupRole, GroupSearchResult, GroupStatistics,
+    GroupSummary, GroupSummaryMember, GroupWithMembers, IntegrityIssue, IntegrityIssueKind,
+    IntegrityReport, MemberContribution, MonthlySpend, RepairAction, SearchEntity,
+};
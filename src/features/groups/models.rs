@@ -1,7 +1,15 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "ssr")]
 use sqlx::FromRow;
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime};
+
+use crate::features::{
+    feature_flags::FeatureFlags,
+    recurring_debts::models::RecurringDebtWithDetails,
+    shared_debts::models::{DebtCategory, RoundingStrategy, SharedDebtPage},
+    transactions::models::{NetType, SettlementSuggestion, TransactionPage, UserBalance},
+};
 
 /// Group model representing a group in the database
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -10,10 +18,38 @@ pub struct Group {
     pub id: i64,
     pub name: String,
     pub created_by: i64,
+    /// Whether members other than the creator may see every member's balances
+    pub full_balances_visible: bool,
+    /// Whether debts by members who aren't admins wait for an admin's approval
+    #[cfg_attr(feature = "ssr", sqlx(default))]
+    pub require_debt_approval: bool,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
+    /// Whether the requesting user can manage the group
+    #[cfg_attr(feature = "ssr", sqlx(default))]
+    pub is_admin: bool,
+    /// Whether the requesting user may only look at the group
+    #[cfg_attr(feature = "ssr", sqlx(default))]
+    pub is_viewer: bool,
+    /// Monthly spending budget in the group's currency, if it has one
+    #[cfg_attr(feature = "ssr", sqlx(skip))]
+    pub monthly_budget: Option<Decimal>,
+    /// IANA timezone recurring debts are generated in; the server's when unset
+    #[cfg_attr(feature = "ssr", sqlx(default))]
+    pub timezone: Option<String>,
+    /// ISO 4217 code of the currency the group keeps its books in
+    #[cfg_attr(feature = "ssr", sqlx(default))]
+    pub currency: String,
+    /// Who absorbs the cents left over when a debt is split
+    #[cfg_attr(feature = "ssr", sqlx(skip))]
+    pub rounding_strategy: RoundingStrategy,
+    /// Whether the group is frozen: still viewable, but nothing can change
+    #[cfg_attr(feature = "ssr", sqlx(default))]
+    pub is_archived: bool,
+    /// Incremented on every edit; updates must pass the version they loaded
+    pub version: i64,
 }
 
 /// Group member join table entry
@@ -39,13 +75,607 @@ pub struct GroupWithMembers {
     pub updated_at: OffsetDateTime,
     pub member_count: i64,
     pub is_admin: bool,
+    pub is_archived: bool,
+    /// ISO 4217 code of the currency the group keeps its books in
+    pub currency: String,
 }
 
 /// Simple user info for member lists
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "ssr", derive(FromRow))]
 pub struct GroupMemberInfo {
+    /// The member's user id
     pub id: i64,
     pub username: String,
     pub is_creator: bool,
+    /// Whether the member can manage the group; always set for the creator
+    pub is_admin: bool,
+    /// Whether the member may only look at the group
+    #[serde(default)]
+    pub is_viewer: bool,
+    /// When the user joined the group; `None` for users listed as candidates
+    /// who aren't members yet
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub joined_at: Option<OffsetDateTime>,
+}
+
+impl GroupMemberInfo {
+    /// Short join date label, e.g. "Joined Mar 2024"
+    pub fn joined_label(&self) -> Option<String> {
+        self.joined_at.map(|joined_at| {
+            let month = joined_at.month().to_string();
+            format!("Joined {} {}", &month[..3], joined_at.year())
+        })
+    }
+}
+
+/// Everything the group page shows on its first render, loaded at once
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupOverview {
+    pub group: Group,
+    pub members: Vec<GroupMemberInfo>,
+    /// First page of shared debts, newest first
+    pub shared_debts: SharedDebtPage,
+    pub recurring_debts: Vec<RecurringDebtWithDetails>,
+    /// The current user's own balance
+    pub my_balance: UserBalance,
+    /// Settlement payments, limited to the user's own unless they may see
+    /// the full balance matrix
+    pub settlement_plan: Vec<SettlementSuggestion>,
+    /// First page of transactions, newest first
+    pub transactions: TransactionPage,
+    /// Latest activity, newest first
+    pub activity: Vec<GroupActivity>,
+    /// Total of the shared debts created in the current UTC month
+    pub monthly_spend: Decimal,
+    pub feature_flags: FeatureFlags,
+}
+
+/// Role of a member within a group
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupRole {
+    #[default]
+    Member,
+    Admin,
+    /// Sees the group's debts and balances but cannot change anything
+    Viewer,
+}
+
+impl GroupRole {
+    /// Value stored in the `role` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GroupRole::Member => "member",
+            GroupRole::Admin => "admin",
+            GroupRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "member" => Some(GroupRole::Member),
+            "admin" => Some(GroupRole::Admin),
+            "viewer" => Some(GroupRole::Viewer),
+            _ => None,
+        }
+    }
+}
+
+/// File format of a group data export
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+}
+
+impl ExportFormat {
+    /// MIME type of the exported file
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    /// File extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Outcome of importing a group backup
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GroupImportResult {
+    /// The newly created group
+    pub group_id: i64,
+    /// Usernames in the backup without an account on this server
+    pub skipped_users: Vec<String>,
+    /// Debts and transactions left out because they involved skipped users
+    pub skipped_records: usize,
+}
+
+/// Kind of inconsistency a group integrity audit looks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntegrityIssueKind {
+    /// A recurring debt still lists someone who is no longer a member, so
+    /// every generated instance gives them a share
+    RecurringDebtNonMember,
+    /// An unsettled shared debt share belongs to someone who is no longer a
+    /// member
+    SharedDebtShareNonMember,
+    /// An active recurring debt without anyone to split it between
+    EmptyRecurringDebt,
+    /// A payment between two people who are both no longer members
+    TransactionBetweenNonMembers,
+}
+
+impl IntegrityIssueKind {
+    pub const ALL: [IntegrityIssueKind; 4] = [
+        IntegrityIssueKind::RecurringDebtNonMember,
+        IntegrityIssueKind::SharedDebtShareNonMember,
+        IntegrityIssueKind::EmptyRecurringDebt,
+        IntegrityIssueKind::TransactionBetweenNonMembers,
+    ];
+
+    /// Heading for issues of this kind
+    pub fn label(self) -> &'static str {
+        match self {
+            IntegrityIssueKind::RecurringDebtNonMember => "Recurring debts with former members",
+            IntegrityIssueKind::SharedDebtShareNonMember => "Open shares of former members",
+            IntegrityIssueKind::EmptyRecurringDebt => "Recurring debts without members",
+            IntegrityIssueKind::TransactionBetweenNonMembers => "Payments between former members",
+        }
+    }
+
+    /// Repair that fixes issues of this kind, if there is one
+    ///
+    /// Payments are history and are only reported.
+    pub fn repair(self) -> Option<RepairAction> {
+        match self {
+            IntegrityIssueKind::RecurringDebtNonMember => {
+                Some(RepairAction::RemoveRecurringDebtNonMembers)
+            }
+            IntegrityIssueKind::SharedDebtShareNonMember => {
+                Some(RepairAction::RemoveSharedDebtNonMembers)
+            }
+            IntegrityIssueKind::EmptyRecurringDebt => Some(RepairAction::PauseEmptyRecurringDebts),
+            IntegrityIssueKind::TransactionBetweenNonMembers => None,
+        }
+    }
+}
+
+/// One inconsistency found in a group's data
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct IntegrityIssue {
+    pub kind: IntegrityIssueKind,
+    /// Row the issue is about: a pivot row, recurring debt or transaction
+    pub record_id: i64,
+    /// What is wrong, in words, naming the people and debts involved
+    pub description: String,
+}
+
+/// Result of a group integrity audit
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct IntegrityReport {
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Repairs that would fix the reported issues, each listed once
+    pub fn repairs(&self) -> Vec<RepairAction> {
+        let mut repairs: Vec<RepairAction> = Vec::new();
+        for repair in self.issues.iter().filter_map(|issue| issue.kind.repair()) {
+            if !repairs.contains(&repair) {
+                repairs.push(repair);
+            }
+        }
+        repairs
+    }
+}
+
+/// Fix for one kind of integrity issue
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RepairAction {
+    /// Delete recurring debt members who left the group
+    RemoveRecurringDebtNonMembers,
+    /// Delete unsettled shares of people who left the group; settled ones
+    /// stay as history
+    RemoveSharedDebtNonMembers,
+    /// Deactivate recurring debts nobody is left to split
+    PauseEmptyRecurringDebts,
+}
+
+/// Weekly activity recap for a group, generated by the scheduler
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupSummary {
+    pub id: i64,
+    pub group_id: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub period_start: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub period_end: OffsetDateTime,
+    pub debt_count: i64,
+    pub total_amount: String,
+    pub largest_expense_name: Option<String>,
+    pub largest_expense_amount: Option<String>,
+    pub members: Vec<GroupSummaryMember>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Net balance movement of a single member within a summary period
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupSummaryMember {
+    pub user_id: i64,
+    pub username: String,
+    pub net_change: String, // Positive: owed more, negative: owes more
+}
+
+/// Change to a group pushed to open group pages over SSE
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GroupEvent {
+    SharedDebtsChanged,
+    TransactionsChanged,
+}
+
+/// Kind of entity a group activity entry is about
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityEntity {
+    SharedDebt,
+    RecurringDebt,
+    Transaction,
+}
+
+impl ActivityEntity {
+    /// Value stored in the `entity_type` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActivityEntity::SharedDebt => "shared_debt",
+            ActivityEntity::RecurringDebt => "recurring_debt",
+            ActivityEntity::Transaction => "transaction",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "shared_debt" => Some(ActivityEntity::SharedDebt),
+            "recurring_debt" => Some(ActivityEntity::RecurringDebt),
+            "transaction" => Some(ActivityEntity::Transaction),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ActivityEntity::SharedDebt => "debt",
+            ActivityEntity::RecurringDebt => "recurring debt",
+            ActivityEntity::Transaction => "payment",
+        }
+    }
+}
+
+/// What happened to the entity of a group activity entry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityAction {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl ActivityAction {
+    /// Value stored in the `action` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActivityAction::Created => "created",
+            ActivityAction::Updated => "updated",
+            ActivityAction::Deleted => "deleted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "created" => Some(ActivityAction::Created),
+            "updated" => Some(ActivityAction::Updated),
+            "deleted" => Some(ActivityAction::Deleted),
+            _ => None,
+        }
+    }
+
+    fn verb(self) -> &'static str {
+        match self {
+            ActivityAction::Created => "added",
+            ActivityAction::Updated => "edited",
+            ActivityAction::Deleted => "deleted",
+        }
+    }
+}
+
+/// One entry of a group's audit trail
+///
+/// Names are copied into the entry when it is logged, so it still reads the
+/// same after the entity or the actor is gone.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupActivity {
+    pub id: i64,
+    pub group_id: i64,
+    pub actor_username: String,
+    pub entity_type: ActivityEntity,
+    pub entity_id: i64,
+    pub action: ActivityAction,
+    /// Short description of the entity, e.g. "Dinner (€30.00)"
+    pub summary: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl GroupActivity {
+    /// Sentence describing the entry, e.g. "alice added the debt Dinner (€30.00)"
+    pub fn description(&self) -> String {
+        format!(
+            "{} {} the {} {}",
+            self.actor_username,
+            self.action.verb(),
+            self.entity_type.label(),
+            self.summary
+        )
+    }
+}
+
+/// Kind of entity a group search result points to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchEntity {
+    SharedDebt,
+    Transaction,
+    RecurringDebt,
+    ShoppingItem { list_id: i64 },
+}
+
+impl SearchEntity {
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchEntity::SharedDebt => "Debt",
+            SearchEntity::Transaction => "Payment",
+            SearchEntity::RecurringDebt => "Recurring debt",
+            SearchEntity::ShoppingItem { .. } => "Shopping item",
+        }
+    }
+}
+
+/// One match of a search within a group
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupSearchResult {
+    pub entity: SearchEntity,
+    pub id: i64,
+    /// Name or description that matched
+    pub text: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl GroupSearchResult {
+    /// Page to open for the result
+    pub fn href(&self, group_id: i64) -> String {
+        match self.entity {
+            SearchEntity::SharedDebt => format!("/groups/{}/debts/{}/edit", group_id, self.id),
+            SearchEntity::Transaction => {
+                format!("/groups/{}/transactions/{}/edit", group_id, self.id)
+            }
+            SearchEntity::RecurringDebt => {
+                format!("/groups/{}/recurring-debts/{}", group_id, self.id)
+            }
+            SearchEntity::ShoppingItem { list_id } => {
+                format!("/groups/{}/shopping-lists/{}", group_id, list_id)
+            }
+        }
+    }
+}
+
+/// Shared debts created in a group during one calendar month (UTC)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MonthlySpend {
+    pub year: i32,
+    /// 1-12
+    pub month: u8,
+    pub total: Decimal,
+    pub debt_count: i64,
+}
+
+impl MonthlySpend {
+    /// Short label such as "Mar 2026"
+    pub fn label(&self) -> String {
+        let month = time::Month::try_from(self.month)
+            .map(|month| month.to_string())
+            .unwrap_or_default();
+        format!("{} {}", month.get(..3).unwrap_or_default(), self.year)
+    }
+}
+
+/// What one member paid for the group's shared debts
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MemberContribution {
+    pub user_id: i64,
+    pub username: String,
+    pub total: Decimal,
+    pub debt_count: i64,
+}
+
+/// Who starts spending in a group and what they end up owing and paying
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MemberContributionSummary {
+    pub user_id: i64,
+    pub username: String,
+    /// Shared debts the member paid
+    pub debts_paid: i64,
+    /// Total of the shared debts the member paid
+    pub paid_total: Decimal,
+    /// Total of the member's own shares in shared debts
+    pub share_total: Decimal,
+    /// Payments the member made to other members
+    pub payments_sent: Decimal,
+    /// Payments the member received from other members
+    pub payments_received: Decimal,
+}
+
+/// Spending on one category of debts
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CategorySpend {
+    /// `None` for debts without a category
+    pub category: Option<DebtCategory>,
+    pub total: Decimal,
+    pub debt_count: i64,
+}
+
+/// Spending statistics of a group over its last months
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GroupStatistics {
+    /// Every month of the period, oldest first, including months without debts
+    pub months: Vec<MonthlySpend>,
+    /// Members by amount paid, highest first
+    pub members: Vec<MemberContribution>,
+    /// Categories that have debts in the period, highest total first
+    pub categories: Vec<CategorySpend>,
+}
+
+impl GroupStatistics {
+    pub fn total(&self) -> Decimal {
+        self.months.iter().map(|month| month.total).sum()
+    }
+
+    /// Largest monthly total, used to scale the chart
+    pub fn max_monthly_total(&self) -> Decimal {
+        self.months
+            .iter()
+            .map(|month| month.total)
+            .max()
+            .unwrap_or(Decimal::ZERO)
+    }
+}
+
+/// A shared debt added during a digest's period
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DigestDebt {
+    pub name: String,
+    pub amount: Decimal,
+    pub payer_username: String,
+    pub created_on: Date,
+}
+
+/// A payment recorded during a digest's period
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DigestTransaction {
+    pub payer_username: String,
+    pub recipient_username: String,
+    pub amount: Decimal,
+    pub created_on: Date,
+}
+
+/// A member's current net balance in a digest
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DigestBalance {
+    pub username: String,
+    pub net_type: NetType,
+    /// Absolute value of the net balance
+    pub amount: Decimal,
+}
+
+/// A recurring debt instance due to be generated after the digest's period
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DigestRecurring {
+    pub name: String,
+    pub amount: Decimal,
+    pub date: Date,
+}
+
+/// Summary of a group's last weeks, and what comes up in as many weeks
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GroupDigest {
+    pub group_name: String,
+    /// First day of the period, inclusive
+    pub from: Date,
+    /// Last day of the period, inclusive; today
+    pub to: Date,
+    /// Last day the upcoming recurring debts are listed for
+    pub upcoming_until: Date,
+    /// Oldest first
+    pub new_debts: Vec<DigestDebt>,
+    /// Oldest first
+    pub new_transactions: Vec<DigestTransaction>,
+    /// Current members by username
+    pub balances: Vec<DigestBalance>,
+    /// Soonest first
+    pub upcoming_recurring: Vec<DigestRecurring>,
+}
+
+impl GroupDigest {
+    /// Total of the debts added during the period
+    pub fn new_debts_total(&self) -> Decimal {
+        self.new_debts.iter().map(|debt| debt.amount).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{Date, Month, Time};
+
+    use super::*;
+
+    #[test]
+    fn test_joined_label() {
+        let mut member = GroupMemberInfo {
+            id: 1,
+            username: "alice".to_string(),
+            is_creator: false,
+            is_admin: false,
+            is_viewer: false,
+            joined_at: None,
+        };
+        assert_eq!(member.joined_label(), None);
+
+        let date = Date::from_calendar_date(2024, Month::March, 5).unwrap();
+        member.joined_at = Some(date.with_time(Time::MIDNIGHT).assume_utc());
+        assert_eq!(member.joined_label().as_deref(), Some("Joined Mar 2024"));
+    }
+
+    #[test]
+    fn test_monthly_spend_label() {
+        let spend = MonthlySpend {
+            year: 2026,
+            month: 3,
+            total: Decimal::ZERO,
+            debt_count: 0,
+        };
+        assert_eq!(spend.label(), "Mar 2026");
+    }
+
+    #[test]
+    fn test_activity_description() {
+        let activity = GroupActivity {
+            id: 1,
+            group_id: 1,
+            actor_username: "alice".to_string(),
+            entity_type: ActivityEntity::SharedDebt,
+            entity_id: 7,
+            action: ActivityAction::Deleted,
+            summary: "Dinner (€30.00)".to_string(),
+            created_at: OffsetDateTime::UNIX_EPOCH,
+        };
+
+        assert_eq!(
+            activity.description(),
+            "alice deleted the debt Dinner (€30.00)"
+        );
+        for entity in [
+            ActivityEntity::SharedDebt,
+            ActivityEntity::RecurringDebt,
+            ActivityEntity::Transaction,
+        ] {
+            assert_eq!(ActivityEntity::parse(entity.as_str()), Some(entity));
+        }
+    }
 }
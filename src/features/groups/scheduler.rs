@@ -0,0 +1,245 @@
+//! Weekly group summary generation
+//!
+//! Called from the cron job in `main.rs`; takes the pool directly so it can be
+//! run without a request context.
+
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::{models::RoundingStrategy, utils::member_share_amounts};
+
+/// Credit the creator of a shared debt and debit every other participant by
+/// their share
+#[cfg(feature = "ssr")]
+fn apply_shared_debt(
+    movements: &mut HashMap<i64, Decimal>,
+    creator_id: i64,
+    shares: &[(i64, Decimal)],
+) {
+    for &(user_id, share) in shares {
+        if user_id == creator_id {
+            continue;
+        }
+        *movements.entry(user_id).or_insert(Decimal::ZERO) -= share;
+        *movements.entry(creator_id).or_insert(Decimal::ZERO) += share;
+    }
+}
+
+/// A payment moves the payer's balance up and the recipient's down
+#[cfg(feature = "ssr")]
+fn apply_transaction(
+    movements: &mut HashMap<i64, Decimal>,
+    payer_id: i64,
+    recipient_id: i64,
+    amount: Decimal,
+) {
+    *movements.entry(payer_id).or_insert(Decimal::ZERO) += amount;
+    *movements.entry(recipient_id).or_insert(Decimal::ZERO) -= amount;
+}
+
+/// Generate a summary for every group with new debts or transactions in the
+/// past 7 days. Returns the number of summaries created.
+#[cfg(feature = "ssr")]
+pub async fn generate_weekly_summaries_internal(
+    pool: sqlx::SqlitePool,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    use std::str::FromStr;
+
+    // Use SQLite's clock so the bounds compare cleanly against CURRENT_TIMESTAMP columns
+    let period = sqlx::query!(
+        r#"SELECT datetime('now', '-7 days') as "start!: String", datetime('now') as "end!: String""#
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    let group_ids = sqlx::query_scalar!(
+        r#"
+        SELECT group_id as "group_id!" FROM shared_debts WHERE created_at >= ? AND created_at < ?
+        UNION
+        SELECT group_id as "group_id!" FROM transactions WHERE created_at >= ? AND created_at < ?
+        "#,
+        period.start,
+        period.end,
+        period.start,
+        period.end
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    let mut created = 0;
+
+    for group_id in group_ids {
+        let members = sqlx::query_scalar!(
+            "SELECT user_id FROM group_members WHERE group_id = ?",
+            group_id
+        )
+        .fetch_all(&pool)
+        .await?;
+
+        let mut movements: HashMap<i64, Decimal> =
+            members.iter().map(|&id| (id, Decimal::ZERO)).collect();
+        let strategy = sqlx::query_scalar!(
+            "SELECT rounding_strategy FROM groups WHERE id = ?",
+            group_id
+        )
+        .fetch_one(&pool)
+        .await?
+        .parse::<RoundingStrategy>()?;
+
+        let debts = sqlx::query!(
+            r#"
+            SELECT
+                id as "id!",
+                COALESCE(payer_id, created_by) as "payer_id!: i64",
+                created_by,
+                name,
+                amount
+            FROM shared_debts
+            WHERE group_id = ? AND created_at >= ? AND created_at < ?
+            "#,
+            group_id,
+            period.start,
+            period.end
+        )
+        .fetch_all(&pool)
+        .await?;
+
+        let mut total_amount = Decimal::ZERO;
+        let mut largest: Option<(String, Decimal)> = None;
+
+        for debt in &debts {
+            let amount = Decimal::from_str(&debt.amount)?;
+            total_amount += amount;
+            if largest.as_ref().is_none_or(|(_, max)| amount > *max) {
+                largest = Some((debt.name.clone(), amount));
+            }
+
+            let participants: Vec<(i64, Option<String>)> = sqlx::query!(
+                "SELECT user_id, share_amount FROM shared_debt_user WHERE shared_debt_id = ?",
+                debt.id
+            )
+            .fetch_all(&pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.user_id, row.share_amount))
+            .collect();
+
+            apply_shared_debt(
+                &mut movements,
+                debt.payer_id,
+                &member_share_amounts(amount, &participants, strategy, debt.created_by),
+            );
+        }
+
+        let transactions = sqlx::query!(
+            r#"
+            SELECT payer_id as "payer_id!", recipient_id as "recipient_id!", amount
+            FROM transactions
+            WHERE group_id = ? AND created_at >= ? AND created_at < ?
+            "#,
+            group_id,
+            period.start,
+            period.end
+        )
+        .fetch_all(&pool)
+        .await?;
+
+        for transaction in &transactions {
+            let amount = Decimal::from_str(&transaction.amount)?;
+            apply_transaction(
+                &mut movements,
+                transaction.payer_id,
+                transaction.recipient_id,
+                amount,
+            );
+        }
+
+        let debt_count = debts.len() as i64;
+        let total_amount = total_amount.round_dp(2).to_string();
+        let (largest_name, largest_amount) = match largest {
+            Some((name, amount)) => (Some(name), Some(amount.round_dp(2).to_string())),
+            None => (None, None),
+        };
+
+        let mut tx = pool.begin().await?;
+
+        let summary_id = sqlx::query!(
+            r#"
+            INSERT INTO group_summaries
+                (group_id, period_start, period_end, debt_count, total_amount, largest_expense_name, largest_expense_amount)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            group_id,
+            period.start,
+            period.end,
+            debt_count,
+            total_amount,
+            largest_name,
+            largest_amount
+        )
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        // Only current members are listed; former members' movements are dropped
+        for user_id in &members {
+            let net_change = movements
+                .get(user_id)
+                .copied()
+                .unwrap_or(Decimal::ZERO)
+                .round_dp(2)
+                .to_string();
+            sqlx::query!(
+                "INSERT INTO group_summary_members (summary_id, user_id, net_change) VALUES (?, ?, ?)",
+                summary_id,
+                user_id,
+                net_change
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_debt_movement() {
+        let mut movements = HashMap::new();
+        let shares = [
+            (1, Decimal::from(10)),
+            (2, Decimal::from(10)),
+            (3, Decimal::from(10)),
+        ];
+        apply_shared_debt(&mut movements, 1, &shares);
+
+        assert_eq!(movements[&1], Decimal::from(20));
+        assert_eq!(movements[&2], Decimal::from(-10));
+        assert_eq!(movements[&3], Decimal::from(-10));
+    }
+
+    #[test]
+    fn test_transaction_offsets_shared_debt() {
+        let mut movements = HashMap::new();
+        apply_shared_debt(
+            &mut movements,
+            1,
+            &[(1, Decimal::from(10)), (2, Decimal::from(10))],
+        );
+        apply_transaction(&mut movements, 2, 1, Decimal::from(10));
+
+        assert_eq!(movements[&1], Decimal::ZERO);
+        assert_eq!(movements[&2], Decimal::ZERO);
+    }
+}
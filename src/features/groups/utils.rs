@@ -0,0 +1,554 @@
+#[cfg(feature = "ssr")]
+use super::models::{ActivityAction, ActivityEntity, GroupRole};
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::DebtStatus;
+#[cfg(feature = "ssr")]
+use crate::validation::rules::{GROUP_ARCHIVED, VIEWER_READ_ONLY};
+
+/// Record a change to one of a group's entities in its activity log
+///
+/// The actor's username is copied into the entry, so pass an executor that is
+/// part of the surrounding transaction when there is one.
+#[cfg(feature = "ssr")]
+pub async fn log_group_activity(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+    actor_id: i64,
+    entity_type: ActivityEntity,
+    entity_id: i64,
+    action: ActivityAction,
+    summary: &str,
+) -> Result<(), AppError> {
+    let entity_type = entity_type.as_str();
+    let action = action.as_str();
+    sqlx::query!(
+        r#"
+        INSERT INTO group_activity
+        (group_id, actor_id, actor_username, entity_type, entity_id, action, summary)
+        SELECT ?, id, username, ?, ?, ?, ?
+        FROM users
+        WHERE id = ?
+        "#,
+        group_id,
+        entity_type,
+        entity_id,
+        action,
+        summary,
+        actor_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Whether a user may manage a group: its creator or a member promoted to
+/// admin
+#[cfg(feature = "ssr")]
+pub async fn is_group_admin(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<bool, AppError> {
+    let count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM group_members gm
+        INNER JOIN groups g ON g.id = gm.group_id
+        WHERE gm.group_id = ? AND gm.user_id = ?
+          AND (gm.role = 'admin' OR g.created_by = gm.user_id)
+        "#,
+        group_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
+
+/// Reject changes to archived groups and by viewers, who may look at a
+/// group but not change anything in it
+///
+/// Call before changing anything in the group on behalf of `user_id`. Users
+/// outside the group and missing groups pass, so callers still check
+/// membership themselves.
+#[cfg(feature = "ssr")]
+pub async fn ensure_group_writable(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+    user_id: i64,
+) -> Result<(), AppError> {
+    let row = sqlx::query!(
+        r#"
+        SELECT g.is_archived as "is_archived!: bool", gm.role as "role?"
+        FROM groups g
+        LEFT JOIN group_members gm ON gm.group_id = g.id AND gm.user_id = ?
+        WHERE g.id = ?
+        "#,
+        user_id,
+        group_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(());
+    };
+    if row.is_archived {
+        return Err(AppError::conflict(GROUP_ARCHIVED));
+    }
+    if row.role.as_deref().and_then(GroupRole::parse) == Some(GroupRole::Viewer) {
+        return Err(AppError::forbidden(VIEWER_READ_ONLY));
+    }
+    Ok(())
+}
+
+/// Reject changes to an archived group, whoever makes them
+#[cfg(feature = "ssr")]
+pub async fn ensure_not_archived(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+) -> Result<(), AppError> {
+    let is_archived = sqlx::query_scalar!(
+        r#"SELECT is_archived as "is_archived!: bool" FROM groups WHERE id = ?"#,
+        group_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    if is_archived == Some(true) {
+        return Err(AppError::conflict(GROUP_ARCHIVED));
+    }
+    Ok(())
+}
+
+/// Status a debt added or edited by `user_id` starts in
+///
+/// In groups that require approval, debts by members who aren't admins wait
+/// for an admin; everyone else's count right away.
+#[cfg(feature = "ssr")]
+pub async fn proposed_debt_status(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+    user_id: i64,
+) -> Result<DebtStatus, sqlx::Error> {
+    let needs_approval = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM groups g
+        LEFT JOIN group_members gm ON gm.group_id = g.id AND gm.user_id = ?
+        WHERE g.id = ? AND g.require_debt_approval = 1
+          AND g.created_by != ? AND COALESCE(gm.role, '') != 'admin'
+        "#,
+        user_id,
+        group_id,
+        user_id
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(if needs_approval > 0 {
+        DebtStatus::Pending
+    } else {
+        DebtStatus::Approved
+    })
+}
+
+/// Forget a group as the default of users who are no longer its members
+///
+/// Call after removing members, with the executor of the same transaction.
+#[cfg(feature = "ssr")]
+pub async fn clear_stale_default_groups(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        UPDATE users SET default_group_id = NULL
+        WHERE default_group_id = ?
+          AND id NOT IN (SELECT user_id FROM group_members WHERE group_id = ?)
+        "#,
+        group_id,
+        group_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Activity summary of a debt in a group keeping its books in `currency`,
+/// e.g. "Dinner (€30.00)"
+#[cfg(feature = "ssr")]
+pub fn debt_summary(name: &str, amount: rust_decimal::Decimal, currency: &str) -> String {
+    format!(
+        "{} ({}{:.2})",
+        name,
+        currency_symbol(currency),
+        amount.round_dp(2)
+    )
+}
+
+/// Activity summary of a payment in a group keeping its books in
+/// `currency`, e.g. "€20.00 to bob"
+#[cfg(feature = "ssr")]
+pub fn payment_summary(amount: rust_decimal::Decimal, recipient: &str, currency: &str) -> String {
+    format!(
+        "{}{:.2} to {}",
+        currency_symbol(currency),
+        amount.round_dp(2),
+        recipient
+    )
+}
+
+/// First day of a month and of the month after it, for half-open range
+/// queries
+#[cfg(feature = "ssr")]
+pub fn month_bounds(year: i32, month: u8) -> Result<(time::Date, time::Date), String> {
+    let month = time::Month::try_from(month).map_err(|_| "Invalid month".to_string())?;
+    let start =
+        time::Date::from_calendar_date(year, month, 1).map_err(|_| "Invalid year".to_string())?;
+    let next_year = if month == time::Month::December {
+        year + 1
+    } else {
+        year
+    };
+    let end = time::Date::from_calendar_date(next_year, month.next(), 1)
+        .map_err(|_| "Invalid year".to_string())?;
+    Ok((start, end))
+}
+
+/// Timezones offered when creating or editing a group
+pub const GROUP_TIMEZONES: [&str; 24] = [
+    "Pacific/Honolulu",
+    "America/Anchorage",
+    "America/Los_Angeles",
+    "America/Denver",
+    "America/Chicago",
+    "America/New_York",
+    "America/Sao_Paulo",
+    "Atlantic/Azores",
+    "Europe/London",
+    "Europe/Lisbon",
+    "Europe/Berlin",
+    "Europe/Paris",
+    "Europe/Athens",
+    "Europe/Istanbul",
+    "Africa/Johannesburg",
+    "Asia/Dubai",
+    "Asia/Kolkata",
+    "Asia/Bangkok",
+    "Asia/Singapore",
+    "Asia/Shanghai",
+    "Asia/Tokyo",
+    "Australia/Sydney",
+    "Pacific/Auckland",
+    "UTC",
+];
+
+/// Currencies a group can keep its books in, as ISO 4217 codes with the
+/// symbol amounts are shown with
+pub const GROUP_CURRENCIES: [(&str, &str); 12] = [
+    ("EUR", "€"),
+    ("USD", "$"),
+    ("GBP", "£"),
+    ("CHF", "CHF "),
+    ("SEK", "SEK "),
+    ("NOK", "NOK "),
+    ("DKK", "DKK "),
+    ("PLN", "zł"),
+    ("CZK", "Kč"),
+    ("JPY", "¥"),
+    ("CAD", "CA$"),
+    ("AUD", "A$"),
+];
+
+/// Currency groups keep their books in unless they choose another
+pub const DEFAULT_CURRENCY: &str = "EUR";
+
+/// Symbol amounts in a currency are shown with; the euro sign for unknown
+/// codes
+pub fn currency_symbol(code: &str) -> &'static str {
+    GROUP_CURRENCIES
+        .into_iter()
+        .find(|(known, _)| *known == code)
+        .map_or(crate::components::EURO, |(_, symbol)| symbol)
+}
+
+/// Check a group currency code such as "usd" and return it in capitals;
+/// euros when omitted or empty
+#[cfg(feature = "ssr")]
+pub fn check_group_currency(value: Option<&str>) -> Result<String, String> {
+    use crate::validation::rules::check_currency_code;
+
+    match value.map(str::trim) {
+        None | Some("") => Ok(DEFAULT_CURRENCY.to_string()),
+        Some(code) => {
+            let code = check_currency_code(code)?;
+            if GROUP_CURRENCIES.iter().any(|(known, _)| *known == code) {
+                Ok(code)
+            } else {
+                Err(format!("Unsupported currency: {}", code))
+            }
+        }
+    }
+}
+
+/// ISO 4217 code of the currency a group keeps its books in
+#[cfg(feature = "ssr")]
+pub async fn group_currency(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+) -> Result<String, AppError> {
+    sqlx::query_scalar!("SELECT currency FROM groups WHERE id = ?", group_id)
+        .fetch_optional(executor)
+        .await?
+        .ok_or_else(|| AppError::not_found("Group not found"))
+}
+
+/// Current year and month (1-12) in UTC
+pub fn current_utc_month() -> (i32, u8) {
+    #[cfg(feature = "hydrate")]
+    {
+        let now = js_sys::Date::new_0();
+        (
+            now.get_utc_full_year() as i32,
+            now.get_utc_month() as u8 + 1,
+        )
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        let now = time::OffsetDateTime::now_utc();
+        (now.year(), now.month() as u8)
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use rust_decimal::Decimal;
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for username in ["alice", "bob"] {
+            sqlx::query("INSERT INTO users (username, password_hash) VALUES (?, 'x')")
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Flat', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_ensure_group_writable() {
+        let pool = setup().await;
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('carol', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'member'), (1, 3, 'viewer')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        ensure_group_writable(&pool, 1, 1).await.unwrap();
+        ensure_group_writable(&pool, 1, 2).await.unwrap();
+        assert_eq!(
+            ensure_group_writable(&pool, 1, 3).await.unwrap_err(),
+            AppError::forbidden(VIEWER_READ_ONLY)
+        );
+        // Membership is left to the callers
+        ensure_group_writable(&pool, 2, 3).await.unwrap();
+        ensure_not_archived(&pool, 1).await.unwrap();
+
+        // Once archived, not even admins may change the group
+        sqlx::query("UPDATE groups SET is_archived = 1 WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for user_id in [1, 2, 3] {
+            assert_eq!(
+                ensure_group_writable(&pool, 1, user_id).await.unwrap_err(),
+                AppError::conflict(GROUP_ARCHIVED)
+            );
+        }
+        assert_eq!(
+            ensure_not_archived(&pool, 1).await.unwrap_err(),
+            AppError::conflict(GROUP_ARCHIVED)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_proposed_debt_status() {
+        let pool = setup().await;
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('carol', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'member'), (1, 2, 'member'), (1, 3, 'admin')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for user_id in [1, 2, 3] {
+            assert_eq!(
+                proposed_debt_status(&pool, 1, user_id).await.unwrap(),
+                DebtStatus::Approved
+            );
+        }
+
+        // Once required, only the creator's and admins' debts skip approval
+        sqlx::query("UPDATE groups SET require_debt_approval = 1 WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let statuses = [
+            proposed_debt_status(&pool, 1, 1).await.unwrap(),
+            proposed_debt_status(&pool, 1, 2).await.unwrap(),
+            proposed_debt_status(&pool, 1, 3).await.unwrap(),
+        ];
+        assert_eq!(
+            statuses,
+            [
+                DebtStatus::Approved,
+                DebtStatus::Pending,
+                DebtStatus::Approved
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_activity_outlives_entity_and_actor() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 2, 2, 'Dinner', '30')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        log_group_activity(
+            &pool,
+            1,
+            2,
+            ActivityEntity::SharedDebt,
+            1,
+            ActivityAction::Created,
+            &debt_summary("Dinner", Decimal::from(30), "EUR"),
+        )
+        .await
+        .unwrap();
+
+        sqlx::query("DELETE FROM shared_debts WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM users WHERE id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (actor, summary): (String, String) =
+            sqlx::query_as("SELECT actor_username, summary FROM group_activity")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(actor, "bob");
+        assert_eq!(summary, "Dinner (€30.00)");
+    }
+
+    #[tokio::test]
+    async fn test_is_group_admin() {
+        let pool = setup().await;
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('carol', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // The creator counts as admin even without the role being set
+        sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(is_group_admin(&pool, 1, 1).await.unwrap());
+        assert!(!is_group_admin(&pool, 1, 2).await.unwrap());
+        assert!(!is_group_admin(&pool, 1, 3).await.unwrap());
+
+        sqlx::query("UPDATE group_members SET role = 'admin' WHERE user_id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(is_group_admin(&pool, 1, 2).await.unwrap());
+    }
+
+    #[test]
+    fn test_month_bounds() {
+        use time::{Date, Month};
+
+        assert_eq!(
+            month_bounds(2026, 2),
+            Ok((
+                Date::from_calendar_date(2026, Month::February, 1).unwrap(),
+                Date::from_calendar_date(2026, Month::March, 1).unwrap(),
+            ))
+        );
+        assert_eq!(
+            month_bounds(2025, 12),
+            Ok((
+                Date::from_calendar_date(2025, Month::December, 1).unwrap(),
+                Date::from_calendar_date(2026, Month::January, 1).unwrap(),
+            ))
+        );
+        assert!(month_bounds(2026, 0).is_err());
+        assert!(month_bounds(2026, 13).is_err());
+    }
+
+    #[test]
+    fn test_check_group_currency() {
+        assert_eq!(check_group_currency(None), Ok("EUR".to_string()));
+        assert_eq!(check_group_currency(Some(" ")), Ok("EUR".to_string()));
+        assert_eq!(check_group_currency(Some(" usd ")), Ok("USD".to_string()));
+        assert!(check_group_currency(Some("US")).is_err());
+        assert_eq!(
+            check_group_currency(Some("XYZ")),
+            Err("Unsupported currency: XYZ".to_string())
+        );
+
+        assert_eq!(currency_symbol("GBP"), "£");
+        assert_eq!(currency_symbol("XYZ"), "€");
+    }
+
+    #[test]
+    fn test_summaries_use_group_currency() {
+        assert_eq!(
+            debt_summary("Dinner", Decimal::from(30), "EUR"),
+            "Dinner (€30.00)"
+        );
+        assert_eq!(
+            payment_summary(Decimal::new(2050, 2), "bob", "GBP"),
+            "£20.50 to bob"
+        );
+    }
+}
@@ -4,15 +4,33 @@ use leptos_axum::extract;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
-use super::models::{InviteListItem, InviteWithGroup};
+use super::models::{
+    InviteAcceptance, InviteListItem, InvitePreview, InviteRedemptions, InviteWithGroup,
+};
 #[cfg(feature = "ssr")]
-use super::utils::{calculate_expiration, generate_invite_uuid, is_invite_valid};
+use super::models::{InviteRedemption, InviteStatus};
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
-
-/// Server function: Get all invites for a group
+use super::utils::{
+    MAX_INVITE_USES, calculate_expiration, claim_invite_use, generate_invite_uuid, invite_url,
+    is_invite_used_up, is_invite_valid, render_qr_svg,
+};
+use crate::features::groups::models::GroupRole;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::utils::{ensure_not_archived, is_group_admin},
+    recurring_debts::handlers::include_new_member_in_recurring_debts,
+};
+
+/// Server function: Get the invites for a group
+///
+/// Expired and used-up invites are only returned (flagged via `is_expired`)
+/// when `include_expired` is set.
 #[server(GetGroupInvites)]
-pub async fn get_group_invites(group_id: i64) -> Result<Vec<InviteListItem>, ServerFnError> {
+pub async fn get_group_invites(
+    group_id: i64,
+    include_expired: bool,
+) -> Result<Vec<InviteListItem>, ServerFnError> {
     use sqlx::SqlitePool;
     use time::format_description;
 
@@ -26,16 +44,16 @@ pub async fn get_group_invites(group_id: i64) -> Result<Vec<InviteListItem>, Ser
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check if user is the group creator
-    let group = sqlx::query!("SELECT created_by, name FROM groups WHERE id = ?", group_id)
+    // Check if user is a group admin
+    sqlx::query!("SELECT id FROM groups WHERE id = ?", group_id)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ServerFnError::new(e.to_string()))?
         .ok_or_else(|| ServerFnError::new("Group not found"))?;
 
-    if group.created_by != user.id {
+    if !is_group_admin(&pool, group_id, user.id).await? {
         return Err(ServerFnError::new(
-            "Unauthorized: Only the group creator can view invites",
+            "Unauthorized: Only group admins can view invites",
         ));
     }
 
@@ -45,6 +63,9 @@ pub async fn get_group_invites(group_id: i64) -> Result<Vec<InviteListItem>, Ser
         SELECT uuid, group_id, name, 
                CASE WHEN is_reusable = 1 THEN true ELSE false END as "is_reusable!: bool",
                duration_days, 
+               max_uses,
+               use_count,
+               grants_role,
                created_at, 
                updated_at
         FROM invites 
@@ -61,21 +82,31 @@ pub async fn get_group_invites(group_id: i64) -> Result<Vec<InviteListItem>, Ser
     let format = format_description::parse("[month repr:long] [day], [year]")
         .map_err(|e| ServerFnError::new(e.to_string()))?;
 
-    // Convert to InviteListItem
+    // Convert to InviteListItem, skipping expired invites unless requested
     let invite_list = invites
         .into_iter()
-        .map(|inv| {
+        .filter_map(|inv| {
+            let is_expired = !is_invite_valid(&inv.created_at, inv.duration_days)
+                || is_invite_used_up(inv.max_uses, inv.use_count);
+            if is_expired && !include_expired {
+                return None;
+            }
+
             let expires_at = calculate_expiration(&inv.created_at, inv.duration_days);
             let expiration_date = expires_at
                 .format(&format)
                 .unwrap_or_else(|_| "Invalid date".to_string());
 
-            InviteListItem {
+            Some(InviteListItem {
                 uuid: inv.uuid,
                 name: inv.name,
                 is_reusable: inv.is_reusable,
                 expiration_date,
-            }
+                is_expired,
+                max_uses: inv.max_uses,
+                use_count: inv.use_count,
+                grants_role: GroupRole::parse(&inv.grants_role).unwrap_or_default(),
+            })
         })
         .collect();
 
@@ -89,14 +120,38 @@ pub async fn create_invite(
     name: Option<String>,
     is_reusable: bool,
     duration_days: i64,
+    /// Most times a reusable invite can be accepted; unlimited when omitted
+    #[server(default)]
+    max_uses: Option<i64>,
+    /// Role whoever accepts gets; plain members when omitted
+    #[server(default)]
+    grants_role: GroupRole,
 ) -> Result<String, ServerFnError> {
     use sqlx::SqlitePool;
 
+    // Admins are promoted by hand, never through a link
+    if grants_role == GroupRole::Admin {
+        return Err(ServerFnError::new(
+            "Invites can only make members or viewers",
+        ));
+    }
+
     // Validate duration_days
     if !(1..=30).contains(&duration_days) {
         return Err(ServerFnError::new("Duration must be between 1 and 30 days"));
     }
 
+    // Single-use invites always allow exactly one use
+    let max_uses = if is_reusable { max_uses } else { Some(1) };
+    if let Some(max_uses) = max_uses
+        && !(1..=MAX_INVITE_USES).contains(&max_uses)
+    {
+        return Err(ServerFnError::new(format!(
+            "Max uses must be between 1 and {}",
+            MAX_INVITE_USES
+        )));
+    }
+
     // Validate name length if provided
     if let Some(ref n) = name
         && n.len() > 128
@@ -114,31 +169,36 @@ pub async fn create_invite(
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check if user is the group creator
-    let group = sqlx::query!("SELECT created_by FROM groups WHERE id = ?", group_id)
+    // Check if user is a group admin
+    sqlx::query!("SELECT id FROM groups WHERE id = ?", group_id)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ServerFnError::new(e.to_string()))?
         .ok_or_else(|| ServerFnError::new("Group not found"))?;
 
-    if group.created_by != user.id {
+    if !is_group_admin(&pool, group_id, user.id).await? {
         return Err(ServerFnError::new(
-            "Unauthorized: Only the group creator can create invites",
+            "Unauthorized: Only group admins can create invites",
         ));
     }
+    ensure_not_archived(&pool, group_id).await?;
 
     // Generate UUID
     let uuid = generate_invite_uuid();
 
     // Insert invite
     let is_reusable_int = i32::from(is_reusable);
+    let grants_role = grants_role.as_str();
     sqlx::query!(
-        "INSERT INTO invites (uuid, group_id, name, is_reusable, duration_days) VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO invites (uuid, group_id, name, is_reusable, duration_days, max_uses, grants_role, created_by) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         uuid,
         group_id,
         name,
         is_reusable_int,
-        duration_days
+        duration_days,
+        max_uses,
+        grants_role,
+        user.id
     )
     .execute(&pool)
     .await
@@ -161,6 +221,9 @@ pub async fn get_invite_by_uuid(uuid: String) -> Result<InviteWithGroup, ServerF
             i.uuid, i.group_id, i.name, 
             CASE WHEN i.is_reusable = 1 THEN true ELSE false END as "is_reusable!: bool",
             i.duration_days, 
+            i.max_uses,
+            i.use_count,
+            i.grants_role,
             i.created_at, 
             i.updated_at,
             g.name as group_name
@@ -175,7 +238,8 @@ pub async fn get_invite_by_uuid(uuid: String) -> Result<InviteWithGroup, ServerF
     .map_err(|e| ServerFnError::new(e.to_string()))?
     .ok_or_else(|| ServerFnError::new("Invite not found"))?;
 
-    let is_valid = is_invite_valid(&invite.created_at, invite.duration_days);
+    let is_valid = is_invite_valid(&invite.created_at, invite.duration_days)
+        && !is_invite_used_up(invite.max_uses, invite.use_count);
     let expires_at = calculate_expiration(&invite.created_at, invite.duration_days);
 
     Ok(InviteWithGroup {
@@ -185,15 +249,86 @@ pub async fn get_invite_by_uuid(uuid: String) -> Result<InviteWithGroup, ServerF
         name: invite.name,
         is_reusable: invite.is_reusable,
         duration_days: invite.duration_days,
+        grants_role: GroupRole::parse(&invite.grants_role).unwrap_or_default(),
         created_at: invite.created_at,
         is_valid,
         expires_at,
     })
 }
 
+/// Server function: Preview the group an invite leads to
+///
+/// Needs no login, so it only tells the group's name, its size and who sent
+/// the invite.
+#[server(GetInvitePreview)]
+pub async fn get_invite_preview(uuid: String) -> Result<InvitePreview, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let pool = expect_context::<SqlitePool>();
+
+    // Anyone can call this, so database errors are logged rather than shown
+    load_invite_preview(&pool, &uuid)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load invite preview");
+            ServerFnError::new("Could not load invite")
+        })?
+        .ok_or_else(|| ServerFnError::new("Invite not found"))
+}
+
+/// Preview of an invite, `None` if there is no such invite
+#[cfg(feature = "ssr")]
+async fn load_invite_preview(
+    pool: &sqlx::SqlitePool,
+    uuid: &str,
+) -> Result<Option<InvitePreview>, sqlx::Error> {
+    let Some(invite) = sqlx::query!(
+        r#"
+        SELECT
+            g.name as group_name,
+            (SELECT COUNT(*) FROM group_members gm WHERE gm.group_id = i.group_id) as "member_count!: i64",
+            u.username as "inviter?",
+            i.grants_role,
+            i.duration_days,
+            i.max_uses,
+            i.use_count,
+            i.created_at
+        FROM invites i
+        INNER JOIN groups g ON i.group_id = g.id
+        LEFT JOIN users u ON i.created_by = u.id
+        WHERE i.uuid = ?
+        "#,
+        uuid
+    )
+    .fetch_optional(pool)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let status = if !is_invite_valid(&invite.created_at, invite.duration_days) {
+        InviteStatus::Expired
+    } else if is_invite_used_up(invite.max_uses, invite.use_count) {
+        InviteStatus::UsedUp
+    } else {
+        InviteStatus::Valid
+    };
+
+    Ok(Some(InvitePreview {
+        group_name: invite.group_name,
+        member_count: invite.member_count,
+        inviter: invite.inviter,
+        grants_role: GroupRole::parse(&invite.grants_role).unwrap_or_default(),
+        status,
+    }))
+}
+
 /// Server function: Accept an invite
+///
+/// Accepting again, e.g. after a double click, reports the user as already
+/// a member without using up the invite.
 #[server(AcceptInvite)]
-pub async fn accept_invite(uuid: String) -> Result<i64, ServerFnError> {
+pub async fn accept_invite(uuid: String) -> Result<InviteAcceptance, ServerFnError> {
     use sqlx::SqlitePool;
 
     let session = extract::<Session>()
@@ -206,6 +341,19 @@ pub async fn accept_invite(uuid: String) -> Result<i64, ServerFnError> {
 
     let pool = expect_context::<SqlitePool>();
 
+    join_group_with_invite(&pool, &uuid, user.id).await
+}
+
+/// Add a user to the invite's group, counting the use against its limit
+///
+/// Everything happens in one transaction; returning before the commit rolls
+/// the join back.
+#[cfg(feature = "ssr")]
+async fn join_group_with_invite(
+    pool: &sqlx::SqlitePool,
+    uuid: &str,
+    user_id: i64,
+) -> Result<InviteAcceptance, ServerFnError> {
     // Start a transaction
     let mut tx = pool
         .begin()
@@ -215,10 +363,7 @@ pub async fn accept_invite(uuid: String) -> Result<i64, ServerFnError> {
     // Fetch invite
     let invite = sqlx::query!(
         r#"
-        SELECT 
-            uuid, group_id, 
-            CASE WHEN is_reusable = 1 THEN true ELSE false END as "is_reusable!: bool",
-            duration_days, created_at
+        SELECT uuid, group_id, duration_days, grants_role, created_at
         FROM invites 
         WHERE uuid = ?
         "#,
@@ -229,11 +374,6 @@ pub async fn accept_invite(uuid: String) -> Result<i64, ServerFnError> {
     .map_err(|e| ServerFnError::new(e.to_string()))?
     .ok_or_else(|| ServerFnError::new("Invite not found"))?;
 
-    // Check if invite is valid
-    if !is_invite_valid(&invite.created_at, invite.duration_days) {
-        return Err(ServerFnError::new("The invite is not valid or has expired"));
-    }
-
     // Check if group exists
     let group = sqlx::query!("SELECT id FROM groups WHERE id = ?", invite.group_id)
         .fetch_optional(&mut *tx)
@@ -243,44 +383,177 @@ pub async fn accept_invite(uuid: String) -> Result<i64, ServerFnError> {
 
     let group_id = group.id;
 
-    // Check if user is already a member
-    let is_member = sqlx::query!(
-        "SELECT COUNT(*) as \"count!\" FROM group_members WHERE group_id = ? AND user_id = ?",
+    let role = GroupRole::parse(&invite.grants_role).unwrap_or_default();
+    let role_str = role.as_str();
+
+    // Add user to group; members who follow the link again just get sent on
+    let joined = sqlx::query!(
+        "INSERT OR IGNORE INTO group_members (group_id, user_id, role) VALUES (?, ?, ?)",
         group_id,
-        user.id
+        user_id,
+        role_str
     )
-    .fetch_one(&mut *tx)
+    .execute(&mut *tx)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .rows_affected()
+        == 1;
+    if !joined {
+        return Ok(InviteAcceptance::AlreadyMember { group_id });
+    }
+
+    ensure_not_archived(&mut *tx, group_id).await?;
+
+    if !is_invite_valid(&invite.created_at, invite.duration_days) {
+        return Ok(InviteAcceptance::Expired);
+    }
+
+    // Only count the use once the join is certain
+    if !claim_invite_use(&mut tx, uuid)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+    {
+        return Ok(InviteAcceptance::UsedUp);
+    }
 
-    if is_member.count > 0 {
-        return Err(ServerFnError::new("You are already a member of this group"));
+    // Viewers only look on, so they share no costs
+    if role != GroupRole::Viewer {
+        include_new_member_in_recurring_debts(&mut *tx, group_id, user_id).await?;
     }
 
-    // Add user to group
     sqlx::query!(
-        "INSERT INTO group_members (group_id, user_id) VALUES (?, ?)",
+        "INSERT INTO invite_redemptions (invite_uuid, group_id, user_id) VALUES (?, ?, ?)",
+        uuid,
         group_id,
-        user.id
+        user_id
     )
     .execute(&mut *tx)
     .await
     .map_err(|e| ServerFnError::new(e.to_string()))?;
 
-    // Delete single-use invites
-    if !invite.is_reusable {
-        sqlx::query!("DELETE FROM invites WHERE uuid = ?", uuid)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| ServerFnError::new(e.to_string()))?;
-    }
-
     // Commit transaction
     tx.commit()
         .await
         .map_err(|e| ServerFnError::new(e.to_string()))?;
 
-    Ok(group_id)
+    Ok(InviteAcceptance::Joined { group_id })
+}
+
+/// Server function: Get who joined a group through which invite
+///
+/// Includes users who have since left the group, flagged via `is_member`.
+#[server(GetInviteRedemptions)]
+pub async fn get_invite_redemptions(
+    group_id: i64,
+) -> Result<Vec<InviteRedemptions>, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(ServerFnError::new(
+            "Unauthorized: Only group admins can view invites",
+        ));
+    }
+
+    load_invite_redemptions(&pool, group_id).await
+}
+
+/// Redemptions of a group's invites, grouped per invite, oldest first
+#[cfg(feature = "ssr")]
+async fn load_invite_redemptions(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+) -> Result<Vec<InviteRedemptions>, ServerFnError> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            r.invite_uuid,
+            u.username,
+            r.redeemed_at,
+            EXISTS(
+                SELECT 1 FROM group_members gm
+                WHERE gm.group_id = r.group_id AND gm.user_id = r.user_id
+            ) as "is_member!: bool"
+        FROM invite_redemptions r
+        JOIN users u ON u.id = r.user_id
+        WHERE r.group_id = ?
+        ORDER BY r.invite_uuid, r.redeemed_at, r.id
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let mut grouped: Vec<InviteRedemptions> = Vec::new();
+    for row in rows {
+        let redemption = InviteRedemption {
+            username: row.username,
+            redeemed_at: row.redeemed_at,
+            is_member: row.is_member,
+        };
+        match grouped.last_mut() {
+            Some(last) if last.invite_uuid == row.invite_uuid => {
+                last.redemptions.push(redemption);
+            }
+            _ => grouped.push(InviteRedemptions {
+                invite_uuid: row.invite_uuid,
+                redemptions: vec![redemption],
+            }),
+        }
+    }
+
+    Ok(grouped)
+}
+
+/// Server function: Render an invite link as an SVG QR code
+///
+/// `origin` is the scheme and host the app is reached on, as seen by the
+/// browser, so the code opens the same link as the copied text.
+#[server(GenerateInviteQr)]
+pub async fn generate_invite_qr(uuid: String, origin: String) -> Result<String, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let origin = origin.trim();
+    if !(origin.starts_with("https://") || origin.starts_with("http://"))
+        || origin.len() > 255
+        || origin.chars().any(char::is_whitespace)
+    {
+        return Err(ServerFnError::new("Invalid origin"));
+    }
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group_id = sqlx::query_scalar!("SELECT group_id FROM invites WHERE uuid = ?", uuid)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .ok_or_else(|| ServerFnError::new("Invite not found"))?;
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(ServerFnError::new(
+            "Unauthorized: Only group admins can view invites",
+        ));
+    }
+
+    render_qr_svg(&invite_url(origin, &uuid)).map_err(ServerFnError::new)
 }
 
 /// Server function: Delete an invite
@@ -298,16 +571,16 @@ pub async fn delete_invite(uuid: String, group_id: i64) -> Result<(), ServerFnEr
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check if user is the group creator
-    let group = sqlx::query!("SELECT created_by FROM groups WHERE id = ?", group_id)
+    // Check if user is a group admin
+    sqlx::query!("SELECT id FROM groups WHERE id = ?", group_id)
         .fetch_optional(&pool)
         .await
         .map_err(|e| ServerFnError::new(e.to_string()))?
         .ok_or_else(|| ServerFnError::new("Group not found"))?;
 
-    if group.created_by != user.id {
+    if !is_group_admin(&pool, group_id, user.id).await? {
         return Err(ServerFnError::new(
-            "Unauthorized: Only the group creator can delete invites",
+            "Unauthorized: Only group admins can delete invites",
         ));
     }
 
@@ -323,3 +596,318 @@ pub async fn delete_invite(uuid: String, group_id: i64) -> Result<(), ServerFnEr
 
     Ok(())
 }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for username in ["alice", "bob", "carol"] {
+            sqlx::query("INSERT INTO users (username, password_hash) VALUES (?, 'x')")
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Trip', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    async fn member_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM group_members WHERE group_id = 1")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_invite_rejects_joins_past_max_uses() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days, max_uses) VALUES ('inv', 1, 1, 7, 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            join_group_with_invite(&pool, "inv", 2).await.unwrap(),
+            InviteAcceptance::Joined { group_id: 1 }
+        );
+        assert_eq!(
+            join_group_with_invite(&pool, "inv", 3).await.unwrap(),
+            InviteAcceptance::UsedUp
+        );
+        assert_eq!(member_count(&pool).await, 2);
+
+        // Deleting the used invite keeps the member and the redemption
+        sqlx::query("DELETE FROM invites WHERE uuid = 'inv'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(member_count(&pool).await, 2);
+        let uses: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM invite_redemptions WHERE invite_uuid = 'inv'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(uses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_preview_shows_group_inviter_and_status() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days, max_uses, created_by) VALUES ('inv', 1, 1, 7, 1, 1), ('old', 1, 1, 1, NULL, NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "UPDATE invites SET created_at = datetime('now', '-2 days') WHERE uuid = 'old'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let preview = load_invite_preview(&pool, "inv").await.unwrap().unwrap();
+        assert_eq!(
+            preview,
+            InvitePreview {
+                group_name: "Trip".to_string(),
+                member_count: 1,
+                inviter: Some("alice".to_string()),
+                grants_role: GroupRole::Member,
+                status: InviteStatus::Valid,
+            }
+        );
+
+        join_group_with_invite(&pool, "inv", 2).await.unwrap();
+        let preview = load_invite_preview(&pool, "inv").await.unwrap().unwrap();
+        assert_eq!(preview.member_count, 2);
+        assert_eq!(preview.status, InviteStatus::UsedUp);
+
+        let preview = load_invite_preview(&pool, "old").await.unwrap().unwrap();
+        assert_eq!(preview.inviter, None);
+        assert_eq!(preview.status, InviteStatus::Expired);
+
+        assert!(
+            load_invite_preview(&pool, "missing")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_joining_adds_to_opted_in_recurring_debts() {
+        let pool = setup().await;
+        for statement in [
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days) VALUES ('inv', 1, 1, 7)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date, auto_include_new_members) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-01-01', '2026-02-01', 1), (1, 1, 'Gym', '40', 'monthly', '2026-01-01', '2026-02-01', 0)",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (2, 1)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, recurring_debt_id) VALUES (1, 1, 1, 'Rent', '900', 1)",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        join_group_with_invite(&pool, "inv", 2).await.unwrap();
+
+        let recurring_users: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT recurring_debt_id, user_id FROM recurring_debt_user ORDER BY recurring_debt_id, user_id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(recurring_users, vec![(1, 1), (1, 2), (2, 1)]);
+        // Rent generated before bob joined is left alone
+        let shared_users: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM shared_debt_user WHERE user_id = 2")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(shared_users, 0);
+    }
+
+    #[tokio::test]
+    async fn test_viewer_invite_joins_as_viewer() {
+        let pool = setup().await;
+        for statement in [
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days, grants_role) VALUES ('inv', 1, 1, 7, 'viewer')",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date, auto_include_new_members) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-01-01', '2026-02-01', 1)",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        assert_eq!(
+            join_group_with_invite(&pool, "inv", 2).await.unwrap(),
+            InviteAcceptance::Joined { group_id: 1 }
+        );
+
+        let role: String =
+            sqlx::query_scalar("SELECT role FROM group_members WHERE group_id = 1 AND user_id = 2")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(role, "viewer");
+        // Viewers don't share the rent
+        let recurring_users: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM recurring_debt_user WHERE user_id = 2")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(recurring_users, 0);
+    }
+
+    #[tokio::test]
+    async fn test_archived_group_cannot_be_joined() {
+        let pool = setup().await;
+        for statement in [
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days) VALUES ('inv', 1, 1, 7)",
+            "UPDATE groups SET is_archived = 1 WHERE id = 1",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        let err = join_group_with_invite(&pool, "inv", 2).await.unwrap_err();
+        assert!(err.to_string().contains("Group is archived"));
+        assert_eq!(member_count(&pool).await, 1);
+        // Members following the link again are still sent on
+        assert_eq!(
+            join_group_with_invite(&pool, "inv", 1).await.unwrap(),
+            InviteAcceptance::AlreadyMember { group_id: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_accepts_share_the_last_slot() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days, max_uses, use_count) VALUES ('inv', 1, 1, 7, 2, 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let (bob, carol) = tokio::join!(
+            join_group_with_invite(&pool, "inv", 2),
+            join_group_with_invite(&pool, "inv", 3),
+        );
+        let outcomes = [bob.unwrap(), carol.unwrap()];
+        assert!(outcomes.contains(&InviteAcceptance::Joined { group_id: 1 }));
+        assert!(outcomes.contains(&InviteAcceptance::UsedUp));
+
+        let use_count: i64 = sqlx::query_scalar("SELECT use_count FROM invites WHERE uuid = 'inv'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(use_count, 2);
+        assert_eq!(member_count(&pool).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_double_accept_of_single_use_invite() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days, max_uses) VALUES ('inv', 1, 0, 7, 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let (first, second) = tokio::join!(
+            join_group_with_invite(&pool, "inv", 2),
+            join_group_with_invite(&pool, "inv", 2),
+        );
+        let outcomes = [first.unwrap(), second.unwrap()];
+        assert!(outcomes.contains(&InviteAcceptance::Joined { group_id: 1 }));
+        assert!(outcomes.contains(&InviteAcceptance::AlreadyMember { group_id: 1 }));
+
+        let use_count: i64 = sqlx::query_scalar("SELECT use_count FROM invites WHERE uuid = 'inv'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(use_count, 1);
+        assert_eq!(member_count(&pool).await, 2);
+        assert_eq!(
+            join_group_with_invite(&pool, "inv", 3).await.unwrap(),
+            InviteAcceptance::UsedUp
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_invite_adds_nobody() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days, created_at) VALUES ('old', 1, 1, 7, '2020-01-01 00:00:00')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            join_group_with_invite(&pool, "old", 2).await.unwrap(),
+            InviteAcceptance::Expired
+        );
+        assert_eq!(member_count(&pool).await, 1);
+        // Existing members are told so rather than that the link expired
+        assert_eq!(
+            join_group_with_invite(&pool, "old", 1).await.unwrap(),
+            InviteAcceptance::AlreadyMember { group_id: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redemptions_are_kept_after_leaving() {
+        let pool = setup().await;
+        sqlx::query(
+            "INSERT INTO invites (uuid, group_id, is_reusable, duration_days, max_uses) VALUES ('inv', 1, 0, 7, 1), ('multi', 1, 1, 7, NULL)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        join_group_with_invite(&pool, "inv", 2).await.unwrap();
+        join_group_with_invite(&pool, "multi", 3).await.unwrap();
+        sqlx::query("DELETE FROM group_members WHERE user_id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let redemptions = load_invite_redemptions(&pool, 1).await.unwrap();
+        assert_eq!(redemptions.len(), 2);
+        assert_eq!(redemptions[0].invite_uuid, "inv");
+        assert_eq!(redemptions[0].redemptions[0].username, "bob");
+        assert!(!redemptions[0].redemptions[0].is_member);
+        assert_eq!(redemptions[1].invite_uuid, "multi");
+        assert_eq!(redemptions[1].redemptions[0].username, "carol");
+        assert!(redemptions[1].redemptions[0].is_member);
+
+        // The used-up single-use invite is kept, so its history stays listed
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM invites WHERE uuid = 'inv'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+}
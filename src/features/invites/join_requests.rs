@@ -0,0 +1,452 @@
+//! Joining groups by code: users ask with the group's join code and an admin
+//! lets them in
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use super::models::{JoinRequest, JoinRequestOutcome};
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::utils::{ensure_not_archived, is_group_admin},
+    notifications::{models::NotificationPayload, utils::notify_users},
+    recurring_debts::handlers::include_new_member_in_recurring_debts,
+};
+
+/// Server function: The group's join code, `None` while joining by code is
+/// turned off
+#[server(GetJoinCode)]
+pub async fn get_join_code(group_id: i64) -> Result<Option<String>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    ensure_admin(&pool, group_id, user.id).await?;
+
+    let join_code = sqlx::query_scalar!("SELECT join_code FROM groups WHERE id = ?", group_id)
+        .fetch_one(&pool)
+        .await?;
+
+    Ok(join_code)
+}
+
+/// Server function: Turn joining by code on with a fresh code, or off
+///
+/// Enabling again rotates the code, so the old one stops working.
+#[server(SetJoinCode)]
+pub async fn set_join_code(group_id: i64, enabled: bool) -> Result<Option<String>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    store_join_code(&pool, group_id, user.id, enabled).await
+}
+
+/// Server function: Ask to join the group with the given join code
+#[server(RequestToJoin)]
+pub async fn request_to_join(code: String) -> Result<JoinRequestOutcome, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    create_join_request(&pool, &code, user.id).await
+}
+
+/// Server function: Open join requests of a group, oldest first
+#[server(ListJoinRequests)]
+pub async fn list_join_requests(group_id: i64) -> Result<Vec<JoinRequest>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    ensure_admin(&pool, group_id, user.id).await?;
+
+    load_join_requests(&pool, group_id).await
+}
+
+/// Server function: Let the requester into the group as a member
+#[server(ApproveJoinRequest)]
+pub async fn approve_join_request(request_id: i64) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    decide_join_request(&pool, request_id, user.id, true).await
+}
+
+/// Server function: Turn a join request down
+#[server(RejectJoinRequest)]
+pub async fn reject_join_request(request_id: i64) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    decide_join_request(&pool, request_id, user.id, false).await
+}
+
+#[cfg(feature = "ssr")]
+async fn ensure_admin(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<(), AppError> {
+    if !is_group_admin(pool, group_id, user_id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can manage join requests",
+        ));
+    }
+    Ok(())
+}
+
+/// Short code that is easy to read out, e.g. "3F9A0C1B"
+#[cfg(feature = "ssr")]
+fn generate_join_code() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..8].to_uppercase()
+}
+
+#[cfg(feature = "ssr")]
+async fn store_join_code(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+    enabled: bool,
+) -> Result<Option<String>, AppError> {
+    ensure_admin(pool, group_id, user_id).await?;
+    ensure_not_archived(pool, group_id).await?;
+
+    let join_code = enabled.then(generate_join_code);
+    sqlx::query!(
+        "UPDATE groups SET join_code = ? WHERE id = ?",
+        join_code,
+        group_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(join_code)
+}
+
+#[cfg(feature = "ssr")]
+async fn create_join_request(
+    pool: &sqlx::SqlitePool,
+    code: &str,
+    user_id: i64,
+) -> Result<JoinRequestOutcome, AppError> {
+    let code = code.trim().to_uppercase();
+    if code.is_empty() {
+        return Err(AppError::validation("Enter the group's join code"));
+    }
+
+    let group = sqlx::query!(
+        r#"SELECT id as "id!", name FROM groups WHERE join_code = ?"#,
+        code
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("No group uses this join code"))?;
+
+    let is_member = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = ? AND user_id = ?) as "exists!: bool""#,
+        group.id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+    if is_member {
+        return Ok(JoinRequestOutcome::AlreadyMember { group_id: group.id });
+    }
+
+    ensure_not_archived(pool, group.id).await?;
+
+    let requested = sqlx::query!(
+        "INSERT OR IGNORE INTO group_join_requests (group_id, user_id) VALUES (?, ?)",
+        group.id,
+        user_id
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        == 1;
+
+    Ok(if requested {
+        JoinRequestOutcome::Requested {
+            group_name: group.name,
+        }
+    } else {
+        JoinRequestOutcome::AlreadyRequested {
+            group_name: group.name,
+        }
+    })
+}
+
+#[cfg(feature = "ssr")]
+async fn load_join_requests(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+) -> Result<Vec<JoinRequest>, AppError> {
+    let requests = sqlx::query!(
+        r#"
+        SELECT r.id as "id!", r.user_id, u.username, r.created_at as "created_at!: time::OffsetDateTime"
+        FROM group_join_requests r
+        INNER JOIN users u ON u.id = r.user_id
+        WHERE r.group_id = ? AND r.status = 'pending'
+        ORDER BY r.created_at ASC, r.id ASC
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(requests
+        .into_iter()
+        .map(|request| JoinRequest {
+            id: request.id,
+            user_id: request.user_id,
+            username: request.username,
+            created_at: request.created_at,
+        })
+        .collect())
+}
+
+/// Approve or reject an open request as `admin_id`
+///
+/// Approving adds the requester in one transaction with the status change, so
+/// two admins answering at once can't both act on it.
+#[cfg(feature = "ssr")]
+async fn decide_join_request(
+    pool: &sqlx::SqlitePool,
+    request_id: i64,
+    admin_id: i64,
+    approve: bool,
+) -> Result<(), AppError> {
+    let request = sqlx::query!(
+        r#"
+        SELECT r.group_id, r.user_id, g.name as group_name
+        FROM group_join_requests r
+        INNER JOIN groups g ON g.id = r.group_id
+        WHERE r.id = ?
+        "#,
+        request_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Join request not found"))?;
+
+    ensure_admin(pool, request.group_id, admin_id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    if approve {
+        ensure_not_archived(&mut *tx, request.group_id).await?;
+    }
+
+    let status = if approve { "approved" } else { "rejected" };
+    let now = time::OffsetDateTime::now_utc();
+    let decided = sqlx::query!(
+        "UPDATE group_join_requests SET status = ?, decided_at = ? WHERE id = ? AND status = 'pending'",
+        status,
+        now,
+        request_id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected()
+        == 1;
+    if !decided {
+        return Err(AppError::conflict(
+            "This join request has already been answered",
+        ));
+    }
+
+    if approve {
+        let joined = sqlx::query!(
+            "INSERT OR IGNORE INTO group_members (group_id, user_id, role) VALUES (?, ?, 'member')",
+            request.group_id,
+            request.user_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            == 1;
+
+        if joined {
+            include_new_member_in_recurring_debts(&mut *tx, request.group_id, request.user_id)
+                .await?;
+            notify_users(
+                &mut tx,
+                request.group_id,
+                &[request.user_id],
+                &NotificationPayload::JoinRequestApproved {
+                    group_name: request.group_name,
+                },
+            )
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Trip', 1)",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin')",
+            "INSERT INTO feature_flags (group_id, key, enabled) VALUES (1, 'notifications', 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_approved_request_joins_and_notifies() {
+        let pool = setup().await;
+
+        // Only admins control the code
+        let err = store_join_code(&pool, 1, 2, true).await.unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+        let code = store_join_code(&pool, 1, 1, true).await.unwrap().unwrap();
+
+        let requested = create_join_request(&pool, &format!(" {} ", code.to_lowercase()), 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            requested,
+            JoinRequestOutcome::Requested {
+                group_name: "Trip".to_string()
+            }
+        );
+        assert_eq!(
+            create_join_request(&pool, &code, 2).await.unwrap(),
+            JoinRequestOutcome::AlreadyRequested {
+                group_name: "Trip".to_string()
+            }
+        );
+        assert_eq!(
+            create_join_request(&pool, &code, 1).await.unwrap(),
+            JoinRequestOutcome::AlreadyMember { group_id: 1 }
+        );
+
+        let requests = load_join_requests(&pool, 1).await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].username, "bob");
+
+        let err = decide_join_request(&pool, requests[0].id, 2, true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+        decide_join_request(&pool, requests[0].id, 1, true)
+            .await
+            .unwrap();
+
+        let role: String =
+            sqlx::query_scalar("SELECT role FROM group_members WHERE group_id = 1 AND user_id = 2")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(role, "member");
+        let kinds: Vec<String> =
+            sqlx::query_scalar("SELECT kind FROM notifications WHERE user_id = 2")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(kinds, vec!["join_request_approved".to_string()]);
+        assert!(load_join_requests(&pool, 1).await.unwrap().is_empty());
+
+        // A second answer to the same request is refused
+        let err = decide_join_request(&pool, requests[0].id, 1, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_rotated_codes() {
+        let pool = setup().await;
+        let old_code = store_join_code(&pool, 1, 1, true).await.unwrap().unwrap();
+
+        create_join_request(&pool, &old_code, 3).await.unwrap();
+        let request_id = load_join_requests(&pool, 1).await.unwrap()[0].id;
+        decide_join_request(&pool, request_id, 1, false)
+            .await
+            .unwrap();
+        let members: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM group_members WHERE group_id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(members, 1);
+
+        // After a rejection the user may ask again, but not with a rotated code
+        let new_code = store_join_code(&pool, 1, 1, true).await.unwrap().unwrap();
+        assert_ne!(old_code, new_code);
+        let err = create_join_request(&pool, &old_code, 3).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+        assert!(matches!(
+            create_join_request(&pool, &new_code, 3).await.unwrap(),
+            JoinRequestOutcome::Requested { .. }
+        ));
+
+        // Turning codes off stops new requests
+        assert_eq!(store_join_code(&pool, 1, 1, false).await.unwrap(), None);
+        let err = create_join_request(&pool, &new_code, 2).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+    }
+}
@@ -1,6 +1,7 @@
 pub mod handlers;
+pub mod join_requests;
 pub mod models;
 pub mod utils;
 
 // Re-export commonly used types
-pub use models::{Invite, InviteWithGroup};
+pub use models::{Invite, InviteAcceptance, InvitePreview, InviteStatus, InviteWithGroup};
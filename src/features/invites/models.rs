@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use time::OffsetDateTime;
 
+use crate::features::groups::models::GroupRole;
+
 /// Invite model representing an invite link to a group
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "ssr", derive(FromRow))]
@@ -12,6 +14,11 @@ pub struct Invite {
     pub name: Option<String>,
     pub is_reusable: bool,
     pub duration_days: i64,
+    /// Most times the invite can be accepted, unlimited when `None`
+    pub max_uses: Option<i64>,
+    pub use_count: i64,
+    /// Role given to whoever accepts the invite, `member` or `viewer`
+    pub grants_role: String,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
@@ -27,13 +34,74 @@ pub struct InviteWithGroup {
     pub name: Option<String>,
     pub is_reusable: bool,
     pub duration_days: i64,
+    /// Role the user gets by accepting
+    pub grants_role: GroupRole,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
+    /// Not expired and not used up
     pub is_valid: bool,
     #[serde(with = "time::serde::rfc3339")]
     pub expires_at: OffsetDateTime,
 }
 
+/// Whether an invite can still be accepted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InviteStatus {
+    Valid,
+    /// Older than its duration
+    Expired,
+    /// Accepted as often as its limit allows
+    UsedUp,
+}
+
+/// What anyone with an invite link may see before joining
+///
+/// Deliberately leaves out everything about the group's money.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvitePreview {
+    pub group_name: String,
+    pub member_count: i64,
+    /// Who created the invite, `None` for old invites and deleted users
+    pub inviter: Option<String>,
+    /// Role the user gets by accepting
+    pub grants_role: GroupRole,
+    pub status: InviteStatus,
+}
+
+/// Outcome of accepting an invite
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InviteAcceptance {
+    /// The user joined the group
+    Joined { group_id: i64 },
+    /// The user was in the group already, so the invite was not used
+    AlreadyMember { group_id: i64 },
+    /// The invite expired before it was accepted
+    Expired,
+    /// The invite reached its maximum number of uses
+    UsedUp,
+}
+
+/// Outcome of asking to join a group by its join code
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JoinRequestOutcome {
+    /// The request waits for an admin of the group
+    Requested { group_name: String },
+    /// The user had asked before and is still waiting
+    AlreadyRequested { group_name: String },
+    /// The user is in the group already
+    AlreadyMember { group_id: i64 },
+}
+
+/// A user waiting to be let into a group, shown to its admins
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JoinRequest {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
 /// Invite list item with formatted expiration date
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct InviteListItem {
@@ -41,4 +109,60 @@ pub struct InviteListItem {
     pub name: Option<String>,
     pub is_reusable: bool,
     pub expiration_date: String,
+    /// Expired or used up, so it can no longer be accepted
+    pub is_expired: bool,
+    pub max_uses: Option<i64>,
+    pub use_count: i64,
+    /// Role given to whoever accepts the invite
+    pub grants_role: GroupRole,
+}
+
+/// A user who joined a group through an invite
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InviteRedemption {
+    pub username: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub redeemed_at: OffsetDateTime,
+    /// Whether the user is still in the group
+    pub is_member: bool,
+}
+
+/// Everyone who joined through one invite, oldest first
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InviteRedemptions {
+    pub invite_uuid: String,
+    pub redemptions: Vec<InviteRedemption>,
+}
+
+impl InviteListItem {
+    /// Usage for display, e.g. "3/10 used" or "3 used" without a limit
+    pub fn usage_label(&self) -> String {
+        match self.max_uses {
+            Some(max_uses) => format!("{}/{} used", self.use_count, max_uses),
+            None => format!("{} used", self.use_count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_label() {
+        let mut invite = InviteListItem {
+            uuid: "abc".to_string(),
+            name: None,
+            is_reusable: true,
+            expiration_date: "March 1, 2026".to_string(),
+            is_expired: false,
+            max_uses: Some(10),
+            use_count: 3,
+            grants_role: GroupRole::Member,
+        };
+        assert_eq!(invite.usage_label(), "3/10 used");
+
+        invite.max_uses = None;
+        assert_eq!(invite.usage_label(), "3 used");
+    }
 }
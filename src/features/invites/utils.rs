@@ -25,3 +25,93 @@ pub fn is_invite_valid(created_at: &OffsetDateTime, duration_days: i64) -> bool
 pub fn calculate_expiration(created_at: &OffsetDateTime, duration_days: i64) -> OffsetDateTime {
     *created_at + Duration::days(duration_days)
 }
+
+/// Full link to an invite, e.g. `https://example.com/invite/<uuid>`
+pub fn invite_url(origin: &str, uuid: &str) -> String {
+    format!("{}/invite/{}", origin.trim_end_matches('/'), uuid)
+}
+
+/// Render a URL as an SVG QR code that scales to fill its container
+#[cfg(feature = "ssr")]
+pub fn render_qr_svg(url: &str) -> Result<String, String> {
+    use qrcode::{QrCode, render::svg};
+
+    /// Modules of blank margin the renderer adds on each side
+    const QUIET_ZONE: usize = 4;
+
+    let code = QrCode::new(url.as_bytes()).map_err(|e| e.to_string())?;
+    let svg = code
+        .render::<svg::Color>()
+        .module_dimensions(1, 1)
+        .quiet_zone(true)
+        .build();
+
+    // Swap the fixed pixel size for a relative one; the viewBox keeps the
+    // aspect ratio. The XML declaration is dropped so the markup can be
+    // embedded inline.
+    let size = code.width() + 2 * QUIET_ZONE;
+    let svg = svg.replacen(
+        &format!(r#"width="{size}" height="{size}" viewBox"#),
+        r#"width="100%" height="100%" viewBox"#,
+        1,
+    );
+    let start = svg.find("<svg").unwrap_or(0);
+    Ok(svg[start..].to_string())
+}
+
+/// Most uses an invite may be limited to
+pub const MAX_INVITE_USES: i64 = 1000;
+
+/// Check if an invite has reached its use limit
+pub fn is_invite_used_up(max_uses: Option<i64>, use_count: i64) -> bool {
+    max_uses.is_some_and(|max_uses| use_count >= max_uses)
+}
+
+/// Count one use of an invite unless it is already used up
+///
+/// The check and the increment happen in a single statement, so two users
+/// accepting the last slot at the same time cannot both get it. Returns
+/// whether the use was counted.
+#[cfg(feature = "ssr")]
+pub async fn claim_invite_use(
+    conn: &mut sqlx::SqliteConnection,
+    uuid: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE invites
+        SET use_count = use_count + 1, updated_at = CURRENT_TIMESTAMP
+        WHERE uuid = ? AND (max_uses IS NULL OR use_count < max_uses)
+        "#,
+        uuid
+    )
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invite_url() {
+        assert_eq!(
+            invite_url("https://example.com", "abc"),
+            "https://example.com/invite/abc"
+        );
+        assert_eq!(
+            invite_url("https://example.com/", "abc"),
+            "https://example.com/invite/abc"
+        );
+    }
+
+    #[test]
+    fn test_render_qr_svg_scales_to_container() {
+        let svg = render_qr_svg("https://example.com/invite/abc").unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="100%" height="100%""#));
+        assert!(svg.ends_with("</svg>"));
+    }
+}
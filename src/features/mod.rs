@@ -5,3 +5,225 @@ pub mod recurring_debts;
 pub mod shared_debts;
 pub mod shopping_lists;
 pub mod transactions;
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::{
+        groups::handlers::{create_group, update_group},
+        recurring_debts::handlers::{create_recurring_debt, update_recurring_debt},
+        shared_debts::handlers::{create_shared_debt, update_shared_debt},
+        shopping_lists::{add_shopping_list_item, create_shopping_list, update_shopping_list},
+        transactions::handlers::{create_transaction, update_transaction},
+    };
+    use crate::error::AppError;
+
+    /// Error messages of handler results, which must all have failed
+    fn messages<T: std::fmt::Debug>(results: Vec<Result<T, AppError>>) -> Vec<String> {
+        results
+            .into_iter()
+            .map(|result| result.unwrap_err().to_string())
+            .collect()
+    }
+
+    fn assert_all_eq(messages: &[String], expected: &str) {
+        for message in messages {
+            assert!(message.contains(expected), "{message}");
+            assert_eq!(message, &messages[0]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blank_name_rejected_alike() {
+        let name = || "   ".to_string();
+        let amount = || "10.00".to_string();
+        let messages = [
+            messages(vec![
+                create_group(name(), None, None, None).await.map(drop),
+                update_group(1, name(), vec![1], 0, None, None, None).await,
+            ]),
+            messages(vec![
+                create_shared_debt(
+                    1,
+                    name(),
+                    amount(),
+                    vec![1],
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .map(drop),
+                update_shared_debt(
+                    1,
+                    name(),
+                    amount(),
+                    vec![1],
+                    0,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await,
+            ]),
+            messages(vec![
+                create_recurring_debt(
+                    1,
+                    name(),
+                    amount(),
+                    "monthly".into(),
+                    "2026-01-01".into(),
+                    None,
+                    vec![1],
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .await
+                .map(drop)
+                .map_err(AppError::from),
+                update_recurring_debt(
+                    1,
+                    name(),
+                    amount(),
+                    "monthly".into(),
+                    None,
+                    true,
+                    vec![1],
+                    0,
+                    None,
+                    None,
+                    false,
+                )
+                .await
+                .map_err(AppError::from),
+            ]),
+            messages(vec![
+                create_shopping_list(1, name())
+                    .await
+                    .map(drop)
+                    .map_err(AppError::from),
+                update_shopping_list(1, name())
+                    .await
+                    .map_err(AppError::from),
+                add_shopping_list_item(1, name(), None, None, None)
+                    .await
+                    .map(drop)
+                    .map_err(AppError::from),
+            ]),
+        ]
+        .concat();
+
+        assert_all_eq(&messages, "Name must be at least 1 character");
+    }
+
+    #[tokio::test]
+    async fn test_long_name_rejected_alike() {
+        let name = || "a".repeat(256);
+        let messages = messages(vec![
+            create_group(name(), None, None, None).await.map(drop),
+            create_shared_debt(
+                1,
+                name(),
+                "10".into(),
+                vec![1],
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .map(drop),
+            create_shopping_list(1, name())
+                .await
+                .map(drop)
+                .map_err(AppError::from),
+        ]);
+
+        assert_all_eq(&messages, "Name must be 255 characters or less");
+    }
+
+    #[tokio::test]
+    async fn test_bad_amount_rejected_alike() {
+        for (amount, expected) in [
+            ("1,50", "decimal point"),
+            ("0", "Amount must be greater than zero"),
+            ("10.999", "Amount can have at most 2 decimal places"),
+            ("1000000.01", "Amount is too large"),
+            ("-5", "Amount must be greater than zero"),
+            ("+5", "plus sign"),
+            ("1,000", "thousands separators"),
+        ] {
+            let messages = messages(vec![
+                create_group("Flat".into(), Some(amount.into()), None, None)
+                    .await
+                    .map(drop),
+                create_shared_debt(
+                    1,
+                    "Rent".into(),
+                    amount.into(),
+                    vec![1],
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .map(drop),
+                create_recurring_debt(
+                    1,
+                    "Rent".into(),
+                    amount.into(),
+                    "monthly".into(),
+                    "2026-01-01".into(),
+                    None,
+                    vec![1],
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .await
+                .map(drop)
+                .map_err(AppError::from),
+                create_transaction(1, 2, amount.into(), None)
+                    .await
+                    .map(drop),
+                update_transaction(1, 1, 2, amount.into(), None, 0).await,
+            ]);
+
+            assert_all_eq(&messages, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_long_description_rejected() {
+        let description = Some("a".repeat(501));
+        let messages = messages(vec![
+            create_transaction(1, 2, "10".into(), description.clone())
+                .await
+                .map(drop),
+            update_transaction(1, 1, 2, "10".into(), description, 0).await,
+        ]);
+
+        assert_all_eq(&messages, "Description must be 500 characters or less");
+    }
+}
@@ -0,0 +1,147 @@
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use super::models::NotificationFeed;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+
+/// Most notifications a single request may ask for
+#[cfg(feature = "ssr")]
+const MAX_NOTIFICATION_LIMIT: i64 = 50;
+
+/// Server function: Get the current user's latest notifications, newest first
+#[server(GetNotifications)]
+pub async fn get_notifications(limit: i64) -> Result<NotificationFeed, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use super::{models::Notification, utils::decode_payload};
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let limit = limit.clamp(1, MAX_NOTIFICATION_LIMIT);
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            n.id as "id!",
+            n.group_id,
+            g.name as group_name,
+            g.currency,
+            n.kind,
+            n.payload,
+            n.read_at as "read_at: time::OffsetDateTime",
+            n.created_at as "created_at!: time::OffsetDateTime"
+        FROM notifications n
+        INNER JOIN groups g ON n.group_id = g.id
+        WHERE n.user_id = ?
+        ORDER BY n.created_at DESC, n.id DESC
+        LIMIT ?
+        "#,
+        user.id,
+        limit
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let unread_count = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM notifications WHERE user_id = ? AND read_at IS NULL",
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    // Notifications of unknown kinds, e.g. written by a newer version, are skipped
+    let notifications = rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(Notification {
+                id: row.id,
+                group_id: row.group_id,
+                group_name: row.group_name,
+                currency: row.currency,
+                payload: decode_payload(&row.kind, &row.payload)?,
+                read_at: row.read_at,
+                created_at: row.created_at,
+            })
+        })
+        .collect();
+
+    Ok(NotificationFeed {
+        notifications,
+        unread_count,
+    })
+}
+
+/// Server function: Mark one of the current user's notifications as read
+#[server(MarkNotificationRead)]
+pub async fn mark_notification_read(notification_id: i64) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE notifications
+        SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP)
+        WHERE id = ? AND user_id = ?
+        "#,
+        notification_id,
+        user.id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .rows_affected();
+
+    if updated == 0 {
+        return Err(ServerFnError::new("Notification not found"));
+    }
+
+    Ok(())
+}
+
+/// Server function: Mark all of the current user's notifications as read
+#[server(MarkAllRead)]
+pub async fn mark_all_read() -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    sqlx::query!(
+        "UPDATE notifications SET read_at = CURRENT_TIMESTAMP WHERE user_id = ? AND read_at IS NULL",
+        user.id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    Ok(())
+}
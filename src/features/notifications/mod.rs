@@ -0,0 +1,11 @@
+pub mod handlers;
+pub mod models;
+pub mod utils;
+
+pub use handlers::{
+    GetNotifications, MarkAllRead, MarkNotificationRead, get_notifications, mark_all_read,
+    mark_notification_read,
+};
+pub use models::{Notification, NotificationFeed, NotificationPayload};
+#[cfg(feature = "ssr")]
+pub use utils::notify_users;
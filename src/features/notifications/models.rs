@@ -0,0 +1,133 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use crate::features::groups::utils::currency_symbol;
+
+/// What a notification is about, with the details needed to show it
+///
+/// Adjacently tagged to match the `kind` and `payload` columns, so a delivery
+/// channel can pick the kinds it handles without parsing the details. Names
+/// are copied in when the notification is written, like in the activity log.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum NotificationPayload {
+    /// Someone added a debt the user has a share in
+    DebtAdded {
+        debt_id: i64,
+        debt_name: String,
+        amount: Decimal,
+        added_by: String,
+    },
+    /// Someone recorded a payment to the user
+    PaymentReceived {
+        transaction_id: i64,
+        amount: Decimal,
+        paid_by: String,
+    },
+    /// A recurring debt the user takes part in generated a new debt
+    RecurringDebtGenerated {
+        debt_id: i64,
+        debt_name: String,
+        amount: Decimal,
+    },
+    /// An admin let the user into a group they asked to join
+    JoinRequestApproved { group_name: String },
+}
+
+impl NotificationPayload {
+    /// Sentence describing the event with amounts in the given currency
+    /// symbol, e.g. "alice added Dinner (€30.00)"
+    pub fn message(&self, currency: &str) -> String {
+        match self {
+            NotificationPayload::DebtAdded {
+                debt_name,
+                amount,
+                added_by,
+                ..
+            } => format!(
+                "{} added {} ({}{:.2})",
+                added_by,
+                debt_name,
+                currency,
+                amount.round_dp(2)
+            ),
+            NotificationPayload::PaymentReceived {
+                amount, paid_by, ..
+            } => format!("{} paid you {}{:.2}", paid_by, currency, amount.round_dp(2)),
+            NotificationPayload::RecurringDebtGenerated {
+                debt_name, amount, ..
+            } => format!(
+                "Recurring debt {} ({}{:.2}) is due",
+                debt_name,
+                currency,
+                amount.round_dp(2)
+            ),
+            NotificationPayload::JoinRequestApproved { group_name } => {
+                format!("Your request to join {} was approved", group_name)
+            }
+        }
+    }
+}
+
+/// A notification for the current user
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: i64,
+    pub group_id: i64,
+    pub group_name: String,
+    /// ISO 4217 code of the group's currency the amounts are in
+    pub currency: String,
+    pub payload: NotificationPayload,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub read_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl Notification {
+    pub fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+
+    /// Sentence describing the event, with amounts in the group's currency
+    pub fn message(&self) -> String {
+        self.payload.message(currency_symbol(&self.currency))
+    }
+
+    /// Page to open for the notification
+    pub fn href(&self) -> String {
+        format!("/groups/{}", self.group_id)
+    }
+}
+
+/// The latest notifications of the current user
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NotificationFeed {
+    pub notifications: Vec<Notification>,
+    /// Unread notifications, including older ones not in the list
+    pub unread_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_messages() {
+        let debt = NotificationPayload::DebtAdded {
+            debt_id: 1,
+            debt_name: "Dinner".to_string(),
+            amount: Decimal::new(30, 0),
+            added_by: "alice".to_string(),
+        };
+        assert_eq!(debt.message("€"), "alice added Dinner (€30.00)");
+
+        let payment = NotificationPayload::PaymentReceived {
+            transaction_id: 1,
+            amount: Decimal::new(1250, 2),
+            paid_by: "bob".to_string(),
+        };
+        assert_eq!(payment.message("$"), "bob paid you $12.50");
+    }
+}
@@ -0,0 +1,152 @@
+//! Writing notifications
+//!
+//! Notifications are only delivered in-app for now: each one is a row in the
+//! `notifications` table. Further channels such as email or webhooks can hook
+//! into [`notify_users`] and render the same payload.
+
+#[cfg(feature = "ssr")]
+use sqlx::SqliteConnection;
+
+#[cfg(feature = "ssr")]
+use super::models::NotificationPayload;
+#[cfg(feature = "ssr")]
+use crate::features::feature_flags::FeatureFlag;
+
+/// Split a payload into the values of its `kind` and `payload` columns
+#[cfg(feature = "ssr")]
+pub fn encode_payload(
+    payload: &NotificationPayload,
+) -> Result<(String, String), serde_json::Error> {
+    let mut value = serde_json::to_value(payload)?;
+    let kind = value["kind"].as_str().unwrap_or_default().to_string();
+    let details = value["payload"].take().to_string();
+    Ok((kind, details))
+}
+
+/// Rebuild a payload from its stored columns
+///
+/// Returns `None` for kinds this version doesn't know.
+#[cfg(feature = "ssr")]
+pub fn decode_payload(kind: &str, payload: &str) -> Option<NotificationPayload> {
+    let details: serde_json::Value = serde_json::from_str(payload).ok()?;
+    serde_json::from_value(serde_json::json!({ "kind": kind, "payload": details })).ok()
+}
+
+/// Notify users about an event in a group
+///
+/// Does nothing unless the group has the notifications feature flag enabled.
+/// Pass the connection of the transaction that records the event, so the
+/// notifications are only kept when it commits.
+#[cfg(feature = "ssr")]
+pub async fn notify_users(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    user_ids: &[i64],
+    payload: &NotificationPayload,
+) -> Result<(), sqlx::Error> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    let key = FeatureFlag::Notifications.as_str();
+    let enabled = sqlx::query_scalar!(
+        r#"SELECT enabled as "enabled!: bool" FROM feature_flags WHERE group_id = ? AND key = ?"#,
+        group_id,
+        key
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .unwrap_or(false);
+    if !enabled {
+        return Ok(());
+    }
+
+    let (kind, details) =
+        encode_payload(payload).map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+    for user_id in user_ids {
+        sqlx::query!(
+            "INSERT INTO notifications (user_id, group_id, kind, payload) VALUES (?, ?, ?, ?)",
+            user_id,
+            group_id,
+            kind,
+            details
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use rust_decimal::Decimal;
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    fn payment() -> NotificationPayload {
+        NotificationPayload::PaymentReceived {
+            transaction_id: 3,
+            amount: Decimal::new(2000, 2),
+            paid_by: "alice".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        let (kind, details) = encode_payload(&payment()).unwrap();
+        assert_eq!(kind, "payment_received");
+        assert!(!details.contains("payment_received"));
+
+        assert_eq!(decode_payload(&kind, &details), Some(payment()));
+        assert_eq!(decode_payload("carrier_pigeon", &details), None);
+    }
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    async fn notification_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM notifications")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_notify_users_respects_feature_flag() {
+        let pool = setup().await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        notify_users(&mut conn, 1, &[2], &payment()).await.unwrap();
+        drop(conn);
+        assert_eq!(notification_count(&pool).await, 0);
+
+        sqlx::query(
+            "INSERT INTO feature_flags (group_id, key, enabled) VALUES (1, 'notifications', 1)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        notify_users(&mut conn, 1, &[2], &payment()).await.unwrap();
+        drop(conn);
+        assert_eq!(notification_count(&pool).await, 1);
+    }
+}
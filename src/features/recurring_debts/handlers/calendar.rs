@@ -0,0 +1,407 @@
+//! iCal feed of upcoming recurring debt generation dates
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::RoundingStrategy;
+
+/// Upcoming occurrences listed per recurring debt
+#[cfg(feature = "ssr")]
+const FEED_OCCURRENCES: usize = 6;
+
+/// Path of the iCal feed for a group (without the token query parameter)
+pub fn calendar_feed_path(group_id: i64) -> String {
+    format!("/api/groups/{}/recurring-debts.ics", group_id)
+}
+
+#[cfg(feature = "ssr")]
+async fn verify_membership(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<(), ServerFnError> {
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    if is_member == 0 {
+        return Err(ServerFnError::new("You are not a member of this group"));
+    }
+
+    Ok(())
+}
+
+/// Server function: Get the current user's calendar token for a group, if any
+#[server(GetCalendarToken)]
+pub async fn get_calendar_token(group_id: i64) -> Result<Option<String>, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    verify_membership(&pool, group_id, user.id).await?;
+
+    sqlx::query_scalar!(
+        "SELECT token FROM calendar_tokens WHERE user_id = ? AND group_id = ?",
+        user.id,
+        group_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))
+}
+
+/// Server function: Issue a calendar feed token for the current user and group
+///
+/// Any previous token for the same user and group is replaced.
+#[server(CreateCalendarToken)]
+pub async fn create_calendar_token(group_id: i64) -> Result<String, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    verify_membership(&pool, group_id, user.id).await?;
+
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+
+    sqlx::query!(
+        r#"
+        INSERT INTO calendar_tokens (user_id, group_id, token)
+        VALUES (?, ?, ?)
+        ON CONFLICT(user_id, group_id) DO UPDATE SET
+            token = excluded.token,
+            created_at = CURRENT_TIMESTAMP
+        "#,
+        user.id,
+        group_id,
+        token
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(token)
+}
+
+/// Server function: Revoke the current user's calendar token for a group
+#[server(DeleteCalendarToken)]
+pub async fn delete_calendar_token(group_id: i64) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    sqlx::query!(
+        "DELETE FROM calendar_tokens WHERE user_id = ? AND group_id = ?",
+        user.id,
+        group_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(())
+}
+
+/// Render the iCal feed for a group
+///
+/// Returns `None` when the token is unknown or its owner is no longer a member
+/// of the group.
+#[cfg(feature = "ssr")]
+pub async fn render_calendar_feed(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    token: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    use std::collections::HashMap;
+
+    use rust_decimal::Decimal;
+    use time::{Date, format_description::well_known::Iso8601};
+
+    use crate::features::{
+        groups::utils::currency_symbol,
+        recurring_debts::{
+            models::Frequency,
+            utils::{app_today, ics_escape, next_occurrences},
+        },
+    };
+
+    let authorized = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM calendar_tokens ct
+            INNER JOIN group_members gm ON gm.group_id = ct.group_id AND gm.user_id = ct.user_id
+            WHERE ct.token = ? AND ct.group_id = ?
+        ) as "exists!: bool"
+        "#,
+        token,
+        group_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if !authorized {
+        return Ok(None);
+    }
+
+    let group = sqlx::query!(
+        "SELECT name, rounding_strategy, currency FROM groups WHERE id = ?",
+        group_id
+    )
+    .fetch_one(pool)
+    .await?;
+    let group_name = group.name;
+    let rounding = group.rounding_strategy.parse::<RoundingStrategy>()?;
+    let currency = currency_symbol(&group.currency);
+
+    let debts = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!",
+            created_by,
+            name,
+            amount,
+            frequency,
+            frequency_interval,
+            end_date as "end_date: String",
+            next_generation_date as "next_generation_date!: String"
+        FROM recurring_debts
+        WHERE group_id = ? AND is_active = 1
+        ORDER BY next_generation_date
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Members and weights of every active recurring debt, for the per-share
+    // amounts in the event descriptions
+    let member_rows = sqlx::query!(
+        r#"
+        SELECT
+            rdu.recurring_debt_id as "recurring_debt_id!",
+            rdu.user_id as "user_id!",
+            u.username,
+            rdu.weight
+        FROM recurring_debt_user rdu
+        INNER JOIN recurring_debts rd ON rd.id = rdu.recurring_debt_id
+        INNER JOIN users u ON u.id = rdu.user_id
+        WHERE rd.group_id = ? AND rd.is_active = 1
+        ORDER BY u.username
+        "#,
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut members: HashMap<i64, Vec<(i64, String, i64)>> = HashMap::new();
+    for row in member_rows {
+        members.entry(row.recurring_debt_id).or_default().push((
+            row.user_id,
+            row.username,
+            row.weight,
+        ));
+    }
+
+    let now = time::OffsetDateTime::now_utc();
+    let today = app_today();
+    let stamp = format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Splitify//Recurring Debts//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!(
+            "X-WR-CALNAME:{}",
+            ics_escape(&format!("{} recurring debts", group_name))
+        ),
+    ];
+
+    for debt in debts {
+        let (Ok(frequency), Ok(next), Ok(amount)) = (
+            Frequency::from_columns(&debt.frequency, debt.frequency_interval),
+            Date::parse(&debt.next_generation_date, &Iso8601::DEFAULT),
+            debt.amount.parse::<Decimal>(),
+        ) else {
+            tracing::warn!(
+                recurring_debt_id = debt.id,
+                "Skipping malformed recurring debt in calendar feed"
+            );
+            continue;
+        };
+        let end_date = debt
+            .end_date
+            .as_deref()
+            .and_then(|d| Date::parse(d, &Iso8601::DEFAULT).ok());
+
+        let summary = ics_escape(&format!(
+            "{}: {}{:.2} ({})",
+            debt.name, currency, amount, group_name
+        ));
+        let description = ics_escape(&share_description(
+            amount,
+            members.get(&debt.id).map(Vec::as_slice).unwrap_or_default(),
+            rounding,
+            debt.created_by,
+            currency,
+        ));
+        for date in next_occurrences(next.max(today), &frequency, end_date, FEED_OCCURRENCES) {
+            let day = ics_date(date);
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:recurring-debt-{}-{}@splitify", debt.id, day));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("DTSTART;VALUE=DATE:{}", day));
+            lines.push(format!(
+                "DTEND;VALUE=DATE:{}",
+                ics_date(date.next_day().unwrap_or(date))
+            ));
+            lines.push(format!("SUMMARY:{}", summary));
+            lines.push(format!("DESCRIPTION:{}", description));
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut body = String::new();
+    for line in lines {
+        body.push_str(&fold_line(&line));
+        body.push_str("\r\n");
+    }
+
+    Ok(Some(body))
+}
+
+#[cfg(feature = "ssr")]
+fn ics_date(date: time::Date) -> String {
+    format!(
+        "{:04}{:02}{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+/// Event description with the total and each member's share, one per line,
+/// in the given currency symbol
+///
+/// `members` holds `(user_id, username, weight)` of the debt's members.
+#[cfg(feature = "ssr")]
+fn share_description(
+    amount: rust_decimal::Decimal,
+    members: &[(i64, String, i64)],
+    rounding: RoundingStrategy,
+    creator_id: i64,
+    currency: &str,
+) -> String {
+    use crate::features::shared_debts::utils::split_by_weight;
+
+    let weights: Vec<(i64, i64)> = members
+        .iter()
+        .map(|(user_id, _, weight)| (*user_id, *weight))
+        .collect();
+    let shares = split_by_weight(amount, &weights, rounding, creator_id);
+
+    let mut description = format!("Amount: {}{:.2}", currency, amount);
+    for ((_, username, _), (_, share)) in members.iter().zip(shares) {
+        description.push_str(&format!("\n{}: {}{:.2}", username, currency, share));
+    }
+    description
+}
+
+/// Fold content lines longer than 75 octets (RFC 5545 §3.1)
+#[cfg(feature = "ssr")]
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / 74 * 3);
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += len;
+    }
+    folded
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    #[test]
+    fn test_share_description() {
+        let members = vec![(1, "alice".to_string(), 2), (2, "bob".to_string(), 1)];
+        assert_eq!(
+            share_description(
+                Decimal::new(900, 0),
+                &members,
+                RoundingStrategy::DistributeCents,
+                1,
+                "€"
+            ),
+            "Amount: €900.00\nalice: €600.00\nbob: €300.00"
+        );
+        assert_eq!(
+            share_description(
+                Decimal::new(10, 0),
+                &[],
+                RoundingStrategy::DistributeCents,
+                1,
+                "£"
+            ),
+            "Amount: £10.00"
+        );
+    }
+}
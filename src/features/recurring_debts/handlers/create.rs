@@ -4,19 +4,36 @@ use leptos::prelude::*;
 #[cfg(feature = "ssr")]
 use leptos_axum::extract;
 #[cfg(feature = "ssr")]
-use rust_decimal::Decimal;
-#[cfg(feature = "ssr")]
-use time::Date;
-#[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
 #[cfg(feature = "ssr")]
-use crate::features::recurring_debts::models::Frequency;
+use crate::features::groups::{
+    models::{ActivityAction, ActivityEntity},
+    utils::{debt_summary, ensure_group_writable, group_currency, log_group_activity},
+};
+#[cfg(feature = "ssr")]
+use crate::features::recurring_debts::{
+    models::Frequency,
+    utils::{
+        app_today, parse_form_date, resolve_weights, today_at, utc_offset_from_minutes,
+        validate_start_date,
+    },
+};
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::DebtCategory;
+#[cfg(feature = "ssr")]
+use crate::validation::{
+    rules::{MAX_NAME_LENGTH, check_date_order},
+    validate_money, validate_name,
+};
 
 /// Server function: Create a new recurring debt
 #[server(CreateRecurringDebt)]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_recurring_debt(
     group_id: i64,
     name: String,
@@ -25,54 +42,50 @@ pub async fn create_recurring_debt(
     start_date: String,
     end_date: Option<String>,
     member_ids: Vec<i64>,
+    /// Client's UTC offset in minutes east of UTC, used to decide what "today"
+    /// is; falls back to APP_TIMEZONE
+    #[server(default)]
+    utc_offset_minutes: Option<i32>,
+    /// Relative weight per member; members without one get weight 1
+    #[server(default)]
+    weights: Option<Vec<(i64, String)>>,
+    /// One of the predefined categories, copied onto generated debts
+    #[server(default)]
+    category: Option<String>,
+    /// Add members who join the group later to this recurring debt
+    #[server(default)]
+    auto_include_new_members: bool,
 ) -> Result<i64, ServerFnError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
-
-    let user = get_user_from_session(&session)
-        .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
-
-    let pool = expect_context::<SqlitePool>();
-
     // Validate input
-    if name.trim().is_empty() {
-        return Err(ServerFnError::new("Name is required"));
-    }
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+    let amount_decimal = validate_money(&amount)?;
+    let amount = amount_decimal.to_string();
 
-    let amount_decimal = amount
-        .parse::<Decimal>()
-        .map_err(|_| ServerFnError::new("Invalid amount"))?;
+    let (frequency, frequency_interval) = frequency
+        .parse::<Frequency>()
+        .map_err(ServerFnError::new)?
+        .to_columns();
 
-    if amount_decimal <= Decimal::ZERO {
-        return Err(ServerFnError::new("Amount must be greater than 0"));
-    }
+    let category = DebtCategory::parse_optional(category.as_deref())
+        .map_err(ServerFnError::new)?
+        .map(|category| category.as_str());
 
-    let _frequency_enum = frequency.parse::<Frequency>().map_err(ServerFnError::new)?;
+    let start_date_parsed =
+        parse_form_date(&start_date, "start date").map_err(ServerFnError::new)?;
 
-    let start_date_parsed = Date::parse(
-        &start_date,
-        &time::format_description::well_known::Iso8601::DEFAULT,
-    )
-    .map_err(|_| ServerFnError::new("Invalid start date format (expected YYYY-MM-DD)"))?;
-
-    let today = time::OffsetDateTime::now_utc().date();
-    if start_date_parsed < today {
-        return Err(ServerFnError::new(
-            "Start date must be today or in the future",
-        ));
-    }
+    let today = utc_offset_minutes
+        .and_then(utc_offset_from_minutes)
+        .map_or_else(app_today, |offset| {
+            today_at(time::OffsetDateTime::now_utc(), offset)
+        });
+    validate_start_date(start_date_parsed, today).map_err(ServerFnError::new)?;
 
     let end_date_for_insert = end_date.clone();
     let _end_date_parsed = if let Some(ed) = end_date {
-        let parsed = Date::parse(&ed, &time::format_description::well_known::Iso8601::DEFAULT)
-            .map_err(|_| ServerFnError::new("Invalid end date format (expected YYYY-MM-DD)"))?;
-        if parsed <= start_date_parsed {
-            return Err(ServerFnError::new("End date must be after start date"));
-        }
+        let parsed = parse_form_date(&ed, "end date").map_err(ServerFnError::new)?;
+        check_date_order(start_date_parsed, parsed).map_err(ServerFnError::new)?;
         Some(parsed)
     } else {
         None
@@ -82,6 +95,19 @@ pub async fn create_recurring_debt(
         return Err(ServerFnError::new("At least one member must be selected"));
     }
 
+    let weights = resolve_weights(&member_ids, weights.as_deref().unwrap_or_default())
+        .map_err(ServerFnError::new)?;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
     // Check if user is a member of the group
     let is_member = sqlx::query!(
         "SELECT COUNT(*) as \"count!\" FROM group_members WHERE group_id = ? AND user_id = ?",
@@ -90,13 +116,14 @@ pub async fn create_recurring_debt(
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .count
         > 0;
 
     if !is_member {
         return Err(ServerFnError::new("Not authorized"));
     }
+    ensure_group_writable(&pool, group_id, user.id).await?;
 
     // Validate that all selected members are in the group
     for member_id in &member_ids {
@@ -107,7 +134,7 @@ pub async fn create_recurring_debt(
         )
         .fetch_one(&pool)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?
         .count
             > 0;
 
@@ -123,47 +150,65 @@ pub async fn create_recurring_debt(
     let mut tx = pool
         .begin()
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     // Insert recurring debt
     let recurring_debt_id = sqlx::query!(
         r#"
         INSERT INTO recurring_debts (
-            group_id, created_by, name, amount, frequency, 
-            start_date, end_date, next_generation_date, is_active
+            group_id, created_by, name, amount, frequency, frequency_interval,
+            start_date, end_date, next_generation_date, is_active, category,
+            auto_include_new_members
         )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)
         "#,
         group_id,
         user.id,
         name,
         amount,
         frequency,
+        frequency_interval,
         start_date,
         end_date_for_insert,
-        start_date // next_generation_date = start_date initially
+        start_date, // next_generation_date = start_date initially
+        category,
+        auto_include_new_members
     )
     .execute(&mut *tx)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .last_insert_rowid();
 
     // Insert members into pivot table
-    for member_id in member_ids {
+    for (member_id, weight) in weights {
         sqlx::query!(
-            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (?, ?)",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id, weight) VALUES (?, ?, ?)",
             recurring_debt_id,
-            member_id
+            member_id,
+            weight
         )
         .execute(&mut *tx)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
     }
 
+    let currency = group_currency(&mut *tx, group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        group_id,
+        user.id,
+        ActivityEntity::RecurringDebt,
+        recurring_debt_id,
+        ActivityAction::Created,
+        &debt_summary(&name, amount_decimal, &currency),
+    )
+    .await?;
+
     // Commit transaction
     tx.commit()
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(recurring_debt_id)
 }
@@ -7,7 +7,15 @@ use leptos_axum::extract;
 use tower_sessions::Session;
 
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        models::{ActivityAction, ActivityEntity},
+        utils::{debt_summary, ensure_group_writable, group_currency, log_group_activity},
+    },
+};
 
 /// Server function: Delete a recurring debt
 #[server(DeleteRecurringDebt)]
@@ -27,7 +35,7 @@ pub async fn delete_recurring_debt(recurring_debt_id: i64) -> Result<(), ServerF
     // Get the recurring debt and verify user is the creator
     let debt = sqlx::query!(
         r#"
-        SELECT rd.id, rd.created_by
+        SELECT rd.id, rd.created_by, rd.group_id, rd.name, rd.amount
         FROM recurring_debts rd
         WHERE rd.id = ?
         "#,
@@ -35,7 +43,7 @@ pub async fn delete_recurring_debt(recurring_debt_id: i64) -> Result<(), ServerF
     )
     .fetch_optional(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .ok_or_else(|| ServerFnError::new("Recurring debt not found"))?;
 
     if debt.created_by != user.id {
@@ -43,6 +51,17 @@ pub async fn delete_recurring_debt(recurring_debt_id: i64) -> Result<(), ServerF
             "Only the creator can delete this recurring debt",
         ));
     }
+    ensure_group_writable(&pool, debt.group_id, user.id).await?;
+
+    let amount = debt
+        .amount
+        .parse::<rust_decimal::Decimal>()
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     // Delete the recurring debt (cascade will handle pivot table and set NULL on
     // shared_debts)
@@ -50,9 +69,26 @@ pub async fn delete_recurring_debt(recurring_debt_id: i64) -> Result<(), ServerF
         "DELETE FROM recurring_debts WHERE id = ?",
         recurring_debt_id
     )
-    .execute(&pool)
+    .execute(&mut *tx)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    let currency = group_currency(&mut *tx, debt.group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        debt.group_id,
+        user.id,
+        ActivityEntity::RecurringDebt,
+        recurring_debt_id,
+        ActivityAction::Deleted,
+        &debt_summary(&debt.name, amount, &currency),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(())
 }
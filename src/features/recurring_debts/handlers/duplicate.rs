@@ -0,0 +1,230 @@
+//! Duplicating recurring debts
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::shared_debts::models::DuplicatedDebt;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        models::{ActivityAction, ActivityEntity},
+        utils::{debt_summary, ensure_group_writable, group_currency, log_group_activity},
+    },
+    shared_debts::utils::copy_name,
+};
+
+/// Server function: Copy a recurring debt into a new one created by the
+/// caller
+///
+/// The copy keeps the amount, category, schedule and members with their
+/// weights; members who have left the group since are dropped and reported
+/// in the result. It starts paused, so nothing is generated twice before the
+/// copy has been edited and resumed.
+#[server(DuplicateRecurringDebt)]
+pub async fn duplicate_recurring_debt(recurring_debt_id: i64) -> Result<DuplicatedDebt, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let mut tx = pool.begin().await?;
+    let duplicate = copy_recurring_debt(&mut tx, recurring_debt_id, user.id).await?;
+    tx.commit().await?;
+
+    Ok(duplicate)
+}
+
+/// Insert a paused copy of `recurring_debt_id` created by `user_id`
+#[cfg(feature = "ssr")]
+async fn copy_recurring_debt(
+    conn: &mut sqlx::SqliteConnection,
+    recurring_debt_id: i64,
+    user_id: i64,
+) -> Result<DuplicatedDebt, AppError> {
+    let debt = sqlx::query!(
+        r#"
+        SELECT group_id, name, amount, frequency, frequency_interval, start_date as "start_date!: String",
+               end_date as "end_date: String", next_generation_date as "next_generation_date!: String",
+               category, auto_include_new_members as "auto_include_new_members!: bool"
+        FROM recurring_debts
+        WHERE id = ?
+        "#,
+        recurring_debt_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("Recurring debt not found"))?;
+
+    let is_member = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = ? AND user_id = ?) as "exists!: bool""#,
+        debt.group_id,
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if !is_member {
+        return Err(AppError::forbidden(
+            "You don't have permission to access this group",
+        ));
+    }
+    ensure_group_writable(&mut *conn, debt.group_id, user_id).await?;
+
+    let members = sqlx::query!(
+        r#"
+        SELECT
+            rdu.user_id,
+            u.username,
+            rdu.weight,
+            EXISTS(
+                SELECT 1 FROM group_members gm
+                WHERE gm.group_id = ? AND gm.user_id = rdu.user_id
+            ) as "is_member!: bool"
+        FROM recurring_debt_user rdu
+        INNER JOIN users u ON u.id = rdu.user_id
+        WHERE rdu.recurring_debt_id = ?
+        ORDER BY rdu.id
+        "#,
+        debt.group_id,
+        recurring_debt_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let (kept, dropped): (Vec<_>, Vec<_>) =
+        members.into_iter().partition(|member| member.is_member);
+    if kept.is_empty() {
+        return Err(AppError::validation(
+            "None of the recurring debt's members are still in the group",
+        ));
+    }
+
+    let name = copy_name(&debt.name);
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO recurring_debts (
+            group_id, created_by, name, amount, frequency, frequency_interval,
+            start_date, end_date, next_generation_date, is_active, category,
+            auto_include_new_members
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 0, ?, ?)
+        "#,
+        debt.group_id,
+        user_id,
+        name,
+        debt.amount,
+        debt.frequency,
+        debt.frequency_interval,
+        debt.start_date,
+        debt.end_date,
+        debt.next_generation_date,
+        debt.category,
+        debt.auto_include_new_members
+    )
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    for member in &kept {
+        sqlx::query!(
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id, weight) VALUES (?, ?, ?)",
+            id,
+            member.user_id,
+            member.weight
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    let amount = debt
+        .amount
+        .parse::<rust_decimal::Decimal>()
+        .map_err(AppError::internal)?;
+    let currency = group_currency(&mut *conn, debt.group_id).await?;
+    log_group_activity(
+        &mut *conn,
+        debt.group_id,
+        user_id,
+        ActivityEntity::RecurringDebt,
+        id,
+        ActivityAction::Created,
+        &debt_summary(&name, amount, &currency),
+    )
+    .await?;
+
+    Ok(DuplicatedDebt {
+        id,
+        dropped_members: dropped.into_iter().map(|member| member.username).collect(),
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_copy_starts_paused_without_members_who_left() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, frequency_interval, start_date, next_generation_date, category) VALUES (1, 1, 'Cleaning', '60', 'weekly', 2, '2026-01-05', '2026-03-02', 'Utilities')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id, weight) VALUES (1, 1, 2), (1, 2, 1), (1, 3, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+
+        let duplicate = copy_recurring_debt(&mut conn, 1, 2).await.unwrap();
+        assert_eq!(duplicate.dropped_members, vec!["carol".to_string()]);
+
+        let row: (String, i64, String, i64, String, bool, Option<String>) = sqlx::query_as(
+            "SELECT name, created_by, frequency, frequency_interval, next_generation_date, is_active, category FROM recurring_debts WHERE id = ?",
+        )
+        .bind(duplicate.id)
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            row,
+            (
+                "Cleaning (copy)".to_string(),
+                2,
+                "weekly".to_string(),
+                2,
+                "2026-03-02".to_string(),
+                false,
+                Some("Utilities".to_string())
+            )
+        );
+        let weights: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT user_id, weight FROM recurring_debt_user WHERE recurring_debt_id = ? ORDER BY user_id",
+        )
+        .bind(duplicate.id)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(weights, vec![(1, 2), (2, 1)]);
+
+        let err = copy_recurring_debt(&mut conn, 1, 3).await.unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+    }
+}
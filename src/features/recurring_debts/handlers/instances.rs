@@ -10,13 +10,26 @@ use time::Date;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+#[cfg(feature = "ssr")]
+use crate::db::with_busy_retry;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
 #[cfg(feature = "ssr")]
+use crate::features::groups::utils::ensure_group_writable;
+#[cfg(feature = "ssr")]
 use crate::features::recurring_debts::models::Frequency;
 use crate::features::recurring_debts::models::GeneratedInstance;
 #[cfg(feature = "ssr")]
-use crate::features::recurring_debts::utils::calculate_next_occurrence;
+use crate::features::recurring_debts::{
+    handlers::scheduler::{dispatch_generated_debt, insert_generated_debt},
+    utils::{calculate_next_occurrence, generated_share_amounts},
+};
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::RoundingStrategy;
+#[cfg(feature = "ssr")]
+use crate::features::webhooks::WebhookDispatcher;
 
 /// Server function: Get generated instances (SharedDebts) from a recurring debt
 #[server(GetGeneratedInstances)]
@@ -48,7 +61,7 @@ pub async fn get_generated_instances(
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .count
         > 0;
 
@@ -72,7 +85,7 @@ pub async fn get_generated_instances(
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     let mut result = Vec::new();
     for instance in instances {
@@ -106,7 +119,23 @@ pub async fn generate_now(recurring_debt_id: i64) -> Result<i64, ServerFnError>
         .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
 
     let pool = expect_context::<SqlitePool>();
+    let webhooks = expect_context::<WebhookDispatcher>();
+
+    generate_next_instance(&pool, &webhooks, recurring_debt_id, user.id).await
+}
 
+/// Generate the instance for a recurring debt's next generation date on
+/// behalf of `user_id`, who must be its creator and not a viewer
+///
+/// If the scheduler generated that period first, the existing instance is
+/// returned instead of creating a duplicate.
+#[cfg(feature = "ssr")]
+async fn generate_next_instance(
+    pool: &sqlx::SqlitePool,
+    webhooks: &WebhookDispatcher,
+    recurring_debt_id: i64,
+    user_id: i64,
+) -> Result<i64, ServerFnError> {
     // Get the recurring debt and verify user is the creator
     let debt = sqlx::query!(
         r#"
@@ -117,23 +146,28 @@ pub async fn generate_now(recurring_debt_id: i64) -> Result<i64, ServerFnError>
             rd.name,
             rd.amount,
             rd.frequency,
+            rd.frequency_interval,
             rd.next_generation_date as "next_generation_date!: String",
-            rd.is_active as "is_active!: bool"
+            rd.is_active as "is_active!: bool",
+            rd.category,
+            g.rounding_strategy
         FROM recurring_debts rd
+        INNER JOIN groups g ON g.id = rd.group_id
         WHERE rd.id = ?
         "#,
         recurring_debt_id
     )
-    .fetch_optional(&pool)
+    .fetch_optional(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .ok_or_else(|| ServerFnError::new("Recurring debt not found"))?;
 
-    if debt.created_by != user.id {
+    if debt.created_by != user_id {
         return Err(ServerFnError::new(
             "Only the creator can manually generate debts",
         ));
     }
+    ensure_group_writable(pool, debt.group_id, user_id).await?;
 
     if !debt.is_active {
         return Err(ServerFnError::new(
@@ -141,9 +175,7 @@ pub async fn generate_now(recurring_debt_id: i64) -> Result<i64, ServerFnError>
         ));
     }
 
-    let frequency = debt
-        .frequency
-        .parse::<Frequency>()
+    let frequency = Frequency::from_columns(&debt.frequency, debt.frequency_interval)
         .map_err(ServerFnError::new)?;
 
     let next_generation_date = Date::parse(
@@ -155,77 +187,302 @@ pub async fn generate_now(recurring_debt_id: i64) -> Result<i64, ServerFnError>
     // Calculate new next_generation_date
     let new_next_date = calculate_next_occurrence(next_generation_date, &frequency);
 
-    // Get members of the recurring debt
+    // Get members of the recurring debt; anyone who left the group since
+    // doesn't get a share
     let members = sqlx::query!(
         r#"
-        SELECT user_id as "user_id!"
+        SELECT user_id as "user_id!", weight
         FROM recurring_debt_user
         WHERE recurring_debt_id = ?
+          AND user_id IN (SELECT user_id FROM group_members WHERE group_id = ?)
         "#,
-        recurring_debt_id
+        recurring_debt_id,
+        debt.group_id
     )
-    .fetch_all(&pool)
+    .fetch_all(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
-    let member_ids: Vec<i64> = members.into_iter().map(|m| m.user_id).collect();
+    let weights: Vec<(i64, i64)> = members.into_iter().map(|m| (m.user_id, m.weight)).collect();
 
-    if member_ids.is_empty() {
+    if weights.is_empty() {
         return Err(ServerFnError::new(
             "No members found for this recurring debt",
         ));
     }
 
-    // Begin transaction
-    let mut tx = pool
-        .begin()
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let amount = debt
+        .amount
+        .parse::<Decimal>()
+        .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
+    let rounding = debt
+        .rounding_strategy
+        .parse::<RoundingStrategy>()
+        .map_err(ServerFnError::new)?;
 
-    // Create SharedDebt
-    let shared_debt_id = sqlx::query!(
-        r#"
-        INSERT INTO shared_debts (group_id, created_by, name, amount, recurring_debt_id)
-        VALUES (?, ?, ?, ?, ?)
-        "#,
-        debt.group_id,
-        debt.created_by,
-        debt.name,
-        debt.amount,
-        recurring_debt_id
-    )
-    .execute(&mut *tx)
+    let new_next_date_str = new_next_date.to_string();
+    let member_shares = generated_share_amounts(amount, &weights, rounding, debt.created_by);
+    // The scheduler may be writing the same period, retry like it does
+    // instead of handing a busy database to the user
+    let generated = with_busy_retry(|| {
+        insert_generated_debt(
+            pool,
+            &debt.name,
+            &debt.amount,
+            debt.category.as_deref(),
+            debt.group_id,
+            debt.created_by,
+            recurring_debt_id,
+            &member_shares,
+            &debt.next_generation_date,
+            &new_next_date_str,
+        )
+    })
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .last_insert_rowid();
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
-    // Insert members into shared_debt_user
-    for member_id in member_ids {
-        sqlx::query!(
-            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (?, ?)",
+    if let Some(shared_debt_id) = generated {
+        dispatch_generated_debt(
+            webhooks,
+            debt.group_id,
             shared_debt_id,
-            member_id
-        )
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+            recurring_debt_id,
+            &debt.name,
+            &debt.amount,
+        );
+        return Ok(shared_debt_id);
     }
 
-    // Update next_generation_date
-    let new_next_date_str = new_next_date.to_string();
-    sqlx::query!(
-        "UPDATE recurring_debts SET next_generation_date = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-        new_next_date_str,
-        recurring_debt_id
+    // Already generated for this period, e.g. by the scheduler
+    sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM shared_debts WHERE recurring_debt_id = ? AND generation_period = ?"#,
+        recurring_debt_id,
+        debt.next_generation_date
     )
-    .execute(&mut *tx)
+    .fetch_optional(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .ok_or_else(|| {
+        ServerFnError::new("This recurring debt changed while generating, please try again")
+    })
+}
 
-    // Commit transaction
-    tx.commit()
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        db::{DbConfig, connect_pool},
+        features::{
+            recurring_debts::{
+                handlers::scheduler::process_due_recurring_debts_internal, utils::app_today,
+            },
+            webhooks::create_webhook_dispatcher,
+        },
+    };
+
+    #[tokio::test]
+    async fn test_generate_now_racing_scheduler_generates_period_once() {
+        // A file database so both paths really run on separate connections
+        let path = std::env::temp_dir().join(format!(
+            "splitify-generate-test-{}.db",
+            uuid::Uuid::new_v4()
+        ));
+        let config = DbConfig {
+            database_url: format!("sqlite:{}?mode=rwc", path.display()),
+            max_connections: 4,
+            busy_timeout: Duration::from_secs(5),
+        };
+        let pool = connect_pool(&config).await.unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        sqlx::query("INSERT INTO users (username, password_hash) VALUES ('alice', 'x')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Flat', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES (1, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let webhooks = create_webhook_dispatcher(pool.clone());
+        let today = app_today();
+        let today_str = today.to_string();
+
+        for _ in 0..5 {
+            let recurring_debt_id = sqlx::query(
+                r#"
+                INSERT INTO recurring_debts
+                    (group_id, created_by, name, amount, frequency, start_date, next_generation_date)
+                VALUES (1, 1, 'Rent', '900', 'monthly', ?, ?)
+                "#,
+            )
+            .bind(&today_str)
+            .bind(&today_str)
+            .execute(&pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            sqlx::query(
+                "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (?, 1)",
+            )
+            .bind(recurring_debt_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let (generated, processed) = tokio::join!(
+                generate_next_instance(&pool, &webhooks, recurring_debt_id, 1),
+                process_due_recurring_debts_internal(pool.clone(), webhooks.clone())
+            );
+            let shared_debt_id = generated.unwrap();
+            processed.unwrap();
+
+            // Whoever lost the race reused the winner's instance
+            let instances_today: Vec<i64> = sqlx::query_scalar(
+                "SELECT id FROM shared_debts WHERE recurring_debt_id = ? AND generation_period = ?",
+            )
+            .bind(recurring_debt_id)
+            .bind(&today_str)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+            assert_eq!(instances_today.len(), 1);
+
+            // "Generate Now" may legitimately run after the scheduler and
+            // produce next month's instance, but the date only ever advances
+            // once per generated period
+            let periods: Vec<String> = sqlx::query_scalar(
+                "SELECT generation_period FROM shared_debts WHERE recurring_debt_id = ? ORDER BY generation_period",
+            )
+            .bind(recurring_debt_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+            let mut expected_next = today;
+            for _ in &periods {
+                expected_next = calculate_next_occurrence(expected_next, &Frequency::Monthly);
+            }
+            let next: String =
+                sqlx::query_scalar("SELECT next_generation_date FROM recurring_debts WHERE id = ?")
+                    .bind(recurring_debt_id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(next, expected_next.to_string());
+
+            let shared_debt_ids: Vec<i64> =
+                sqlx::query_scalar("SELECT id FROM shared_debts WHERE recurring_debt_id = ?")
+                    .bind(recurring_debt_id)
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap();
+            assert!(shared_debt_ids.contains(&shared_debt_id));
+        }
+
+        pool.close().await;
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_viewer_cannot_generate_now() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // bob created the debt before an admin made him a viewer
+        let today = app_today().to_string();
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'viewer')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO recurring_debts
+                (group_id, created_by, name, amount, frequency, start_date, next_generation_date)
+            VALUES (1, 2, 'Rent', '900', 'monthly', ?, ?)
+            "#,
+        )
+        .bind(&today)
+        .bind(&today)
+        .execute(&pool)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .unwrap();
 
-    Ok(shared_debt_id)
+        let webhooks = create_webhook_dispatcher(pool.clone());
+        let err = generate_next_instance(&pool, &webhooks, 1, 2)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Viewers"));
+
+        let generated: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM shared_debts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(generated, 0);
+    }
+
+    #[tokio::test]
+    async fn test_generate_now_skips_members_who_left() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // bob was part of the debt but has since left the group
+        let today = app_today().to_string();
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO recurring_debts
+                (group_id, created_by, name, amount, frequency, start_date, next_generation_date)
+            VALUES (1, 1, 'Rent', '900', 'monthly', ?, ?)
+            "#,
+        )
+        .bind(&today)
+        .bind(&today)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (1, 2)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let webhooks = create_webhook_dispatcher(pool.clone());
+        let shared_debt_id = generate_next_instance(&pool, &webhooks, 1, 1)
+            .await
+            .unwrap();
+
+        let charged: Vec<i64> =
+            sqlx::query_scalar("SELECT user_id FROM shared_debt_user WHERE shared_debt_id = ?")
+                .bind(shared_debt_id)
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(charged, vec![1]);
+    }
 }
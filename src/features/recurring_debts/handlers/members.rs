@@ -6,6 +6,8 @@ use leptos_axum::extract;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
 
@@ -37,7 +39,7 @@ pub async fn get_recurring_debt_members(recurring_debt_id: i64) -> Result<Vec<i6
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .count
         > 0;
 
@@ -56,7 +58,33 @@ pub async fn get_recurring_debt_members(recurring_debt_id: i64) -> Result<Vec<i6
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(members.into_iter().map(|m| m.user_id).collect())
 }
+
+/// Add a user who just joined a group to each of its recurring debts that
+/// include new members automatically, with weight 1
+///
+/// Only future instances are affected; debts generated before stay as they
+/// are. Pass the executor of the transaction that adds the member.
+#[cfg(feature = "ssr")]
+pub async fn include_new_member_in_recurring_debts(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+    user_id: i64,
+) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        INSERT OR IGNORE INTO recurring_debt_user (recurring_debt_id, user_id)
+        SELECT id, ? FROM recurring_debts
+        WHERE group_id = ? AND auto_include_new_members = 1
+        "#,
+        user_id,
+        group_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
@@ -1,18 +1,28 @@
+mod calendar;
 mod create;
 mod delete;
+mod duplicate;
 mod instances;
 mod members;
+mod preview;
 mod query;
 pub mod scheduler;
 mod shares;
+mod skip;
+mod status;
 mod toggle;
 mod update;
 
+pub use calendar::*;
 pub use create::*;
 pub use delete::*;
+pub use duplicate::*;
 pub use instances::*;
 pub use members::*;
+pub use preview::*;
 pub use query::*;
 pub use shares::*;
+pub use skip::*;
+pub use status::*;
 pub use toggle::*;
 pub use update::*;
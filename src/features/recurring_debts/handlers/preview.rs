@@ -0,0 +1,234 @@
+//! Preview of upcoming recurring debt generations
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use time::Date;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+#[cfg(feature = "ssr")]
+use super::shares::recurring_debt_member_shares;
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+use crate::features::recurring_debts::models::OccurrencePreview;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    recurring_debts::{
+        models::{Frequency, PreviewOccurrence},
+        utils::next_occurrences,
+    },
+};
+
+/// Most occurrences a preview lists
+pub const MAX_PREVIEW_OCCURRENCES: i64 = 12;
+
+/// Server function: The next `count` (at most 12) dates a recurring debt will
+/// generate a debt on, with each member's share
+///
+/// Empty with a reason while the debt is paused or after it ended.
+#[server(PreviewOccurrences)]
+pub async fn preview_occurrences(
+    recurring_debt_id: i64,
+    count: i64,
+) -> Result<OccurrencePreview, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let has_access = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM recurring_debts rd
+        INNER JOIN group_members gm ON rd.group_id = gm.group_id
+        WHERE rd.id = ? AND gm.user_id = ?
+        "#,
+        recurring_debt_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+        > 0;
+
+    if !has_access {
+        return Err(ServerFnError::new("Not authorized"));
+    }
+
+    let count = count.clamp(1, MAX_PREVIEW_OCCURRENCES);
+    compute_occurrence_preview(&pool, recurring_debt_id, count as usize).await
+}
+
+#[cfg(feature = "ssr")]
+fn parse_date(date: &str) -> Result<Date, ServerFnError> {
+    Date::parse(
+        date,
+        &time::format_description::well_known::Iso8601::DEFAULT,
+    )
+    .map_err(|e| ServerFnError::new(format!("Invalid date: {}", e)))
+}
+
+/// Up to `count` upcoming generations of a recurring debt, without access
+/// checks
+///
+/// Dates follow the scheduler, so a monthly debt on the 31st moves to the last
+/// day of shorter months.
+#[cfg(feature = "ssr")]
+pub async fn compute_occurrence_preview(
+    pool: &sqlx::SqlitePool,
+    recurring_debt_id: i64,
+    count: usize,
+) -> Result<OccurrencePreview, ServerFnError> {
+    let debt = sqlx::query!(
+        r#"
+        SELECT amount, frequency, frequency_interval, is_active as "is_active!: bool",
+            next_generation_date as "next_generation_date!: String",
+            end_date as "end_date: String"
+        FROM recurring_debts
+        WHERE id = ?
+        "#,
+        recurring_debt_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .ok_or_else(|| ServerFnError::new("Recurring debt not found"))?;
+
+    let next = parse_date(&debt.next_generation_date)?;
+    let end_date = debt.end_date.as_deref().map(parse_date).transpose()?;
+
+    let reason = if !debt.is_active {
+        Some("Paused; resume it to schedule new debts".to_string())
+    } else {
+        end_date
+            .filter(|end_date| next > *end_date)
+            .map(|end_date| format!("Ended on {}", end_date))
+    };
+    if reason.is_some() {
+        return Ok(OccurrencePreview {
+            occurrences: Vec::new(),
+            reason,
+        });
+    }
+
+    let frequency = Frequency::from_columns(&debt.frequency, debt.frequency_interval)
+        .map_err(ServerFnError::new)?;
+    let amount = debt
+        .amount
+        .parse::<Decimal>()
+        .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
+    let shares = recurring_debt_member_shares(pool, recurring_debt_id, amount).await?;
+
+    let occurrences = next_occurrences(next, &frequency, end_date, count)
+        .into_iter()
+        .map(|date| PreviewOccurrence {
+            date,
+            shares: shares.clone(),
+        })
+        .collect();
+
+    Ok(OccurrencePreview {
+        occurrences,
+        reason: None,
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+    use time::Month;
+
+    use super::*;
+
+    fn date(month: Month, day: u8) -> Date {
+        Date::from_calendar_date(2026, month, day).unwrap()
+    }
+
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, end_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-01-31', '2026-05-15', '2026-01-31')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id, weight) VALUES (1, 1, 2), (1, 2, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_preview_follows_month_ends_and_stops_at_end_date() {
+        let pool = setup().await;
+
+        let preview = compute_occurrence_preview(&pool, 1, 12).await.unwrap();
+        assert_eq!(preview.reason, None);
+        let dates: Vec<Date> = preview
+            .occurrences
+            .iter()
+            .map(|occurrence| occurrence.date)
+            .collect();
+        assert_eq!(
+            dates,
+            vec![
+                date(Month::January, 31),
+                date(Month::February, 28),
+                date(Month::March, 28),
+                date(Month::April, 28),
+            ]
+        );
+        let shares: Vec<(&str, Decimal)> = preview.occurrences[0]
+            .shares
+            .iter()
+            .map(|share| (share.username.as_str(), share.share_amount))
+            .collect();
+        assert_eq!(
+            shares,
+            vec![("alice", Decimal::from(600)), ("bob", Decimal::from(300))]
+        );
+
+        let preview = compute_occurrence_preview(&pool, 1, 2).await.unwrap();
+        assert_eq!(preview.occurrences.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_paused_and_ended_debts_give_a_reason() {
+        let pool = setup().await;
+
+        sqlx::query("UPDATE recurring_debts SET is_active = 0")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let preview = compute_occurrence_preview(&pool, 1, 12).await.unwrap();
+        assert!(preview.occurrences.is_empty());
+        assert!(preview.reason.unwrap().starts_with("Paused"));
+
+        sqlx::query(
+            "UPDATE recurring_debts SET is_active = 1, next_generation_date = '2026-05-28'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let preview = compute_occurrence_preview(&pool, 1, 12).await.unwrap();
+        assert!(preview.occurrences.is_empty());
+        assert_eq!(preview.reason.as_deref(), Some("Ended on 2026-05-15"));
+    }
+}
@@ -11,10 +11,14 @@ use time::Date;
 use tower_sessions::Session;
 
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
-use crate::features::recurring_debts::models::Frequency;
+use crate::features::auth::utils::get_user_from_session;
 use crate::features::recurring_debts::models::RecurringDebtWithDetails;
+#[cfg(feature = "ssr")]
+use crate::features::recurring_debts::{models::Frequency, utils::app_today};
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::DebtCategory;
 
 /// Server function: Get all recurring debts for a group
 #[server(GetRecurringDebts)]
@@ -41,7 +45,7 @@ pub async fn get_recurring_debts(
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .count
         > 0;
 
@@ -49,7 +53,20 @@ pub async fn get_recurring_debts(
         return Err(ServerFnError::new("Not authorized"));
     }
 
-    let today = time::OffsetDateTime::now_utc().date();
+    load_recurring_debts(&pool, group_id, user.id).await
+}
+
+/// Load a group's recurring debts as seen by `user_id`, newest first
+///
+/// Callers are responsible for verifying that the user is a member of the
+/// group.
+#[cfg(feature = "ssr")]
+pub async fn load_recurring_debts(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<Vec<RecurringDebtWithDetails>, ServerFnError> {
+    let today = app_today();
 
     // Get all recurring debts for the group
     let debts = sqlx::query!(
@@ -61,10 +78,14 @@ pub async fn get_recurring_debts(
             rd.name,
             rd.amount,
             rd.frequency,
+            rd.frequency_interval,
             rd.start_date as "start_date!: String",
             rd.end_date as "end_date: String",
             rd.next_generation_date as "next_generation_date!: String",
             rd.is_active as "is_active!: bool",
+            rd.category,
+            rd.auto_include_new_members as "auto_include_new_members!: bool",
+            rd.version,
             rd.created_at,
             rd.updated_at,
             u.username as creator_username,
@@ -74,12 +95,12 @@ pub async fn get_recurring_debts(
         WHERE rd.group_id = ?
         ORDER BY rd.created_at DESC
         "#,
-        user.id,
+        user_id,
         group_id
     )
-    .fetch_all(&pool)
+    .fetch_all(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     let mut result = Vec::new();
     for debt in debts {
@@ -88,10 +109,10 @@ pub async fn get_recurring_debts(
             .parse::<Decimal>()
             .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
 
-        let frequency = debt
-            .frequency
-            .parse::<Frequency>()
+        let frequency = Frequency::from_columns(&debt.frequency, debt.frequency_interval)
             .map_err(ServerFnError::new)?;
+        let category =
+            DebtCategory::parse_optional(debt.category.as_deref()).map_err(ServerFnError::new)?;
 
         let start_date = Date::parse(
             &debt.start_date,
@@ -134,6 +155,7 @@ pub async fn get_recurring_debts(
             creator_username: debt.creator_username,
             name: debt.name,
             amount,
+            category,
             frequency,
             start_date,
             end_date,
@@ -143,6 +165,8 @@ pub async fn get_recurring_debts(
             updated_at: debt.updated_at,
             is_creator: debt.is_creator,
             status,
+            auto_include_new_members: debt.auto_include_new_members,
+            version: debt.version,
         });
     }
 
@@ -166,7 +190,7 @@ pub async fn get_recurring_debt(
 
     let pool = expect_context::<SqlitePool>();
 
-    let today = time::OffsetDateTime::now_utc().date();
+    let today = app_today();
 
     // Get the recurring debt and verify user has access
     let debt = sqlx::query!(
@@ -178,10 +202,14 @@ pub async fn get_recurring_debt(
             rd.name,
             rd.amount,
             rd.frequency,
+            rd.frequency_interval,
             rd.start_date as "start_date!: String",
             rd.end_date as "end_date: String",
             rd.next_generation_date as "next_generation_date!: String",
             rd.is_active as "is_active!: bool",
+            rd.category,
+            rd.auto_include_new_members as "auto_include_new_members!: bool",
+            rd.version,
             rd.created_at,
             rd.updated_at,
             u.username as creator_username,
@@ -197,7 +225,7 @@ pub async fn get_recurring_debt(
     )
     .fetch_optional(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .ok_or_else(|| ServerFnError::new("Recurring debt not found or access denied"))?;
 
     let amount = debt
@@ -205,10 +233,10 @@ pub async fn get_recurring_debt(
         .parse::<Decimal>()
         .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
 
-    let frequency = debt
-        .frequency
-        .parse::<Frequency>()
+    let frequency = Frequency::from_columns(&debt.frequency, debt.frequency_interval)
         .map_err(ServerFnError::new)?;
+    let category =
+        DebtCategory::parse_optional(debt.category.as_deref()).map_err(ServerFnError::new)?;
 
     let start_date = Date::parse(
         &debt.start_date,
@@ -251,6 +279,7 @@ pub async fn get_recurring_debt(
         creator_username: debt.creator_username,
         name: debt.name,
         amount,
+        category,
         frequency,
         start_date,
         end_date,
@@ -260,5 +289,7 @@ pub async fn get_recurring_debt(
         updated_at: debt.updated_at,
         is_creator: debt.is_creator,
         status,
+        auto_include_new_members: debt.auto_include_new_members,
+        version: debt.version,
     })
 }
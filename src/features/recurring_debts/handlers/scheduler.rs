@@ -3,55 +3,202 @@ use rust_decimal::Decimal;
 #[cfg(feature = "ssr")]
 use time::Date;
 
+#[cfg(feature = "ssr")]
+use crate::db::with_busy_retry;
+#[cfg(feature = "ssr")]
+use crate::features::groups::utils::proposed_debt_status;
+#[cfg(feature = "ssr")]
+use crate::features::notifications::{NotificationPayload, notify_users};
 #[cfg(feature = "ssr")]
 use crate::features::recurring_debts::models::{Frequency, RecurringDebt};
 #[cfg(feature = "ssr")]
-use crate::features::recurring_debts::utils::{calculate_next_occurrence, should_generate};
+use crate::features::recurring_debts::utils::{
+    calculate_next_occurrence, generated_share_amounts, group_today_at, should_generate, today_at,
+};
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::RoundingStrategy;
+#[cfg(feature = "ssr")]
+use crate::features::transactions::balances::add_shared_debt_to_balances;
+#[cfg(feature = "ssr")]
+use crate::features::webhooks::{WebhookDispatcher, WebhookEvent};
+
+/// Cron expression used when RECURRING_DEBTS_CRON is not set (daily at
+/// 6:00 AM UTC)
+#[cfg(feature = "ssr")]
+pub const DEFAULT_RECURRING_DEBTS_CRON: &str = "0 0 6 * * *";
+
+/// Number of scheduler runs kept in the scheduler_runs table
+#[cfg(feature = "ssr")]
+const SCHEDULER_RUNS_RETAINED: i64 = 100;
+
+/// The configured cron expression for the recurring debts job
+#[cfg(feature = "ssr")]
+pub fn recurring_debts_cron_expression() -> String {
+    std::env::var("RECURRING_DEBTS_CRON")
+        .unwrap_or_else(|_| DEFAULT_RECURRING_DEBTS_CRON.to_string())
+}
+
+/// Next time a cron expression fires strictly after `after` (UTC)
+///
+/// Parses with the same options as tokio-cron-scheduler. Returns `None` for
+/// an invalid expression.
+#[cfg(feature = "ssr")]
+pub fn next_cron_run(
+    expression: &str,
+    after: time::OffsetDateTime,
+) -> Option<time::OffsetDateTime> {
+    let cron = croner::parser::CronParser::builder()
+        .seconds(croner::parser::Seconds::Required)
+        .dom_and_dow(true)
+        .build()
+        .parse(expression)
+        .ok()?;
+    let after = chrono::DateTime::<chrono::Utc>::from_timestamp(after.unix_timestamp(), 0)?;
+    let next = cron.find_next_occurrence(&after, false).ok()?;
+    time::OffsetDateTime::from_unix_timestamp(next.timestamp()).ok()
+}
 
+/// Generate due recurring debts and record the run in scheduler_runs
+///
+/// Failing to record the run is logged but does not fail the generation.
 #[cfg(feature = "ssr")]
 pub async fn process_due_recurring_debts_internal(
     pool: sqlx::SqlitePool,
+    webhooks: WebhookDispatcher,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let started_at = time::OffsetDateTime::now_utc();
+    let run_id = match with_busy_retry(|| record_run_start(&pool, started_at)).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to record scheduler run start");
+            None
+        }
+    };
+
+    let result = generate_due_recurring_debts(&pool, &webhooks, started_at).await;
+
+    if let Some(run_id) = run_id {
+        let (generated_count, error) = match &result {
+            Ok(count) => (*count as i64, None),
+            Err(e) => (0, Some(e.to_string())),
+        };
+        if let Err(e) =
+            with_busy_retry(|| record_run_finish(&pool, run_id, generated_count, error.as_deref()))
+                .await
+        {
+            tracing::warn!(error = %e, run_id = run_id, "Failed to record scheduler run result");
+        }
+    }
+
+    result
+}
+
+#[cfg(feature = "ssr")]
+async fn record_run_start(
+    pool: &sqlx::SqlitePool,
+    started_at: time::OffsetDateTime,
+) -> Result<i64, sqlx::Error> {
+    let id = sqlx::query!(
+        "INSERT INTO scheduler_runs (started_at) VALUES (?)",
+        started_at
+    )
+    .execute(pool)
+    .await?
+    .last_insert_rowid();
+    Ok(id)
+}
+
+#[cfg(feature = "ssr")]
+async fn record_run_finish(
+    pool: &sqlx::SqlitePool,
+    run_id: i64,
+    generated_count: i64,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    let finished_at = time::OffsetDateTime::now_utc();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "UPDATE scheduler_runs SET finished_at = ?, generated_count = ?, error = ? WHERE id = ?",
+        finished_at,
+        generated_count,
+        error,
+        run_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM scheduler_runs
+        WHERE id NOT IN (SELECT id FROM scheduler_runs ORDER BY id DESC LIMIT ?)
+        "#,
+        SCHEDULER_RUNS_RETAINED
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// Generate the recurring debts due at `now`
+///
+/// "Today" is the calendar day in each group's own timezone. The query only
+/// narrows the debts down to those due somewhere on Earth; `should_generate`
+/// then decides per group.
+#[cfg(feature = "ssr")]
+async fn generate_due_recurring_debts(
+    pool: &sqlx::SqlitePool,
+    webhooks: &WebhookDispatcher,
+    now: time::OffsetDateTime,
 ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
-    let today = time::OffsetDateTime::now_utc().date();
-    let today_str = today.to_string();
+    let latest_today = today_at(now, time::UtcOffset::from_hms(14, 0, 0)?).to_string();
+    let earliest_today = today_at(now, time::UtcOffset::from_hms(-12, 0, 0)?).to_string();
 
     let debts = sqlx::query!(
         r#"
         SELECT 
-            id as "id!",
-            group_id as "group_id!",
-            created_by as "created_by!",
-            name,
-            amount,
-            frequency,
-            start_date as "start_date!: String",
-            end_date as "end_date: String",
-            next_generation_date as "next_generation_date!: String",
-            is_active as "is_active!: bool"
-        FROM recurring_debts
-        WHERE is_active = 1 
-        AND next_generation_date <= ?
-        AND (end_date IS NULL OR end_date >= ?)
+            rd.id as "id!",
+            rd.group_id as "group_id!",
+            rd.created_by as "created_by!",
+            rd.name,
+            rd.amount,
+            rd.frequency,
+            rd.frequency_interval,
+            rd.start_date as "start_date!: String",
+            rd.end_date as "end_date: String",
+            rd.next_generation_date as "next_generation_date!: String",
+            rd.is_active as "is_active!: bool",
+            rd.category,
+            g.timezone,
+            g.rounding_strategy
+        FROM recurring_debts rd
+        INNER JOIN groups g ON g.id = rd.group_id
+        WHERE rd.is_active = 1 
+        AND g.is_archived = 0
+        AND rd.next_generation_date <= ?
+        AND (rd.end_date IS NULL OR rd.end_date >= ?)
         "#,
-        today_str,
-        today_str
+        latest_today,
+        earliest_today
     )
-    .fetch_all(&pool)
+    .fetch_all(pool)
     .await?;
 
     let mut generated_count = 0;
 
     for debt_row in debts {
-        let frequency = match debt_row.frequency.parse::<Frequency>() {
-            Ok(f) => f,
-            Err(e) => {
-                eprintln!(
-                    "Error parsing frequency for recurring debt {}: {}",
-                    debt_row.id, e
-                );
-                continue;
-            }
-        };
+        let frequency =
+            match Frequency::from_columns(&debt_row.frequency, debt_row.frequency_interval) {
+                Ok(f) => f,
+                Err(e) => {
+                    eprintln!(
+                        "Error parsing frequency for recurring debt {}: {}",
+                        debt_row.id, e
+                    );
+                    continue;
+                }
+            };
 
         let next_generation_date = match Date::parse(
             &debt_row.next_generation_date,
@@ -107,12 +254,27 @@ pub async fn process_due_recurring_debts_internal(
             }
         };
 
+        let rounding = match debt_row.rounding_strategy.parse::<RoundingStrategy>() {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!(
+                    "Error parsing rounding strategy for recurring debt {}: {}",
+                    debt_row.id, e
+                );
+                continue;
+            }
+        };
+
         let recurring_debt = RecurringDebt {
             id: debt_row.id,
             group_id: debt_row.group_id,
             created_by: debt_row.created_by,
             name: debt_row.name.clone(),
             amount,
+            category: debt_row
+                .category
+                .as_deref()
+                .and_then(|category| category.parse().ok()),
             frequency: frequency.clone(),
             start_date,
             end_date,
@@ -122,19 +284,23 @@ pub async fn process_due_recurring_debts_internal(
             updated_at: time::OffsetDateTime::now_utc(),
         };
 
+        let today = group_today_at(now, debt_row.timezone.as_deref());
         if !should_generate(&recurring_debt, today) {
             continue;
         }
 
+        // Anyone who left the group since doesn't get a share
         let members = match sqlx::query!(
             r#"
-            SELECT user_id as "user_id!"
+            SELECT user_id as "user_id!", weight
             FROM recurring_debt_user
             WHERE recurring_debt_id = ?
+              AND user_id IN (SELECT user_id FROM group_members WHERE group_id = ?)
             "#,
-            debt_row.id
+            debt_row.id,
+            debt_row.group_id
         )
-        .fetch_all(&pool)
+        .fetch_all(pool)
         .await
         {
             Ok(m) => m,
@@ -147,94 +313,413 @@ pub async fn process_due_recurring_debts_internal(
             }
         };
 
-        let member_ids: Vec<i64> = members.into_iter().map(|m| m.user_id).collect();
+        let weights: Vec<(i64, i64)> = members.into_iter().map(|m| (m.user_id, m.weight)).collect();
 
-        if member_ids.is_empty() {
+        if weights.is_empty() {
             eprintln!("No members found for recurring debt {}", debt_row.id);
             continue;
         }
 
         let new_next_date = calculate_next_occurrence(next_generation_date, &frequency);
 
-        let mut tx = match pool.begin().await {
-            Ok(t) => t,
+        let new_next_date_str = new_next_date.to_string();
+        let member_shares =
+            generated_share_amounts(amount, &weights, rounding, debt_row.created_by);
+        let shared_debt_id = match with_busy_retry(|| {
+            insert_generated_debt(
+                pool,
+                &debt_row.name,
+                &debt_row.amount,
+                debt_row.category.as_deref(),
+                debt_row.group_id,
+                debt_row.created_by,
+                debt_row.id,
+                &member_shares,
+                &debt_row.next_generation_date,
+                &new_next_date_str,
+            )
+        })
+        .await
+        {
+            Ok(Some(id)) => id,
+            Ok(None) => {
+                tracing::info!(
+                    recurring_debt_id = debt_row.id,
+                    generation_period = %debt_row.next_generation_date,
+                    "Recurring debt period was already generated"
+                );
+                continue;
+            }
             Err(e) => {
                 eprintln!(
-                    "Error starting transaction for recurring debt {}: {}",
+                    "Error generating shared debt for recurring debt {}: {}",
                     debt_row.id, e
                 );
                 continue;
             }
         };
 
-        let shared_debt_id = match sqlx::query!(
-            r#"
-            INSERT INTO shared_debts (group_id, created_by, name, amount, recurring_debt_id)
-            VALUES (?, ?, ?, ?, ?)
-            "#,
+        dispatch_generated_debt(
+            webhooks,
             debt_row.group_id,
-            debt_row.created_by,
-            debt_row.name,
-            debt_row.amount,
-            debt_row.id
+            shared_debt_id,
+            debt_row.id,
+            &debt_row.name,
+            &debt_row.amount,
+        );
+
+        generated_count += 1;
+        tracing::info!(
+            shared_debt_id = shared_debt_id,
+            recurring_debt_id = debt_row.id,
+            "Generated shared debt from recurring debt"
+        );
+    }
+
+    Ok(generated_count)
+}
+
+/// Insert one generated shared debt with its members' shares and advance the
+/// recurring debt's next generation date, all in a single transaction
+///
+/// The generated debt gets the recurring debt's category and counts as
+/// incurred on the day it was due, `generation_period`. In groups that
+/// require approval it waits for an admin unless an admin set up the
+/// recurring debt.
+///
+/// Returns `None` without changing anything when `generation_period` was
+/// already generated, e.g. by "Generate Now" racing the scheduler.
+#[cfg(feature = "ssr")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn insert_generated_debt(
+    pool: &sqlx::SqlitePool,
+    name: &str,
+    amount: &str,
+    category: Option<&str>,
+    group_id: i64,
+    created_by: i64,
+    recurring_debt_id: i64,
+    member_shares: &[(i64, Option<String>)],
+    generation_period: &str,
+    next_generation_date: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let status = proposed_debt_status(&mut *tx, group_id, created_by).await?;
+    let status_str = status.as_str();
+    let inserted = sqlx::query!(
+        r#"
+        INSERT INTO shared_debts
+            (group_id, created_by, payer_id, name, amount, category, recurring_debt_id, generation_period, incurred_on, status)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        group_id,
+        created_by,
+        created_by,
+        name,
+        amount,
+        category,
+        recurring_debt_id,
+        generation_period,
+        generation_period,
+        status_str
+    )
+    .execute(&mut *tx)
+    .await;
+
+    let shared_debt_id = match inserted {
+        Ok(result) => result.last_insert_rowid(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    for (member_id, share_amount) in member_shares {
+        sqlx::query!(
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount) VALUES (?, ?, ?)",
+            shared_debt_id,
+            member_id,
+            share_amount
         )
         .execute(&mut *tx)
-        .await
-        {
-            Ok(result) => result.last_insert_rowid(),
-            Err(e) => {
-                eprintln!(
-                    "Error creating shared debt for recurring debt {}: {}",
-                    debt_row.id, e
-                );
-                continue;
-            }
-        };
+        .await?;
+    }
+    add_shared_debt_to_balances(&mut tx, shared_debt_id).await?;
 
-        for member_id in member_ids {
-            if let Err(e) = sqlx::query!(
-                "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (?, ?)",
-                shared_debt_id,
-                member_id
-            )
-            .execute(&mut *tx)
+    // Only the writer that still sees the period as due advances it
+    let advanced = sqlx::query!(
+        r#"
+        UPDATE recurring_debts
+        SET next_generation_date = ?, updated_at = CURRENT_TIMESTAMP
+        WHERE id = ? AND next_generation_date = ?
+        "#,
+        next_generation_date,
+        recurring_debt_id,
+        generation_period
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    if advanced == 0 {
+        // Dropping the transaction rolls the insert back
+        return Ok(None);
+    }
+
+    let notified: Vec<i64> = member_shares
+        .iter()
+        .map(|(member_id, _)| *member_id)
+        .filter(|member_id| *member_id != created_by)
+        .collect();
+    let amount = amount
+        .parse::<Decimal>()
+        .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+    notify_users(
+        &mut tx,
+        group_id,
+        &notified,
+        &NotificationPayload::RecurringDebtGenerated {
+            debt_id: shared_debt_id,
+            debt_name: name.to_string(),
+            amount,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(shared_debt_id))
+}
+
+/// Tell the group's webhooks about a debt generated from a recurring debt
+#[cfg(feature = "ssr")]
+pub(crate) fn dispatch_generated_debt(
+    webhooks: &WebhookDispatcher,
+    group_id: i64,
+    shared_debt_id: i64,
+    recurring_debt_id: i64,
+    name: &str,
+    amount: &str,
+) {
+    webhooks.dispatch(
+        group_id,
+        WebhookEvent::RecurringDebtGenerated,
+        serde_json::json!({
+            "id": shared_debt_id,
+            "recurring_debt_id": recurring_debt_id,
+            "name": name,
+            "amount": amount,
+        }),
+    );
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use time::{Date, Month, OffsetDateTime};
+
+    use super::*;
+
+    fn utc(day: u8, hour: u8, minute: u8) -> OffsetDateTime {
+        Date::from_calendar_date(2024, Month::May, day)
+            .unwrap()
+            .with_hms(hour, minute, 0)
+            .unwrap()
+            .assume_utc()
+    }
+
+    #[test]
+    fn test_next_cron_run() {
+        assert_eq!(
+            next_cron_run(DEFAULT_RECURRING_DEBTS_CRON, utc(2, 7, 30)),
+            Some(utc(3, 6, 0))
+        );
+        assert_eq!(
+            next_cron_run(DEFAULT_RECURRING_DEBTS_CRON, utc(2, 5, 0)),
+            Some(utc(2, 6, 0))
+        );
+        assert_eq!(next_cron_run("not a cron", utc(2, 7, 30)), None);
+    }
+
+    #[tokio::test]
+    async fn test_generated_debt_copies_category() {
+        // One connection so every query sees the same in-memory database
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
             .await
-            {
-                eprintln!(
-                    "Error inserting member {} for shared debt {}: {}",
-                    member_id, shared_debt_id, e
-                );
-            }
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date, category) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-03-01', '2026-03-01', 'Rent')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
         }
 
-        let new_next_date_str = new_next_date.to_string();
-        if let Err(e) = sqlx::query!(
-            "UPDATE recurring_debts SET next_generation_date = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-            new_next_date_str,
-            debt_row.id
+        let shared_debt_id = insert_generated_debt(
+            &pool,
+            "Rent",
+            "900",
+            Some("Rent"),
+            1,
+            1,
+            1,
+            &[(1, None)],
+            "2026-03-01",
+            "2026-04-01",
         )
-        .execute(&mut *tx)
         .await
-        {
-            eprintln!("Error updating next_generation_date for recurring debt {}: {}", debt_row.id, e);
-            continue;
+        .unwrap()
+        .unwrap();
+
+        let (category, incurred_on): (Option<String>, String) =
+            sqlx::query_as("SELECT category, incurred_on FROM shared_debts WHERE id = ?")
+                .bind(shared_debt_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(category.as_deref(), Some("Rent"));
+        assert_eq!(incurred_on, "2026-03-01");
+    }
+
+    #[tokio::test]
+    async fn test_generated_debts_of_members_await_approval() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by, require_debt_approval) VALUES ('Family', 1, 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-03-01', '2026-03-01'), (1, 2, 'Games', '10', 'monthly', '2026-03-01', '2026-03-01')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
         }
 
-        if let Err(e) = tx.commit().await {
-            eprintln!(
-                "Error committing transaction for recurring debt {}: {}",
-                debt_row.id, e
-            );
-            continue;
+        let mut statuses = Vec::new();
+        for (recurring_debt_id, created_by) in [(1, 1), (2, 2)] {
+            let shared_debt_id = insert_generated_debt(
+                &pool,
+                "Debt",
+                "10",
+                None,
+                1,
+                created_by,
+                recurring_debt_id,
+                &[(1, None), (2, None)],
+                "2026-03-01",
+                "2026-04-01",
+            )
+            .await
+            .unwrap()
+            .unwrap();
+            let status: String = sqlx::query_scalar("SELECT status FROM shared_debts WHERE id = ?")
+                .bind(shared_debt_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            statuses.push(status);
         }
+        assert_eq!(statuses, ["approved", "pending"]);
+    }
 
-        generated_count += 1;
-        tracing::info!(
-            shared_debt_id = shared_debt_id,
-            recurring_debt_id = debt_row.id,
-            "Generated shared debt from recurring debt"
-        );
+    #[tokio::test]
+    async fn test_generation_follows_each_groups_midnight() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x')",
+            "INSERT INTO groups (name, created_by, timezone) VALUES ('Auckland flat', 1, 'Pacific/Auckland'), ('LA flat', 1, 'America/Los_Angeles')",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (2, 1)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-03-01', '2026-03-01'), (2, 1, 'Rent', '900', 'monthly', '2026-03-01', '2026-03-01')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (2, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let webhooks = crate::features::webhooks::create_webhook_dispatcher(pool.clone());
+        let at = |month: Month, day: u8, hour: u8| {
+            Date::from_calendar_date(2026, month, day)
+                .unwrap()
+                .with_hms(hour, 0, 0)
+                .unwrap()
+                .assume_utc()
+        };
+        async fn generated_groups(pool: &sqlx::SqlitePool) -> Vec<i64> {
+            sqlx::query_scalar("SELECT group_id FROM shared_debts ORDER BY group_id")
+                .fetch_all(pool)
+                .await
+                .unwrap()
+        }
+
+        // 23:00 on February 28th in Auckland, 02:00 in Los Angeles
+        let generated = generate_due_recurring_debts(&pool, &webhooks, at(Month::February, 28, 10))
+            .await
+            .unwrap();
+        assert_eq!(generated, 0);
+
+        // 01:00 on March 1st in Auckland
+        generate_due_recurring_debts(&pool, &webhooks, at(Month::February, 28, 12))
+            .await
+            .unwrap();
+        assert_eq!(generated_groups(&pool).await, vec![1]);
+
+        // 23:00 on February 28th in Los Angeles, although March 1st in UTC
+        generate_due_recurring_debts(&pool, &webhooks, at(Month::March, 1, 7))
+            .await
+            .unwrap();
+        assert_eq!(generated_groups(&pool).await, vec![1]);
+
+        // Midnight in Los Angeles
+        generate_due_recurring_debts(&pool, &webhooks, at(Month::March, 1, 8))
+            .await
+            .unwrap();
+        assert_eq!(generated_groups(&pool).await, vec![1, 2]);
     }
 
-    Ok(generated_count)
+    #[tokio::test]
+    async fn test_archived_groups_are_skipped() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x')",
+            "INSERT INTO groups (name, created_by, is_archived) VALUES ('Flat', 1, 0), ('Trip', 1, 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (2, 1)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-03-01', '2026-03-01'), (2, 1, 'Car', '50', 'monthly', '2026-03-01', '2026-03-01')",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (1, 1), (2, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let webhooks = crate::features::webhooks::create_webhook_dispatcher(pool.clone());
+
+        let now = Date::from_calendar_date(2026, Month::March, 1)
+            .unwrap()
+            .with_hms(12, 0, 0)
+            .unwrap()
+            .assume_utc();
+        generate_due_recurring_debts(&pool, &webhooks, now)
+            .await
+            .unwrap();
+
+        let generated_groups: Vec<i64> =
+            sqlx::query_scalar("SELECT group_id FROM shared_debts ORDER BY group_id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(generated_groups, vec![1]);
+    }
 }
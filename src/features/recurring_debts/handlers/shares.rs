@@ -8,11 +8,13 @@ use rust_decimal::Decimal;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
 use crate::features::recurring_debts::models::RecurringDebtMember;
 #[cfg(feature = "ssr")]
-use crate::features::shared_debts::utils::calculate_shares;
+use crate::features::shared_debts::{models::RoundingStrategy, utils::calculate_weighted_shares};
 
 /// Server function: Get member shares for a recurring debt (for display)
 #[server(GetRecurringDebtShares)]
@@ -44,7 +46,7 @@ pub async fn get_recurring_debt_shares(
     )
     .fetch_optional(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .ok_or_else(|| ServerFnError::new("Not authorized"))?;
 
     let amount = debt
@@ -52,10 +54,37 @@ pub async fn get_recurring_debt_shares(
         .parse::<Decimal>()
         .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
 
+    recurring_debt_member_shares(&pool, recurring_debt_id, amount).await
+}
+
+/// What each member of a recurring debt owes of `amount`, ordered by username
+#[cfg(feature = "ssr")]
+pub async fn recurring_debt_member_shares(
+    pool: &sqlx::SqlitePool,
+    recurring_debt_id: i64,
+    amount: Decimal,
+) -> Result<Vec<RecurringDebtMember>, ServerFnError> {
+    let debt = sqlx::query!(
+        r#"
+        SELECT rd.created_by, g.rounding_strategy
+        FROM recurring_debts rd
+        INNER JOIN groups g ON g.id = rd.group_id
+        WHERE rd.id = ?
+        "#,
+        recurring_debt_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    let rounding = debt
+        .rounding_strategy
+        .parse::<RoundingStrategy>()
+        .map_err(ServerFnError::new)?;
+
     // Get members with usernames
     let members = sqlx::query!(
         r#"
-        SELECT u.id as "user_id!", u.username
+        SELECT u.id as "user_id!", u.username, rdu.weight
         FROM recurring_debt_user rdu
         INNER JOIN users u ON rdu.user_id = u.id
         WHERE rdu.recurring_debt_id = ?
@@ -63,23 +92,26 @@ pub async fn get_recurring_debt_shares(
         "#,
         recurring_debt_id
     )
-    .fetch_all(&pool)
+    .fetch_all(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
-    let user_data: Vec<(i64, String)> = members
+    let user_data: Vec<(i64, String, i64)> = members
         .into_iter()
-        .map(|m| (m.user_id, m.username))
+        .map(|m| (m.user_id, m.username, m.weight))
         .collect();
 
-    let shares = calculate_shares(amount, &user_data);
+    let shares = calculate_weighted_shares(amount, &user_data, rounding, debt.created_by);
 
     Ok(shares
         .into_iter()
-        .map(|s| RecurringDebtMember {
+        .zip(&user_data)
+        .map(|(s, (_, _, weight))| RecurringDebtMember {
             user_id: s.user_id,
             username: s.username,
             share_amount: s.share_amount,
+            weight: *weight,
+            absorbs_remainder: s.absorbs_remainder,
         })
         .collect())
 }
@@ -0,0 +1,405 @@
+//! Skipping single generations of a recurring debt
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+use time::Date;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+use crate::features::recurring_debts::models::SkippedOccurrence;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::utils::ensure_group_writable,
+    recurring_debts::{models::Frequency, utils::calculate_next_occurrence},
+};
+
+/// Server function: Get the skipped generations of a recurring debt, newest
+/// first
+#[server(GetSkippedOccurrences)]
+pub async fn get_skipped_occurrences(
+    recurring_debt_id: i64,
+) -> Result<Vec<SkippedOccurrence>, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    load_skipped_occurrences(&pool, recurring_debt_id, user.id).await
+}
+
+/// Server function: Skip the next generation of a recurring debt
+///
+/// Moves the next generation date one interval ahead and returns the new
+/// date. Only the creator can skip.
+#[server(SkipNextOccurrence)]
+pub async fn skip_next_occurrence(recurring_debt_id: i64) -> Result<Date, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    skip_occurrence(&pool, recurring_debt_id, user.id).await
+}
+
+/// Server function: Undo the latest skip of a recurring debt
+///
+/// Moves the next generation date back to the skipped date and returns it.
+/// Fails once a debt was generated or the schedule was changed since.
+#[server(UnskipOccurrence)]
+pub async fn unskip_occurrence(recurring_debt_id: i64) -> Result<Date, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    revert_latest_skip(&pool, recurring_debt_id, user.id).await
+}
+
+#[cfg(feature = "ssr")]
+fn parse_date(value: &str) -> Result<Date, ServerFnError> {
+    Date::parse(
+        value,
+        &time::format_description::well_known::Iso8601::DEFAULT,
+    )
+    .map_err(|e| ServerFnError::new(format!("Invalid date: {}", e)))
+}
+
+/// Load the skips of a recurring debt for `user_id`, who must be a member of
+/// its group
+#[cfg(feature = "ssr")]
+async fn load_skipped_occurrences(
+    pool: &sqlx::SqlitePool,
+    recurring_debt_id: i64,
+    user_id: i64,
+) -> Result<Vec<SkippedOccurrence>, ServerFnError> {
+    let has_access = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM recurring_debts rd
+        INNER JOIN group_members gm ON rd.group_id = gm.group_id
+        WHERE rd.id = ? AND gm.user_id = ?
+        "#,
+        recurring_debt_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .count
+        > 0;
+
+    if !has_access {
+        return Err(ServerFnError::new("Not authorized"));
+    }
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            s.id as "id!",
+            s.skipped_date,
+            s.next_date,
+            u.username as skipped_by_username,
+            s.created_at as "created_at!: time::OffsetDateTime"
+        FROM recurring_debt_skips s
+        INNER JOIN users u ON u.id = s.skipped_by
+        WHERE s.recurring_debt_id = ?
+        ORDER BY s.id DESC
+        "#,
+        recurring_debt_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(SkippedOccurrence {
+                id: row.id,
+                skipped_date: parse_date(&row.skipped_date)?,
+                next_date: parse_date(&row.next_date)?,
+                skipped_by_username: row.skipped_by_username,
+                created_at: row.created_at,
+            })
+        })
+        .collect()
+}
+
+/// Skip the next generation of a recurring debt on behalf of `user_id`, who
+/// must be its creator and not a viewer
+#[cfg(feature = "ssr")]
+async fn skip_occurrence(
+    pool: &sqlx::SqlitePool,
+    recurring_debt_id: i64,
+    user_id: i64,
+) -> Result<Date, ServerFnError> {
+    let debt = sqlx::query!(
+        r#"
+        SELECT
+            rd.group_id,
+            rd.created_by,
+            rd.frequency,
+            rd.frequency_interval,
+            rd.next_generation_date as "next_generation_date!: String"
+        FROM recurring_debts rd
+        WHERE rd.id = ?
+        "#,
+        recurring_debt_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .ok_or_else(|| ServerFnError::new("Recurring debt not found"))?;
+
+    if debt.created_by != user_id {
+        return Err(ServerFnError::new(
+            "Only the creator can skip generations of this recurring debt",
+        ));
+    }
+    ensure_group_writable(pool, debt.group_id, user_id).await?;
+
+    let frequency = Frequency::from_columns(&debt.frequency, debt.frequency_interval)
+        .map_err(ServerFnError::new)?;
+    let skipped_date = parse_date(&debt.next_generation_date)?;
+    let next_date = calculate_next_occurrence(skipped_date, &frequency);
+    let next_date_str = next_date.to_string();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    // Only advance from the date we read, so a debt the scheduler generated
+    // meanwhile doesn't skip a second period
+    let updated = sqlx::query!(
+        r#"
+        UPDATE recurring_debts
+        SET next_generation_date = ?, updated_at = CURRENT_TIMESTAMP, version = version + 1
+        WHERE id = ? AND next_generation_date = ?
+        "#,
+        next_date_str,
+        recurring_debt_id,
+        debt.next_generation_date
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .rows_affected();
+
+    if updated == 0 {
+        return Err(ServerFnError::new(
+            "This recurring debt changed while skipping, please try again",
+        ));
+    }
+
+    sqlx::query!(
+        r#"
+        INSERT INTO recurring_debt_skips (recurring_debt_id, skipped_date, next_date, skipped_by)
+        VALUES (?, ?, ?, ?)
+        "#,
+        recurring_debt_id,
+        debt.next_generation_date,
+        next_date_str,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(next_date)
+}
+
+/// Undo the latest skip of a recurring debt on behalf of `user_id`, who must
+/// be its creator and not a viewer
+#[cfg(feature = "ssr")]
+async fn revert_latest_skip(
+    pool: &sqlx::SqlitePool,
+    recurring_debt_id: i64,
+    user_id: i64,
+) -> Result<Date, ServerFnError> {
+    let debt = sqlx::query!(
+        r#"
+        SELECT rd.group_id, rd.created_by
+        FROM recurring_debts rd
+        WHERE rd.id = ?
+        "#,
+        recurring_debt_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .ok_or_else(|| ServerFnError::new("Recurring debt not found"))?;
+
+    if debt.created_by != user_id {
+        return Err(ServerFnError::new(
+            "Only the creator can undo skips of this recurring debt",
+        ));
+    }
+    ensure_group_writable(pool, debt.group_id, user_id).await?;
+
+    let skip = sqlx::query!(
+        r#"
+        SELECT id as "id!", skipped_date, next_date
+        FROM recurring_debt_skips
+        WHERE recurring_debt_id = ?
+        ORDER BY id DESC
+        LIMIT 1
+        "#,
+        recurring_debt_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .ok_or_else(|| ServerFnError::new("Nothing has been skipped"))?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    // Generating advances the date past the skip's, as does editing the
+    // schedule, so an unchanged date means the skip can still be undone
+    let reverted = sqlx::query!(
+        r#"
+        UPDATE recurring_debts
+        SET next_generation_date = ?, updated_at = CURRENT_TIMESTAMP, version = version + 1
+        WHERE id = ? AND next_generation_date = ?
+        "#,
+        skip.skipped_date,
+        recurring_debt_id,
+        skip.next_date
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .rows_affected();
+
+    if reverted == 0 {
+        return Err(ServerFnError::new(
+            "The schedule changed since the skip, so it can't be undone",
+        ));
+    }
+
+    sqlx::query!("DELETE FROM recurring_debt_skips WHERE id = ?", skip.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    parse_date(&skip.skipped_date)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    async fn setup() -> sqlx::SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Cleaner', '120', 'monthly', '2026-07-01', '2026-08-01')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    async fn next_generation_date(pool: &sqlx::SqlitePool) -> String {
+        sqlx::query_scalar("SELECT next_generation_date FROM recurring_debts WHERE id = 1")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_skip_and_unskip_next_occurrence() {
+        let pool = setup().await;
+
+        let err = skip_occurrence(&pool, 1, 2).await.unwrap_err();
+        assert!(err.to_string().contains("Only the creator"));
+
+        let next = skip_occurrence(&pool, 1, 1).await.unwrap();
+        assert_eq!(next.to_string(), "2026-09-01");
+        assert_eq!(next_generation_date(&pool).await, "2026-09-01");
+
+        // Members see the skip in the history
+        let skips = load_skipped_occurrences(&pool, 1, 2).await.unwrap();
+        assert_eq!(skips.len(), 1);
+        assert_eq!(skips[0].skipped_date.to_string(), "2026-08-01");
+        assert_eq!(skips[0].skipped_by_username, "alice");
+
+        let restored = revert_latest_skip(&pool, 1, 1).await.unwrap();
+        assert_eq!(restored.to_string(), "2026-08-01");
+        assert_eq!(next_generation_date(&pool).await, "2026-08-01");
+        assert!(
+            load_skipped_occurrences(&pool, 1, 1)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let err = revert_latest_skip(&pool, 1, 1).await.unwrap_err();
+        assert!(err.to_string().contains("Nothing has been skipped"));
+    }
+
+    #[tokio::test]
+    async fn test_unskip_fails_after_generation() {
+        let pool = setup().await;
+        skip_occurrence(&pool, 1, 1).await.unwrap();
+
+        // The September debt was generated after the skip
+        sqlx::query("UPDATE recurring_debts SET next_generation_date = '2026-10-01' WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let err = revert_latest_skip(&pool, 1, 1).await.unwrap_err();
+        assert!(err.to_string().contains("can't be undone"));
+        assert_eq!(next_generation_date(&pool).await, "2026-10-01");
+        assert_eq!(
+            load_skipped_occurrences(&pool, 1, 1).await.unwrap().len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+use crate::features::recurring_debts::models::SchedulerStatus;
+
+/// Server function: Get the last recurring debts scheduler run and the next
+/// expected one
+#[server(GetSchedulerStatus)]
+pub async fn get_scheduler_status() -> Result<SchedulerStatus, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use super::scheduler::{next_cron_run, recurring_debts_cron_expression};
+    use crate::features::recurring_debts::models::SchedulerRun;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let last_run = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!",
+            started_at as "started_at!: time::OffsetDateTime",
+            finished_at as "finished_at: time::OffsetDateTime",
+            generated_count,
+            error
+        FROM scheduler_runs
+        ORDER BY id DESC
+        LIMIT 1
+        "#
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .map(|row| SchedulerRun {
+        id: row.id,
+        started_at: row.started_at,
+        finished_at: row.finished_at,
+        generated_count: row.generated_count,
+        error: row.error,
+    });
+
+    let expression = recurring_debts_cron_expression();
+    let now = time::OffsetDateTime::now_utc();
+    let next_run = next_cron_run(&expression, now);
+
+    // Overdue when the run that should have followed the last one is more
+    // than a day late
+    let is_overdue = last_run
+        .as_ref()
+        .and_then(|run| next_cron_run(&expression, run.started_at))
+        .is_some_and(|expected| now - expected > time::Duration::days(1));
+
+    Ok(SchedulerStatus {
+        last_run,
+        next_run,
+        is_overdue,
+    })
+}
@@ -7,7 +7,9 @@ use leptos_axum::extract;
 use tower_sessions::Session;
 
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{auth::utils::get_user_from_session, groups::utils::ensure_group_writable};
 
 /// Server function: Toggle active status of a recurring debt
 #[server(ToggleRecurringDebtActive)]
@@ -27,7 +29,7 @@ pub async fn toggle_recurring_debt_active(recurring_debt_id: i64) -> Result<bool
     // Get the recurring debt and verify user is the creator
     let debt = sqlx::query!(
         r#"
-        SELECT rd.id, rd.created_by, rd.is_active as "is_active!: bool"
+        SELECT rd.id, rd.group_id, rd.created_by, rd.is_active as "is_active!: bool"
         FROM recurring_debts rd
         WHERE rd.id = ?
         "#,
@@ -35,7 +37,7 @@ pub async fn toggle_recurring_debt_active(recurring_debt_id: i64) -> Result<bool
     )
     .fetch_optional(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .ok_or_else(|| ServerFnError::new("Recurring debt not found"))?;
 
     if debt.created_by != user.id {
@@ -43,18 +45,19 @@ pub async fn toggle_recurring_debt_active(recurring_debt_id: i64) -> Result<bool
             "Only the creator can toggle this recurring debt",
         ));
     }
+    ensure_group_writable(&pool, debt.group_id, user.id).await?;
 
     let new_status = !debt.is_active;
 
-    // Toggle is_active
+    // Toggle is_active; the version bump makes open edit forms reload first
     sqlx::query!(
-        "UPDATE recurring_debts SET is_active = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        "UPDATE recurring_debts SET is_active = ?, updated_at = CURRENT_TIMESTAMP, version = version + 1 WHERE id = ?",
         new_status,
         recurring_debt_id
     )
     .execute(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(new_status)
 }
@@ -4,19 +4,38 @@ use leptos::prelude::*;
 #[cfg(feature = "ssr")]
 use leptos_axum::extract;
 #[cfg(feature = "ssr")]
-use rust_decimal::Decimal;
-#[cfg(feature = "ssr")]
 use time::Date;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
 #[cfg(feature = "ssr")]
-use crate::features::recurring_debts::models::Frequency;
+use crate::features::groups::{
+    models::{ActivityAction, ActivityEntity},
+    utils::{debt_summary, ensure_group_writable, group_currency, log_group_activity},
+};
+#[cfg(feature = "ssr")]
+use crate::features::recurring_debts::{
+    models::Frequency,
+    utils::{parse_form_date, resolve_weights},
+};
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::DebtCategory;
+#[cfg(feature = "ssr")]
+use crate::validation::{
+    rules::{EDIT_CONFLICT_ERROR, MAX_NAME_LENGTH},
+    validate_money, validate_name,
+};
 
 /// Server function: Update a recurring debt
+///
+/// `version` is the recurring debt version the editor loaded; the update is
+/// rejected with a conflict error if the debt was saved or toggled since.
 #[server(UpdateRecurringDebt)]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_recurring_debt(
     recurring_debt_id: i64,
     name: String,
@@ -25,38 +44,35 @@ pub async fn update_recurring_debt(
     end_date: Option<String>,
     is_active: bool,
     member_ids: Vec<i64>,
+    version: i64,
+    /// Relative weight per member; keeps the current weights when omitted
+    #[server(default)]
+    weights: Option<Vec<(i64, String)>>,
+    /// One of the predefined categories; cleared when omitted
+    #[server(default)]
+    category: Option<String>,
+    /// Add members who join the group later; turned off when omitted
+    #[server(default)]
+    auto_include_new_members: bool,
 ) -> Result<(), ServerFnError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
-
-    let user = get_user_from_session(&session)
-        .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
-
-    let pool = expect_context::<SqlitePool>();
-
     // Validate input
-    if name.trim().is_empty() {
-        return Err(ServerFnError::new("Name is required"));
-    }
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+    let amount_decimal = validate_money(&amount)?;
+    let amount = amount_decimal.to_string();
 
-    let amount_decimal = amount
-        .parse::<Decimal>()
-        .map_err(|_| ServerFnError::new("Invalid amount"))?;
-
-    if amount_decimal <= Decimal::ZERO {
-        return Err(ServerFnError::new("Amount must be greater than 0"));
-    }
+    let (frequency, frequency_interval) = frequency
+        .parse::<Frequency>()
+        .map_err(ServerFnError::new)?
+        .to_columns();
 
-    let _frequency_enum = frequency.parse::<Frequency>().map_err(ServerFnError::new)?;
+    let category = DebtCategory::parse_optional(category.as_deref())
+        .map_err(ServerFnError::new)?
+        .map(|category| category.as_str());
 
     let end_date_parsed = if let Some(ed) = &end_date {
-        let parsed = Date::parse(ed, &time::format_description::well_known::Iso8601::DEFAULT)
-            .map_err(|_| ServerFnError::new("Invalid end date format (expected YYYY-MM-DD)"))?;
-        Some(parsed)
+        Some(parse_form_date(ed, "end date").map_err(ServerFnError::new)?)
     } else {
         None
     };
@@ -65,6 +81,16 @@ pub async fn update_recurring_debt(
         return Err(ServerFnError::new("At least one member must be selected"));
     }
 
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
     // Get the recurring debt and verify user is the creator
     let debt = sqlx::query!(
         r#"
@@ -76,7 +102,7 @@ pub async fn update_recurring_debt(
     )
     .fetch_optional(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
     .ok_or_else(|| ServerFnError::new("Recurring debt not found"))?;
 
     if debt.created_by != user.id {
@@ -84,6 +110,7 @@ pub async fn update_recurring_debt(
             "Only the creator can update this recurring debt",
         ));
     }
+    ensure_group_writable(&pool, debt.group_id, user.id).await?;
 
     // Validate end_date against start_date
     if let Some(end_date_parsed) = end_date_parsed {
@@ -106,7 +133,7 @@ pub async fn update_recurring_debt(
         )
         .fetch_one(&pool)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?
         .count
             > 0;
 
@@ -118,30 +145,45 @@ pub async fn update_recurring_debt(
         }
     }
 
+    let weights = match weights {
+        Some(weights) => weights,
+        None => sqlx::query!(
+            r#"SELECT user_id as "user_id!", weight FROM recurring_debt_user WHERE recurring_debt_id = ?"#,
+            recurring_debt_id
+        )
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+        .into_iter()
+        .filter(|row| member_ids.contains(&row.user_id))
+        .map(|row| (row.user_id, row.weight.to_string()))
+        .collect(),
+    };
+    let weights = resolve_weights(&member_ids, &weights).map_err(ServerFnError::new)?;
+
     // Begin transaction
     let mut tx = pool
         .begin()
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     // Update recurring debt
-    sqlx::query!(
-        r#"
-        UPDATE recurring_debts
-        SET name = ?, amount = ?, frequency = ?, end_date = ?, 
-            is_active = ?, updated_at = CURRENT_TIMESTAMP
-        WHERE id = ?
-        "#,
-        name,
-        amount,
-        frequency,
-        end_date,
-        is_active,
-        recurring_debt_id
+    update_recurring_debt_row(
+        &mut tx,
+        recurring_debt_id,
+        version,
+        &RecurringDebtFields {
+            name: &name,
+            amount: &amount,
+            frequency,
+            frequency_interval,
+            end_date: end_date.as_deref(),
+            is_active,
+            category,
+            auto_include_new_members,
+        },
     )
-    .execute(&mut *tx)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     // Delete existing members
     sqlx::query!(
@@ -150,24 +192,147 @@ pub async fn update_recurring_debt(
     )
     .execute(&mut *tx)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     // Insert new members
-    for member_id in member_ids {
+    for (member_id, weight) in weights {
         sqlx::query!(
-            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id) VALUES (?, ?)",
+            "INSERT INTO recurring_debt_user (recurring_debt_id, user_id, weight) VALUES (?, ?, ?)",
             recurring_debt_id,
-            member_id
+            member_id,
+            weight
         )
         .execute(&mut *tx)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
     }
 
+    let currency = group_currency(&mut *tx, debt.group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        debt.group_id,
+        user.id,
+        ActivityEntity::RecurringDebt,
+        recurring_debt_id,
+        ActivityAction::Updated,
+        &debt_summary(&name, amount_decimal, &currency),
+    )
+    .await?;
+
     // Commit transaction
     tx.commit()
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(())
 }
+
+/// Columns of a recurring debt that an edit overwrites
+#[cfg(feature = "ssr")]
+struct RecurringDebtFields<'a> {
+    name: &'a str,
+    amount: &'a str,
+    frequency: &'a str,
+    frequency_interval: i64,
+    end_date: Option<&'a str>,
+    is_active: bool,
+    category: Option<&'a str>,
+    auto_include_new_members: bool,
+}
+
+/// Write a recurring debt's fields and updated_at timestamp if it is still at
+/// `version`, bumping the version
+#[cfg(feature = "ssr")]
+async fn update_recurring_debt_row(
+    conn: &mut sqlx::SqliteConnection,
+    recurring_debt_id: i64,
+    version: i64,
+    fields: &RecurringDebtFields<'_>,
+) -> Result<(), ServerFnError> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE recurring_debts
+        SET name = ?, amount = ?, frequency = ?, frequency_interval = ?, end_date = ?,
+            is_active = ?, category = ?, auto_include_new_members = ?,
+            updated_at = CURRENT_TIMESTAMP, version = version + 1
+        WHERE id = ? AND version = ?
+        "#,
+        fields.name,
+        fields.amount,
+        fields.frequency,
+        fields.frequency_interval,
+        fields.end_date,
+        fields.is_active,
+        fields.category,
+        fields.auto_include_new_members,
+        recurring_debt_id,
+        version
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ServerFnError::new(EDIT_CONFLICT_ERROR));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_update_recurring_debt_row_rejects_stale_version() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO recurring_debts (group_id, created_by, name, amount, frequency, start_date, next_generation_date) VALUES (1, 1, 'Rent', '900', 'monthly', '2026-03-01', '2026-03-01')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        let fields = RecurringDebtFields {
+            name: "Rent",
+            amount: "950",
+            frequency: "monthly",
+            frequency_interval: 1,
+            end_date: None,
+            is_active: true,
+            category: Some("Rent"),
+            auto_include_new_members: false,
+        };
+        let mut conn = pool.acquire().await.unwrap();
+        update_recurring_debt_row(&mut conn, 1, 0, &fields)
+            .await
+            .unwrap();
+
+        // A second editor still holding version 0 is turned away
+        let stale = RecurringDebtFields {
+            amount: "800",
+            ..fields
+        };
+        let err = update_recurring_debt_row(&mut conn, 1, 0, &stale)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(EDIT_CONFLICT_ERROR));
+
+        let (amount, version): (String, i64) =
+            sqlx::query_as("SELECT amount, version FROM recurring_debts WHERE id = 1")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!((amount.as_str(), version), ("950", 1));
+    }
+}
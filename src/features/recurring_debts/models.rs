@@ -4,7 +4,15 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use time::{Date, OffsetDateTime};
 
+use crate::{
+    features::shared_debts::models::DebtCategory,
+    validation::rules::{MAX_FREQUENCY_INTERVAL, check_interval},
+};
+
 /// Frequency enum for recurring debts
+///
+/// Custom intervals are written as e.g. "every_3_weeks". An interval of 1 is
+/// the same as the plain daily or weekly frequency.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Frequency {
@@ -12,24 +20,52 @@ pub enum Frequency {
     Weekly,
     Monthly,
     Yearly,
+    EveryNDays(u32),
+    EveryNWeeks(u32),
 }
 
 impl Frequency {
-    pub fn as_str(&self) -> &str {
+    /// Frequency from its `frequency` and `frequency_interval` columns
+    pub fn from_columns(frequency: &str, interval: i64) -> Result<Self, String> {
+        let base = frequency.parse::<Frequency>()?;
+        if interval == 1 {
+            return Ok(base);
+        }
+
+        let interval = u32::try_from(interval)
+            .ok()
+            .filter(|n| (1..=MAX_FREQUENCY_INTERVAL).contains(n))
+            .ok_or_else(|| format!("Invalid frequency interval: {}", interval))?;
+        match base {
+            Frequency::Daily => Ok(Frequency::EveryNDays(interval)),
+            Frequency::Weekly => Ok(Frequency::EveryNWeeks(interval)),
+            _ => Err(format!("{} debts can't have an interval", base.label())),
+        }
+    }
+
+    /// Values for the `frequency` and `frequency_interval` columns
+    ///
+    /// The column only allows the plain frequencies, so custom intervals are
+    /// stored as "daily" or "weekly" with the interval next to it.
+    pub fn to_columns(&self) -> (&'static str, i64) {
         match self {
-            Frequency::Daily => "daily",
-            Frequency::Weekly => "weekly",
-            Frequency::Monthly => "monthly",
-            Frequency::Yearly => "yearly",
+            Frequency::Daily => ("daily", 1),
+            Frequency::Weekly => ("weekly", 1),
+            Frequency::Monthly => ("monthly", 1),
+            Frequency::Yearly => ("yearly", 1),
+            Frequency::EveryNDays(n) => ("daily", i64::from(*n)),
+            Frequency::EveryNWeeks(n) => ("weekly", i64::from(*n)),
         }
     }
 
-    pub fn label(&self) -> &str {
+    pub fn label(&self) -> String {
         match self {
-            Frequency::Daily => "Daily",
-            Frequency::Weekly => "Weekly",
-            Frequency::Monthly => "Monthly",
-            Frequency::Yearly => "Yearly",
+            Frequency::Daily => "Daily".to_string(),
+            Frequency::Weekly => "Weekly".to_string(),
+            Frequency::Monthly => "Monthly".to_string(),
+            Frequency::Yearly => "Yearly".to_string(),
+            Frequency::EveryNDays(n) => format!("Every {} days", n),
+            Frequency::EveryNWeeks(n) => format!("Every {} weeks", n),
         }
     }
 }
@@ -38,19 +74,40 @@ impl std::str::FromStr for Frequency {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "daily" => Ok(Frequency::Daily),
-            "weekly" => Ok(Frequency::Weekly),
-            "monthly" => Ok(Frequency::Monthly),
-            "yearly" => Ok(Frequency::Yearly),
-            _ => Err(format!("Invalid frequency: {}", s)),
+        let lower = s.to_lowercase();
+        match lower.as_str() {
+            "daily" => return Ok(Frequency::Daily),
+            "weekly" => return Ok(Frequency::Weekly),
+            "monthly" => return Ok(Frequency::Monthly),
+            "yearly" => return Ok(Frequency::Yearly),
+            _ => {}
         }
+
+        let custom = lower.strip_prefix("every_").and_then(|rest| {
+            let (interval, unit) = rest.split_once('_')?;
+            let interval = check_interval(interval).ok()?;
+            match (unit, interval) {
+                ("days", 1) => Some(Frequency::Daily),
+                ("weeks", 1) => Some(Frequency::Weekly),
+                ("days", n) => Some(Frequency::EveryNDays(n)),
+                ("weeks", n) => Some(Frequency::EveryNWeeks(n)),
+                _ => None,
+            }
+        });
+        custom.ok_or_else(|| format!("Invalid frequency: {}", s))
     }
 }
 
 impl std::fmt::Display for Frequency {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_str())
+        match self {
+            Frequency::Daily => write!(f, "daily"),
+            Frequency::Weekly => write!(f, "weekly"),
+            Frequency::Monthly => write!(f, "monthly"),
+            Frequency::Yearly => write!(f, "yearly"),
+            Frequency::EveryNDays(n) => write!(f, "every_{}_days", n),
+            Frequency::EveryNWeeks(n) => write!(f, "every_{}_weeks", n),
+        }
     }
 }
 
@@ -63,6 +120,8 @@ pub struct RecurringDebt {
     pub created_by: i64,
     pub name: String,
     pub amount: Decimal,
+    /// Copied onto every generated debt
+    pub category: Option<DebtCategory>,
     pub frequency: Frequency,
     pub start_date: Date,
     pub end_date: Option<Date>,
@@ -83,6 +142,8 @@ pub struct RecurringDebtWithDetails {
     pub creator_username: String,
     pub name: String,
     pub amount: Decimal,
+    /// Copied onto every generated debt
+    pub category: Option<DebtCategory>,
     pub frequency: Frequency,
     pub start_date: Date,
     pub end_date: Option<Date>,
@@ -94,6 +155,10 @@ pub struct RecurringDebtWithDetails {
     pub updated_at: OffsetDateTime,
     pub is_creator: bool,
     pub status: String, // "Active", "Paused", or "Expired"
+    /// Whether members who join the group later are added automatically
+    pub auto_include_new_members: bool,
+    /// Incremented on every edit; updates must pass the version they loaded
+    pub version: i64,
 }
 
 /// Pivot table entry for recurring_debt_user
@@ -103,6 +168,7 @@ pub struct RecurringDebtUser {
     pub id: i64,
     pub recurring_debt_id: i64,
     pub user_id: i64,
+    pub weight: i64,
 }
 
 /// Member information for a recurring debt
@@ -111,6 +177,25 @@ pub struct RecurringDebtMember {
     pub user_id: i64,
     pub username: String,
     pub share_amount: Decimal,
+    /// Relative weight of the member's share; 1 unless weighted
+    pub weight: i64,
+    /// Whether the share includes cents left over by an uneven split
+    pub absorbs_remainder: bool,
+}
+
+/// An upcoming generation of a recurring debt with each member's share
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreviewOccurrence {
+    pub date: Date,
+    pub shares: Vec<RecurringDebtMember>,
+}
+
+/// Upcoming generations of a recurring debt
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OccurrencePreview {
+    pub occurrences: Vec<PreviewOccurrence>,
+    /// Why nothing is coming up, e.g. while the debt is paused
+    pub reason: Option<String>,
 }
 
 /// Generated instance linking a SharedDebt to its RecurringDebt parent
@@ -122,3 +207,86 @@ pub struct GeneratedInstance {
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
 }
+
+/// A generation of a recurring debt that was skipped on purpose
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SkippedOccurrence {
+    pub id: i64,
+    /// Date the skipped debt would have been generated
+    pub skipped_date: Date,
+    /// Next generation date right after the skip
+    pub next_date: Date,
+    pub skipped_by_username: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// One execution of the recurring debts scheduler
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SchedulerRun {
+    pub id: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished_at: Option<OffsetDateTime>,
+    pub generated_count: i64,
+    pub error: Option<String>,
+}
+
+/// Health of the recurring debts scheduler for display
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SchedulerStatus {
+    pub last_run: Option<SchedulerRun>,
+    /// Next run according to the configured cron expression, if it parses
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_run: Option<OffsetDateTime>,
+    /// The run expected after the last one is more than a day late
+    pub is_overdue: bool,
+}
+
+impl SchedulerStatus {
+    /// Whether the status should be shown as a warning
+    pub fn needs_attention(&self) -> bool {
+        self.is_overdue
+            || self
+                .last_run
+                .as_ref()
+                .is_some_and(|run| run.error.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequency_round_trips_through_string() {
+        for frequency in [
+            Frequency::Daily,
+            Frequency::Weekly,
+            Frequency::Monthly,
+            Frequency::Yearly,
+            Frequency::EveryNDays(10),
+            Frequency::EveryNWeeks(3),
+        ] {
+            assert_eq!(frequency.to_string().parse::<Frequency>(), Ok(frequency));
+        }
+
+        assert_eq!("every_1_weeks".parse::<Frequency>(), Ok(Frequency::Weekly));
+        assert!("every_0_days".parse::<Frequency>().is_err());
+        assert!("every_3_months".parse::<Frequency>().is_err());
+        assert!("fortnightly".parse::<Frequency>().is_err());
+    }
+
+    #[test]
+    fn test_frequency_columns() {
+        assert_eq!(Frequency::EveryNWeeks(3).to_columns(), ("weekly", 3));
+        assert_eq!(
+            Frequency::from_columns("weekly", 3),
+            Ok(Frequency::EveryNWeeks(3))
+        );
+        assert_eq!(Frequency::from_columns("daily", 1), Ok(Frequency::Daily));
+        assert!(Frequency::from_columns("monthly", 2).is_err());
+        assert!(Frequency::from_columns("daily", 0).is_err());
+    }
+}
@@ -1,12 +1,21 @@
-use time::Date;
+use rust_decimal::Decimal;
+use time::{Date, OffsetDateTime, UtcOffset};
 
 use super::models::{Frequency, RecurringDebt};
+use crate::{
+    features::shared_debts::{models::RoundingStrategy, utils::split_by_weight},
+    validation::rules::check_weight,
+};
 
 /// Calculate the next occurrence date based on frequency
 pub fn calculate_next_occurrence(current_date: Date, frequency: &Frequency) -> Date {
     match frequency {
         Frequency::Daily => current_date.saturating_add(time::Duration::days(1)),
         Frequency::Weekly => current_date.saturating_add(time::Duration::weeks(1)),
+        Frequency::EveryNDays(n) => current_date.saturating_add(time::Duration::days((*n).into())),
+        Frequency::EveryNWeeks(n) => {
+            current_date.saturating_add(time::Duration::weeks((*n).into()))
+        }
         Frequency::Monthly => {
             // Add one month - handle edge cases like Jan 31 -> Feb 28
             let year = current_date.year();
@@ -62,12 +71,341 @@ pub fn should_generate(recurring_debt: &RecurringDebt, today: Date) -> bool {
     true
 }
 
+/// Largest offset accepted from a client, in minutes (UTC+14 is the furthest
+/// real-world zone)
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// Parse a fixed UTC offset such as `UTC`, `Z`, `+02:00`, `-0530` or `+01`
+pub fn parse_utc_offset(value: &str) -> Option<UtcOffset> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("utc") || value.eq_ignore_ascii_case("z") {
+        return Some(UtcOffset::UTC);
+    }
+
+    let sign = match value.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    let digits: String = value[1..].chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    if minutes >= 60 {
+        return None;
+    }
+
+    utc_offset_from_minutes(sign * (hours * 60 + minutes))
+}
+
+/// UTC offset from minutes east of UTC, as sent by the browser
+pub fn utc_offset_from_minutes(minutes: i32) -> Option<UtcOffset> {
+    if minutes.abs() > MAX_UTC_OFFSET_MINUTES {
+        return None;
+    }
+    UtcOffset::from_whole_seconds(minutes * 60).ok()
+}
+
+/// Calendar day of `now` in the given offset
+pub fn today_at(now: OffsetDateTime, offset: UtcOffset) -> Date {
+    now.to_offset(offset).date()
+}
+
+/// Application timezone from APP_TIMEZONE (a fixed offset like `+02:00`),
+/// defaulting to UTC
+#[cfg(feature = "ssr")]
+pub fn app_timezone() -> UtcOffset {
+    match std::env::var("APP_TIMEZONE") {
+        Ok(value) => parse_utc_offset(&value).unwrap_or_else(|| {
+            tracing::warn!(value = %value, "Invalid APP_TIMEZONE, falling back to UTC");
+            UtcOffset::UTC
+        }),
+        Err(_) => UtcOffset::UTC,
+    }
+}
+
+/// Today's date in the application timezone
+#[cfg(feature = "ssr")]
+pub fn app_today() -> Date {
+    today_at(OffsetDateTime::now_utc(), app_timezone())
+}
+
+/// Check an IANA timezone name such as `Europe/Berlin`; empty means none
+#[cfg(feature = "ssr")]
+pub fn check_timezone(value: Option<&str>) -> Result<Option<String>, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(name) => name
+            .parse::<chrono_tz::Tz>()
+            .map(|tz| Some(tz.name().to_string()))
+            .map_err(|_| format!("Unknown timezone: {}", name)),
+    }
+}
+
+/// Offset of an IANA timezone at `now`, which moves with daylight saving time
+#[cfg(feature = "ssr")]
+fn timezone_offset(timezone: chrono_tz::Tz, now: OffsetDateTime) -> Option<UtcOffset> {
+    use chrono::{Offset, TimeZone};
+
+    let utc = chrono::DateTime::from_timestamp(now.unix_timestamp(), 0)?.naive_utc();
+    let offset = timezone.offset_from_utc_datetime(&utc).fix();
+    UtcOffset::from_whole_seconds(offset.local_minus_utc()).ok()
+}
+
+/// Calendar day of `now` in a group's IANA timezone, or in the application
+/// timezone when the group has none
+#[cfg(feature = "ssr")]
+pub fn group_today_at(now: OffsetDateTime, timezone: Option<&str>) -> Date {
+    let offset = timezone
+        .and_then(|name| name.parse::<chrono_tz::Tz>().ok())
+        .and_then(|timezone| timezone_offset(timezone, now))
+        .unwrap_or_else(app_timezone);
+    today_at(now, offset)
+}
+
+/// Browser's current offset in minutes east of UTC
+pub fn browser_utc_offset_minutes() -> Option<i32> {
+    #[cfg(feature = "hydrate")]
+    {
+        Some(-(js_sys::Date::new_0().get_timezone_offset() as i32))
+    }
+    #[cfg(not(feature = "hydrate"))]
+    {
+        None
+    }
+}
+
+/// Parse a `YYYY-MM-DD` form date
+///
+/// Date inputs report a value while the year is still being typed (e.g.
+/// `0202-05-01`), so years before 1000 are rejected as incomplete.
+pub fn parse_form_date(value: &str, field: &str) -> Result<Date, String> {
+    let date = Date::parse(
+        value.trim(),
+        &time::format_description::well_known::Iso8601::DEFAULT,
+    )
+    .map_err(|_| format!("Invalid {} format (expected YYYY-MM-DD)", field))?;
+
+    if date.year() < 1000 {
+        return Err(format!("The {} year is incomplete", field));
+    }
+
+    Ok(date)
+}
+
+/// Frequency select choices that take an interval next to them
+pub const CUSTOM_FREQUENCY_CHOICES: [&str; 2] = ["every_n_days", "every_n_weeks"];
+
+/// Frequency value to submit for a form's frequency choice and interval
+///
+/// Only the custom choices use the interval.
+pub fn form_frequency(choice: &str, interval: &str) -> String {
+    match choice {
+        "every_n_days" => format!("every_{}_days", interval.trim()),
+        "every_n_weeks" => format!("every_{}_weeks", interval.trim()),
+        other => other.to_string(),
+    }
+}
+
+/// Form choice and interval showing an existing frequency
+pub fn frequency_form_values(frequency: &Frequency) -> (String, String) {
+    match frequency {
+        Frequency::EveryNDays(n) => ("every_n_days".to_string(), n.to_string()),
+        Frequency::EveryNWeeks(n) => ("every_n_weeks".to_string(), n.to_string()),
+        other => (other.to_string(), String::new()),
+    }
+}
+
+/// Check that a start date isn't before `today`
+pub fn validate_start_date(start_date: Date, today: Date) -> Result<(), String> {
+    if start_date < today {
+        return Err("Start date must be today or in the future".to_string());
+    }
+    Ok(())
+}
+
+/// Weight of each member of a recurring debt, in member order
+///
+/// Members without an entry get the default weight of 1. Entries for anyone
+/// who isn't a member are rejected.
+pub fn resolve_weights(
+    member_ids: &[i64],
+    weights: &[(i64, String)],
+) -> Result<Vec<(i64, i64)>, String> {
+    if weights
+        .iter()
+        .any(|(user_id, _)| !member_ids.contains(user_id))
+    {
+        return Err("Weights can only be set for the selected members".to_string());
+    }
+
+    member_ids
+        .iter()
+        .map(|member_id| {
+            let weight = weights
+                .iter()
+                .find(|(user_id, _)| user_id == member_id)
+                .map_or(Ok(1), |(_, entry)| check_weight(entry))?;
+            Ok((*member_id, weight))
+        })
+        .collect()
+}
+
+/// Share amount to store for each member of a generated debt
+///
+/// All `None` when every member has the same weight, so the debt keeps a
+/// plain equal split.
+pub fn generated_share_amounts(
+    amount: Decimal,
+    weights: &[(i64, i64)],
+    strategy: RoundingStrategy,
+    creator_id: i64,
+) -> Vec<(i64, Option<String>)> {
+    if weights.windows(2).all(|pair| pair[0].1 == pair[1].1) {
+        return weights
+            .iter()
+            .map(|(user_id, _)| (*user_id, None))
+            .collect();
+    }
+
+    split_by_weight(amount, weights, strategy, creator_id)
+        .into_iter()
+        .map(|(user_id, share)| (user_id, Some(share.to_string())))
+        .collect()
+}
+
+/// Upcoming generation dates starting at `next`, up to and including `until`
+///
+/// Stops early at `end_date`. Capped so a daily schedule can't produce an
+/// unbounded list.
+pub fn upcoming_occurrences(
+    next: Date,
+    frequency: &Frequency,
+    end_date: Option<Date>,
+    until: Date,
+) -> Vec<Date> {
+    const MAX_OCCURRENCES: usize = 200;
+
+    let last = end_date.map_or(until, |end| end.min(until));
+    let mut dates = Vec::new();
+    let mut current = next;
+    while current <= last && dates.len() < MAX_OCCURRENCES {
+        dates.push(current);
+        let following = calculate_next_occurrence(current, frequency);
+        if following <= current {
+            break;
+        }
+        current = following;
+    }
+    dates
+}
+
+/// The next `count` generation dates starting at `next`
+///
+/// Fewer are returned when `end_date` comes first.
+pub fn next_occurrences(
+    next: Date,
+    frequency: &Frequency,
+    end_date: Option<Date>,
+    count: usize,
+) -> Vec<Date> {
+    let mut dates = Vec::with_capacity(count);
+    let mut current = next;
+    while dates.len() < count && end_date.is_none_or(|end| current <= end) {
+        dates.push(current);
+        let following = calculate_next_occurrence(current, frequency);
+        if following <= current {
+            break;
+        }
+        current = following;
+    }
+    dates
+}
+
+/// Escape a text value for use in an iCalendar property (RFC 5545 §3.3.11)
+pub fn ics_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use time::Month;
 
     use super::*;
 
+    #[test]
+    fn test_resolve_weights() {
+        assert_eq!(
+            resolve_weights(&[1, 2, 3], &[(2, "3".to_string())]).unwrap(),
+            vec![(1, 1), (2, 3), (3, 1)]
+        );
+        assert_eq!(
+            resolve_weights(&[1], &[(2, "3".to_string())]).unwrap_err(),
+            "Weights can only be set for the selected members"
+        );
+        assert!(resolve_weights(&[1], &[(1, "0".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_generated_share_amounts() {
+        assert_eq!(
+            generated_share_amounts(
+                Decimal::from(30),
+                &[(1, 2), (2, 2)],
+                RoundingStrategy::DistributeCents,
+                1
+            ),
+            vec![(1, None), (2, None)]
+        );
+        assert_eq!(
+            generated_share_amounts(
+                Decimal::from(30),
+                &[(1, 2), (2, 1)],
+                RoundingStrategy::DistributeCents,
+                1
+            ),
+            vec![
+                (1, Some("20.00".to_string())),
+                (2, Some("10.00".to_string()))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_form_frequency_round_trip() {
+        assert_eq!(form_frequency("monthly", "3"), "monthly");
+        assert_eq!(form_frequency("every_n_weeks", " 3 "), "every_3_weeks");
+        assert_eq!(
+            frequency_form_values(&Frequency::EveryNDays(10)),
+            ("every_n_days".to_string(), "10".to_string())
+        );
+
+        let (choice, interval) = frequency_form_values(&Frequency::EveryNWeeks(3));
+        assert_eq!(
+            form_frequency(&choice, &interval).parse::<Frequency>(),
+            Ok(Frequency::EveryNWeeks(3))
+        );
+    }
+
     #[test]
     fn test_calculate_next_occurrence_daily() {
         let date = Date::from_calendar_date(2026, Month::February, 15).unwrap();
@@ -109,4 +447,290 @@ mod tests {
             Date::from_calendar_date(2025, Month::February, 28).unwrap()
         );
     }
+
+    #[test]
+    fn test_calculate_next_occurrence_monthly_year_boundary() {
+        let date = Date::from_calendar_date(2026, Month::December, 31).unwrap();
+        let next = calculate_next_occurrence(date, &Frequency::Monthly);
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2027, Month::January, 31).unwrap()
+        );
+
+        // Month-end clamping into a leap February
+        let date = Date::from_calendar_date(2028, Month::January, 31).unwrap();
+        let next = calculate_next_occurrence(date, &Frequency::Monthly);
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2028, Month::February, 29).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_calculate_next_occurrence_yearly_keeps_regular_dates() {
+        let date = Date::from_calendar_date(2027, Month::February, 28).unwrap();
+        let next = calculate_next_occurrence(date, &Frequency::Yearly);
+        // Feb 28 stays Feb 28 even when the next year has a Feb 29
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2028, Month::February, 28).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_calculate_next_occurrence_every_n_days() {
+        // Crosses Feb 29 in a leap year
+        let date = Date::from_calendar_date(2028, Month::February, 27).unwrap();
+        let next = calculate_next_occurrence(date, &Frequency::EveryNDays(3));
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2028, Month::March, 1).unwrap()
+        );
+
+        let date = Date::from_calendar_date(2026, Month::February, 27).unwrap();
+        let next = calculate_next_occurrence(date, &Frequency::EveryNDays(3));
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2026, Month::March, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_calculate_next_occurrence_every_n_weeks() {
+        let date = Date::from_calendar_date(2026, Month::December, 21).unwrap();
+        let next = calculate_next_occurrence(date, &Frequency::EveryNWeeks(3));
+        assert_eq!(
+            next,
+            Date::from_calendar_date(2027, Month::January, 11).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_upcoming_occurrences_every_n_weeks() {
+        let next = Date::from_calendar_date(2026, Month::March, 2).unwrap();
+        let until = Date::from_calendar_date(2026, Month::April, 30).unwrap();
+
+        let dates = upcoming_occurrences(next, &Frequency::EveryNWeeks(3), None, until);
+        assert_eq!(
+            dates,
+            vec![
+                next,
+                Date::from_calendar_date(2026, Month::March, 23).unwrap(),
+                Date::from_calendar_date(2026, Month::April, 13).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_occurrences() {
+        let next = Date::from_calendar_date(2026, Month::January, 31).unwrap();
+
+        let dates = next_occurrences(next, &Frequency::Monthly, None, 3);
+        assert_eq!(
+            dates,
+            vec![
+                next,
+                Date::from_calendar_date(2026, Month::February, 28).unwrap(),
+                Date::from_calendar_date(2026, Month::March, 28).unwrap(),
+            ]
+        );
+
+        let end = Date::from_calendar_date(2026, Month::February, 28).unwrap();
+        assert_eq!(
+            next_occurrences(next, &Frequency::Monthly, Some(end), 6).len(),
+            2
+        );
+    }
+
+    fn debt_due_on(next_generation_date: Date) -> RecurringDebt {
+        RecurringDebt {
+            id: 1,
+            group_id: 1,
+            created_by: 1,
+            name: "Rent".to_string(),
+            amount: rust_decimal::Decimal::new(100, 0),
+            category: None,
+            frequency: Frequency::Monthly,
+            start_date: next_generation_date,
+            end_date: None,
+            next_generation_date,
+            is_active: true,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("UTC"), Some(UtcOffset::UTC));
+        assert_eq!(
+            parse_utc_offset("-05:00"),
+            Some(UtcOffset::from_hms(-5, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_utc_offset("+0530"),
+            Some(UtcOffset::from_hms(5, 30, 0).unwrap())
+        );
+        assert_eq!(parse_utc_offset("+15:00"), None);
+        assert_eq!(parse_utc_offset("Europe/Berlin"), None);
+    }
+
+    #[test]
+    fn test_start_date_validation_at_midnight_in_new_york() {
+        // 20:00 in New York on May 2nd is already May 3rd in UTC
+        let now = Date::from_calendar_date(2024, Month::May, 3)
+            .unwrap()
+            .with_hms(0, 0, 0)
+            .unwrap()
+            .assume_utc();
+        let new_york = utc_offset_from_minutes(-4 * 60).unwrap();
+        let may_2 = Date::from_calendar_date(2024, Month::May, 2).unwrap();
+
+        assert!(validate_start_date(may_2, today_at(now, new_york)).is_ok());
+        assert!(validate_start_date(may_2, today_at(now, UtcOffset::UTC)).is_err());
+
+        // One minute before midnight local time is still May 2nd
+        let before_midnight = now + time::Duration::hours(4) - time::Duration::minutes(1);
+        assert_eq!(today_at(before_midnight, new_york), may_2);
+        assert_eq!(
+            today_at(before_midnight + time::Duration::minutes(1), new_york),
+            may_2.next_day().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_generate_at_midnight_in_berlin() {
+        // 00:30 in Berlin (UTC+2) on May 3rd is still May 2nd in UTC
+        let now = Date::from_calendar_date(2024, Month::May, 2)
+            .unwrap()
+            .with_hms(22, 30, 0)
+            .unwrap()
+            .assume_utc();
+        let berlin = parse_utc_offset("+02:00").unwrap();
+        let debt = debt_due_on(Date::from_calendar_date(2024, Month::May, 3).unwrap());
+
+        assert!(should_generate(&debt, today_at(now, berlin)));
+        assert!(!should_generate(&debt, today_at(now, UtcOffset::UTC)));
+        assert!(!should_generate(
+            &debt,
+            today_at(now - time::Duration::hours(1), berlin)
+        ));
+    }
+
+    #[cfg(feature = "ssr")]
+    fn utc(month: Month, day: u8, hour: u8, minute: u8) -> OffsetDateTime {
+        Date::from_calendar_date(2026, month, day)
+            .unwrap()
+            .with_hms(hour, minute, 0)
+            .unwrap()
+            .assume_utc()
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_should_generate_at_midnight_in_auckland() {
+        // Auckland is UTC+13 in March (daylight saving time)
+        let debt = debt_due_on(Date::from_calendar_date(2026, Month::March, 1).unwrap());
+        let auckland = Some("Pacific/Auckland");
+
+        // 00:30 on March 1st in Auckland, while UTC is still on February 28th
+        let just_after = utc(Month::February, 28, 11, 30);
+        assert!(should_generate(&debt, group_today_at(just_after, auckland)));
+        assert!(!should_generate(
+            &debt,
+            today_at(just_after, UtcOffset::UTC)
+        ));
+
+        // 23:30 on February 28th in Auckland
+        let just_before = utc(Month::February, 28, 10, 30);
+        assert!(!should_generate(
+            &debt,
+            group_today_at(just_before, auckland)
+        ));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_should_generate_at_midnight_in_los_angeles() {
+        let los_angeles = Some("America/Los_Angeles");
+
+        // 23:30 on February 28th in Los Angeles (UTC-8) is already March 1st in UTC
+        let debt = debt_due_on(Date::from_calendar_date(2026, Month::March, 1).unwrap());
+        let just_before = utc(Month::March, 1, 7, 30);
+        assert!(!should_generate(
+            &debt,
+            group_today_at(just_before, los_angeles)
+        ));
+        assert!(should_generate(
+            &debt,
+            today_at(just_before, UtcOffset::UTC)
+        ));
+        let just_after = utc(Month::March, 1, 8, 30);
+        assert!(should_generate(
+            &debt,
+            group_today_at(just_after, los_angeles)
+        ));
+
+        // Midnight moves an hour earlier with daylight saving time (UTC-7)
+        let debt = debt_due_on(Date::from_calendar_date(2026, Month::July, 1).unwrap());
+        assert!(!should_generate(
+            &debt,
+            group_today_at(utc(Month::July, 1, 6, 30), los_angeles)
+        ));
+        assert!(should_generate(
+            &debt,
+            group_today_at(utc(Month::July, 1, 7, 30), los_angeles)
+        ));
+    }
+
+    #[cfg(feature = "ssr")]
+    #[test]
+    fn test_check_timezone() {
+        assert_eq!(
+            check_timezone(Some(" Pacific/Auckland ")),
+            Ok(Some("Pacific/Auckland".to_string()))
+        );
+        assert_eq!(check_timezone(Some("")), Ok(None));
+        assert_eq!(check_timezone(None), Ok(None));
+        assert!(check_timezone(Some("+02:00")).is_err());
+        assert!(check_timezone(Some("Mars/Olympus")).is_err());
+    }
+
+    #[test]
+    fn test_parse_form_date_rejects_partial_year() {
+        assert!(parse_form_date("2024-05-02", "start date").is_ok());
+        assert_eq!(
+            parse_form_date("0202-05-02", "start date"),
+            Err("The start date year is incomplete".to_string())
+        );
+        assert!(parse_form_date("2024-5", "start date").is_err());
+    }
+
+    #[test]
+    fn test_upcoming_occurrences_respects_end_date() {
+        let next = Date::from_calendar_date(2026, Month::January, 1).unwrap();
+        let until = Date::from_calendar_date(2026, Month::June, 30).unwrap();
+        let end = Date::from_calendar_date(2026, Month::March, 15).unwrap();
+
+        let dates = upcoming_occurrences(next, &Frequency::Monthly, Some(end), until);
+        assert_eq!(
+            dates,
+            vec![
+                Date::from_calendar_date(2026, Month::January, 1).unwrap(),
+                Date::from_calendar_date(2026, Month::February, 1).unwrap(),
+                Date::from_calendar_date(2026, Month::March, 1).unwrap(),
+            ]
+        );
+
+        let dates = upcoming_occurrences(next, &Frequency::Monthly, None, until);
+        assert_eq!(dates.len(), 6);
+    }
+
+    #[test]
+    fn test_ics_escape() {
+        assert_eq!(
+            ics_escape("Rent; flat, 1\\2\nnote"),
+            "Rent\\; flat\\, 1\\\\2\\nnote"
+        );
+    }
 }
@@ -0,0 +1,217 @@
+//! Approving or rejecting debts proposed in groups that require approval
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{
+            debt_summary, ensure_group_writable, group_currency, is_group_admin, log_group_activity,
+        },
+    },
+    shared_debts::models::DebtStatus,
+    transactions::balances::{add_shared_debt_to_balances, remove_shared_debt_from_balances},
+};
+#[cfg(feature = "ssr")]
+use crate::validation::{rules::MAX_DESCRIPTION_LENGTH, validate_description};
+
+/// Server function: Approve a debt waiting for approval so it counts towards
+/// balances (admins only)
+#[server(ApproveSharedDebt)]
+pub async fn approve_shared_debt(debt_id: i64) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group_id = review_debt(&pool, debt_id, user.id, DebtStatus::Approved, None).await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, group_id, GroupEvent::SharedDebtsChanged);
+
+    Ok(())
+}
+
+/// Server function: Send a debt waiting for approval back to its creator
+/// with the reason (admins only)
+#[server(RejectSharedDebt)]
+pub async fn reject_shared_debt(debt_id: i64, reason: String) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let reason = validate_description(&reason, MAX_DESCRIPTION_LENGTH)?;
+    if reason.is_empty() {
+        return Err(AppError::validation("Please say why you reject the debt"));
+    }
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group_id = review_debt(&pool, debt_id, user.id, DebtStatus::Draft, Some(&reason)).await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, group_id, GroupEvent::SharedDebtsChanged);
+
+    Ok(())
+}
+
+/// Move a pending debt to `status` on behalf of `user_id`, who must be an
+/// admin of its group, and return the group id
+#[cfg(feature = "ssr")]
+async fn review_debt(
+    pool: &sqlx::SqlitePool,
+    debt_id: i64,
+    user_id: i64,
+    status: DebtStatus,
+    reason: Option<&str>,
+) -> Result<i64, AppError> {
+    let debt = sqlx::query!(
+        "SELECT group_id, name, amount FROM shared_debts WHERE id = ?",
+        debt_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found"))?;
+
+    if !is_group_admin(pool, debt.group_id, user_id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can approve or reject debts",
+        ));
+    }
+    ensure_group_writable(pool, debt.group_id, user_id).await?;
+
+    let amount = debt
+        .amount
+        .parse::<rust_decimal::Decimal>()
+        .map_err(AppError::internal)?;
+
+    let mut tx = pool.begin().await?;
+
+    let status_str = status.as_str();
+    remove_shared_debt_from_balances(&mut tx, debt_id).await?;
+    let reviewed = sqlx::query!(
+        r#"
+        UPDATE shared_debts
+        SET status = ?, rejection_reason = ?, updated_at = CURRENT_TIMESTAMP, version = version + 1
+        WHERE id = ? AND status = 'pending'
+        "#,
+        status_str,
+        reason,
+        debt_id
+    )
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    if reviewed == 0 {
+        return Err(AppError::conflict("This debt isn't waiting for approval"));
+    }
+    add_shared_debt_to_balances(&mut tx, debt_id).await?;
+
+    let currency = group_currency(&mut *tx, debt.group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        debt.group_id,
+        user_id,
+        ActivityEntity::SharedDebt,
+        debt_id,
+        ActivityAction::Updated,
+        &debt_summary(&debt.name, amount, &currency),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(debt.group_id)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::features::transactions::{handlers::compute_group_balances, models::NetType};
+
+    #[tokio::test]
+    async fn test_only_approved_debts_count() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // bob proposed two debts in alice's family group
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by, require_debt_approval) VALUES ('Family', 1, 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, status) VALUES (1, 2, 2, 'Cinema', '20', 'pending'), (1, 2, 2, 'Console', '400', 'pending')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1), (1, 2), (2, 1), (2, 2)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        async fn bob_net(pool: &sqlx::SqlitePool) -> (NetType, String) {
+            let balance = compute_group_balances(pool, 1)
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|balance| balance.user_id == 2)
+                .unwrap();
+            (balance.net_type, balance.net_amount)
+        }
+        assert_eq!(bob_net(&pool).await.0, NetType::Neutral);
+
+        let err = review_debt(&pool, 1, 2, DebtStatus::Approved, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+
+        assert_eq!(
+            review_debt(&pool, 1, 1, DebtStatus::Approved, None)
+                .await
+                .unwrap(),
+            1
+        );
+        review_debt(&pool, 2, 1, DebtStatus::Draft, Some("Too expensive"))
+            .await
+            .unwrap();
+        let (net_type, net_amount) = bob_net(&pool).await;
+        assert_eq!(net_type, NetType::Positive);
+        assert_eq!(
+            net_amount.parse::<rust_decimal::Decimal>().unwrap(),
+            10.into()
+        );
+
+        let reason: Option<String> =
+            sqlx::query_scalar("SELECT rejection_reason FROM shared_debts WHERE id = 2")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(reason.as_deref(), Some("Too expensive"));
+
+        // Only pending debts can be reviewed
+        let err = review_debt(&pool, 2, 1, DebtStatus::Approved, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+    }
+}
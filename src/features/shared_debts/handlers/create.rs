@@ -2,44 +2,190 @@ use leptos::prelude::*;
 #[cfg(feature = "ssr")]
 use leptos_axum::extract;
 #[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::features::{
+    auth::{models::UserSession, utils::get_user_from_session},
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{
+            debt_summary, ensure_group_writable, group_currency, log_group_activity,
+            proposed_debt_status,
+        },
+    },
+    notifications::{NotificationPayload, notify_users},
+    recurring_debts::utils::app_today,
+    shared_debts::{
+        models::{DebtCategory, ForeignAmount, NewSharedDebt},
+        utils::{resolve_custom_shares, resolve_foreign_amount},
+    },
+    transactions::balances::add_shared_debt_to_balances,
+    webhooks::{WebhookDispatcher, WebhookEvent},
+};
 #[cfg(feature = "ssr")]
-use crate::validation::{validate_amount, validate_name};
+use crate::validation::{
+    rules::{MAX_NAME_LENGTH, check_due_date, check_incurred_on},
+    validate_money, validate_name,
+};
 
 /// Server function: Create a new shared debt
 #[server(CreateSharedDebt)]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_shared_debt(
     group_id: i64,
     name: String,
     amount: String,
     member_ids: Vec<i64>,
-) -> Result<i64, ServerFnError> {
+    /// Member who paid; defaults to the caller
+    #[server(default)]
+    payer_id: Option<i64>,
+    /// Share per member as an amount or percentage; split equally when omitted
+    #[server(default)]
+    custom_shares: Option<Vec<(i64, String)>>,
+    /// One of the predefined categories, e.g. "Groceries"
+    #[server(default)]
+    category: Option<String>,
+    /// Date by which the debt should be paid back (YYYY-MM-DD), today or later
+    #[server(default)]
+    due_date: Option<String>,
+    /// Amount paid in another currency; together with `original_currency`
+    /// and `exchange_rate` it replaces `amount`, which is then converted
+    #[server(default)]
+    original_amount: Option<String>,
+    /// Three-letter code of the currency paid in, e.g. "THB"
+    #[server(default)]
+    original_currency: Option<String>,
+    /// Euros per unit of the original currency, entered by hand
+    #[server(default)]
+    exchange_rate: Option<String>,
+    /// Day the expense happened (YYYY-MM-DD); today when omitted
+    #[server(default)]
+    incurred_on: Option<String>,
+) -> Result<i64, AppError> {
     use sqlx::SqlitePool;
 
+    let debt = check_new_shared_debt(NewSharedDebt {
+        name,
+        amount,
+        member_ids,
+        payer_id,
+        custom_shares,
+        category,
+        due_date,
+        original_amount,
+        original_currency,
+        exchange_rate,
+        incurred_on,
+    })?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    let webhooks = expect_context::<WebhookDispatcher>();
+
+    create_shared_debt_internal(&pool, &broadcaster, &webhooks, &user, group_id, debt).await
+}
+
+/// A [`NewSharedDebt`] that passed [`check_new_shared_debt`]
+#[cfg(feature = "ssr")]
+pub struct CheckedSharedDebt {
+    name: String,
+    amount: Decimal,
+    member_ids: Vec<i64>,
+    payer_id: Option<i64>,
+    shares: Option<Vec<(i64, Decimal)>>,
+    category: Option<DebtCategory>,
+    due_date: Option<String>,
+    incurred_on: String,
+    original: Option<ForeignAmount>,
+}
+
+/// Check the fields of a new debt that need no database
+#[cfg(feature = "ssr")]
+pub fn check_new_shared_debt(debt: NewSharedDebt) -> Result<CheckedSharedDebt, AppError> {
     // Validate debt name
-    let name = validate_name(&name, 1, 255, "Debt name")?;
+    let name = validate_name(&debt.name, 1, MAX_NAME_LENGTH, "Name")?;
+
+    let original = resolve_foreign_amount(
+        debt.original_amount.as_deref(),
+        debt.original_currency.as_deref(),
+        debt.exchange_rate.as_deref(),
+    )
+    .map_err(AppError::Validation)?;
+
+    // Validate amount; debts paid in another currency are booked converted
+    let amount = match &original {
+        Some(original) => validate_money(&original.converted().to_string())?,
+        None => validate_money(&debt.amount)?,
+    };
+
+    let category =
+        DebtCategory::parse_optional(debt.category.as_deref()).map_err(AppError::Validation)?;
+
+    let due_date = check_due_date(debt.due_date.as_deref(), app_today())
+        .map_err(AppError::Validation)?
+        .map(|due_date| due_date.to_string());
 
-    // Validate amount
-    let amount_decimal = validate_amount(&amount)?;
+    let incurred_on = check_incurred_on(debt.incurred_on.as_deref(), app_today())
+        .map_err(AppError::Validation)?
+        .to_string();
 
-    if member_ids.is_empty() {
-        return Err(ServerFnError::new(
+    if debt.member_ids.is_empty() {
+        return Err(AppError::validation(
             "At least one member must be selected to split the debt",
         ));
     }
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let shares = resolve_custom_shares(amount, &debt.member_ids, debt.custom_shares.as_deref())
+        .map_err(AppError::Validation)?;
 
-    let user = get_user_from_session(&session)
-        .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated. Please log in."))?;
+    Ok(CheckedSharedDebt {
+        name,
+        amount,
+        member_ids: debt.member_ids,
+        payer_id: debt.payer_id,
+        shares,
+        category,
+        due_date,
+        incurred_on,
+        original,
+    })
+}
 
-    let pool = expect_context::<SqlitePool>();
+/// Add a checked debt to a group on behalf of `user` and return its id
+///
+/// Used by [`create_shared_debt`] and the JSON API, which bring their own
+/// pool, broadcaster and webhook dispatcher.
+#[cfg(feature = "ssr")]
+pub async fn create_shared_debt_internal(
+    pool: &sqlx::SqlitePool,
+    broadcaster: &GroupEventBroadcaster,
+    webhooks: &WebhookDispatcher,
+    user: &UserSession,
+    group_id: i64,
+    debt: CheckedSharedDebt,
+) -> Result<i64, AppError> {
+    let CheckedSharedDebt {
+        name,
+        amount: amount_decimal,
+        member_ids,
+        payer_id,
+        shares,
+        category,
+        due_date,
+        incurred_on,
+        original,
+    } = debt;
 
     // Check if user is a member of the group
     let is_member = sqlx::query!(
@@ -47,15 +193,30 @@ pub async fn create_shared_debt(
         group_id,
         user.id
     )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .fetch_one(pool)
+    .await?;
 
     if is_member.count == 0 {
-        return Err(ServerFnError::new(
+        return Err(AppError::forbidden(
             "You don't have permission to access this group",
         ));
     }
+    ensure_group_writable(pool, group_id, user.id).await?;
+
+    let payer_id = payer_id.unwrap_or(user.id);
+    if payer_id != user.id {
+        let payer_is_member = sqlx::query!(
+            "SELECT COUNT(*) as \"count!\" FROM group_members WHERE group_id = ? AND user_id = ?",
+            group_id,
+            payer_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if payer_is_member.count == 0 {
+            return Err(AppError::validation("The payer is not part of this group"));
+        }
+    }
 
     // Validate all selected members are part of the group
     for member_id in &member_ids {
@@ -64,54 +225,128 @@ pub async fn create_shared_debt(
             group_id,
             member_id
         )
-        .fetch_one(&pool)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .fetch_one(pool)
+        .await?;
 
         if is_group_member.count == 0 {
-            return Err(ServerFnError::new(
+            return Err(AppError::validation(
                 "Some selected members are not part of this group",
             ));
         }
     }
 
+    // Debts by members who aren't admins may need an admin's approval first
+    let status = proposed_debt_status(pool, group_id, user.id).await?;
+    let status_str = status.as_str();
+
     // Start a transaction
-    let mut tx = pool
-        .begin()
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let mut tx = pool.begin().await?;
 
     // Insert the shared debt
     let amount_str = amount_decimal.to_string();
+    let category_str = category.map(|category| category.as_str());
+    let original_amount = original
+        .as_ref()
+        .map(|original| original.amount.to_string());
+    let original_currency = original.as_ref().map(|original| original.currency.as_str());
+    let exchange_rate = original
+        .as_ref()
+        .map(|original| original.exchange_rate.to_string());
     let result = sqlx::query!(
-        "INSERT INTO shared_debts (group_id, created_by, name, amount) VALUES (?, ?, ?, ?)",
+        r#"
+        INSERT INTO shared_debts
+            (group_id, created_by, payer_id, name, amount, category, due_date, incurred_on, original_amount, original_currency, exchange_rate, status)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
         group_id,
         user.id,
+        payer_id,
         name,
-        amount_str
+        amount_str,
+        category_str,
+        due_date,
+        incurred_on,
+        original_amount,
+        original_currency,
+        exchange_rate,
+        status_str
     )
     .execute(&mut *tx)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     let debt_id = result.last_insert_rowid();
 
+    // Everyone with a share hears about the debt, except whoever added it
+    let notified: Vec<i64> = member_ids
+        .iter()
+        .copied()
+        .filter(|member_id| *member_id != user.id)
+        .collect();
+
     // Add members to the shared debt
     for member_id in member_ids {
+        let share_amount = shares.as_ref().and_then(|shares| {
+            shares
+                .iter()
+                .find(|(user_id, _)| *user_id == member_id)
+                .map(|(_, share)| share.to_string())
+        });
         sqlx::query!(
-            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (?, ?)",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount) VALUES (?, ?, ?)",
             debt_id,
-            member_id
+            member_id,
+            share_amount
         )
         .execute(&mut *tx)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .await?;
     }
+    add_shared_debt_to_balances(&mut tx, debt_id).await?;
+
+    let currency = group_currency(&mut *tx, group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        group_id,
+        user.id,
+        ActivityEntity::SharedDebt,
+        debt_id,
+        ActivityAction::Created,
+        &debt_summary(&name, amount_decimal, &currency),
+    )
+    .await?;
+
+    notify_users(
+        &mut tx,
+        group_id,
+        &notified,
+        &NotificationPayload::DebtAdded {
+            debt_id,
+            debt_name: name.clone(),
+            amount: amount_decimal,
+            added_by: user.username.clone(),
+        },
+    )
+    .await?;
 
     // Commit transaction
-    tx.commit()
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    tx.commit().await?;
+
+    broadcast_group_event(broadcaster, group_id, GroupEvent::SharedDebtsChanged);
+
+    webhooks.dispatch(
+        group_id,
+        WebhookEvent::DebtCreated,
+        serde_json::json!({
+            "id": debt_id,
+            "name": name,
+            "amount": amount_str,
+            "category": category_str,
+            "due_date": due_date,
+            "incurred_on": incurred_on,
+            "payer_id": payer_id,
+            "created_by": user.username,
+        }),
+    );
 
     Ok(debt_id)
 }
@@ -4,42 +4,92 @@ use leptos_axum::extract;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{
+            debt_summary, ensure_group_writable, group_currency, is_group_admin, log_group_activity,
+        },
+    },
+    transactions::balances::remove_shared_debt_from_balances,
+    webhooks::{WebhookDispatcher, WebhookEvent},
+};
 
 /// Server function: Delete a shared debt
 #[server(DeleteSharedDebt)]
-pub async fn delete_shared_debt(debt_id: i64) -> Result<(), ServerFnError> {
+pub async fn delete_shared_debt(debt_id: i64) -> Result<(), AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
 
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check if user is the creator of the debt
-    let debt = sqlx::query!("SELECT created_by FROM shared_debts WHERE id = ?", debt_id)
-        .fetch_optional(&pool)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?
-        .ok_or_else(|| ServerFnError::new("Shared debt not found"))?;
+    // Check if user is the creator of the debt or a group admin
+    let debt = sqlx::query!(
+        "SELECT created_by, group_id, name, amount FROM shared_debts WHERE id = ?",
+        debt_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found"))?;
 
-    if debt.created_by != user.id {
-        return Err(ServerFnError::new(
-            "Unauthorized: Only the creator can delete this debt",
+    if debt.created_by != user.id && !is_group_admin(&pool, debt.group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only the creator or a group admin can delete this debt",
         ));
     }
+    ensure_group_writable(&pool, debt.group_id, user.id).await?;
+
+    let amount = debt
+        .amount
+        .parse::<rust_decimal::Decimal>()
+        .map_err(AppError::internal)?;
+
+    let mut tx = pool.begin().await?;
 
     // Delete the debt (CASCADE will handle related data)
+    remove_shared_debt_from_balances(&mut tx, debt_id).await?;
     sqlx::query!("DELETE FROM shared_debts WHERE id = ?", debt_id)
-        .execute(&pool)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .execute(&mut *tx)
+        .await?;
+
+    let currency = group_currency(&mut *tx, debt.group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        debt.group_id,
+        user.id,
+        ActivityEntity::SharedDebt,
+        debt_id,
+        ActivityAction::Deleted,
+        &debt_summary(&debt.name, amount, &currency),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, debt.group_id, GroupEvent::SharedDebtsChanged);
+
+    let webhooks = expect_context::<WebhookDispatcher>();
+    webhooks.dispatch(
+        debt.group_id,
+        WebhookEvent::DebtDeleted,
+        serde_json::json!({
+            "id": debt_id,
+            "name": debt.name,
+            "amount": debt.amount,
+            "deleted_by": user.username,
+        }),
+    );
 
     Ok(())
 }
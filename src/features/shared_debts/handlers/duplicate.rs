@@ -0,0 +1,283 @@
+//! Duplicating shared debts
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::shared_debts::models::DuplicatedDebt;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{
+            debt_summary, ensure_group_writable, group_currency, log_group_activity,
+            proposed_debt_status,
+        },
+    },
+    shared_debts::utils::copy_name,
+    transactions::balances::add_shared_debt_to_balances,
+    webhooks::{WebhookDispatcher, WebhookEvent},
+};
+
+/// Server function: Copy a shared debt into a new one paid by the caller
+///
+/// The copy keeps the amount, original amount, category and members; members
+/// who have left the group since are dropped and reported in the result.
+#[server(DuplicateSharedDebt)]
+pub async fn duplicate_shared_debt(debt_id: i64) -> Result<DuplicatedDebt, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let mut tx = pool.begin().await?;
+    let copy = copy_shared_debt(&mut tx, debt_id, user.id).await?;
+    tx.commit().await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, copy.group_id, GroupEvent::SharedDebtsChanged);
+
+    let webhooks = expect_context::<WebhookDispatcher>();
+    webhooks.dispatch(
+        copy.group_id,
+        WebhookEvent::DebtCreated,
+        serde_json::json!({
+            "id": copy.duplicate.id,
+            "name": copy.name,
+            "amount": copy.amount,
+            "category": copy.category,
+            "due_date": None::<String>,
+            "payer_id": user.id,
+            "created_by": user.username,
+        }),
+    );
+
+    Ok(copy.duplicate)
+}
+
+/// What [`copy_shared_debt`] created, for the events sent afterwards
+#[cfg(feature = "ssr")]
+struct SharedDebtCopy {
+    group_id: i64,
+    name: String,
+    amount: String,
+    category: Option<String>,
+    duplicate: DuplicatedDebt,
+}
+
+/// Insert a copy of `debt_id` created and paid by `user_id`
+///
+/// Custom shares are only kept while every member is still in the group;
+/// otherwise they would no longer add up, so the copy is split equally.
+#[cfg(feature = "ssr")]
+async fn copy_shared_debt(
+    conn: &mut sqlx::SqliteConnection,
+    debt_id: i64,
+    user_id: i64,
+) -> Result<SharedDebtCopy, AppError> {
+    let debt = sqlx::query!(
+        "SELECT group_id, name, amount, category, original_amount, original_currency, exchange_rate FROM shared_debts WHERE id = ?",
+        debt_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found"))?;
+
+    let is_member = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = ? AND user_id = ?) as "exists!: bool""#,
+        debt.group_id,
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if !is_member {
+        return Err(AppError::forbidden(
+            "You don't have permission to access this group",
+        ));
+    }
+    ensure_group_writable(&mut *conn, debt.group_id, user_id).await?;
+
+    let members = sqlx::query!(
+        r#"
+        SELECT
+            sdu.user_id,
+            u.username,
+            sdu.share_amount,
+            EXISTS(
+                SELECT 1 FROM group_members gm
+                WHERE gm.group_id = ? AND gm.user_id = sdu.user_id
+            ) as "is_member!: bool"
+        FROM shared_debt_user sdu
+        INNER JOIN users u ON u.id = sdu.user_id
+        WHERE sdu.shared_debt_id = ?
+        ORDER BY sdu.id
+        "#,
+        debt.group_id,
+        debt_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let (kept, dropped): (Vec<_>, Vec<_>) =
+        members.into_iter().partition(|member| member.is_member);
+    if kept.is_empty() {
+        return Err(AppError::validation(
+            "None of the debt's members are still in the group",
+        ));
+    }
+    let keep_shares = dropped.is_empty();
+
+    let name = copy_name(&debt.name);
+    let status = proposed_debt_status(&mut *conn, debt.group_id, user_id).await?;
+    let status_str = status.as_str();
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO shared_debts
+            (group_id, created_by, payer_id, name, amount, category, original_amount, original_currency, exchange_rate, status)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        debt.group_id,
+        user_id,
+        user_id,
+        name,
+        debt.amount,
+        debt.category,
+        debt.original_amount,
+        debt.original_currency,
+        debt.exchange_rate,
+        status_str
+    )
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    for member in &kept {
+        let share_amount = member.share_amount.as_deref().filter(|_| keep_shares);
+        sqlx::query!(
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount) VALUES (?, ?, ?)",
+            id,
+            member.user_id,
+            share_amount
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    add_shared_debt_to_balances(conn, id).await?;
+
+    let amount = debt
+        .amount
+        .parse::<rust_decimal::Decimal>()
+        .map_err(AppError::internal)?;
+    let currency = group_currency(&mut *conn, debt.group_id).await?;
+    log_group_activity(
+        &mut *conn,
+        debt.group_id,
+        user_id,
+        ActivityEntity::SharedDebt,
+        id,
+        ActivityAction::Created,
+        &debt_summary(&name, amount, &currency),
+    )
+    .await?;
+
+    Ok(SharedDebtCopy {
+        group_id: debt.group_id,
+        name,
+        amount: debt.amount,
+        category: debt.category,
+        duplicate: DuplicatedDebt {
+            id,
+            dropped_members: dropped.into_iter().map(|member| member.username).collect(),
+        },
+    })
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_copy_drops_members_who_left() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, category) VALUES (1, 1, 1, 'Groceries week 42', '90', 'Groceries')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount) VALUES (1, 1, '50'), (1, 2, '20'), (1, 3, '20')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+
+        // With everyone still around, the custom split is kept
+        let copy = copy_shared_debt(&mut conn, 1, 2).await.unwrap();
+        assert!(copy.duplicate.dropped_members.is_empty());
+        let row: (String, String, Option<String>, i64, i64) = sqlx::query_as(
+            "SELECT name, amount, category, created_by, payer_id FROM shared_debts WHERE id = ?",
+        )
+        .bind(copy.duplicate.id)
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            row,
+            (
+                "Groceries week 42 (copy)".to_string(),
+                "90".to_string(),
+                Some("Groceries".to_string()),
+                2,
+                2
+            )
+        );
+        let shares: Vec<Option<String>> = sqlx::query_scalar(
+            "SELECT share_amount FROM shared_debt_user WHERE shared_debt_id = ? ORDER BY user_id",
+        )
+        .bind(copy.duplicate.id)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            shares,
+            vec![Some("50".into()), Some("20".into()), Some("20".into())]
+        );
+
+        // Once carol leaves the group, carol is dropped and the rest split equally
+        sqlx::query("DELETE FROM group_members WHERE user_id = 3")
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        let copy = copy_shared_debt(&mut conn, 1, 1).await.unwrap();
+        assert_eq!(copy.duplicate.dropped_members, vec!["carol".to_string()]);
+        let shares: Vec<(i64, Option<String>)> = sqlx::query_as(
+            "SELECT user_id, share_amount FROM shared_debt_user WHERE shared_debt_id = ? ORDER BY user_id",
+        )
+        .bind(copy.duplicate.id)
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(shares, vec![(1, None), (2, None)]);
+
+        // Users outside the group can't copy its debts
+        let err = copy_shared_debt(&mut conn, 1, 3).await.err().unwrap();
+        assert!(matches!(err, AppError::Forbidden(_)));
+    }
+}
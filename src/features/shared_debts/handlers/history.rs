@@ -0,0 +1,143 @@
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+use crate::features::shared_debts::models::SharedDebtRevision;
+
+/// Maximum number of revisions returned per page
+pub const MAX_HISTORY_PAGE_SIZE: i64 = 50;
+
+/// Server function: Get the edit history of a shared debt, newest first
+#[server(GetSharedDebtHistory)]
+pub async fn get_shared_debt_history(
+    debt_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SharedDebtRevision>, AppError> {
+    use std::collections::HashMap;
+
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    // Verify user is a member of the debt's group
+    let has_access = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!"
+        FROM shared_debts sd
+        INNER JOIN group_members gm ON sd.group_id = gm.group_id
+        WHERE sd.id = ? AND gm.user_id = ?
+        "#,
+        debt_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if has_access.count == 0 {
+        return Err(AppError::not_found(
+            "Shared debt not found or access denied",
+        ));
+    }
+
+    let limit = limit.clamp(1, MAX_HISTORY_PAGE_SIZE);
+    let offset = offset.max(0);
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            r.id as "id!",
+            r.shared_debt_id as "shared_debt_id!",
+            u.username as "editor_username?",
+            r.old_name,
+            r.new_name,
+            r.old_amount,
+            r.new_amount,
+            r.old_member_ids,
+            r.new_member_ids,
+            r.created_at as "created_at!: time::OffsetDateTime"
+        FROM shared_debt_revisions r
+        LEFT JOIN users u ON r.edited_by = u.id
+        WHERE r.shared_debt_id = ?
+        ORDER BY r.created_at DESC, r.id DESC
+        LIMIT ? OFFSET ?
+        "#,
+        debt_id,
+        limit,
+        offset
+    )
+    .fetch_all(&pool)
+    .await?;
+
+    // Resolve member ids to usernames once for all revisions
+    let usernames: HashMap<i64, String> = sqlx::query!(
+        r#"
+        SELECT u.id as "id!", u.username
+        FROM users u
+        INNER JOIN group_members gm ON u.id = gm.user_id
+        INNER JOIN shared_debts sd ON sd.group_id = gm.group_id
+        WHERE sd.id = ?
+        UNION
+        SELECT u.id as "id!", u.username
+        FROM users u
+        INNER JOIN shared_debt_user sdu ON u.id = sdu.user_id
+        WHERE sdu.shared_debt_id = ?
+        "#,
+        debt_id,
+        debt_id
+    )
+    .fetch_all(&pool)
+    .await?
+    .into_iter()
+    .map(|row| (row.id, row.username))
+    .collect();
+
+    let member_names = |json: &str| -> Result<Vec<String>, AppError> {
+        let ids: Vec<i64> = serde_json::from_str(json).map_err(AppError::internal)?;
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                usernames
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| "former member".to_string())
+            })
+            .collect())
+    };
+
+    rows.into_iter()
+        .map(|row| {
+            Ok(SharedDebtRevision {
+                id: row.id,
+                shared_debt_id: row.shared_debt_id,
+                editor_username: row.editor_username,
+                old_amount: row
+                    .old_amount
+                    .parse::<Decimal>()
+                    .map_err(AppError::internal)?,
+                new_amount: row
+                    .new_amount
+                    .parse::<Decimal>()
+                    .map_err(AppError::internal)?,
+                old_members: member_names(&row.old_member_ids)?,
+                new_members: member_names(&row.new_member_ids)?,
+                old_name: row.old_name,
+                new_name: row.new_name,
+                created_at: row.created_at,
+            })
+        })
+        .collect()
+}
@@ -2,13 +2,23 @@
 //!
 //! This module contains all server-side handlers for shared debt operations.
 
+mod approval;
 mod create;
 mod delete;
+mod duplicate;
+mod history;
+mod presets;
 mod query;
+mod settle;
 mod update;
 
 // Re-export all server functions
+pub use approval::*;
 pub use create::*;
 pub use delete::*;
+pub use duplicate::*;
+pub use history::*;
+pub use presets::*;
 pub use query::*;
+pub use settle::*;
 pub use update::*;
@@ -0,0 +1,333 @@
+//! Debt presets: member selections a user reuses for new debts
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::shared_debts::models::DebtPreset;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session, groups::utils::ensure_group_writable,
+    shared_debts::models::DebtCategory,
+};
+#[cfg(feature = "ssr")]
+use crate::validation::{rules::MAX_NAME_LENGTH, validate_name};
+
+/// Presets a user can keep per group
+pub const MAX_DEBT_PRESETS: usize = 10;
+
+/// Server function: Save the current user's preset, replacing one with the
+/// same name
+#[server(SaveDebtPreset)]
+pub async fn save_debt_preset(
+    group_id: i64,
+    name: String,
+    member_ids: Vec<i64>,
+    /// One of the predefined categories, e.g. "Groceries"
+    #[server(default)]
+    category: Option<String>,
+) -> Result<DebtPreset, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let mut tx = pool.begin().await?;
+    let preset =
+        store_debt_preset(&mut tx, group_id, user.id, &name, &member_ids, category).await?;
+    tx.commit().await?;
+
+    Ok(preset)
+}
+
+/// Server function: The current user's presets in a group, by name
+#[server(GetDebtPresets)]
+pub async fn get_debt_presets(group_id: i64) -> Result<Vec<DebtPreset>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    ensure_member(&pool, group_id, user.id).await?;
+
+    load_debt_presets(&pool, group_id, user.id).await
+}
+
+#[cfg(feature = "ssr")]
+async fn ensure_member(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+    user_id: i64,
+) -> Result<(), AppError> {
+    let is_member = sqlx::query_scalar!(
+        r#"SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = ? AND user_id = ?) as "exists!: bool""#,
+        group_id,
+        user_id
+    )
+    .fetch_one(executor)
+    .await?;
+
+    if !is_member {
+        return Err(AppError::forbidden(
+            "You don't have permission to access this group",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "ssr")]
+async fn store_debt_preset(
+    conn: &mut sqlx::SqliteConnection,
+    group_id: i64,
+    user_id: i64,
+    name: &str,
+    member_ids: &[i64],
+    category: Option<String>,
+) -> Result<DebtPreset, AppError> {
+    let name = validate_name(name, 1, MAX_NAME_LENGTH, "Name")?;
+    let category =
+        DebtCategory::parse_optional(category.as_deref()).map_err(AppError::Validation)?;
+    if member_ids.is_empty() {
+        return Err(AppError::validation("A preset needs at least one member"));
+    }
+
+    ensure_member(&mut *conn, group_id, user_id).await?;
+    ensure_group_writable(&mut *conn, group_id, user_id).await?;
+
+    let mut member_ids = member_ids.to_vec();
+    member_ids.sort_unstable();
+    member_ids.dedup();
+    for member_id in &member_ids {
+        let is_group_member = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = ? AND user_id = ?) as "exists!: bool""#,
+            group_id,
+            member_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        if !is_group_member {
+            return Err(AppError::validation(
+                "Some selected members are not part of this group",
+            ));
+        }
+    }
+
+    let existing = sqlx::query_scalar!(
+        r#"SELECT id as "id!" FROM debt_presets WHERE group_id = ? AND user_id = ? AND name = ?"#,
+        group_id,
+        user_id,
+        name
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let category_str = category.map(|category| category.as_str());
+    let id = match existing {
+        Some(id) => {
+            sqlx::query!(
+                "UPDATE debt_presets SET category = ? WHERE id = ?",
+                category_str,
+                id
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query!("DELETE FROM debt_preset_members WHERE preset_id = ?", id)
+                .execute(&mut *conn)
+                .await?;
+            id
+        }
+        None => {
+            let count = sqlx::query_scalar!(
+                "SELECT COUNT(*) FROM debt_presets WHERE group_id = ? AND user_id = ?",
+                group_id,
+                user_id
+            )
+            .fetch_one(&mut *conn)
+            .await?;
+
+            if count as usize >= MAX_DEBT_PRESETS {
+                return Err(AppError::conflict(format!(
+                    "You can keep at most {} presets per group; save over an existing one instead",
+                    MAX_DEBT_PRESETS
+                )));
+            }
+
+            sqlx::query!(
+                "INSERT INTO debt_presets (group_id, user_id, name, category) VALUES (?, ?, ?, ?)",
+                group_id,
+                user_id,
+                name,
+                category_str
+            )
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid()
+        }
+    };
+
+    for member_id in &member_ids {
+        sqlx::query!(
+            "INSERT INTO debt_preset_members (preset_id, user_id) VALUES (?, ?)",
+            id,
+            member_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(DebtPreset {
+        id,
+        name,
+        member_ids,
+        category: category_str.map(str::to_string),
+    })
+}
+
+#[cfg(feature = "ssr")]
+async fn load_debt_presets(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+) -> Result<Vec<DebtPreset>, AppError> {
+    let presets = sqlx::query!(
+        r#"
+        SELECT id as "id!", name, category
+        FROM debt_presets
+        WHERE group_id = ? AND user_id = ?
+        ORDER BY name COLLATE NOCASE ASC, id ASC
+        "#,
+        group_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Members who have left the group since are skipped
+    let members = sqlx::query!(
+        r#"
+        SELECT dpm.preset_id, dpm.user_id
+        FROM debt_preset_members dpm
+        INNER JOIN debt_presets dp ON dp.id = dpm.preset_id
+        INNER JOIN group_members gm ON gm.group_id = dp.group_id AND gm.user_id = dpm.user_id
+        WHERE dp.group_id = ? AND dp.user_id = ?
+        ORDER BY dpm.user_id
+        "#,
+        group_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(presets
+        .into_iter()
+        .map(|preset| DebtPreset {
+            member_ids: members
+                .iter()
+                .filter(|member| member.preset_id == preset.id)
+                .map(|member| member.user_id)
+                .collect(),
+            id: preset.id,
+            name: preset.name,
+            category: preset.category,
+        })
+        .collect())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_presets_are_private_capped_and_skip_members_who_left() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x'), ('dave', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+
+        let preset = store_debt_preset(
+            &mut conn,
+            1,
+            1,
+            "Car costs",
+            &[3, 1, 2, 3],
+            Some("Travel".to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(preset.member_ids, vec![1, 2, 3]);
+
+        // Saving under the same name replaces the preset
+        store_debt_preset(&mut conn, 1, 1, "Car costs", &[1, 3], None)
+            .await
+            .unwrap();
+        drop(conn);
+        let presets = load_debt_presets(&pool, 1, 1).await.unwrap();
+        assert_eq!(
+            presets,
+            vec![DebtPreset {
+                id: preset.id,
+                name: "Car costs".to_string(),
+                member_ids: vec![1, 3],
+                category: None,
+            }]
+        );
+        assert!(load_debt_presets(&pool, 1, 2).await.unwrap().is_empty());
+
+        sqlx::query("DELETE FROM group_members WHERE user_id = 3")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            load_debt_presets(&pool, 1, 1).await.unwrap()[0].member_ids,
+            vec![1]
+        );
+
+        let mut conn = pool.acquire().await.unwrap();
+        let err = store_debt_preset(&mut conn, 1, 1, "Party", &[1, 4], None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        for i in 1..MAX_DEBT_PRESETS {
+            store_debt_preset(&mut conn, 1, 1, &format!("Preset {}", i), &[1], None)
+                .await
+                .unwrap();
+        }
+        let err = store_debt_preset(&mut conn, 1, 1, "One too many", &[1], None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)));
+        // Others still have room of their own
+        store_debt_preset(&mut conn, 1, 2, "Car costs", &[2], None)
+            .await
+            .unwrap();
+    }
+}
@@ -4,28 +4,36 @@ use leptos_axum::extract;
 #[cfg(feature = "ssr")]
 use rust_decimal::Decimal;
 #[cfg(feature = "ssr")]
+use time::Date;
+#[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
-use crate::features::shared_debts::models::{SharedDebtWithDetails, UserShare};
 #[cfg(feature = "ssr")]
-use crate::features::shared_debts::utils::calculate_shares;
+use crate::features::recurring_debts::utils::app_today;
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::models::{DebtCategory, DebtStatus, RoundingStrategy};
+use crate::features::shared_debts::models::{SharedDebtPage, SharedDebtWithDetails, UserShare};
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::utils::{stored_foreign_amount, stored_shares};
+#[cfg(feature = "ssr")]
+use crate::validation::rules::{check_date_filter, check_page};
 
 /// Server function: Get a specific shared debt
 #[server(GetSharedDebt)]
-pub async fn get_shared_debt(debt_id: i64) -> Result<SharedDebtWithDetails, ServerFnError> {
+pub async fn get_shared_debt(debt_id: i64) -> Result<SharedDebtWithDetails, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
 
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
+    let today = app_today().to_string();
 
     // Get the shared debt and verify user has access
     let debt = sqlx::query!(
@@ -36,56 +44,128 @@ pub async fn get_shared_debt(debt_id: i64) -> Result<SharedDebtWithDetails, Serv
             sd.created_by as "created_by!",
             sd.name,
             sd.amount,
+            sd.category,
+            sd.due_date as "due_date: String",
+            sd.incurred_on as "incurred_on!: String",
+            sd.original_amount,
+            sd.original_currency,
+            sd.exchange_rate,
+            sd.status,
+            sd.rejection_reason,
+            sd.version,
             sd.created_at,
             sd.updated_at,
             u.username as creator_username,
-            CASE WHEN sd.created_by = ? THEN 1 ELSE 0 END as "is_creator!: bool"
+            p.id as "payer_id!",
+            p.username as payer_username,
+            CASE WHEN sd.created_by = ? THEN 1 ELSE 0 END as "is_creator!: bool",
+            CASE WHEN sd.created_by = ? OR g.created_by = ? OR gm.role = 'admin' THEN 1 ELSE 0 END
+                as "can_manage!: bool",
+            CASE WHEN sd.due_date < ? AND EXISTS (
+                SELECT 1 FROM shared_debt_user sdu
+                WHERE sdu.shared_debt_id = sd.id AND sdu.is_settled = 0
+                  AND sdu.user_id != COALESCE(sd.payer_id, sd.created_by)
+            ) THEN 1 ELSE 0 END as "is_overdue!: bool"
         FROM shared_debts sd
+        INNER JOIN groups g ON sd.group_id = g.id
         INNER JOIN users u ON sd.created_by = u.id
+        INNER JOIN users p ON COALESCE(sd.payer_id, sd.created_by) = p.id
         INNER JOIN group_members gm ON sd.group_id = gm.group_id
         WHERE sd.id = ? AND gm.user_id = ?
         "#,
         user.id,
+        user.id,
+        user.id,
+        today,
         debt_id,
         user.id
     )
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .ok_or_else(|| ServerFnError::new("Shared debt not found or access denied"))?;
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found or access denied"))?;
 
-    let amount = debt
-        .amount
-        .parse::<Decimal>()
-        .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
+    let amount = debt.amount.parse::<Decimal>().map_err(AppError::internal)?;
+    let category =
+        DebtCategory::parse_optional(debt.category.as_deref()).map_err(AppError::internal)?;
+    let due_date = parse_due_date(debt.due_date)?;
+    let incurred_on = parse_stored_date(&debt.incurred_on)?;
 
     Ok(SharedDebtWithDetails {
         id: debt.id,
         group_id: debt.group_id,
         created_by: debt.created_by,
         creator_username: debt.creator_username,
+        payer_id: debt.payer_id,
+        payer_username: debt.payer_username,
         name: debt.name,
         amount,
+        category,
+        due_date,
+        is_overdue: debt.is_overdue,
+        incurred_on,
         created_at: debt.created_at,
         updated_at: debt.updated_at,
         is_creator: debt.is_creator,
+        can_manage: debt.can_manage,
+        version: debt.version,
+        original: stored_foreign_amount(
+            debt.original_amount.as_deref(),
+            debt.original_currency,
+            debt.exchange_rate.as_deref(),
+        ),
+        status: debt
+            .status
+            .parse::<DebtStatus>()
+            .map_err(AppError::internal)?,
+        rejection_reason: debt.rejection_reason,
     })
 }
 
-/// Server function: Get all shared debts for a group
+/// Server function: Get a page of a group's shared debts, newest first
+///
+/// The date and category filters apply to the total count as well. Balances
+/// are computed separately and always cover every row. Sorting by due date
+/// puts the earliest deadlines first and debts without one last.
 #[server(GetGroupSharedDebts)]
 pub async fn get_group_shared_debts(
     group_id: i64,
-) -> Result<Vec<SharedDebtWithDetails>, ServerFnError> {
+    /// Rows per page; 25 when omitted
+    #[server(default)]
+    limit: Option<i64>,
+    /// Rows to skip, counted from the newest
+    #[server(default)]
+    offset: Option<i64>,
+    /// Earliest date incurred on to include (YYYY-MM-DD)
+    #[server(default)]
+    from_date: Option<String>,
+    /// Latest date incurred on to include (YYYY-MM-DD)
+    #[server(default)]
+    to_date: Option<String>,
+    /// Only debts in this category
+    #[server(default)]
+    category: Option<String>,
+    /// Order by due date instead of the date incurred on
+    #[server(default)]
+    sort_by_due_date: bool,
+) -> Result<SharedDebtPage, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let (limit, offset) = check_page(limit, offset).map_err(AppError::Validation)?;
+    let from_date = check_date_filter(from_date.as_deref(), "start date")
+        .map_err(AppError::Validation)?
+        .map(|d| d.to_string());
+    let to_date = check_date_filter(to_date.as_deref(), "end date")
+        .map_err(AppError::Validation)?
+        .map(|d| d.to_string());
+    let category = DebtCategory::parse_optional(category.as_deref())
+        .map_err(AppError::Validation)?
+        .map(|category| category.as_str());
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
 
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
@@ -96,16 +176,46 @@ pub async fn get_group_shared_debts(
         user.id
     )
     .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     if is_member.count == 0 {
-        return Err(ServerFnError::new(
-            "Unauthorized: Not a member of this group",
-        ));
+        return Err(AppError::forbidden("Not a member of this group"));
     }
 
-    // Fetch all shared debts for this group with creator info
+    load_shared_debt_page(
+        &pool,
+        group_id,
+        user.id,
+        limit,
+        offset,
+        from_date.as_deref(),
+        to_date.as_deref(),
+        category,
+        sort_by_due_date,
+    )
+    .await
+}
+
+/// Load a page of a group's shared debts as seen by `user_id`
+///
+/// Takes filters already checked by [`get_group_shared_debts`]. Callers are
+/// responsible for verifying that the user is a member of the group.
+#[cfg(feature = "ssr")]
+#[allow(clippy::too_many_arguments)]
+pub async fn load_shared_debt_page(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+    limit: i64,
+    offset: i64,
+    from_date: Option<&str>,
+    to_date: Option<&str>,
+    category: Option<&str>,
+    sort_by_due_date: bool,
+) -> Result<SharedDebtPage, AppError> {
+    let today = app_today().to_string();
+
+    // Fetch the page of shared debts with creator info
     let debts = sqlx::query!(
         r#"
         SELECT 
@@ -114,59 +224,156 @@ pub async fn get_group_shared_debts(
             sd.created_by as "created_by!",
             sd.name,
             sd.amount,
+            sd.category,
+            sd.due_date as "due_date: String",
+            sd.incurred_on as "incurred_on!: String",
+            sd.original_amount,
+            sd.original_currency,
+            sd.exchange_rate,
+            sd.status,
+            sd.rejection_reason,
+            sd.version,
             sd.created_at,
             sd.updated_at,
             u.username as creator_username,
-            CASE WHEN sd.created_by = ? THEN 1 ELSE 0 END as "is_creator!: bool"
+            p.id as "payer_id!",
+            p.username as payer_username,
+            CASE WHEN sd.created_by = ? THEN 1 ELSE 0 END as "is_creator!: bool",
+            CASE WHEN sd.created_by = ? OR g.created_by = ? OR me.role = 'admin' THEN 1 ELSE 0 END
+                as "can_manage!: bool",
+            CASE WHEN sd.due_date < ? AND EXISTS (
+                SELECT 1 FROM shared_debt_user sdu
+                WHERE sdu.shared_debt_id = sd.id AND sdu.is_settled = 0
+                  AND sdu.user_id != COALESCE(sd.payer_id, sd.created_by)
+            ) THEN 1 ELSE 0 END as "is_overdue!: bool"
         FROM shared_debts sd
+        INNER JOIN groups g ON sd.group_id = g.id
         INNER JOIN users u ON sd.created_by = u.id
+        INNER JOIN users p ON COALESCE(sd.payer_id, sd.created_by) = p.id
+        INNER JOIN group_members me ON sd.group_id = me.group_id AND me.user_id = ?
         WHERE sd.group_id = ?
-        ORDER BY sd.created_at DESC
+          AND (? IS NULL OR sd.incurred_on >= ?)
+          AND (? IS NULL OR sd.incurred_on <= ?)
+          AND (? IS NULL OR sd.category = ?)
+        ORDER BY
+            CASE WHEN ? THEN sd.due_date IS NULL ELSE 0 END,
+            CASE WHEN ? THEN sd.due_date END ASC,
+            sd.incurred_on DESC, sd.created_at DESC, sd.id DESC
+        LIMIT ? OFFSET ?
         "#,
-        user.id,
-        group_id
+        user_id,
+        user_id,
+        user_id,
+        today,
+        user_id,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date,
+        category,
+        category,
+        sort_by_due_date,
+        sort_by_due_date,
+        limit,
+        offset
     )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .fetch_all(pool)
+    .await?;
+
+    let total_count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM shared_debts sd
+        WHERE sd.group_id = ?
+          AND (? IS NULL OR sd.incurred_on >= ?)
+          AND (? IS NULL OR sd.incurred_on <= ?)
+          AND (? IS NULL OR sd.category = ?)
+        "#,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date,
+        category,
+        category
+    )
+    .fetch_one(pool)
+    .await?;
 
     let debts_with_details = debts
         .into_iter()
         .map(|row| {
-            let amount = row
-                .amount
-                .parse::<Decimal>()
-                .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
+            let amount = row.amount.parse::<Decimal>().map_err(AppError::internal)?;
+            let category = DebtCategory::parse_optional(row.category.as_deref())
+                .map_err(AppError::internal)?;
+            let due_date = parse_due_date(row.due_date)?;
+            let incurred_on = parse_stored_date(&row.incurred_on)?;
 
             Ok(SharedDebtWithDetails {
                 id: row.id,
                 group_id: row.group_id,
                 created_by: row.created_by,
                 creator_username: row.creator_username,
+                payer_id: row.payer_id,
+                payer_username: row.payer_username,
                 name: row.name,
                 amount,
+                category,
+                due_date,
+                is_overdue: row.is_overdue,
+                incurred_on,
                 created_at: row.created_at,
                 updated_at: row.updated_at,
                 is_creator: row.is_creator,
+                can_manage: row.can_manage,
+                version: row.version,
+                original: stored_foreign_amount(
+                    row.original_amount.as_deref(),
+                    row.original_currency,
+                    row.exchange_rate.as_deref(),
+                ),
+                status: row
+                    .status
+                    .parse::<DebtStatus>()
+                    .map_err(AppError::internal)?,
+                rejection_reason: row.rejection_reason,
             })
         })
-        .collect::<Result<Vec<_>, ServerFnError>>()?;
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    Ok(SharedDebtPage {
+        debts: debts_with_details,
+        total_count,
+    })
+}
 
-    Ok(debts_with_details)
+/// Parse a stored YYYY-MM-DD due date
+#[cfg(feature = "ssr")]
+fn parse_due_date(value: Option<String>) -> Result<Option<Date>, AppError> {
+    value.as_deref().map(parse_stored_date).transpose()
+}
+
+/// Parse a stored YYYY-MM-DD date
+#[cfg(feature = "ssr")]
+pub(crate) fn parse_stored_date(value: &str) -> Result<Date, AppError> {
+    Date::parse(
+        value,
+        &time::format_description::well_known::Iso8601::DEFAULT,
+    )
+    .map_err(AppError::internal)
 }
 
 /// Server function: Get user IDs involved in a shared debt
 #[server(GetSharedDebtMembers)]
-pub async fn get_shared_debt_members(debt_id: i64) -> Result<Vec<i64>, ServerFnError> {
+pub async fn get_shared_debt_members(debt_id: i64) -> Result<Vec<i64>, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
 
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
@@ -182,9 +389,8 @@ pub async fn get_shared_debt_members(debt_id: i64) -> Result<Vec<i64>, ServerFnE
         user.id
     )
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .ok_or_else(|| ServerFnError::new("Shared debt not found or access denied"))?;
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found or access denied"))?;
 
     // Get member IDs
     let members = sqlx::query!(
@@ -192,32 +398,30 @@ pub async fn get_shared_debt_members(debt_id: i64) -> Result<Vec<i64>, ServerFnE
         debt_id
     )
     .fetch_all(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     Ok(members.into_iter().map(|row| row.user_id).collect())
 }
 
 /// Server function: Get user shares for a specific shared debt
 #[server(GetSharedDebtShares)]
-pub async fn get_shared_debt_shares(debt_id: i64) -> Result<Vec<UserShare>, ServerFnError> {
+pub async fn get_shared_debt_shares(debt_id: i64) -> Result<Vec<UserShare>, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
 
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
     // Get the shared debt and verify user has access
     let debt = sqlx::query!(
         r#"
-        SELECT sd.id as "id!", sd.group_id as "group_id!", sd.amount
+        SELECT sd.id as "id!", sd.group_id as "group_id!", sd.amount, sd.created_by, g.rounding_strategy
         FROM shared_debts sd
+        INNER JOIN groups g ON sd.group_id = g.id
         INNER JOIN group_members gm ON sd.group_id = gm.group_id
         WHERE sd.id = ? AND gm.user_id = ?
         "#,
@@ -225,19 +429,19 @@ pub async fn get_shared_debt_shares(debt_id: i64) -> Result<Vec<UserShare>, Serv
         user.id
     )
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .ok_or_else(|| ServerFnError::new("Shared debt not found or access denied"))?;
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found or access denied"))?;
 
-    let amount = debt
-        .amount
-        .parse::<Decimal>()
-        .map_err(|e| ServerFnError::new(format!("Invalid amount: {}", e)))?;
+    let amount = debt.amount.parse::<Decimal>().map_err(AppError::internal)?;
+    let rounding = debt
+        .rounding_strategy
+        .parse::<RoundingStrategy>()
+        .map_err(AppError::internal)?;
 
-    // Get all users involved in this debt
+    // Get all users involved in this debt with their stored shares
     let users = sqlx::query!(
         r#"
-        SELECT u.id as "id!", u.username
+        SELECT u.id as "id!", u.username, sdu.share_amount, sdu.is_settled as "is_settled!: bool"
         FROM users u
         INNER JOIN shared_debt_user sdu ON u.id = sdu.user_id
         WHERE sdu.shared_debt_id = ?
@@ -246,15 +450,18 @@ pub async fn get_shared_debt_shares(debt_id: i64) -> Result<Vec<UserShare>, Serv
         debt_id
     )
     .fetch_all(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
-    let user_data: Vec<(i64, String)> = users
+    let settled: Vec<bool> = users.iter().map(|row| row.is_settled).collect();
+    let user_data: Vec<(i64, String, Option<String>)> = users
         .into_iter()
-        .map(|row| (row.id, row.username))
+        .map(|row| (row.id, row.username, row.share_amount))
         .collect();
 
-    let shares = calculate_shares(amount, &user_data);
+    let mut shares = stored_shares(amount, &user_data, rounding, debt.created_by);
+    for (share, is_settled) in shares.iter_mut().zip(settled) {
+        share.is_settled = is_settled;
+    }
 
     Ok(shares)
 }
@@ -0,0 +1,77 @@
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event, models::GroupEvent,
+        utils::ensure_group_writable,
+    },
+    transactions::balances::{add_shared_debt_to_balances, remove_shared_debt_from_balances},
+};
+
+/// Server function: Mark one member's share of a shared debt as settled or unsettled
+///
+/// Only the member owning the share and the creator of the debt may change it.
+#[server(SettleDebtShare)]
+pub async fn settle_debt_share(debt_id: i64, user_id: i64, settled: bool) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let debt = sqlx::query!(
+        "SELECT created_by, group_id FROM shared_debts WHERE id = ?",
+        debt_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found"))?;
+
+    if user.id != user_id && user.id != debt.created_by {
+        return Err(AppError::forbidden(
+            "Only the member or the creator of the debt can settle this share",
+        ));
+    }
+    ensure_group_writable(&pool, debt.group_id, user.id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    remove_shared_debt_from_balances(&mut tx, debt_id).await?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE shared_debt_user
+        SET is_settled = ?,
+            settled_at = CASE WHEN ? THEN CURRENT_TIMESTAMP ELSE NULL END
+        WHERE shared_debt_id = ? AND user_id = ?
+        "#,
+        settled,
+        settled,
+        debt_id,
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::validation("This member has no share in the debt"));
+    }
+    add_shared_debt_to_balances(&mut tx, debt_id).await?;
+
+    tx.commit().await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, debt.group_id, GroupEvent::SharedDebtsChanged);
+
+    Ok(())
+}
@@ -4,55 +4,160 @@ use leptos_axum::extract;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{
+            debt_summary, ensure_group_writable, group_currency, is_group_admin,
+            log_group_activity, proposed_debt_status,
+        },
+    },
+    recurring_debts::utils::app_today,
+    shared_debts::{
+        models::{DebtCategory, ForeignAmount},
+        utils::{resolve_custom_shares, resolve_foreign_amount},
+    },
+    transactions::balances::{add_shared_debt_to_balances, remove_shared_debt_from_balances},
+};
 #[cfg(feature = "ssr")]
-use crate::validation::{validate_amount, validate_name};
+use crate::validation::{
+    rules::{
+        EDIT_CONFLICT_ERROR, MAX_NAME_LENGTH, check_date_filter, check_due_date, check_incurred_on,
+    },
+    validate_money, validate_name,
+};
 
 /// Server function: Update a shared debt
+///
+/// `version` is the debt version the editor loaded; the update is rejected
+/// with a conflict error if the debt was saved since.
 #[server(UpdateSharedDebt)]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_shared_debt(
     debt_id: i64,
     name: String,
     amount: String,
     member_ids: Vec<i64>,
-) -> Result<(), ServerFnError> {
+    version: i64,
+    /// Member who paid; keeps the current payer when omitted
+    #[server(default)]
+    payer_id: Option<i64>,
+    /// Share per member as an amount or percentage; split equally when omitted
+    #[server(default)]
+    custom_shares: Option<Vec<(i64, String)>>,
+    /// One of the predefined categories; cleared when omitted
+    #[server(default)]
+    category: Option<String>,
+    /// Date by which the debt should be paid back (YYYY-MM-DD); cleared when
+    /// omitted
+    #[server(default)]
+    due_date: Option<String>,
+    /// Amount paid in another currency; together with `original_currency`
+    /// and `exchange_rate` it replaces `amount`, which is
+    /// then converted; cleared when omitted
+    #[server(default)]
+    original_amount: Option<String>,
+    /// Three-letter code of the currency paid in, e.g. "THB"
+    #[server(default)]
+    original_currency: Option<String>,
+    /// Euros per unit of the original currency, entered by hand
+    #[server(default)]
+    exchange_rate: Option<String>,
+    /// Day the expense happened (YYYY-MM-DD); kept when omitted
+    #[server(default)]
+    incurred_on: Option<String>,
+) -> Result<(), AppError> {
     use sqlx::SqlitePool;
 
     // Validate debt name
-    let name = validate_name(&name, 1, 255, "Debt name")?;
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
 
-    // Validate amount
-    let amount_decimal = validate_amount(&amount)?;
+    let original = resolve_foreign_amount(
+        original_amount.as_deref(),
+        original_currency.as_deref(),
+        exchange_rate.as_deref(),
+    )
+    .map_err(AppError::Validation)?;
+
+    // Validate amount; debts paid in another currency are booked converted
+    let amount_decimal = match &original {
+        Some(original) => validate_money(&original.converted().to_string())?,
+        None => validate_money(&amount)?,
+    };
+
+    let category =
+        DebtCategory::parse_optional(category.as_deref()).map_err(AppError::Validation)?;
+
+    let due_date = check_date_filter(due_date.as_deref(), "due date")
+        .map_err(AppError::Validation)?
+        .map(|due_date| due_date.to_string());
+
+    let incurred_on = check_date_filter(incurred_on.as_deref(), "date")
+        .map_err(AppError::Validation)?
+        .map(|incurred_on| incurred_on.to_string());
 
     if member_ids.is_empty() {
-        return Err(ServerFnError::new(
+        return Err(AppError::validation(
             "At least one member must be selected to split the debt",
         ));
     }
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let shares = resolve_custom_shares(amount_decimal, &member_ids, custom_shares.as_deref())
+        .map_err(AppError::Validation)?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
 
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated. Please log in."))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check if user is the creator of the debt
+    // Check if user is the creator of the debt or a group admin
     let debt = sqlx::query!(
-        "SELECT created_by, group_id FROM shared_debts WHERE id = ?",
+        r#"SELECT created_by, COALESCE(payer_id, created_by) as "payer_id!: i64", group_id, name, amount, due_date as "due_date: String", incurred_on as "incurred_on!: String" FROM shared_debts WHERE id = ?"#,
         debt_id
     )
     .fetch_optional(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .ok_or_else(|| ServerFnError::new("Shared debt not found"))?;
+    .await?
+    .ok_or_else(|| AppError::not_found("Shared debt not found"))?;
 
-    if debt.created_by != user.id {
-        return Err(ServerFnError::new("Only the creator can update this debt"));
+    if debt.created_by != user.id && !is_group_admin(&pool, debt.group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only the creator or a group admin can update this debt",
+        ));
+    }
+    ensure_group_writable(&pool, debt.group_id, user.id).await?;
+
+    // A due date that already passed may stay, but not be newly set
+    if due_date != debt.due_date {
+        check_due_date(due_date.as_deref(), app_today()).map_err(AppError::Validation)?;
+    }
+    // Likewise for a date further back than new debts may have
+    if incurred_on
+        .as_ref()
+        .is_some_and(|date| *date != debt.incurred_on)
+    {
+        check_incurred_on(incurred_on.as_deref(), app_today()).map_err(AppError::Validation)?;
+    }
+
+    let payer_id = payer_id.unwrap_or(debt.payer_id);
+    if payer_id != debt.payer_id {
+        let payer_is_member = sqlx::query!(
+            "SELECT COUNT(*) as \"count!\" FROM group_members WHERE group_id = ? AND user_id = ?",
+            debt.group_id,
+            payer_id
+        )
+        .fetch_one(&pool)
+        .await?;
+
+        if payer_is_member.count == 0 {
+            return Err(AppError::validation("The payer is not part of this group"));
+        }
     }
 
     // Validate all selected members are part of the group
@@ -63,33 +168,70 @@ pub async fn update_shared_debt(
             member_id
         )
         .fetch_one(&pool)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .await?;
 
         if is_group_member.count == 0 {
-            return Err(ServerFnError::new(
+            return Err(AppError::validation(
                 "Some selected members are not part of this group",
             ));
         }
     }
 
     // Start a transaction
-    let mut tx = pool
-        .begin()
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    let mut tx = pool.begin().await?;
+
+    // Snapshot the current member set for the revision
+    let mut old_member_ids = sqlx::query_scalar!(
+        "SELECT user_id FROM shared_debt_user WHERE shared_debt_id = ?",
+        debt_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+    old_member_ids.sort_unstable();
+
+    // Members who already settled their share stay settled
+    let settled_members = sqlx::query!(
+        "SELECT user_id, settled_at FROM shared_debt_user WHERE shared_debt_id = ? AND is_settled = 1",
+        debt_id
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut new_member_ids = member_ids.clone();
+    new_member_ids.sort_unstable();
+    new_member_ids.dedup();
+
+    // Take the old amount and shares out of the balances until they are rewritten
+    remove_shared_debt_from_balances(&mut tx, debt_id).await?;
 
     // Update the shared debt
     let amount_str = amount_decimal.to_string();
+    let category_str = category.map(|category| category.as_str());
+    update_debt_row(
+        &mut tx,
+        debt_id,
+        version,
+        &name,
+        &amount_str,
+        payer_id,
+        category_str,
+        due_date.as_deref(),
+        incurred_on.as_deref(),
+        original.as_ref(),
+    )
+    .await?;
+
+    // Edits by members who aren't admins need approval again, and a rejected
+    // debt goes back to the admins once its creator fixed it
+    let status = proposed_debt_status(&mut *tx, debt.group_id, user.id).await?;
+    let status_str = status.as_str();
     sqlx::query!(
-        "UPDATE shared_debts SET name = ?, amount = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-        name,
-        amount_str,
+        "UPDATE shared_debts SET status = ?, rejection_reason = NULL WHERE id = ?",
+        status_str,
         debt_id
     )
     .execute(&mut *tx)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     // Remove all existing members
     sqlx::query!(
@@ -97,25 +239,280 @@ pub async fn update_shared_debt(
         debt_id
     )
     .execute(&mut *tx)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     // Add new members
     for member_id in member_ids {
+        let share_amount = shares.as_ref().and_then(|shares| {
+            shares
+                .iter()
+                .find(|(user_id, _)| *user_id == member_id)
+                .map(|(_, share)| share.to_string())
+        });
+        let settled = settled_members.iter().find(|row| row.user_id == member_id);
+        let is_settled = settled.is_some();
+        let settled_at = settled.and_then(|row| row.settled_at);
         sqlx::query!(
-            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (?, ?)",
+            r#"
+            INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount, is_settled, settled_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
             debt_id,
-            member_id
+            member_id,
+            share_amount,
+            is_settled,
+            settled_at
         )
         .execute(&mut *tx)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .await?;
+    }
+    add_shared_debt_to_balances(&mut tx, debt_id).await?;
+
+    // Record the edit if anything actually changed
+    let amount_unchanged = debt
+        .amount
+        .parse::<rust_decimal::Decimal>()
+        .is_ok_and(|old| old == amount_decimal);
+    if debt.name != name || !amount_unchanged || old_member_ids != new_member_ids {
+        let old_members_json =
+            serde_json::to_string(&old_member_ids).map_err(AppError::internal)?;
+        let new_members_json =
+            serde_json::to_string(&new_member_ids).map_err(AppError::internal)?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO shared_debt_revisions
+                (shared_debt_id, edited_by, old_name, new_name, old_amount, new_amount, old_member_ids, new_member_ids)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            debt_id,
+            user.id,
+            debt.name,
+            name,
+            debt.amount,
+            amount_str,
+            old_members_json,
+            new_members_json
+        )
+        .execute(&mut *tx)
+        .await?;
     }
 
+    let currency = group_currency(&mut *tx, debt.group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        debt.group_id,
+        user.id,
+        ActivityEntity::SharedDebt,
+        debt_id,
+        ActivityAction::Updated,
+        &debt_summary(&name, amount_decimal, &currency),
+    )
+    .await?;
+
     // Commit transaction
-    tx.commit()
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+    tx.commit().await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, debt.group_id, GroupEvent::SharedDebtsChanged);
 
     Ok(())
 }
+
+/// Write a debt's name, amount, payer, category, due date, original amount
+/// and updated_at timestamp if it is still at `version`, bumping the version
+#[cfg(feature = "ssr")]
+#[allow(clippy::too_many_arguments)]
+async fn update_debt_row(
+    conn: &mut sqlx::SqliteConnection,
+    debt_id: i64,
+    version: i64,
+    name: &str,
+    amount: &str,
+    payer_id: i64,
+    category: Option<&str>,
+    due_date: Option<&str>,
+    incurred_on: Option<&str>,
+    original: Option<&ForeignAmount>,
+) -> Result<(), AppError> {
+    let original_amount = original.map(|original| original.amount.to_string());
+    let original_currency = original.map(|original| original.currency.as_str());
+    let exchange_rate = original.map(|original| original.exchange_rate.to_string());
+    let result = sqlx::query!(
+        r#"
+        UPDATE shared_debts
+        SET name = ?, amount = ?, payer_id = ?, category = ?, due_date = ?,
+            incurred_on = COALESCE(?, incurred_on),
+            original_amount = ?, original_currency = ?, exchange_rate = ?,
+            updated_at = CURRENT_TIMESTAMP, version = version + 1
+        WHERE id = ? AND version = ?
+        "#,
+        name,
+        amount,
+        payer_id,
+        category,
+        due_date,
+        incurred_on,
+        original_amount,
+        original_currency,
+        exchange_rate,
+        debt_id,
+        version
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict(EDIT_CONFLICT_ERROR));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for username in ["alice", "bob"] {
+            sqlx::query("INSERT INTO users (username, password_hash) VALUES (?, 'x')")
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Flat', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, 1, 'Rent', '900')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_update_debt_row_rejects_stale_version() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        // Both editors loaded version 0; the second save must not clobber the first
+        update_debt_row(
+            &mut conn,
+            1,
+            0,
+            "Rent",
+            "950",
+            1,
+            Some("Rent"),
+            Some("2026-04-01"),
+            Some("2026-03-01"),
+            None,
+        )
+        .await
+        .unwrap();
+        let err = update_debt_row(
+            &mut conn,
+            1,
+            0,
+            "Rent March",
+            "900",
+            2,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains(EDIT_CONFLICT_ERROR));
+
+        let (name, amount, payer_id, version): (String, String, i64, i64) =
+            sqlx::query_as("SELECT name, amount, payer_id, version FROM shared_debts WHERE id = 1")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(
+            (name.as_str(), amount.as_str(), payer_id, version),
+            ("Rent", "950", 1, 1)
+        );
+
+        // Leaving out the date keeps it
+        update_debt_row(&mut conn, 1, 1, "Rent", "950", 1, None, None, None, None)
+            .await
+            .unwrap();
+        let incurred_on: String =
+            sqlx::query_scalar("SELECT incurred_on FROM shared_debts WHERE id = 1")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(incurred_on, "2026-03-01");
+    }
+
+    #[tokio::test]
+    async fn test_update_debt_row_stores_original_amount() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+        let original = ForeignAmount {
+            amount: rust_decimal::Decimal::from(1200),
+            currency: "THB".to_string(),
+            exchange_rate: rust_decimal::Decimal::new(26, 3),
+        };
+
+        let amount = original.converted().to_string();
+        update_debt_row(
+            &mut conn,
+            1,
+            0,
+            "Hotel",
+            &amount,
+            1,
+            None,
+            None,
+            None,
+            Some(&original),
+        )
+        .await
+        .unwrap();
+        let row: (String, Option<String>, Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT amount, original_amount, original_currency, exchange_rate FROM shared_debts WHERE id = 1",
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            row,
+            (
+                "31.20".to_string(),
+                Some("1200".to_string()),
+                Some("THB".to_string()),
+                Some("0.026".to_string())
+            )
+        );
+
+        // Switching back to euros clears the original amount
+        update_debt_row(&mut conn, 1, 1, "Hotel", "30", 1, None, None, None, None)
+            .await
+            .unwrap();
+        let original_currency: Option<String> =
+            sqlx::query_scalar("SELECT original_currency FROM shared_debts WHERE id = 1")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(original_currency, None);
+    }
+}
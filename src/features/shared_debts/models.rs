@@ -2,7 +2,192 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "ssr")]
 use sqlx::FromRow;
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime};
+
+/// Kind of expense a debt is for
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DebtCategory {
+    Groceries,
+    Rent,
+    Utilities,
+    Travel,
+    Entertainment,
+    Other,
+}
+
+impl DebtCategory {
+    pub const ALL: [DebtCategory; 6] = [
+        DebtCategory::Groceries,
+        DebtCategory::Rent,
+        DebtCategory::Utilities,
+        DebtCategory::Travel,
+        DebtCategory::Entertainment,
+        DebtCategory::Other,
+    ];
+
+    /// Name stored in the `category` column and submitted by forms
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DebtCategory::Groceries => "Groceries",
+            DebtCategory::Rent => "Rent",
+            DebtCategory::Utilities => "Utilities",
+            DebtCategory::Travel => "Travel",
+            DebtCategory::Entertainment => "Entertainment",
+            DebtCategory::Other => "Other",
+        }
+    }
+
+    /// Tailwind classes for the category's badge
+    pub fn badge_class(&self) -> &'static str {
+        match self {
+            DebtCategory::Groceries => {
+                "bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-300"
+            }
+            DebtCategory::Rent => {
+                "bg-indigo-100 text-indigo-800 dark:bg-indigo-900/30 dark:text-indigo-300"
+            }
+            DebtCategory::Utilities => {
+                "bg-yellow-100 text-yellow-800 dark:bg-yellow-900/30 dark:text-yellow-300"
+            }
+            DebtCategory::Travel => "bg-sky-100 text-sky-800 dark:bg-sky-900/30 dark:text-sky-300",
+            DebtCategory::Entertainment => {
+                "bg-pink-100 text-pink-800 dark:bg-pink-900/30 dark:text-pink-300"
+            }
+            DebtCategory::Other => "bg-gray-100 text-gray-800 dark:bg-gray-700 dark:text-gray-300",
+        }
+    }
+
+    /// Category of a submitted or stored value; empty means no category
+    pub fn parse_optional(value: Option<&str>) -> Result<Option<Self>, String> {
+        match value.map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(name) => name.parse().map(Some),
+        }
+    }
+}
+
+impl std::str::FromStr for DebtCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DebtCategory::ALL
+            .into_iter()
+            .find(|category| category.as_str() == s)
+            .ok_or_else(|| format!("Unknown category: {}", s))
+    }
+}
+
+impl std::fmt::Display for DebtCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Who gets the cents left over when a debt does not split evenly
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RoundingStrategy {
+    /// The member who created the debt, or the first member if they are not
+    /// part of it
+    CreatorAbsorbs,
+    /// The member with the lowest user id
+    FirstMemberAbsorbs,
+    /// One cent each to the members who lost the most to rounding
+    #[default]
+    DistributeCents,
+}
+
+impl RoundingStrategy {
+    pub const ALL: [RoundingStrategy; 3] = [
+        RoundingStrategy::DistributeCents,
+        RoundingStrategy::CreatorAbsorbs,
+        RoundingStrategy::FirstMemberAbsorbs,
+    ];
+
+    /// Value stored in the `rounding_strategy` column and submitted by forms
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoundingStrategy::CreatorAbsorbs => "creator_absorbs",
+            RoundingStrategy::FirstMemberAbsorbs => "first_member_absorbs",
+            RoundingStrategy::DistributeCents => "distribute_cents",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RoundingStrategy::CreatorAbsorbs => "The debt's creator pays the extra cents",
+            RoundingStrategy::FirstMemberAbsorbs => "The first member pays the extra cents",
+            RoundingStrategy::DistributeCents => "Spread the extra cents across members",
+        }
+    }
+}
+
+impl std::str::FromStr for RoundingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RoundingStrategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.as_str() == s)
+            .ok_or_else(|| format!("Unknown rounding strategy: {}", s))
+    }
+}
+
+/// Where a shared debt stands in the group's approval workflow
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DebtStatus {
+    /// Rejected by an admin; waits for its creator to edit it
+    Draft,
+    /// Proposed by a member and waiting for an admin; left out of balances
+    Pending,
+    /// Counts towards balances
+    #[default]
+    Approved,
+}
+
+impl DebtStatus {
+    /// Value stored in the `status` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DebtStatus::Draft => "draft",
+            DebtStatus::Pending => "pending",
+            DebtStatus::Approved => "approved",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DebtStatus::Draft => "Rejected",
+            DebtStatus::Pending => "Awaiting approval",
+            DebtStatus::Approved => "Approved",
+        }
+    }
+
+    /// Tailwind classes for the status badge
+    pub fn badge_class(self) -> &'static str {
+        match self {
+            DebtStatus::Draft => "bg-red-100 text-red-800 dark:bg-red-900/30 dark:text-red-300",
+            DebtStatus::Pending => {
+                "bg-yellow-100 text-yellow-800 dark:bg-yellow-900/30 dark:text-yellow-300"
+            }
+            DebtStatus::Approved => {
+                "bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-300"
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for DebtStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "draft" => Ok(DebtStatus::Draft),
+            "pending" => Ok(DebtStatus::Pending),
+            "approved" => Ok(DebtStatus::Approved),
+            _ => Err(format!("Unknown debt status: {}", s)),
+        }
+    }
+}
 
 /// SharedDebt model representing a shared expense in a group
 /// Note: We don't derive FromRow because amount needs custom parsing from TEXT
@@ -11,8 +196,13 @@ pub struct SharedDebt {
     pub id: i64,
     pub group_id: i64,
     pub created_by: i64,
+    /// Member who paid; may differ from the member who entered the debt
+    pub payer_id: i64,
     pub name: String,
     pub amount: Decimal,
+    pub category: Option<DebtCategory>,
+    /// Date by which the debt should be paid back
+    pub due_date: Option<Date>,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
@@ -26,13 +216,101 @@ pub struct SharedDebtWithDetails {
     pub group_id: i64,
     pub created_by: i64,
     pub creator_username: String,
+    pub payer_id: i64,
+    pub payer_username: String,
     pub name: String,
     pub amount: Decimal,
+    pub category: Option<DebtCategory>,
+    /// Date by which the debt should be paid back
+    pub due_date: Option<Date>,
+    /// Whether the due date has passed while shares are still unsettled
+    pub is_overdue: bool,
+    /// Day the expense happened, which may lie before the debt was entered
+    pub incurred_on: Date,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
     pub is_creator: bool,
+    /// Whether the current user may edit or delete the debt: its creator or a
+    /// group admin
+    pub can_manage: bool,
+    /// Incremented on every edit; updates must pass the version they loaded
+    pub version: i64,
+    /// What was paid when the debt was paid in another currency
+    pub original: Option<ForeignAmount>,
+    pub status: DebtStatus,
+    /// Why an admin rejected the debt
+    pub rejection_reason: Option<String>,
+}
+
+/// Amount paid in another currency and the rate used to convert it to the
+/// group's currency
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForeignAmount {
+    pub amount: Decimal,
+    /// Three-letter currency code, e.g. "THB"
+    pub currency: String,
+    /// Units of the group's currency per unit of this one, entered by hand
+    pub exchange_rate: Decimal,
+}
+
+impl ForeignAmount {
+    /// The amount in the group's currency, rounded to the cent
+    pub fn converted(&self) -> Decimal {
+        (self.amount * self.exchange_rate).round_dp(2)
+    }
+}
+
+/// A shared debt as entered, before it is checked
+///
+/// Mirrors the arguments of
+/// [`create_shared_debt`](super::handlers::create_shared_debt) apart from the
+/// group.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NewSharedDebt {
+    pub name: String,
+    /// Amount in the group's currency; ignored when `original_amount` is given
+    #[serde(default)]
+    pub amount: String,
+    pub member_ids: Vec<i64>,
+    /// Member who paid; defaults to the creator
+    #[serde(default)]
+    pub payer_id: Option<i64>,
+    /// Share per member as an amount or percentage; split equally when omitted
+    #[serde(default)]
+    pub custom_shares: Option<Vec<(i64, String)>>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// YYYY-MM-DD, today or later
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub original_amount: Option<String>,
+    #[serde(default)]
+    pub original_currency: Option<String>,
+    #[serde(default)]
+    pub exchange_rate: Option<String>,
+    /// YYYY-MM-DD; today when omitted
+    #[serde(default)]
+    pub incurred_on: Option<String>,
+}
+
+/// One page of a group's shared debts, newest first
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SharedDebtPage {
+    pub debts: Vec<SharedDebtWithDetails>,
+    /// Number of debts matching the filter across all pages
+    pub total_count: i64,
+}
+
+/// Result of duplicating a shared or recurring debt
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicatedDebt {
+    /// Id of the new copy
+    pub id: i64,
+    /// Members of the original who have left the group and were left out
+    pub dropped_members: Vec<String>,
 }
 
 /// User share information for a shared debt
@@ -41,6 +319,12 @@ pub struct UserShare {
     pub user_id: i64,
     pub username: String,
     pub share_amount: Decimal,
+    /// Whether the amount was entered explicitly instead of split equally
+    pub is_custom: bool,
+    /// Whether the member has settled this share outside of the app
+    pub is_settled: bool,
+    /// Whether the share includes cents left over by an uneven split
+    pub absorbs_remainder: bool,
 }
 
 /// Pivot table entry for shared_debt_user
@@ -50,4 +334,94 @@ pub struct SharedDebtUser {
     pub id: i64,
     pub shared_debt_id: i64,
     pub user_id: i64,
+    /// Explicit share stored as TEXT; `None` when the debt is split equally
+    pub share_amount: Option<String>,
+    pub is_settled: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub settled_at: Option<OffsetDateTime>,
+}
+
+/// A single edit of a shared debt, with the values before and after
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SharedDebtRevision {
+    pub id: i64,
+    pub shared_debt_id: i64,
+    pub editor_username: Option<String>,
+    pub old_name: String,
+    pub new_name: String,
+    pub old_amount: Decimal,
+    pub new_amount: Decimal,
+    pub old_members: Vec<String>,
+    pub new_members: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl SharedDebtRevision {
+    /// Human readable list of what changed with amounts in the given currency
+    /// symbol, e.g. "changed amount €60.00 → €75.00"
+    pub fn changes(&self, currency: &str) -> Vec<String> {
+        let mut changes = Vec::new();
+
+        if self.old_name != self.new_name {
+            changes.push(format!(
+                "renamed \"{}\" → \"{}\"",
+                self.old_name, self.new_name
+            ));
+        }
+        if self.old_amount != self.new_amount {
+            changes.push(format!(
+                "changed amount {}{:.2} → {}{:.2}",
+                currency, self.old_amount, currency, self.new_amount
+            ));
+        }
+
+        let added: Vec<&str> = self
+            .new_members
+            .iter()
+            .filter(|m| !self.old_members.contains(m))
+            .map(String::as_str)
+            .collect();
+        let removed: Vec<&str> = self
+            .old_members
+            .iter()
+            .filter(|m| !self.new_members.contains(m))
+            .map(String::as_str)
+            .collect();
+        if !added.is_empty() {
+            changes.push(format!("added {}", added.join(", ")));
+        }
+        if !removed.is_empty() {
+            changes.push(format!("removed {}", removed.join(", ")));
+        }
+
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debt_category_parse_optional() {
+        assert_eq!(
+            DebtCategory::parse_optional(Some("Groceries")),
+            Ok(Some(DebtCategory::Groceries))
+        );
+        assert_eq!(DebtCategory::parse_optional(Some(" ")), Ok(None));
+        assert_eq!(DebtCategory::parse_optional(None), Ok(None));
+        assert!(DebtCategory::parse_optional(Some("groceries")).is_err());
+        assert!(DebtCategory::parse_optional(Some("Fuel")).is_err());
+    }
+}
+
+/// Members and category a user saved to fill in new debts quickly
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebtPreset {
+    pub id: i64,
+    pub name: String,
+    /// Members still in the group; those who left are not returned
+    pub member_ids: Vec<i64>,
+    pub category: Option<String>,
 }
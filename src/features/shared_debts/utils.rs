@@ -1,23 +1,580 @@
 use rust_decimal::Decimal;
 
-use super::models::UserShare;
+use super::models::{ForeignAmount, RoundingStrategy, UserShare};
+use crate::validation::rules::{
+    MAX_NAME_LENGTH, check_currency_code, check_custom_shares, check_exchange_rate,
+    check_original_amount,
+};
 
 /// Calculate individual shares for a shared debt
-/// Divides the total amount equally among all participants
-pub fn calculate_shares(amount: Decimal, user_ids: &[(i64, String)]) -> Vec<UserShare> {
-    if user_ids.is_empty() {
+///
+/// Divides the total amount equally among all participants; `strategy`
+/// decides who pays the cents that do not divide evenly.
+pub fn calculate_shares(
+    amount: Decimal,
+    user_ids: &[(i64, String)],
+    strategy: RoundingStrategy,
+    creator_id: i64,
+) -> Vec<UserShare> {
+    let weights: Vec<(i64, i64)> = user_ids.iter().map(|(user_id, _)| (*user_id, 1)).collect();
+
+    user_ids
+        .iter()
+        .zip(split_with_remainder(amount, &weights, strategy, creator_id))
+        .map(
+            |((user_id, username), (_, share_amount, absorbs_remainder))| UserShare {
+                user_id: *user_id,
+                username: username.clone(),
+                share_amount,
+                is_custom: false,
+                is_settled: false,
+                absorbs_remainder,
+            },
+        )
+        .collect()
+}
+
+/// Split an amount between members in proportion to their weights
+///
+/// Each member gets their part rounded down to the cent, and `strategy`
+/// decides who gets the cents left over, so the shares always add up to the
+/// amount.
+pub fn split_by_weight(
+    amount: Decimal,
+    weights: &[(i64, i64)],
+    strategy: RoundingStrategy,
+    creator_id: i64,
+) -> Vec<(i64, Decimal)> {
+    split_with_remainder(amount, weights, strategy, creator_id)
+        .into_iter()
+        .map(|(user_id, share, _)| (user_id, share))
+        .collect()
+}
+
+/// Like [`split_by_weight`], also flagging the members who got leftover cents
+fn split_with_remainder(
+    amount: Decimal,
+    weights: &[(i64, i64)],
+    strategy: RoundingStrategy,
+    creator_id: i64,
+) -> Vec<(i64, Decimal, bool)> {
+    let total_weight: i64 = weights.iter().map(|(_, weight)| *weight).sum();
+    if total_weight <= 0 {
         return Vec::new();
     }
 
-    let count = Decimal::from(user_ids.len());
-    let share_per_user = (amount / count).round_dp(2); // Round to 2 decimal places
-
-    user_ids
+    let cents = (amount * Decimal::ONE_HUNDRED).round();
+    let total_weight = Decimal::from(total_weight);
+    let parts: Vec<(i64, Decimal, Decimal)> = weights
         .iter()
-        .map(|(user_id, username)| UserShare {
-            user_id: *user_id,
-            username: username.clone(),
-            share_amount: share_per_user,
+        .map(|(user_id, weight)| {
+            let exact = cents * Decimal::from(*weight) / total_weight;
+            let floor = exact.floor();
+            (*user_id, floor, exact - floor)
+        })
+        .collect();
+
+    let leftover = cents - parts.iter().map(|(_, part, _)| *part).sum::<Decimal>();
+    let mut extra = vec![Decimal::ZERO; parts.len()];
+    if leftover > Decimal::ZERO {
+        let first_member = (0..parts.len())
+            .min_by_key(|&index| parts[index].0)
+            .unwrap_or_default();
+        match strategy {
+            RoundingStrategy::DistributeCents => {
+                // Largest remainders first, then the lowest user id
+                let mut order: Vec<usize> = (0..parts.len()).collect();
+                order.sort_by(|&a, &b| {
+                    parts[b]
+                        .2
+                        .cmp(&parts[a].2)
+                        .then(parts[a].0.cmp(&parts[b].0))
+                });
+                // Fewer cents are left over than there are members
+                let mut leftover = leftover;
+                for index in order {
+                    if leftover <= Decimal::ZERO {
+                        break;
+                    }
+                    extra[index] = Decimal::ONE;
+                    leftover -= Decimal::ONE;
+                }
+            }
+            RoundingStrategy::CreatorAbsorbs => {
+                let creator = parts
+                    .iter()
+                    .position(|(user_id, _, _)| *user_id == creator_id)
+                    .unwrap_or(first_member);
+                extra[creator] = leftover;
+            }
+            RoundingStrategy::FirstMemberAbsorbs => extra[first_member] = leftover,
+        }
+    }
+
+    parts
+        .into_iter()
+        .zip(extra)
+        .map(|((user_id, part, _), extra)| {
+            let mut share = (part + extra) / Decimal::ONE_HUNDRED;
+            share.rescale(2);
+            (user_id, share, extra > Decimal::ZERO)
         })
         .collect()
 }
+
+/// Calculate individual shares for a debt split by member weights
+///
+/// Shares count as custom unless all members have the same weight.
+pub fn calculate_weighted_shares(
+    amount: Decimal,
+    members: &[(i64, String, i64)],
+    strategy: RoundingStrategy,
+    creator_id: i64,
+) -> Vec<UserShare> {
+    let is_custom = members.windows(2).any(|pair| pair[0].2 != pair[1].2);
+    let weights: Vec<(i64, i64)> = members
+        .iter()
+        .map(|(user_id, _, weight)| (*user_id, *weight))
+        .collect();
+
+    members
+        .iter()
+        .zip(split_with_remainder(amount, &weights, strategy, creator_id))
+        .map(
+            |((user_id, username, _), (_, share_amount, absorbs_remainder))| UserShare {
+                user_id: *user_id,
+                username: username.clone(),
+                share_amount,
+                is_custom,
+                is_settled: false,
+                absorbs_remainder,
+            },
+        )
+        .collect()
+}
+
+/// Shares of a debt as stored for its members
+///
+/// Uses the custom amounts when every member has one and falls back to an
+/// equal split otherwise, e.g. for debts created before custom splits existed.
+pub fn stored_shares(
+    amount: Decimal,
+    members: &[(i64, String, Option<String>)],
+    strategy: RoundingStrategy,
+    creator_id: i64,
+) -> Vec<UserShare> {
+    let custom = custom_amounts(members.iter().map(|(_, _, share)| share.as_deref()));
+
+    match custom {
+        Some(amounts) if !members.is_empty() => members
+            .iter()
+            .zip(amounts)
+            .map(|((user_id, username, _), share_amount)| UserShare {
+                user_id: *user_id,
+                username: username.clone(),
+                share_amount,
+                is_custom: true,
+                is_settled: false,
+                absorbs_remainder: false,
+            })
+            .collect(),
+        _ => {
+            let user_ids: Vec<(i64, String)> = members
+                .iter()
+                .map(|(user_id, username, _)| (*user_id, username.clone()))
+                .collect();
+            calculate_shares(amount, &user_ids, strategy, creator_id)
+        }
+    }
+}
+
+/// Amount each member owes for a debt, for balance calculations
+///
+/// The same amounts [`stored_shares`] shows on the debt, so paying the
+/// displayed share leaves a balance of exactly zero.
+pub fn member_share_amounts(
+    amount: Decimal,
+    members: &[(i64, Option<String>)],
+    strategy: RoundingStrategy,
+    creator_id: i64,
+) -> Vec<(i64, Decimal)> {
+    if members.is_empty() {
+        return Vec::new();
+    }
+
+    match custom_amounts(members.iter().map(|(_, share)| share.as_deref())) {
+        Some(amounts) => members
+            .iter()
+            .map(|(user_id, _)| *user_id)
+            .zip(amounts)
+            .collect(),
+        None => {
+            let weights: Vec<(i64, i64)> =
+                members.iter().map(|(user_id, _)| (*user_id, 1)).collect();
+            split_by_weight(amount, &weights, strategy, creator_id)
+        }
+    }
+}
+
+/// Check the custom shares sent for a debt against its members and total
+///
+/// Returns the amount to store for each member, or `None` when the debt is
+/// split equally.
+pub fn resolve_custom_shares(
+    amount: Decimal,
+    member_ids: &[i64],
+    custom_shares: Option<&[(i64, String)]>,
+) -> Result<Option<Vec<(i64, Decimal)>>, String> {
+    let Some(custom_shares) = custom_shares else {
+        return Ok(None);
+    };
+
+    let covers_members = custom_shares.len() == member_ids.len()
+        && custom_shares
+            .iter()
+            .all(|(user_id, _)| member_ids.contains(user_id));
+    if !covers_members {
+        return Err("Custom shares must cover exactly the selected members".to_string());
+    }
+
+    check_custom_shares(amount, custom_shares).map(Some)
+}
+
+/// Check the original amount, currency and exchange rate sent for a debt
+///
+/// Returns `None` when all three are left blank, i.e. the debt was paid in
+/// the group's currency.
+pub fn resolve_foreign_amount(
+    amount: Option<&str>,
+    currency: Option<&str>,
+    exchange_rate: Option<&str>,
+) -> Result<Option<ForeignAmount>, String> {
+    let fields = [amount, currency, exchange_rate]
+        .map(|field| field.map(str::trim).filter(|field| !field.is_empty()));
+    if fields.iter().all(Option::is_none) {
+        return Ok(None);
+    }
+    let [Some(amount), Some(currency), Some(exchange_rate)] = fields else {
+        return Err(
+            "Enter the original amount, its currency and the exchange rate together".to_string(),
+        );
+    };
+
+    Ok(Some(ForeignAmount {
+        amount: check_original_amount(amount)?,
+        currency: check_currency_code(currency)?,
+        exchange_rate: check_exchange_rate(exchange_rate)?,
+    }))
+}
+
+/// Original amount of a debt as stored, or `None` unless all three columns
+/// hold valid values
+pub fn stored_foreign_amount(
+    amount: Option<&str>,
+    currency: Option<String>,
+    exchange_rate: Option<&str>,
+) -> Option<ForeignAmount> {
+    Some(ForeignAmount {
+        amount: amount?.parse().ok()?,
+        currency: currency?,
+        exchange_rate: exchange_rate?.parse().ok()?,
+    })
+}
+
+/// Name of a duplicated debt: the original's with " (copy)" appended,
+/// shortened to stay within [`MAX_NAME_LENGTH`]
+pub fn copy_name(name: &str) -> String {
+    const SUFFIX: &str = " (copy)";
+    let mut end = name.len().min(MAX_NAME_LENGTH - SUFFIX.len());
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", name[..end].trim_end(), SUFFIX)
+}
+
+/// Parse the stored custom shares, or `None` unless every member has one
+fn custom_amounts<'a>(shares: impl Iterator<Item = Option<&'a str>>) -> Option<Vec<Decimal>> {
+    shares
+        .map(|share| share.and_then(|share| share.parse().ok()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_name() {
+        assert_eq!(copy_name("Groceries week 42"), "Groceries week 42 (copy)");
+
+        let long = "é".repeat(MAX_NAME_LENGTH / 2);
+        let copied = copy_name(&long);
+        assert!(copied.len() <= MAX_NAME_LENGTH);
+        assert!(copied.ends_with("é (copy)"));
+    }
+
+    #[test]
+    fn test_resolve_foreign_amount() {
+        assert_eq!(resolve_foreign_amount(None, None, None).unwrap(), None);
+        assert_eq!(
+            resolve_foreign_amount(Some(""), Some(" "), None).unwrap(),
+            None
+        );
+
+        let foreign = resolve_foreign_amount(Some("1200"), Some("thb"), Some("0.026"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(foreign.currency, "THB");
+        assert_eq!(foreign.converted(), Decimal::new(3120, 2));
+
+        // Converted amounts are rounded to the cent
+        let foreign = resolve_foreign_amount(Some("999"), Some("JPY"), Some("0.00617"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(foreign.converted(), Decimal::new(616, 2));
+
+        assert_eq!(
+            resolve_foreign_amount(Some("1200"), Some("THB"), Some("")).unwrap_err(),
+            "Enter the original amount, its currency and the exchange rate together"
+        );
+        assert!(resolve_foreign_amount(Some("1200"), Some("THB"), Some("0")).is_err());
+    }
+
+    #[test]
+    fn test_stored_foreign_amount() {
+        let stored = stored_foreign_amount(Some("1200"), Some("THB".into()), Some("0.026"));
+        assert_eq!(stored.unwrap().converted(), Decimal::new(3120, 2));
+        assert_eq!(stored_foreign_amount(None, None, None), None);
+        assert_eq!(
+            stored_foreign_amount(Some("1200"), Some("THB".into()), None),
+            None
+        );
+    }
+
+    const DISTRIBUTE: RoundingStrategy = RoundingStrategy::DistributeCents;
+
+    #[test]
+    fn test_stored_shares_prefer_custom_amounts() {
+        let custom = [
+            (1, "alice".to_string(), Some("70.00".to_string())),
+            (2, "bob".to_string(), Some("30.00".to_string())),
+        ];
+        let shares = stored_shares(Decimal::from(100), &custom, DISTRIBUTE, 1);
+        assert_eq!(shares[0].share_amount, Decimal::from(70));
+        assert_eq!(shares[1].share_amount, Decimal::from(30));
+        assert!(shares.iter().all(|share| share.is_custom));
+
+        // Debts from before custom splits fall back to an equal split
+        let legacy = [(1, "alice".to_string(), None), (2, "bob".to_string(), None)];
+        let shares = stored_shares(Decimal::from(100), &legacy, DISTRIBUTE, 1);
+        assert_eq!(shares[0].share_amount, Decimal::from(50));
+        assert!(!shares[0].is_custom);
+    }
+
+    #[test]
+    fn test_split_by_weight() {
+        // Two rooms at weight 2, one at weight 1
+        assert_eq!(
+            split_by_weight(Decimal::from(500), &[(1, 2), (2, 2), (3, 1)], DISTRIBUTE, 1),
+            vec![
+                (1, Decimal::from(200)),
+                (2, Decimal::from(200)),
+                (3, Decimal::from(100))
+            ]
+        );
+
+        // Leftover cents go to the largest remainders, then the lowest id
+        assert_eq!(
+            split_by_weight(Decimal::from(10), &[(3, 1), (1, 1), (2, 1)], DISTRIBUTE, 1),
+            vec![
+                (3, Decimal::new(333, 2)),
+                (1, Decimal::new(334, 2)),
+                (2, Decimal::new(333, 2))
+            ]
+        );
+        assert_eq!(
+            split_by_weight(Decimal::new(100, 2), &[(1, 1), (2, 2)], DISTRIBUTE, 1),
+            vec![(1, Decimal::new(33, 2)), (2, Decimal::new(67, 2))]
+        );
+
+        let shares = split_by_weight(
+            Decimal::new(9999, 2),
+            &[(1, 3), (2, 5), (3, 7)],
+            DISTRIBUTE,
+            1,
+        );
+        let total: Decimal = shares.iter().map(|(_, share)| *share).sum();
+        assert_eq!(total, Decimal::new(9999, 2));
+
+        assert!(split_by_weight(Decimal::from(10), &[], DISTRIBUTE, 1).is_empty());
+    }
+
+    #[test]
+    fn test_rounding_strategies() {
+        let members = [
+            (2, "bob".to_string()),
+            (1, "alice".to_string()),
+            (3, "carol".to_string()),
+        ];
+        let amounts = |strategy| {
+            calculate_shares(Decimal::new(1001, 2), &members, strategy, 3)
+                .into_iter()
+                .map(|share| (share.share_amount, share.absorbs_remainder))
+                .collect::<Vec<_>>()
+        };
+
+        // 10.01 leaves two cents after 3.33 each
+        assert_eq!(
+            amounts(RoundingStrategy::DistributeCents),
+            vec![
+                (Decimal::new(334, 2), true),
+                (Decimal::new(334, 2), true),
+                (Decimal::new(333, 2), false)
+            ]
+        );
+        assert_eq!(
+            amounts(RoundingStrategy::CreatorAbsorbs),
+            vec![
+                (Decimal::new(333, 2), false),
+                (Decimal::new(333, 2), false),
+                (Decimal::new(335, 2), true)
+            ]
+        );
+        assert_eq!(
+            amounts(RoundingStrategy::FirstMemberAbsorbs),
+            vec![
+                (Decimal::new(333, 2), false),
+                (Decimal::new(335, 2), true),
+                (Decimal::new(333, 2), false)
+            ]
+        );
+
+        // A creator outside the debt leaves the cents to the first member
+        let shares = calculate_shares(
+            Decimal::new(1001, 2),
+            &members,
+            RoundingStrategy::CreatorAbsorbs,
+            9,
+        );
+        assert!(shares[1].absorbs_remainder);
+
+        // Even splits have no remainder to absorb
+        let shares = calculate_shares(
+            Decimal::from(9),
+            &members,
+            RoundingStrategy::CreatorAbsorbs,
+            3,
+        );
+        assert!(shares.iter().all(|share| !share.absorbs_remainder));
+    }
+
+    #[test]
+    fn test_shares_add_up_under_every_strategy() {
+        // Small linear congruential generator, so failures can be replayed
+        let mut state: u64 = 0x5eed;
+        let mut next = |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+
+        for _ in 0..500 {
+            let amount = Decimal::new(next(10_000_000) as i64 + 1, 2);
+            let member_count = next(12) as i64 + 1;
+            let weights: Vec<(i64, i64)> = (1..=member_count)
+                .map(|user_id| (user_id, next(5) as i64 + 1))
+                .collect();
+            let members: Vec<(i64, String)> = weights
+                .iter()
+                .map(|(user_id, _)| (*user_id, format!("user{}", user_id)))
+                .collect();
+            let creator_id = next(member_count as u64 + 2) as i64;
+
+            for strategy in RoundingStrategy::ALL {
+                let equal: Decimal = calculate_shares(amount, &members, strategy, creator_id)
+                    .iter()
+                    .map(|share| share.share_amount)
+                    .sum();
+                assert_eq!(equal, amount, "{amount} / {member_count} ({strategy:?})");
+
+                let weighted: Decimal = split_by_weight(amount, &weights, strategy, creator_id)
+                    .iter()
+                    .map(|(_, share)| *share)
+                    .sum();
+                assert_eq!(weighted, amount, "{amount} by {weights:?} ({strategy:?})");
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_weighted_shares() {
+        let members = [(1, "alice".to_string(), 1), (2, "bob".to_string(), 3)];
+        let shares = calculate_weighted_shares(Decimal::from(40), &members, DISTRIBUTE, 1);
+        assert_eq!(shares[0].share_amount, Decimal::from(10));
+        assert_eq!(shares[1].share_amount, Decimal::from(30));
+        assert!(shares.iter().all(|share| share.is_custom));
+
+        let equal = [(1, "alice".to_string(), 2), (2, "bob".to_string(), 2)];
+        let shares = calculate_weighted_shares(Decimal::from(40), &equal, DISTRIBUTE, 1);
+        assert_eq!(shares[0].share_amount, Decimal::from(20));
+        assert!(!shares[0].is_custom);
+    }
+
+    #[test]
+    fn test_resolve_custom_shares() {
+        let total = Decimal::from(30);
+        let shares = [(1, "20".to_string()), (2, String::new())];
+
+        assert_eq!(resolve_custom_shares(total, &[1, 2], None).unwrap(), None);
+        assert_eq!(
+            resolve_custom_shares(total, &[1, 2], Some(&shares)).unwrap(),
+            Some(vec![(1, Decimal::from(20)), (2, Decimal::from(10))])
+        );
+        assert!(resolve_custom_shares(total, &[1, 2, 3], Some(&shares)).is_err());
+        assert!(resolve_custom_shares(total, &[1, 3], Some(&shares)).is_err());
+    }
+
+    #[test]
+    fn test_member_share_amounts() {
+        let even = RoundingStrategy::DistributeCents;
+        assert_eq!(
+            member_share_amounts(
+                Decimal::from(30),
+                &[(1, None), (2, None), (3, None)],
+                even,
+                1
+            ),
+            vec![
+                (1, Decimal::from(10)),
+                (2, Decimal::from(10)),
+                (3, Decimal::from(10))
+            ]
+        );
+        // Equal splits are rounded to the cent like the shares shown on the
+        // debt, with the leftover cent going where the strategy says
+        assert_eq!(
+            member_share_amounts(
+                Decimal::from(10),
+                &[(1, None), (2, None), (3, None)],
+                RoundingStrategy::CreatorAbsorbs,
+                2
+            ),
+            vec![
+                (1, Decimal::new(333, 2)),
+                (2, Decimal::new(334, 2)),
+                (3, Decimal::new(333, 2))
+            ]
+        );
+        assert_eq!(
+            member_share_amounts(
+                Decimal::from(10),
+                &[(1, Some("7.5".to_string())), (2, Some("2.5".to_string()))],
+                even,
+                1
+            ),
+            vec![(1, Decimal::new(75, 1)), (2, Decimal::new(25, 1))]
+        );
+        assert!(member_share_amounts(Decimal::from(10), &[], even, 1).is_empty());
+    }
+}
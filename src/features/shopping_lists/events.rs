@@ -38,8 +38,63 @@ pub fn broadcast_event(broadcaster: &EventBroadcaster, list_id: i64, event: Shop
     }
 }
 
+/// Drop the channels nobody listens to anymore, returning how many went
+///
+/// Run periodically, since a channel is created for every list ever opened.
 #[cfg(feature = "ssr")]
-pub fn cleanup_inactive_channels(broadcaster: &EventBroadcaster) {
+pub fn cleanup_inactive_channels(broadcaster: &EventBroadcaster) -> usize {
     let mut map = broadcaster.write();
+    let before = map.len();
     map.retain(|_, tx| tx.receiver_count() > 0);
+    before - map.len()
+}
+
+/// Forget the channel of a deleted list
+#[cfg(feature = "ssr")]
+pub fn remove_channel(broadcaster: &EventBroadcaster, list_id: i64) {
+    broadcaster.write().remove(&list_id);
+}
+
+/// Size of the broadcaster, for the health endpoint
+#[cfg(feature = "ssr")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct BroadcasterStats {
+    pub channels: usize,
+    pub receivers: usize,
+}
+
+#[cfg(feature = "ssr")]
+pub fn broadcaster_stats(broadcaster: &EventBroadcaster) -> BroadcasterStats {
+    let map = broadcaster.read();
+    BroadcasterStats {
+        channels: map.len(),
+        receivers: map.values().map(broadcast::Sender::receiver_count).sum(),
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_drops_channels_without_receivers() {
+        let broadcaster = create_broadcaster();
+        let rx = get_or_create_channel(&broadcaster, 1).subscribe();
+        let _kept = get_or_create_channel(&broadcaster, 2).subscribe();
+        assert_eq!(
+            broadcaster_stats(&broadcaster),
+            BroadcasterStats {
+                channels: 2,
+                receivers: 2
+            }
+        );
+
+        drop(rx);
+        assert_eq!(cleanup_inactive_channels(&broadcaster), 1);
+        assert!(!broadcaster.read().contains_key(&1));
+        assert_eq!(broadcaster_stats(&broadcaster).channels, 1);
+
+        remove_channel(&broadcaster, 2);
+        assert_eq!(broadcaster_stats(&broadcaster).channels, 0);
+    }
 }
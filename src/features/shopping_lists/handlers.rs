@@ -6,6 +6,8 @@ use sqlx::SqlitePool;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
 #[cfg(feature = "ssr")]
@@ -13,9 +15,17 @@ use crate::features::shopping_lists::events::*;
 use crate::features::shopping_lists::models::*;
 #[cfg(feature = "ssr")]
 use crate::features::shopping_lists::utils::*;
+#[cfg(feature = "ssr")]
+use crate::validation::{rules::MAX_NAME_LENGTH, validate_name};
 
+/// List a group's shopping lists, newest first
 #[server(GetShoppingLists)]
-pub async fn get_shopping_lists(group_id: i64) -> Result<Vec<ShoppingListSummary>, ServerFnError> {
+pub async fn get_shopping_lists(
+    group_id: i64,
+    /// Return only archived lists instead of the active ones
+    #[server(default)]
+    archived: Option<bool>,
+) -> Result<Vec<ShoppingListSummary>, ServerFnError> {
     let session = extract::<Session>()
         .await
         .map_err(|_| ServerFnError::new("Authentication error"))?;
@@ -27,6 +37,7 @@ pub async fn get_shopping_lists(group_id: i64) -> Result<Vec<ShoppingListSummary
 
     verify_group_membership(&pool, user.id, group_id).await?;
 
+    let archived = archived.unwrap_or(false);
     let lists = sqlx::query!(
         r#"
         SELECT 
@@ -35,20 +46,22 @@ pub async fn get_shopping_lists(group_id: i64) -> Result<Vec<ShoppingListSummary
             sl.group_id as "group_id!",
             sl.created_by as "created_by!",
             u.username as creator_username,
+            sl.is_archived,
             CAST(COUNT(sli.id) AS INTEGER) as "total_items!: i64",
             CAST(COUNT(CASE WHEN sli.is_completed = 1 THEN 1 END) AS INTEGER) as "completed_items!: i64"
         FROM shopping_lists sl
         INNER JOIN users u ON sl.created_by = u.id
         LEFT JOIN shopping_list_items sli ON sl.id = sli.shopping_list_id
-        WHERE sl.group_id = ?
+        WHERE sl.group_id = ? AND sl.is_archived = ?
         GROUP BY sl.id
         ORDER BY sl.created_at DESC
         "#,
-        group_id
+        group_id,
+        archived
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(lists
         .into_iter()
@@ -58,6 +71,7 @@ pub async fn get_shopping_lists(group_id: i64) -> Result<Vec<ShoppingListSummary
             group_id: row.group_id,
             created_by: row.created_by,
             creator_username: row.creator_username,
+            is_archived: row.is_archived != 0,
             total_items: row.total_items,
             completed_items: row.completed_items,
         })
@@ -79,19 +93,25 @@ pub async fn get_shopping_list(list_id: i64) -> Result<ShoppingList, ServerFnErr
 
     let list = sqlx::query_as!(
         ShoppingList,
-        "SELECT * FROM shopping_lists WHERE id = ?",
+        r#"
+        SELECT id, group_id, created_by, name,
+            is_archived as "is_archived: bool",
+            archived_at,
+            created_at, updated_at
+        FROM shopping_lists WHERE id = ?
+        "#,
         list_id
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(list)
 }
 
 #[server(CreateShoppingList)]
 pub async fn create_shopping_list(group_id: i64, name: String) -> Result<i64, ServerFnError> {
-    validate_name(&name)?;
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
 
     let session = extract::<Session>()
         .await
@@ -102,25 +122,24 @@ pub async fn create_shopping_list(group_id: i64, name: String) -> Result<i64, Se
 
     let pool = expect_context::<SqlitePool>();
 
-    verify_group_membership(&pool, user.id, group_id).await?;
+    verify_group_edit_access(&pool, user.id, group_id).await?;
 
-    let trimmed_name = name.trim();
     let result = sqlx::query!(
         "INSERT INTO shopping_lists (group_id, created_by, name) VALUES (?, ?, ?)",
         group_id,
         user.id,
-        trimmed_name
+        name
     )
     .execute(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(result.last_insert_rowid())
 }
 
 #[server(UpdateShoppingList)]
 pub async fn update_shopping_list(list_id: i64, name: String) -> Result<(), ServerFnError> {
-    validate_name(&name)?;
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
 
     let session = extract::<Session>()
         .await
@@ -132,25 +151,72 @@ pub async fn update_shopping_list(list_id: i64, name: String) -> Result<(), Serv
     let pool = expect_context::<SqlitePool>();
     let broadcaster = expect_context::<EventBroadcaster>();
 
-    verify_list_access(&pool, user.id, list_id).await?;
+    verify_list_edit_access(&pool, user.id, list_id).await?;
 
-    let trimmed_name = name.trim();
     let now = time::OffsetDateTime::now_utc();
     sqlx::query!(
         "UPDATE shopping_lists SET name = ?, updated_at = ? WHERE id = ?",
-        trimmed_name,
+        name,
         now,
         list_id
     )
     .execute(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     broadcast_event(
         &broadcaster,
         list_id,
-        ShoppingListEvent::ListUpdated {
-            name: trimmed_name.to_string(),
+        ShoppingListEvent::ListUpdated { name: name.clone() },
+    );
+
+    Ok(())
+}
+
+/// Archive a list, hiding it from the group while keeping its items
+#[server(ArchiveShoppingList)]
+pub async fn archive_shopping_list(list_id: i64) -> Result<(), ServerFnError> {
+    set_list_archived(list_id, true).await
+}
+
+/// Restore an archived list
+#[server(UnarchiveShoppingList)]
+pub async fn unarchive_shopping_list(list_id: i64) -> Result<(), ServerFnError> {
+    set_list_archived(list_id, false).await
+}
+
+#[cfg(feature = "ssr")]
+async fn set_list_archived(list_id: i64, archived: bool) -> Result<(), ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<EventBroadcaster>();
+
+    verify_list_edit_access(&pool, user.id, list_id).await?;
+
+    let now = time::OffsetDateTime::now_utc();
+    let archived_at = archived.then_some(now);
+    sqlx::query!(
+        "UPDATE shopping_lists SET is_archived = ?, archived_at = ?, updated_at = ? WHERE id = ?",
+        archived,
+        archived_at,
+        now,
+        list_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    broadcast_event(
+        &broadcaster,
+        list_id,
+        ShoppingListEvent::ListArchived {
+            is_archived: archived,
         },
     );
 
@@ -169,14 +235,30 @@ pub async fn delete_shopping_list(list_id: i64) -> Result<(), ServerFnError> {
     let pool = expect_context::<SqlitePool>();
     let broadcaster = expect_context::<EventBroadcaster>();
 
+    verify_list_edit_access(&pool, user.id, list_id).await?;
     verify_list_creator(&pool, user.id, list_id).await?;
 
+    let is_archived = sqlx::query_scalar!(
+        "SELECT is_archived FROM shopping_lists WHERE id = ?",
+        list_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    if is_archived == 0 {
+        return Err(ServerFnError::new(
+            "Archive the list before deleting it".to_string(),
+        ));
+    }
+
     sqlx::query!("DELETE FROM shopping_lists WHERE id = ?", list_id)
         .execute(&pool)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     broadcast_event(&broadcaster, list_id, ShoppingListEvent::ListDeleted);
+    remove_channel(&broadcaster, list_id);
 
     Ok(())
 }
@@ -200,17 +282,22 @@ pub async fn get_shopping_list_items(list_id: i64) -> Result<Vec<ShoppingListIte
             sli.id as "id!",
             sli.shopping_list_id as "shopping_list_id!",
             sli.name,
-            sli.quantity,
+            sli.quantity_value,
+            sli.quantity_unit,
             sli.category,
             sli.is_completed,
             sli.completed_by,
             sli.completed_at,
+            sli.assigned_to,
             sli.position as "position!",
+            sli.version,
             sli.created_at,
             sli.updated_at,
-            u.username as "completed_by_username?"
+            u.username as "completed_by_username?",
+            au.username as "assigned_to_username?"
         FROM shopping_list_items sli
         LEFT JOIN users u ON sli.completed_by = u.id
+        LEFT JOIN users au ON sli.assigned_to = au.id
         WHERE sli.shopping_list_id = ?
         ORDER BY sli.position ASC, sli.created_at ASC
         "#,
@@ -218,7 +305,7 @@ pub async fn get_shopping_list_items(list_id: i64) -> Result<Vec<ShoppingListIte
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(items
         .into_iter()
@@ -226,27 +313,142 @@ pub async fn get_shopping_list_items(list_id: i64) -> Result<Vec<ShoppingListIte
             id: row.id,
             shopping_list_id: row.shopping_list_id,
             name: row.name,
-            quantity: row.quantity,
+            quantity_value: row.quantity_value.and_then(|v| v.parse().ok()),
+            quantity_unit: row.quantity_unit,
             category: row.category,
             is_completed: row.is_completed != 0,
             completed_by: row.completed_by,
             completed_by_username: row.completed_by_username,
             completed_at: row.completed_at,
+            assigned_to: row.assigned_to,
+            assigned_to_username: row.assigned_to_username,
             position: row.position,
+            version: row.version,
             created_at: row.created_at,
             updated_at: row.updated_at,
         })
         .collect())
 }
 
+#[server(GetCategoryOrder)]
+pub async fn get_category_order(list_id: i64) -> Result<Vec<String>, ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    verify_list_access(&pool, user.id, list_id).await?;
+
+    sqlx::query_scalar!(
+        r#"
+        SELECT category
+        FROM shopping_list_category_order
+        WHERE shopping_list_id = ?
+        ORDER BY position ASC
+        "#,
+        list_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))
+}
+
+/// Categories already used for items in the lists of this list's group, to
+/// suggest while adding items
+#[server(GetListCategories)]
+pub async fn get_list_categories(list_id: i64) -> Result<Vec<String>, ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    verify_list_access(&pool, user.id, list_id).await?;
+
+    group_item_categories(&pool, list_id).await
+}
+
+#[server(SetCategoryOrder)]
+pub async fn set_category_order(
+    list_id: i64,
+    categories: Vec<String>,
+) -> Result<(), ServerFnError> {
+    let categories = normalize_category_order(&categories)?;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<EventBroadcaster>();
+
+    verify_list_edit_access(&pool, user.id, list_id).await?;
+    verify_list_not_archived(&pool, list_id).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    sqlx::query!(
+        "DELETE FROM shopping_list_category_order WHERE shopping_list_id = ?",
+        list_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    for (position, category) in categories.iter().enumerate() {
+        let position = position as i64;
+        sqlx::query!(
+            "INSERT INTO shopping_list_category_order (shopping_list_id, category, position) VALUES (?, ?, ?)",
+            list_id,
+            category,
+            position
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    broadcast_event(
+        &broadcaster,
+        list_id,
+        ShoppingListEvent::CategoryOrderUpdated { categories },
+    );
+
+    Ok(())
+}
+
 #[server(AddShoppingListItem)]
 pub async fn add_shopping_list_item(
     list_id: i64,
     name: String,
-    quantity: Option<String>,
+    /// Amount as typed, e.g. "1,5"
+    #[server(default)]
+    quantity_value: Option<String>,
+    /// Unit such as "kg"; blank for a plain count
+    #[server(default)]
+    quantity_unit: Option<String>,
     category: Option<String>,
 ) -> Result<i64, ServerFnError> {
-    validate_name(&name)?;
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+    let (quantity_value, quantity_unit) =
+        parse_quantity(quantity_value.as_deref(), quantity_unit.as_deref())?;
 
     let session = extract::<Session>()
         .await
@@ -258,7 +460,8 @@ pub async fn add_shopping_list_item(
     let pool = expect_context::<SqlitePool>();
     let broadcaster = expect_context::<EventBroadcaster>();
 
-    verify_list_access(&pool, user.id, list_id).await?;
+    verify_list_edit_access(&pool, user.id, list_id).await?;
+    verify_list_not_archived(&pool, list_id).await?;
 
     let max_position = sqlx::query_scalar!(
         "SELECT COALESCE(MAX(position), -1) FROM shopping_list_items WHERE shopping_list_id = ?",
@@ -266,26 +469,27 @@ pub async fn add_shopping_list_item(
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     let position = max_position + 1;
 
-    let trimmed_name = name.trim();
+    let stored_value = quantity_value.map(|v| v.to_string());
     let result = sqlx::query!(
         r#"
         INSERT INTO shopping_list_items 
-        (shopping_list_id, name, quantity, category, position) 
-        VALUES (?, ?, ?, ?, ?)
+        (shopping_list_id, name, quantity_value, quantity_unit, category, position) 
+        VALUES (?, ?, ?, ?, ?, ?)
         "#,
         list_id,
-        trimmed_name,
-        quantity,
+        name,
+        stored_value,
+        quantity_unit,
         category,
         position
     )
     .execute(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     let item_id = result.last_insert_rowid();
 
@@ -294,21 +498,140 @@ pub async fn add_shopping_list_item(
         list_id,
         ShoppingListEvent::ItemAdded {
             item_id,
-            name: trimmed_name.to_string(),
-            quantity: quantity.clone(),
+            name: name.clone(),
+            quantity_value,
+            quantity_unit: quantity_unit.clone(),
             category: category.clone(),
             position,
             added_by_username: user.username.clone(),
         },
     );
 
-    log_activity(&pool, list_id, user.id, "added_item", trimmed_name).await?;
+    log_activity(&pool, list_id, user.id, "added_item", &name).await?;
 
     Ok(item_id)
 }
 
+/// Add several items at once, e.g. from a pasted list.
+///
+/// Items are validated up front and inserted in order within one transaction;
+/// a single invalid item rejects the whole batch. Returns the new item ids.
+#[server(AddShoppingListItems)]
+pub async fn add_shopping_list_items(
+    list_id: i64,
+    items: Vec<ShoppingListItemDraft>,
+) -> Result<Vec<i64>, ServerFnError> {
+    if items.is_empty() {
+        return Err(ServerFnError::new("No items to add".to_string()));
+    }
+    if items.len() > MAX_BATCH_ITEMS {
+        return Err(ServerFnError::new(format!(
+            "Too many items: at most {} can be added at once",
+            MAX_BATCH_ITEMS
+        )));
+    }
+    let mut items = items;
+    for (index, item) in items.iter_mut().enumerate() {
+        item.name = validate_name(&item.name, 1, MAX_NAME_LENGTH, "Name")
+            .map_err(|e| ServerFnError::new(format!("Item {}: {}", index + 1, e)))?;
+        let value = item.quantity_value.map(|v| v.to_string());
+        (item.quantity_value, item.quantity_unit) =
+            parse_quantity(value.as_deref(), item.quantity_unit.as_deref())
+                .map_err(|e| ServerFnError::new(format!("Item {}: {}", index + 1, e)))?;
+    }
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<EventBroadcaster>();
+
+    verify_list_edit_access(&pool, user.id, list_id).await?;
+    verify_list_not_archived(&pool, list_id).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    let max_position = sqlx::query_scalar!(
+        "SELECT COALESCE(MAX(position), -1) FROM shopping_list_items WHERE shopping_list_id = ?",
+        list_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    let mut item_ids = Vec::with_capacity(items.len());
+    for (offset, item) in items.iter().enumerate() {
+        let position = max_position + 1 + offset as i64;
+        let name = &item.name;
+        let quantity_value = item.quantity_value.map(|v| v.to_string());
+        let category = item
+            .category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty());
+
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO shopping_list_items
+            (shopping_list_id, name, quantity_value, quantity_unit, category, position)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            list_id,
+            name,
+            quantity_value,
+            item.quantity_unit,
+            category,
+            position
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+        item_ids.push(result.last_insert_rowid());
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    let summary = if item_ids.len() == 1 {
+        "1 item".to_string()
+    } else {
+        format!("{} items", item_ids.len())
+    };
+    log_activity(&pool, list_id, user.id, "added_items", &summary).await?;
+
+    broadcast_event(
+        &broadcaster,
+        list_id,
+        ShoppingListEvent::ItemsAdded {
+            count: item_ids.len(),
+            added_by_username: user.username.clone(),
+        },
+    );
+
+    Ok(item_ids)
+}
+
+/// Set the completion state of an item.
+///
+/// The caller passes the desired state together with the item version it last
+/// saw. The update only applies if the version is unchanged, so two people
+/// toggling the same item at once cannot silently overwrite each other; the
+/// loser gets a conflict error and should refetch. Returns the new version.
 #[server(ToggleShoppingListItem)]
-pub async fn toggle_shopping_list_item(item_id: i64) -> Result<bool, ServerFnError> {
+pub async fn toggle_shopping_list_item(
+    item_id: i64,
+    completed: bool,
+    version: i64,
+) -> Result<i64, ServerFnError> {
     let session = extract::<Session>()
         .await
         .map_err(|_| ServerFnError::new("Authentication error"))?;
@@ -320,71 +643,92 @@ pub async fn toggle_shopping_list_item(item_id: i64) -> Result<bool, ServerFnErr
     let broadcaster = expect_context::<EventBroadcaster>();
 
     let item = sqlx::query!(
-        "SELECT shopping_list_id, is_completed, name FROM shopping_list_items WHERE id = ?",
+        "SELECT shopping_list_id, name FROM shopping_list_items WHERE id = ?",
         item_id
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
-    verify_list_access(&pool, user.id, item.shopping_list_id).await?;
+    verify_list_edit_access(&pool, user.id, item.shopping_list_id).await?;
 
-    let new_completed = item.is_completed == 0;
     let now = time::OffsetDateTime::now_utc();
-    let completed_by_value = if new_completed { Some(user.id) } else { None };
-    let completed_at_value = if new_completed { Some(now) } else { None };
+    let completed_by_value = if completed { Some(user.id) } else { None };
+    let completed_at_value = if completed { Some(now) } else { None };
 
-    sqlx::query!(
+    // Conditional update: completion state, attribution and version bump are
+    // written in a single statement guarded by the caller's version
+    let result = sqlx::query!(
         r#"
         UPDATE shopping_list_items 
         SET is_completed = ?, 
             completed_by = ?, 
             completed_at = ?,
-            updated_at = ?
-        WHERE id = ?
+            updated_at = ?,
+            version = version + 1
+        WHERE id = ? AND version = ?
         "#,
-        new_completed,
+        completed,
         completed_by_value,
         completed_at_value,
         now,
-        item_id
+        item_id,
+        version
     )
     .execute(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    if result.rows_affected() == 0 {
+        return Err(ServerFnError::new(CONFLICT_ERROR));
+    }
+
+    let new_version = version + 1;
 
     broadcast_event(
         &broadcaster,
         item.shopping_list_id,
         ShoppingListEvent::ItemToggled {
             item_id,
-            is_completed: new_completed,
-            completed_by_username: if new_completed {
+            is_completed: completed,
+            completed_by_username: if completed {
                 Some(user.username.clone())
             } else {
                 None
             },
+            version: new_version,
         },
     );
 
-    let action = if new_completed {
+    let action = if completed {
         "completed_item"
     } else {
         "uncompleted_item"
     };
     log_activity(&pool, item.shopping_list_id, user.id, action, &item.name).await?;
 
-    Ok(new_completed)
+    Ok(new_version)
 }
 
+/// Change an item's name, quantity and category, completed or not
+///
+/// Concurrent edits don't conflict: the last one saved wins and the
+/// `ItemUpdated` event brings everyone else up to date.
 #[server(UpdateShoppingListItem)]
 pub async fn update_shopping_list_item(
     item_id: i64,
     name: String,
-    quantity: Option<String>,
+    /// Amount as typed, e.g. "1,5"
+    #[server(default)]
+    quantity_value: Option<String>,
+    /// Unit such as "kg"; blank for a plain count
+    #[server(default)]
+    quantity_unit: Option<String>,
     category: Option<String>,
 ) -> Result<(), ServerFnError> {
-    validate_name(&name)?;
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+    let (quantity_value, quantity_unit) =
+        parse_quantity(quantity_value.as_deref(), quantity_unit.as_deref())?;
 
     let session = extract::<Session>()
         .await
@@ -402,39 +746,306 @@ pub async fn update_shopping_list_item(
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
-    verify_list_access(&pool, user.id, item.shopping_list_id).await?;
+    verify_list_edit_access(&pool, user.id, item.shopping_list_id).await?;
+    verify_list_not_archived(&pool, item.shopping_list_id).await?;
 
-    let trimmed_name = name.trim();
+    let stored_value = quantity_value.map(|v| v.to_string());
     let now = time::OffsetDateTime::now_utc();
     sqlx::query!(
         r#"
         UPDATE shopping_list_items 
-        SET name = ?, quantity = ?, category = ?, updated_at = ?
+        SET name = ?, quantity_value = ?, quantity_unit = ?, category = ?, updated_at = ?,
+            version = version + 1
         WHERE id = ?
         "#,
-        trimmed_name,
-        quantity,
+        name,
+        stored_value,
+        quantity_unit,
         category,
         now,
         item_id
     )
     .execute(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     broadcast_event(
         &broadcaster,
         item.shopping_list_id,
         ShoppingListEvent::ItemUpdated {
             item_id,
-            name: trimmed_name.to_string(),
-            quantity: quantity.clone(),
+            name: name.clone(),
+            quantity_value,
+            quantity_unit: quantity_unit.clone(),
             category: category.clone(),
         },
     );
 
+    log_activity(&pool, item.shopping_list_id, user.id, "updated_item", &name).await?;
+
+    Ok(())
+}
+
+/// Assign an item to a member of the list's group, or clear the assignment
+/// with `None`
+#[server(AssignShoppingListItem)]
+pub async fn assign_shopping_list_item(
+    item_id: i64,
+    user_id: Option<i64>,
+) -> Result<(), ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<EventBroadcaster>();
+
+    let item = sqlx::query!(
+        "SELECT shopping_list_id FROM shopping_list_items WHERE id = ?",
+        item_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    verify_list_edit_access(&pool, user.id, item.shopping_list_id).await?;
+
+    let assigned_to_username = match user_id {
+        Some(assignee) => {
+            if !is_list_member(&pool, assignee, item.shopping_list_id).await? {
+                return Err(ServerFnError::new(
+                    "Items can only be assigned to group members",
+                ));
+            }
+            sqlx::query_scalar!("SELECT username FROM users WHERE id = ?", assignee)
+                .fetch_one(&pool)
+                .await
+                .map(Some)
+                .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+        }
+        None => None,
+    };
+
+    let now = time::OffsetDateTime::now_utc();
+    sqlx::query!(
+        r#"
+        UPDATE shopping_list_items 
+        SET assigned_to = ?, updated_at = ?, version = version + 1
+        WHERE id = ?
+        "#,
+        user_id,
+        now,
+        item_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    broadcast_event(
+        &broadcaster,
+        item.shopping_list_id,
+        ShoppingListEvent::ItemAssigned {
+            item_id,
+            assigned_to: user_id,
+            assigned_to_username,
+        },
+    );
+
+    Ok(())
+}
+
+/// Move an item to a new index in its list
+///
+/// The other items shift to make room and every position is rewritten in
+/// one transaction, so positions stay contiguous from zero afterwards.
+#[server(ReorderShoppingListItem)]
+pub async fn reorder_shopping_list_item(
+    item_id: i64,
+    new_position: i64,
+) -> Result<(), ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<EventBroadcaster>();
+
+    let item = sqlx::query!(
+        "SELECT shopping_list_id FROM shopping_list_items WHERE id = ?",
+        item_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    verify_list_edit_access(&pool, user.id, item.shopping_list_id).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    let ids = sqlx::query_scalar!(
+        r#"
+        SELECT id as "id!" FROM shopping_list_items
+        WHERE shopping_list_id = ?
+        ORDER BY position ASC, created_at ASC
+        "#,
+        item.shopping_list_id
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    let new_position = usize::try_from(new_position.max(0)).unwrap_or(0);
+    for (position, id) in move_item(ids, item_id, new_position)
+        .into_iter()
+        .enumerate()
+    {
+        let position = position as i64;
+        sqlx::query!(
+            "UPDATE shopping_list_items SET position = ? WHERE id = ? AND position != ?",
+            position,
+            id,
+            position
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    broadcast_event(
+        &broadcaster,
+        item.shopping_list_id,
+        ShoppingListEvent::ItemsReordered,
+    );
+
+    Ok(())
+}
+
+/// Merge a duplicate item into another item with the same name and unit
+///
+/// The target keeps its place and gets the summed quantity; the source item
+/// is deleted.
+#[server(MergeShoppingListItems)]
+pub async fn merge_shopping_list_items(
+    source_id: i64,
+    target_id: i64,
+) -> Result<(), ServerFnError> {
+    if source_id == target_id {
+        return Err(ServerFnError::new(
+            "An item cannot be merged into itself".to_string(),
+        ));
+    }
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<EventBroadcaster>();
+
+    let source = sqlx::query!(
+        "SELECT shopping_list_id, name, quantity_value, quantity_unit FROM shopping_list_items WHERE id = ?",
+        source_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    let target = sqlx::query!(
+        "SELECT shopping_list_id, name, quantity_value, quantity_unit, category FROM shopping_list_items WHERE id = ?",
+        target_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    verify_list_edit_access(&pool, user.id, target.shopping_list_id).await?;
+    verify_list_not_archived(&pool, target.shopping_list_id).await?;
+
+    if source.shopping_list_id != target.shopping_list_id
+        || source.name.trim().to_lowercase() != target.name.trim().to_lowercase()
+        || source.quantity_unit != target.quantity_unit
+    {
+        return Err(ServerFnError::new(
+            "Only items with the same name and unit can be merged".to_string(),
+        ));
+    }
+
+    let quantity_value = merged_quantity(
+        source.quantity_value.and_then(|v| v.parse().ok()),
+        target.quantity_value.and_then(|v| v.parse().ok()),
+    );
+    let stored_value = quantity_value.to_string();
+    let now = time::OffsetDateTime::now_utc();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    sqlx::query!(
+        r#"
+        UPDATE shopping_list_items 
+        SET quantity_value = ?, updated_at = ?, version = version + 1
+        WHERE id = ?
+        "#,
+        stored_value,
+        now,
+        target_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    sqlx::query!("DELETE FROM shopping_list_items WHERE id = ?", source_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    broadcast_event(
+        &broadcaster,
+        target.shopping_list_id,
+        ShoppingListEvent::ItemUpdated {
+            item_id: target_id,
+            name: target.name.clone(),
+            quantity_value: Some(quantity_value),
+            quantity_unit: target.quantity_unit,
+            category: target.category,
+        },
+    );
+    broadcast_event(
+        &broadcaster,
+        target.shopping_list_id,
+        ShoppingListEvent::ItemDeleted { item_id: source_id },
+    );
+
+    log_activity(
+        &pool,
+        target.shopping_list_id,
+        user.id,
+        "merged_item",
+        &target.name,
+    )
+    .await?;
+
     Ok(())
 }
 
@@ -456,14 +1067,14 @@ pub async fn delete_shopping_list_item(item_id: i64) -> Result<(), ServerFnError
     )
     .fetch_one(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
-    verify_list_access(&pool, user.id, item.shopping_list_id).await?;
+    verify_list_edit_access(&pool, user.id, item.shopping_list_id).await?;
 
     sqlx::query!("DELETE FROM shopping_list_items WHERE id = ?", item_id)
         .execute(&pool)
         .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     broadcast_event(
         &broadcaster,
@@ -520,7 +1131,7 @@ pub async fn get_shopping_list_activity(
     )
     .fetch_all(&pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(activities
         .into_iter()
@@ -1,10 +1,16 @@
 pub mod events;
 pub mod handlers;
 pub mod models;
+pub mod offline_queue;
+pub mod templates;
 pub mod utils;
 
 // Re-export commonly used items
 #[cfg(feature = "ssr")]
-pub use events::{EventBroadcaster, broadcast_event, create_broadcaster};
+pub use events::{
+    BroadcasterStats, EventBroadcaster, broadcast_event, broadcaster_stats,
+    cleanup_inactive_channels, create_broadcaster,
+};
 pub use handlers::*;
 pub use models::*;
+pub use templates::*;
@@ -1,3 +1,4 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
@@ -7,6 +8,9 @@ pub struct ShoppingList {
     pub group_id: i64,
     pub created_by: i64,
     pub name: String,
+    /// Archived lists are read-only and hidden from the group by default
+    pub is_archived: bool,
+    pub archived_at: Option<OffsetDateTime>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
@@ -18,6 +22,7 @@ pub struct ShoppingListSummary {
     pub group_id: i64,
     pub created_by: i64,
     pub creator_username: String,
+    pub is_archived: bool,
     pub total_items: i64,
     pub completed_items: i64,
 }
@@ -37,17 +42,125 @@ pub struct ShoppingListItem {
     pub id: i64,
     pub shopping_list_id: i64,
     pub name: String,
-    pub quantity: Option<String>,
+    /// Amount to buy; `None` when no amount was given
+    pub quantity_value: Option<Decimal>,
+    /// Unit of the amount, e.g. "kg"; `None` means a plain count
+    pub quantity_unit: Option<String>,
     pub category: Option<String>,
     pub is_completed: bool,
     pub completed_by: Option<i64>,
     pub completed_by_username: Option<String>,
     pub completed_at: Option<OffsetDateTime>,
+    /// Member who is expected to buy the item
+    pub assigned_to: Option<i64>,
+    pub assigned_to_username: Option<String>,
     pub position: i64,
+    pub version: i64,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
 
+impl ShoppingListItem {
+    /// Display form of the quantity, e.g. "1.5 kg" or "3"
+    pub fn quantity_label(&self) -> Option<String> {
+        format_quantity(self.quantity_value, self.quantity_unit.as_deref())
+    }
+}
+
+/// A group's reusable set of items that new lists can start from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShoppingListTemplate {
+    pub id: i64,
+    pub group_id: i64,
+    pub name: String,
+    pub created_by: i64,
+    pub creator_username: String,
+    pub item_count: i64,
+    /// Whether the current user may delete it: its creator or a group admin
+    pub can_delete: bool,
+}
+
+/// A new item that has not been saved yet, used for batch adds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShoppingListItemDraft {
+    pub name: String,
+    pub quantity_value: Option<Decimal>,
+    pub quantity_unit: Option<String>,
+    pub category: Option<String>,
+}
+
+impl ShoppingListItemDraft {
+    /// Display form of the quantity, e.g. "1.5 kg" or "3"
+    pub fn quantity_label(&self) -> Option<String> {
+        format_quantity(self.quantity_value, self.quantity_unit.as_deref())
+    }
+}
+
+fn format_quantity(value: Option<Decimal>, unit: Option<&str>) -> Option<String> {
+    let value = value?.normalize();
+    Some(match unit {
+        Some(unit) => format!("{} {}", value, unit),
+        None => value.to_string(),
+    })
+}
+
+/// Units offered by the unit picker
+///
+/// Items store the unit as text, so `Other` stands for any custom unit the
+/// user typed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuantityUnit {
+    Gram,
+    Kilogram,
+    Millilitre,
+    Litre,
+    Pack,
+    Can,
+    Bottle,
+    Other,
+}
+
+impl QuantityUnit {
+    /// All units in picker order
+    pub const ALL: [QuantityUnit; 8] = [
+        QuantityUnit::Gram,
+        QuantityUnit::Kilogram,
+        QuantityUnit::Millilitre,
+        QuantityUnit::Litre,
+        QuantityUnit::Pack,
+        QuantityUnit::Can,
+        QuantityUnit::Bottle,
+        QuantityUnit::Other,
+    ];
+
+    /// Text stored for the unit; `None` for `Other`
+    pub fn abbreviation(self) -> Option<&'static str> {
+        match self {
+            QuantityUnit::Gram => Some("g"),
+            QuantityUnit::Kilogram => Some("kg"),
+            QuantityUnit::Millilitre => Some("ml"),
+            QuantityUnit::Litre => Some("l"),
+            QuantityUnit::Pack => Some("pack"),
+            QuantityUnit::Can => Some("can"),
+            QuantityUnit::Bottle => Some("bottle"),
+            QuantityUnit::Other => None,
+        }
+    }
+
+    /// Label shown in the unit picker
+    pub fn label(self) -> &'static str {
+        self.abbreviation().unwrap_or("other")
+    }
+
+    /// The common unit for a stored unit, or `Other` for custom ones
+    pub fn from_abbreviation(unit: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|u| u.abbreviation() == Some(unit))
+            .unwrap_or(QuantityUnit::Other)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShoppingListActivity {
     pub id: i64,
@@ -62,10 +175,14 @@ pub struct ShoppingListActivity {
 impl ShoppingListActivity {
     pub fn action_description(&self) -> String {
         match self.action.as_str() {
-            "added_item" => format!("{} added {}", self.username, self.item_name),
+            "added_item" | "added_items" => {
+                format!("{} added {}", self.username, self.item_name)
+            }
             "completed_item" => format!("{} completed {}", self.username, self.item_name),
             "uncompleted_item" => format!("{} uncompleted {}", self.username, self.item_name),
+            "updated_item" => format!("{} edited {}", self.username, self.item_name),
             "deleted_item" => format!("{} deleted {}", self.username, self.item_name),
+            "merged_item" => format!("{} merged duplicates of {}", self.username, self.item_name),
             _ => format!("{} {} {}", self.username, self.action, self.item_name),
         }
     }
@@ -77,27 +194,46 @@ pub enum ShoppingListEvent {
     ItemAdded {
         item_id: i64,
         name: String,
-        quantity: Option<String>,
+        quantity_value: Option<Decimal>,
+        quantity_unit: Option<String>,
         category: Option<String>,
         position: i64,
         added_by_username: String,
     },
+    ItemsAdded {
+        count: usize,
+        added_by_username: String,
+    },
     ItemToggled {
         item_id: i64,
         is_completed: bool,
         completed_by_username: Option<String>,
+        version: i64,
     },
     ItemDeleted {
         item_id: i64,
     },
+    ItemAssigned {
+        item_id: i64,
+        assigned_to: Option<i64>,
+        assigned_to_username: Option<String>,
+    },
     ItemUpdated {
         item_id: i64,
         name: String,
-        quantity: Option<String>,
+        quantity_value: Option<Decimal>,
+        quantity_unit: Option<String>,
         category: Option<String>,
     },
     ListUpdated {
         name: String,
     },
+    ListArchived {
+        is_archived: bool,
+    },
+    ItemsReordered,
+    CategoryOrderUpdated {
+        categories: Vec<String>,
+    },
     ListDeleted,
 }
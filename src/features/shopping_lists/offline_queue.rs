@@ -0,0 +1,400 @@
+//! Shopping list changes made while offline
+//!
+//! Ticking off or adding an item fails outright without a connection, which
+//! is exactly when people use the list in a shop. Such changes are queued in
+//! `localStorage`, shown right away, and sent in order once the browser is
+//! back online.
+
+use std::{collections::VecDeque, future::Future};
+
+use leptos::prelude::ServerFnError;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::{
+    handlers::{add_shopping_list_item, toggle_shopping_list_item},
+    models::ShoppingListItem,
+    utils::parse_quantity,
+};
+
+/// `localStorage` key the queue is kept under
+pub const STORAGE_KEY: &str = "splitify.shopping_list_offline_queue";
+
+/// A change waiting to be sent to the server
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueuedChange {
+    Toggle {
+        item_id: i64,
+        completed: bool,
+        /// Version of the item when it was toggled, so a change made by
+        /// someone else in the meantime is not overwritten
+        version: i64,
+    },
+    Add {
+        list_id: i64,
+        name: String,
+        quantity_value: Option<String>,
+        quantity_unit: Option<String>,
+        category: Option<String>,
+    },
+}
+
+impl QueuedChange {
+    /// Short description for notices, e.g. `Adding "Milk"`
+    pub fn describe(&self) -> String {
+        match self {
+            QueuedChange::Toggle {
+                completed: true, ..
+            } => "Ticking off an item".to_string(),
+            QueuedChange::Toggle { .. } => "Unticking an item".to_string(),
+            QueuedChange::Add { name, .. } => format!("Adding \"{}\"", name),
+        }
+    }
+}
+
+/// Changes waiting to be sent, oldest first
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct OfflineQueue {
+    changes: Vec<QueuedChange>,
+}
+
+impl OfflineQueue {
+    pub fn changes(&self) -> &[QueuedChange] {
+        &self.changes
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Queue a change
+    ///
+    /// Toggling an item back before it was sent cancels the earlier toggle,
+    /// since both were made against the same server version.
+    pub fn push(&mut self, change: QueuedChange) {
+        if let QueuedChange::Toggle {
+            item_id, completed, ..
+        } = change
+        {
+            let earlier = self.changes.iter().position(|queued| {
+                matches!(queued, QueuedChange::Toggle { item_id: id, .. } if *id == item_id)
+            });
+            if let Some(index) = earlier {
+                let QueuedChange::Toggle {
+                    completed: earlier_completed,
+                    ..
+                } = self.changes.remove(index)
+                else {
+                    unreachable!("position only matches toggles");
+                };
+                if earlier_completed != completed {
+                    return;
+                }
+            }
+        }
+
+        self.changes.push(change);
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Queue from its stored form; anything unreadable counts as empty
+    pub fn from_json(json: &str) -> Self {
+        serde_json::from_str(json).unwrap_or_default()
+    }
+
+    /// A list's items as they will look once the queue is sent
+    ///
+    /// Queued items get negative ids since they have none yet.
+    pub fn apply(&self, list_id: i64, mut items: Vec<ShoppingListItem>) -> Vec<ShoppingListItem> {
+        let mut next_id = -1;
+        for change in &self.changes {
+            match change {
+                QueuedChange::Toggle {
+                    item_id, completed, ..
+                } => {
+                    if let Some(item) = items.iter_mut().find(|item| item.id == *item_id) {
+                        item.is_completed = *completed;
+                    }
+                }
+                QueuedChange::Add {
+                    list_id: queued_list_id,
+                    name,
+                    quantity_value,
+                    quantity_unit,
+                    category,
+                } if *queued_list_id == list_id => {
+                    let (quantity_value, quantity_unit) =
+                        parse_quantity(quantity_value.as_deref(), quantity_unit.as_deref())
+                            .unwrap_or_default();
+                    items.push(ShoppingListItem {
+                        id: next_id,
+                        shopping_list_id: list_id,
+                        name: name.trim().to_string(),
+                        quantity_value,
+                        quantity_unit,
+                        category: category.clone(),
+                        is_completed: false,
+                        completed_by: None,
+                        completed_by_username: None,
+                        completed_at: None,
+                        assigned_to: None,
+                        assigned_to_username: None,
+                        position: i64::MAX,
+                        version: 0,
+                        created_at: OffsetDateTime::UNIX_EPOCH,
+                        updated_at: OffsetDateTime::UNIX_EPOCH,
+                    });
+                    next_id -= 1;
+                }
+                QueuedChange::Add { .. } => {}
+            }
+        }
+        items
+    }
+}
+
+/// Whether a server function failed because the server couldn't be reached,
+/// rather than because it refused the change
+pub fn is_network_error(error: &ServerFnError) -> bool {
+    matches!(error, ServerFnError::Request(_))
+}
+
+/// Result of sending the queue
+#[derive(Debug, Default)]
+pub struct ReplayOutcome {
+    /// Changes still waiting because the connection dropped again
+    pub remaining: OfflineQueue,
+    /// Notices for changes the server refused, e.g. for a deleted item
+    pub dropped: Vec<String>,
+}
+
+/// Send queued changes one at a time, oldest first
+///
+/// Stops at the first network error and keeps that change and everything
+/// after it. A change the server refuses can't succeed later, so it is
+/// dropped with a notice.
+pub async fn replay<F, Fut>(queue: OfflineQueue, mut send: F) -> ReplayOutcome
+where
+    F: FnMut(QueuedChange) -> Fut,
+    Fut: Future<Output = Result<(), ServerFnError>>,
+{
+    let mut outcome = ReplayOutcome::default();
+    let mut pending = VecDeque::from(queue.changes);
+    while let Some(change) = pending.pop_front() {
+        let description = change.describe();
+        match send(change.clone()).await {
+            Ok(()) => {}
+            Err(e) if is_network_error(&e) => {
+                pending.push_front(change);
+                outcome.remaining.changes = pending.into();
+                break;
+            }
+            Err(e) => outcome
+                .dropped
+                .push(format!("{} failed and was dropped: {}", description, e)),
+        }
+    }
+    outcome
+}
+
+/// Send one queued change to the server
+pub async fn send_change(change: QueuedChange) -> Result<(), ServerFnError> {
+    match change {
+        QueuedChange::Toggle {
+            item_id,
+            completed,
+            version,
+        } => toggle_shopping_list_item(item_id, completed, version)
+            .await
+            .map(|_| ()),
+        QueuedChange::Add {
+            list_id,
+            name,
+            quantity_value,
+            quantity_unit,
+            category,
+        } => add_shopping_list_item(list_id, name, quantity_value, quantity_unit, category)
+            .await
+            .map(|_| ()),
+    }
+}
+
+/// Queue saved in this browser, or an empty one
+#[cfg(feature = "hydrate")]
+pub fn load() -> OfflineQueue {
+    leptos::prelude::window()
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+        .map(|json| OfflineQueue::from_json(&json))
+        .unwrap_or_default()
+}
+
+/// Save the queue in this browser
+#[cfg(feature = "hydrate")]
+pub fn save(queue: &OfflineQueue) {
+    let Some(storage) = leptos::prelude::window().local_storage().ok().flatten() else {
+        return;
+    };
+    let _ = if queue.is_empty() {
+        storage.remove_item(STORAGE_KEY)
+    } else {
+        storage.set_item(STORAGE_KEY, &queue.to_json())
+    };
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    fn toggle(item_id: i64, completed: bool) -> QueuedChange {
+        QueuedChange::Toggle {
+            item_id,
+            completed,
+            version: 1,
+        }
+    }
+
+    fn add(name: &str) -> QueuedChange {
+        QueuedChange::Add {
+            list_id: 1,
+            name: name.to_string(),
+            quantity_value: Some("1,5".to_string()),
+            quantity_unit: Some("kg".to_string()),
+            category: None,
+        }
+    }
+
+    fn item(id: i64) -> ShoppingListItem {
+        ShoppingListItem {
+            id,
+            shopping_list_id: 1,
+            name: format!("Item {}", id),
+            quantity_value: None,
+            quantity_unit: None,
+            category: None,
+            is_completed: false,
+            completed_by: None,
+            completed_by_username: None,
+            completed_at: None,
+            assigned_to: None,
+            assigned_to_username: None,
+            position: id,
+            version: 1,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip_keeps_order() {
+        let mut queue = OfflineQueue::default();
+        queue.push(add("Milk"));
+        queue.push(toggle(3, true));
+        queue.push(add("Eggs"));
+
+        let json = queue.to_json();
+        assert!(json.contains("\"type\":\"toggle\""));
+        assert_eq!(OfflineQueue::from_json(&json), queue);
+        assert_eq!(OfflineQueue::from_json("not json"), OfflineQueue::default());
+    }
+
+    #[test]
+    fn test_toggling_back_cancels_out() {
+        let mut queue = OfflineQueue::default();
+        queue.push(toggle(3, true));
+        queue.push(add("Milk"));
+        queue.push(toggle(3, false));
+        assert_eq!(queue.changes(), &[add("Milk")]);
+    }
+
+    #[test]
+    fn test_apply_shows_queued_changes() {
+        let mut queue = OfflineQueue::default();
+        queue.push(toggle(2, true));
+        queue.push(add("Flour"));
+        queue.push(QueuedChange::Add {
+            list_id: 9,
+            name: "Elsewhere".to_string(),
+            quantity_value: None,
+            quantity_unit: None,
+            category: None,
+        });
+
+        let items = queue.apply(1, vec![item(1), item(2)]);
+        let shown: Vec<_> = items
+            .iter()
+            .map(|item| (item.id, item.name.as_str(), item.is_completed))
+            .collect();
+        assert_eq!(
+            shown,
+            vec![
+                (1, "Item 1", false),
+                (2, "Item 2", true),
+                (-1, "Flour", false)
+            ]
+        );
+        assert_eq!(items[2].quantity_label().as_deref(), Some("1.5 kg"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_sends_in_order_and_drops_conflicts() {
+        let mut queue = OfflineQueue::default();
+        queue.push(add("Milk"));
+        queue.push(toggle(3, true));
+        queue.push(add("Eggs"));
+
+        let mut sent = Vec::new();
+        let outcome = replay(queue, |change| {
+            sent.push(change.describe());
+            let result = match change {
+                QueuedChange::Toggle { .. } => Err(ServerFnError::new("Item not found")),
+                QueuedChange::Add { .. } => Ok(()),
+            };
+            async move { result }
+        })
+        .await;
+
+        assert_eq!(
+            sent,
+            vec!["Adding \"Milk\"", "Ticking off an item", "Adding \"Eggs\""]
+        );
+        assert!(outcome.remaining.is_empty());
+        assert_eq!(outcome.dropped.len(), 1);
+        assert!(outcome.dropped[0].starts_with("Ticking off an item failed and was dropped"));
+        assert!(outcome.dropped[0].contains("Item not found"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_stops_while_offline() {
+        let mut queue = OfflineQueue::default();
+        queue.push(add("Milk"));
+        queue.push(add("Eggs"));
+        queue.push(add("Flour"));
+
+        let mut calls = 0;
+        let outcome = replay(queue, |_| {
+            calls += 1;
+            let result = if calls == 2 {
+                Err(ServerFnError::Request("offline".to_string()))
+            } else {
+                Ok(())
+            };
+            async move { result }
+        })
+        .await;
+
+        assert_eq!(calls, 2);
+        assert_eq!(outcome.remaining.changes(), &[add("Eggs"), add("Flour")]);
+        assert!(outcome.dropped.is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+//! Shopping list templates: a snapshot of a list's items that the group can
+//! start new lists from
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use sqlx::{SqliteConnection, SqlitePool};
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::auth::utils::get_user_from_session;
+#[cfg(feature = "ssr")]
+use crate::features::groups::utils::is_group_admin;
+use crate::features::shopping_lists::models::ShoppingListTemplate;
+#[cfg(feature = "ssr")]
+use crate::features::shopping_lists::utils::*;
+#[cfg(feature = "ssr")]
+use crate::validation::{rules::MAX_NAME_LENGTH, validate_name};
+
+/// Copy a list's items into a new template, returning the template id
+#[cfg(feature = "ssr")]
+pub async fn snapshot_list_as_template(
+    conn: &mut SqliteConnection,
+    list_id: i64,
+    user_id: i64,
+    name: &str,
+) -> Result<i64, sqlx::Error> {
+    let group_id = sqlx::query_scalar!("SELECT group_id FROM shopping_lists WHERE id = ?", list_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    let template_id = sqlx::query!(
+        "INSERT INTO shopping_list_templates (group_id, created_by, name) VALUES (?, ?, ?)",
+        group_id,
+        user_id,
+        name
+    )
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO template_items
+        (template_id, name, quantity_value, quantity_unit, category, position)
+        SELECT ?, name, quantity_value, quantity_unit, category,
+            ROW_NUMBER() OVER (ORDER BY position ASC, created_at ASC) - 1
+        FROM shopping_list_items
+        WHERE shopping_list_id = ?
+        "#,
+        template_id,
+        list_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(template_id)
+}
+
+/// Create a list holding a template's items, none of them completed, and
+/// return the new list id
+#[cfg(feature = "ssr")]
+pub async fn instantiate_template(
+    conn: &mut SqliteConnection,
+    template_id: i64,
+    user_id: i64,
+    name: &str,
+) -> Result<i64, sqlx::Error> {
+    let group_id = sqlx::query_scalar!(
+        "SELECT group_id FROM shopping_list_templates WHERE id = ?",
+        template_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let list_id = sqlx::query!(
+        "INSERT INTO shopping_lists (group_id, created_by, name) VALUES (?, ?, ?)",
+        group_id,
+        user_id,
+        name
+    )
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO shopping_list_items
+        (shopping_list_id, name, quantity_value, quantity_unit, category, position)
+        SELECT ?, name, quantity_value, quantity_unit, category, position
+        FROM template_items
+        WHERE template_id = ?
+        "#,
+        list_id,
+        template_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(list_id)
+}
+
+/// Templates of a group, by name
+#[server(GetShoppingListTemplates)]
+pub async fn get_shopping_list_templates(
+    group_id: i64,
+) -> Result<Vec<ShoppingListTemplate>, ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    verify_group_membership(&pool, user.id, group_id).await?;
+    let is_admin = is_group_admin(&pool, group_id, user.id).await?;
+
+    let templates = sqlx::query!(
+        r#"
+        SELECT
+            t.id as "id!",
+            t.group_id,
+            t.name,
+            t.created_by,
+            u.username as creator_username,
+            CAST(COUNT(ti.id) AS INTEGER) as "item_count!: i64"
+        FROM shopping_list_templates t
+        INNER JOIN users u ON t.created_by = u.id
+        LEFT JOIN template_items ti ON ti.template_id = t.id
+        WHERE t.group_id = ?
+        GROUP BY t.id
+        ORDER BY t.name COLLATE NOCASE ASC
+        "#,
+        group_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(templates
+        .into_iter()
+        .map(|row| ShoppingListTemplate {
+            id: row.id,
+            group_id: row.group_id,
+            name: row.name,
+            created_by: row.created_by,
+            creator_username: row.creator_username,
+            item_count: row.item_count,
+            can_delete: is_admin || row.created_by == user.id,
+        })
+        .collect())
+}
+
+/// Save a list's current items as a template for its group
+#[server(SaveListAsTemplate)]
+pub async fn save_list_as_template(
+    list_id: i64,
+    template_name: String,
+) -> Result<i64, ServerFnError> {
+    let template_name = validate_name(&template_name, 1, MAX_NAME_LENGTH, "Name")?;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    verify_list_edit_access(&pool, user.id, list_id).await?;
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    let template_id = snapshot_list_as_template(&mut tx, list_id, user.id, &template_name)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(template_id)
+}
+
+/// Start a new list from a template, returning the new list id
+#[server(CreateListFromTemplate)]
+pub async fn create_list_from_template(
+    group_id: i64,
+    template_id: i64,
+    name: String,
+) -> Result<i64, ServerFnError> {
+    let name = validate_name(&name, 1, MAX_NAME_LENGTH, "Name")?;
+
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    verify_group_edit_access(&pool, user.id, group_id).await?;
+
+    let template_group_id = sqlx::query_scalar!(
+        "SELECT group_id FROM shopping_list_templates WHERE id = ?",
+        template_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    if template_group_id != Some(group_id) {
+        return Err(ServerFnError::new("Template not found".to_string()));
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    let list_id = instantiate_template(&mut tx, template_id, user.id, &name)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    tx.commit()
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(list_id)
+}
+
+/// Delete a template; only its creator or a group admin may
+#[server(DeleteShoppingListTemplate)]
+pub async fn delete_shopping_list_template(template_id: i64) -> Result<(), ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let template = sqlx::query!(
+        "SELECT group_id, created_by FROM shopping_list_templates WHERE id = ?",
+        template_id
+    )
+    .fetch_optional(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .ok_or_else(|| ServerFnError::new("Template not found"))?;
+
+    verify_group_edit_access(&pool, user.id, template.group_id).await?;
+    if template.created_by != user.id && !is_group_admin(&pool, template.group_id, user.id).await? {
+        return Err(ServerFnError::new(
+            "Unauthorized: Only the template's creator or a group admin can delete it".to_string(),
+        ));
+    }
+
+    sqlx::query!(
+        "DELETE FROM shopping_list_templates WHERE id = ?",
+        template_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO shopping_lists (group_id, created_by, name) VALUES (1, 1, 'Week 1')",
+            "INSERT INTO shopping_list_items (shopping_list_id, name, quantity_value, quantity_unit, category, position, is_completed) VALUES (1, 'Milk', '2', 'l', 'Dairy', 5, 1)",
+            "INSERT INTO shopping_list_items (shopping_list_id, name, position) VALUES (1, 'Bread', 2)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_template_round_trip() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        let template_id = snapshot_list_as_template(&mut conn, 1, 1, "Weekly")
+            .await
+            .unwrap();
+        let list_id = instantiate_template(&mut conn, template_id, 1, "Week 2")
+            .await
+            .unwrap();
+        drop(conn);
+
+        let items: Vec<(String, Option<String>, Option<String>, Option<String>, i64, i64)> =
+            sqlx::query_as(
+                "SELECT name, quantity_value, quantity_unit, category, position, is_completed FROM shopping_list_items WHERE shopping_list_id = ? ORDER BY position",
+            )
+            .bind(list_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            items,
+            vec![
+                ("Bread".to_string(), None, None, None, 0, 0),
+                (
+                    "Milk".to_string(),
+                    Some("2".to_string()),
+                    Some("l".to_string()),
+                    Some("Dairy".to_string()),
+                    1,
+                    0
+                ),
+            ]
+        );
+
+        // Later changes to the source list don't touch the template
+        sqlx::query("DELETE FROM shopping_lists WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let template_items: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM template_items")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(template_items, 2);
+    }
+}
@@ -1,13 +1,27 @@
+use std::collections::HashMap;
+
 use leptos::prelude::ServerFnError;
+use rust_decimal::Decimal;
 #[cfg(feature = "ssr")]
 use sqlx::SqlitePool;
 
+use super::models::{QuantityUnit, ShoppingListItem, ShoppingListItemDraft};
 #[cfg(feature = "ssr")]
-pub async fn verify_list_access(
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::groups::utils::ensure_group_writable;
+
+/// Error message returned when an item was modified concurrently
+pub const CONFLICT_ERROR: &str =
+    "Conflict: this item was changed by someone else. Please refresh and try again.";
+
+/// Whether a user belongs to the group that owns a list
+#[cfg(feature = "ssr")]
+pub async fn is_list_member(
     pool: &SqlitePool,
     user_id: i64,
     list_id: i64,
-) -> Result<(), ServerFnError> {
+) -> Result<bool, ServerFnError> {
     let is_member = sqlx::query_scalar!(
         r#"
         SELECT EXISTS(
@@ -21,9 +35,18 @@ pub async fn verify_list_access(
     )
     .fetch_one(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
-    if is_member == 0 {
+    Ok(is_member != 0)
+}
+
+#[cfg(feature = "ssr")]
+pub async fn verify_list_access(
+    pool: &SqlitePool,
+    user_id: i64,
+    list_id: i64,
+) -> Result<(), ServerFnError> {
+    if !is_list_member(pool, user_id, list_id).await? {
         return Err(ServerFnError::new(
             "Unauthorized: Not a member of this list's group".to_string(),
         ));
@@ -32,6 +55,74 @@ pub async fn verify_list_access(
     Ok(())
 }
 
+/// Like [`verify_list_access`], but also turns away viewers and lists of
+/// archived groups
+#[cfg(feature = "ssr")]
+pub async fn verify_list_edit_access(
+    pool: &SqlitePool,
+    user_id: i64,
+    list_id: i64,
+) -> Result<(), ServerFnError> {
+    verify_list_access(pool, user_id, list_id).await?;
+
+    let group_id = sqlx::query_scalar!("SELECT group_id FROM shopping_lists WHERE id = ?", list_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+    ensure_group_writable(pool, group_id, user_id).await?;
+
+    Ok(())
+}
+
+/// Distinct item categories used across the lists of a list's group,
+/// alphabetically
+///
+/// Categories differing only in case are listed once.
+#[cfg(feature = "ssr")]
+pub async fn group_item_categories(
+    pool: &SqlitePool,
+    list_id: i64,
+) -> Result<Vec<String>, ServerFnError> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT MIN(i.category) as "category!: String"
+        FROM shopping_list_items i
+        INNER JOIN shopping_lists sl ON sl.id = i.shopping_list_id
+        WHERE sl.group_id = (SELECT group_id FROM shopping_lists WHERE id = ?)
+          AND TRIM(COALESCE(i.category, '')) != ''
+        GROUP BY LOWER(i.category)
+        ORDER BY LOWER(i.category)
+        "#,
+        list_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))
+}
+
+/// Reject changes to the items of an archived list
+#[cfg(feature = "ssr")]
+pub async fn verify_list_not_archived(
+    pool: &SqlitePool,
+    list_id: i64,
+) -> Result<(), ServerFnError> {
+    let is_archived = sqlx::query_scalar!(
+        "SELECT is_archived FROM shopping_lists WHERE id = ?",
+        list_id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    if is_archived != 0 {
+        return Err(ServerFnError::new(
+            "This list is archived. Unarchive it to add items.".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "ssr")]
 pub async fn verify_list_creator(
     pool: &SqlitePool,
@@ -50,7 +141,7 @@ pub async fn verify_list_creator(
     )
     .fetch_one(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     if is_creator == 0 {
         return Err(ServerFnError::new(
@@ -79,7 +170,7 @@ pub async fn verify_group_membership(
     )
     .fetch_one(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     if is_member == 0 {
         return Err(ServerFnError::new(
@@ -90,6 +181,20 @@ pub async fn verify_group_membership(
     Ok(())
 }
 
+/// Like [`verify_group_membership`], but also turns away viewers and
+/// archived groups
+#[cfg(feature = "ssr")]
+pub async fn verify_group_edit_access(
+    pool: &SqlitePool,
+    user_id: i64,
+    group_id: i64,
+) -> Result<(), ServerFnError> {
+    verify_group_membership(pool, user_id, group_id).await?;
+    ensure_group_writable(pool, group_id, user_id).await?;
+
+    Ok(())
+}
+
 #[cfg(feature = "ssr")]
 pub async fn log_activity(
     pool: &SqlitePool,
@@ -111,19 +216,537 @@ pub async fn log_activity(
     )
     .execute(pool)
     .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
 
     Ok(())
 }
 
-pub fn validate_name(name: &str) -> Result<(), ServerFnError> {
-    if name.trim().is_empty() {
-        return Err(ServerFnError::new("Name cannot be empty".to_string()));
+/// Maximum number of items accepted in a single batch add
+pub const MAX_BATCH_ITEMS: usize = 200;
+
+/// Longest custom unit accepted
+pub const MAX_UNIT_LENGTH: usize = 20;
+
+/// Canonical form of a unit
+///
+/// Lowercases and folds plurals of the common units ("Cans" becomes "can").
+/// Count words ("x", "pc", "pcs") and blank input mean no unit.
+pub fn normalize_quantity_unit(unit: &str) -> Option<String> {
+    let unit = unit.trim().to_lowercase();
+    match unit.as_str() {
+        "" | "x" | "pc" | "pcs" => None,
+        "packs" => Some("pack".to_string()),
+        "cans" => Some("can".to_string()),
+        "bottles" => Some("bottle".to_string()),
+        _ => Some(unit),
     }
-    if name.len() > 255 {
-        return Err(ServerFnError::new(
-            "Name must be 255 characters or less".to_string(),
-        ));
+}
+
+/// Whether a unit is one of the common ones or a count word
+fn is_known_unit(unit: &str) -> bool {
+    normalize_quantity_unit(unit)
+        .is_none_or(|u| QuantityUnit::from_abbreviation(&u) != QuantityUnit::Other)
+}
+
+/// Parse an amount, accepting a comma as decimal separator
+fn parse_amount(amount: &str) -> Option<Decimal> {
+    amount.trim().replace(',', ".").parse::<Decimal>().ok()
+}
+
+/// Validate a quantity entered in a form
+///
+/// An empty amount means no quantity. A unit needs an amount, and amounts
+/// must be positive.
+pub fn parse_quantity(
+    value: Option<&str>,
+    unit: Option<&str>,
+) -> Result<(Option<Decimal>, Option<String>), ServerFnError> {
+    let unit = unit.and_then(normalize_quantity_unit);
+    if unit
+        .as_ref()
+        .is_some_and(|u| u.chars().count() > MAX_UNIT_LENGTH)
+    {
+        return Err(ServerFnError::new(format!(
+            "Unit must be {} characters or less",
+            MAX_UNIT_LENGTH
+        )));
+    }
+
+    let value = match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => {
+            let amount = parse_amount(value)
+                .ok_or_else(|| ServerFnError::new("Quantity must be a number".to_string()))?;
+            if amount <= Decimal::ZERO {
+                return Err(ServerFnError::new(
+                    "Quantity must be greater than zero".to_string(),
+                ));
+            }
+            Some(amount.normalize())
+        }
+        None if unit.is_some() => {
+            return Err(ServerFnError::new(
+                "Enter an amount for the unit".to_string(),
+            ));
+        }
+        None => None,
+    };
+
+    Ok((value, unit))
+}
+
+/// Parse pasted multi-line text into item drafts
+///
+/// Each non-empty line becomes one item. List markers ("-", "*", "•") are
+/// stripped, and a leading number, optionally followed by a common unit, is
+/// taken as the quantity ("2kg potatoes", "3 eggs", "2 kg potatoes"). Returns
+/// the 1-based line number alongside each draft so errors can point at the
+/// right line.
+pub fn parse_item_lines(text: &str) -> Vec<(usize, ShoppingListItemDraft)> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.trim().trim_start_matches(['-', '*', '•']).trim_start();
+            if line.is_empty() {
+                return None;
+            }
+
+            let mut tokens = line.split_whitespace().peekable();
+            let mut quantity_value = None;
+            let mut quantity_unit = None;
+            if let Some(&first) = tokens.peek() {
+                let split = first
+                    .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+                    .unwrap_or(first.len());
+                let (amount, suffix) = first.split_at(split);
+                if let Some(amount) = parse_amount(amount)
+                    && (suffix.is_empty() || is_known_unit(suffix))
+                {
+                    tokens.next();
+                    quantity_value = Some(amount.normalize());
+                    quantity_unit = normalize_quantity_unit(suffix);
+                    if suffix.is_empty()
+                        && let Some(unit) = tokens.peek()
+                        && is_known_unit(unit)
+                    {
+                        quantity_unit = normalize_quantity_unit(unit);
+                        tokens.next();
+                    }
+                }
+            }
+
+            Some((
+                index + 1,
+                ShoppingListItemDraft {
+                    name: tokens.collect::<Vec<_>>().join(" "),
+                    quantity_value,
+                    quantity_unit,
+                    category: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Item ids after moving one item to a new index
+///
+/// `ids` is the list's current order. The index is clamped to the list, and
+/// an unknown item leaves the order unchanged.
+pub fn move_item(mut ids: Vec<i64>, item_id: i64, new_position: usize) -> Vec<i64> {
+    let Some(current) = ids.iter().position(|id| *id == item_id) else {
+        return ids;
+    };
+    ids.remove(current);
+    let new_position = new_position.min(ids.len());
+    ids.insert(new_position, item_id);
+    ids
+}
+
+/// Items are duplicates when their names match ignoring case and their units
+/// match exactly
+fn merge_key(item: &ShoppingListItem) -> (String, Option<String>) {
+    (item.name.trim().to_lowercase(), item.quantity_unit.clone())
+}
+
+/// Duplicate open items and the earlier item each one can be merged into
+///
+/// Maps the id of every open item that repeats an earlier open item (by
+/// position) to that earlier item's id. Completed items are left alone.
+pub fn merge_targets(items: &[ShoppingListItem]) -> HashMap<i64, i64> {
+    let mut open: Vec<&ShoppingListItem> = items.iter().filter(|i| !i.is_completed).collect();
+    open.sort_by_key(|item| (item.position, item.id));
+
+    let mut first_by_key: HashMap<(String, Option<String>), i64> = HashMap::new();
+    let mut targets = HashMap::new();
+    for item in open {
+        match first_by_key.get(&merge_key(item)) {
+            Some(&target) => {
+                targets.insert(item.id, target);
+            }
+            None => {
+                first_by_key.insert(merge_key(item), item.id);
+            }
+        }
+    }
+    targets
+}
+
+/// Combined amount of two merged items; an item without an amount counts as one
+pub fn merged_quantity(a: Option<Decimal>, b: Option<Decimal>) -> Decimal {
+    a.unwrap_or(Decimal::ONE) + b.unwrap_or(Decimal::ONE)
+}
+
+/// Maximum number of categories stored in a list's category order
+pub const MAX_ORDERED_CATEGORIES: usize = 100;
+
+/// Trim, drop empty and duplicate entries from a submitted category order
+pub fn normalize_category_order(categories: &[String]) -> Result<Vec<String>, ServerFnError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(categories.len());
+    for category in categories {
+        let category = category.trim();
+        if category.is_empty() || normalized.iter().any(|c| c == category) {
+            continue;
+        }
+        if category.len() > 255 {
+            return Err(ServerFnError::new(
+                "Category must be 255 characters or less".to_string(),
+            ));
+        }
+        normalized.push(category.to_string());
+    }
+    if normalized.len() > MAX_ORDERED_CATEGORIES {
+        return Err(ServerFnError::new(format!(
+            "At most {} categories can be ordered",
+            MAX_ORDERED_CATEGORIES
+        )));
+    }
+    Ok(normalized)
+}
+
+/// Group items under their category for display
+///
+/// Categories listed in `order` come first in that order, any other categories
+/// follow alphabetically and uncategorized items form a trailing `None` group.
+/// Within a group open items come before completed ones, each by position.
+pub fn group_items_by_category(
+    items: Vec<ShoppingListItem>,
+    order: &[String],
+) -> Vec<(Option<String>, Vec<ShoppingListItem>)> {
+    let mut groups: Vec<(Option<String>, Vec<ShoppingListItem>)> = Vec::new();
+    for item in items {
+        let category = item
+            .category
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+        match groups.iter_mut().find(|(c, _)| *c == category) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((category, vec![item])),
+        }
+    }
+
+    groups.sort_by(|(a, _), (b, _)| {
+        let rank = |category: &Option<String>| match category {
+            Some(name) => match order.iter().position(|c| c == name) {
+                Some(index) => (0, index, name.to_lowercase()),
+                None => (1, 0, name.to_lowercase()),
+            },
+            None => (2, 0, String::new()),
+        };
+        rank(a).cmp(&rank(b))
+    });
+
+    for (_, items) in &mut groups {
+        items.sort_by_key(|item| (item.is_completed, item.position));
+    }
+
+    groups
+}
+
+/// `localStorage` key a list's collapsed categories are kept under
+pub fn collapsed_categories_key(list_id: i64) -> String {
+    format!("splitify.shopping_list_collapsed.{}", list_id)
+}
+
+/// Categories collapsed in a list in this browser, "" for uncategorized
+#[cfg(feature = "hydrate")]
+pub fn load_collapsed_categories(list_id: i64) -> std::collections::HashSet<String> {
+    leptos::prelude::window()
+        .local_storage()
+        .ok()
+        .flatten()
+        .and_then(|storage| {
+            storage
+                .get_item(&collapsed_categories_key(list_id))
+                .ok()
+                .flatten()
+        })
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Remember the categories collapsed in a list in this browser
+#[cfg(feature = "hydrate")]
+pub fn save_collapsed_categories(list_id: i64, collapsed: &std::collections::HashSet<String>) {
+    let Some(storage) = leptos::prelude::window().local_storage().ok().flatten() else {
+        return;
+    };
+    let key = collapsed_categories_key(list_id);
+    let _ = if collapsed.is_empty() {
+        storage.remove_item(&key)
+    } else {
+        storage.set_item(&key, &serde_json::to_string(collapsed).unwrap_or_default())
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::rules::{MAX_NAME_LENGTH, check_name};
+
+    fn draft(name: &str, value: Option<i64>, unit: Option<&str>) -> ShoppingListItemDraft {
+        ShoppingListItemDraft {
+            name: name.to_string(),
+            quantity_value: value.map(Decimal::from),
+            quantity_unit: unit.map(str::to_string),
+            category: None,
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    #[tokio::test]
+    async fn test_group_item_categories() {
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1), ('Trip', 1)",
+            "INSERT INTO shopping_lists (group_id, name, created_by) VALUES (1, 'Weekly', 1), (1, 'Party', 1), (2, 'Camping', 1)",
+            "INSERT INTO shopping_list_items (shopping_list_id, name, category) VALUES (1, 'Milk', 'Dairy'), (1, 'Bread', NULL), (2, 'Cheese', 'dairy'), (2, 'Chips', 'Snacks'), (2, 'Cups', ' '), (3, 'Tent', 'Gear')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        // Any list of the group gives the group's categories
+        assert_eq!(
+            group_item_categories(&pool, 1).await.unwrap(),
+            vec!["Dairy".to_string(), "Snacks".to_string()]
+        );
+        assert_eq!(
+            group_item_categories(&pool, 3).await.unwrap(),
+            vec!["Gear".to_string()]
+        );
+    }
+
+    #[cfg(feature = "ssr")]
+    #[tokio::test]
+    async fn test_viewers_only_read_lists() {
+        use sqlx::sqlite::SqlitePoolOptions;
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id, role) VALUES (1, 1, 'admin'), (1, 2, 'viewer')",
+            "INSERT INTO shopping_lists (group_id, name, created_by) VALUES (1, 'Weekly', 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        verify_list_edit_access(&pool, 1, 1).await.unwrap();
+        verify_group_edit_access(&pool, 1, 1).await.unwrap();
+
+        verify_list_access(&pool, 2, 1).await.unwrap();
+        verify_group_membership(&pool, 2, 1).await.unwrap();
+        for err in [
+            verify_list_edit_access(&pool, 2, 1).await.unwrap_err(),
+            verify_group_edit_access(&pool, 2, 1).await.unwrap_err(),
+        ] {
+            assert!(err.to_string().contains("Viewers"));
+        }
+
+        // Outsiders still get the membership error
+        let err = verify_list_edit_access(&pool, 3, 1).await.unwrap_err();
+        assert!(err.to_string().contains("Not a member"));
+    }
+
+    #[test]
+    fn test_parse_item_lines() {
+        let parsed = parse_item_lines(
+            "2kg potatoes\n\n- milk\n3 eggs\n2 kg flour\n  * 1,5 l juice\n4 Cans beans\n3rd aisle",
+        );
+
+        let juice = ShoppingListItemDraft {
+            quantity_value: Some(Decimal::new(15, 1)),
+            ..draft("juice", None, Some("l"))
+        };
+        assert_eq!(
+            parsed,
+            vec![
+                (1, draft("potatoes", Some(2), Some("kg"))),
+                (3, draft("milk", None, None)),
+                (4, draft("eggs", Some(3), None)),
+                (5, draft("flour", Some(2), Some("kg"))),
+                (6, juice),
+                (7, draft("beans", Some(4), Some("can"))),
+                (8, draft("3rd aisle", None, None)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity(None, None).unwrap(), (None, None));
+        assert_eq!(
+            parse_quantity(Some(" 1,50 "), Some("KG")).unwrap(),
+            (Some(Decimal::new(15, 1)), Some("kg".to_string()))
+        );
+        assert_eq!(
+            parse_quantity(Some("6"), Some("pcs")).unwrap(),
+            (Some(Decimal::from(6)), None)
+        );
+        assert_eq!(
+            parse_quantity(Some("2"), Some("bunches")).unwrap(),
+            (Some(Decimal::from(2)), Some("bunches".to_string()))
+        );
+        assert!(parse_quantity(Some("two"), None).is_err());
+        assert!(parse_quantity(Some("0"), None).is_err());
+        assert!(parse_quantity(Some(""), Some("kg")).is_err());
+        assert!(parse_quantity(Some("1"), Some(&"u".repeat(MAX_UNIT_LENGTH + 1))).is_err());
+    }
+
+    fn item(
+        id: i64,
+        category: Option<&str>,
+        is_completed: bool,
+        position: i64,
+    ) -> ShoppingListItem {
+        let now = time::OffsetDateTime::UNIX_EPOCH;
+        ShoppingListItem {
+            id,
+            shopping_list_id: 1,
+            name: format!("item {}", id),
+            quantity_value: None,
+            quantity_unit: None,
+            category: category.map(str::to_string),
+            is_completed,
+            completed_by: None,
+            completed_by_username: None,
+            completed_at: None,
+            assigned_to: None,
+            assigned_to_username: None,
+            position,
+            version: 1,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_group_items_by_category() {
+        let items = vec![
+            item(1, Some("Dairy"), true, 0),
+            item(2, None, false, 1),
+            item(3, Some("Produce"), false, 2),
+            item(4, Some("Dairy"), false, 3),
+            item(5, Some("bakery"), false, 4),
+            item(6, Some("  "), false, 5),
+            item(7, Some("Frozen"), false, 6),
+        ];
+        let order = vec!["Produce".to_string(), "Dairy".to_string()];
+
+        let grouped: Vec<(Option<String>, Vec<i64>)> = group_items_by_category(items, &order)
+            .into_iter()
+            .map(|(category, items)| (category, items.iter().map(|i| i.id).collect()))
+            .collect();
+
+        assert_eq!(
+            grouped,
+            vec![
+                (Some("Produce".to_string()), vec![3]),
+                (Some("Dairy".to_string()), vec![4, 1]),
+                (Some("bakery".to_string()), vec![5]),
+                (Some("Frozen".to_string()), vec![7]),
+                (None, vec![2, 6]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_normalize_category_order() {
+        let order = normalize_category_order(&[
+            " Produce ".to_string(),
+            "".to_string(),
+            "Dairy".to_string(),
+            "Produce".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(order, vec!["Produce".to_string(), "Dairy".to_string()]);
+
+        assert!(normalize_category_order(&["x".repeat(256)]).is_err());
+    }
+
+    #[test]
+    fn test_merge_targets() {
+        let named =
+            |id: i64, name: &str, unit: Option<&str>, is_completed: bool| ShoppingListItem {
+                name: name.to_string(),
+                quantity_unit: unit.map(str::to_string),
+                ..item(id, None, is_completed, id)
+            };
+        let items = vec![
+            named(1, "Milk", Some("l"), false),
+            named(2, "milk ", Some("l"), false),
+            named(3, "milk", None, false),
+            named(4, "Milk", Some("l"), true),
+            named(5, "MILK", Some("l"), false),
+            named(6, "eggs", None, false),
+        ];
+
+        let targets = merge_targets(&items);
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets.get(&2), Some(&1));
+        assert_eq!(targets.get(&5), Some(&1));
+    }
+
+    #[test]
+    fn test_move_item() {
+        assert_eq!(move_item(vec![1, 2, 3, 4], 3, 0), vec![3, 1, 2, 4]);
+        assert_eq!(move_item(vec![1, 2, 3, 4], 1, 2), vec![2, 3, 1, 4]);
+        assert_eq!(move_item(vec![1, 2, 3, 4], 2, 99), vec![1, 3, 4, 2]);
+        assert_eq!(move_item(vec![1, 2, 3], 7, 0), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_merged_quantity() {
+        assert_eq!(
+            merged_quantity(Some(Decimal::new(15, 1)), Some(Decimal::from(2))),
+            Decimal::new(35, 1)
+        );
+        assert_eq!(
+            merged_quantity(None, Some(Decimal::from(2))),
+            Decimal::from(3)
+        );
+        assert_eq!(merged_quantity(None, None), Decimal::from(2));
+    }
+
+    #[test]
+    fn test_parse_quantity_only_line_has_empty_name() {
+        let parsed = parse_item_lines("12");
+        assert_eq!(parsed, vec![(1, draft("", Some(12), None))]);
+        assert!(check_name(&parsed[0].1.name, 1, MAX_NAME_LENGTH, "Name").is_err());
     }
-    Ok(())
 }
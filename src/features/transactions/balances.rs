@@ -0,0 +1,836 @@
+//! Cached pairwise balances of a group
+//!
+//! Every handler that changes a shared debt, its shares or a payment takes
+//! the old contribution out of `group_balances` and adds the new one inside
+//! its own database transaction, so reading a group's balances is a single
+//! query. [`rebuild_group_balances`] recomputes the cache from scratch.
+
+#[cfg(feature = "ssr")]
+use std::{collections::HashMap, str::FromStr};
+
+#[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
+use sqlx::{SqliteConnection, SqlitePool};
+
+#[cfg(feature = "ssr")]
+use crate::features::{
+    shared_debts::{
+        models::{DebtStatus, RoundingStrategy},
+        utils::member_share_amounts,
+    },
+    transactions::models::ConfirmationStatus,
+};
+
+/// What members owe each other, keyed by (lower user id, higher user id)
+///
+/// A positive amount means the first user owes the second, a negative one
+/// that the second owes the first.
+#[cfg(feature = "ssr")]
+pub type PairBalances = HashMap<(i64, i64), Decimal>;
+
+/// Record that `debtor` owes `creditor` another `amount`
+#[cfg(feature = "ssr")]
+fn add_owed(pairs: &mut PairBalances, debtor: i64, creditor: i64, amount: Decimal) {
+    if debtor < creditor {
+        *pairs.entry((debtor, creditor)).or_default() += amount;
+    } else {
+        *pairs.entry((creditor, debtor)).or_default() -= amount;
+    }
+}
+
+/// A shared debt as far as balances are concerned
+#[cfg(feature = "ssr")]
+struct DebtSplit<'a> {
+    payer_id: i64,
+    creator_id: i64,
+    amount: Decimal,
+    participants: &'a [(i64, Option<String>)],
+    settled: &'a [i64],
+}
+
+/// Add a shared debt: every participant except the payer owes the payer
+/// their share, unless it was settled outside of the app
+///
+/// Equal splits are rounded to the cent with the group's `strategy`, so the
+/// shares charged are the ones shown on the debt.
+#[cfg(feature = "ssr")]
+fn add_shared_debt(pairs: &mut PairBalances, debt: DebtSplit, strategy: RoundingStrategy) {
+    let shares = member_share_amounts(debt.amount, debt.participants, strategy, debt.creator_id);
+    for (user_id, share) in shares {
+        if user_id == debt.payer_id || debt.settled.contains(&user_id) {
+            continue;
+        }
+        add_owed(pairs, user_id, debt.payer_id, share);
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn parse_amount(amount: &str) -> Result<Decimal, sqlx::Error> {
+    Decimal::from_str(amount).map_err(|e| sqlx::Error::Decode(Box::new(e)))
+}
+
+#[cfg(feature = "ssr")]
+fn parse_strategy(strategy: &str) -> Result<RoundingStrategy, sqlx::Error> {
+    strategy
+        .parse()
+        .map_err(|e: String| sqlx::Error::Decode(e.into()))
+}
+
+/// Compute a group's balances from all of its shared debts and payments
+///
+/// This is the slow path the cache replaces; it ignores `group_balances`
+/// entirely.
+#[cfg(feature = "ssr")]
+pub async fn compute_pair_balances(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+) -> Result<PairBalances, sqlx::Error> {
+    compute_pair_balances_between(conn, group_id, None, None).await
+}
+
+/// Compute a group's balances from the shared debts incurred and payments
+/// made between two dates (YYYY-MM-DD, both inclusive)
+///
+/// A missing bound leaves that side of the range open. Like
+/// [`compute_pair_balances`] this never reads the cache, which only holds
+/// all-time balances.
+#[cfg(feature = "ssr")]
+pub async fn compute_pair_balances_between(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    from_date: Option<&str>,
+    to_date: Option<&str>,
+) -> Result<PairBalances, sqlx::Error> {
+    let mut pairs = PairBalances::new();
+
+    let strategy = sqlx::query_scalar!(
+        "SELECT rounding_strategy FROM groups WHERE id = ?",
+        group_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .map(|strategy| parse_strategy(&strategy))
+    .transpose()?
+    .unwrap_or_default();
+
+    let shares = sqlx::query!(
+        r#"
+        SELECT
+            sd.id as "debt_id!",
+            COALESCE(sd.payer_id, sd.created_by) as "payer_id!: i64",
+            sd.created_by,
+            sd.amount,
+            sdu.user_id,
+            sdu.share_amount,
+            sdu.is_settled as "is_settled!: bool"
+        FROM shared_debts sd
+        INNER JOIN shared_debt_user sdu ON sdu.shared_debt_id = sd.id
+        WHERE sd.group_id = ? AND sd.status = 'approved'
+          AND (? IS NULL OR sd.incurred_on >= ?)
+          AND (? IS NULL OR sd.incurred_on <= ?)
+        ORDER BY sd.id, sdu.id
+        "#,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for debt in shares.chunk_by(|a, b| a.debt_id == b.debt_id) {
+        let participants: Vec<(i64, Option<String>)> = debt
+            .iter()
+            .map(|row| (row.user_id, row.share_amount.clone()))
+            .collect();
+        let settled: Vec<i64> = debt
+            .iter()
+            .filter(|row| row.is_settled)
+            .map(|row| row.user_id)
+            .collect();
+        add_shared_debt(
+            &mut pairs,
+            DebtSplit {
+                payer_id: debt[0].payer_id,
+                creator_id: debt[0].created_by,
+                amount: parse_amount(&debt[0].amount)?,
+                participants: &participants,
+                settled: &settled,
+            },
+            strategy,
+        );
+    }
+
+    let transactions = sqlx::query!(
+        r#"
+        SELECT payer_id, recipient_id, amount
+        FROM transactions
+        WHERE group_id = ? AND confirmation_status != 'disputed'
+          AND (? IS NULL OR date(created_at) >= ?)
+          AND (? IS NULL OR date(created_at) <= ?)
+        "#,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for transaction in transactions {
+        // Paying someone lowers what the payer owes them
+        add_owed(
+            &mut pairs,
+            transaction.payer_id,
+            transaction.recipient_id,
+            -parse_amount(&transaction.amount)?,
+        );
+    }
+
+    Ok(pairs)
+}
+
+/// A single shared debt's contribution, with the debt's group id
+#[cfg(feature = "ssr")]
+async fn shared_debt_pairs(
+    conn: &mut SqliteConnection,
+    debt_id: i64,
+) -> Result<(i64, PairBalances), sqlx::Error> {
+    let debt = sqlx::query!(
+        r#"
+        SELECT
+            sd.group_id,
+            COALESCE(sd.payer_id, sd.created_by) as "payer_id!: i64",
+            sd.created_by,
+            sd.amount,
+            sd.status,
+            g.rounding_strategy
+        FROM shared_debts sd
+        INNER JOIN groups g ON g.id = sd.group_id
+        WHERE sd.id = ?
+        "#,
+        debt_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Debts waiting for an admin's approval don't count yet
+    let mut pairs = PairBalances::new();
+    if debt.status != DebtStatus::Approved.as_str() {
+        return Ok((debt.group_id, pairs));
+    }
+
+    let rows = sqlx::query!(
+        r#"SELECT user_id, share_amount, is_settled as "is_settled!: bool" FROM shared_debt_user WHERE shared_debt_id = ?"#,
+        debt_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let settled: Vec<i64> = rows
+        .iter()
+        .filter(|row| row.is_settled)
+        .map(|row| row.user_id)
+        .collect();
+    let participants: Vec<(i64, Option<String>)> = rows
+        .into_iter()
+        .map(|row| (row.user_id, row.share_amount))
+        .collect();
+
+    add_shared_debt(
+        &mut pairs,
+        DebtSplit {
+            payer_id: debt.payer_id,
+            creator_id: debt.created_by,
+            amount: parse_amount(&debt.amount)?,
+            participants: &participants,
+            settled: &settled,
+        },
+        parse_strategy(&debt.rounding_strategy)?,
+    );
+    Ok((debt.group_id, pairs))
+}
+
+/// A single payment's contribution, with the payment's group id
+#[cfg(feature = "ssr")]
+async fn transaction_pairs(
+    conn: &mut SqliteConnection,
+    transaction_id: i64,
+) -> Result<(i64, PairBalances), sqlx::Error> {
+    let transaction = sqlx::query!(
+        "SELECT group_id, payer_id, recipient_id, amount, confirmation_status FROM transactions WHERE id = ?",
+        transaction_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Disputed payments don't count until the recipient confirms them
+    let mut pairs = PairBalances::new();
+    if transaction.confirmation_status == ConfirmationStatus::Disputed.as_str() {
+        return Ok((transaction.group_id, pairs));
+    }
+    add_owed(
+        &mut pairs,
+        transaction.payer_id,
+        transaction.recipient_id,
+        -parse_amount(&transaction.amount)?,
+    );
+    Ok((transaction.group_id, pairs))
+}
+
+/// Add `pairs` to the cached balances, or subtract them when `remove` is set
+#[cfg(feature = "ssr")]
+async fn apply_pairs(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+    pairs: PairBalances,
+    remove: bool,
+) -> Result<(), sqlx::Error> {
+    for ((user_a, user_b), amount) in pairs {
+        if amount.is_zero() {
+            continue;
+        }
+
+        let current = sqlx::query_scalar!(
+            "SELECT amount FROM group_balances WHERE group_id = ? AND user_a = ? AND user_b = ?",
+            group_id,
+            user_a,
+            user_b
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .map(|current| parse_amount(&current))
+        .transpose()?
+        .unwrap_or_default();
+        let updated = if remove {
+            current - amount
+        } else {
+            current + amount
+        };
+
+        if updated.is_zero() {
+            sqlx::query!(
+                "DELETE FROM group_balances WHERE group_id = ? AND user_a = ? AND user_b = ?",
+                group_id,
+                user_a,
+                user_b
+            )
+            .execute(&mut *conn)
+            .await?;
+        } else {
+            let updated = updated.to_string();
+            sqlx::query!(
+                r#"
+                INSERT INTO group_balances (group_id, user_a, user_b, amount)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT (group_id, user_a, user_b)
+                DO UPDATE SET amount = excluded.amount, updated_at = CURRENT_TIMESTAMP
+                "#,
+                group_id,
+                user_a,
+                user_b,
+                updated
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Add a shared debt to the cache after it or its shares were written
+#[cfg(feature = "ssr")]
+pub async fn add_shared_debt_to_balances(
+    conn: &mut SqliteConnection,
+    debt_id: i64,
+) -> Result<(), sqlx::Error> {
+    let (group_id, pairs) = shared_debt_pairs(conn, debt_id).await?;
+    apply_pairs(conn, group_id, pairs, false).await
+}
+
+/// Take a shared debt out of the cache before it or its shares change
+#[cfg(feature = "ssr")]
+pub async fn remove_shared_debt_from_balances(
+    conn: &mut SqliteConnection,
+    debt_id: i64,
+) -> Result<(), sqlx::Error> {
+    let (group_id, pairs) = shared_debt_pairs(conn, debt_id).await?;
+    apply_pairs(conn, group_id, pairs, true).await
+}
+
+/// Add a payment to the cache after it was written
+#[cfg(feature = "ssr")]
+pub async fn add_transaction_to_balances(
+    conn: &mut SqliteConnection,
+    transaction_id: i64,
+) -> Result<(), sqlx::Error> {
+    let (group_id, pairs) = transaction_pairs(conn, transaction_id).await?;
+    apply_pairs(conn, group_id, pairs, false).await
+}
+
+/// Take a payment out of the cache before it changes
+#[cfg(feature = "ssr")]
+pub async fn remove_transaction_from_balances(
+    conn: &mut SqliteConnection,
+    transaction_id: i64,
+) -> Result<(), sqlx::Error> {
+    let (group_id, pairs) = transaction_pairs(conn, transaction_id).await?;
+    apply_pairs(conn, group_id, pairs, true).await
+}
+
+/// Mark a group's cache stale so the next read rebuilds it
+///
+/// For changes that touch many debts at once, like a member leaving.
+#[cfg(feature = "ssr")]
+pub async fn invalidate_group_balances(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE groups SET balances_cached_at = NULL WHERE id = ?",
+        group_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Replace a group's cached balances with a full recompute
+#[cfg(feature = "ssr")]
+pub async fn rebuild_group_balances(
+    conn: &mut SqliteConnection,
+    group_id: i64,
+) -> Result<(), sqlx::Error> {
+    let pairs = compute_pair_balances(conn, group_id).await?;
+
+    sqlx::query!("DELETE FROM group_balances WHERE group_id = ?", group_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for ((user_a, user_b), amount) in pairs {
+        if amount.is_zero() {
+            continue;
+        }
+        let amount = amount.to_string();
+        sqlx::query!(
+            "INSERT INTO group_balances (group_id, user_a, user_b, amount) VALUES (?, ?, ?, ?)",
+            group_id,
+            user_a,
+            user_b,
+            amount
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    sqlx::query!(
+        "UPDATE groups SET balances_cached_at = CURRENT_TIMESTAMP WHERE id = ?",
+        group_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Read a group's cached balances, building the cache first if it is stale
+#[cfg(feature = "ssr")]
+pub async fn cached_pair_balances(
+    pool: &SqlitePool,
+    group_id: i64,
+) -> Result<PairBalances, sqlx::Error> {
+    let cached = sqlx::query_scalar!(
+        r#"SELECT balances_cached_at IS NOT NULL as "cached!: bool" FROM groups WHERE id = ?"#,
+        group_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    if cached == Some(false) {
+        let mut tx = pool.begin().await?;
+        rebuild_group_balances(&mut tx, group_id).await?;
+        tx.commit().await?;
+    }
+
+    let rows = sqlx::query!(
+        "SELECT user_a, user_b, amount FROM group_balances WHERE group_id = ?",
+        group_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| Ok(((row.user_a, row.user_b), parse_amount(&row.amount)?)))
+        .collect()
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    /// Small xorshift generator so the operation sequence is reproducible
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % n
+        }
+
+        fn pick(&mut self, ids: &[i64]) -> i64 {
+            ids[self.below(ids.len() as u64) as usize]
+        }
+
+        /// Between €0.01 and €200.00
+        fn amount(&mut self) -> Decimal {
+            Decimal::new(self.below(20_000) as i64 + 1, 2)
+        }
+    }
+
+    const MEMBERS: [i64; 4] = [1, 2, 3, 4];
+
+    /// Group 1 with four members and an empty, built cache
+    async fn setup() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for username in ["alice", "bob", "carol", "dave"] {
+            sqlx::query("INSERT INTO users (username, password_hash) VALUES (?, 'x')")
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Flat', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for user_id in MEMBERS {
+            sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES (1, ?)")
+                .bind(user_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let mut conn = pool.acquire().await.unwrap();
+        rebuild_group_balances(&mut conn, 1).await.unwrap();
+        pool
+    }
+
+    /// Write a debt's shares: an equal split, or every other time custom
+    /// amounts adding up to the total
+    async fn insert_shares(conn: &mut SqliteConnection, rng: &mut Rng, debt_id: i64) {
+        let amount: String = sqlx::query_scalar("SELECT amount FROM shared_debts WHERE id = ?")
+            .bind(debt_id)
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        let amount: Decimal = amount.parse().unwrap();
+
+        let mut members: Vec<i64> = MEMBERS.into_iter().filter(|_| rng.below(3) > 0).collect();
+        if members.is_empty() {
+            members.push(rng.pick(&MEMBERS));
+        }
+
+        let custom = rng.below(2) == 0;
+        let mut remaining = amount;
+        for (index, member_id) in members.iter().enumerate() {
+            let share = if !custom {
+                None
+            } else if index + 1 == members.len() {
+                Some(remaining.to_string())
+            } else {
+                let share = (remaining / Decimal::from(2)).round_dp(2);
+                remaining -= share;
+                Some(share.to_string())
+            };
+            sqlx::query(
+                "INSERT INTO shared_debt_user (shared_debt_id, user_id, share_amount) VALUES (?, ?, ?)",
+            )
+            .bind(debt_id)
+            .bind(member_id)
+            .bind(share)
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn ids(conn: &mut SqliteConnection, table: &str) -> Vec<i64> {
+        sqlx::query_scalar(&format!("SELECT id FROM {table}"))
+            .fetch_all(&mut *conn)
+            .await
+            .unwrap()
+    }
+
+    /// Apply one random change the way the handlers do, keeping the cache up
+    /// to date
+    async fn random_operation(conn: &mut SqliteConnection, rng: &mut Rng) {
+        let debt_ids = ids(conn, "shared_debts").await;
+        let transaction_ids = ids(conn, "transactions").await;
+
+        match rng.below(7) {
+            // Create a shared debt
+            0 | 1 => {
+                let debt_id = sqlx::query(
+                    "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, ?, 'Debt', ?)",
+                )
+                .bind(rng.pick(&MEMBERS))
+                .bind(rng.amount().to_string())
+                .execute(&mut *conn)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+                insert_shares(conn, rng, debt_id).await;
+                add_shared_debt_to_balances(conn, debt_id).await.unwrap();
+            }
+            // Edit a shared debt's amount, payer and members
+            2 if !debt_ids.is_empty() => {
+                let debt_id = rng.pick(&debt_ids);
+                remove_shared_debt_from_balances(conn, debt_id)
+                    .await
+                    .unwrap();
+                sqlx::query("UPDATE shared_debts SET amount = ?, payer_id = ? WHERE id = ?")
+                    .bind(rng.amount().to_string())
+                    .bind(rng.pick(&MEMBERS))
+                    .bind(debt_id)
+                    .execute(&mut *conn)
+                    .await
+                    .unwrap();
+                sqlx::query("DELETE FROM shared_debt_user WHERE shared_debt_id = ?")
+                    .bind(debt_id)
+                    .execute(&mut *conn)
+                    .await
+                    .unwrap();
+                insert_shares(conn, rng, debt_id).await;
+                add_shared_debt_to_balances(conn, debt_id).await.unwrap();
+            }
+            // Delete a shared debt
+            3 if !debt_ids.is_empty() => {
+                let debt_id = rng.pick(&debt_ids);
+                remove_shared_debt_from_balances(conn, debt_id)
+                    .await
+                    .unwrap();
+                sqlx::query("DELETE FROM shared_debts WHERE id = ?")
+                    .bind(debt_id)
+                    .execute(&mut *conn)
+                    .await
+                    .unwrap();
+            }
+            // Toggle whether a member settled their share
+            4 if !debt_ids.is_empty() => {
+                let debt_id = rng.pick(&debt_ids);
+                remove_shared_debt_from_balances(conn, debt_id)
+                    .await
+                    .unwrap();
+                sqlx::query(
+                    "UPDATE shared_debt_user SET is_settled = NOT is_settled WHERE shared_debt_id = ? AND user_id = ?",
+                )
+                .bind(debt_id)
+                .bind(rng.pick(&MEMBERS))
+                .execute(&mut *conn)
+                .await
+                .unwrap();
+                add_shared_debt_to_balances(conn, debt_id).await.unwrap();
+            }
+            // Edit a payment or its confirmation
+            5 if !transaction_ids.is_empty() => {
+                let transaction_id = rng.pick(&transaction_ids);
+                remove_transaction_from_balances(conn, transaction_id)
+                    .await
+                    .unwrap();
+                if rng.below(3) == 0 {
+                    sqlx::query("DELETE FROM transactions WHERE id = ?")
+                        .bind(transaction_id)
+                        .execute(&mut *conn)
+                        .await
+                        .unwrap();
+                } else {
+                    let status = ["pending", "confirmed", "disputed"][rng.below(3) as usize];
+                    sqlx::query(
+                        "UPDATE transactions SET amount = ?, confirmation_status = ? WHERE id = ?",
+                    )
+                    .bind(rng.amount().to_string())
+                    .bind(status)
+                    .bind(transaction_id)
+                    .execute(&mut *conn)
+                    .await
+                    .unwrap();
+                    add_transaction_to_balances(conn, transaction_id)
+                        .await
+                        .unwrap();
+                }
+            }
+            // Record a payment
+            _ => {
+                let payer_id = rng.pick(&MEMBERS);
+                let recipient_id = MEMBERS[(payer_id as usize + rng.below(3) as usize) % 4];
+                let transaction_id = sqlx::query(
+                    "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, ?, ?, ?)",
+                )
+                .bind(payer_id)
+                .bind(recipient_id)
+                .bind(rng.amount().to_string())
+                .execute(&mut *conn)
+                .await
+                .unwrap()
+                .last_insert_rowid();
+                add_transaction_to_balances(conn, transaction_id)
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Full recompute without the pairs that cancel out, which the cache
+    /// doesn't store
+    async fn recomputed(conn: &mut SqliteConnection) -> PairBalances {
+        let mut pairs = compute_pair_balances(conn, 1).await.unwrap();
+        pairs.retain(|_, amount| !amount.is_zero());
+        pairs
+    }
+
+    #[tokio::test]
+    async fn test_cache_matches_full_recompute_after_random_operations() {
+        let pool = setup().await;
+
+        for seed in [1, 7, 42, 2026] {
+            let mut rng = Rng(seed);
+            for _ in 0..150 {
+                let mut conn = pool.acquire().await.unwrap();
+                random_operation(&mut conn, &mut rng).await;
+            }
+
+            let cached = cached_pair_balances(&pool, 1).await.unwrap();
+            let mut conn = pool.acquire().await.unwrap();
+            assert_eq!(cached, recomputed(&mut conn).await, "seed {seed}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalidated_cache_is_rebuilt_on_read() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        // Written behind the cache's back, as a member leaving would
+        sqlx::query(
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 2, 1, '12.50')",
+        )
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+        drop(conn);
+        assert!(cached_pair_balances(&pool, 1).await.unwrap().is_empty());
+
+        let mut conn = pool.acquire().await.unwrap();
+        invalidate_group_balances(&mut conn, 1).await.unwrap();
+        drop(conn);
+
+        assert_eq!(
+            cached_pair_balances(&pool, 1).await.unwrap(),
+            PairBalances::from([((1, 2), Decimal::new(1250, 2))])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_date_range_only_counts_debts_and_payments_inside_it() {
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        // bob owes alice 15 for February, alice owes bob 10 for March and
+        // bob paid alice 5 in March
+        for statement in [
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount, incurred_on) VALUES (1, 1, 1, 'Rent', '30', '2026-02-10'), (1, 2, 2, 'Dinner', '20', '2026-03-05')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1), (1, 2), (2, 1), (2, 2)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount, created_at) VALUES (1, 2, 1, '5', '2026-03-10 18:00:00')",
+        ] {
+            sqlx::query(statement).execute(&mut *conn).await.unwrap();
+        }
+
+        let march =
+            compute_pair_balances_between(&mut conn, 1, Some("2026-03-01"), Some("2026-03-31"))
+                .await
+                .unwrap();
+        assert_eq!(march, PairBalances::from([((1, 2), Decimal::from(15))]));
+
+        let february = compute_pair_balances_between(&mut conn, 1, None, Some("2026-02-28"))
+            .await
+            .unwrap();
+        assert_eq!(february, PairBalances::from([((1, 2), Decimal::from(-15))]));
+
+        let all_time = compute_pair_balances(&mut conn, 1).await.unwrap();
+        assert!(all_time.values().all(Decimal::is_zero));
+    }
+
+    #[tokio::test]
+    async fn test_paying_the_displayed_shares_settles_exactly() {
+        use crate::features::shared_debts::utils::stored_shares;
+
+        let pool = setup().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        // alice paid 10.00 for herself, bob and carol and absorbs the
+        // leftover cent
+        for statement in [
+            "UPDATE groups SET rounding_strategy = 'creator_absorbs' WHERE id = 1",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, 1, 'Pizza', '10.00')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+        ] {
+            sqlx::query(statement).execute(&mut *conn).await.unwrap();
+        }
+        add_shared_debt_to_balances(&mut conn, 1).await.unwrap();
+
+        let members: Vec<(i64, String, Option<String>)> = [(1, "alice"), (2, "bob"), (3, "carol")]
+            .into_iter()
+            .map(|(user_id, username)| (user_id, username.to_string(), None))
+            .collect();
+        let shares = stored_shares(
+            Decimal::from(10),
+            &members,
+            RoundingStrategy::CreatorAbsorbs,
+            1,
+        );
+        let amounts: Vec<Decimal> = shares.iter().map(|share| share.share_amount).collect();
+        assert_eq!(
+            amounts,
+            [
+                Decimal::new(334, 2),
+                Decimal::new(333, 2),
+                Decimal::new(333, 2)
+            ]
+        );
+
+        // bob and carol pay exactly what the debt shows them
+        for share in shares.iter().filter(|share| share.user_id != 1) {
+            let transaction_id = sqlx::query(
+                "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, ?, 1, ?)",
+            )
+            .bind(share.user_id)
+            .bind(share.share_amount.to_string())
+            .execute(&mut *conn)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            add_transaction_to_balances(&mut conn, transaction_id)
+                .await
+                .unwrap();
+        }
+
+        assert!(recomputed(&mut conn).await.is_empty());
+        drop(conn);
+        assert!(cached_pair_balances(&pool, 1).await.unwrap().is_empty());
+    }
+}
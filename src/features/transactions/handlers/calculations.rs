@@ -3,49 +3,375 @@
 use leptos::prelude::*;
 #[cfg(feature = "ssr")]
 use leptos_axum::extract;
-#[cfg(feature = "ssr")]
 use rust_decimal::Decimal;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
 use crate::features::auth::utils::get_user_from_session;
-use crate::features::transactions::models::UserBalance;
+#[cfg(feature = "ssr")]
+use crate::features::groups::utils::is_group_admin;
+#[cfg(feature = "ssr")]
+use crate::features::shared_debts::{models::RoundingStrategy, utils::member_share_amounts};
+#[cfg(feature = "ssr")]
+use crate::features::transactions::balances::{
+    PairBalances, cached_pair_balances, compute_pair_balances_between, rebuild_group_balances,
+};
 #[cfg(feature = "ssr")]
 use crate::features::transactions::models::{DebtRelationship, NetType, RelationshipType};
+use crate::features::transactions::models::{GroupNetBalance, SettlementSuggestion, UserBalance};
+#[cfg(feature = "ssr")]
+use crate::validation::rules::check_date_range;
 
 /// Calculate user debts for a group (combines shared debts and transactions)
+///
+/// Without dates every debt and payment counts; with them only the debts
+/// incurred and payments made in that range do.
 #[server(CalculateUserDebts)]
-pub async fn calculate_user_debts(group_id: i64) -> Result<Vec<UserBalance>, ServerFnError> {
-    use std::collections::HashMap;
+pub async fn calculate_user_debts(
+    group_id: i64,
+    /// Earliest day to include (YYYY-MM-DD)
+    #[server(default)]
+    from_date: Option<String>,
+    /// Latest day to include (YYYY-MM-DD)
+    #[server(default)]
+    to_date: Option<String>,
+) -> Result<Vec<UserBalance>, AppError> {
+    use sqlx::SqlitePool;
+
+    let (from_date, to_date) =
+        check_date_range(from_date.as_deref(), to_date.as_deref()).map_err(AppError::Validation)?;
+    let from_date = from_date.map(|d| d.to_string());
+    let to_date = to_date.map(|d| d.to_string());
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    // Check user is member of group
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden(
+            "You are not a member of this group".to_string(),
+        ));
+    }
+
+    let group = sqlx::query!(
+        r#"SELECT full_balances_visible as "full_balances_visible!: bool" FROM groups WHERE id = ?"#,
+        group_id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Group not found"))?;
+
+    if !group.full_balances_visible && !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can view the full balance matrix in this group",
+        ));
+    }
+
+    compute_group_balances_between(&pool, group_id, from_date.as_deref(), to_date.as_deref()).await
+}
+
+/// Throw away a group's cached balances and recompute them from all of its
+/// debts and payments
+///
+/// A repair tool for group admins; the cache is normally kept up to date by
+/// the handlers that change debts and payments.
+#[server(RebuildGroupBalances)]
+pub async fn rebuild_balances(group_id: i64) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    if !is_group_admin(&pool, group_id, user.id).await? {
+        return Err(AppError::forbidden(
+            "Only group admins can rebuild the balances",
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+    rebuild_group_balances(&mut tx, group_id).await?;
+    tx.commit().await?;
+
+    tracing::info!(group_id, user_id = user.id, "Rebuilt cached group balances");
+
+    Ok(())
+}
 
+/// Calculate the current user's balance in a group
+///
+/// Only contains the user's own relationships and totals, so other members'
+/// debts between each other are never sent to the client.
+#[server(CalculateMyDebts)]
+pub async fn calculate_my_debts(group_id: i64) -> Result<UserBalance, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+    let user = get_user_from_session(&session)
         .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user.id
+    )
+    .fetch_one(&pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden(
+            "You are not a member of this group".to_string(),
+        ));
+    }
+
+    compute_group_balances(&pool, group_id)
+        .await?
+        .into_iter()
+        .find(|balance| balance.user_id == user.id)
+        .ok_or_else(|| AppError::not_found("Balance not found"))
+}
+
+/// Amount the current user owes another group member, if any
+///
+/// Used to suggest a payment amount without sending the whole group balance
+/// structure to the client.
+#[server(GetMyDebtTo)]
+pub async fn get_my_debt_to(
+    group_id: i64,
+    other_user_id: i64,
+) -> Result<Option<Decimal>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check user is member of group
     let is_member = sqlx::query_scalar!(
         "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
         group_id,
         user.id
     )
     .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     if is_member == 0 {
-        return Err(ServerFnError::new(
+        return Err(AppError::forbidden(
             "You are not a member of this group".to_string(),
         ));
     }
 
+    let balances = compute_group_balances(&pool, group_id).await?;
+
+    Ok(balances
+        .into_iter()
+        .find(|balance| balance.user_id == user.id)
+        .and_then(|balance| {
+            balance.relationships.into_iter().find(|r| {
+                r.other_user_id == other_user_id
+                    && matches!(r.relationship_type, RelationshipType::Owes)
+            })
+        })
+        .and_then(|r| r.amount.parse::<Decimal>().ok())
+        .filter(|amount| *amount > Decimal::ZERO))
+}
+
+/// The current user's net balance in each of their groups, by group name
+#[server(GetUserOverallBalances)]
+pub async fn get_user_overall_balances() -> Result<Vec<GroupNetBalance>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    compute_user_group_balances(&pool, user.id).await
+}
+
+/// Minimal set of payments that settles every balance in a group
+///
+/// Members who may not view the full balance matrix only get the payments
+/// they are part of.
+#[server(GetSettlementPlan)]
+pub async fn get_settlement_plan(group_id: i64) -> Result<Vec<SettlementSuggestion>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let group = sqlx::query!(
+        r#"
+        SELECT g.full_balances_visible as "full_balances_visible!: bool"
+        FROM groups g
+        INNER JOIN group_members gm ON g.id = gm.group_id
+        WHERE g.id = ? AND gm.user_id = ?
+        "#,
+        group_id,
+        user.id
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::forbidden("You are not a member of this group"))?;
+
+    let balances = compute_group_balances(&pool, group_id).await?;
+    let sees_everything =
+        group.full_balances_visible || is_group_admin(&pool, group_id, user.id).await?;
+
+    Ok(visible_settlements(&balances, user.id, sees_everything))
+}
+
+/// Settlement plan for the balances as shown to `user_id`: every payment when
+/// they may see the full balance matrix, otherwise only their own
+#[cfg(feature = "ssr")]
+pub fn visible_settlements(
+    balances: &[UserBalance],
+    user_id: i64,
+    sees_everything: bool,
+) -> Vec<SettlementSuggestion> {
+    let mut plan = plan_settlements(balances);
+    if !sees_everything {
+        plan.retain(|s| s.from_user_id == user_id || s.to_user_id == user_id);
+    }
+    plan
+}
+
+/// Greedy minimum cash flow over the members' net balances
+///
+/// The member who owes the most repeatedly pays the member who is owed the
+/// most, until everyone is settled. Ties go to the lower user id so the plan
+/// is the same for the same balances.
+#[cfg(feature = "ssr")]
+pub fn plan_settlements(balances: &[UserBalance]) -> Vec<SettlementSuggestion> {
+    // Positive when the member is owed money
+    let mut nets: Vec<(i64, &str, Decimal)> = balances
+        .iter()
+        .map(|balance| {
+            let net = balance
+                .net_amount
+                .parse::<Decimal>()
+                .unwrap_or_default()
+                .round_dp(2);
+            let net = match balance.net_type {
+                NetType::Negative => -net,
+                NetType::Positive | NetType::Neutral => net,
+            };
+            (balance.user_id, balance.username.as_str(), net)
+        })
+        .filter(|(_, _, net)| !net.is_zero())
+        .collect();
+    nets.sort_by_key(|(user_id, _, _)| *user_id);
+
+    let mut plan = Vec::new();
+    loop {
+        // min_by keeps the first of equal elements, i.e. the lowest id
+        let debtor = nets
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, _, net))| *net < Decimal::ZERO)
+            .min_by(|(_, a), (_, b)| a.2.cmp(&b.2))
+            .map(|(index, _)| index);
+        let creditor = nets
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, _, net))| *net > Decimal::ZERO)
+            .min_by(|(_, a), (_, b)| b.2.cmp(&a.2))
+            .map(|(index, _)| index);
+
+        let (Some(debtor), Some(creditor)) = (debtor, creditor) else {
+            break;
+        };
+
+        let amount = (-nets[debtor].2).min(nets[creditor].2);
+        nets[debtor].2 += amount;
+        nets[creditor].2 -= amount;
+
+        plan.push(SettlementSuggestion {
+            from_user_id: nets[debtor].0,
+            from_username: nets[debtor].1.to_string(),
+            to_user_id: nets[creditor].0,
+            to_username: nets[creditor].1.to_string(),
+            amount,
+        });
+    }
+
+    plan
+}
+
+/// Compute the balances of every current group member without any access checks.
+///
+/// Reads the cached pairwise balances, see [`crate::features::transactions::balances`].
+/// Callers are responsible for verifying that the requesting user may see the group.
+#[cfg(feature = "ssr")]
+pub async fn compute_group_balances(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+) -> Result<Vec<UserBalance>, AppError> {
+    let pairs = cached_pair_balances(pool, group_id).await?;
+    balances_from_pairs(pool, group_id, pairs).await
+}
+
+/// Compute the balances of every current group member from the debts
+/// incurred and payments made between two dates (YYYY-MM-DD, both inclusive)
+///
+/// Recomputes from scratch when a bound is given, since the cache only holds
+/// all-time balances. Like [`compute_group_balances`] this checks no access.
+#[cfg(feature = "ssr")]
+pub async fn compute_group_balances_between(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    from_date: Option<&str>,
+    to_date: Option<&str>,
+) -> Result<Vec<UserBalance>, AppError> {
+    if from_date.is_none() && to_date.is_none() {
+        return compute_group_balances(pool, group_id).await;
+    }
+
+    let mut conn = pool.acquire().await?;
+    let pairs = compute_pair_balances_between(&mut conn, group_id, from_date, to_date).await?;
+    drop(conn);
+
+    balances_from_pairs(pool, group_id, pairs).await
+}
+
+/// Turn pairwise balances into every current member's relationships and
+/// totals
+#[cfg(feature = "ssr")]
+async fn balances_from_pairs(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    pairs: PairBalances,
+) -> Result<Vec<UserBalance>, AppError> {
+    use std::collections::HashMap;
+
     // Get all group members
     let members = sqlx::query!(
         r#"
@@ -56,9 +382,8 @@ pub async fn calculate_user_debts(group_id: i64) -> Result<Vec<UserBalance>, Ser
         "#,
         group_id
     )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .fetch_all(pool)
+    .await?;
 
     // Initialize debt matrix (who owes whom)
     let mut debts: HashMap<i64, HashMap<i64, Decimal>> = HashMap::new();
@@ -66,11 +391,15 @@ pub async fn calculate_user_debts(group_id: i64) -> Result<Vec<UserBalance>, Ser
         debts.insert(member.id, HashMap::new());
     }
 
-    // Calculate debts from shared debts
-    calculate_shared_debt_contributions(&pool, group_id, &mut debts).await?;
-
-    // Factor in direct transactions
-    calculate_transaction_contributions(&pool, group_id, &mut debts).await?;
+    // Fill in both directions from the pairwise balances
+    for ((user_a, user_b), amount) in pairs {
+        if let Some(user_debts) = debts.get_mut(&user_a) {
+            *user_debts.entry(user_b).or_insert(Decimal::ZERO) += amount;
+        }
+        if let Some(user_debts) = debts.get_mut(&user_b) {
+            *user_debts.entry(user_a).or_insert(Decimal::ZERO) -= amount;
+        }
+    }
 
     // Build UserBalance objects
     let mut balances = Vec::new();
@@ -139,105 +468,376 @@ pub async fn calculate_user_debts(group_id: i64) -> Result<Vec<UserBalance>, Ser
     Ok(balances)
 }
 
+/// Compute a user's net balance in every group they belong to
+///
+/// Gives the same nets as [`compute_group_balances`], but fetches the shared
+/// debts and transactions involving the user for all groups in one query.
 #[cfg(feature = "ssr")]
-async fn calculate_shared_debt_contributions(
+pub async fn compute_user_group_balances(
     pool: &sqlx::SqlitePool,
-    group_id: i64,
-    debts: &mut std::collections::HashMap<i64, std::collections::HashMap<i64, Decimal>>,
-) -> Result<(), ServerFnError> {
-    use std::str::FromStr;
-    // Fetch all shared debts for the group
-    let shared_debts = sqlx::query!(
+    user_id: i64,
+) -> Result<Vec<GroupNetBalance>, AppError> {
+    use std::{collections::HashMap, str::FromStr};
+
+    // One row per group without activity, per participant of a shared debt
+    // the user is part of, and per transaction the user paid or received
+    // (with a NULL debt_id and the recipient as user_id)
+    let rows = sqlx::query!(
         r#"
-        SELECT id as "id!", created_by as "created_by!", amount
-        FROM shared_debts
-        WHERE group_id = ?
+        SELECT
+            g.id as "group_id!",
+            g.name as group_name,
+            g.rounding_strategy,
+            e.debt_id as "debt_id?: i64",
+            e.payer_id as "payer_id?: i64",
+            e.created_by as "created_by?: i64",
+            e.amount as "amount?: String",
+            e.user_id as "user_id?: i64",
+            e.share_amount as "share_amount?: String",
+            e.is_settled as "is_settled?: bool"
+        FROM group_members gm
+        INNER JOIN groups g ON g.id = gm.group_id
+        LEFT JOIN (
+            SELECT
+                sd.group_id,
+                sd.id as debt_id,
+                COALESCE(sd.payer_id, sd.created_by) as payer_id,
+                sd.created_by,
+                sd.amount,
+                sdu.user_id,
+                sdu.share_amount,
+                sdu.is_settled
+            FROM shared_debts sd
+            INNER JOIN shared_debt_user sdu ON sdu.shared_debt_id = sd.id
+            WHERE sd.status = 'approved'
+                AND (COALESCE(sd.payer_id, sd.created_by) = ?
+                    OR EXISTS (
+                        SELECT 1 FROM shared_debt_user me
+                        WHERE me.shared_debt_id = sd.id AND me.user_id = ?
+                    ))
+            UNION ALL
+            SELECT group_id, NULL, payer_id, NULL, amount, recipient_id, NULL, 0
+            FROM transactions
+            WHERE (payer_id = ? OR recipient_id = ?) AND confirmation_status != 'disputed'
+        ) e ON e.group_id = g.id
+        WHERE gm.user_id = ?
+        ORDER BY g.name COLLATE NOCASE ASC, g.id ASC
         "#,
-        group_id
+        user_id,
+        user_id,
+        user_id,
+        user_id,
+        user_id
     )
     .fetch_all(pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
-
-    for debt in shared_debts {
-        let creator_id = debt.created_by;
-        let total_amount =
-            Decimal::from_str(&debt.amount).map_err(|e| ServerFnError::new(e.to_string()))?;
-
-        // Get participants in this shared debt
-        let participants = sqlx::query!(
-            "SELECT user_id FROM shared_debt_user WHERE shared_debt_id = ?",
-            debt.id
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
-
-        if participants.is_empty() {
-            continue;
-        }
+    .await?;
+
+    struct DebtRows {
+        group_index: usize,
+        payer_id: i64,
+        creator_id: i64,
+        strategy: RoundingStrategy,
+        amount: Decimal,
+        participants: Vec<(i64, Option<String>)>,
+        settled: Vec<i64>,
+    }
 
-        let share_per_user = total_amount / Decimal::from(participants.len());
+    // (group id, group name, net), positive when the user is owed money
+    let mut groups: Vec<(i64, String, Decimal)> = Vec::new();
+    let mut debts: HashMap<i64, DebtRows> = HashMap::new();
 
-        // Each participant (except creator) owes their share to the creator
-        for participant in participants {
-            let user_id = participant.user_id;
-            if user_id == creator_id {
-                continue; // Creator doesn't owe themselves
+    for row in rows {
+        let index = match groups.iter().position(|(id, _, _)| *id == row.group_id) {
+            Some(index) => index,
+            None => {
+                groups.push((row.group_id, row.group_name, Decimal::ZERO));
+                groups.len() - 1
             }
+        };
 
-            // User owes creator
-            if let Some(user_debts) = debts.get_mut(&user_id) {
-                *user_debts.entry(creator_id).or_insert(Decimal::ZERO) += share_per_user;
+        let (Some(payer_id), Some(amount), Some(other_id)) =
+            (row.payer_id, row.amount, row.user_id)
+        else {
+            continue; // Group without activity
+        };
+        let amount = Decimal::from_str(&amount).map_err(AppError::internal)?;
+
+        match row.debt_id {
+            Some(debt_id) => {
+                let strategy = row
+                    .rounding_strategy
+                    .parse::<RoundingStrategy>()
+                    .map_err(AppError::internal)?;
+                let debt = debts.entry(debt_id).or_insert_with(|| DebtRows {
+                    group_index: index,
+                    payer_id,
+                    creator_id: row.created_by.unwrap_or(payer_id),
+                    strategy,
+                    amount,
+                    participants: Vec::new(),
+                    settled: Vec::new(),
+                });
+                debt.participants.push((other_id, row.share_amount));
+                if row.is_settled.unwrap_or(false) {
+                    debt.settled.push(other_id);
+                }
+            }
+            None => {
+                // Paying someone lowers what the user owes them, receiving raises it
+                if payer_id == user_id {
+                    groups[index].2 += amount;
+                }
+                if other_id == user_id {
+                    groups[index].2 -= amount;
+                }
             }
+        }
+    }
 
-            // Creator is owed by user (negative debt)
-            if let Some(creator_debts) = debts.get_mut(&creator_id) {
-                *creator_debts.entry(user_id).or_insert(Decimal::ZERO) -= share_per_user;
+    // Same rules as the group balance cache, seen from the user
+    for debt in debts.into_values() {
+        let shares = member_share_amounts(
+            debt.amount,
+            &debt.participants,
+            debt.strategy,
+            debt.creator_id,
+        );
+        for (participant_id, share) in shares {
+            if participant_id == debt.payer_id || debt.settled.contains(&participant_id) {
+                continue;
+            }
+            if debt.payer_id == user_id {
+                groups[debt.group_index].2 += share;
+            } else if participant_id == user_id {
+                groups[debt.group_index].2 -= share;
             }
         }
     }
 
-    Ok(())
+    Ok(groups
+        .into_iter()
+        .map(|(group_id, group_name, net)| GroupNetBalance {
+            group_id,
+            group_name,
+            net_amount: net.abs().round_dp(2).to_string(),
+            net_type: if net > Decimal::ZERO {
+                NetType::Positive
+            } else if net < Decimal::ZERO {
+                NetType::Negative
+            } else {
+                NetType::Neutral
+            },
+        })
+        .collect())
 }
 
-#[cfg(feature = "ssr")]
-async fn calculate_transaction_contributions(
-    pool: &sqlx::SqlitePool,
-    group_id: i64,
-    debts: &mut std::collections::HashMap<i64, std::collections::HashMap<i64, Decimal>>,
-) -> Result<(), ServerFnError> {
-    use std::str::FromStr;
-    // Fetch all transactions for the group
-    let transactions = sqlx::query!(
-        r#"
-        SELECT payer_id as "payer_id!", recipient_id as "recipient_id!", amount
-        FROM transactions
-        WHERE group_id = ?
-        "#,
-        group_id
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
-
-    for transaction in transactions {
-        let payer_id = transaction.payer_id;
-        let recipient_id = transaction.recipient_id;
-        let amount = Decimal::from_str(&transaction.amount)
-            .map_err(|e| ServerFnError::new(e.to_string()))?;
-
-        // When payer pays recipient, payer's debt to recipient decreases
-        // Payer owes less (or is owed more)
-        if let Some(payer_debts) = debts.get_mut(&payer_id) {
-            *payer_debts.entry(recipient_id).or_insert(Decimal::ZERO) -= amount;
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+
+    /// Group 1 with alice (1) and bob (2) and a €30 dinner entered by alice,
+    /// split between both and paid by `payer`
+    async fn setup_dinner(payer: i64) -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for username in ["alice", "bob"] {
+            sqlx::query("INSERT INTO users (username, password_hash) VALUES (?, 'x')")
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Flat', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for user_id in [1, 2] {
+            sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES (1, ?)")
+                .bind(user_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, ?, 'Dinner', '30')",
+        )
+        .bind(payer)
+        .execute(&pool)
+        .await
+        .unwrap();
+        for user_id in [1, 2] {
+            sqlx::query("INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, ?)")
+                .bind(user_id)
+                .execute(&pool)
+                .await
+                .unwrap();
         }
 
-        // Recipient is owed less by payer (or owes more to payer)
-        if let Some(recipient_debts) = debts.get_mut(&recipient_id) {
-            *recipient_debts.entry(payer_id).or_insert(Decimal::ZERO) += amount;
+        pool
+    }
+
+    async fn net_of(pool: &SqlitePool, user_id: i64) -> (NetType, Decimal) {
+        let balance = compute_group_balances(pool, 1)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|balance| balance.user_id == user_id)
+            .unwrap();
+        (balance.net_type, balance.net_amount.parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_creator_paid_is_owed() {
+        let pool = setup_dinner(1).await;
+
+        assert_eq!(
+            net_of(&pool, 1).await,
+            (NetType::Positive, Decimal::from(15))
+        );
+        assert_eq!(
+            net_of(&pool, 2).await,
+            (NetType::Negative, Decimal::from(15))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_settled_share_is_excluded() {
+        let pool = setup_dinner(1).await;
+        sqlx::query(
+            "UPDATE shared_debt_user SET is_settled = 1, settled_at = CURRENT_TIMESTAMP WHERE user_id = 2",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(net_of(&pool, 1).await, (NetType::Neutral, Decimal::ZERO));
+        assert_eq!(net_of(&pool, 2).await, (NetType::Neutral, Decimal::ZERO));
+    }
+
+    #[tokio::test]
+    async fn test_payer_other_than_creator_is_owed() {
+        let pool = setup_dinner(2).await;
+
+        assert_eq!(
+            net_of(&pool, 1).await,
+            (NetType::Negative, Decimal::from(15))
+        );
+        assert_eq!(
+            net_of(&pool, 2).await,
+            (NetType::Positive, Decimal::from(15))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_user_group_balances_match_group_balances() {
+        let pool = setup_dinner(1).await;
+        for statement in [
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 2, 1, '5')",
+            "INSERT INTO groups (name, created_by) VALUES ('Trip', 2)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (2, 1), (2, 2)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (2, 2, 2, 'Fuel', '12')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (2, 1), (2, 2)",
+            "INSERT INTO groups (name, created_by) VALUES ('Book club', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (3, 1)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
         }
+
+        let overall: Vec<(String, NetType, String)> = compute_user_group_balances(&pool, 1)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|b| (b.group_name, b.net_type, b.net_amount))
+            .collect();
+        assert_eq!(
+            overall,
+            vec![
+                ("Book club".to_string(), NetType::Neutral, "0".to_string()),
+                ("Flat".to_string(), NetType::Positive, "10.00".to_string()),
+                ("Trip".to_string(), NetType::Negative, "6.00".to_string()),
+            ]
+        );
+        assert_eq!(
+            net_of(&pool, 1).await,
+            (NetType::Positive, Decimal::from(10))
+        );
     }
 
-    Ok(())
+    fn balance(user_id: i64, username: &str, net: i64) -> UserBalance {
+        let net = Decimal::new(net, 2);
+        UserBalance {
+            user_id,
+            username: username.to_string(),
+            relationships: Vec::new(),
+            total_owed: "0".to_string(),
+            total_owing: "0".to_string(),
+            net_amount: net.abs().to_string(),
+            net_type: if net > Decimal::ZERO {
+                NetType::Positive
+            } else if net < Decimal::ZERO {
+                NetType::Negative
+            } else {
+                NetType::Neutral
+            },
+        }
+    }
+
+    fn payments(plan: &[SettlementSuggestion]) -> Vec<(i64, i64, Decimal)> {
+        plan.iter()
+            .map(|s| (s.from_user_id, s.to_user_id, s.amount))
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_settlements_five_members() {
+        let balances = [
+            balance(1, "alice", 4000),
+            balance(2, "bob", -2500),
+            balance(3, "carol", -1500),
+            balance(4, "dave", 1000),
+            balance(5, "erin", -1000),
+        ];
+
+        assert_eq!(
+            payments(&plan_settlements(&balances)),
+            vec![
+                (2, 1, Decimal::new(2500, 2)),
+                (3, 1, Decimal::new(1500, 2)),
+                (5, 4, Decimal::new(1000, 2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_settlements_ties_and_zero_balances() {
+        let balances = [
+            balance(3, "carol", 500),
+            balance(1, "alice", 0),
+            balance(2, "bob", 500),
+            balance(4, "dave", -1000),
+        ];
+
+        let plan = plan_settlements(&balances);
+        assert_eq!(
+            payments(&plan),
+            vec![(4, 2, Decimal::new(500, 2)), (4, 3, Decimal::new(500, 2))]
+        );
+        assert_eq!(plan[0].from_username, "dave");
+        assert_eq!(plan[0].to_username, "bob");
+    }
+
+    #[test]
+    fn test_plan_settlements_settled_group() {
+        let balances = [balance(1, "alice", 0), balance(2, "bob", 0)];
+
+        assert!(plan_settlements(&balances).is_empty());
+    }
 }
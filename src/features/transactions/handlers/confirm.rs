@@ -0,0 +1,253 @@
+//! Confirming or disputing received payments
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event, models::GroupEvent,
+        utils::ensure_group_writable,
+    },
+    transactions::{
+        balances::{add_transaction_to_balances, remove_transaction_from_balances},
+        models::ConfirmationStatus,
+        utils::find_member_transaction,
+    },
+};
+#[cfg(feature = "ssr")]
+use crate::validation::{rules::MAX_DESCRIPTION_LENGTH, validate_description};
+
+/// Confirm that a payment arrived; only its recipient can
+#[server(ConfirmTransaction)]
+pub async fn confirm_transaction(group_id: i64, transaction_id: i64) -> Result<(), AppError> {
+    let user_id = current_user_id().await?;
+    change_status(
+        group_id,
+        transaction_id,
+        user_id,
+        ConfirmationStatus::Confirmed,
+        None,
+    )
+    .await
+}
+
+/// Dispute a payment that never arrived; only its recipient can
+///
+/// Disputed payments are left out of balances until they are confirmed or
+/// edited by the payer.
+#[server(DisputeTransaction)]
+pub async fn dispute_transaction(
+    group_id: i64,
+    transaction_id: i64,
+    reason: String,
+) -> Result<(), AppError> {
+    let reason = validate_description(&reason, MAX_DESCRIPTION_LENGTH)?;
+    if reason.is_empty() {
+        return Err(AppError::validation(
+            "Please say why you dispute the payment",
+        ));
+    }
+
+    let user_id = current_user_id().await?;
+    change_status(
+        group_id,
+        transaction_id,
+        user_id,
+        ConfirmationStatus::Disputed,
+        Some(&reason),
+    )
+    .await
+}
+
+#[cfg(feature = "ssr")]
+async fn current_user_id() -> Result<i64, AppError> {
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+    Ok(user.id)
+}
+
+#[cfg(feature = "ssr")]
+async fn change_status(
+    group_id: i64,
+    transaction_id: i64,
+    user_id: i64,
+    status: ConfirmationStatus,
+    reason: Option<&str>,
+) -> Result<(), AppError> {
+    use sqlx::SqlitePool;
+
+    let pool = expect_context::<SqlitePool>();
+    let mut tx = pool.begin().await?;
+    set_confirmation_status(&mut tx, group_id, transaction_id, user_id, status, reason).await?;
+    tx.commit().await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, group_id, GroupEvent::TransactionsChanged);
+
+    Ok(())
+}
+
+/// Move a payment to `status` on behalf of `user_id`, who must be its
+/// recipient and not a viewer of an unarchived group, keeping the cached
+/// balances in step
+#[cfg(feature = "ssr")]
+async fn set_confirmation_status(
+    conn: &mut sqlx::SqliteConnection,
+    group_id: i64,
+    transaction_id: i64,
+    user_id: i64,
+    status: ConfirmationStatus,
+    reason: Option<&str>,
+) -> Result<(), AppError> {
+    let existing = find_member_transaction(&mut *conn, group_id, transaction_id, user_id).await?;
+    if existing.recipient_id != user_id {
+        return Err(AppError::forbidden(
+            "Only the recipient can confirm or dispute a payment",
+        ));
+    }
+    ensure_group_writable(&mut *conn, existing.group_id, user_id).await?;
+
+    let confirmation_status = sqlx::query_scalar!(
+        "SELECT confirmation_status FROM transactions WHERE id = ?",
+        transaction_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if confirmation_status == status.as_str() {
+        return Err(AppError::conflict(format!(
+            "This payment is already {}",
+            status.as_str()
+        )));
+    }
+
+    let status_str = status.as_str();
+    remove_transaction_from_balances(conn, transaction_id).await?;
+    sqlx::query!(
+        "UPDATE transactions SET confirmation_status = ?, dispute_reason = ? WHERE id = ?",
+        status_str,
+        reason,
+        transaction_id
+    )
+    .execute(&mut *conn)
+    .await?;
+    add_transaction_to_balances(conn, transaction_id).await?;
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+
+    use super::*;
+    use crate::features::transactions::{
+        balances::rebuild_group_balances, handlers::compute_group_balances, models::NetType,
+    };
+
+    #[tokio::test]
+    async fn test_only_the_recipient_changes_status_and_disputes_leave_balances() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 1, 2, '20')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        {
+            let mut conn = pool.acquire().await.unwrap();
+            rebuild_group_balances(&mut conn, 1).await.unwrap();
+        }
+
+        async fn alice_net(pool: &SqlitePool) -> NetType {
+            compute_group_balances(pool, 1)
+                .await
+                .unwrap()
+                .into_iter()
+                .find(|balance| balance.user_id == 1)
+                .unwrap()
+                .net_type
+        }
+        async fn set(
+            pool: &SqlitePool,
+            user_id: i64,
+            status: ConfirmationStatus,
+            reason: Option<&str>,
+        ) -> Result<(), AppError> {
+            let mut conn = pool.acquire().await.unwrap();
+            set_confirmation_status(&mut conn, 1, 1, user_id, status, reason).await
+        }
+
+        // Pending payments already count
+        assert_eq!(alice_net(&pool).await, NetType::Positive);
+
+        let err = set(&pool, 1, ConfirmationStatus::Confirmed, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Only the recipient"));
+
+        set(
+            &pool,
+            2,
+            ConfirmationStatus::Disputed,
+            Some("Never arrived"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(alice_net(&pool).await, NetType::Neutral);
+        let reason: Option<String> =
+            sqlx::query_scalar("SELECT dispute_reason FROM transactions WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(reason.as_deref(), Some("Never arrived"));
+
+        let err = set(&pool, 2, ConfirmationStatus::Disputed, Some("Again"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already disputed"));
+
+        // Confirming after all brings the payment back and clears the reason
+        set(&pool, 2, ConfirmationStatus::Confirmed, None)
+            .await
+            .unwrap();
+        assert_eq!(alice_net(&pool).await, NetType::Positive);
+        let reason: Option<String> =
+            sqlx::query_scalar("SELECT dispute_reason FROM transactions WHERE id = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(reason, None);
+
+        // Recipients made viewers since can no longer change the status
+        sqlx::query("UPDATE group_members SET role = 'viewer' WHERE user_id = 2")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let err = set(
+            &pool,
+            2,
+            ConfirmationStatus::Disputed,
+            Some("Changed my mind"),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+        assert_eq!(alice_net(&pool).await, NetType::Positive);
+    }
+}
@@ -8,8 +8,24 @@ use rust_decimal::Decimal;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::features::{
+    auth::{models::UserSession, utils::get_user_from_session},
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{ensure_group_writable, group_currency, log_group_activity, payment_summary},
+    },
+    notifications::{NotificationPayload, notify_users},
+    transactions::{
+        balances::add_transaction_to_balances, models::NewTransaction,
+        utils::check_transaction_parties,
+    },
+    webhooks::{WebhookDispatcher, WebhookEvent},
+};
+#[cfg(feature = "ssr")]
+use crate::validation::{rules::MAX_DESCRIPTION_LENGTH, validate_description, validate_money};
 
 /// Create a new transaction
 #[server(CreateTransaction)]
@@ -18,29 +34,71 @@ pub async fn create_transaction(
     recipient_id: i64,
     amount: String,
     description: Option<String>,
-) -> Result<i64, ServerFnError> {
-    use std::str::FromStr;
-
+) -> Result<i64, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let payment = check_new_transaction(NewTransaction {
+        recipient_id,
+        amount,
+        description,
+    })?;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    let webhooks = expect_context::<WebhookDispatcher>();
 
-    // Validate amount
-    let amount_decimal = Decimal::from_str(&amount)
-        .map_err(|_| ServerFnError::new("Invalid amount format".to_string()))?;
+    create_transaction_internal(&pool, &broadcaster, &webhooks, &user, group_id, payment).await
+}
 
-    if amount_decimal <= Decimal::ZERO {
-        return Err(ServerFnError::new(
-            "Amount must be greater than 0".to_string(),
-        ));
-    }
+/// A [`NewTransaction`] that passed [`check_new_transaction`]
+#[cfg(feature = "ssr")]
+pub struct CheckedTransaction {
+    recipient_id: i64,
+    amount: Decimal,
+    description: Option<String>,
+}
+
+/// Check the fields of a new payment that need no database
+#[cfg(feature = "ssr")]
+pub fn check_new_transaction(payment: NewTransaction) -> Result<CheckedTransaction, AppError> {
+    // Validate amount and description
+    let amount = validate_money(&payment.amount)?;
+    let description = payment
+        .description
+        .map(|description| validate_description(&description, MAX_DESCRIPTION_LENGTH))
+        .transpose()?
+        .filter(|description| !description.is_empty());
+
+    Ok(CheckedTransaction {
+        recipient_id: payment.recipient_id,
+        amount,
+        description,
+    })
+}
+
+/// Record a checked payment from `user` in a group and return its id
+///
+/// Used by [`create_transaction`] and the JSON API, which bring their own
+/// pool, broadcaster and webhook dispatcher.
+#[cfg(feature = "ssr")]
+pub async fn create_transaction_internal(
+    pool: &sqlx::SqlitePool,
+    broadcaster: &GroupEventBroadcaster,
+    webhooks: &WebhookDispatcher,
+    user: &UserSession,
+    group_id: i64,
+    payment: CheckedTransaction,
+) -> Result<i64, AppError> {
+    let CheckedTransaction {
+        recipient_id,
+        amount: amount_decimal,
+        description,
+    } = payment;
 
     // Check user is member of group
     let is_member = sqlx::query_scalar!(
@@ -48,42 +106,27 @@ pub async fn create_transaction(
         group_id,
         user.id
     )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .fetch_one(pool)
+    .await?;
 
     if is_member == 0 {
-        return Err(ServerFnError::new(
+        return Err(AppError::forbidden(
             "You are not a member of this group".to_string(),
         ));
     }
+    ensure_group_writable(pool, group_id, user.id).await?;
 
-    // Check recipient is member of group
-    let recipient_is_member = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
-        group_id,
-        recipient_id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
-
-    if recipient_is_member == 0 {
-        return Err(ServerFnError::new(
-            "Recipient is not a member of this group".to_string(),
-        ));
-    }
-
-    // Don't allow self-transactions
-    if user.id == recipient_id {
-        return Err(ServerFnError::new(
-            "Cannot create transaction with yourself".to_string(),
-        ));
-    }
+    check_transaction_parties(pool, group_id, user.id, user.id, recipient_id).await?;
 
     // Store amount rounded to 2 decimal places
     let amount_str = amount_decimal.round_dp(2).to_string();
 
+    let recipient = sqlx::query_scalar!("SELECT username FROM users WHERE id = ?", recipient_id)
+        .fetch_one(pool)
+        .await?;
+
+    let mut tx = pool.begin().await?;
+
     // Insert transaction
     let result = sqlx::query!(
         r#"
@@ -96,9 +139,52 @@ pub async fn create_transaction(
         amount_str,
         description
     )
-    .execute(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .execute(&mut *tx)
+    .await?;
+
+    let transaction_id = result.last_insert_rowid();
+    add_transaction_to_balances(&mut tx, transaction_id).await?;
+
+    let currency = group_currency(&mut *tx, group_id).await?;
 
-    Ok(result.last_insert_rowid())
+    log_group_activity(
+        &mut *tx,
+        group_id,
+        user.id,
+        ActivityEntity::Transaction,
+        transaction_id,
+        ActivityAction::Created,
+        &payment_summary(amount_decimal, &recipient, &currency),
+    )
+    .await?;
+
+    notify_users(
+        &mut tx,
+        group_id,
+        &[recipient_id],
+        &NotificationPayload::PaymentReceived {
+            transaction_id,
+            amount: amount_decimal.round_dp(2),
+            paid_by: user.username.clone(),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    broadcast_group_event(broadcaster, group_id, GroupEvent::TransactionsChanged);
+
+    webhooks.dispatch(
+        group_id,
+        WebhookEvent::TransactionCreated,
+        serde_json::json!({
+            "id": transaction_id,
+            "amount": amount_str,
+            "description": description,
+            "payer": user.username,
+            "recipient": recipient,
+        }),
+    );
+
+    Ok(transaction_id)
 }
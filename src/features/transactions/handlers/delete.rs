@@ -6,45 +6,143 @@ use leptos_axum::extract;
 #[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{ensure_group_writable, group_currency, log_group_activity, payment_summary},
+    },
+    transactions::{balances::remove_transaction_from_balances, utils::find_member_transaction},
+};
 
 /// Delete a transaction
 #[server(DeleteTransaction)]
-pub async fn delete_transaction(group_id: i64, transaction_id: i64) -> Result<(), ServerFnError> {
+pub async fn delete_transaction(group_id: i64, transaction_id: i64) -> Result<(), AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check transaction exists and user is the payer
-    let existing = sqlx::query!(
-        "SELECT payer_id FROM transactions WHERE id = ? AND group_id = ?",
-        transaction_id,
-        group_id
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .ok_or_else(|| ServerFnError::new("Transaction not found"))?;
+    let group_id = remove_transaction(&pool, group_id, transaction_id, user.id).await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, group_id, GroupEvent::TransactionsChanged);
+
+    Ok(())
+}
 
-    if existing.payer_id != user.id {
-        return Err(ServerFnError::new(
+/// Delete a transaction on behalf of `user_id`, who must be its payer, and
+/// return the group it belonged to
+#[cfg(feature = "ssr")]
+async fn remove_transaction(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    transaction_id: i64,
+    user_id: i64,
+) -> Result<i64, AppError> {
+    let transaction = find_member_transaction(pool, group_id, transaction_id, user_id).await?;
+    if transaction.payer_id != user_id {
+        return Err(AppError::forbidden(
             "You can only delete your own transactions",
         ));
     }
+    let group_id = transaction.group_id;
+    ensure_group_writable(pool, group_id, user_id).await?;
+
+    let existing = sqlx::query!(
+        r#"
+        SELECT t.amount, r.username as recipient_username
+        FROM transactions t
+        INNER JOIN users r ON t.recipient_id = r.id
+        WHERE t.id = ?
+        "#,
+        transaction_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let amount = existing
+        .amount
+        .parse::<rust_decimal::Decimal>()
+        .map_err(AppError::internal)?;
+
+    let mut tx = pool.begin().await?;
 
     // Delete transaction
+    remove_transaction_from_balances(&mut tx, transaction_id).await?;
     sqlx::query!("DELETE FROM transactions WHERE id = ?", transaction_id)
-        .execute(&pool)
-        .await
-        .map_err(|e| ServerFnError::new(e.to_string()))?;
+        .execute(&mut *tx)
+        .await?;
 
-    Ok(())
+    let currency = group_currency(&mut *tx, group_id).await?;
+
+    log_group_activity(
+        &mut *tx,
+        group_id,
+        user_id,
+        ActivityEntity::Transaction,
+        transaction_id,
+        ActivityAction::Deleted,
+        &payment_summary(amount, &existing.recipient_username, &currency),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(group_id)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_guessed_ids_of_other_groups_are_not_found() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // mallory is only in group 2 and paid bob there
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('mallory', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1), ('Trip', 3)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (2, 2), (2, 3)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 1, 2, '20'), (2, 3, 2, '5')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        // Neither through their own group nor the victim's
+        for group_id in [1, 2] {
+            let err = remove_transaction(&pool, group_id, 1, 3).await.unwrap_err();
+            assert!(matches!(err, AppError::NotFound(_)));
+        }
+        // Their own payment can't be deleted through another group either
+        let err = remove_transaction(&pool, 1, 2, 3).await.unwrap_err();
+        assert!(matches!(err, AppError::NotFound(_)));
+
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 2);
+
+        // Members other than the payer may know it exists
+        let err = remove_transaction(&pool, 1, 1, 2).await.unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(_)));
+
+        assert_eq!(remove_transaction(&pool, 2, 2, 3).await.unwrap(), 2);
+    }
 }
@@ -3,14 +3,18 @@
 //! This module contains all server-side handlers for transaction operations.
 
 mod calculations;
+mod confirm;
 mod create;
 mod delete;
 mod query;
+mod settle_all;
 mod update;
 
 // Re-export all server functions
 pub use calculations::*;
+pub use confirm::*;
 pub use create::*;
 pub use delete::*;
 pub use query::*;
+pub use settle_all::*;
 pub use update::*;
@@ -4,46 +4,36 @@ use leptos::prelude::*;
 #[cfg(feature = "ssr")]
 use leptos_axum::extract;
 #[cfg(feature = "ssr")]
+use rust_decimal::Decimal;
+#[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::transactions::models::TransactionWithDetails;
+use crate::features::transactions::models::{Transaction, TransactionPage};
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session, transactions::utils::find_member_transaction,
+};
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
-use crate::features::transactions::models::{Transaction, TransactionWithDetails};
+use crate::validation::rules::{check_date_filter, check_page};
 
 /// Get a single transaction by ID
 #[server(GetTransaction)]
-pub async fn get_transaction(
-    group_id: i64,
-    transaction_id: i64,
-) -> Result<Transaction, ServerFnError> {
+pub async fn get_transaction(group_id: i64, transaction_id: i64) -> Result<Transaction, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
-    // Check user is member of group
-    let is_member = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
-        group_id,
-        user.id
-    )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
-
-    if is_member == 0 {
-        return Err(ServerFnError::new(
-            "You are not a member of this group".to_string(),
-        ));
-    }
+    // Transactions of groups the user isn't in are reported as not found
+    find_member_transaction(&pool, group_id, transaction_id, user.id).await?;
 
-    // Fetch transaction
     let record = sqlx::query!(
         r#"
         SELECT 
@@ -53,44 +43,66 @@ pub async fn get_transaction(
             recipient_id as "recipient_id!",
             amount,
             description,
+            version,
             created_at,
             updated_at
         FROM transactions
-        WHERE id = ? AND group_id = ?
+        WHERE id = ?
         "#,
-        transaction_id,
-        group_id
+        transaction_id
     )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .ok_or_else(|| ServerFnError::new("Transaction not found".to_string()))?;
+    .fetch_one(&pool)
+    .await?;
+
+    let amount = parse_amount(record.id, &record.amount)?;
 
     Ok(Transaction {
         id: record.id,
         group_id: record.group_id,
         payer_id: record.payer_id,
         recipient_id: record.recipient_id,
-        amount: record.amount,
+        amount,
         description: record.description,
         created_at: record.created_at,
         updated_at: record.updated_at,
+        version: record.version,
     })
 }
 
-/// Get all transactions for a group
+/// Get a page of a group's transactions, newest first
+///
+/// The date filter applies to the total count as well. Balances are computed
+/// separately and always cover every row.
 #[server(GetGroupTransactions)]
 pub async fn get_group_transactions(
     group_id: i64,
-) -> Result<Vec<TransactionWithDetails>, ServerFnError> {
+    /// Rows per page; 25 when omitted
+    #[server(default)]
+    limit: Option<i64>,
+    /// Rows to skip, counted from the newest
+    #[server(default)]
+    offset: Option<i64>,
+    /// Earliest creation date to include (YYYY-MM-DD)
+    #[server(default)]
+    from_date: Option<String>,
+    /// Latest creation date to include (YYYY-MM-DD)
+    #[server(default)]
+    to_date: Option<String>,
+) -> Result<TransactionPage, AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    let (limit, offset) = check_page(limit, offset).map_err(AppError::Validation)?;
+    let from_date = check_date_filter(from_date.as_deref(), "start date")
+        .map_err(AppError::Validation)?
+        .map(|d| d.to_string());
+    let to_date = check_date_filter(to_date.as_deref(), "end date")
+        .map_err(AppError::Validation)?
+        .map(|d| d.to_string());
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
@@ -101,15 +113,38 @@ pub async fn get_group_transactions(
         user.id
     )
     .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
     if is_member == 0 {
-        return Err(ServerFnError::new(
+        return Err(AppError::forbidden(
             "You are not a member of this group".to_string(),
         ));
     }
 
+    load_transaction_page(
+        &pool,
+        group_id,
+        limit,
+        offset,
+        from_date.as_deref(),
+        to_date.as_deref(),
+    )
+    .await
+}
+
+/// Load a page of a group's transactions
+///
+/// Takes filters already checked by [`get_group_transactions`]. Callers are
+/// responsible for verifying that the user is a member of the group.
+#[cfg(feature = "ssr")]
+pub async fn load_transaction_page(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    limit: i64,
+    offset: i64,
+    from_date: Option<&str>,
+    to_date: Option<&str>,
+) -> Result<TransactionPage, AppError> {
     // Fetch transactions with payer and recipient usernames
     let records = sqlx::query!(
         r#"
@@ -122,33 +157,82 @@ pub async fn get_group_transactions(
             recipient.username as recipient_username,
             t.amount,
             t.description,
+            t.version,
+            t.confirmation_status,
+            t.dispute_reason,
             t.created_at,
             t.updated_at
         FROM transactions t
         JOIN users payer ON t.payer_id = payer.id
         JOIN users recipient ON t.recipient_id = recipient.id
         WHERE t.group_id = ?
-        ORDER BY t.created_at DESC
+          AND (? IS NULL OR date(t.created_at) >= ?)
+          AND (? IS NULL OR date(t.created_at) <= ?)
+        ORDER BY t.created_at DESC, t.id DESC
+        LIMIT ? OFFSET ?
         "#,
-        group_id
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date,
+        limit,
+        offset
     )
-    .fetch_all(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .fetch_all(pool)
+    .await?;
 
-    Ok(records
+    let total_count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM transactions t
+        WHERE t.group_id = ?
+          AND (? IS NULL OR date(t.created_at) >= ?)
+          AND (? IS NULL OR date(t.created_at) <= ?)
+        "#,
+        group_id,
+        from_date,
+        from_date,
+        to_date,
+        to_date
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let transactions = records
         .into_iter()
-        .map(|r| TransactionWithDetails {
-            id: r.id,
-            group_id: r.group_id,
-            payer_id: r.payer_id,
-            payer_username: r.payer_username,
-            recipient_id: r.recipient_id,
-            recipient_username: r.recipient_username,
-            amount: r.amount,
-            description: r.description,
-            created_at: r.created_at,
-            updated_at: r.updated_at,
+        .map(|r| {
+            Ok(TransactionWithDetails {
+                id: r.id,
+                group_id: r.group_id,
+                payer_id: r.payer_id,
+                payer_username: r.payer_username,
+                recipient_id: r.recipient_id,
+                recipient_username: r.recipient_username,
+                amount: parse_amount(r.id, &r.amount)?,
+                description: r.description,
+                created_at: r.created_at,
+                updated_at: r.updated_at,
+                version: r.version,
+                confirmation_status: r.confirmation_status.parse().map_err(AppError::internal)?,
+                dispute_reason: r.dispute_reason,
+            })
         })
-        .collect())
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    Ok(TransactionPage {
+        transactions,
+        total_count,
+    })
+}
+
+/// Parse a stored transaction amount, failing loudly on malformed data
+#[cfg(feature = "ssr")]
+fn parse_amount(transaction_id: i64, amount: &str) -> Result<Decimal, AppError> {
+    amount.parse::<Decimal>().map_err(|e| {
+        AppError::internal(format!(
+            "Invalid amount stored for transaction {}: {}",
+            transaction_id, e
+        ))
+    })
 }
@@ -0,0 +1,255 @@
+//! Settling everything a member owes in one go
+
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use crate::error::AppError;
+use crate::features::transactions::models::RecordedSettlement;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{ensure_group_writable, group_currency, log_group_activity, payment_summary},
+    },
+    notifications::{NotificationPayload, notify_users},
+    transactions::{
+        balances::add_transaction_to_balances, handlers::compute_group_balances,
+        models::RelationshipType,
+    },
+    webhooks::{WebhookDispatcher, WebhookEvent},
+};
+
+/// Description of the payments recorded by [`settle_all_my_debts`]
+pub const SETTLEMENT_DESCRIPTION: &str = "Settlement";
+
+/// Server function: Record a payment to every member the current user owes
+///
+/// Either every payment is recorded or, if one of them fails, none are.
+#[server(SettleAllMyDebts)]
+pub async fn settle_all_my_debts(group_id: i64) -> Result<Vec<RecordedSettlement>, AppError> {
+    use sqlx::SqlitePool;
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or(AppError::Unauthorized)?;
+
+    let pool = expect_context::<SqlitePool>();
+
+    let settlements = record_settlements(&pool, group_id, user.id, &user.username).await?;
+
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, group_id, GroupEvent::TransactionsChanged);
+
+    let webhooks = expect_context::<WebhookDispatcher>();
+    for settlement in &settlements {
+        webhooks.dispatch(
+            group_id,
+            WebhookEvent::TransactionCreated,
+            serde_json::json!({
+                "id": settlement.transaction_id,
+                "amount": settlement.amount.to_string(),
+                "description": SETTLEMENT_DESCRIPTION,
+                "payer": user.username,
+                "recipient": settlement.recipient_username,
+            }),
+        );
+    }
+
+    Ok(settlements)
+}
+
+/// Insert one payment per member `user_id` owes, in a single transaction
+#[cfg(feature = "ssr")]
+async fn record_settlements(
+    pool: &sqlx::SqlitePool,
+    group_id: i64,
+    user_id: i64,
+    username: &str,
+) -> Result<Vec<RecordedSettlement>, AppError> {
+    let is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        user_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if is_member == 0 {
+        return Err(AppError::forbidden("You are not a member of this group"));
+    }
+    ensure_group_writable(pool, group_id, user_id).await?;
+
+    let mut owed = compute_group_balances(pool, group_id)
+        .await?
+        .into_iter()
+        .find(|balance| balance.user_id == user_id)
+        .map(|balance| balance.relationships)
+        .unwrap_or_default();
+    owed.retain(|relationship| matches!(relationship.relationship_type, RelationshipType::Owes));
+    owed.sort_by_key(|relationship| relationship.other_user_id);
+
+    if owed.is_empty() {
+        return Err(AppError::validation("You don't owe anyone in this group"));
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut settlements = Vec::with_capacity(owed.len());
+    let currency = group_currency(&mut *tx, group_id).await?;
+
+    for relationship in owed {
+        let amount = relationship
+            .amount
+            .parse::<rust_decimal::Decimal>()
+            .map_err(AppError::internal)?;
+        let amount_str = amount.to_string();
+
+        let transaction_id = sqlx::query!(
+            r#"
+            INSERT INTO transactions (group_id, payer_id, recipient_id, amount, description)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            group_id,
+            user_id,
+            relationship.other_user_id,
+            amount_str,
+            SETTLEMENT_DESCRIPTION
+        )
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+        add_transaction_to_balances(&mut tx, transaction_id).await?;
+
+        log_group_activity(
+            &mut *tx,
+            group_id,
+            user_id,
+            ActivityEntity::Transaction,
+            transaction_id,
+            ActivityAction::Created,
+            &payment_summary(amount, &relationship.other_username, &currency),
+        )
+        .await?;
+
+        notify_users(
+            &mut tx,
+            group_id,
+            &[relationship.other_user_id],
+            &NotificationPayload::PaymentReceived {
+                transaction_id,
+                amount,
+                paid_by: username.to_string(),
+            },
+        )
+        .await?;
+
+        settlements.push(RecordedSettlement {
+            transaction_id,
+            recipient_id: relationship.other_user_id,
+            recipient_username: relationship.other_username,
+            amount,
+        });
+    }
+
+    tx.commit().await?;
+
+    Ok(settlements)
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use rust_decimal::Decimal;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::features::transactions::{balances::rebuild_group_balances, models::NetType};
+
+    async fn setup() -> sqlx::SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        // carol owes alice 30 and bob 20, and is owed 5 by alice
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x'), ('carol', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (1, 1), (1, 2), (1, 3)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 1, 1, 'Rent', '60')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (1, 1), (1, 3)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 2, 2, 'Food', '40')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (2, 2), (2, 3)",
+            "INSERT INTO shared_debts (group_id, created_by, payer_id, name, amount) VALUES (1, 3, 3, 'Taxi', '10')",
+            "INSERT INTO shared_debt_user (shared_debt_id, user_id) VALUES (3, 1), (3, 3)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        let mut conn = pool.acquire().await.unwrap();
+        rebuild_group_balances(&mut conn, 1).await.unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_settle_all_pays_every_creditor() {
+        let pool = setup().await;
+
+        let settlements = record_settlements(&pool, 1, 3, "carol").await.unwrap();
+        let paid: Vec<(i64, Decimal)> = settlements
+            .iter()
+            .map(|settlement| (settlement.recipient_id, settlement.amount))
+            .collect();
+        assert_eq!(paid, vec![(1, Decimal::from(25)), (2, Decimal::from(20))]);
+
+        let descriptions: Vec<Option<String>> =
+            sqlx::query_scalar("SELECT description FROM transactions WHERE payer_id = 3")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(descriptions, vec![Some("Settlement".to_string()); 2]);
+
+        let balances = compute_group_balances(&pool, 1).await.unwrap();
+        assert!(
+            balances
+                .iter()
+                .all(|balance| balance.net_type == NetType::Neutral)
+        );
+
+        // Nothing is left to settle
+        let err = record_settlements(&pool, 1, 3, "carol").await.unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_settle_all_records_nothing_when_a_payment_fails() {
+        let pool = setup().await;
+
+        // The second payment, to bob, is rejected
+        sqlx::query(
+            "CREATE TRIGGER reject_bob BEFORE INSERT ON transactions WHEN NEW.recipient_id = 2 BEGIN SELECT RAISE(ABORT, 'rejected'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(record_settlements(&pool, 1, 3, "carol").await.is_err());
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM transactions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+        let notifications: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notifications")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(notifications, 0);
+    }
+}
@@ -4,14 +4,32 @@ use leptos::prelude::*;
 #[cfg(feature = "ssr")]
 use leptos_axum::extract;
 #[cfg(feature = "ssr")]
-use rust_decimal::Decimal;
-#[cfg(feature = "ssr")]
 use tower_sessions::Session;
 
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{
+    auth::utils::get_user_from_session,
+    groups::{
+        GroupEventBroadcaster, broadcast_group_event,
+        models::{ActivityAction, ActivityEntity, GroupEvent},
+        utils::{ensure_group_writable, group_currency, log_group_activity, payment_summary},
+    },
+    transactions::{
+        balances::{add_transaction_to_balances, remove_transaction_from_balances},
+        utils::{check_transaction_parties, find_member_transaction},
+    },
+};
 #[cfg(feature = "ssr")]
-use crate::features::auth::utils::get_user_from_session;
+use crate::validation::{
+    rules::{EDIT_CONFLICT_ERROR, MAX_DESCRIPTION_LENGTH},
+    validate_description, validate_money,
+};
 
 /// Update an existing transaction
+///
+/// `version` is the transaction version the editor loaded; the update is
+/// rejected with a conflict error if the transaction was saved since.
 #[server(UpdateTransaction)]
 pub async fn update_transaction(
     group_id: i64,
@@ -19,86 +37,155 @@ pub async fn update_transaction(
     recipient_id: i64,
     amount: String,
     description: Option<String>,
-) -> Result<(), ServerFnError> {
-    use std::str::FromStr;
-
+    version: i64,
+) -> Result<(), AppError> {
     use sqlx::SqlitePool;
 
-    let session = extract::<Session>()
-        .await
-        .map_err(|_| ServerFnError::new("Authentication error"))?;
+    // Validate amount and description
+    let amount_decimal = validate_money(&amount)?;
+    let description = description
+        .map(|description| validate_description(&description, MAX_DESCRIPTION_LENGTH))
+        .transpose()?
+        .filter(|description| !description.is_empty());
+
+    let session = extract::<Session>().await.map_err(AppError::internal)?;
     let user = get_user_from_session(&session)
         .await
-        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+        .ok_or(AppError::Unauthorized)?;
 
     let pool = expect_context::<SqlitePool>();
 
-    // Validate amount
-    let amount_decimal =
-        Decimal::from_str(&amount).map_err(|_| ServerFnError::new("Invalid amount format"))?;
-
-    if amount_decimal <= Decimal::ZERO {
-        return Err(ServerFnError::new("Amount must be greater than 0"));
-    }
-
     // Check transaction exists and user is the payer
-    let existing = sqlx::query!(
-        "SELECT payer_id FROM transactions WHERE id = ? AND group_id = ?",
-        transaction_id,
-        group_id
-    )
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?
-    .ok_or_else(|| ServerFnError::new("Transaction not found"))?;
-
+    let existing = find_member_transaction(&pool, group_id, transaction_id, user.id).await?;
     if existing.payer_id != user.id {
-        return Err(ServerFnError::new(
+        return Err(AppError::forbidden(
             "You can only edit your own transactions",
         ));
     }
+    let group_id = existing.group_id;
+    ensure_group_writable(&pool, group_id, user.id).await?;
+
+    check_transaction_parties(&pool, group_id, user.id, existing.payer_id, recipient_id).await?;
+
+    // Store amount rounded to 2 decimal places
+    let amount_str = amount_decimal.round_dp(2).to_string();
+
+    let recipient = sqlx::query_scalar!("SELECT username FROM users WHERE id = ?", recipient_id)
+        .fetch_one(&pool)
+        .await?;
+
+    let mut tx = pool.begin().await?;
+
+    // Update transaction, swapping its old balance contribution for the new one
+    remove_transaction_from_balances(&mut tx, transaction_id).await?;
+    update_transaction_row(
+        &mut tx,
+        transaction_id,
+        version,
+        recipient_id,
+        &amount_str,
+        description.as_deref(),
+    )
+    .await?;
+    add_transaction_to_balances(&mut tx, transaction_id).await?;
+
+    let currency = group_currency(&mut *tx, group_id).await?;
 
-    // Check recipient is member of group
-    let recipient_is_member = sqlx::query_scalar!(
-        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+    log_group_activity(
+        &mut *tx,
         group_id,
-        recipient_id
+        user.id,
+        ActivityEntity::Transaction,
+        transaction_id,
+        ActivityAction::Updated,
+        &payment_summary(amount_decimal, &recipient, &currency),
     )
-    .fetch_one(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .await?;
 
-    if recipient_is_member == 0 {
-        return Err(ServerFnError::new(
-            "Recipient is not a member of this group",
-        ));
-    }
+    tx.commit().await?;
 
-    // Don't allow self-transactions
-    if user.id == recipient_id {
-        return Err(ServerFnError::new(
-            "Cannot create transaction with yourself",
-        ));
-    }
+    let broadcaster = expect_context::<GroupEventBroadcaster>();
+    broadcast_group_event(&broadcaster, group_id, GroupEvent::TransactionsChanged);
 
-    // Store amount rounded to 2 decimal places
-    let amount_str = amount_decimal.round_dp(2).to_string();
+    Ok(())
+}
 
-    // Update transaction
-    sqlx::query!(
+/// Write a transaction's recipient, amount, description and updated_at
+/// timestamp if it is still at `version`, bumping the version
+///
+/// The edited payment goes back to pending for the recipient to check again.
+#[cfg(feature = "ssr")]
+async fn update_transaction_row(
+    conn: &mut sqlx::SqliteConnection,
+    transaction_id: i64,
+    version: i64,
+    recipient_id: i64,
+    amount: &str,
+    description: Option<&str>,
+) -> Result<(), AppError> {
+    let result = sqlx::query!(
         r#"
         UPDATE transactions
-        SET recipient_id = ?, amount = ?, description = ?, updated_at = CURRENT_TIMESTAMP
-        WHERE id = ?
+        SET recipient_id = ?, amount = ?, description = ?, updated_at = CURRENT_TIMESTAMP,
+            version = version + 1, confirmation_status = 'pending', dispute_reason = NULL
+        WHERE id = ? AND version = ?
         "#,
         recipient_id,
-        amount_str,
+        amount,
         description,
-        transaction_id
+        transaction_id,
+        version
     )
-    .execute(&pool)
-    .await
-    .map_err(|e| ServerFnError::new(e.to_string()))?;
+    .execute(&mut *conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict(EDIT_CONFLICT_ERROR));
+    }
 
     Ok(())
 }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_update_transaction_row_rejects_stale_version() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for statement in [
+            "INSERT INTO users (username, password_hash) VALUES ('alice', 'x'), ('bob', 'x')",
+            "INSERT INTO groups (name, created_by) VALUES ('Flat', 1)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 1, 2, '20')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        // The same payment edited in two tabs, both opened at version 0
+        let mut conn = pool.acquire().await.unwrap();
+        update_transaction_row(&mut conn, 1, 0, 2, "25", Some("Pizza"))
+            .await
+            .unwrap();
+        let err = update_transaction_row(&mut conn, 1, 0, 2, "30", None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(EDIT_CONFLICT_ERROR));
+
+        let (amount, description, version): (String, Option<String>, i64) =
+            sqlx::query_as("SELECT amount, description, version FROM transactions WHERE id = 1")
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        assert_eq!(amount, "25");
+        assert_eq!(description.as_deref(), Some("Pizza"));
+        assert_eq!(version, 1);
+    }
+}
@@ -1,5 +1,7 @@
+pub mod balances;
 pub mod handlers;
 pub mod models;
+pub mod utils;
 
 pub use handlers::*;
 pub use models::*;
@@ -1,3 +1,4 @@
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "ssr")]
 use sqlx::FromRow;
@@ -14,12 +15,72 @@ pub struct Transaction {
     // Note: amount is stored as TEXT in SQLite, so we don't use FromRow
     // and will parse manually in queries
     #[cfg_attr(feature = "ssr", sqlx(skip))]
-    pub amount: String,
+    pub amount: Decimal,
     pub description: Option<String>,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
+    /// Incremented on every edit; updates must pass the version they loaded
+    pub version: i64,
+}
+
+/// Whether the recipient has acknowledged a payment
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConfirmationStatus {
+    /// Not looked at by the recipient yet; counts towards balances
+    #[default]
+    Pending,
+    Confirmed,
+    /// The recipient says the money never arrived; left out of balances
+    Disputed,
+}
+
+impl ConfirmationStatus {
+    /// Value stored in the `confirmation_status` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfirmationStatus::Pending => "pending",
+            ConfirmationStatus::Confirmed => "confirmed",
+            ConfirmationStatus::Disputed => "disputed",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConfirmationStatus::Pending => "Pending",
+            ConfirmationStatus::Confirmed => "Confirmed",
+            ConfirmationStatus::Disputed => "Disputed",
+        }
+    }
+
+    /// Tailwind classes for the status badge
+    pub fn badge_class(self) -> &'static str {
+        match self {
+            ConfirmationStatus::Pending => {
+                "bg-yellow-100 text-yellow-800 dark:bg-yellow-900/30 dark:text-yellow-300"
+            }
+            ConfirmationStatus::Confirmed => {
+                "bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-300"
+            }
+            ConfirmationStatus::Disputed => {
+                "bg-red-100 text-red-800 dark:bg-red-900/30 dark:text-red-300"
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for ConfirmationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ConfirmationStatus::Pending),
+            "confirmed" => Ok(ConfirmationStatus::Confirmed),
+            "disputed" => Ok(ConfirmationStatus::Disputed),
+            _ => Err(format!("Unknown confirmation status: {}", s)),
+        }
+    }
 }
 
 /// Transaction with user details (payer and recipient names)
@@ -31,12 +92,38 @@ pub struct TransactionWithDetails {
     pub payer_username: String,
     pub recipient_id: i64,
     pub recipient_username: String,
-    pub amount: String, // Stored as string to maintain precision
+    pub amount: Decimal, // Serialized as string to maintain precision
     pub description: Option<String>,
     #[serde(with = "time::serde::rfc3339")]
     pub created_at: OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
+    /// Incremented on every edit; updates must pass the version they loaded
+    pub version: i64,
+    pub confirmation_status: ConfirmationStatus,
+    /// Why the recipient disputed the payment
+    pub dispute_reason: Option<String>,
+}
+
+/// A payment from the caller as entered, before it is checked
+///
+/// Mirrors the arguments of
+/// [`create_transaction`](super::handlers::create_transaction) apart from the
+/// group.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NewTransaction {
+    pub recipient_id: i64,
+    pub amount: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// One page of a group's transactions, newest first
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionPage {
+    pub transactions: Vec<TransactionWithDetails>,
+    /// Number of transactions matching the filter across all pages
+    pub total_count: i64,
 }
 
 /// User balance information
@@ -51,6 +138,15 @@ pub struct UserBalance {
     pub net_type: NetType,   // Whether user is net positive, negative, or neutral
 }
 
+/// The current user's net balance in one of their groups
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupNetBalance {
+    pub group_id: i64,
+    pub group_name: String,
+    pub net_amount: String, // Absolute value of net balance
+    pub net_type: NetType,
+}
+
 /// Relationship between two users (one owes the other)
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DebtRelationship {
@@ -72,3 +168,33 @@ pub enum NetType {
     Negative, // User owes more than they are owed
     Neutral,  // User is balanced
 }
+
+impl NetType {
+    /// Signed value of an absolute net amount, negative when the user owes
+    pub fn signed_amount(&self, net_amount: &str) -> Decimal {
+        let amount = net_amount.parse::<Decimal>().unwrap_or_default();
+        match self {
+            NetType::Negative => -amount,
+            NetType::Positive | NetType::Neutral => amount,
+        }
+    }
+}
+
+/// A payment recorded when a member settles everything they owe at once
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RecordedSettlement {
+    pub transaction_id: i64,
+    pub recipient_id: i64,
+    pub recipient_username: String,
+    pub amount: Decimal,
+}
+
+/// A payment that helps settle a group's balances
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SettlementSuggestion {
+    pub from_user_id: i64,
+    pub from_username: String,
+    pub to_user_id: i64,
+    pub to_username: String,
+    pub amount: Decimal,
+}
@@ -0,0 +1,213 @@
+//! Checks shared by the transaction handlers
+
+#[cfg(feature = "ssr")]
+use sqlx::SqlitePool;
+
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+
+/// Group and parties of a stored transaction
+#[cfg(feature = "ssr")]
+#[derive(Debug)]
+pub struct TransactionAccess {
+    pub group_id: i64,
+    pub payer_id: i64,
+    pub recipient_id: i64,
+}
+
+/// Look up a transaction on behalf of `user_id`, who must be a member of the
+/// group it belongs to
+///
+/// The group is read from the transaction row itself. A transaction of
+/// another group than `group_id`, or of a group the user isn't in, is
+/// reported as not found, so guessing ids reveals nothing.
+#[cfg(feature = "ssr")]
+pub async fn find_member_transaction(
+    executor: impl sqlx::SqliteExecutor<'_>,
+    group_id: i64,
+    transaction_id: i64,
+    user_id: i64,
+) -> Result<TransactionAccess, AppError> {
+    sqlx::query_as!(
+        TransactionAccess,
+        r#"
+        SELECT t.group_id as "group_id!", t.payer_id as "payer_id!", t.recipient_id as "recipient_id!"
+        FROM transactions t
+        INNER JOIN group_members gm ON gm.group_id = t.group_id AND gm.user_id = ?
+        WHERE t.id = ?
+        "#,
+        user_id,
+        transaction_id
+    )
+    .fetch_optional(executor)
+    .await?
+    .filter(|transaction| transaction.group_id == group_id)
+    .ok_or_else(|| AppError::not_found("Transaction not found"))
+}
+
+/// Check who a transaction is between before it is written
+///
+/// The payer and recipient must be two different members of the group, and
+/// the session user must be one of them unless they created the group.
+#[cfg(feature = "ssr")]
+pub async fn check_transaction_parties(
+    pool: &SqlitePool,
+    group_id: i64,
+    session_user_id: i64,
+    payer_id: i64,
+    recipient_id: i64,
+) -> Result<(), AppError> {
+    if payer_id == recipient_id {
+        return Err(AppError::validation(
+            "The payer and recipient must be different people",
+        ));
+    }
+
+    let payer_is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        payer_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if payer_is_member == 0 {
+        return Err(AppError::validation("The payer is not part of this group"));
+    }
+
+    let recipient_is_member = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_members WHERE group_id = ? AND user_id = ?",
+        group_id,
+        recipient_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if recipient_is_member == 0 {
+        return Err(AppError::validation(
+            "Recipient is not a member of this group",
+        ));
+    }
+
+    if session_user_id != payer_id && session_user_id != recipient_id {
+        let is_creator = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM groups WHERE id = ? AND created_by = ?",
+            group_id,
+            session_user_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if is_creator == 0 {
+            return Err(AppError::forbidden(
+                "Only the group creator can record transactions between other members",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    /// Group 1 created by user 1 with members 1, 2 and 3; user 4 is outside
+    async fn setup() -> SqlitePool {
+        // One connection so every query sees the same in-memory database
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+        for username in ["alice", "bob", "carol", "dave"] {
+            sqlx::query("INSERT INTO users (username, password_hash) VALUES (?, 'x')")
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO groups (name, created_by) VALUES ('Flat', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for user_id in [1, 2, 3] {
+            sqlx::query("INSERT INTO group_members (group_id, user_id) VALUES (1, ?)")
+                .bind(user_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        pool
+    }
+
+    async fn message(pool: &SqlitePool, session: i64, payer: i64, recipient: i64) -> String {
+        check_transaction_parties(pool, 1, session, payer, recipient)
+            .await
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_valid_parties() {
+        let pool = setup().await;
+        assert!(check_transaction_parties(&pool, 1, 2, 2, 3).await.is_ok());
+        assert!(check_transaction_parties(&pool, 1, 3, 2, 3).await.is_ok());
+        // The creator may record on behalf of others
+        assert!(check_transaction_parties(&pool, 1, 1, 2, 3).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_same_payer_and_recipient() {
+        let pool = setup().await;
+        assert!(message(&pool, 2, 2, 2).await.contains("must be different people"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_payer_outside_group() {
+        let pool = setup().await;
+        assert!(message(&pool, 4, 4, 2).await.contains("payer is not part"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_recipient_outside_group() {
+        let pool = setup().await;
+        assert!(message(&pool, 2, 2, 4).await.contains("Recipient is not a member"));
+    }
+
+    #[tokio::test]
+    async fn test_find_member_transaction_hides_other_groups() {
+        let pool = setup().await;
+        for statement in [
+            "INSERT INTO groups (name, created_by) VALUES ('Trip', 4)",
+            "INSERT INTO group_members (group_id, user_id) VALUES (2, 3), (2, 4)",
+            "INSERT INTO transactions (group_id, payer_id, recipient_id, amount) VALUES (1, 2, 3, '10'), (2, 4, 3, '5')",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        let transaction = find_member_transaction(&pool, 1, 1, 3).await.unwrap();
+        assert_eq!(transaction.group_id, 1);
+        assert_eq!((transaction.payer_id, transaction.recipient_id), (2, 3));
+
+        // Another group's transaction, asked for through either group, and a
+        // missing one all look the same
+        for (group_id, transaction_id, user_id) in [(1, 2, 1), (2, 2, 1), (2, 1, 4), (1, 99, 1)] {
+            let err = find_member_transaction(&pool, group_id, transaction_id, user_id)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, AppError::NotFound(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_uninvolved_non_creator() {
+        let pool = setup().await;
+        assert!(message(&pool, 3, 1, 2).await.contains("Only the group creator"));
+    }
+}
@@ -0,0 +1,334 @@
+//! Delivering webhooks
+//!
+//! Server functions hand events to a [`WebhookDispatcher`], which only queues
+//! them on a channel. A background worker looks up the subscribed webhooks and
+//! delivers to each in its own task, so a slow or failing endpoint never
+//! delays or fails the request that caused the event.
+
+use std::{net::SocketAddr, time::Duration};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio::sync::mpsc;
+
+use super::models::{WebhookEvent, parse_events};
+use crate::validation::rules::{PRIVATE_WEBHOOK_HOST, is_public_ip, webhook_url_host};
+
+/// Header carrying `sha256=<hex HMAC of the body>`
+pub const SIGNATURE_HEADER: &str = "X-Splitify-Signature";
+
+/// Header carrying the event name, e.g. `debt.created`
+pub const EVENT_HEADER: &str = "X-Splitify-Event";
+
+/// Retries after a failed delivery before giving up
+const MAX_RETRIES: u32 = 3;
+
+/// Wait before the first retry; doubled for every further one
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Time an endpoint gets to respond to a single attempt
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An event waiting to be delivered to a group's webhooks
+#[derive(Debug)]
+struct WebhookJob {
+    group_id: i64,
+    event: WebhookEvent,
+    data: serde_json::Value,
+    occurred_at: OffsetDateTime,
+}
+
+/// Handle for queueing webhook deliveries, provided as context
+#[derive(Clone, Debug)]
+pub struct WebhookDispatcher {
+    tx: mpsc::UnboundedSender<WebhookJob>,
+}
+
+impl WebhookDispatcher {
+    /// Queue an event for the group's webhooks subscribed to it
+    ///
+    /// Returns immediately. Call it after the change was committed so
+    /// endpoints never hear about rolled back events.
+    pub fn dispatch(&self, group_id: i64, event: WebhookEvent, data: serde_json::Value) {
+        let job = WebhookJob {
+            group_id,
+            event,
+            data,
+            occurred_at: OffsetDateTime::now_utc(),
+        };
+        if self.tx.send(job).is_err() {
+            tracing::warn!(
+                group_id = group_id,
+                event = %event,
+                "Webhook worker is not running, dropping event"
+            );
+        }
+    }
+}
+
+/// Start the webhook worker and return a dispatcher feeding it
+///
+/// Must be called from within a tokio runtime.
+pub fn create_webhook_dispatcher(pool: SqlitePool) -> WebhookDispatcher {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(run_worker(pool, rx));
+    WebhookDispatcher { tx }
+}
+
+/// Resolve a webhook URL's host and make sure every address it points at is
+/// public, so webhooks cannot be used to reach the server's own network
+///
+/// Returns the host with the vetted addresses, so a delivery can connect to
+/// exactly those instead of resolving the name a second time.
+pub async fn check_webhook_host(url: &str) -> Result<(String, Vec<SocketAddr>), String> {
+    let (host, port) =
+        webhook_url_host(url).ok_or_else(|| "Webhook URL is not valid".to_string())?;
+    let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|_| format!("Could not resolve {}", host))?
+        .collect();
+
+    if addrs.is_empty() {
+        return Err(format!("Could not resolve {}", host));
+    }
+    if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(PRIVATE_WEBHOOK_HOST.to_string());
+    }
+
+    Ok((host, addrs))
+}
+
+/// Client for a single delivery attempt, connecting only to vetted addresses
+fn pinned_client(host: &str, addrs: &[SocketAddr]) -> reqwest::Result<reqwest::Client> {
+    // Redirects are not followed, they could lead to a private address
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve_to_addrs(host, addrs)
+        .build()
+}
+
+async fn run_worker(pool: SqlitePool, mut rx: mpsc::UnboundedReceiver<WebhookJob>) {
+    while let Some(job) = rx.recv().await {
+        let webhooks = match sqlx::query!(
+            r#"SELECT id as "id!", url, secret, events FROM group_webhooks WHERE group_id = ?"#,
+            job.group_id
+        )
+        .fetch_all(&pool)
+        .await
+        {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::error!(error = %e, group_id = job.group_id, "Failed to load webhooks");
+                continue;
+            }
+        };
+
+        let subscribed: Vec<_> = webhooks
+            .into_iter()
+            .filter(|webhook| parse_events(&webhook.events).contains(&job.event))
+            .collect();
+        if subscribed.is_empty() {
+            continue;
+        }
+
+        let body = webhook_body(job.group_id, job.event, job.data, job.occurred_at).to_string();
+        for webhook in subscribed {
+            tokio::spawn(deliver(
+                pool.clone(),
+                webhook.id,
+                webhook.url,
+                webhook.secret,
+                job.event,
+                body.clone(),
+            ));
+        }
+    }
+}
+
+/// JSON body sent for an event
+pub fn webhook_body(
+    group_id: i64,
+    event: WebhookEvent,
+    data: serde_json::Value,
+    occurred_at: OffsetDateTime,
+) -> serde_json::Value {
+    let occurred_at = occurred_at
+        .format(&time::format_description::well_known::Rfc3339)
+        .unwrap_or_default();
+    serde_json::json!({
+        "event": event.as_str(),
+        "group_id": group_id,
+        "occurred_at": occurred_at,
+        "data": data,
+    })
+}
+
+/// Hex encoded HMAC-SHA256 of a body, keyed with the webhook's secret
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Result of the last attempt at delivering to a webhook
+struct DeliveryOutcome {
+    status_code: Option<i64>,
+    error: Option<String>,
+}
+
+async fn deliver(
+    pool: SqlitePool,
+    webhook_id: i64,
+    url: String,
+    secret: String,
+    event: WebhookEvent,
+    body: String,
+) {
+    let signature = format!("sha256={}", sign_payload(&secret, body.as_bytes()));
+    let mut backoff = INITIAL_BACKOFF;
+    let mut retries = 0;
+
+    let outcome = loop {
+        // Resolved again for every attempt, the host may have moved since the
+        // webhook was saved. The request then goes to the vetted addresses
+        // rather than letting the client resolve the name on its own.
+        let client = check_webhook_host(&url)
+            .await
+            .and_then(|(host, addrs)| pinned_client(&host, &addrs).map_err(|e| e.to_string()));
+        let outcome = match client {
+            Err(error) => DeliveryOutcome {
+                status_code: None,
+                error: Some(error),
+            },
+            Ok(client) => match client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(EVENT_HEADER, event.as_str())
+                .body(body.clone())
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    let status = response.status();
+                    DeliveryOutcome {
+                        status_code: Some(i64::from(status.as_u16())),
+                        error: (!status.is_success())
+                            .then(|| format!("Endpoint responded {}", status)),
+                    }
+                }
+                Err(e) => DeliveryOutcome {
+                    status_code: None,
+                    error: Some(e.to_string()),
+                },
+            },
+        };
+
+        if outcome.error.is_none() || retries == MAX_RETRIES {
+            break outcome;
+        }
+
+        retries += 1;
+        tracing::debug!(
+            webhook_id = webhook_id,
+            retry = retries,
+            error = outcome.error.as_deref().unwrap_or_default(),
+            "Webhook delivery failed, retrying"
+        );
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    };
+
+    if let Some(error) = &outcome.error {
+        tracing::warn!(
+            webhook_id = webhook_id,
+            event = %event,
+            error = %error,
+            "Giving up on webhook delivery"
+        );
+    }
+
+    if let Err(e) = record_delivery(&pool, webhook_id, &outcome).await {
+        tracing::warn!(error = %e, webhook_id = webhook_id, "Failed to record webhook delivery");
+    }
+}
+
+async fn record_delivery(
+    pool: &SqlitePool,
+    webhook_id: i64,
+    outcome: &DeliveryOutcome,
+) -> Result<(), sqlx::Error> {
+    let delivered_at = OffsetDateTime::now_utc();
+    sqlx::query!(
+        r#"
+        UPDATE group_webhooks
+        SET last_delivery_at = ?, last_status_code = ?, last_error = ?
+        WHERE id = ?
+        "#,
+        delivered_at,
+        outcome.status_code,
+        outcome.error,
+        webhook_id
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_payload() {
+        // Well-known vector from the HMAC article on Wikipedia
+        assert_eq!(
+            sign_payload("key", b"The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_webhook_host_rejects_private_addresses() {
+        for url in [
+            "http://localhost:8080/hook",
+            "http://127.0.0.1/hook",
+            "http://[::1]/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.10/hook",
+            // Forms a resolver accepts but the URL check does not recognise
+            "http://127.1/hook",
+            "http://2130706433/hook",
+        ] {
+            assert!(check_webhook_host(url).await.is_err(), "{url}");
+        }
+
+        check_webhook_host("https://93.184.216.34/hook")
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_webhook_body() {
+        let body = webhook_body(
+            7,
+            WebhookEvent::DebtDeleted,
+            serde_json::json!({ "id": 3 }),
+            OffsetDateTime::UNIX_EPOCH,
+        );
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "event": "debt.deleted",
+                "group_id": 7,
+                "occurred_at": "1970-01-01T00:00:00Z",
+                "data": { "id": 3 },
+            })
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use leptos::prelude::*;
+#[cfg(feature = "ssr")]
+use leptos_axum::extract;
+#[cfg(feature = "ssr")]
+use tower_sessions::Session;
+
+use super::models::GroupWebhook;
+#[cfg(feature = "ssr")]
+use super::models::{WebhookEvent, join_events};
+#[cfg(feature = "ssr")]
+use crate::error::AppError;
+#[cfg(feature = "ssr")]
+use crate::features::{auth::utils::get_user_from_session, groups::utils::is_group_admin};
+
+/// Most webhooks a single group may have
+#[cfg(feature = "ssr")]
+const MAX_WEBHOOKS_PER_GROUP: i64 = 10;
+
+/// Check that the current user is an admin of the group and return their id
+#[cfg(feature = "ssr")]
+async fn require_group_admin(pool: &sqlx::SqlitePool, group_id: i64) -> Result<i64, ServerFnError> {
+    let session = extract::<Session>()
+        .await
+        .map_err(|_| ServerFnError::new("Authentication error"))?;
+
+    let user = get_user_from_session(&session)
+        .await
+        .ok_or_else(|| ServerFnError::new("Not authenticated"))?;
+
+    if !is_group_admin(pool, group_id, user.id).await? {
+        return Err(ServerFnError::new(
+            "Unauthorized: Only group admins can manage webhooks",
+        ));
+    }
+
+    Ok(user.id)
+}
+
+/// Parse the submitted event names, requiring at least one
+#[cfg(feature = "ssr")]
+fn parse_event_names(events: &[String]) -> Result<String, ServerFnError> {
+    let events = events
+        .iter()
+        .map(|name| name.parse::<WebhookEvent>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(ServerFnError::new)?;
+
+    if events.is_empty() {
+        return Err(ServerFnError::new("Select at least one event"));
+    }
+
+    Ok(join_events(&events))
+}
+
+/// Group a webhook belongs to
+#[cfg(feature = "ssr")]
+async fn webhook_group_id(pool: &sqlx::SqlitePool, webhook_id: i64) -> Result<i64, ServerFnError> {
+    sqlx::query_scalar!(
+        "SELECT group_id FROM group_webhooks WHERE id = ?",
+        webhook_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .ok_or_else(|| ServerFnError::new("Webhook not found"))
+}
+
+/// Server function: Get the webhooks of a group (group admins only)
+#[server(GetGroupWebhooks)]
+pub async fn get_group_webhooks(group_id: i64) -> Result<Vec<GroupWebhook>, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use super::models::parse_events;
+
+    let pool = expect_context::<SqlitePool>();
+    require_group_admin(&pool, group_id).await?;
+
+    let rows = sqlx::query!(
+        r#"
+        SELECT
+            id as "id!",
+            group_id,
+            url,
+            secret,
+            events,
+            last_delivery_at as "last_delivery_at: time::OffsetDateTime",
+            last_status_code,
+            last_error,
+            created_at as "created_at!: time::OffsetDateTime"
+        FROM group_webhooks
+        WHERE group_id = ?
+        ORDER BY created_at, id
+        "#,
+        group_id
+    )
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| GroupWebhook {
+            id: row.id,
+            group_id: row.group_id,
+            url: row.url,
+            secret: row.secret,
+            events: parse_events(&row.events),
+            last_delivery_at: row.last_delivery_at,
+            last_status_code: row.last_status_code,
+            last_error: row.last_error,
+            created_at: row.created_at,
+        })
+        .collect())
+}
+
+/// Server function: Add a webhook to a group (group admins only)
+///
+/// A random signing secret is generated for it.
+#[server(CreateGroupWebhook)]
+pub async fn create_group_webhook(
+    group_id: i64,
+    url: String,
+    /// Names of the subscribed events, e.g. `debt.created`
+    #[server(default)]
+    events: Vec<String>,
+) -> Result<i64, ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use super::dispatcher::check_webhook_host;
+    use crate::validation::rules::check_webhook_url;
+
+    let url = check_webhook_url(&url).map_err(ServerFnError::new)?;
+    let events = parse_event_names(&events)?;
+
+    let pool = expect_context::<SqlitePool>();
+    let user_id = require_group_admin(&pool, group_id).await?;
+    check_webhook_host(&url).await.map_err(ServerFnError::new)?;
+
+    let count = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM group_webhooks WHERE group_id = ?",
+        group_id
+    )
+    .fetch_one(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    if count >= MAX_WEBHOOKS_PER_GROUP {
+        return Err(ServerFnError::new(format!(
+            "A group can have at most {} webhooks",
+            MAX_WEBHOOKS_PER_GROUP
+        )));
+    }
+
+    let secret = uuid::Uuid::new_v4().simple().to_string();
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO group_webhooks (group_id, url, secret, events, created_by)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        group_id,
+        url,
+        secret,
+        events,
+        user_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?
+    .last_insert_rowid();
+
+    Ok(id)
+}
+
+/// Server function: Change a webhook's URL and events (group admins only)
+#[server(UpdateGroupWebhook)]
+pub async fn update_group_webhook(
+    webhook_id: i64,
+    url: String,
+    /// Names of the subscribed events, e.g. `debt.created`
+    #[server(default)]
+    events: Vec<String>,
+) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    use super::dispatcher::check_webhook_host;
+    use crate::validation::rules::check_webhook_url;
+
+    let url = check_webhook_url(&url).map_err(ServerFnError::new)?;
+    let events = parse_event_names(&events)?;
+
+    let pool = expect_context::<SqlitePool>();
+    let group_id = webhook_group_id(&pool, webhook_id).await?;
+    require_group_admin(&pool, group_id).await?;
+    check_webhook_host(&url).await.map_err(ServerFnError::new)?;
+
+    sqlx::query!(
+        "UPDATE group_webhooks SET url = ?, events = ? WHERE id = ?",
+        url,
+        events,
+        webhook_id
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(())
+}
+
+/// Server function: Remove a webhook (group admins only)
+#[server(DeleteGroupWebhook)]
+pub async fn delete_group_webhook(webhook_id: i64) -> Result<(), ServerFnError> {
+    use sqlx::SqlitePool;
+
+    let pool = expect_context::<SqlitePool>();
+    let group_id = webhook_group_id(&pool, webhook_id).await?;
+    require_group_admin(&pool, group_id).await?;
+
+    sqlx::query!("DELETE FROM group_webhooks WHERE id = ?", webhook_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| ServerFnError::new(AppError::internal(e)))?;
+
+    Ok(())
+}
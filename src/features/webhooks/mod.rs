@@ -0,0 +1,12 @@
+#[cfg(feature = "ssr")]
+pub mod dispatcher;
+pub mod handlers;
+pub mod models;
+
+#[cfg(feature = "ssr")]
+pub use dispatcher::{WebhookDispatcher, create_webhook_dispatcher};
+pub use handlers::{
+    CreateGroupWebhook, DeleteGroupWebhook, GetGroupWebhooks, UpdateGroupWebhook,
+    create_group_webhook, delete_group_webhook, get_group_webhooks, update_group_webhook,
+};
+pub use models::{GroupWebhook, WebhookEvent};
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Group events a webhook can subscribe to
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    DebtCreated,
+    DebtDeleted,
+    TransactionCreated,
+    RecurringDebtGenerated,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 4] = [
+        WebhookEvent::DebtCreated,
+        WebhookEvent::DebtDeleted,
+        WebhookEvent::TransactionCreated,
+        WebhookEvent::RecurringDebtGenerated,
+    ];
+
+    /// Name sent in payloads and stored in the `events` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::DebtCreated => "debt.created",
+            WebhookEvent::DebtDeleted => "debt.deleted",
+            WebhookEvent::TransactionCreated => "transaction.created",
+            WebhookEvent::RecurringDebtGenerated => "recurring_debt.generated",
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            WebhookEvent::DebtCreated => "Debt created",
+            WebhookEvent::DebtDeleted => "Debt deleted",
+            WebhookEvent::TransactionCreated => "Payment recorded",
+            WebhookEvent::RecurringDebtGenerated => "Recurring debt generated",
+        }
+    }
+}
+
+impl std::str::FromStr for WebhookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WebhookEvent::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| format!("Unknown webhook event: {}", s))
+    }
+}
+
+impl std::fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Parse a stored comma-separated event list, skipping unknown names
+pub fn parse_events(events: &str) -> Vec<WebhookEvent> {
+    events
+        .split(',')
+        .filter_map(|name| name.trim().parse().ok())
+        .collect()
+}
+
+/// Join events into the stored comma-separated form, in [`WebhookEvent::ALL`]
+/// order and without duplicates
+pub fn join_events(events: &[WebhookEvent]) -> String {
+    WebhookEvent::ALL
+        .into_iter()
+        .filter(|event| events.contains(event))
+        .map(|event| event.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// A webhook of a group, as shown to its admins
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GroupWebhook {
+    pub id: i64,
+    pub group_id: i64,
+    pub url: String,
+    /// Key of the HMAC-SHA256 signature sent with every delivery
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_delivery_at: Option<OffsetDateTime>,
+    pub last_status_code: Option<i64>,
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl GroupWebhook {
+    /// Whether the last delivery succeeded; `None` before the first one
+    pub fn last_delivery_ok(&self) -> Option<bool> {
+        self.last_delivery_at?;
+        Some(self.last_error.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_names_round_trip() {
+        for event in WebhookEvent::ALL {
+            assert_eq!(event.as_str().parse::<WebhookEvent>(), Ok(event));
+        }
+        assert!("debt.exploded".parse::<WebhookEvent>().is_err());
+    }
+
+    #[test]
+    fn test_join_and_parse_events() {
+        let joined = join_events(&[
+            WebhookEvent::TransactionCreated,
+            WebhookEvent::DebtCreated,
+            WebhookEvent::DebtCreated,
+        ]);
+        assert_eq!(joined, "debt.created,transaction.created");
+        assert_eq!(
+            parse_events("debt.created, unknown ,transaction.created"),
+            vec![WebhookEvent::DebtCreated, WebhookEvent::TransactionCreated]
+        );
+        assert!(parse_events("").is_empty());
+    }
+}
@@ -6,13 +6,17 @@ pub mod pages;
 pub mod validation;
 
 pub mod features {
+    pub mod api;
     pub mod auth;
+    pub mod feature_flags;
     pub mod groups;
     pub mod invites;
+    pub mod notifications;
     pub mod recurring_debts;
     pub mod shared_debts;
     pub mod shopping_lists;
     pub mod transactions;
+    pub mod webhooks;
 }
 
 #[cfg(feature = "ssr")]
@@ -21,7 +25,6 @@ pub mod db;
 #[cfg(feature = "ssr")]
 pub mod session_store;
 
-#[cfg(feature = "ssr")]
 pub mod error;
 
 #[cfg(feature = "hydrate")]
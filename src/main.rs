@@ -6,9 +6,13 @@ async fn main() {
     use std::convert::Infallible;
 
     use axum::{
-        Router,
-        extract::{Path, State},
-        response::sse::{Event, KeepAlive, Sse},
+        Json, Router,
+        extract::{Path, Query, State},
+        http::{StatusCode, header},
+        response::{
+            IntoResponse, Response,
+            sse::{Event, KeepAlive, Sse},
+        },
         routing::get,
     };
     use futures::stream::Stream;
@@ -16,10 +20,29 @@ async fn main() {
     use leptos_axum::{LeptosRoutes, generate_route_list};
     use rustify_app::{
         app::*,
-        db::init_db,
+        db::{
+            MaintenanceConfig, init_db, normalize_transaction_amounts, run_maintenance,
+            scan_invalid_amounts, seed_demo_data, seed_requested,
+        },
         features::{
-            recurring_debts::handlers::scheduler::process_due_recurring_debts_internal,
-            shopping_lists::{EventBroadcaster, create_broadcaster},
+            api::{ApiState, api_router},
+            auth::utils::load_session_user,
+            groups::{
+                ExportFormat, GroupEventBroadcaster, create_group_broadcaster,
+                events::get_or_create_group_channel,
+                handlers::{render_group_backup, render_group_export},
+                scheduler::generate_weekly_summaries_internal,
+            },
+            recurring_debts::handlers::{
+                render_calendar_feed,
+                scheduler::{
+                    process_due_recurring_debts_internal, recurring_debts_cron_expression,
+                },
+            },
+            shopping_lists::{
+                EventBroadcaster, broadcaster_stats, cleanup_inactive_channels, create_broadcaster,
+            },
+            webhooks::create_webhook_dispatcher,
         },
         session_store::SqliteStore,
     };
@@ -56,6 +79,31 @@ async fn main() {
 
     tracing::info!("Database initialized successfully");
 
+    // Demo users and a group to click through, see `cargo run -- --seed`
+    if seed_requested() {
+        let group_id = seed_demo_data(&pool)
+            .await
+            .expect("FATAL: Failed to seed demo data");
+        tracing::info!(group_id = group_id, "Seeded demo data");
+    }
+
+    // Clean up amounts stored with stray whitespace or decimal commas
+    match normalize_transaction_amounts(&pool).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count = count, "Normalized stored transaction amounts"),
+        Err(e) => tracing::error!(error = %e, "Failed to normalize stored amounts"),
+    }
+
+    // Surface malformed amounts early instead of letting balances silently disagree
+    match scan_invalid_amounts(&pool).await {
+        Ok(0) => tracing::debug!("All stored amounts are valid decimals"),
+        Ok(count) => tracing::warn!(
+            count = count,
+            "Found stored amounts that are not valid decimals"
+        ),
+        Err(e) => tracing::error!(error = %e, "Failed to scan stored amounts"),
+    }
+
     // Setup session store
     let session_store = SqliteStore::new(pool.clone());
     session_store
@@ -113,8 +161,7 @@ async fn main() {
     // Cron expression can be configured via RECURRING_DEBTS_CRON environment
     // variable Default: "0 0 6 * * *" (daily at 6:00 AM)
     // Format: sec min hour day_of_month month day_of_week
-    let cron_expression =
-        std::env::var("RECURRING_DEBTS_CRON").unwrap_or_else(|_| "0 0 6 * * *".to_string());
+    let cron_expression = recurring_debts_cron_expression();
 
     tracing::info!(
         cron_expression = %cron_expression,
@@ -125,14 +172,19 @@ async fn main() {
         .await
         .expect("FATAL: Failed to create job scheduler - system resources may be exhausted");
 
+    // Queue for outgoing webhooks; deliveries run in the background
+    let webhook_dispatcher = create_webhook_dispatcher(pool.clone());
+
     let pool_for_scheduler = pool.clone();
+    let webhooks_for_scheduler = webhook_dispatcher.clone();
     let job = Job::new_async(cron_expression.as_str(), move |_uuid, _lock| {
         let pool_clone = pool_for_scheduler.clone();
+        let webhooks = webhooks_for_scheduler.clone();
         Box::pin(async move {
             tracing::info!("Running scheduled recurring debts generation");
 
             // Call the internal function directly - no need for provide_context
-            match process_due_recurring_debts_internal(pool_clone).await {
+            match process_due_recurring_debts_internal(pool_clone, webhooks).await {
                 Ok(count) => {
                     tracing::info!(count = count, "Successfully generated recurring debts");
                 }
@@ -152,17 +204,120 @@ async fn main() {
         .await
         .expect("FATAL: Failed to add job to scheduler");
 
+    // Weekly group summaries, configurable via WEEKLY_SUMMARY_CRON
+    // Default: "0 0 18 * * Sun" (Sundays at 6:00 PM)
+    let summary_cron_expression =
+        std::env::var("WEEKLY_SUMMARY_CRON").unwrap_or_else(|_| "0 0 18 * * Sun".to_string());
+
+    tracing::info!(
+        cron_expression = %summary_cron_expression,
+        "Setting up weekly summary scheduler"
+    );
+
+    let pool_for_summaries = pool.clone();
+    let summary_job = Job::new_async(summary_cron_expression.as_str(), move |_uuid, _lock| {
+        let pool_clone = pool_for_summaries.clone();
+        Box::pin(async move {
+            tracing::info!("Running scheduled weekly summary generation");
+
+            match generate_weekly_summaries_internal(pool_clone).await {
+                Ok(count) => {
+                    tracing::info!(count = count, "Successfully generated weekly summaries");
+                }
+                Err(e) => {
+                    tracing::error!(
+                        error = %e,
+                        "Failed to generate weekly summaries"
+                    );
+                }
+            }
+        })
+    })
+    .expect("FATAL: Failed to create cron job - check WEEKLY_SUMMARY_CRON syntax");
+
     scheduler
-        .start()
+        .add(summary_job)
         .await
-        .expect("FATAL: Failed to start scheduler");
+        .expect("FATAL: Failed to add job to scheduler");
+
+    // Nightly retention purge, configurable via MAINTENANCE_CRON
+    // Default: "0 30 3 * * *" (daily at 3:30 AM)
+    let maintenance_config = MaintenanceConfig::from_env()
+        .unwrap_or_else(|e| panic!("FATAL: Invalid maintenance configuration: {}", e));
+    let maintenance_cron_expression =
+        std::env::var("MAINTENANCE_CRON").unwrap_or_else(|_| "0 30 3 * * *".to_string());
+
+    tracing::info!(
+        cron_expression = %maintenance_cron_expression,
+        config = ?maintenance_config,
+        "Setting up maintenance scheduler"
+    );
 
-    tracing::info!("Recurring debts scheduler started successfully");
+    let pool_for_maintenance = pool.clone();
+    let maintenance_job =
+        Job::new_async(maintenance_cron_expression.as_str(), move |_uuid, _lock| {
+            let pool_clone = pool_for_maintenance.clone();
+            let config = maintenance_config.clone();
+            Box::pin(async move {
+                tracing::info!("Running scheduled maintenance");
+
+                match run_maintenance(&pool_clone, &config).await {
+                    Ok(report) => {
+                        tracing::info!(
+                            expired_invites = report.expired_invites,
+                            shopping_activity = report.shopping_activity,
+                            vacuumed = report.vacuumed,
+                            "Maintenance finished"
+                        );
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            error = %e,
+                            "Failed to run maintenance"
+                        );
+                    }
+                }
+            })
+        })
+        .expect("FATAL: Failed to create cron job - check MAINTENANCE_CRON syntax");
+
+    scheduler
+        .add(maintenance_job)
+        .await
+        .expect("FATAL: Failed to add job to scheduler");
 
     // Create event broadcaster for shopping list real-time updates
     let broadcaster = create_broadcaster();
     tracing::debug!("Shopping list event broadcaster created");
 
+    // Channels outlive the SSE connections that opened them, so sweep the
+    // ones without listeners every ten minutes
+    let broadcaster_for_sweep = broadcaster.clone();
+    let sweep_job = Job::new_async("0 */10 * * * *", move |_uuid, _lock| {
+        let broadcaster = broadcaster_for_sweep.clone();
+        Box::pin(async move {
+            let removed = cleanup_inactive_channels(&broadcaster);
+            tracing::debug!(
+                removed = removed,
+                stats = ?broadcaster_stats(&broadcaster),
+                "Swept shopping list event channels"
+            );
+        })
+    })
+    .expect("FATAL: Failed to create channel sweep job");
+
+    scheduler
+        .add(sweep_job)
+        .await
+        .expect("FATAL: Failed to add job to scheduler");
+
+    scheduler
+        .start()
+        .await
+        .expect("FATAL: Failed to start scheduler");
+
+    tracing::info!("Schedulers started successfully");
+
     // SSE endpoint handler for shopping list updates
     async fn shopping_list_events(
         Path((_group_id, list_id)): Path<(i64, i64)>,
@@ -205,6 +360,182 @@ async fn main() {
         )
         .with_state(broadcaster.clone());
 
+    // Liveness check that also reports how many live update channels are held
+    async fn health(State(broadcaster): State<EventBroadcaster>) -> Response {
+        Json(serde_json::json!({
+            "status": "ok",
+            "shopping_list_channels": broadcaster_stats(&broadcaster),
+        }))
+        .into_response()
+    }
+
+    let health_router = Router::new()
+        .route("/api/health", get(health))
+        .with_state(broadcaster.clone());
+
+    // Create event broadcaster for group page real-time updates
+    let group_broadcaster = create_group_broadcaster();
+
+    // SSE endpoint handler for group updates; only members may listen
+    async fn group_events(
+        Path(group_id): Path<i64>,
+        State((pool, broadcaster)): State<(sqlx::SqlitePool, GroupEventBroadcaster)>,
+        session: Session,
+    ) -> Response {
+        let Some(user) = load_session_user(&session, &pool).await else {
+            return StatusCode::UNAUTHORIZED.into_response();
+        };
+
+        let is_member = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = ? AND user_id = ?) as "exists!: bool""#,
+            group_id,
+            user.id
+        )
+        .fetch_one(&pool)
+        .await;
+        match is_member {
+            Ok(true) => {}
+            Ok(false) => return StatusCode::NOT_FOUND.into_response(),
+            Err(e) => {
+                tracing::error!(error = %e, group_id = group_id, "Failed to check group membership");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+
+        let rx = get_or_create_group_channel(&broadcaster, group_id).subscribe();
+        let stream = BroadcastStream::new(rx).filter_map(|result| match result {
+            Ok(event) => match serde_json::to_string(&event) {
+                Ok(json) => Some(Ok::<_, Infallible>(Event::default().data(json))),
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to serialize SSE event");
+                    None
+                }
+            },
+            Err(e) => {
+                tracing::debug!(error = %e, "SSE broadcast stream error");
+                None
+            }
+        });
+
+        Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    }
+
+    let group_events_router = Router::new()
+        .route("/api/groups/{group_id}/events", get(group_events))
+        .with_state((pool.clone(), group_broadcaster.clone()));
+
+    #[derive(serde::Deserialize)]
+    struct CalendarFeedQuery {
+        token: String,
+    }
+
+    // iCal feed of upcoming recurring debts, authenticated by a per-user token
+    // because calendar clients cannot send session cookies
+    async fn recurring_debts_calendar(
+        Path(group_id): Path<i64>,
+        Query(query): Query<CalendarFeedQuery>,
+        State(pool): State<sqlx::SqlitePool>,
+    ) -> Response {
+        match render_calendar_feed(&pool, group_id, &query.token).await {
+            Ok(Some(body)) => (
+                [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+                body,
+            )
+                .into_response(),
+            Ok(None) => StatusCode::NOT_FOUND.into_response(),
+            Err(e) => {
+                tracing::error!(error = %e, group_id = group_id, "Failed to render calendar feed");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+
+    let calendar_router = Router::new()
+        .route(
+            "/api/groups/{group_id}/recurring-debts.ics",
+            get(recurring_debts_calendar),
+        )
+        .with_state(pool.clone());
+
+    // CSV download of a group's debts, transactions and balances
+    async fn group_export_csv(
+        Path(group_id): Path<i64>,
+        State(pool): State<sqlx::SqlitePool>,
+        session: Session,
+    ) -> Response {
+        let Some(user) = load_session_user(&session, &pool).await else {
+            return StatusCode::UNAUTHORIZED.into_response();
+        };
+
+        let format = ExportFormat::Csv;
+        match render_group_export(&pool, group_id, user.id, format).await {
+            Ok(Some(body)) => (
+                [
+                    (header::CONTENT_TYPE, format.content_type().to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!(
+                            "attachment; filename=\"group-{}.{}\"",
+                            group_id,
+                            format.extension()
+                        ),
+                    ),
+                ],
+                body,
+            )
+                .into_response(),
+            Ok(None) => StatusCode::NOT_FOUND.into_response(),
+            Err(e) => {
+                tracing::error!(error = %e, group_id = group_id, "Failed to export group");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+
+    // JSON backup of a whole group, which can be imported as a new group
+    async fn group_backup_json(
+        Path(group_id): Path<i64>,
+        State(pool): State<sqlx::SqlitePool>,
+        session: Session,
+    ) -> Response {
+        let Some(user) = load_session_user(&session, &pool).await else {
+            return StatusCode::UNAUTHORIZED.into_response();
+        };
+
+        match render_group_backup(&pool, group_id, user.id).await {
+            Ok(Some(body)) => (
+                [
+                    (header::CONTENT_TYPE, "application/json".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"group-{}-backup.json\"", group_id),
+                    ),
+                ],
+                body,
+            )
+                .into_response(),
+            Ok(None) => StatusCode::NOT_FOUND.into_response(),
+            Err(e) => {
+                tracing::error!(error = %e, group_id = group_id, "Failed to back up group");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+
+    let export_router = Router::new()
+        .route("/api/groups/{group_id}/export.csv", get(group_export_csv))
+        .route("/api/groups/{group_id}/backup.json", get(group_backup_json))
+        .with_state(pool.clone());
+
+    // JSON API for scripts, authenticated with personal API tokens
+    let api_router = api_router(ApiState {
+        pool: pool.clone(),
+        group_broadcaster: group_broadcaster.clone(),
+        webhooks: webhook_dispatcher.clone(),
+    });
+
     let app = Router::new()
         .leptos_routes_with_context(
             &leptos_options,
@@ -213,10 +544,14 @@ async fn main() {
                 let leptos_options = leptos_options.clone();
                 let pool = pool.clone();
                 let broadcaster = broadcaster.clone();
+                let group_broadcaster = group_broadcaster.clone();
+                let webhook_dispatcher = webhook_dispatcher.clone();
                 move || {
                     provide_context(leptos_options.clone());
                     provide_context(pool.clone());
                     provide_context(broadcaster.clone());
+                    provide_context(group_broadcaster.clone());
+                    provide_context(webhook_dispatcher.clone());
                 }
             },
             {
@@ -225,6 +560,11 @@ async fn main() {
             },
         )
         .merge(sse_router)
+        .merge(health_router)
+        .merge(group_events_router)
+        .merge(calendar_router)
+        .merge(export_router)
+        .merge(api_router)
         .fallback(leptos_axum::file_and_error_handler(shell))
         .layer(ServiceBuilder::new().layer(session_layer))
         .with_state(leptos_options)
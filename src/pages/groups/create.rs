@@ -5,13 +5,17 @@ use crate::{
     components::{
         AppLayout, Navigation,
         forms::{
-            CancelButton, ErrorAlert, FormCard, FormField, FormInput, LoadingSpinner, PageHeader,
-            SubmitButton,
+            CancelButton, ErrorAlert, FormCard, FormField, FormInput, FormNumberInput, FormSelect,
+            LoadingSpinner, PageHeader, SubmitButton,
         },
     },
+    error::handle_app_error,
     features::{
-        auth::{UserSession, use_logout},
-        groups::handlers::CreateGroup,
+        auth::{UserSession, use_logout, use_redirect_to_login},
+        groups::{
+            handlers::CreateGroup,
+            utils::{DEFAULT_CURRENCY, GROUP_CURRENCIES, GROUP_TIMEZONES},
+        },
     },
 };
 
@@ -26,6 +30,9 @@ pub fn GroupsCreate() -> impl IntoView {
     let on_logout = use_logout();
 
     let (group_name, set_group_name) = signal(String::new());
+    let (monthly_budget, set_monthly_budget) = signal(String::new());
+    let (timezone, set_timezone) = signal(String::new());
+    let (currency, set_currency) = signal(DEFAULT_CURRENCY.to_string());
     let (error_message, set_error_message) = signal(None::<String>);
 
     // Clone navigate for use in multiple effects
@@ -39,9 +46,11 @@ pub fn GroupsCreate() -> impl IntoView {
     });
 
     // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
+    let redirect_on_error = redirect_to_login.clone();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -50,7 +59,7 @@ pub fn GroupsCreate() -> impl IntoView {
         if let Some(result) = create_action.value().get() {
             match result {
                 Ok(_) => set_error_message.set(None),
-                Err(e) => set_error_message.set(Some(e.to_string())),
+                Err(e) => set_error_message.set(handle_app_error(&e, &redirect_on_error)),
             }
         }
     });
@@ -59,7 +68,12 @@ pub fn GroupsCreate() -> impl IntoView {
         ev.prevent_default();
         let name = group_name.get();
         if !name.trim().is_empty() {
-            create_action.dispatch(CreateGroup { name });
+            create_action.dispatch(CreateGroup {
+                name,
+                monthly_budget: Some(monthly_budget.get()),
+                timezone: Some(timezone.get()),
+                currency: Some(currency.get()),
+            });
         }
     };
 
@@ -98,6 +112,53 @@ pub fn GroupsCreate() -> impl IntoView {
                                                     />
                                                 </FormField>
 
+                                                <FormField
+                                                    label="Currency"
+                                                    for_id="currency"
+                                                    helper_text="All amounts in the group are in this currency."
+                                                >
+                                                    <FormSelect
+                                                        id="currency"
+                                                        value=Signal::derive(move || currency.get())
+                                                        on_change=Callback::new(move |val| set_currency.set(val))
+                                                    >
+                                                        {GROUP_CURRENCIES.into_iter().map(|(code, _)| view! {
+                                                            <option value=code>{code}</option>
+                                                        }).collect_view()}
+                                                    </FormSelect>
+                                                </FormField>
+
+                                                <FormField
+                                                    label="Monthly Budget (optional)"
+                                                    for_id="monthly-budget"
+                                                    helper_text="In the group's currency. Leave empty to track spending without a budget."
+                                                >
+                                                    <FormNumberInput
+                                                        id="monthly-budget"
+                                                        min="0.01"
+                                                        placeholder="e.g., 500.00"
+                                                        value=Signal::derive(move || monthly_budget.get())
+                                                        on_input=Callback::new(move |val| set_monthly_budget.set(val))
+                                                    />
+                                                </FormField>
+
+                                                <FormField
+                                                    label="Timezone"
+                                                    for_id="timezone"
+                                                    helper_text="Recurring debts are added when the day starts here."
+                                                >
+                                                    <FormSelect
+                                                        id="timezone"
+                                                        value=Signal::derive(move || timezone.get())
+                                                        on_change=Callback::new(move |val| set_timezone.set(val))
+                                                    >
+                                                        <option value="">"Server default"</option>
+                                                        {GROUP_TIMEZONES.into_iter().map(|name| view! {
+                                                            <option value=name>{name}</option>
+                                                        }).collect_view()}
+                                                    </FormSelect>
+                                                </FormField>
+
                                                 <ErrorAlert message=error_message />
 
                                                 <div class="flex gap-3">
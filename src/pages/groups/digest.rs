@@ -0,0 +1,223 @@
+use leptos::prelude::*;
+use leptos_router::{
+    components::A,
+    hooks::{use_params_map, use_query_map},
+};
+
+use crate::{
+    components::Money,
+    features::{
+        auth::{UserSession, use_redirect_to_login},
+        groups::{
+            handlers::{MAX_DIGEST_WEEKS, get_group_digest},
+            models::GroupDigest,
+        },
+        transactions::NetType,
+    },
+};
+
+/// Periods offered as links, in weeks
+const PERIOD_OPTIONS: [i64; 3] = [1, 2, 4];
+
+/// One titled part of the digest; `empty` is shown when it has no rows
+#[component]
+fn DigestSection(
+    title: &'static str,
+    empty: &'static str,
+    is_empty: bool,
+    children: Children,
+) -> impl IntoView {
+    view! {
+        <section class="mb-8 break-inside-avoid">
+            <h2 class="text-lg font-semibold text-gray-900 border-b border-gray-200 pb-1 mb-3">{title}</h2>
+            {if is_empty {
+                view! { <p class="text-sm text-gray-500">{empty}</p> }.into_any()
+            } else {
+                children().into_any()
+            }}
+        </section>
+    }
+}
+
+/// The digest itself, kept plain so it prints well
+#[component]
+fn DigestBody(digest: GroupDigest) -> impl IntoView {
+    let new_debts_total = digest.new_debts_total();
+
+    view! {
+        <header class="mb-8">
+            <h1 class="text-2xl font-bold text-gray-900">{digest.group_name.clone()}</h1>
+            <p class="text-sm text-gray-600">
+                {format!("{} – {}", digest.from, digest.to)}
+            </p>
+        </header>
+
+        <DigestSection
+            title="New debts"
+            empty="No debts were added."
+            is_empty=digest.new_debts.is_empty()
+        >
+            <table class="min-w-full text-sm">
+                <tbody class="divide-y divide-gray-200">
+                    {digest.new_debts.into_iter().map(|debt| view! {
+                        <tr>
+                            <td class="py-1 pr-3 text-gray-500 whitespace-nowrap">{debt.created_on.to_string()}</td>
+                            <td class="py-1 pr-3 text-gray-900">{debt.name}</td>
+                            <td class="py-1 pr-3 text-gray-600">"paid by " {debt.payer_username}</td>
+                            <td class="py-1 text-right text-gray-900 whitespace-nowrap"><Money amount=debt.amount /></td>
+                        </tr>
+                    }).collect_view()}
+                </tbody>
+                <tfoot>
+                    <tr class="border-t border-gray-300">
+                        <td colspan="3" class="py-1 pr-3 font-medium text-gray-900">"Total"</td>
+                        <td class="py-1 text-right font-medium text-gray-900"><Money amount=new_debts_total /></td>
+                    </tr>
+                </tfoot>
+            </table>
+        </DigestSection>
+
+        <DigestSection
+            title="Payments"
+            empty="No payments were recorded."
+            is_empty=digest.new_transactions.is_empty()
+        >
+            <table class="min-w-full text-sm">
+                <tbody class="divide-y divide-gray-200">
+                    {digest.new_transactions.into_iter().map(|transaction| view! {
+                        <tr>
+                            <td class="py-1 pr-3 text-gray-500 whitespace-nowrap">{transaction.created_on.to_string()}</td>
+                            <td class="py-1 pr-3 text-gray-900">
+                                {transaction.payer_username} " paid " {transaction.recipient_username}
+                            </td>
+                            <td class="py-1 text-right text-gray-900 whitespace-nowrap"><Money amount=transaction.amount /></td>
+                        </tr>
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </DigestSection>
+
+        <DigestSection
+            title="Balances"
+            empty="The group has no members."
+            is_empty=digest.balances.is_empty()
+        >
+            <table class="min-w-full text-sm">
+                <tbody class="divide-y divide-gray-200">
+                    {digest.balances.into_iter().map(|balance| {
+                        let (label, class) = match balance.net_type {
+                            NetType::Positive => ("is owed", "py-1 text-right text-green-700 whitespace-nowrap"),
+                            NetType::Negative => ("owes", "py-1 text-right text-red-700 whitespace-nowrap"),
+                            NetType::Neutral => ("is settled up", "py-1 text-right text-gray-500 whitespace-nowrap"),
+                        };
+                        view! {
+                            <tr>
+                                <td class="py-1 pr-3 text-gray-900">{balance.username} " " {label}</td>
+                                <td class=class>
+                                    {(balance.net_type != NetType::Neutral).then(|| view! { <Money amount=balance.amount /> })}
+                                </td>
+                            </tr>
+                        }
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </DigestSection>
+
+        <DigestSection
+            title="Coming up"
+            empty="No recurring debts are due."
+            is_empty=digest.upcoming_recurring.is_empty()
+        >
+            <p class="text-xs text-gray-500 mb-2">{format!("Until {}", digest.upcoming_until)}</p>
+            <table class="min-w-full text-sm">
+                <tbody class="divide-y divide-gray-200">
+                    {digest.upcoming_recurring.into_iter().map(|recurring| view! {
+                        <tr>
+                            <td class="py-1 pr-3 text-gray-500 whitespace-nowrap">{recurring.date.to_string()}</td>
+                            <td class="py-1 pr-3 text-gray-900">{recurring.name}</td>
+                            <td class="py-1 text-right text-gray-900 whitespace-nowrap"><Money amount=recurring.amount /></td>
+                        </tr>
+                    }).collect_view()}
+                </tbody>
+            </table>
+        </DigestSection>
+    }
+}
+
+/// Group digest page - a bookmarkable, print-friendly summary of the last
+/// weeks, chosen with `?weeks=`
+#[must_use]
+#[component]
+pub fn GroupsDigest() -> impl IntoView {
+    let user_resource =
+        expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
+    let params = use_params_map();
+    let query = use_query_map();
+
+    let group_id = Memo::new(move |_| {
+        params
+            .read()
+            .get("id")
+            .and_then(|id| id.parse::<i64>().ok())
+            .unwrap_or(0)
+    });
+    let weeks = Memo::new(move |_| {
+        query
+            .read()
+            .get("weeks")
+            .and_then(|weeks| weeks.parse::<i64>().ok())
+            .unwrap_or(1)
+            .clamp(1, MAX_DIGEST_WEEKS)
+    });
+
+    let digest_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        let weeks = weeks.get();
+        async move { get_group_digest(id, weeks).await }
+    });
+
+    // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(Ok(None)) = user_resource.get() {
+            redirect_to_login();
+        }
+    });
+
+    view! {
+        <div class="min-h-screen bg-white">
+            <div class="max-w-2xl mx-auto px-4 py-8">
+                <nav class="print:hidden mb-6 flex flex-wrap gap-4 justify-between items-center text-sm">
+                    <A href=move || format!("/groups/{}", group_id.get()) attr:class="text-indigo-600 hover:text-indigo-900">
+                        "Back to Group"
+                    </A>
+                    <div class="flex gap-3">
+                        {PERIOD_OPTIONS.into_iter().map(|option| view! {
+                            <A
+                                href=move || format!("/groups/{}/digest?weeks={}", group_id.get(), option)
+                                attr:class=move || if weeks.get() == option {
+                                    "font-semibold text-gray-900"
+                                } else {
+                                    "text-indigo-600 hover:text-indigo-900"
+                                }
+                            >
+                                {if option == 1 { "1 week".to_string() } else { format!("{} weeks", option) }}
+                            </A>
+                        }).collect_view()}
+                    </div>
+                </nav>
+
+                <Suspense fallback=move || view! { <p class="text-sm text-gray-500">"Loading digest..."</p> }>
+                    {move || digest_resource.get().map(|result| match result {
+                        Ok(digest) => view! { <DigestBody digest=digest /> }.into_any(),
+                        Err(e) => view! {
+                            <div class="rounded-md bg-red-50 p-4">
+                                <p class="text-sm text-red-700">{e.to_string()}</p>
+                            </div>
+                        }.into_any(),
+                    })}
+                </Suspense>
+            </div>
+        </div>
+    }
+}
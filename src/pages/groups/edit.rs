@@ -6,15 +6,32 @@ use leptos_router::{
 
 use crate::{
     components::{
-        AppLayout, Navigation,
+        AppLayout, ErrorCard, Navigation, format_money,
         forms::{
-            CancelButton, ErrorAlert, FormCard, FormField, FormInput, LoadingSpinner, SubmitButton,
+            CancelButton, EditErrorAlert, ErrorAlert, FormCard, FormField, FormInput,
+            FormNumberInput, FormSelect, LoadingSpinner, MemberPicker, SubmitButton, SuccessAlert,
         },
+        use_currency_symbol, use_number_format,
     },
+    error::handle_app_error,
     features::{
-        auth::{UserSession, use_logout},
-        groups::handlers::{DeleteGroup, UpdateGroup, get_all_users, get_group, get_group_members},
+        auth::{UserSession, use_logout, use_redirect_to_login},
+        feature_flags::{FeatureFlag, SetFeatureFlag, get_feature_flags},
+        groups::{
+            handlers::{
+                ArchiveGroup, DeleteGroup, ImportGroupJson, RemoveGroupMember,
+                RepairGroupIntegrity, SetFullBalancesVisible, SetRequireDebtApproval,
+                SetRoundingStrategy, UpdateGroup, audit_group_integrity, backup_path,
+                get_all_users, get_group, get_group_members,
+            },
+            models::{GroupMemberInfo, IntegrityIssueKind},
+            utils::{DEFAULT_CURRENCY, GROUP_CURRENCIES, GROUP_TIMEZONES},
+        },
+        shared_debts::models::RoundingStrategy,
+        transactions::{NetType, RebuildGroupBalances, calculate_user_debts},
     },
+    pages::groups::webhooks::GroupWebhooks,
+    validation::rules::is_member_removal_blocked,
 };
 
 /// Groups edit page - edit group name and members
@@ -25,6 +42,15 @@ pub fn GroupsEdit() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let update_group_action = ServerAction::<UpdateGroup>::new();
     let delete_group_action = ServerAction::<DeleteGroup>::new();
+    let remove_member_action = ServerAction::<RemoveGroupMember>::new();
+    let set_flag_action = ServerAction::<SetFeatureFlag>::new();
+    let set_full_balances_action = ServerAction::<SetFullBalancesVisible>::new();
+    let set_debt_approval_action = ServerAction::<SetRequireDebtApproval>::new();
+    let set_rounding_action = ServerAction::<SetRoundingStrategy>::new();
+    let archive_group_action = ServerAction::<ArchiveGroup>::new();
+    let rebuild_balances_action = ServerAction::<RebuildGroupBalances>::new();
+    let import_group_action = ServerAction::<ImportGroupJson>::new();
+    let repair_integrity_action = ServerAction::<RepairGroupIntegrity>::new();
     let navigate = use_navigate();
     let on_logout = use_logout();
     let params = use_params_map();
@@ -49,18 +75,57 @@ pub fn GroupsEdit() -> impl IntoView {
 
     let all_users_resource = LocalResource::new(|| async move { get_all_users().await });
 
+    let feature_flags_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { get_feature_flags(id).await }
+    });
+
+    let balances_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { calculate_user_debts(id, None, None).await }
+    });
+
+    let integrity_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { audit_group_integrity(id).await }
+    });
+
     // Form signals
     let name_signal = RwSignal::new(String::new());
+    let budget_signal = RwSignal::new(String::new());
+    let timezone_signal = RwSignal::new(String::new());
+    let currency_signal = RwSignal::new(DEFAULT_CURRENCY.to_string());
     let selected_members = RwSignal::new(Vec::<i64>::new());
     let show_delete_modal = RwSignal::new(false);
+    let member_to_remove = RwSignal::new(None::<(i64, String)>);
+    let (remove_error, set_remove_error) = signal(None::<String>);
+    let (flag_error, set_flag_error) = signal(None::<String>);
+    let full_balances_visible = RwSignal::new(true);
+    let (privacy_error, set_privacy_error) = signal(None::<String>);
+    let require_debt_approval = RwSignal::new(false);
+    let (approval_error, set_approval_error) = signal(None::<String>);
+    let rounding_signal = RwSignal::new(String::new());
+    let (rounding_error, set_rounding_error) = signal(None::<String>);
+    let (archive_error, set_archive_error) = signal(None::<String>);
     let (update_error, set_update_error) = signal(None::<String>);
+    let (members_error, set_members_error) = signal(None::<String>);
     let (delete_error, set_delete_error) = signal(None::<String>);
+    let (rebuild_error, set_rebuild_error) = signal(None::<String>);
+    let (repair_error, set_repair_error) = signal(None::<String>);
+    let (backup_json, set_backup_json) = signal(String::new());
+    let import_error = Signal::derive(move || match import_group_action.value().get() {
+        Some(Err(e)) => Some(e.to_string()),
+        _ => None,
+    });
 
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
+    let redirect_on_error = redirect_to_login.clone();
+    let redirect_on_rounding_error = redirect_to_login.clone();
+    let redirect_on_archive_error = redirect_to_login.clone();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -68,6 +133,17 @@ pub fn GroupsEdit() -> impl IntoView {
     Effect::new(move |_| {
         if let Some(Ok(group)) = group_resource.get() {
             name_signal.set(group.name.clone());
+            budget_signal.set(
+                group
+                    .monthly_budget
+                    .map(|budget| format!("{:.2}", budget))
+                    .unwrap_or_default(),
+            );
+            timezone_signal.set(group.timezone.clone().unwrap_or_default());
+            currency_signal.set(group.currency.clone());
+            full_balances_visible.set(group.full_balances_visible);
+            require_debt_approval.set(group.require_debt_approval);
+            rounding_signal.set(group.rounding_strategy.as_str().to_string());
         }
     });
 
@@ -87,7 +163,117 @@ pub fn GroupsEdit() -> impl IntoView {
                     let id = group_id.get();
                     navigate_clone(&format!("/groups/{id}"), Default::default());
                 }
-                Err(e) => set_update_error.set(Some(e.to_string())),
+                // Removals blocked by open debts are shown next to the member list
+                Err(e) if is_member_removal_blocked(&e.to_string()) => {
+                    set_members_error.set(Some(e.to_string()))
+                }
+                Err(e) => set_update_error.set(handle_app_error(&e, &redirect_on_error)),
+            }
+        }
+    });
+
+    // Effect to refresh the member list after a removal
+    Effect::new(move |_| {
+        if let Some(result) = remove_member_action.value().get() {
+            match result {
+                Ok(()) => {
+                    member_to_remove.set(None);
+                    set_remove_error.set(None);
+                    members_resource.refetch();
+                    balances_resource.refetch();
+                }
+                Err(e) => set_remove_error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    // Effect to reload feature flags after a toggle
+    Effect::new(move |_| {
+        if let Some(result) = set_flag_action.value().get() {
+            match result {
+                Ok(()) => set_flag_error.set(None),
+                Err(e) => set_flag_error.set(Some(e.to_string())),
+            }
+            feature_flags_resource.refetch();
+        }
+    });
+
+    // The checkbox is updated optimistically, so undo it when saving fails
+    Effect::new(move |_| {
+        if let Some(result) = set_full_balances_action.value().get() {
+            match result {
+                Ok(()) => set_privacy_error.set(None),
+                Err(e) => {
+                    full_balances_visible.update(|v| *v = !*v);
+                    set_privacy_error.set(Some(e.to_string()));
+                }
+            }
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(result) = set_debt_approval_action.value().get() {
+            match result {
+                Ok(()) => set_approval_error.set(None),
+                Err(e) => {
+                    require_debt_approval.update(|v| *v = !*v);
+                    set_approval_error.set(Some(e.to_string()));
+                }
+            }
+        }
+    });
+
+    // Like the checkbox, the select changes right away; reload the group to
+    // show the saved strategy again when saving fails
+    Effect::new(move |_| {
+        if let Some(result) = set_rounding_action.value().get() {
+            match result {
+                Ok(()) => set_rounding_error.set(None),
+                Err(e) => {
+                    set_rounding_error.set(handle_app_error(&e, &redirect_on_rounding_error));
+                    group_resource.refetch();
+                }
+            }
+        }
+    });
+
+    // Balances may look different once the cache is rebuilt
+    Effect::new(move |_| {
+        if let Some(result) = rebuild_balances_action.value().get() {
+            match result {
+                Ok(()) => {
+                    set_rebuild_error.set(None);
+                    balances_resource.refetch();
+                }
+                Err(e) => set_rebuild_error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    // Check again so the card shows what is left
+    Effect::new(move |_| {
+        if let Some(result) = repair_integrity_action.value().get() {
+            match result {
+                Ok(_) => {
+                    set_repair_error.set(None);
+                    integrity_resource.refetch();
+                    balances_resource.refetch();
+                }
+                Err(e) => set_repair_error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    // Archived groups can't be edited, so go back to the group page
+    let navigate_after_archive = navigate.clone();
+    Effect::new(move |_| {
+        if let Some(result) = archive_group_action.value().get() {
+            match result {
+                Ok(()) => navigate_after_archive(
+                    &format!("/groups/{}", group_id.get_untracked()),
+                    Default::default(),
+                ),
+                Err(e) => set_archive_error.set(handle_app_error(&e, &redirect_on_archive_error)),
             }
         }
     });
@@ -104,13 +290,31 @@ pub fn GroupsEdit() -> impl IntoView {
 
     let on_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
+        set_update_error.set(None);
+        set_members_error.set(None);
         update_group_action.dispatch(UpdateGroup {
             group_id: group_id.get(),
             name: name_signal.get(),
             member_ids: selected_members.get(),
+            // Version of the group the form was loaded from
+            version: group_resource
+                .get()
+                .and_then(Result::ok)
+                .map(|group| group.version)
+                .unwrap_or_default(),
+            monthly_budget: Some(budget_signal.get()),
+            timezone: Some(timezone_signal.get()),
+            currency: Some(currency_signal.get()),
         });
     };
 
+    // Load the group again after someone else saved it
+    let on_reload = Callback::new(move |()| {
+        set_update_error.set(None);
+        group_resource.refetch();
+        members_resource.refetch();
+    });
+
     let on_delete_confirm = move |_| {
         delete_group_action.dispatch(DeleteGroup {
             group_id: group_id.get(),
@@ -118,6 +322,37 @@ pub fn GroupsEdit() -> impl IntoView {
         show_delete_modal.set(false);
     };
 
+    // Outstanding balance of the member pending removal, if any
+    let number_format = use_number_format();
+    let currency = use_currency_symbol();
+    let removal_warning = move || {
+        let (member_id, member_name) = member_to_remove.get()?;
+        let balances = balances_resource.get()?.ok()?;
+        let balance = balances.into_iter().find(|b| b.user_id == member_id)?;
+        let amount = format_money(
+            balance.net_type.signed_amount(&balance.net_amount).abs(),
+            currency.get(),
+            number_format.get(),
+        );
+        match balance.net_type {
+            NetType::Negative => Some(format!("{member_name} still owes {amount} in this group.")),
+            NetType::Positive => Some(format!(
+                "{member_name} is still owed {amount} in this group."
+            )),
+            NetType::Neutral => None,
+        }
+    };
+
+    let on_remove_confirm = move |_| {
+        if let Some((member_id, _)) = member_to_remove.get() {
+            remove_member_action.dispatch(RemoveGroupMember {
+                group_id: group_id.get(),
+                user_id: member_id,
+                force: removal_warning().is_some(),
+            });
+        }
+    };
+
     view! {
         <Suspense fallback=move || view! { <LoadingSpinner /> }>
             {move || {
@@ -136,7 +371,7 @@ pub fn GroupsEdit() -> impl IntoView {
                                                 {move || {
                                                     match group_resource.get() {
                                                         Some(Ok(group)) => {
-                                                            let is_admin = group.created_by == user_id;
+                                                            let is_admin = group.is_admin;
 
                                                         if !is_admin {
                                                             return view! {
@@ -178,6 +413,63 @@ pub fn GroupsEdit() -> impl IntoView {
                                                                             />
                                                                         </FormField>
 
+                                                                        <FormField
+                                                                            label="Currency"
+                                                                            for_id="currency"
+                                                                            helper_text="Changing it relabels existing amounts; nothing is converted."
+                                                                        >
+                                                                            <FormSelect
+                                                                                id="currency"
+                                                                                value=Signal::derive(move || currency_signal.get())
+                                                                                on_change=Callback::new(move |val| currency_signal.set(val))
+                                                                            >
+                                                                                {GROUP_CURRENCIES.into_iter().map(|(code, _)| view! {
+                                                                                    <option value=code>{code}</option>
+                                                                                }).collect_view()}
+                                                                            </FormSelect>
+                                                                        </FormField>
+
+                                                                        <FormField
+                                                                            label="Monthly Budget (optional)"
+                                                                            for_id="monthly-budget"
+                                                                            helper_text="In the group's currency. Leave empty to track spending without a budget."
+                                                                        >
+                                                                            <FormNumberInput
+                                                                                id="monthly-budget"
+                                                                                min="0.01"
+                                                                                placeholder="e.g., 500.00"
+                                                                                value=Signal::derive(move || budget_signal.get())
+                                                                                on_input=Callback::new(move |val| budget_signal.set(val))
+                                                                            />
+                                                                        </FormField>
+
+                                                                        <FormField
+                                                                            label="Timezone"
+                                                                            for_id="timezone"
+                                                                            helper_text="Recurring debts are added when the day starts here."
+                                                                        >
+                                                                            <FormSelect
+                                                                                id="timezone"
+                                                                                value=Signal::derive(move || timezone_signal.get())
+                                                                                on_change=Callback::new(move |val| timezone_signal.set(val))
+                                                                            >
+                                                                                <option value="">"Server default"</option>
+                                                                                {GROUP_TIMEZONES.into_iter().map(|name| view! {
+                                                                                    <option value=name>{name}</option>
+                                                                                }).collect_view()}
+                                                                                // A timezone set elsewhere, e.g. by a backup, stays selectable
+                                                                                {move || group_resource
+                                                                                    .get()
+                                                                                    .and_then(Result::ok)
+                                                                                    .and_then(|group| group.timezone)
+                                                                                    .filter(|name| !GROUP_TIMEZONES.contains(&name.as_str()))
+                                                                                    .map(|name| {
+                                                                                        let value = name.clone();
+                                                                                        view! { <option value=value>{name}</option> }
+                                                                                    })}
+                                                                            </FormSelect>
+                                                                        </FormField>
+
                                                                         <div>
                                                                             <label class="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
                                                                                 "Members"
@@ -191,54 +483,53 @@ pub fn GroupsEdit() -> impl IntoView {
                                                                                     match (all_users_resource.get(), members_resource.get()) {
                                                                                         (Some(Ok(all_users)), Some(Ok(current_members))) => {
                                                                                             // Combine current user with all users to create full list
-                                                                                            let mut available_users = vec![UserSession {
+                                                                                            let current_user_id = user_id;
+                                                                                            let is_creator = current_members.iter().any(|m| m.id == current_user_id && m.is_creator);
+                                                                                            let creator_ids: Vec<i64> = current_members
+                                                                                                .iter()
+                                                                                                .filter(|m| m.is_creator)
+                                                                                                .map(|m| m.id)
+                                                                                                .collect();
+                                                                                            let mut available_users: Vec<GroupMemberInfo> = std::iter::once(UserSession {
                                                                                                 id: user_id,
-                                                                                                username: username.get_value()
-                                                                                            }];
-                                                                                            available_users.extend(all_users);
+                                                                                                username: username.get_value(),
+                                                                                                session_generation: 0,
+                                                                                            })
+                                                                                            .chain(all_users)
+                                                                                            .map(|u| {
+                                                                                                let member = current_members.iter().find(|m| m.id == u.id);
+                                                                                                GroupMemberInfo {
+                                                                                                    id: u.id,
+                                                                                                    is_creator: member.is_some_and(|m| m.is_creator),
+                                                                                                    is_admin: member.is_some_and(|m| m.is_admin),
+                                                                                                    is_viewer: member.is_some_and(|m| m.is_viewer),
+                                                                                                    joined_at: member.and_then(|m| m.joined_at),
+                                                                                                    username: u.username,
+                                                                                                }
+                                                                                            })
+                                                                                            .collect();
                                                                                             available_users.sort_by(|a, b| a.username.cmp(&b.username));
-                                                                                            available_users.dedup_by(|a, b| a.id == b.id);
 
-                                                                                            let current_user_id = user_id;
-                                                                                            let is_creator = current_members.iter().any(|m| m.id == current_user_id && m.is_creator);
+                                                                                            // The creator can't be deselected, and only non-creator members can be removed
+                                                                                            let disabled_ids = creator_ids;
+                                                                                            let removable_ids: Vec<i64> = current_members
+                                                                                                .iter()
+                                                                                                .filter(|m| !m.is_creator)
+                                                                                                .map(|m| m.id)
+                                                                                                .collect();
 
                                                                                             view! {
-                                                                                                <div class="border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 max-h-64 overflow-y-auto">
-                                                                                                    {available_users.into_iter().map(|u| {
-                                                                                                        let user_id = u.id;
-                                                                                                        let is_current_user = user_id == current_user_id;
-                                                                                                        let is_user_creator = current_members.iter().any(|m| m.id == user_id && m.is_creator);
-                                                                                                        let disabled = is_current_user && is_creator;
-
-                                                                                                        view! {
-                                                                                                            <label class="flex items-center px-4 py-3 hover:bg-gray-50 dark:hover:bg-gray-600 cursor-pointer border-b border-gray-200 dark:border-gray-600 last:border-0">
-                                                                                                                <input
-                                                                                                                    type="checkbox"
-                                                                                                                    class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
-                                                                                                                    disabled=disabled
-                                                                                                                    prop:checked=move || selected_members.get().contains(&user_id)
-                                                                                                                    on:change=move |ev| {
-                                                                                                                        let checked = event_target_checked(&ev);
-                                                                                                                        selected_members.update(|members| {
-                                                                                                                            if checked {
-                                                                                                                                if !members.contains(&user_id) {
-                                                                                                                                    members.push(user_id);
-                                                                                                                                }
-                                                                                                                            } else {
-                                                                                                                                members.retain(|&id| id != user_id);
-                                                                                                                            }
-                                                                                                                        });
-                                                                                                                    }
-                                                                                                                />
-                                                                                                                <span class="ml-3 text-gray-900 dark:text-white">
-                                                                                                                    {u.username}
-                                                                                                                    {if is_current_user { " (You)" } else { "" }}
-                                                                                                                    {if is_user_creator { " - Creator" } else { "" }}
-                                                                                                                </span>
-                                                                                                            </label>
-                                                                                                        }
-                                                                                                    }).collect_view()}
-                                                                                                </div>
+                                                                                                <MemberPicker
+                                                                                                    members=available_users
+                                                                                                    selected=selected_members
+                                                                                                    disabled_ids=disabled_ids
+                                                                                                    current_user_id=current_user_id
+                                                                                                    removable_ids=removable_ids
+                                                                                                    on_remove=Callback::new(move |member: GroupMemberInfo| {
+                                                                                                        set_remove_error.set(None);
+                                                                                                        member_to_remove.set(Some((member.id, member.username)));
+                                                                                                    })
+                                                                                                />
                                                                                                 {if is_creator {
                                                                                                     view! {
                                                                                                         <p class="mt-2 text-xs text-gray-500 dark:text-gray-400">
@@ -254,9 +545,10 @@ pub fn GroupsEdit() -> impl IntoView {
                                                                                     }
                                                                                 }}
                                                                             </Suspense>
+                                                                            <ErrorAlert message=members_error />
                                                                         </div>
 
-                                                                        <ErrorAlert message=update_error />
+                                                                        <EditErrorAlert message=update_error on_reload=on_reload />
 
                                                                         <div class="flex flex-col sm:flex-row gap-3">
                                                                             <SubmitButton
@@ -269,6 +561,303 @@ pub fn GroupsEdit() -> impl IntoView {
                                                                     </form>
                                                                 </FormCard>
 
+                                                                // Privacy
+                                                                <div class="mt-6">
+                                                                <FormCard>
+                                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Privacy"</h2>
+                                                                    <label class="flex items-start cursor-pointer">
+                                                                        <input
+                                                                            type="checkbox"
+                                                                            class="mt-1 w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                            prop:checked=move || full_balances_visible.get()
+                                                                            disabled=move || set_full_balances_action.pending().get()
+                                                                            on:change=move |ev| {
+                                                                                let visible = event_target_checked(&ev);
+                                                                                full_balances_visible.set(visible);
+                                                                                set_full_balances_action.dispatch(SetFullBalancesVisible {
+                                                                                    group_id: group_id.get(),
+                                                                                    visible,
+                                                                                });
+                                                                            }
+                                                                        />
+                                                                        <span class="ml-3">
+                                                                            <span class="block text-sm font-medium text-gray-900 dark:text-white">"Members can view the full balance matrix"</span>
+                                                                            <span class="block text-sm text-gray-500 dark:text-gray-400">"When disabled, members only see their own balance. You can always see everyone's."</span>
+                                                                        </span>
+                                                                    </label>
+                                                                    <div class="mt-4">
+                                                                        <ErrorAlert message=privacy_error />
+                                                                    </div>
+                                                                </FormCard>
+                                                                </div>
+
+                                                                // Approval
+                                                                <div class="mt-6">
+                                                                <FormCard>
+                                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Approval"</h2>
+                                                                    <label class="flex items-start cursor-pointer">
+                                                                        <input
+                                                                            type="checkbox"
+                                                                            class="mt-1 w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                            prop:checked=move || require_debt_approval.get()
+                                                                            disabled=move || set_debt_approval_action.pending().get()
+                                                                            on:change=move |ev| {
+                                                                                let required = event_target_checked(&ev);
+                                                                                require_debt_approval.set(required);
+                                                                                set_debt_approval_action.dispatch(SetRequireDebtApproval {
+                                                                                    group_id: group_id.get(),
+                                                                                    required,
+                                                                                });
+                                                                            }
+                                                                        />
+                                                                        <span class="ml-3">
+                                                                            <span class="block text-sm font-medium text-gray-900 dark:text-white">"Debts need an admin's approval"</span>
+                                                                            <span class="block text-sm text-gray-500 dark:text-gray-400">"Debts added by other members only count towards balances once an admin approves them."</span>
+                                                                        </span>
+                                                                    </label>
+                                                                    <div class="mt-4">
+                                                                        <ErrorAlert message=approval_error />
+                                                                    </div>
+                                                                </FormCard>
+                                                                </div>
+
+                                                                // Rounding
+                                                                <div class="mt-6">
+                                                                <FormCard>
+                                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Rounding"</h2>
+                                                                    <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">
+                                                                        "When a debt doesn't split evenly into cents, this decides who pays the leftover cents."
+                                                                    </p>
+                                                                    <FormField label="Leftover cents" for_id="rounding_strategy">
+                                                                        <FormSelect
+                                                                            id="rounding_strategy"
+                                                                            value=Signal::derive(move || rounding_signal.get())
+                                                                            on_change=Callback::new(move |val: String| {
+                                                                                rounding_signal.set(val.clone());
+                                                                                set_rounding_action.dispatch(SetRoundingStrategy {
+                                                                                    group_id: group_id.get(),
+                                                                                    strategy: val,
+                                                                                });
+                                                                            })
+                                                                        >
+                                                                            {RoundingStrategy::ALL.into_iter().map(|strategy| view! {
+                                                                                <option value=strategy.as_str()>{strategy.label()}</option>
+                                                                            }).collect_view()}
+                                                                        </FormSelect>
+                                                                    </FormField>
+                                                                    <div class="mt-4">
+                                                                        <ErrorAlert message=rounding_error />
+                                                                    </div>
+                                                                </FormCard>
+                                                                </div>
+
+                                                                // Experimental Features
+                                                                <div class="mt-6">
+                                                                <FormCard>
+                                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Experimental features"</h2>
+                                                                    <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">
+                                                                        "Try out features that are still in development. They only affect this group."
+                                                                    </p>
+                                                                    <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+                                                                        {move || feature_flags_resource.get().map(|result| match result {
+                                                                            Ok(flags) => view! {
+                                                                                <div class="space-y-3">
+                                                                                    {FeatureFlag::ALL.into_iter().map(|flag| {
+                                                                                        let enabled = flags.is_enabled(flag);
+                                                                                        view! {
+                                                                                            <label class="flex items-start cursor-pointer">
+                                                                                                <input
+                                                                                                    type="checkbox"
+                                                                                                    class="mt-1 w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                                                    prop:checked=enabled
+                                                                                                    disabled=move || set_flag_action.pending().get()
+                                                                                                    on:change=move |ev| {
+                                                                                                        set_flag_action.dispatch(SetFeatureFlag {
+                                                                                                            group_id: group_id.get(),
+                                                                                                            key: flag.as_str().to_string(),
+                                                                                                            enabled: event_target_checked(&ev),
+                                                                                                        });
+                                                                                                    }
+                                                                                                />
+                                                                                                <span class="ml-3">
+                                                                                                    <span class="block text-sm font-medium text-gray-900 dark:text-white">{flag.label().to_string()}</span>
+                                                                                                    <span class="block text-sm text-gray-500 dark:text-gray-400">{flag.description().to_string()}</span>
+                                                                                                </span>
+                                                                                            </label>
+                                                                                        }
+                                                                                    }).collect_view()}
+                                                                                </div>
+                                                                            }.into_any(),
+                                                                            Err(e) => view! {
+                                                                                <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
+                                                                            }.into_any(),
+                                                                        })}
+                                                                    </Suspense>
+                                                                    <div class="mt-4">
+                                                                        <ErrorAlert message=flag_error />
+                                                                    </div>
+                                                                </FormCard>
+                                                                </div>
+
+                                                                // Backup
+                                                                <div class="mt-6">
+                                                                <FormCard>
+                                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Backup"</h2>
+                                                                    <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">
+                                                                        "Download everything in this group as JSON, or paste a backup to restore it as a new group. Members are matched by username."
+                                                                    </p>
+                                                                    <a
+                                                                        href=backup_path(group.id)
+                                                                        download
+                                                                        class="inline-block px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                                                    >
+                                                                        "Download JSON backup"
+                                                                    </a>
+                                                                    <textarea
+                                                                        id="backup_json"
+                                                                        rows="4"
+                                                                        placeholder="Paste a group backup"
+                                                                        class="w-full mt-4 px-3 py-2 font-mono text-sm border border-gray-300 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-300 focus:border-indigo-500 dark:focus:border-indigo-600 focus:ring-indigo-500 dark:focus:ring-indigo-600 rounded-md shadow-sm"
+                                                                        prop:value=move || backup_json.get()
+                                                                        on:input=move |ev| set_backup_json.set(event_target_value(&ev))
+                                                                    ></textarea>
+                                                                    <button
+                                                                        type="button"
+                                                                        disabled=move || backup_json.get().trim().is_empty() || import_group_action.pending().get()
+                                                                        on:click=move |_| {
+                                                                            import_group_action.dispatch(ImportGroupJson {
+                                                                                json: backup_json.get(),
+                                                                            });
+                                                                        }
+                                                                        class="mt-2 px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white rounded-lg font-medium transition-colors disabled:opacity-50"
+                                                                    >
+                                                                        "Import as new group"
+                                                                    </button>
+                                                                    <div class="mt-4 space-y-2">
+                                                                        {move || match import_group_action.value().get() {
+                                                                            Some(Ok(result)) => Some(view! {
+                                                                                <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4 text-sm text-green-700 dark:text-green-300">
+                                                                                    <p>
+                                                                                        "Backup imported. "
+                                                                                        <A href=format!("/groups/{}", result.group_id) attr:class="font-medium underline">
+                                                                                            "Open the new group"
+                                                                                        </A>
+                                                                                    </p>
+                                                                                    {(!result.skipped_users.is_empty()).then(|| view! {
+                                                                                        <p class="mt-2">
+                                                                                            "Users without an account here were left out: "
+                                                                                            {result.skipped_users.join(", ")}
+                                                                                        </p>
+                                                                                    })}
+                                                                                    {(result.skipped_records > 0).then(|| view! {
+                                                                                        <p class="mt-2">
+                                                                                            {result.skipped_records} " debt(s) or payment(s) involving them were skipped."
+                                                                                        </p>
+                                                                                    })}
+                                                                                </div>
+                                                                            }),
+                                                                            _ => None,
+                                                                        }}
+                                                                        <ErrorAlert message=import_error />
+                                                                    </div>
+                                                                </FormCard>
+                                                                </div>
+
+                                                                // Data health
+                                                                <div class="mt-6">
+                                                                <FormCard>
+                                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Data health"</h2>
+                                                                    <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">
+                                                                        "Debts and payments that still involve people who left the group."
+                                                                    </p>
+                                                                    <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+                                                                        {move || integrity_resource.get().map(|result| match result {
+                                                                            Ok(report) if report.is_healthy() => view! {
+                                                                                <p class="text-sm text-green-700 dark:text-green-400">"No problems found."</p>
+                                                                            }.into_any(),
+                                                                            Ok(report) => {
+                                                                                let repairs = report.repairs();
+                                                                                view! {
+                                                                                    <div class="space-y-4">
+                                                                                        {IntegrityIssueKind::ALL.into_iter().filter_map(|kind| {
+                                                                                            let descriptions: Vec<String> = report
+                                                                                                .issues
+                                                                                                .iter()
+                                                                                                .filter(|issue| issue.kind == kind)
+                                                                                                .map(|issue| issue.description.clone())
+                                                                                                .collect();
+                                                                                            (!descriptions.is_empty()).then(|| view! {
+                                                                                                <div>
+                                                                                                    <h3 class="text-sm font-medium text-gray-900 dark:text-white">
+                                                                                                        {kind.label()}
+                                                                                                        {kind.repair().is_none().then_some(" (not repairable)")}
+                                                                                                    </h3>
+                                                                                                    <ul class="mt-1 list-disc list-inside text-sm text-gray-600 dark:text-gray-400">
+                                                                                                        {descriptions.into_iter().map(|description| view! { <li>{description}</li> }).collect_view()}
+                                                                                                    </ul>
+                                                                                                </div>
+                                                                                            })
+                                                                                        }).collect_view()}
+                                                                                        {(!repairs.is_empty()).then(|| view! {
+                                                                                            <button
+                                                                                                type="button"
+                                                                                                disabled=move || repair_integrity_action.pending().get()
+                                                                                                on:click=move |_| {
+                                                                                                    repair_integrity_action.dispatch(RepairGroupIntegrity {
+                                                                                                        group_id: group_id.get(),
+                                                                                                        actions: repairs.clone(),
+                                                                                                    });
+                                                                                                }
+                                                                                                class="px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white rounded-lg font-medium transition-colors disabled:opacity-50"
+                                                                                            >
+                                                                                                "Repair"
+                                                                                            </button>
+                                                                                        })}
+                                                                                    </div>
+                                                                                }.into_any()
+                                                                            }
+                                                                            Err(e) => view! {
+                                                                                <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
+                                                                            }.into_any(),
+                                                                        })}
+                                                                    </Suspense>
+                                                                    <div class="mt-4">
+                                                                        <ErrorAlert message=repair_error />
+                                                                    </div>
+                                                                </FormCard>
+                                                                </div>
+
+                                                                // Archive
+                                                                <div class="mt-6">
+                                                                <FormCard>
+                                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Archive"</h2>
+                                                                    <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">
+                                                                        "Freeze the group once it has run its course, e.g. after a trip. Everything stays viewable, but nobody can add or change debts, payments or shopping lists, and recurring debts stop. Admins can unarchive it from the group page."
+                                                                    </p>
+                                                                    <button
+                                                                        type="button"
+                                                                        disabled=move || archive_group_action.pending().get()
+                                                                        on:click=move |_| {
+                                                                            set_archive_error.set(None);
+                                                                            archive_group_action.dispatch(ArchiveGroup {
+                                                                                group_id: group_id.get(),
+                                                                            });
+                                                                        }
+                                                                        class="px-4 py-2 bg-amber-600 hover:bg-amber-700 text-white font-semibold rounded-lg transition-colors disabled:opacity-50"
+                                                                    >
+                                                                        "Archive Group"
+                                                                    </button>
+                                                                    <div class="mt-4">
+                                                                        <ErrorAlert message=archive_error />
+                                                                    </div>
+                                                                </FormCard>
+                                                                </div>
+
+                                                                // Webhooks
+                                                                <div class="mt-6">
+                                                                    <GroupWebhooks group_id=group_id />
+                                                                </div>
+
                                                                 // Danger Zone
                                                                 <div class="mt-6 bg-red-50 dark:bg-red-900/20 rounded-xl shadow-sm border border-red-200 dark:border-red-800 p-6">
                                                                     <h2 class="text-lg font-semibold text-red-900 dark:text-red-200 mb-2">"Danger Zone"</h2>
@@ -284,14 +873,37 @@ pub fn GroupsEdit() -> impl IntoView {
                                                                     </button>
 
                                                                     <ErrorAlert message=delete_error />
+
+                                                                    // Repair tool, collapsed so it stays out of the way
+                                                                    <details class="mt-6 text-sm text-red-700 dark:text-red-300">
+                                                                        <summary class="cursor-pointer">"Troubleshooting"</summary>
+                                                                        <p class="mt-2 mb-3">
+                                                                            "If balances look wrong, recompute them from all debts and payments in this group."
+                                                                        </p>
+                                                                        <button
+                                                                            type="button"
+                                                                            disabled=move || rebuild_balances_action.pending().get()
+                                                                            on:click=move |_| {
+                                                                                rebuild_balances_action.dispatch(RebuildGroupBalances {
+                                                                                    group_id: group_id.get(),
+                                                                                });
+                                                                            }
+                                                                            class="px-4 py-2 bg-white dark:bg-gray-800 border border-red-300 dark:border-red-700 text-red-700 dark:text-red-300 hover:bg-red-100 dark:hover:bg-red-900/40 rounded-lg font-medium transition-colors disabled:opacity-50"
+                                                                        >
+                                                                            "Rebuild balances"
+                                                                        </button>
+                                                                        <div class="mt-3">
+                                                                            {move || matches!(rebuild_balances_action.value().get(), Some(Ok(())))
+                                                                                .then(|| view! { <SuccessAlert message="Balances rebuilt." /> })}
+                                                                            <ErrorAlert message=rebuild_error />
+                                                                        </div>
+                                                                    </details>
                                                                 </div>
                                                             </div>
                                                         }.into_any()
                                                     },
                                                     Some(Err(e)) => view! {
-                                                        <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
-                                                            <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
-                                                        </div>
+                                                        <ErrorCard error=e back_label="Back to groups" back_href="/groups" />
                                                     }.into_any(),
                                                     None => view! { <LoadingSpinner /> }.into_any()
                                                 }
@@ -308,6 +920,57 @@ pub fn GroupsEdit() -> impl IntoView {
             }}
         </Suspense>
 
+        // Remove Member Confirmation Modal
+        {move || {
+            member_to_remove.get().map(|(_, member_name)| {
+                let warning = removal_warning();
+                let has_balance = warning.is_some();
+                view! {
+                    <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50">
+                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-2xl max-w-md w-full p-6">
+                            <h3 class="text-xl font-bold text-gray-900 dark:text-white mb-3">
+                                "Remove " {member_name.clone()} "?"
+                            </h3>
+                            <p class="text-gray-700 dark:text-gray-300 mb-4">
+                                "They will lose access to this group and be taken off its recurring debts. Existing expenses they were part of are kept."
+                            </p>
+                            {warning.map(|msg| view! {
+                                <div class="rounded-md bg-yellow-50 dark:bg-yellow-900/30 p-4 mb-4">
+                                    <p class="text-sm text-yellow-800 dark:text-yellow-200">
+                                        {msg} " Removing them now leaves this balance unsettled."
+                                    </p>
+                                </div>
+                            })}
+                            <div class="mb-4">
+                                <ErrorAlert message=remove_error />
+                            </div>
+                            <div class="flex gap-3">
+                                <button
+                                    on:click=on_remove_confirm
+                                    disabled=move || remove_member_action.pending().get()
+                                    class="flex-1 px-4 py-2 bg-red-600 hover:bg-red-700 disabled:bg-gray-400 text-white font-semibold rounded-lg transition-colors"
+                                >
+                                    {move || if remove_member_action.pending().get() {
+                                        "Removing..."
+                                    } else if has_balance {
+                                        "Remove Anyway"
+                                    } else {
+                                        "Yes, Remove"
+                                    }}
+                                </button>
+                                <button
+                                    on:click=move |_| member_to_remove.set(None)
+                                    class="flex-1 px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white font-semibold rounded-lg transition-colors"
+                                >
+                                    "Cancel"
+                                </button>
+                            </div>
+                        </div>
+                    </div>
+                }
+            })
+        }}
+
         // Delete Confirmation Modal
         {move || {
             if show_delete_modal.get() {
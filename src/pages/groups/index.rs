@@ -1,29 +1,126 @@
 use leptos::prelude::*;
-use leptos_router::hooks::use_navigate;
+use rust_decimal::Decimal;
 
 use crate::{
-    components::{AppLayout, Navigation},
+    components::{AppLayout, Money, Navigation},
     features::{
-        auth::{UserSession, use_logout},
-        groups::handlers::get_user_groups,
+        auth::{UserSession, use_logout, use_redirect_to_login},
+        groups::{
+            handlers::{SetDefaultGroup, get_default_group, get_user_groups},
+            models::GroupWithMembers,
+        },
+        transactions::{GroupNetBalance, NetType, get_user_overall_balances},
     },
 };
 
+/// Sum of the per-group nets, positive when the user is owed money
+fn overall_net(balances: &[GroupNetBalance]) -> Decimal {
+    balances
+        .iter()
+        .map(|balance| balance.net_type.signed_amount(&balance.net_amount))
+        .sum()
+}
+
+/// The colour used for balances elsewhere
+fn net_class(net_type: &NetType) -> &'static str {
+    match net_type {
+        NetType::Positive => "text-green-600 dark:text-green-400",
+        NetType::Negative => "text-red-600 dark:text-red-400",
+        NetType::Neutral => "text-gray-600 dark:text-gray-400",
+    }
+}
+
+/// The user's balance across all groups, with a card per group
+#[must_use]
+#[component]
+pub fn OverallBalances() -> impl IntoView {
+    let balances_resource =
+        LocalResource::new(move || async move { get_user_overall_balances().await });
+
+    view! {
+        <Suspense fallback=move || view! { <div>"Loading balances..."</div> }>
+            {move || {
+                match balances_resource.get() {
+                    Some(Ok(balances)) if !balances.is_empty() => {
+                        let total = overall_net(&balances).round_dp(2);
+                        let total_type = if total > Decimal::ZERO {
+                            NetType::Positive
+                        } else if total < Decimal::ZERO {
+                            NetType::Negative
+                        } else {
+                            NetType::Neutral
+                        };
+                        let total_class = net_class(&total_type);
+                        let total_hint = match total_type {
+                            NetType::Positive => "You are owed overall",
+                            NetType::Negative => "You owe overall",
+                            NetType::Neutral => "You are settled up everywhere",
+                        };
+
+                        view! {
+                            <div class="mb-8 bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6">
+                                <div class="mb-4">
+                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white">"Overall Balance"</h2>
+                                    <p class=format!("text-3xl font-bold mt-1 {total_class}")><Money amount=total signed=true /></p>
+                                    <p class="text-sm text-gray-600 dark:text-gray-400">{total_hint}</p>
+                                </div>
+                                <div class="grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-4 gap-4">
+                                    {balances.into_iter().map(|balance| {
+                                        let class = net_class(&balance.net_type);
+                                        let net = balance.net_type.signed_amount(&balance.net_amount);
+                                        view! {
+                                            <a
+                                                href=format!("/groups/{}", balance.group_id)
+                                                class="rounded-lg border border-gray-200 dark:border-gray-700 p-4 hover:bg-gray-50 dark:hover:bg-gray-700/50 transition-colors"
+                                            >
+                                                <div class="text-sm text-gray-600 dark:text-gray-400 truncate">{balance.group_name}</div>
+                                                <div class=format!("text-lg font-semibold {class}")><Money amount=net signed=true /></div>
+                                            </a>
+                                        }
+                                    }).collect_view()}
+                                </div>
+                            </div>
+                        }.into_any()
+                    }
+                    Some(Err(e)) => view! {
+                        <div class="mb-8 rounded-md bg-red-50 dark:bg-red-900/30 p-4">
+                            <p class="text-sm text-red-700 dark:text-red-300">"Error loading balances: " {e.to_string()}</p>
+                        </div>
+                    }.into_any(),
+                    _ => ().into_any(),
+                }
+            }}
+        </Suspense>
+    }
+}
+
 /// Groups index page - lists all user's groups
 #[must_use]
 #[component]
 pub fn GroupsIndex() -> impl IntoView {
     let user_resource =
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
-    let navigate = use_navigate();
     let on_logout = use_logout();
 
     let groups_resource = LocalResource::new(move || async move { get_user_groups().await });
+    let default_group_resource =
+        LocalResource::new(move || async move { get_default_group().await });
+    let set_default_action = ServerAction::<SetDefaultGroup>::new();
+
+    // Effect to show the new default once it is saved
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = set_default_action.value().get() {
+            default_group_resource.refetch();
+        }
+    });
+
+    let default_group_id = move || default_group_resource.get().and_then(Result::ok).flatten();
 
     // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -50,17 +147,27 @@ pub fn GroupsIndex() -> impl IntoView {
                                                 <h1 class="text-2xl sm:text-3xl font-bold text-gray-900 dark:text-white">"My Groups"</h1>
                                                 <p class="text-gray-600 dark:text-gray-400 mt-1">"Manage your expense groups"</p>
                                             </div>
-                                            <a
-                                                href="/groups/create"
-                                                class="inline-flex items-center px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white font-semibold rounded-lg shadow-md hover:shadow-lg transition-all duration-200"
-                                            >
-                                                <svg class="w-5 h-5 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4" />
-                                                </svg>
-                                                "Create Group"
-                                            </a>
+                                            <div class="flex items-center gap-3">
+                                                <a
+                                                    href="/join"
+                                                    class="px-4 py-2 text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-semibold"
+                                                >
+                                                    "Join with Code"
+                                                </a>
+                                                <a
+                                                    href="/groups/create"
+                                                    class="inline-flex items-center px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white font-semibold rounded-lg shadow-md hover:shadow-lg transition-all duration-200"
+                                                >
+                                                    <svg class="w-5 h-5 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4" />
+                                                    </svg>
+                                                    "Create Group"
+                                                </a>
+                                            </div>
                                         </div>
 
+                                        <OverallBalances />
+
                                         <Suspense fallback=move || view! { <div>"Loading groups..."</div> }>
                                             {move || {
                                                 match groups_resource.get() {
@@ -88,28 +195,70 @@ pub fn GroupsIndex() -> impl IntoView {
                                                                 </div>
                                                             }.into_any()
                                                         } else {
-                                                            view! {
-                                                                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-6">
-                                                                    {groups.into_iter().map(|group| view! {
-                                                                        <a
-                                                                            href=format!("/groups/{}", group.id)
-                                                                            class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 hover:shadow-lg transition-all duration-200"
-                                                                        >
-                                                                            <div class="flex justify-between items-start mb-4">
-                                                                                <h3 class="text-lg font-semibold text-gray-900 dark:text-white">{group.name}</h3>
+                                                            let (archived, active): (Vec<_>, Vec<_>) =
+                                                                groups.into_iter().partition(|group| group.is_archived);
+                                                            let group_card = move |group: GroupWithMembers| {
+                                                                let group_id = group.id;
+                                                                let is_default = move || default_group_id() == Some(group_id);
+                                                                view! {
+                                                                    <a
+                                                                        href=format!("/groups/{}", group.id)
+                                                                        class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 hover:shadow-lg transition-all duration-200"
+                                                                    >
+                                                                        <div class="flex justify-between items-start mb-4">
+                                                                            <h3 class="text-lg font-semibold text-gray-900 dark:text-white">{group.name}</h3>
+                                                                            <div class="flex items-center gap-2">
                                                                                 {group.is_admin.then(|| view! {
                                                                                     <span class="px-2 py-1 bg-indigo-100 dark:bg-indigo-900/30 text-indigo-700 dark:text-indigo-300 text-xs font-medium rounded">"Admin"</span>
                                                                                 })}
+                                                                                <button
+                                                                                    type="button"
+                                                                                    title=move || if is_default() { "Default group; click to unset" } else { "Open this group after logging in" }
+                                                                                    class=move || if is_default() {
+                                                                                        "text-yellow-500 hover:text-yellow-600"
+                                                                                    } else {
+                                                                                        "text-gray-300 dark:text-gray-600 hover:text-yellow-500"
+                                                                                    }
+                                                                                    disabled=move || set_default_action.pending().get()
+                                                                                    on:click=move |ev| {
+                                                                                        // The card itself is a link
+                                                                                        ev.prevent_default();
+                                                                                        ev.stop_propagation();
+                                                                                        set_default_action.dispatch(SetDefaultGroup {
+                                                                                            group_id: (!is_default()).then_some(group_id),
+                                                                                        });
+                                                                                    }
+                                                                                >
+                                                                                    <svg class="w-5 h-5" fill="currentColor" viewBox="0 0 20 20">
+                                                                                        <path d="M9.049 2.927c.3-.921 1.603-.921 1.902 0l1.07 3.292a1 1 0 00.95.69h3.462c.969 0 1.371 1.24.588 1.81l-2.8 2.034a1 1 0 00-.364 1.118l1.07 3.292c.3.921-.755 1.688-1.54 1.118l-2.8-2.034a1 1 0 00-1.175 0l-2.8 2.034c-.784.57-1.838-.197-1.539-1.118l1.07-3.292a1 1 0 00-.364-1.118L2.98 8.72c-.783-.57-.38-1.81.588-1.81h3.461a1 1 0 00.951-.69l1.07-3.292z" />
+                                                                                    </svg>
+                                                                                </button>
                                                                             </div>
-                                                                            <div class="flex items-center text-sm text-gray-600 dark:text-gray-400">
-                                                                                <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                                                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4.354a4 4 0 110 5.292M15 21H3v-1a6 6 0 0112 0v1zm0 0h6v-1a6 6 0 00-9-5.197M13 7a4 4 0 11-8 0 4 4 0 018 0z" />
-                                                                                </svg>
-                                                                                {format!("{} member{}", group.member_count, if group.member_count == 1 { "" } else { "s" })}
-                                                                            </div>
-                                                                        </a>
-                                                                    }).collect_view()}
+                                                                        </div>
+                                                                        <div class="flex items-center text-sm text-gray-600 dark:text-gray-400">
+                                                                            <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4.354a4 4 0 110 5.292M15 21H3v-1a6 6 0 0112 0v1zm0 0h6v-1a6 6 0 00-9-5.197M13 7a4 4 0 11-8 0 4 4 0 018 0z" />
+                                                                            </svg>
+                                                                            {format!("{} member{}", group.member_count, if group.member_count == 1 { "" } else { "s" })}
+                                                                        </div>
+                                                                    </a>
+                                                                }
+                                                            };
+                                                            view! {
+                                                                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-6">
+                                                                    {active.into_iter().map(group_card).collect_view()}
                                                                 </div>
+                                                                // Archived groups are kept out of the way
+                                                                {(!archived.is_empty()).then(|| view! {
+                                                                    <details class="mt-8">
+                                                                        <summary class="cursor-pointer text-sm font-medium text-gray-600 dark:text-gray-400">
+                                                                            {format!("Archived groups ({})", archived.len())}
+                                                                        </summary>
+                                                                        <div class="mt-4 grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-6 opacity-75">
+                                                                            {archived.into_iter().map(group_card).collect_view()}
+                                                                        </div>
+                                                                    </details>
+                                                                })}
                                                             }.into_any()
                                                         }
                                                     },
@@ -1,19 +1,21 @@
 use leptos::prelude::*;
-use leptos_router::{
-    components::A,
-    hooks::{use_navigate, use_params_map},
-};
+use leptos_router::{components::A, hooks::use_params_map};
 
 use crate::{
-    components::{AppLayout, FormField, FormInput, Navigation},
+    components::{AppLayout, ErrorCard, FormField, FormInput, Navigation},
     features::{
-        auth::{UserSession, use_logout},
-        groups::handlers::get_group,
+        auth::{UserSession, use_logout, use_redirect_to_login},
+        groups::{handlers::get_group, models::GroupRole},
         invites::{
-            handlers::{CreateInvite, DeleteInvite, get_group_invites},
-            models::InviteListItem,
+            handlers::{
+                CreateInvite, DeleteInvite, generate_invite_qr, get_group_invites,
+                get_invite_redemptions,
+            },
+            models::{InviteListItem, InviteRedemption, InviteRedemptions},
+            utils::invite_url,
         },
     },
+    pages::groups::join_requests::JoinRequestsPanel,
 };
 
 /// Groups invites page - manage invites for a group
@@ -24,7 +26,6 @@ pub fn GroupsInvites() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let create_invite_action = ServerAction::<CreateInvite>::new();
     let delete_invite_action = ServerAction::<DeleteInvite>::new();
-    let navigate = use_navigate();
     let on_logout = use_logout();
     let params = use_params_map();
 
@@ -43,18 +44,58 @@ pub fn GroupsInvites() -> impl IntoView {
 
     let invites_resource = LocalResource::new(move || {
         let id = group_id.get();
-        async move { get_group_invites(id).await }
+        async move { get_group_invites(id, false).await }
+    });
+
+    let expired_invites_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move {
+            get_group_invites(id, true).await.map(|invites| {
+                invites
+                    .into_iter()
+                    .filter(|i| i.is_expired)
+                    .collect::<Vec<_>>()
+            })
+        }
+    });
+
+    let redemptions_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { get_invite_redemptions(id).await }
+    });
+    // Redemptions of one invite, empty while loading or on error
+    let redemptions_for = move |uuid: &str| -> Vec<InviteRedemption> {
+        redemptions_resource
+            .get()
+            .and_then(Result::ok)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|invite: &InviteRedemptions| invite.invite_uuid == uuid)
+            .map(|invite| invite.redemptions)
+            .unwrap_or_default()
+    };
+
+    // QR code of the invite picked via "Show QR", rendered for the browser's
+    // origin so it matches the copied link
+    let show_qr_modal = RwSignal::new(false);
+    let qr_action = Action::new(move |uuid: &String| {
+        let uuid = uuid.clone();
+        let origin = window().location().origin().unwrap_or_default();
+        async move { generate_invite_qr(uuid, origin).await }
     });
 
     // Form signals
     let (name_signal, set_name_signal) = signal(String::new());
     let (duration_days_signal, set_duration_days_signal) = signal(String::from("7"));
     let is_reusable_signal = RwSignal::new(false);
+    let (max_uses_signal, set_max_uses_signal) = signal(String::new());
+    let viewer_only_signal = RwSignal::new(false);
 
     // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -66,6 +107,8 @@ pub fn GroupsInvites() -> impl IntoView {
             set_name_signal.set(String::new());
             set_duration_days_signal.set(String::from("7"));
             is_reusable_signal.set(false);
+            set_max_uses_signal.set(String::new());
+            viewer_only_signal.set(false);
         }
     });
 
@@ -73,6 +116,7 @@ pub fn GroupsInvites() -> impl IntoView {
     Effect::new(move |_| {
         if let Some(Ok(_)) = delete_invite_action.value().get() {
             invites_resource.refetch();
+            expired_invites_resource.refetch();
         }
     });
 
@@ -90,6 +134,12 @@ pub fn GroupsInvites() -> impl IntoView {
             },
             duration_days: duration,
             is_reusable: is_reusable_signal.get(),
+            max_uses: max_uses_signal.get().trim().parse::<i64>().ok(),
+            grants_role: if viewer_only_signal.get() {
+                GroupRole::Viewer
+            } else {
+                GroupRole::Member
+            },
         });
     };
 
@@ -111,7 +161,7 @@ pub fn GroupsInvites() -> impl IntoView {
                                             {move || {
                                                 match group_resource.get() {
                                                     Some(Ok(group)) => {
-                                                        let is_admin = group.created_by == user.id;
+                                                        let is_admin = group.is_admin;
 
                                                         if !is_admin {
                                                             return view! {
@@ -137,6 +187,8 @@ pub fn GroupsInvites() -> impl IntoView {
                                                                     </h1>
                                                                 </div>
 
+                                                                <JoinRequestsPanel group_id=group.id />
+
                                                                 // Create Invite Form
                                                                 <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
                                                                     <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Create New Invite"</h2>
@@ -174,6 +226,35 @@ pub fn GroupsInvites() -> impl IntoView {
                                                                             </label>
                                                                         </div>
 
+                                                                        <Show when=move || is_reusable_signal.get()>
+                                                                            <FormField
+                                                                                label="Max Uses (optional)"
+                                                                                for_id="max_uses"
+                                                                                helper_text="Leave empty to allow unlimited uses."
+                                                                            >
+                                                                                <FormInput
+                                                                                    id="max_uses"
+                                                                                    input_type="number"
+                                                                                    placeholder="e.g., 10"
+                                                                                    value=Signal::derive(move || max_uses_signal.get())
+                                                                                    on_input=Callback::new(move |val| set_max_uses_signal.set(val))
+                                                                                />
+                                                                            </FormField>
+                                                                        </Show>
+
+                                                                        <div class="flex items-center">
+                                                                            <input
+                                                                                type="checkbox"
+                                                                                id="viewer_only"
+                                                                                class="h-4 w-4 text-indigo-600 focus:ring-indigo-500 border-gray-300 dark:border-gray-700 rounded bg-white dark:bg-gray-700"
+                                                                                prop:checked=move || viewer_only_signal.get()
+                                                                                on:change=move |ev| viewer_only_signal.set(event_target_checked(&ev))
+                                                                            />
+                                                                            <label for="viewer_only" class="ml-2 text-sm text-gray-700 dark:text-gray-300">
+                                                                                "Viewer only (can see debts and balances, but not change anything)"
+                                                                            </label>
+                                                                        </div>
+
                                                                         <button
                                                                             type="submit"
                                                                             disabled=move || create_invite_action.pending().get()
@@ -219,6 +300,7 @@ pub fn GroupsInvites() -> impl IntoView {
                                                                                                 {invites.into_iter().map(|invite: InviteListItem| {
                                                                                                     let invite_uuid = invite.uuid.clone();
                                                                                                     let invite_uuid_for_delete = invite.uuid.clone();
+                                                                                                    let invite_uuid_for_qr = invite.uuid.clone();
                                                                                                     let group_id_val = group_id.get();
 
                                                                                                     // Signal to track copied state for this invite
@@ -245,15 +327,21 @@ pub fn GroupsInvites() -> impl IntoView {
                                                                                                                                 </span>
                                                                                                                             }.into_any()
                                                                                                                         }}
+                                                                                                                        {(invite.grants_role == GroupRole::Viewer).then(|| view! {
+                                                                                                                            <span class="px-2 py-1 bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 text-xs font-medium rounded">
+                                                                                                                                "Viewer"
+                                                                                                                            </span>
+                                                                                                                        })}
                                                                                                                     </div>
                                                                                                                     <p class="text-sm text-gray-600 dark:text-gray-400">
-                                                                                                                        "Expires: " {invite.expiration_date}
+                                                                                                                        "Expires: " {invite.expiration_date.clone()} " · " {invite.usage_label()}
                                                                                                                     </p>
+                                                                                                                    <InviteRedemptionsList redemptions=redemptions_for(&invite.uuid) />
                                                                                                     <div class="mt-2 bg-gray-50 dark:bg-gray-700/50 rounded-lg p-3 border border-gray-200 dark:border-gray-600">
                                                                                                         <div class="flex items-center gap-3">
                                                                                                             <div class="flex-1 min-w-0">
                                                                                                                 <code class="text-xs text-gray-800 dark:text-gray-200 break-all block">
-                                                                                                                    {window().location().origin().map(|origin| format!("{}/invite/{}", origin, invite_uuid.clone())).unwrap_or_else(|_| format!("/invite/{}", invite_uuid.clone()))}
+                                                                                                                    {invite_url(&window().location().origin().unwrap_or_default(), &invite_uuid)}
                                                                                                                 </code>
                                                                                                             </div>
                                                                                                             <button
@@ -266,9 +354,10 @@ pub fn GroupsInvites() -> impl IntoView {
                                                                                                                     #[cfg(target_arch = "wasm32")]
                                                                                                                     {
                                                                                                                         if let Some(window) = web_sys::window() {
-                                                                                                                            let full_url = window.location().origin()
-                                                                                                                                .map(|origin| format!("{}/invite/{}", origin, invite_uuid.clone()))
-                                                                                                                                .unwrap_or_else(|_| format!("/invite/{}", invite_uuid.clone()));
+                                                                                                                            let full_url = invite_url(
+                                                                                                                                &window.location().origin().unwrap_or_default(),
+                                                                                                                                &invite_uuid,
+                                                                                                                            );
                                                                                                                             let navigator = window.navigator();
                                                                                                                             let clipboard = navigator.clipboard();
                                                                                                                             let _ = clipboard.write_text(&full_url);
@@ -299,6 +388,15 @@ pub fn GroupsInvites() -> impl IntoView {
                                                                                                                     }
                                                                                                                 }}
                                                                                                             </button>
+                                                                                                            <button
+                                                                                                                class="flex-shrink-0 inline-flex items-center px-3 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-800 dark:text-gray-100 text-xs font-medium rounded-lg transition-colors"
+                                                                                                                on:click=move |_| {
+                                                                                                                    qr_action.dispatch(invite_uuid_for_qr.clone());
+                                                                                                                    show_qr_modal.set(true);
+                                                                                                                }
+                                                                                                            >
+                                                                                                                "Show QR"
+                                                                                                            </button>
                                                                                                         </div>
                                                                                                     </div>
                                                                                                                 </div>
@@ -331,13 +429,54 @@ pub fn GroupsInvites() -> impl IntoView {
                                                                         }}
                                                                     </Suspense>
                                                                 </div>
+
+                                                                // Expired and used-up invites, kept until the maintenance job purges them
+                                                                {move || {
+                                                                    let expired = expired_invites_resource.get()?.ok()?;
+                                                                    if expired.is_empty() {
+                                                                        return None;
+                                                                    }
+                                                                    Some(view! {
+                                                                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mt-6 opacity-75">
+                                                                            <h2 class="text-lg font-semibold text-gray-500 dark:text-gray-400 mb-4">"Expired or Used Up"</h2>
+                                                                            <div class="space-y-3">
+                                                                                {expired.into_iter().map(|invite| {
+                                                                                    let uuid = invite.uuid.clone();
+                                                                                    let group_id_val = group_id.get();
+                                                                                    view! {
+                                                                                        <div class="flex justify-between items-center border border-gray-200 dark:border-gray-700 rounded-lg p-4 bg-gray-50 dark:bg-gray-800/50">
+                                                                                            <div>
+                                                                                                <span class="font-medium text-gray-500 dark:text-gray-400">
+                                                                                                    {invite.name.clone().unwrap_or_else(|| "Invite".to_string())}
+                                                                                                </span>
+                                                                                                <p class="text-sm text-gray-400 dark:text-gray-500">
+                                                                                                    "Expiry: " {invite.expiration_date.clone()} " · " {invite.usage_label()}
+                                                                                                </p>
+                                                                                                <InviteRedemptionsList redemptions=redemptions_for(&invite.uuid) />
+                                                                                            </div>
+                                                                                            <button
+                                                                                                class="px-3 py-1.5 text-sm bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-600 dark:text-gray-300 rounded-lg font-medium transition-colors"
+                                                                                                on:click=move |_| {
+                                                                                                    delete_invite_action.dispatch(DeleteInvite {
+                                                                                                        uuid: uuid.clone(),
+                                                                                                        group_id: group_id_val,
+                                                                                                    });
+                                                                                                }
+                                                                                            >
+                                                                                                "Delete"
+                                                                                            </button>
+                                                                                        </div>
+                                                                                    }
+                                                                                }).collect_view()}
+                                                                            </div>
+                                                                        </div>
+                                                                    })
+                                                                }}
                                                             </div>
                                                         }.into_any()
                                                     },
                                                     Some(Err(e)) => view! {
-                                                        <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
-                                                            <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
-                                                        </div>
+                                                        <ErrorCard error=e back_label="Back to groups" back_href="/groups" />
                                                     }.into_any(),
                                                     None => view! { <div>"Loading..."</div> }.into_any()
                                                 }
@@ -346,6 +485,40 @@ pub fn GroupsInvites() -> impl IntoView {
                                     </div>
                                 </div>
                             </AppLayout>
+
+                            <Show when=move || show_qr_modal.get()>
+                                <div
+                                    class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50"
+                                    on:click=move |_| show_qr_modal.set(false)
+                                >
+                                    <div
+                                        class="bg-white dark:bg-gray-800 rounded-xl shadow-2xl max-w-sm w-full p-6"
+                                        on:click=|ev| ev.stop_propagation()
+                                    >
+                                        <h3 class="text-xl font-bold text-gray-900 dark:text-white mb-4">"Scan to Join"</h3>
+                                        {move || match qr_action.value().get() {
+                                            Some(Ok(svg)) if !qr_action.pending().get() => view! {
+                                                <div class="w-full max-w-xs mx-auto aspect-square bg-white rounded-lg" inner_html=svg></div>
+                                            }.into_any(),
+                                            Some(Err(e)) if !qr_action.pending().get() => view! {
+                                                <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
+                                                    <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
+                                                </div>
+                                            }.into_any(),
+                                            _ => view! {
+                                                <div class="w-full max-w-xs mx-auto aspect-square bg-gray-100 dark:bg-gray-700 rounded-lg animate-pulse"></div>
+                                            }.into_any(),
+                                        }}
+                                        <button
+                                            type="button"
+                                            on:click=move |_| show_qr_modal.set(false)
+                                            class="mt-4 w-full px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                        >
+                                            "Close"
+                                        </button>
+                                    </div>
+                                </div>
+                            </Show>
                         </div>
                     }.into_any(),
                     _ => view! {
@@ -358,3 +531,47 @@ pub fn GroupsInvites() -> impl IntoView {
         </Suspense>
     }
 }
+
+/// Expandable list of the users who joined through an invite
+///
+/// Users who have since left the group are greyed out.
+#[must_use]
+#[component]
+fn InviteRedemptionsList(redemptions: Vec<InviteRedemption>) -> impl IntoView {
+    if redemptions.is_empty() {
+        return None;
+    }
+
+    Some(view! {
+        <details class="mt-2">
+            <summary class="text-sm text-indigo-600 dark:text-indigo-400 cursor-pointer">
+                {format!("Joined via this invite ({})", redemptions.len())}
+            </summary>
+            <ul class="mt-2 space-y-1">
+                {redemptions.into_iter().map(|redemption| {
+                    let class = if redemption.is_member {
+                        "flex justify-between text-sm text-gray-900 dark:text-white"
+                    } else {
+                        "flex justify-between text-sm text-gray-400 dark:text-gray-500"
+                    };
+                    view! {
+                        <li class=class>
+                            <span>
+                                {redemption.username}
+                                {(!redemption.is_member).then_some(" (left the group)")}
+                            </span>
+                            <span>
+                                {format!(
+                                    "{} {:02}:{:02}",
+                                    redemption.redeemed_at.date(),
+                                    redemption.redeemed_at.hour(),
+                                    redemption.redeemed_at.minute()
+                                )}
+                            </span>
+                        </li>
+                    }
+                }).collect_view()}
+            </ul>
+        </details>
+    })
+}
@@ -0,0 +1,158 @@
+use leptos::prelude::*;
+
+use crate::{
+    components::ErrorAlert,
+    error::AppError,
+    features::invites::join_requests::{
+        ApproveJoinRequest, RejectJoinRequest, SetJoinCode, get_join_code, list_join_requests,
+    },
+};
+
+/// Join code settings and the requests waiting for an admin's answer
+#[must_use]
+#[component]
+pub fn JoinRequestsPanel(group_id: i64) -> impl IntoView {
+    let join_code_resource = LocalResource::new(move || get_join_code(group_id));
+    let requests_resource = LocalResource::new(move || list_join_requests(group_id));
+
+    let set_code_action = ServerAction::<SetJoinCode>::new();
+    let approve_action = ServerAction::<ApproveJoinRequest>::new();
+    let reject_action = ServerAction::<RejectJoinRequest>::new();
+    let (error, set_error) = signal(None::<String>);
+
+    Effect::new(move |_| match set_code_action.value().get() {
+        Some(Ok(_)) => {
+            set_error.set(None);
+            join_code_resource.refetch();
+        }
+        Some(Err(e)) => set_error.set(Some(e.to_string())),
+        None => {}
+    });
+
+    // Answered requests drop out of the list, also when another admin was faster
+    let on_answered = move |result: Option<Result<(), AppError>>| {
+        let Some(result) = result else {
+            return;
+        };
+        set_error.set(result.err().map(|e| e.to_string()));
+        requests_resource.refetch();
+    };
+    Effect::new(move |_| on_answered(approve_action.value().get()));
+    Effect::new(move |_| on_answered(reject_action.value().get()));
+
+    let on_rotate = move |_| {
+        let confirmed = window()
+            .confirm_with_message("Create a new join code? The current one stops working.")
+            .unwrap_or(false);
+        if confirmed {
+            set_code_action.dispatch(SetJoinCode {
+                group_id,
+                enabled: true,
+            });
+        }
+    };
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6 space-y-6">
+            <ErrorAlert message=error />
+
+            <div>
+                <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-1">"Join Code"</h2>
+                <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                    "Anyone with the code can ask to join at /join; you decide who gets in."
+                </p>
+                {move || match join_code_resource.get() {
+                    Some(Ok(Some(code))) => view! {
+                        <div class="flex flex-wrap items-center gap-3">
+                            <span class="px-3 py-1.5 font-mono text-lg tracking-widest bg-gray-100 dark:bg-gray-700 text-gray-900 dark:text-white rounded-lg">
+                                {code}
+                            </span>
+                            <button
+                                type="button"
+                                on:click=on_rotate
+                                disabled=move || set_code_action.pending().get()
+                                class="px-4 py-2 text-sm font-medium text-indigo-700 dark:text-indigo-300 bg-indigo-50 dark:bg-indigo-900/30 rounded-lg hover:bg-indigo-100 dark:hover:bg-indigo-900/50 disabled:opacity-50"
+                            >
+                                "New Code"
+                            </button>
+                            <button
+                                type="button"
+                                on:click=move |_| {
+                                    set_code_action.dispatch(SetJoinCode { group_id, enabled: false });
+                                }
+                                disabled=move || set_code_action.pending().get()
+                                class="px-4 py-2 text-sm font-medium text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/20 rounded-lg disabled:opacity-50"
+                            >
+                                "Turn Off"
+                            </button>
+                        </div>
+                    }.into_any(),
+                    Some(Ok(None)) => view! {
+                        <button
+                            type="button"
+                            on:click=move |_| {
+                                set_code_action.dispatch(SetJoinCode { group_id, enabled: true });
+                            }
+                            disabled=move || set_code_action.pending().get()
+                            class="px-4 py-2 bg-indigo-600 hover:bg-indigo-700 disabled:bg-gray-400 text-white font-semibold rounded-lg transition-colors"
+                        >
+                            "Enable Join Code"
+                        </button>
+                    }.into_any(),
+                    Some(Err(e)) => view! {
+                        <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
+                    }.into_any(),
+                    None => view! { <div>"Loading..."</div> }.into_any(),
+                }}
+            </div>
+
+            <div>
+                <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Pending Requests"</h2>
+                {move || match requests_resource.get() {
+                    Some(Ok(requests)) if requests.is_empty() => view! {
+                        <p class="text-gray-500 dark:text-gray-400">"Nobody is waiting to join."</p>
+                    }.into_any(),
+                    Some(Ok(requests)) => view! {
+                        <ul class="divide-y divide-gray-200 dark:divide-gray-700">
+                            {requests.into_iter().map(|request| {
+                                let request_id = request.id;
+                                view! {
+                                    <li class="py-3 flex items-center justify-between gap-3">
+                                        <div>
+                                            <p class="font-medium text-gray-900 dark:text-white">{request.username}</p>
+                                            <p class="text-xs text-gray-500 dark:text-gray-400">
+                                                "Asked on " {request.created_at.date().to_string()}
+                                            </p>
+                                        </div>
+                                        <div class="flex gap-2">
+                                            <button
+                                                type="button"
+                                                on:click=move |_| { approve_action.dispatch(ApproveJoinRequest { request_id }); }
+                                                disabled=move || approve_action.pending().get() || reject_action.pending().get()
+                                                class="px-3 py-1.5 text-sm bg-green-600 hover:bg-green-700 text-white font-medium rounded-lg disabled:opacity-50"
+                                            >
+                                                "Approve"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                on:click=move |_| { reject_action.dispatch(RejectJoinRequest { request_id }); }
+                                                disabled=move || approve_action.pending().get() || reject_action.pending().get()
+                                                class="px-3 py-1.5 text-sm bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white font-medium rounded-lg disabled:opacity-50"
+                                            >
+                                                "Reject"
+                                            </button>
+                                        </div>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                    }.into_any(),
+                    Some(Err(e)) => view! {
+                        <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
+                    }.into_any(),
+                    None => view! { <div>"Loading requests..."</div> }.into_any(),
+                }}
+            </div>
+        </div>
+    }
+}
@@ -1,11 +1,19 @@
 pub mod create;
+pub mod digest;
 pub mod edit;
 pub mod index;
 pub mod invites;
+pub mod join_requests;
+pub mod scope;
 pub mod show;
+pub mod stats;
+pub mod webhooks;
 
 pub use create::GroupsCreate;
+pub use digest::GroupsDigest;
 pub use edit::GroupsEdit;
 pub use index::GroupsIndex;
 pub use invites::GroupsInvites;
+pub use scope::GroupScope;
 pub use show::GroupsShow;
+pub use stats::GroupsStats;
@@ -0,0 +1,61 @@
+use leptos::prelude::*;
+use leptos_router::hooks::use_location;
+
+use crate::{
+    components::{EURO, GroupCurrency},
+    features::groups::{handlers::get_group, utils::currency_symbol},
+};
+
+/// Id of the group a path like "/groups/3/debts/create" belongs to
+fn group_id_from_path(path: &str) -> Option<i64> {
+    path.strip_prefix("/groups/")?
+        .split('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Provides the [`GroupCurrency`] of the group whose pages are on screen,
+/// so every page under "/groups/:id" shows amounts in it
+///
+/// The group is only fetched again when navigating to another group.
+#[must_use]
+#[component]
+pub fn GroupScope(children: Children) -> impl IntoView {
+    let location = use_location();
+    let group_id = Memo::new(move |_| location.pathname.with(|path| group_id_from_path(path)));
+
+    let group_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move {
+            match id {
+                Some(id) => get_group(id).await.ok(),
+                None => None,
+            }
+        }
+    });
+
+    let currency = Signal::derive(move || {
+        group_id
+            .get()
+            .and(group_resource.get().flatten())
+            .map_or(EURO, |group| currency_symbol(&group.currency))
+    });
+    provide_context(GroupCurrency(currency));
+
+    children()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_id_from_path() {
+        assert_eq!(group_id_from_path("/groups/3"), Some(3));
+        assert_eq!(group_id_from_path("/groups/3/debts/create"), Some(3));
+        assert_eq!(group_id_from_path("/groups/create"), None);
+        assert_eq!(group_id_from_path("/groups"), None);
+        assert_eq!(group_id_from_path("/profile"), None);
+    }
+}
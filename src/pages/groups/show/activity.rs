@@ -0,0 +1,48 @@
+use leptos::prelude::*;
+
+use super::common::SectionSkeleton;
+
+use crate::{components::CachedResource, error::AppError, features::groups::models::GroupActivity};
+
+/// Recent changes to the group's debts and payments
+#[must_use]
+#[component]
+pub fn ActivitySection(
+    activity_resource: CachedResource<Result<Vec<GroupActivity>, AppError>>,
+) -> impl IntoView {
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
+            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Recent Activity"</h2>
+            <Suspense fallback=move || view! { <SectionSkeleton rows=3 row_height="h-10" /> }>
+                {move || {
+                    match activity_resource.get() {
+                        Some(Ok(activity)) if activity.is_empty() => view! {
+                            <p class="text-sm text-gray-500 dark:text-gray-400">"No activity yet."</p>
+                        }.into_any(),
+                        Some(Ok(activity)) => view! {
+                            <ul class="divide-y divide-gray-200 dark:divide-gray-700">
+                                {activity.into_iter().map(|entry| view! {
+                                    <li class="py-2">
+                                        <p class="text-sm text-gray-900 dark:text-white">{entry.description()}</p>
+                                        <p class="text-xs text-gray-500 dark:text-gray-400">
+                                            {format!(
+                                                "{} {:02}:{:02}",
+                                                entry.created_at.date(),
+                                                entry.created_at.hour(),
+                                                entry.created_at.minute()
+                                            )}
+                                        </p>
+                                    </li>
+                                }).collect_view()}
+                            </ul>
+                        }.into_any(),
+                        Some(Err(e)) => view! {
+                            <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
+                        }.into_any(),
+                        None => view! { <SectionSkeleton rows=3 row_height="h-10" /> }.into_any()
+                    }
+                }}
+            </Suspense>
+        </div>
+    }
+}
@@ -1,83 +1,348 @@
 use leptos::prelude::*;
+use rust_decimal::Decimal;
 
-use crate::features::transactions::models::{NetType, RelationshipType, UserBalance};
+use super::common::SectionSkeleton;
+
+use crate::{
+    components::{CachedResource, Money, forms::ErrorAlert},
+    error::{AppError, handle_app_error},
+    features::{
+        auth::use_redirect_to_login,
+        groups::utils::current_utc_month,
+        transactions::{
+            handlers::SettleAllMyDebts,
+            models::{
+                DebtRelationship, NetType, RelationshipType, SettlementSuggestion, UserBalance,
+            },
+        },
+    },
+};
+
+/// Which debts and payments the full balance matrix counts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalanceRange {
+    AllTime,
+    ThisMonth,
+    LastMonth,
+}
+
+impl BalanceRange {
+    const ALL: [Self; 3] = [Self::ThisMonth, Self::LastMonth, Self::AllTime];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::AllTime => "All time",
+            Self::ThisMonth => "This month",
+            Self::LastMonth => "Last month",
+        }
+    }
+
+    /// First and last day to count (YYYY-MM-DD), `None` for an open end
+    pub fn bounds(self) -> (Option<String>, Option<String>) {
+        let (year, month) = current_utc_month();
+        let (year, month) = match self {
+            Self::AllTime => return (None, None),
+            Self::ThisMonth => (year, month),
+            Self::LastMonth if month == 1 => (year - 1, 12),
+            Self::LastMonth => (year, month - 1),
+        };
+        match month_days(year, month) {
+            Some((first, last)) => (Some(first.to_string()), Some(last.to_string())),
+            None => (None, None),
+        }
+    }
+}
+
+/// First and last day of a month
+fn month_days(year: i32, month: u8) -> Option<(time::Date, time::Date)> {
+    let month = time::Month::try_from(month).ok()?;
+    let first = time::Date::from_calendar_date(year, month, 1).ok()?;
+    let next_year = if month == time::Month::December {
+        year + 1
+    } else {
+        year
+    };
+    let next_first = time::Date::from_calendar_date(next_year, month.next(), 1).ok()?;
+    Some((first, next_first.previous_day()?))
+}
 
 /// Balance overview section component
+///
+/// Shows the current user's own balance by default. The full matrix of every
+/// member's balances is only fetched once the user asks for it, optionally
+/// for a single month.
 #[must_use]
 #[component]
 pub fn BalancesSection(
-    balances_resource: LocalResource<Result<Vec<UserBalance>, ServerFnError>>,
+    group_id: Memo<i64>,
+    /// Current user, who can record the payments they are suggested to make
+    user_id: i64,
+    my_balance_resource: CachedResource<Result<UserBalance, AppError>>,
+    balances_resource: LocalResource<Result<Vec<UserBalance>, AppError>>,
+    /// Whether the full matrix is shown; `balances_resource` only loads while set
+    show_full_matrix: RwSignal<bool>,
+    /// Debts and payments the full matrix counts
+    balance_range: RwSignal<BalanceRange>,
+    /// Whether the user may view the full matrix in this group
+    full_matrix_allowed: bool,
+    settlement_resource: CachedResource<Result<Vec<SettlementSuggestion>, AppError>>,
+    /// Records a payment to everyone the current user owes
+    settle_all_action: ServerAction<SettleAllMyDebts>,
+    /// Whether the user may record payments; viewers may not
+    can_edit: bool,
 ) -> impl IntoView {
+    let show_settle_all_modal = RwSignal::new(false);
+    let (settle_all_error, set_settle_all_error) = signal(None::<String>);
+    // What the user owes, as the payments settling up would record
+    let my_debts = move || {
+        my_balance_resource
+            .get()
+            .and_then(Result::ok)
+            .map(|balance| {
+                balance
+                    .relationships
+                    .into_iter()
+                    .filter(|rel| matches!(rel.relationship_type, RelationshipType::Owes))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+
+    let redirect_on_error = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = settle_all_action.value().get() {
+            match result {
+                Ok(_) => show_settle_all_modal.set(false),
+                Err(e) => set_settle_all_error.set(handle_app_error(&e, &redirect_on_error)),
+            }
+        }
+    });
+
     view! {
         <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
-            <h2 class="text-xl font-semibold text-gray-900 dark:text-white mb-4">"Balance Overview"</h2>
-            <Suspense fallback=move || view! { <div>"Loading balances..."</div> }>
+            <div class="flex items-center justify-between mb-4">
+                <h2 class="text-xl font-semibold text-gray-900 dark:text-white">"Balance Overview"</h2>
+                {full_matrix_allowed.then(|| view! {
+                    <button
+                        type="button"
+                        on:click=move |_| show_full_matrix.update(|v| *v = !*v)
+                        class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
+                    >
+                        {move || if show_full_matrix.get() { "Show only my balance" } else { "Show full group matrix" }}
+                    </button>
+                })}
+            </div>
+            {move || show_full_matrix.get().then(|| view! {
+                <div class="flex flex-wrap gap-2 mb-4" role="group" aria-label="Balance period">
+                    {BalanceRange::ALL.into_iter().map(|range| view! {
+                        <button
+                            type="button"
+                            on:click=move |_| balance_range.set(range)
+                            aria-pressed=move || (balance_range.get() == range).to_string()
+                            class=move || if balance_range.get() == range {
+                                "px-3 py-1.5 bg-indigo-600 text-white rounded-lg text-sm font-medium transition-colors"
+                            } else {
+                                "px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors"
+                            }
+                        >
+                            {range.label()}
+                        </button>
+                    }).collect_view()}
+                </div>
+            })}
+            <Suspense fallback=move || view! { <SectionSkeleton rows=1 row_height="h-40" /> }>
                 {move || {
-                    match balances_resource.get() {
-                        Some(Ok(balances)) if balances.is_empty() => view! {
-                            <p class="text-gray-500 dark:text-gray-400 text-center py-4">"No debt information available"</p>
+                    if show_full_matrix.get() {
+                        match balances_resource.get() {
+                            Some(Ok(balances)) if balances.is_empty() => view! {
+                                <p class="text-gray-500 dark:text-gray-400 text-center py-4">"No debt information available"</p>
+                            }.into_any(),
+                            Some(Ok(balances)) => view! {
+                                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-4">
+                                    {balances.into_iter().map(|balance| view! { <BalanceCard balance=balance /> }).collect_view()}
+                                </div>
+                            }.into_any(),
+                            Some(Err(e)) => view! {
+                                <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
+                            }.into_any(),
+                            None => view! { <SectionSkeleton rows=1 row_height="h-40" /> }.into_any()
+                        }
+                    } else {
+                        match my_balance_resource.get() {
+                            Some(Ok(balance)) => view! {
+                                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-4">
+                                    <BalanceCard balance=balance />
+                                </div>
+                            }.into_any(),
+                            Some(Err(e)) => view! {
+                                <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
+                            }.into_any(),
+                            None => view! { <SectionSkeleton rows=1 row_height="h-40" /> }.into_any()
+                        }
+                    }
+                }}
+            </Suspense>
+            <div class="mt-6 pt-4 border-t border-gray-200 dark:border-gray-700">
+                <div class="flex flex-wrap items-center justify-between gap-3 mb-3">
+                    <h3 class="text-lg font-semibold text-gray-900 dark:text-white">"Suggested Payments"</h3>
+                    {move || (can_edit && !my_debts().is_empty()).then(|| view! {
+                        <button
+                            type="button"
+                            on:click=move |_| {
+                                set_settle_all_error.set(None);
+                                show_settle_all_modal.set(true);
+                            }
+                            class="px-4 py-2 bg-emerald-600 hover:bg-emerald-700 text-white rounded-lg text-sm font-medium transition-colors"
+                        >
+                            "Settle everything I owe"
+                        </button>
+                    })}
+                </div>
+                <Suspense fallback=move || view! { <SectionSkeleton rows=2 row_height="h-10" /> }>
+                    {move || match settlement_resource.get() {
+                        Some(Ok(plan)) if plan.is_empty() => view! {
+                            <p class="text-sm text-gray-500 dark:text-gray-400">"Everyone is settled up."</p>
                         }.into_any(),
-                        Some(Ok(balances)) => view! {
-                            <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-4">
-                                {balances.into_iter().map(|balance| {
-                                    view! {
-                                        <div class="bg-gray-50 dark:bg-gray-700 p-4 rounded-lg border border-gray-100 dark:border-gray-600">
-                                            <h3 class="text-lg font-semibold text-gray-900 dark:text-white mb-3">
-                                                {balance.username.clone()}
-                                            </h3>
-                                            <div class="space-y-2 mb-4">
-                                                {balance.relationships.into_iter().map(|rel| {
-                                                    match rel.relationship_type {
-                                                        RelationshipType::Owes => view! {
-                                                            <div class="text-sm text-red-600 dark:text-red-400">
-                                                                "Owes " {rel.other_username} " " <span class="font-semibold">"€" {rel.amount}</span>
-                                                            </div>
-                                                        },
-                                                        RelationshipType::Owed => view! {
-                                                            <div class="text-sm text-green-600 dark:text-green-400">
-                                                                "Is owed by " {rel.other_username} " " <span class="font-semibold">"€" {rel.amount}</span>
-                                                            </div>
-                                                        }
-                                                    }
-                                                }).collect_view()}
-                                            </div>
-                                            <div class="pt-4 border-t border-gray-200 dark:border-gray-600 space-y-1">
-                                                <div class="text-xs text-gray-600 dark:text-gray-400">
-                                                    "Total Owed: " <span class="font-medium text-green-600 dark:text-green-400">"€" {balance.total_owed}</span>
-                                                </div>
-                                                <div class="text-xs text-gray-600 dark:text-gray-400">
-                                                    "Total Owing: " <span class="font-medium text-red-600 dark:text-red-400">"€" {balance.total_owing}</span>
-                                                </div>
-                                                {match balance.net_type {
-                                                    NetType::Positive => view! {
-                                                        <div class="text-sm font-semibold text-green-600 dark:text-green-400">
-                                                            "Net: +€" {balance.net_amount}
-                                                        </div>
-                                                    }.into_any(),
-                                                    NetType::Negative => view! {
-                                                        <div class="text-sm font-semibold text-red-600 dark:text-red-400">
-                                                            "Net: -€" {balance.net_amount}
-                                                        </div>
-                                                    }.into_any(),
-                                                    NetType::Neutral => view! {
-                                                        <div class="text-sm font-semibold text-gray-600 dark:text-gray-400">
-                                                            "Net: €0.00"
-                                                        </div>
-                                                    }.into_any()
-                                                }}
-                                            </div>
-                                        </div>
-                                    }
+                        Some(Ok(plan)) => view! {
+                            <ul class="space-y-2">
+                                {plan.into_iter().map(|suggestion| view! {
+                                    <SettlementRow suggestion=suggestion group_id=group_id.get_untracked() user_id=user_id />
                                 }).collect_view()}
-                            </div>
+                            </ul>
                         }.into_any(),
                         Some(Err(e)) => view! {
                             <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
                         }.into_any(),
-                        None => view! { <div>"Loading..."</div> }.into_any()
+                        None => view! { <SectionSkeleton rows=2 row_height="h-10" /> }.into_any()
+                    }}
+                </Suspense>
+            </div>
+
+            {move || show_settle_all_modal.get().then(|| view! {
+                <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50">
+                    <div class="bg-white dark:bg-gray-800 rounded-xl shadow-2xl max-w-md w-full p-6">
+                        <h3 class="text-xl font-bold text-gray-900 dark:text-white mb-3">
+                            "Settle everything you owe?"
+                        </h3>
+                        <p class="text-gray-700 dark:text-gray-300 mb-3">
+                            "These payments will be recorded from you:"
+                        </p>
+                        <ul class="space-y-2 mb-4">
+                            {my_debts().into_iter().map(|rel| view! { <SettleAllRow rel=rel /> }).collect_view()}
+                        </ul>
+                        <div class="mb-4">
+                            <ErrorAlert message=settle_all_error />
+                        </div>
+                        <div class="flex gap-3">
+                            <button
+                                on:click=move |_| {
+                                    settle_all_action.dispatch(SettleAllMyDebts { group_id: group_id.get_untracked() });
+                                }
+                                disabled=move || settle_all_action.pending().get()
+                                class="flex-1 px-4 py-2 bg-emerald-600 hover:bg-emerald-700 disabled:bg-gray-400 text-white font-semibold rounded-lg transition-colors"
+                            >
+                                {move || if settle_all_action.pending().get() { "Recording..." } else { "Record payments" }}
+                            </button>
+                            <button
+                                on:click=move |_| show_settle_all_modal.set(false)
+                                class="flex-1 px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white font-semibold rounded-lg transition-colors"
+                            >
+                                "Cancel"
+                            </button>
+                        </div>
+                    </div>
+                </div>
+            })}
+        </div>
+    }
+}
+
+/// One payment listed before settling up
+#[component]
+fn SettleAllRow(rel: DebtRelationship) -> impl IntoView {
+    view! {
+        <li class="flex justify-between text-sm bg-gray-50 dark:bg-gray-700 px-4 py-2 rounded-lg">
+            <span class="text-gray-900 dark:text-white">"To " {rel.other_username}</span>
+            <span class="font-semibold text-gray-900 dark:text-white"><Money amount=parse_amount(&rel.amount) /></span>
+        </li>
+    }
+}
+
+/// One suggested payment, recordable by the member who makes it
+#[component]
+fn SettlementRow(suggestion: SettlementSuggestion, group_id: i64, user_id: i64) -> impl IntoView {
+    let record_href = (suggestion.from_user_id == user_id).then(|| {
+        format!(
+            "/groups/{}/transactions/create?recipient={}&amount={:.2}",
+            group_id, suggestion.to_user_id, suggestion.amount
+        )
+    });
+
+    view! {
+        <li class="flex items-center justify-between gap-3 bg-gray-50 dark:bg-gray-700 px-4 py-2 rounded-lg">
+            <span class="text-sm text-gray-900 dark:text-white">
+                {suggestion.from_username} " pays " {suggestion.to_username} " "
+                <span class="font-semibold"><Money amount=suggestion.amount /></span>
+            </span>
+            {record_href.map(|href| view! {
+                <a
+                    href=href
+                    class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
+                >
+                    "Record this payment"
+                </a>
+            })}
+        </li>
+    }
+}
+
+/// Amounts in [`UserBalance`] are sent as strings to keep their precision
+fn parse_amount(amount: &str) -> Decimal {
+    amount.parse().unwrap_or_default()
+}
+
+/// One member's relationships and totals
+#[component]
+fn BalanceCard(balance: UserBalance) -> impl IntoView {
+    let net = balance.net_type.signed_amount(&balance.net_amount);
+    let net_class = match balance.net_type {
+        NetType::Positive => "text-sm font-semibold text-green-600 dark:text-green-400",
+        NetType::Negative => "text-sm font-semibold text-red-600 dark:text-red-400",
+        NetType::Neutral => "text-sm font-semibold text-gray-600 dark:text-gray-400",
+    };
+
+    view! {
+        <div class="bg-gray-50 dark:bg-gray-700 p-4 rounded-lg border border-gray-100 dark:border-gray-600">
+            <h3 class="text-lg font-semibold text-gray-900 dark:text-white mb-3">
+                {balance.username.clone()}
+            </h3>
+            <div class="space-y-2 mb-4">
+                {balance.relationships.into_iter().map(|rel| {
+                    match rel.relationship_type {
+                        RelationshipType::Owes => view! {
+                            <div class="text-sm text-red-600 dark:text-red-400">
+                                "Owes " {rel.other_username} " " <span class="font-semibold"><Money amount=parse_amount(&rel.amount) /></span>
+                            </div>
+                        },
+                        RelationshipType::Owed => view! {
+                            <div class="text-sm text-green-600 dark:text-green-400">
+                                "Is owed by " {rel.other_username} " " <span class="font-semibold"><Money amount=parse_amount(&rel.amount) /></span>
+                            </div>
+                        }
                     }
-                }}
-            </Suspense>
+                }).collect_view()}
+            </div>
+            <div class="pt-4 border-t border-gray-200 dark:border-gray-600 space-y-1">
+                <div class="text-xs text-gray-600 dark:text-gray-400">
+                    "Total Owed: " <span class="font-medium text-green-600 dark:text-green-400"><Money amount=parse_amount(&balance.total_owed) /></span>
+                </div>
+                <div class="text-xs text-gray-600 dark:text-gray-400">
+                    "Total Owing: " <span class="font-medium text-red-600 dark:text-red-400"><Money amount=parse_amount(&balance.total_owing) /></span>
+                </div>
+                <div class=net_class>
+                    "Net: " <Money amount=net signed=true />
+                </div>
+            </div>
         </div>
     }
 }
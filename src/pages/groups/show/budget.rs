@@ -0,0 +1,100 @@
+use leptos::prelude::*;
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+
+use super::common::SectionSkeleton;
+
+use crate::{
+    components::{CachedResource, Money},
+    error::AppError,
+};
+
+/// Share of the budget spent, in percent rounded down
+///
+/// A zero budget counts as fully used once anything is spent.
+fn budget_percent(spend: Decimal, budget: Decimal) -> u32 {
+    if budget <= Decimal::ZERO {
+        return if spend > Decimal::ZERO { 100 } else { 0 };
+    }
+    (spend / budget * Decimal::ONE_HUNDRED)
+        .floor()
+        .to_u32()
+        .unwrap_or(0)
+}
+
+/// This month's spending measured against the group's monthly budget
+#[must_use]
+#[component]
+pub fn BudgetCard(
+    spend_resource: CachedResource<Result<Decimal, AppError>>,
+    budget: Decimal,
+) -> impl IntoView {
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
+            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Monthly Budget"</h2>
+            <Suspense fallback=move || view! { <SectionSkeleton rows=1 row_height="h-10" /> }>
+                {move || {
+                    match spend_resource.get() {
+                        Some(Ok(spend)) => {
+                            let percent = budget_percent(spend, budget);
+                            let over_budget = percent > 100;
+                            let bar_class = if over_budget {
+                                "h-2 rounded-full bg-red-500"
+                            } else {
+                                "h-2 rounded-full bg-indigo-500"
+                            };
+                            view! {
+                                <div class="flex justify-between text-sm mb-2">
+                                    <span class="text-gray-700 dark:text-gray-300">
+                                        <Money amount=spend /> " of " <Money amount=budget /> " spent"
+                                    </span>
+                                    <span class=if over_budget {
+                                        "font-medium text-red-600 dark:text-red-400"
+                                    } else {
+                                        "font-medium text-gray-500 dark:text-gray-400"
+                                    }>
+                                        {percent} "%"
+                                    </span>
+                                </div>
+                                <div class="w-full h-2 bg-gray-100 dark:bg-gray-700 rounded-full overflow-hidden">
+                                    <div class=bar_class style=format!("width: {}%", percent.min(100))></div>
+                                </div>
+                                {over_budget.then(|| view! {
+                                    <p class="mt-2 text-sm text-red-600 dark:text-red-400">
+                                        "Over budget by " <Money amount={spend - budget} />
+                                    </p>
+                                })}
+                            }.into_any()
+                        }
+                        Some(Err(e)) => view! {
+                            <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
+                        }.into_any(),
+                        None => view! { <SectionSkeleton rows=1 row_height="h-10" /> }.into_any()
+                    }
+                }}
+            </Suspense>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_percent() {
+        assert_eq!(
+            budget_percent(Decimal::new(50, 0), Decimal::new(200, 0)),
+            25
+        );
+        assert_eq!(
+            budget_percent(Decimal::new(2999, 2), Decimal::new(30, 0)),
+            99
+        );
+        assert_eq!(
+            budget_percent(Decimal::new(450, 0), Decimal::new(300, 0)),
+            150
+        );
+        assert_eq!(budget_percent(Decimal::ZERO, Decimal::ZERO), 0);
+        assert_eq!(budget_percent(Decimal::ONE, Decimal::ZERO), 100);
+    }
+}
@@ -0,0 +1,119 @@
+//! Rendering helpers shared by the group page sections
+
+use leptos::prelude::*;
+
+/// Ask the user to confirm a destructive action, then run it
+pub fn confirm_then(message: &str, action: impl FnOnce()) {
+    if window().confirm_with_message(message).unwrap_or(false) {
+        action();
+    }
+}
+
+/// Centered placeholder shown when a section has nothing to list
+#[must_use]
+#[component]
+pub fn EmptyState(
+    /// SVG path of the icon
+    icon_path: &'static str,
+    /// Classes for the icon circle background
+    #[prop(default = "bg-gray-100 dark:bg-gray-700")]
+    icon_background: &'static str,
+    /// Classes for the icon stroke color
+    #[prop(default = "text-gray-400")]
+    icon_color: &'static str,
+    title: &'static str,
+    message: &'static str,
+) -> impl IntoView {
+    view! {
+        <div class="text-center py-12">
+            <div class=format!("w-16 h-16 mx-auto mb-4 {} rounded-full flex items-center justify-center", icon_background)>
+                <svg class=format!("w-8 h-8 {}", icon_color) fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d=icon_path/>
+                </svg>
+            </div>
+            <h3 class="text-lg font-medium text-gray-900 dark:text-white mb-2">{title}</h3>
+            <p class="text-gray-500 dark:text-gray-400 mb-6 text-sm">{message}</p>
+        </div>
+    }
+}
+
+/// Pulsing placeholder rows sized like a section's cards, shown during the
+/// first load so the page doesn't jump when the data arrives
+#[must_use]
+#[component]
+pub fn SectionSkeleton(
+    /// Number of placeholder rows
+    #[prop(default = 3)]
+    rows: usize,
+    /// Height class of each row, matching the card it stands in for
+    #[prop(default = "h-20")]
+    row_height: &'static str,
+) -> impl IntoView {
+    view! {
+        <div class="space-y-4 animate-pulse" aria-hidden="true">
+            {(0..rows).map(|_| view! {
+                <div class=format!("{} bg-gray-100 dark:bg-gray-700 rounded-lg", row_height)></div>
+            }).collect_view()}
+        </div>
+    }
+}
+
+/// Button below a paged list that loads its next page
+#[must_use]
+#[component]
+pub fn LoadMoreButton(
+    /// Rows not shown yet
+    remaining: i64,
+    /// Whether a page is currently loading
+    #[prop(into)]
+    pending: Signal<bool>,
+    on_click: Callback<()>,
+) -> impl IntoView {
+    view! {
+        <div class="mt-4 text-center">
+            <button
+                type="button"
+                disabled=move || pending.get()
+                on:click=move |_| on_click.run(())
+                class="px-4 py-2 text-sm font-medium text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 disabled:opacity-50"
+            >
+                {move || if pending.get() {
+                    "Loading...".to_string()
+                } else {
+                    format!("Load more ({} older)", remaining)
+                }}
+            </button>
+        </div>
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_state_renders_title_and_message() {
+        let html = Owner::new().with(|| {
+            view! {
+                <EmptyState
+                    icon_path="M12 4v16m8-8H4"
+                    title="No transactions yet"
+                    message="Record payments between group members to settle debts."
+                />
+            }
+            .to_html()
+        });
+
+        assert!(html.contains("No transactions yet"));
+        assert!(html.contains("Record payments between group members"));
+        assert!(html.contains("bg-gray-100 dark:bg-gray-700"));
+    }
+
+    #[test]
+    fn test_section_skeleton_renders_one_row_each() {
+        let html =
+            Owner::new().with(|| view! { <SectionSkeleton rows=2 row_height="h-12" /> }.to_html());
+
+        assert_eq!(html.matches("h-12 bg-gray-100").count(), 2);
+    }
+}
@@ -1,17 +1,43 @@
 use leptos::prelude::*;
 
-use crate::features::groups::models::GroupMemberInfo;
+use super::common::SectionSkeleton;
+
+use crate::{
+    components::CachedResource,
+    error::AppError,
+    features::groups::{GroupRole, handlers::SetMemberRole, models::GroupMemberInfo},
+};
 
 /// Group members section component
 #[must_use]
 #[component]
 pub fn MembersSection(
-    members_resource: LocalResource<Result<Vec<GroupMemberInfo>, ServerFnError>>,
+    group_id: Memo<i64>,
+    members_resource: CachedResource<Result<Vec<GroupMemberInfo>, AppError>>,
+    /// Whether the current user can promote and demote members
+    is_admin: bool,
+    set_role_action: ServerAction<SetMemberRole>,
 ) -> impl IntoView {
     view! {
         <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
-            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Group Members"</h2>
-            <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
+            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">
+                "Group Members"
+                {move || match members_resource.get() {
+                    Some(Ok(members)) => Some(view! {
+                        <span class="ml-2 text-sm font-normal text-gray-500 dark:text-gray-400">
+                            {format!("{} member{}", members.len(), if members.len() == 1 { "" } else { "s" })}
+                        </span>
+                    }),
+                    _ => None,
+                }}
+            </h2>
+            {move || match set_role_action.value().get() {
+                Some(Err(e)) => Some(view! {
+                    <p class="mb-3 text-sm text-red-600 dark:text-red-400">{e.to_string()}</p>
+                }),
+                _ => None,
+            }}
+            <Suspense fallback=move || view! { <SectionSkeleton rows=3 row_height="h-12" /> }>
                 {move || {
                     match members_resource.get() {
                         Some(Ok(members)) => view! {
@@ -24,11 +50,45 @@ pub fn MembersSection(
                                                     {member.username.chars().next().unwrap_or('?').to_uppercase().to_string()}
                                                 </span>
                                             </div>
-                                            <span class="text-gray-900 dark:text-white font-medium">{member.username}</span>
+                                            <div>
+                                                <span class="block text-gray-900 dark:text-white font-medium">{member.username.clone()}</span>
+                                                {member.joined_label().map(|label| view! {
+                                                    <span class="block text-xs text-gray-500 dark:text-gray-400">{label}</span>
+                                                })}
+                                            </div>
+                                        </div>
+                                        <div class="flex items-center gap-2">
+                                            {member.is_admin.then(|| view! {
+                                                <span class="px-2 py-1 bg-indigo-100 dark:bg-indigo-900/30 text-indigo-700 dark:text-indigo-300 text-xs font-medium rounded">"Admin"</span>
+                                            })}
+                                            {member.is_viewer.then(|| view! {
+                                                <span class="px-2 py-1 bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 text-xs font-medium rounded">"Viewer"</span>
+                                            })}
+                                            {(is_admin && !member.is_creator).then(|| {
+                                                let user_id = member.id;
+                                                let (label, role) = if member.is_admin {
+                                                    ("Demote", GroupRole::Member)
+                                                } else {
+                                                    ("Make admin", GroupRole::Admin)
+                                                };
+                                                view! {
+                                                    <button
+                                                        type="button"
+                                                        on:click=move |_| {
+                                                            set_role_action.dispatch(SetMemberRole {
+                                                                group_id: group_id.get_untracked(),
+                                                                user_id,
+                                                                role,
+                                                            });
+                                                        }
+                                                        disabled=move || set_role_action.pending().get()
+                                                        class="px-2 py-1 text-xs font-medium text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 disabled:opacity-50"
+                                                    >
+                                                        {label}
+                                                    </button>
+                                                }
+                                            })}
                                         </div>
-                                        {member.is_creator.then(|| view! {
-                                            <span class="px-2 py-1 bg-indigo-100 dark:bg-indigo-900/30 text-indigo-700 dark:text-indigo-300 text-xs font-medium rounded">"Admin"</span>
-                                        })}
                                     </div>
                                 }).collect_view()}
                             </div>
@@ -36,7 +96,7 @@ pub fn MembersSection(
                         Some(Err(e)) => view! {
                             <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
                         }.into_any(),
-                        None => view! { <div>"Loading..."</div> }.into_any()
+                        None => view! { <SectionSkeleton rows=3 row_height="h-12" /> }.into_any()
                     }
                 }}
             </Suspense>
@@ -8,30 +8,55 @@ use leptos::prelude::*;
 use leptos_router::hooks::{use_navigate, use_params_map};
 
 use crate::{
-    components::{AppLayout, Navigation},
+    components::{AppLayout, CachedResource, ErrorCard, Navigation, forms::ErrorAlert},
+    error::handle_app_error,
     features::{
-        auth::{UserSession, use_logout},
-        groups::handlers::{get_group, get_group_members},
+        auth::{UserSession, use_logout, use_redirect_to_login},
+        feature_flags::{FeatureFlags, get_feature_flags},
+        groups::{
+            ExportFormat,
+            handlers::{
+                LeaveGroup, RECENT_ACTIVITY_LIMIT, SetMemberRole, UnarchiveGroup, export_path,
+                get_group, get_group_activity, get_group_members, get_group_overview,
+                get_monthly_spend,
+            },
+            utils::current_utc_month,
+        },
         recurring_debts::handlers::{DeleteRecurringDebt, get_recurring_debts},
-        shared_debts::handlers::{DeleteSharedDebt, get_group_shared_debts},
+        shared_debts::handlers::{
+            ApproveSharedDebt, DeleteSharedDebt, RejectSharedDebt, SettleDebtShare,
+            get_group_shared_debts,
+        },
         transactions::handlers::{
-            calculate_user_debts, delete_transaction, get_group_transactions,
+            ConfirmTransaction, DisputeTransaction, SettleAllMyDebts, UpdateTransaction,
+            calculate_my_debts, calculate_user_debts, delete_transaction, get_group_transactions,
+            get_settlement_plan,
         },
     },
 };
 
+mod activity;
 mod balances;
+mod budget;
+mod common;
 mod members;
 mod recurring_debts;
+mod search;
 mod shared_debts;
 mod shopping_lists;
+mod summary;
 mod transactions;
 
-use balances::BalancesSection;
+use activity::ActivitySection;
+use balances::{BalanceRange, BalancesSection};
+use budget::BudgetCard;
+use common::SectionSkeleton;
 use members::MembersSection;
 use recurring_debts::RecurringDebtsSection;
+use search::GroupSearch;
 use shared_debts::SharedDebtsSection;
 use shopping_lists::ShoppingListsSection;
+use summary::WeeklySummaryCard;
 use transactions::TransactionsSection;
 
 /// Group show page - displays group details and members
@@ -40,7 +65,6 @@ use transactions::TransactionsSection;
 pub fn GroupsShow() -> impl IntoView {
     let user_resource =
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
-    let navigate = use_navigate();
     let on_logout = use_logout();
     let params = use_params_map();
 
@@ -52,69 +76,300 @@ pub fn GroupsShow() -> impl IntoView {
             .unwrap_or(0)
     });
 
-    let group_resource = LocalResource::new(move || {
-        let id = group_id.get();
-        async move { get_group(id).await }
-    });
-
-    let members_resource = LocalResource::new(move || {
-        let id = group_id.get();
-        async move { get_group_members(id).await }
-    });
-
-    let shared_debts_resource = LocalResource::new(move || {
-        let id = group_id.get();
-        async move { get_group_shared_debts(id).await }
-    });
-
-    let recurring_debts_resource = LocalResource::new(move || {
-        let id = group_id.get();
-        async move { get_recurring_debts(id).await }
-    });
+    // The main sections load together in one request; refetching a section
+    // after a change only reloads that section. Cached per group so revisiting
+    // the page shows the last known data while it reloads.
+    let overview_resource = LocalResource::new(move || get_group_overview(group_id.get()));
+    let group_resource = CachedResource::with_initial(
+        "group",
+        group_id,
+        move |_| async move { overview_resource.await.map(|overview| overview.group) },
+        get_group,
+    );
+    let members_resource = CachedResource::with_initial(
+        "members",
+        group_id,
+        move |_| async move { overview_resource.await.map(|overview| overview.members) },
+        get_group_members,
+    );
+    let shared_debts_resource = CachedResource::with_initial(
+        "shared_debts",
+        group_id,
+        move |_| async move {
+            overview_resource
+                .await
+                .map(|overview| overview.shared_debts)
+        },
+        |id| get_group_shared_debts(id, None, None, None, None, None, false),
+    );
+    let recurring_debts_resource = CachedResource::with_initial(
+        "recurring_debts",
+        group_id,
+        move |_| async move {
+            overview_resource
+                .await
+                .map(|overview| overview.recurring_debts)
+                .map_err(|e| ServerFnError::new(e.to_string()))
+        },
+        get_recurring_debts,
+    );
+    let my_balance_resource = CachedResource::with_initial(
+        "my_balance",
+        group_id,
+        move |_| async move { overview_resource.await.map(|overview| overview.my_balance) },
+        calculate_my_debts,
+    );
+    let settlement_resource = CachedResource::with_initial(
+        "settlement",
+        group_id,
+        move |_| async move {
+            overview_resource
+                .await
+                .map(|overview| overview.settlement_plan)
+        },
+        get_settlement_plan,
+    );
+    let activity_resource = CachedResource::with_initial(
+        "activity",
+        group_id,
+        move |_| async move { overview_resource.await.map(|overview| overview.activity) },
+        |id| get_group_activity(id, RECENT_ACTIVITY_LIMIT),
+    );
+    let monthly_spend_resource = CachedResource::with_initial(
+        "monthly_spend",
+        group_id,
+        move |_| async move {
+            overview_resource
+                .await
+                .map(|overview| overview.monthly_spend)
+        },
+        |id| {
+            let (year, month) = current_utc_month();
+            get_monthly_spend(id, year, month)
+        },
+    );
 
+    // The full matrix is only requested once the user opts into it and is not
+    // cached, since it is hidden again on every visit
+    let show_full_matrix = RwSignal::new(false);
+    let balance_range = RwSignal::new(BalanceRange::AllTime);
     let balances_resource = LocalResource::new(move || {
         let id = group_id.get();
-        async move { calculate_user_debts(id).await }
+        let full = show_full_matrix.get();
+        let (from_date, to_date) = balance_range.get().bounds();
+        async move {
+            if full {
+                calculate_user_debts(id, from_date, to_date).await
+            } else {
+                Ok(Vec::new())
+            }
+        }
     });
 
-    let transactions_resource = LocalResource::new(move || {
-        let id = group_id.get();
-        async move { get_group_transactions(id).await }
+    let transactions_resource = CachedResource::with_initial(
+        "transactions",
+        group_id,
+        move |_| async move {
+            overview_resource
+                .await
+                .map(|overview| overview.transactions)
+        },
+        |id| get_group_transactions(id, None, None, None, None),
+    );
+
+    // Feature flags are shared with the sections via context
+    let feature_flags_resource = CachedResource::with_initial(
+        "feature_flags",
+        group_id,
+        move |_| async move {
+            overview_resource
+                .await
+                .map(|overview| overview.feature_flags)
+                .map_err(|e| ServerFnError::new(e.to_string()))
+        },
+        get_feature_flags,
+    );
+    let feature_flags: Signal<FeatureFlags> = Signal::derive(move || {
+        feature_flags_resource
+            .get()
+            .and_then(Result::ok)
+            .unwrap_or_default()
     });
+    provide_context(feature_flags);
 
     let delete_debt_action = ServerAction::<DeleteSharedDebt>::new();
+    let settle_share_action = ServerAction::<SettleDebtShare>::new();
+    let approve_debt_action = ServerAction::<ApproveSharedDebt>::new();
+    let reject_debt_action = ServerAction::<RejectSharedDebt>::new();
     let delete_recurring_debt_action = ServerAction::<DeleteRecurringDebt>::new();
     let delete_transaction_action = Action::new(move |(gid, tid): &(i64, i64)| {
         let gid = *gid;
         let tid = *tid;
         async move { delete_transaction(gid, tid).await }
     });
+    let update_transaction_action = ServerAction::<UpdateTransaction>::new();
+    let confirm_transaction_action = ServerAction::<ConfirmTransaction>::new();
+    let dispute_transaction_action = ServerAction::<DisputeTransaction>::new();
+    let settle_all_action = ServerAction::<SettleAllMyDebts>::new();
+    let set_role_action = ServerAction::<SetMemberRole>::new();
+    let leave_group_action = ServerAction::<LeaveGroup>::new();
+    let unarchive_action = ServerAction::<UnarchiveGroup>::new();
+    let (unarchive_error, set_unarchive_error) = signal(None::<String>);
+    let show_leave_modal = RwSignal::new(false);
+    let (leave_error, set_leave_error) = signal(None::<String>);
+    let navigate = use_navigate();
 
-    // Refetch resources after deletions
+    // Refetch resources after deletions, dropping their cached values
     Effect::new(move |_| {
         if delete_debt_action.value().get().is_some() {
             shared_debts_resource.refetch();
-            balances_resource.refetch(); // Recalculate balances
+            monthly_spend_resource.refetch();
+            activity_resource.refetch();
+            my_balance_resource.refetch(); // Recalculate balances
+            settlement_resource.refetch();
+            balances_resource.refetch();
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(())) = settle_share_action.value().get() {
+            shared_debts_resource.refetch();
+            my_balance_resource.refetch(); // Recalculate balances
+            settlement_resource.refetch();
+            balances_resource.refetch();
+        }
+    });
+
+    // Debts only count towards balances once approved
+    Effect::new(move |_| {
+        let approved = matches!(approve_debt_action.value().get(), Some(Ok(())));
+        let rejected = matches!(reject_debt_action.value().get(), Some(Ok(())));
+        if approved || rejected {
+            shared_debts_resource.refetch();
+            activity_resource.refetch();
+            my_balance_resource.refetch(); // Recalculate balances
+            settlement_resource.refetch();
+            balances_resource.refetch();
         }
     });
 
     Effect::new(move |_| {
         if delete_recurring_debt_action.value().get().is_some() {
             recurring_debts_resource.refetch();
+            activity_resource.refetch();
         }
     });
 
     Effect::new(move |_| {
         if delete_transaction_action.value().get().is_some() {
             transactions_resource.refetch();
-            balances_resource.refetch(); // Recalculate balances
+            activity_resource.refetch();
+            my_balance_resource.refetch(); // Recalculate balances
+            settlement_resource.refetch();
+            balances_resource.refetch();
+        }
+    });
+
+    // Refetch after inline transaction edits
+    Effect::new(move |_| {
+        if let Some(Ok(())) = update_transaction_action.value().get() {
+            transactions_resource.refetch();
+            activity_resource.refetch();
+            my_balance_resource.refetch(); // Recalculate balances
+            settlement_resource.refetch();
+            balances_resource.refetch();
+        }
+    });
+
+    // Disputed payments drop out of the balances, confirmed ones come back
+    Effect::new(move |_| {
+        let confirmed = matches!(confirm_transaction_action.value().get(), Some(Ok(())));
+        let disputed = matches!(dispute_transaction_action.value().get(), Some(Ok(())));
+        if confirmed || disputed {
+            transactions_resource.refetch();
+            my_balance_resource.refetch(); // Recalculate balances
+            settlement_resource.refetch();
+            balances_resource.refetch();
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = settle_all_action.value().get() {
+            transactions_resource.refetch();
+            activity_resource.refetch();
+            my_balance_resource.refetch(); // Recalculate balances
+            settlement_resource.refetch();
+            balances_resource.refetch();
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(())) = set_role_action.value().get() {
+            members_resource.refetch();
+        }
+    });
+
+    let redirect_on_unarchive_error = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = unarchive_action.value().get() {
+            match result {
+                Ok(()) => {
+                    set_unarchive_error.set(None);
+                    group_resource.refetch();
+                }
+                Err(e) => {
+                    set_unarchive_error.set(handle_app_error(&e, &redirect_on_unarchive_error))
+                }
+            }
+        }
+    });
+
+    // Leaving only succeeds with a settled balance, so keep the modal open
+    // with the reason otherwise
+    let redirect_on_error = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = leave_group_action.value().get() {
+            match result {
+                Ok(()) => navigate("/groups", Default::default()),
+                Err(e) => set_leave_error.set(handle_app_error(&e, &redirect_on_error)),
+            }
         }
     });
 
+    // SSE connection so changes made by other members show up right away
+    #[cfg(feature = "hydrate")]
+    {
+        use leptos::web_sys::{EventSource, MessageEvent};
+        use wasm_bindgen::{JsCast, prelude::*};
+
+        Effect::new(move |_| {
+            let url = format!("/api/groups/{}/events", group_id.get());
+
+            if let Ok(es) = EventSource::new(&url) {
+                let on_message = Closure::wrap(Box::new(move |_: MessageEvent| {
+                    shared_debts_resource.refetch();
+                    monthly_spend_resource.refetch();
+                    transactions_resource.refetch();
+                    activity_resource.refetch();
+                    my_balance_resource.refetch(); // Recalculate balances
+                    settlement_resource.refetch();
+                    balances_resource.refetch();
+                }) as Box<dyn FnMut(MessageEvent)>);
+
+                es.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+                on_message.forget();
+
+                // Leak EventSource to keep connection alive
+                Box::leak(Box::new(es));
+            }
+        });
+    }
+
     // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -132,23 +387,46 @@ pub fn GroupsShow() -> impl IntoView {
                             <AppLayout>
                                 <div class="py-6">
                                     <div class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8">
-                                        <Suspense fallback=move || view! { <div>"Loading group..."</div> }>
+                                        <Suspense fallback=move || view! { <SectionSkeleton rows=4 row_height="h-32" /> }>
                                             {move || {
                                                 match group_resource.get() {
                                                     Some(Ok(group)) => {
-                                                        let is_admin = group.created_by == user.id;
+                                                        let is_admin = group.is_admin;
+                                                        let is_archived = group.is_archived;
+                                                        let can_edit = !group.is_viewer && !is_archived;
+                                                        let full_matrix_allowed = is_admin || group.full_balances_visible;
                                                         view! {
                                                             <div>
                                                                 // Header section
-                                                                <div class="mb-8 flex justify-between items-center">
+                                                                <div class="mb-8 flex flex-wrap gap-4 justify-between items-center">
                                                                     <div>
                                                                         <h1 class="text-2xl sm:text-3xl font-bold text-gray-900 dark:text-white">{group.name.clone()}</h1>
                                                                         <p class="text-gray-600 dark:text-gray-400 mt-1">"Group Details"</p>
                                                                     </div>
-                                                    {is_admin.then(|| {
-                                                        let gid = group_id.get_untracked();
-                                                        view! {
-                                                        <div class="flex gap-2">
+                                                    <div class="flex flex-wrap gap-2 items-center">
+                                                        <GroupSearch group_id=group_id />
+                                                        <a
+                                                            href=export_path(group_id.get_untracked(), ExportFormat::Csv)
+                                                            download
+                                                            class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                                        >
+                                                            "Export CSV"
+                                                        </a>
+                                                        <a
+                                                            href=format!("/groups/{}/stats", group_id.get_untracked())
+                                                            class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                                        >
+                                                            "Statistics"
+                                                        </a>
+                                                        <a
+                                                            href=format!("/groups/{}/digest", group_id.get_untracked())
+                                                            class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                                        >
+                                                            "Digest"
+                                                        </a>
+                                                        {is_admin.then(|| {
+                                                            let gid = group_id.get_untracked();
+                                                            view! {
                                                             <a
                                                                 href=format!("/groups/{}/invites", gid)
                                                                 class="px-4 py-2 bg-indigo-100 hover:bg-indigo-200 dark:bg-indigo-900/30 dark:hover:bg-indigo-900/50 text-indigo-700 dark:text-indigo-300 rounded-lg font-medium transition-colors"
@@ -161,39 +439,103 @@ pub fn GroupsShow() -> impl IntoView {
                                                             >
                                                                 "Edit Group"
                                                             </a>
-                                                        </div>
-                                                    }})}
+                                                        }})}
+                                                        {(!is_admin).then(|| view! {
+                                                            <button
+                                                                type="button"
+                                                                on:click=move |_| {
+                                                                    set_leave_error.set(None);
+                                                                    show_leave_modal.set(true);
+                                                                }
+                                                                class="px-4 py-2 bg-red-100 hover:bg-red-200 dark:bg-red-900/30 dark:hover:bg-red-900/50 text-red-700 dark:text-red-300 rounded-lg font-medium transition-colors"
+                                                            >
+                                                                "Leave Group"
+                                                            </button>
+                                                        })}
+                                                    </div>
                                                                 </div>
 
+                                                                {is_archived.then(|| view! {
+                                                                    <div class="mb-6 rounded-lg border border-amber-200 dark:border-amber-800 bg-amber-50 dark:bg-amber-900/30 p-4 flex flex-wrap gap-4 justify-between items-center">
+                                                                        <div>
+                                                                            <p class="font-medium text-amber-800 dark:text-amber-200">"This group is archived"</p>
+                                                                            <p class="text-sm text-amber-700 dark:text-amber-300">"Everything stays viewable, but nothing can be added or changed."</p>
+                                                                            <ErrorAlert message=unarchive_error />
+                                                                        </div>
+                                                                        {is_admin.then(|| view! {
+                                                                            <button
+                                                                                type="button"
+                                                                                on:click=move |_| {
+                                                                                    unarchive_action.dispatch(UnarchiveGroup { group_id: group_id.get_untracked() });
+                                                                                }
+                                                                                disabled=move || unarchive_action.pending().get()
+                                                                                class="px-4 py-2 bg-amber-600 hover:bg-amber-700 text-white rounded-lg font-medium transition-colors disabled:opacity-50"
+                                                                            >
+                                                                                "Unarchive"
+                                                                            </button>
+                                                                        })}
+                                                                    </div>
+                                                                })}
+
                                                                 // Component sections
-                                                                <BalancesSection balances_resource=balances_resource />
-                                                                <MembersSection members_resource=members_resource />
-                                                                <ShoppingListsSection group_id=group_id />
+                                                                <WeeklySummaryCard group_id=group_id />
+                                                                {group.monthly_budget.map(|budget| view! {
+                                                                    <BudgetCard spend_resource=monthly_spend_resource budget=budget />
+                                                                })}
+                                                                <BalancesSection
+                                                                    group_id=group_id
+                                                                    user_id=user.id
+                                                                    my_balance_resource=my_balance_resource
+                                                                    balances_resource=balances_resource
+                                                                    show_full_matrix=show_full_matrix
+                                                                    balance_range=balance_range
+                                                                    full_matrix_allowed=full_matrix_allowed
+                                                                    settlement_resource=settlement_resource
+                                                                    settle_all_action=settle_all_action
+                                                                    can_edit=can_edit
+                                                                />
+                                                                <ActivitySection activity_resource=activity_resource />
+                                                                <MembersSection
+                                                                    group_id=group_id
+                                                                    members_resource=members_resource
+                                                                    is_admin=is_admin
+                                                                    set_role_action=set_role_action
+                                                                />
+                                                                <ShoppingListsSection group_id=group_id can_edit=can_edit />
                                                                 <SharedDebtsSection
                                                                     group_id=group_id
+                                                                    user_id=user.id
                                                                     shared_debts_resource=shared_debts_resource
                                                                     delete_action=delete_debt_action
+                                                                    settle_action=settle_share_action
+                                                                    approve_action=approve_debt_action
+                                                                    reject_action=reject_debt_action
+                                                                    can_edit=can_edit
+                                                                    is_admin=is_admin
                                                                 />
                                                                 <RecurringDebtsSection
                                                                     group_id=group_id
                                                                     recurring_debts_resource=recurring_debts_resource
                                                                     delete_action=delete_recurring_debt_action
+                                                                    can_edit=can_edit
                                                                 />
                                                                 <TransactionsSection
                                                                     group_id=group_id
                                                                     user_id=user.id
                                                                     transactions_resource=transactions_resource
                                                                     delete_action=delete_transaction_action
+                                                                    update_action=update_transaction_action
+                                                                    confirm_action=confirm_transaction_action
+                                                                    dispute_action=dispute_transaction_action
+                                                                    can_edit=can_edit
                                                                 />
                                                             </div>
                                                         }.into_any()
                                                     },
                                                     Some(Err(e)) => view! {
-                                                        <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
-                                                            <p class="text-sm text-red-700 dark:text-red-300">"Error: " {e.to_string()}</p>
-                                                        </div>
+                                                        <ErrorCard error=e back_label="Back to groups" back_href="/groups" />
                                                     }.into_any(),
-                                                    None => view! { <div>"Loading..."</div> }.into_any()
+                                                    None => view! { <SectionSkeleton rows=4 row_height="h-32" /> }.into_any()
                                                 }
                                             }}
                                         </Suspense>
@@ -201,6 +543,40 @@ pub fn GroupsShow() -> impl IntoView {
                                 </div>
                             </AppLayout>
                         </div>
+
+                        // Leave Group Confirmation Modal
+                        {move || show_leave_modal.get().then(|| view! {
+                            <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50">
+                                <div class="bg-white dark:bg-gray-800 rounded-xl shadow-2xl max-w-md w-full p-6">
+                                    <h3 class="text-xl font-bold text-gray-900 dark:text-white mb-3">
+                                        "Leave this group?"
+                                    </h3>
+                                    <p class="text-gray-700 dark:text-gray-300 mb-4">
+                                        "You will lose access to this group and be taken off its recurring debts. Your balance must be settled before you can leave."
+                                    </p>
+                                    <div class="mb-4">
+                                        <ErrorAlert message=leave_error />
+                                    </div>
+                                    <div class="flex gap-3">
+                                        <button
+                                            on:click=move |_| {
+                                                leave_group_action.dispatch(LeaveGroup { group_id: group_id.get_untracked() });
+                                            }
+                                            disabled=move || leave_group_action.pending().get()
+                                            class="flex-1 px-4 py-2 bg-red-600 hover:bg-red-700 disabled:bg-gray-400 text-white font-semibold rounded-lg transition-colors"
+                                        >
+                                            {move || if leave_group_action.pending().get() { "Leaving..." } else { "Yes, Leave" }}
+                                        </button>
+                                        <button
+                                            on:click=move |_| show_leave_modal.set(false)
+                                            class="flex-1 px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white font-semibold rounded-lg transition-colors"
+                                        >
+                                            "Cancel"
+                                        </button>
+                                    </div>
+                                </div>
+                            </div>
+                        })}
                     }.into_any(),
                     _ => view! {
                         <div class="flex justify-center items-center min-h-screen bg-gray-100 dark:bg-gray-900">
@@ -1,7 +1,11 @@
 use leptos::prelude::*;
 
-use crate::features::recurring_debts::{
-    handlers::DeleteRecurringDebt, models::RecurringDebtWithDetails,
+use super::common::{EmptyState, SectionSkeleton, confirm_then};
+
+use crate::{
+    components::{CachedResource, CategoryBadge, Money},
+    features::recurring_debts::{handlers::DeleteRecurringDebt, models::RecurringDebtWithDetails},
+    pages::recurring_debts::CalendarSubscription,
 };
 
 /// Recurring debts section component
@@ -9,36 +13,39 @@ use crate::features::recurring_debts::{
 #[component]
 pub fn RecurringDebtsSection(
     group_id: Memo<i64>,
-    recurring_debts_resource: LocalResource<Result<Vec<RecurringDebtWithDetails>, ServerFnError>>,
+    recurring_debts_resource: CachedResource<Result<Vec<RecurringDebtWithDetails>, ServerFnError>>,
     delete_action: ServerAction<DeleteRecurringDebt>,
+    /// Whether the current user may add to the group; viewers may not
+    can_edit: bool,
 ) -> impl IntoView {
     view! {
         <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mt-6">
             <div class="flex justify-between items-center mb-4">
                 <h2 class="text-lg font-semibold text-gray-900 dark:text-white">"Recurring Debts"</h2>
-                <a
-                    href=move || format!("/groups/{}/recurring-debts/create", group_id.get())
-                    class="px-4 py-2 bg-purple-600 hover:bg-purple-700 text-white rounded-lg font-medium transition-colors inline-flex items-center"
-                >
-                    <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
-                    </svg>
-                    "Add Recurring Debt"
-                </a>
+                {can_edit.then(|| view! {
+                    <a
+                        href=move || format!("/groups/{}/recurring-debts/create", group_id.get())
+                        class="px-4 py-2 bg-purple-600 hover:bg-purple-700 text-white rounded-lg font-medium transition-colors inline-flex items-center"
+                    >
+                        <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
+                        </svg>
+                        "Add Recurring Debt"
+                    </a>
+                })}
             </div>
-            <Suspense fallback=move || view! { <div>"Loading recurring debts..."</div> }>
+            <CalendarSubscription group_id=group_id />
+            <Suspense fallback=move || view! { <SectionSkeleton rows=2 row_height="h-24" /> }>
                 {move || {
                     match recurring_debts_resource.get() {
                         Some(Ok(debts)) if debts.is_empty() => view! {
-                            <div class="text-center py-12">
-                                <div class="w-16 h-16 mx-auto mb-4 bg-purple-100 dark:bg-purple-900/30 rounded-full flex items-center justify-center">
-                                    <svg class="w-8 h-8 text-purple-600 dark:text-purple-400" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M4 4v5h.582m15.356 2A8.001 8.001 0 004.582 9m0 0H9m11 11v-5h-.581m0 0a8.003 8.003 0 01-15.357-2m15.357 2H15"/>
-                                    </svg>
-                                </div>
-                                <h3 class="text-lg font-medium text-gray-900 dark:text-white mb-2">"No recurring debts yet"</h3>
-                                <p class="text-gray-500 dark:text-gray-400 mb-6 text-sm">"Set up automatic debt generation for recurring expenses."</p>
-                            </div>
+                            <EmptyState
+                                icon_path="M4 4v5h.582m15.356 2A8.001 8.001 0 004.582 9m0 0H9m11 11v-5h-.581m0 0a8.003 8.003 0 01-15.357-2m15.357 2H15"
+                                icon_background="bg-purple-100 dark:bg-purple-900/30"
+                                icon_color="text-purple-600 dark:text-purple-400"
+                                title="No recurring debts yet"
+                                message="Set up automatic debt generation for recurring expenses."
+                            />
                         }.into_any(),
                         Some(Ok(debts)) => view! {
                             <div class="space-y-4">
@@ -61,12 +68,13 @@ pub fn RecurringDebtsSection(
                                                         )}>
                                                             {if debt.is_active { "Active" } else { "Paused" }}
                                                         </span>
+                                                        {debt.category.map(|category| view! { <CategoryBadge category=category /> })}
                                                     </div>
                                                     <p class="text-2xl font-bold text-purple-600 dark:text-purple-400">
-                                                        "€" {format!("{:.2}", debt.amount)}
+                                                        <Money amount=debt.amount />
                                                     </p>
                                                     <p class="text-sm text-gray-600 dark:text-gray-400 capitalize">
-                                                        {debt.frequency.to_string()} " • Next: " {debt.next_generation_date.to_string()}
+                                                        {debt.frequency.label()} " • Next: " {debt.next_generation_date.to_string()}
                                                     </p>
                                                     <p class="text-xs text-gray-500 dark:text-gray-400">
                                                         "Created by " {debt.creator_username.clone()}
@@ -86,9 +94,9 @@ pub fn RecurringDebtsSection(
                                                     {debt.is_creator.then(|| view! {
                                                         <button
                                                             on:click=move |_| {
-                                                                if window().confirm_with_message("Are you sure you want to delete this recurring debt? Generated debts will remain, but no new ones will be created.").unwrap_or(false) {
+                                                                confirm_then("Are you sure you want to delete this recurring debt? Generated debts will remain, but no new ones will be created.", || {
                                                                     delete_action.dispatch(DeleteRecurringDebt { recurring_debt_id: recurring_id });
-                                                                }
+                                                                });
                                                             }
                                                             class="px-3 py-1.5 bg-red-600 hover:bg-red-700 text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
                                                         >
@@ -108,7 +116,7 @@ pub fn RecurringDebtsSection(
                         Some(Err(e)) => view! {
                             <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
                         }.into_any(),
-                        None => view! { <div>"Loading..."</div> }.into_any()
+                        None => view! { <SectionSkeleton rows=2 row_height="h-24" /> }.into_any()
                     }
                 }}
             </Suspense>
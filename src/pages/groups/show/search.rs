@@ -0,0 +1,82 @@
+use leptos::prelude::*;
+
+use crate::features::groups::handlers::search_group;
+
+/// Shortest search term that triggers a search
+const MIN_QUERY_LENGTH: usize = 2;
+
+/// Search box for the group's debts, payments and shopping items, with the
+/// results in a dropdown
+#[must_use]
+#[component]
+pub fn GroupSearch(group_id: Memo<i64>) -> impl IntoView {
+    let query = RwSignal::new(String::new());
+    let is_searching = Memo::new(move |_| query.read().trim().chars().count() >= MIN_QUERY_LENGTH);
+
+    let results_resource = LocalResource::new(move || {
+        let group_id = group_id.get();
+        let query = query.get();
+        async move {
+            if query.trim().chars().count() < MIN_QUERY_LENGTH {
+                return Ok(Vec::new());
+            }
+            search_group(group_id, query).await
+        }
+    });
+
+    view! {
+        <div class="relative w-full sm:w-72">
+            <input
+                type="search"
+                placeholder="Search this group..."
+                prop:value=move || query.get()
+                on:input=move |ev| query.set(event_target_value(&ev))
+                on:keydown=move |ev| {
+                    if ev.key() == "Escape" {
+                        query.set(String::new());
+                    }
+                }
+                class="w-full px-3 py-2 rounded-lg border border-gray-300 dark:border-gray-600 bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500"
+            />
+            <Show when=move || is_searching.get()>
+                <div class="absolute right-0 mt-2 w-full sm:w-96 max-h-96 overflow-y-auto bg-white dark:bg-gray-800 rounded-lg shadow-lg border border-gray-200 dark:border-gray-700 z-20">
+                    <Suspense fallback=move || view! {
+                        <p class="p-3 text-sm text-gray-500 dark:text-gray-400">"Searching..."</p>
+                    }>
+                        {move || {
+                            let gid = group_id.get();
+                            match results_resource.get() {
+                                Some(Ok(results)) if results.is_empty() => view! {
+                                    <p class="p-3 text-sm text-gray-500 dark:text-gray-400">"No matches."</p>
+                                }.into_any(),
+                                Some(Ok(results)) => view! {
+                                    <ul class="divide-y divide-gray-200 dark:divide-gray-700">
+                                        {results.into_iter().map(|result| view! {
+                                            <li>
+                                                <a
+                                                    href=result.href(gid)
+                                                    class="block px-3 py-2 hover:bg-gray-50 dark:hover:bg-gray-700"
+                                                >
+                                                    <p class="text-sm text-gray-900 dark:text-white truncate">{result.text.clone()}</p>
+                                                    <p class="text-xs text-gray-500 dark:text-gray-400">
+                                                        {format!("{} · {}", result.entity.label(), result.created_at.date())}
+                                                    </p>
+                                                </a>
+                                            </li>
+                                        }).collect_view()}
+                                    </ul>
+                                }.into_any(),
+                                Some(Err(e)) => view! {
+                                    <p class="p-3 text-sm text-red-600 dark:text-red-400">"Error: " {e.to_string()}</p>
+                                }.into_any(),
+                                None => view! {
+                                    <p class="p-3 text-sm text-gray-500 dark:text-gray-400">"Searching..."</p>
+                                }.into_any(),
+                            }
+                        }}
+                    </Suspense>
+                </div>
+            </Show>
+        </div>
+    }
+}
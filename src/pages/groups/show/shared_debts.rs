@@ -1,8 +1,24 @@
 use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
 
-use crate::features::shared_debts::{
-    handlers::{DeleteSharedDebt, get_shared_debt_shares},
-    models::SharedDebtWithDetails,
+use super::common::{EmptyState, LoadMoreButton, SectionSkeleton, confirm_then};
+
+use crate::{
+    components::{
+        CachedResource, CategoryBadge, CategorySelect, ForeignMoney, Money, forms::ErrorAlert,
+    },
+    error::{AppError, handle_app_error},
+    features::{
+        auth::use_redirect_to_login,
+        shared_debts::{
+            handlers::{
+                ApproveSharedDebt, DeleteSharedDebt, DuplicateSharedDebt, RejectSharedDebt,
+                SettleDebtShare, get_group_shared_debts, get_shared_debt_shares,
+            },
+            models::{DebtStatus, SharedDebtPage, SharedDebtWithDetails},
+        },
+    },
+    pages::shared_debts::{SharedDebtHistory, duplicate_edit_path},
 };
 
 /// Shared debts section component
@@ -10,83 +26,325 @@ use crate::features::shared_debts::{
 #[component]
 pub fn SharedDebtsSection(
     group_id: Memo<i64>,
-    shared_debts_resource: LocalResource<Result<Vec<SharedDebtWithDetails>, ServerFnError>>,
+    /// Current user, who can mark their own shares settled
+    user_id: i64,
+    /// First page of shared debts; older ones are loaded on request
+    shared_debts_resource: CachedResource<Result<SharedDebtPage, AppError>>,
     delete_action: ServerAction<DeleteSharedDebt>,
+    settle_action: ServerAction<SettleDebtShare>,
+    approve_action: ServerAction<ApproveSharedDebt>,
+    reject_action: ServerAction<RejectSharedDebt>,
+    /// Whether the current user may add to the group; viewers may not
+    can_edit: bool,
+    /// Admins approve or reject debts waiting for approval
+    is_admin: bool,
 ) -> impl IntoView {
+    // A duplicate opens in the editor right away, since usually something
+    // like the amount or name differs from the original
+    let duplicate_action = ServerAction::<DuplicateSharedDebt>::new();
+    let (duplicate_error, set_duplicate_error) = signal(None::<String>);
+    let navigate = use_navigate();
+    let redirect_on_error = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = duplicate_action.value().get() {
+            match result {
+                Ok(duplicate) => {
+                    let edit_path = format!(
+                        "/groups/{}/debts/{}/edit",
+                        group_id.get_untracked(),
+                        duplicate.id
+                    );
+                    navigate(
+                        &duplicate_edit_path(edit_path, &duplicate),
+                        Default::default(),
+                    );
+                }
+                Err(e) => set_duplicate_error.set(handle_app_error(&e, &redirect_on_error)),
+            }
+        }
+    });
+
+    // A successful review refetches the list in the parent
+    let (review_error, set_review_error) = signal(None::<String>);
+    let on_review_result = move |result: Option<Result<(), AppError>>| match result {
+        Some(Ok(())) => set_review_error.set(None),
+        Some(Err(e)) => set_review_error.set(Some(e.to_string())),
+        None => {}
+    };
+    Effect::new(move |_| on_review_result(approve_action.value().get()));
+    Effect::new(move |_| on_review_result(reject_action.value().get()));
+
+    let more_debts = RwSignal::new(Vec::<SharedDebtWithDetails>::new());
+    let load_more_action = Action::new(
+        move |(gid, offset, category, by_due_date): &(i64, i64, Option<String>, bool)| {
+            let (gid, offset, category, by_due_date) =
+                (*gid, *offset, category.clone(), *by_due_date);
+            async move {
+                get_group_shared_debts(gid, None, Some(offset), None, None, category, by_due_date)
+                    .await
+            }
+        },
+    );
+
+    // Empty for all categories; a filtered or re-sorted list is fetched
+    // separately so the cached first page always stays unfiltered
+    let (category_filter, set_category_filter) = signal(String::new());
+    let sort_by_due_date = RwSignal::new(false);
+    let is_default_view = move || category_filter.get().is_empty() && !sort_by_due_date.get();
+    let filtered_resource = LocalResource::new(move || {
+        // Reload along with the unfiltered page, e.g. after a debt changed
+        shared_debts_resource.get();
+        let gid = group_id.get();
+        let category = Some(category_filter.get()).filter(|category| !category.is_empty());
+        let by_due_date = sort_by_due_date.get();
+        async move {
+            if category.is_none() && !by_due_date {
+                None
+            } else {
+                Some(
+                    get_group_shared_debts(gid, None, None, None, None, category, by_due_date)
+                        .await,
+                )
+            }
+        }
+    });
+    let debts_page = move || {
+        if is_default_view() {
+            shared_debts_resource.get()
+        } else {
+            filtered_resource.get().flatten()
+        }
+    };
+
+    Effect::new(move |_| {
+        if let Some(Ok(page)) = load_more_action.value().get() {
+            more_debts.update(|more| more.extend(page.debts));
+        }
+    });
+
+    // Older pages are stale once the first page reloads or the filter or
+    // order changes
+    Effect::new(move |_| {
+        shared_debts_resource.get();
+        category_filter.track();
+        sort_by_due_date.track();
+        more_debts.set(Vec::new());
+    });
+
     view! {
         <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6">
-            <div class="flex justify-between items-center mb-4">
+            <div class="flex flex-wrap gap-3 justify-between items-center mb-4">
                 <h2 class="text-lg font-semibold text-gray-900 dark:text-white">"Shared Debts"</h2>
-                <a
-                    href=move || format!("/groups/{}/debts/create", group_id.get())
-                    class="px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded-lg font-medium transition-colors inline-flex items-center"
-                >
-                    <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
-                    </svg>
-                    "Add Debt"
-                </a>
+                <div class="flex flex-wrap gap-2 items-center">
+                    <div class="w-44">
+                        <CategorySelect
+                            id="shared_debts_category"
+                            empty_label="All categories"
+                            value=category_filter
+                            on_change=Callback::new(move |val| set_category_filter.set(val))
+                        />
+                    </div>
+                    <label class="inline-flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                        <input
+                            type="checkbox"
+                            class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                            prop:checked=move || sort_by_due_date.get()
+                            on:change=move |ev| sort_by_due_date.set(event_target_checked(&ev))
+                        />
+                        "Sort by due date"
+                    </label>
+                    {can_edit.then(|| view! {
+                        <a
+                            href=move || format!("/groups/{}/debts/create", group_id.get())
+                            class="px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded-lg font-medium transition-colors inline-flex items-center"
+                        >
+                            <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
+                            </svg>
+                            "Add Debt"
+                        </a>
+                    })}
+                </div>
             </div>
-            <Suspense fallback=move || view! { <div>"Loading debts..."</div> }>
+            <div class="mb-4">
+                <ErrorAlert message=duplicate_error />
+                <ErrorAlert message=review_error />
+            </div>
+            <Suspense fallback=move || view! { <SectionSkeleton rows=2 row_height="h-24" /> }>
                 {move || {
-                    match shared_debts_resource.get() {
-                        Some(Ok(debts)) if debts.is_empty() => view! {
-                            <div class="text-center py-12">
-                                <div class="w-16 h-16 mx-auto mb-4 bg-gray-100 dark:bg-gray-700 rounded-full flex items-center justify-center">
-                                    <svg class="w-8 h-8 text-gray-400" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z"/>
-                                    </svg>
-                                </div>
-                                <h3 class="text-lg font-medium text-gray-900 dark:text-white mb-2">"No shared debts yet"</h3>
-                                <p class="text-gray-500 dark:text-gray-400 mb-6 text-sm">"Start tracking shared expenses with your group members."</p>
-                            </div>
+                    match debts_page() {
+                        Some(Ok(page)) if page.debts.is_empty() && !category_filter.get().is_empty() => view! {
+                            <p class="text-sm text-gray-500 dark:text-gray-400 py-6 text-center">
+                                "No debts in this category."
+                            </p>
                         }.into_any(),
-                        Some(Ok(debts)) => view! {
+                        Some(Ok(page)) if page.debts.is_empty() => view! {
+                            <EmptyState
+                                icon_path="M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z"
+                                title="No shared debts yet"
+                                message="Start tracking shared expenses with your group members."
+                            />
+                        }.into_any(),
+                        Some(Ok(page)) => {
+                            let total_count = page.total_count;
+                            let debts: Vec<_> = page.debts.into_iter().chain(more_debts.get()).collect();
+                            let shown = debts.len() as i64;
+                            view! {
                             <div class="space-y-4">
                                 {debts.into_iter().map(|debt| {
                                     let debt_id = debt.id;
+                                    let payer_id = debt.payer_id;
                                     let shares_resource = LocalResource::new(move || async move { get_shared_debt_shares(debt_id).await });
+                                    let status = debt.status;
+                                    let (rejecting, set_rejecting) = signal(false);
+                                    let (rejection_reason, set_rejection_reason) = signal(String::new());
 
                                     view! {
                                         <div class="bg-gray-50 dark:bg-gray-700 rounded-lg p-4 border border-gray-100 dark:border-gray-600">
                                             <div class="flex flex-col sm:flex-row sm:items-start sm:justify-between gap-3">
                                                 <div class="flex-1 min-w-0">
-                                                    <h3 class="text-lg font-semibold text-gray-900 dark:text-white truncate">{debt.name.clone()}</h3>
+                                                    <div class="flex items-center gap-2 min-w-0">
+                                                        <h3 class="text-lg font-semibold text-gray-900 dark:text-white truncate">{debt.name.clone()}</h3>
+                                                        {debt.category.map(|category| view! { <CategoryBadge category=category /> })}
+                                                        {debt.is_overdue.then(|| view! {
+                                                            <span class="inline-flex items-center px-2 py-0.5 rounded-full text-xs font-semibold bg-red-100 text-red-800 dark:bg-red-900/30 dark:text-red-300">
+                                                                "Overdue"
+                                                            </span>
+                                                        })}
+                                                        {(status != DebtStatus::Approved).then(|| view! {
+                                                            <span class=format!("inline-flex items-center px-2 py-0.5 rounded-full text-xs font-semibold {}", status.badge_class())>
+                                                                {status.label()}
+                                                            </span>
+                                                        })}
+                                                    </div>
                                                     <p class="text-2xl font-bold text-red-600 dark:text-red-400">
-                                                        "€" {format!("{:.2}", debt.amount)}
+                                                        <Money amount=debt.amount />
                                                     </p>
+                                                    {debt.original.clone().map(|original| view! {
+                                                        <p class="text-sm text-gray-500 dark:text-gray-400">
+                                                            <ForeignMoney original=original />
+                                                        </p>
+                                                    })}
                                                     <p class="text-sm text-gray-600 dark:text-gray-400">
-                                                        "Created by " {debt.creator_username.clone()} " • "
-                                                        {debt.created_at.date().to_string()}
+                                                        "Paid by " {debt.payer_username.clone()}
+                                                        {(debt.payer_id != debt.created_by).then(|| format!(" · entered by {}", debt.creator_username))}
+                                                        " • "
+                                                        {debt.incurred_on.to_string()}
+                                                        {debt.due_date.map(|due_date| {
+                                                            let due_class = if debt.is_overdue {
+                                                                "text-red-600 dark:text-red-400 font-medium"
+                                                            } else {
+                                                                ""
+                                                            };
+                                                            view! {
+                                                                " • "
+                                                                <span class=due_class>"Due " {due_date.to_string()}</span>
+                                                            }
+                                                        })}
                                                     </p>
+                                                    {debt.rejection_reason.clone()
+                                                        .filter(|_| status == DebtStatus::Draft)
+                                                        .map(|reason| view! {
+                                                            <p class="mt-1 text-sm text-red-700 dark:text-red-300">
+                                                                "Reason: " {reason}
+                                                                {debt.can_manage.then_some(" · Edit the debt to ask again")}
+                                                            </p>
+                                                        })}
+                                                    {(is_admin && status == DebtStatus::Pending).then(|| view! {
+                                                        <div class="mt-3 space-y-2">
+                                                            <div class="flex flex-wrap gap-2">
+                                                                <button
+                                                                    type="button"
+                                                                    on:click=move |_| {
+                                                                        set_review_error.set(None);
+                                                                        approve_action.dispatch(ApproveSharedDebt { debt_id });
+                                                                    }
+                                                                    disabled=move || approve_action.pending().get()
+                                                                    class="px-3 py-1.5 bg-emerald-600 hover:bg-emerald-700 disabled:opacity-50 text-white rounded-lg text-sm font-medium transition-colors"
+                                                                >
+                                                                    "Approve"
+                                                                </button>
+                                                                <button
+                                                                    type="button"
+                                                                    on:click=move |_| set_rejecting.update(|open| *open = !*open)
+                                                                    class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors"
+                                                                >
+                                                                    "Reject"
+                                                                </button>
+                                                            </div>
+                                                            <Show when=move || rejecting.get()>
+                                                                <div class="flex flex-col sm:flex-row gap-2">
+                                                                    <input
+                                                                        type="text"
+                                                                        prop:value=move || rejection_reason.get()
+                                                                        on:input=move |ev| set_rejection_reason.set(event_target_value(&ev))
+                                                                        placeholder="Why can't the debt count?"
+                                                                        class="flex-1 px-3 py-1.5 text-sm bg-white dark:bg-gray-800 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white"
+                                                                    />
+                                                                    <button
+                                                                        type="button"
+                                                                        on:click=move |_| {
+                                                                            set_review_error.set(None);
+                                                                            reject_action.dispatch(RejectSharedDebt {
+                                                                                debt_id,
+                                                                                reason: rejection_reason.get_untracked().trim().to_string(),
+                                                                            });
+                                                                        }
+                                                                        disabled=move || reject_action.pending().get()
+                                                                        class="px-3 py-1.5 bg-red-600 hover:bg-red-700 disabled:opacity-50 text-white rounded-lg text-sm font-medium transition-colors"
+                                                                    >
+                                                                        {move || if reject_action.pending().get() { "Sending..." } else { "Send rejection" }}
+                                                                    </button>
+                                                                </div>
+                                                            </Show>
+                                                        </div>
+                                                    })}
                                                 </div>
-                                                {debt.is_creator.then(|| {
+                                                {(can_edit || debt.can_manage).then(|| {
                                                     let gid = group_id.get_untracked();
                                                     let debt_id = debt.id;
+                                                    let can_manage = debt.can_manage;
                                                     view! {
                                                     <div class="flex flex-wrap gap-2">
-                                                        <a
-                                                            href=format!("/groups/{}/debts/{}/edit", gid, debt_id)
-                                                            class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
-                                                        >
-                                                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z"/>
-                                                            </svg>
-                                                            "Edit"
-                                                        </a>
-                                                        <button
-                                                            on:click=move |_| {
-                                                                if window().confirm_with_message("Are you sure you want to delete this debt?").unwrap_or(false) {
-                                                                    delete_action.dispatch(DeleteSharedDebt { debt_id: debt.id });
+                                                        {can_edit.then(|| view! {
+                                                            <button
+                                                                on:click=move |_| {
+                                                                    set_duplicate_error.set(None);
+                                                                    duplicate_action.dispatch(DuplicateSharedDebt { debt_id });
                                                                 }
-                                                            }
-                                                            class="px-3 py-1.5 bg-red-600 hover:bg-red-700 text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
-                                                        >
-                                                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16"/>
-                                                            </svg>
-                                                            "Delete"
-                                                        </button>
+                                                                disabled=move || duplicate_action.pending().get()
+                                                                class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center disabled:opacity-50"
+                                                            >
+                                                                <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 16H6a2 2 0 01-2-2V6a2 2 0 012-2h8a2 2 0 012 2v2m-6 12h8a2 2 0 002-2v-8a2 2 0 00-2-2h-8a2 2 0 00-2 2v8a2 2 0 002 2z"/>
+                                                                </svg>
+                                                                "Duplicate"
+                                                            </button>
+                                                        })}
+                                                        {can_manage.then(|| view! {
+                                                            <a
+                                                                href=format!("/groups/{}/debts/{}/edit", gid, debt_id)
+                                                                class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
+                                                            >
+                                                                <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z"/>
+                                                                </svg>
+                                                                "Edit"
+                                                            </a>
+                                                            <button
+                                                                on:click=move |_| {
+                                                                    confirm_then("Are you sure you want to delete this debt?", || {
+                                                                        delete_action.dispatch(DeleteSharedDebt { debt_id });
+                                                                    });
+                                                                }
+                                                                class="px-3 py-1.5 bg-red-600 hover:bg-red-700 text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
+                                                            >
+                                                                <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16"/>
+                                                                </svg>
+                                                                "Delete"
+                                                            </button>
+                                                        })}
                                                     </div>
                                                 }})}
                                             </div>
@@ -94,20 +352,59 @@ pub fn SharedDebtsSection(
                                                 <Suspense fallback=move || view! { <div class="text-xs text-gray-500">"Loading shares..."</div> }>
                                                     {move || {
                                                         match shares_resource.get() {
-                                                            Some(Ok(shares)) => view! {
+                                                            Some(Ok(shares)) => {
+                                                                // The payer's own share is never owed to anyone
+                                                                let my_settled = shares
+                                                                    .iter()
+                                                                    .find(|share| share.user_id == user_id && user_id != payer_id)
+                                                                    .map(|share| share.is_settled);
+                                                                view! {
                                                                 <div>
                                                                     <p class="text-xs text-gray-500 dark:text-gray-400 mb-2">
                                                                         "Split between " {shares.len().to_string()} " member(s):"
                                                                     </p>
                                                                     <div class="flex flex-wrap gap-2">
-                                                                        {shares.into_iter().map(|share| view! {
-                                                                            <span class="inline-flex items-center px-2.5 py-0.5 rounded-full text-xs font-medium bg-red-100 text-red-800 dark:bg-red-900/20 dark:text-red-400">
-                                                                                {share.username} ": €" {format!("{:.2}", share.share_amount)}
-                                                                            </span>
+                                                                        {shares.into_iter().map(|share| {
+                                                                            let remainder = share.absorbs_remainder.then(|| view! {
+                                                                                <span title="Includes the cents left over by the split">"· +rounding"</span>
+                                                                            });
+                                                                            if share.is_settled {
+                                                                                view! {
+                                                                                    <span class="inline-flex items-center gap-1 px-2.5 py-0.5 rounded-full text-xs font-medium bg-green-100 text-green-800 dark:bg-green-900/20 dark:text-green-400">
+                                                                                        {share.username} ": " <Money amount=share.share_amount />
+                                                                                        {remainder}
+                                                                                        <span class="font-semibold">"· Settled"</span>
+                                                                                    </span>
+                                                                                }.into_any()
+                                                                            } else {
+                                                                                view! {
+                                                                                    <span class="inline-flex items-center gap-1 px-2.5 py-0.5 rounded-full text-xs font-medium bg-red-100 text-red-800 dark:bg-red-900/20 dark:text-red-400">
+                                                                                        {share.username} ": " <Money amount=share.share_amount />
+                                                                                        {remainder}
+                                                                                    </span>
+                                                                                }.into_any()
+                                                                            }
                                                                         }).collect_view()}
                                                                     </div>
+                                                                    {my_settled.map(|is_settled| view! {
+                                                                        <button
+                                                                            type="button"
+                                                                            disabled=move || settle_action.pending().get()
+                                                                            on:click=move |_| {
+                                                                                settle_action.dispatch(SettleDebtShare {
+                                                                                    debt_id,
+                                                                                    user_id,
+                                                                                    settled: !is_settled,
+                                                                                });
+                                                                            }
+                                                                            class="mt-2 text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium disabled:opacity-50"
+                                                                        >
+                                                                            {if is_settled { "Mark my share unsettled" } else { "Mark my share settled" }}
+                                                                        </button>
+                                                                    })}
                                                                 </div>
-                                                            }.into_any(),
+                                                                }.into_any()
+                                                            },
                                                             Some(Err(_)) => view! {
                                                                 <p class="text-xs text-red-500">"Error loading shares"</p>
                                                             }.into_any(),
@@ -116,15 +413,39 @@ pub fn SharedDebtsSection(
                                                     }}
                                                 </Suspense>
                                             </div>
+                                            <SharedDebtHistory debt_id=debt_id />
                                         </div>
                                     }
                                 }).collect_view()}
                             </div>
-                        }.into_any(),
+                            {(shown < total_count).then(|| view! {
+                                <LoadMoreButton
+                                    remaining=total_count - shown
+                                    pending=load_more_action.pending()
+                                    on_click=Callback::new(move |()| {
+                                        let category = Some(category_filter.get_untracked())
+                                            .filter(|category| !category.is_empty());
+                                        load_more_action.dispatch((
+                                            group_id.get_untracked(),
+                                            shown,
+                                            category,
+                                            sort_by_due_date.get_untracked(),
+                                        ));
+                                    })
+                                />
+                            })}
+                            {move || match load_more_action.value().get() {
+                                Some(Err(e)) => Some(view! {
+                                    <div class="mt-2 text-sm text-red-600 dark:text-red-400">{e.to_string()}</div>
+                                }),
+                                _ => None,
+                            }}
+                            }.into_any()
+                        },
                         Some(Err(e)) => view! {
                             <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
                         }.into_any(),
-                        None => view! { <div>"Loading..."</div> }.into_any()
+                        None => view! { <SectionSkeleton rows=2 row_height="h-24" /> }.into_any()
                     }
                 }}
             </Suspense>
@@ -1,13 +1,22 @@
 use leptos::prelude::*;
 
-use crate::features::shopping_lists::{ShoppingListSummary, get_shopping_lists};
+use super::common::SectionSkeleton;
+
+use crate::{
+    components::CachedResource,
+    features::shopping_lists::{ShoppingListSummary, get_shopping_lists},
+};
 
 #[component]
-pub fn ShoppingListsSection(group_id: Memo<i64>) -> impl IntoView {
-    let lists_resource = LocalResource::new(move || {
-        let id = group_id.get();
-        async move { get_shopping_lists(id).await }
+pub fn ShoppingListsSection(
+    group_id: Memo<i64>,
+    /// Whether the current user may add to the group; viewers may not
+    can_edit: bool,
+) -> impl IntoView {
+    let lists_resource = CachedResource::new("shopping_lists", group_id, |gid| {
+        get_shopping_lists(gid, None)
     });
+    let (show_archived, set_show_archived) = signal(false);
 
     view! {
         <div class="bg-white dark:bg-gray-800 shadow-md rounded-lg p-6 mb-6">
@@ -18,22 +27,29 @@ pub fn ShoppingListsSection(group_id: Memo<i64>) -> impl IntoView {
                         "Collaborative shopping lists for your group"
                     </p>
                 </div>
-                <a
-                    href=move || format!("/groups/{}/shopping-lists/create", group_id.get())
-                    class="inline-flex items-center px-3 py-2 text-sm font-medium rounded-lg text-white bg-indigo-600 hover:bg-indigo-700 transition-colors"
-                >
-                    <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
-                    </svg>
-                    "New List"
-                </a>
+                <div class="flex items-center gap-3">
+                    <button
+                        type="button"
+                        on:click=move |_| set_show_archived.update(|v| *v = !*v)
+                        class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
+                    >
+                        {move || if show_archived.get() { "Hide archived" } else { "Show archived" }}
+                    </button>
+                    {can_edit.then(|| view! {
+                        <a
+                            href=move || format!("/groups/{}/shopping-lists/create", group_id.get())
+                            class="inline-flex items-center px-3 py-2 text-sm font-medium rounded-lg text-white bg-indigo-600 hover:bg-indigo-700 transition-colors"
+                        >
+                            <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
+                            </svg>
+                            "New List"
+                        </a>
+                    })}
+                </div>
             </div>
 
-            <Suspense fallback=move || view! {
-                <div class="flex justify-center py-8">
-                    <div class="animate-spin rounded-full h-8 w-8 border-b-2 border-indigo-600"></div>
-                </div>
-            }>
+            <Suspense fallback=move || view! { <SectionSkeleton rows=1 row_height="h-28" /> }>
                 {move || {
                     match lists_resource.get() {
                         Some(Ok(lists)) if lists.is_empty() => view! {
@@ -62,11 +78,42 @@ pub fn ShoppingListsSection(group_id: Memo<i64>) -> impl IntoView {
                                 </div>
                             </div>
                         }.into_any(),
-                        None => view! {
-                            <div class="flex justify-center py-8">
-                                <div class="animate-spin rounded-full h-8 w-8 border-b-2 border-indigo-600"></div>
+                        None => view! { <SectionSkeleton rows=1 row_height="h-28" /> }.into_any()
+                    }
+                }}
+            </Suspense>
+
+            <Show when=move || show_archived.get()>
+                <ArchivedShoppingLists group_id=group_id />
+            </Show>
+        </div>
+    }
+}
+
+/// Archived lists, only loaded once the user asks for them
+#[component]
+fn ArchivedShoppingLists(group_id: Memo<i64>) -> impl IntoView {
+    let archived_resource =
+        LocalResource::new(move || get_shopping_lists(group_id.get(), Some(true)));
+
+    view! {
+        <div class="mt-6">
+            <h3 class="text-sm font-semibold text-gray-700 dark:text-gray-300 mb-3">"Archived"</h3>
+            <Suspense fallback=move || view! { <SectionSkeleton rows=1 row_height="h-28" /> }>
+                {move || {
+                    match archived_resource.get() {
+                        Some(Ok(lists)) if lists.is_empty() => view! {
+                            <p class="text-sm text-gray-500 dark:text-gray-400 italic">"No archived lists"</p>
+                        }.into_any(),
+                        Some(Ok(lists)) => view! {
+                            <div class="grid gap-4 sm:grid-cols-2 lg:grid-cols-3">
+                                {lists.into_iter().map(|list| view! { <ShoppingListCard list=list group_id=group_id /> }).collect_view()}
                             </div>
-                        }.into_any()
+                        }.into_any(),
+                        Some(Err(e)) => view! {
+                            <p class="text-sm text-red-600 dark:text-red-400">{e.to_string()}</p>
+                        }.into_any(),
+                        None => view! { <SectionSkeleton rows=1 row_height="h-28" /> }.into_any()
                     }
                 }}
             </Suspense>
@@ -92,6 +139,11 @@ fn ShoppingListCard(list: ShoppingListSummary, group_id: Memo<i64>) -> impl Into
                         "by " {list.creator_username}
                     </p>
                 </div>
+                {list.is_archived.then(|| view! {
+                    <span class="ml-2 inline-flex items-center px-2 py-0.5 rounded text-xs font-medium bg-gray-200 dark:bg-gray-600 text-gray-700 dark:text-gray-300">
+                        "Archived"
+                    </span>
+                })}
                 {(list.total_items > 0).then(|| view! {
                     <span class="ml-2 inline-flex items-center px-2 py-0.5 rounded text-xs font-medium bg-indigo-100 dark:bg-indigo-900/50 text-indigo-800 dark:text-indigo-300">
                         {list.completed_items} "/" {list.total_items}
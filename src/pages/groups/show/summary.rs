@@ -0,0 +1,74 @@
+use leptos::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::{components::Money, features::groups::handlers::get_group_summaries};
+
+/// Summary amounts are sent as strings to keep their precision
+fn parse_amount(amount: &str) -> Decimal {
+    amount.parse().unwrap_or_default()
+}
+
+/// Latest weekly summary card, dismissible for the current page view
+#[must_use]
+#[component]
+pub fn WeeklySummaryCard(group_id: Memo<i64>) -> impl IntoView {
+    let summary_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { get_group_summaries(id, 1).await }
+    });
+    let dismissed = RwSignal::new(false);
+
+    move || {
+        if dismissed.get() {
+            return None;
+        }
+        let summary = summary_resource.get()?.ok()?.into_iter().next()?;
+
+        Some(view! {
+            <div class="bg-indigo-50 dark:bg-indigo-900/20 rounded-xl border border-indigo-200 dark:border-indigo-800 p-6 mb-6">
+                <div class="flex justify-between items-start mb-3">
+                    <div>
+                        <h2 class="text-lg font-semibold text-indigo-900 dark:text-indigo-200">"Weekly Summary"</h2>
+                        <p class="text-sm text-indigo-700 dark:text-indigo-300">
+                            {summary.period_start.date().to_string()} " – " {summary.period_end.date().to_string()}
+                        </p>
+                    </div>
+                    <button
+                        type="button"
+                        on:click=move |_| dismissed.set(true)
+                        class="text-indigo-500 hover:text-indigo-700 dark:text-indigo-400 dark:hover:text-indigo-200"
+                        aria-label="Dismiss summary"
+                    >
+                        <svg class="w-5 h-5" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12"/>
+                        </svg>
+                    </button>
+                </div>
+                <p class="text-sm text-gray-700 dark:text-gray-300">
+                    {summary.debt_count} " new debt(s) totalling " <Money amount=parse_amount(&summary.total_amount) />
+                    {summary.largest_expense_name.zip(summary.largest_expense_amount).map(|(name, amount)| view! {
+                        ". Largest expense: " {name} " (" <Money amount=parse_amount(&amount) /> ")"
+                    })}
+                </p>
+                <ul class="mt-3 space-y-1">
+                    {summary.members.into_iter().map(|member| {
+                        let net_change = parse_amount(&member.net_change);
+                        let class = if net_change < Decimal::ZERO {
+                            "text-red-600 dark:text-red-400"
+                        } else if net_change.is_zero() {
+                            "text-gray-500 dark:text-gray-400"
+                        } else {
+                            "text-green-600 dark:text-green-400"
+                        };
+                        view! {
+                            <li class="flex justify-between text-sm">
+                                <span class="text-gray-900 dark:text-white">{member.username}</span>
+                                <span class=class><Money amount=net_change signed=true /></span>
+                            </li>
+                        }
+                    }).collect_view()}
+                </ul>
+            </div>
+        })
+    }
+}
@@ -1,6 +1,17 @@
 use leptos::prelude::*;
 
-use crate::features::transactions::models::TransactionWithDetails;
+use super::common::{EmptyState, LoadMoreButton, SectionSkeleton, confirm_then};
+
+use crate::{
+    components::{CachedResource, EditErrorAlert, Money, use_currency_symbol},
+    error::AppError,
+    features::transactions::{
+        handlers::{
+            ConfirmTransaction, DisputeTransaction, UpdateTransaction, get_group_transactions,
+        },
+        models::{ConfirmationStatus, TransactionPage, TransactionWithDetails},
+    },
+};
 
 /// Transactions section component
 #[must_use]
@@ -8,97 +19,425 @@ use crate::features::transactions::models::TransactionWithDetails;
 pub fn TransactionsSection(
     group_id: Memo<i64>,
     user_id: i64,
-    transactions_resource: LocalResource<Result<Vec<TransactionWithDetails>, ServerFnError>>,
-    delete_action: Action<(i64, i64), Result<(), ServerFnError>>,
+    /// First page of transactions; older ones are loaded on request
+    transactions_resource: CachedResource<Result<TransactionPage, AppError>>,
+    delete_action: Action<(i64, i64), Result<(), AppError>>,
+    update_action: ServerAction<UpdateTransaction>,
+    confirm_action: ServerAction<ConfirmTransaction>,
+    dispute_action: ServerAction<DisputeTransaction>,
+    /// Whether the current user may add to the group; viewers may not
+    can_edit: bool,
 ) -> impl IntoView {
+    let more_transactions = RwSignal::new(Vec::<TransactionWithDetails>::new());
+    let load_more_action = Action::new(move |&(gid, offset): &(i64, i64)| async move {
+        get_group_transactions(gid, None, Some(offset), None, None).await
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(page)) = load_more_action.value().get() {
+            more_transactions.update(|more| more.extend(page.transactions));
+        }
+    });
+
+    // Older pages are stale once the first page reloads
+    Effect::new(move |_| {
+        transactions_resource.get();
+        more_transactions.set(Vec::new());
+    });
+
     view! {
         <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mt-6">
             <div class="flex justify-between items-center mb-4">
                 <h2 class="text-lg font-semibold text-gray-900 dark:text-white">"Transactions"</h2>
-                <a
-                    href=move || format!("/groups/{}/transactions/create", group_id.get())
-                    class="px-4 py-2 bg-green-600 hover:bg-green-700 text-white rounded-lg font-medium transition-colors inline-flex items-center"
-                >
-                    <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
-                    </svg>
-                    "Add Transaction"
-                </a>
+                {can_edit.then(|| view! {
+                    <a
+                        href=move || format!("/groups/{}/transactions/create", group_id.get())
+                        class="px-4 py-2 bg-green-600 hover:bg-green-700 text-white rounded-lg font-medium transition-colors inline-flex items-center"
+                    >
+                        <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 4v16m8-8H4"/>
+                        </svg>
+                        "Add Transaction"
+                    </a>
+                })}
             </div>
-            <Suspense fallback=move || view! { <div>"Loading transactions..."</div> }>
+            <Suspense fallback=move || view! { <SectionSkeleton rows=3 row_height="h-20" /> }>
                 {move || {
                     match transactions_resource.get() {
-                        Some(Ok(transactions)) if transactions.is_empty() => view! {
-                            <div class="text-center py-12">
-                                <div class="w-16 h-16 mx-auto mb-4 bg-gray-100 dark:bg-gray-700 rounded-full flex items-center justify-center">
-                                    <svg class="w-8 h-8 text-gray-400" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M17 9V7a2 2 0 00-2-2H5a2 2 0 00-2 2v6a2 2 0 002 2h2m2 4h10a2 2 0 002-2v-6a2 2 0 00-2-2H9a2 2 0 00-2 2v6a2 2 0 002 2zm7-5a2 2 0 11-4 0 2 2 0 014 0z"/>
-                                    </svg>
-                                </div>
-                                <h3 class="text-lg font-medium text-gray-900 dark:text-white mb-2">"No transactions yet"</h3>
-                                <p class="text-gray-500 dark:text-gray-400 mb-6 text-sm">"Record payments between group members to settle debts."</p>
-                            </div>
+                        Some(Ok(page)) if page.transactions.is_empty() => view! {
+                            <EmptyState
+                                icon_path="M17 9V7a2 2 0 00-2-2H5a2 2 0 00-2 2v6a2 2 0 002 2h2m2 4h10a2 2 0 002-2v-6a2 2 0 00-2-2H9a2 2 0 00-2 2v6a2 2 0 002 2zm7-5a2 2 0 11-4 0 2 2 0 014 0z"
+                                title="No transactions yet"
+                                message="Record payments between group members to settle debts."
+                            />
                         }.into_any(),
-                        Some(Ok(transactions)) => view! {
+                        Some(Ok(page)) => {
+                            let total_count = page.total_count;
+                            let transactions: Vec<_> = page
+                                .transactions
+                                .into_iter()
+                                .chain(more_transactions.get())
+                                .collect();
+                            let shown = transactions.len() as i64;
+                            view! {
                             <div class="space-y-4">
                                 {transactions.into_iter().map(|transaction| {
-                                    let trans_id = transaction.id;
-                                    let gid = group_id.get_untracked();
-                                    let is_payer = transaction.payer_id == user_id;
                                     view! {
-                                        <div class="bg-gray-50 dark:bg-gray-700 rounded-lg p-4 border border-gray-100 dark:border-gray-600">
-                                            <div class="flex flex-col sm:flex-row sm:items-start sm:justify-between gap-3">
-                                                <div class="flex-1 min-w-0">
-                                                    <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
-                                                        {transaction.description.clone().unwrap_or_else(|| "Payment".to_string())}
-                                                    </h3>
-                                                    <p class="text-2xl font-bold text-emerald-600 dark:text-emerald-400">
-                                                        "€" {format!("{:.2}", transaction.amount.parse::<f64>().unwrap_or(0.0))}
-                                                    </p>
-                                                    <p class="text-sm text-gray-600 dark:text-gray-400">
-                                                        {transaction.payer_username.clone()} " → " {transaction.recipient_username.clone()} " • "
-                                                        {transaction.created_at.date().to_string()}
-                                                    </p>
-                                                </div>
-                                                {is_payer.then(|| view! {
-                                                    <div class="flex flex-wrap gap-2">
-                                                        <a
-                                                            href=format!("/groups/{}/transactions/{}/edit", gid, trans_id)
-                                                            class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
-                                                        >
-                                                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z"/>
-                                                            </svg>
-                                                            "Edit"
-                                                        </a>
-                                                        <button
-                                                            on:click=move |_| {
-                                                                if window().confirm_with_message("Are you sure you want to delete this transaction?").unwrap_or(false) {
-                                                                    delete_action.dispatch((gid, trans_id));
-                                                                }
-                                                            }
-                                                            class="px-3 py-1.5 bg-red-600 hover:bg-red-700 text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
-                                                        >
-                                                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16"/>
-                                                            </svg>
-                                                            "Delete"
-                                                        </button>
-                                                    </div>
-                                                })}
-                                            </div>
-                                        </div>
+                                        <TransactionCard
+                                            group_id=group_id
+                                            user_id=user_id
+                                            transaction=transaction
+                                            delete_action=delete_action
+                                            update_action=update_action
+                                            confirm_action=confirm_action
+                                            dispute_action=dispute_action
+                                            on_reload=Callback::new(move |()| transactions_resource.refetch())
+                                        />
                                     }
                                 }).collect_view()}
                             </div>
-                        }.into_any(),
+                            {(shown < total_count).then(|| view! {
+                                <LoadMoreButton
+                                    remaining=total_count - shown
+                                    pending=load_more_action.pending()
+                                    on_click=Callback::new(move |()| {
+                                        load_more_action.dispatch((group_id.get_untracked(), shown));
+                                    })
+                                />
+                            })}
+                            {move || match load_more_action.value().get() {
+                                Some(Err(e)) => Some(view! {
+                                    <div class="mt-2 text-sm text-red-600 dark:text-red-400">{e.to_string()}</div>
+                                }),
+                                _ => None,
+                            }}
+                            }.into_any()
+                        },
                         Some(Err(e)) => view! {
                             <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
                         }.into_any(),
-                        None => view! { <div>"Loading..."</div> }.into_any()
+                        None => view! { <SectionSkeleton rows=3 row_height="h-20" /> }.into_any()
                     }
                 }}
             </Suspense>
         </div>
     }
 }
+
+/// Single transaction card with an inline amount/description editor
+///
+/// The recipient can confirm or dispute the payment from here.
+#[component]
+fn TransactionCard(
+    group_id: Memo<i64>,
+    user_id: i64,
+    transaction: TransactionWithDetails,
+    delete_action: Action<(i64, i64), Result<(), AppError>>,
+    update_action: ServerAction<UpdateTransaction>,
+    confirm_action: ServerAction<ConfirmTransaction>,
+    dispute_action: ServerAction<DisputeTransaction>,
+    /// Reloads the transactions after an edit conflict
+    on_reload: Callback<()>,
+) -> impl IntoView {
+    let trans_id = transaction.id;
+    let version = transaction.version;
+    let recipient_id = transaction.recipient_id;
+    let gid = group_id.get_untracked();
+    let is_payer = transaction.payer_id == user_id;
+    let is_recipient = transaction.recipient_id == user_id;
+    let status = transaction.confirmation_status;
+    let original_amount = format!("{:.2}", transaction.amount);
+    let original_description = transaction.description.clone().unwrap_or_default();
+    let currency = use_currency_symbol();
+
+    let (editing, set_editing) = signal(false);
+    let (amount, set_amount) = signal(original_amount.clone());
+    let (description, set_description) = signal(original_description.clone());
+    let (submitted, set_submitted) = signal(false);
+    let (amount_error, set_amount_error) = signal(None::<String>);
+    let (form_error, set_form_error) = signal(None::<String>);
+    let (disputing, set_disputing) = signal(false);
+    let (dispute_reason, set_dispute_reason) = signal(String::new());
+    let (status_submitted, set_status_submitted) = signal(false);
+    let (status_error, set_status_error) = signal(None::<String>);
+
+    let start_editing = {
+        let original_amount = original_amount.clone();
+        let original_description = original_description.clone();
+        move || {
+            set_amount.set(original_amount.clone());
+            set_description.set(original_description.clone());
+            set_amount_error.set(None);
+            set_form_error.set(None);
+            set_editing.set(true);
+        }
+    };
+
+    let cancel = move || {
+        set_editing.set(false);
+        set_submitted.set(false);
+        set_amount_error.set(None);
+        set_form_error.set(None);
+    };
+
+    let save = move || {
+        let description = description.get_untracked().trim().to_string();
+        set_amount_error.set(None);
+        set_form_error.set(None);
+        set_submitted.set(true);
+        update_action.dispatch(UpdateTransaction {
+            group_id: gid,
+            transaction_id: trans_id,
+            recipient_id,
+            amount: amount.get_untracked().trim().to_string(),
+            description: (!description.is_empty()).then_some(description),
+            version,
+        });
+    };
+
+    // Only react to results of saves dispatched from this card; a successful
+    // save refetches the list in the parent
+    Effect::new(move |_| {
+        if let Some(result) = update_action.value().get()
+            && submitted.get_untracked()
+        {
+            set_submitted.set(false);
+            match result {
+                Ok(()) => set_editing.set(false),
+                Err(e) => {
+                    let message = e.to_string();
+                    if message.to_lowercase().contains("amount") {
+                        set_amount_error.set(Some(message));
+                    } else {
+                        set_form_error.set(Some(message));
+                    }
+                }
+            }
+        }
+    });
+
+    let confirm = move || {
+        set_status_error.set(None);
+        set_status_submitted.set(true);
+        confirm_action.dispatch(ConfirmTransaction {
+            group_id: gid,
+            transaction_id: trans_id,
+        });
+    };
+
+    let dispute = move || {
+        set_status_error.set(None);
+        set_status_submitted.set(true);
+        dispute_action.dispatch(DisputeTransaction {
+            group_id: gid,
+            transaction_id: trans_id,
+            reason: dispute_reason.get_untracked().trim().to_string(),
+        });
+    };
+
+    // As with edits, only results of this card's confirm or dispute matter;
+    // a success refetches the list in the parent
+    let on_status_result = move |result: Option<Result<(), AppError>>| {
+        if let Some(result) = result
+            && status_submitted.get_untracked()
+        {
+            set_status_submitted.set(false);
+            match result {
+                Ok(()) => set_disputing.set(false),
+                Err(e) => set_status_error.set(Some(e.to_string())),
+            }
+        }
+    };
+    Effect::new(move |_| on_status_result(confirm_action.value().get()));
+    Effect::new(move |_| on_status_result(dispute_action.value().get()));
+
+    let on_keydown = move |ev: leptos::ev::KeyboardEvent| match ev.key().as_str() {
+        "Escape" => {
+            ev.prevent_default();
+            cancel();
+        }
+        "Enter" => {
+            ev.prevent_default();
+            save();
+        }
+        _ => {}
+    };
+
+    view! {
+        <div class="bg-gray-50 dark:bg-gray-700 rounded-lg p-4 border border-gray-100 dark:border-gray-600">
+            <Show
+                when=move || editing.get()
+                fallback={
+                    let start_editing = start_editing.clone();
+                    let transaction = transaction.clone();
+                    move || {
+                        let start_editing = start_editing.clone();
+                        view! {
+                            <div class="flex flex-col sm:flex-row sm:items-start sm:justify-between gap-3">
+                                <div class="flex-1 min-w-0">
+                                    <h3 class="text-lg font-semibold text-gray-900 dark:text-white">
+                                        {transaction.description.clone().unwrap_or_else(|| "Payment".to_string())}
+                                    </h3>
+                                    <p class="text-2xl font-bold text-emerald-600 dark:text-emerald-400">
+                                        <Money amount=transaction.amount />
+                                    </p>
+                                    <p class="text-sm text-gray-600 dark:text-gray-400">
+                                        {transaction.payer_username.clone()} " → " {transaction.recipient_username.clone()} " • "
+                                        {transaction.created_at.date().to_string()}
+                                        <span class=format!("ml-2 inline-flex px-2 py-0.5 rounded-full text-xs font-medium {}", status.badge_class())>
+                                            {status.label()}
+                                        </span>
+                                    </p>
+                                    {(is_payer || is_recipient)
+                                        .then(|| transaction.dispute_reason.clone())
+                                        .flatten()
+                                        .filter(|_| status == ConfirmationStatus::Disputed)
+                                        .map(|reason| view! {
+                                            <p class="mt-1 text-sm text-red-700 dark:text-red-300">
+                                                "Disputed: " {reason}
+                                            </p>
+                                        })}
+                                    {is_recipient.then(|| view! {
+                                        <div class="mt-3 space-y-2">
+                                            <div class="flex flex-wrap gap-2">
+                                                {(status != ConfirmationStatus::Confirmed).then(|| view! {
+                                                    <button
+                                                        type="button"
+                                                        on:click=move |_| confirm()
+                                                        disabled=move || confirm_action.pending().get()
+                                                        class="px-3 py-1.5 bg-emerald-600 hover:bg-emerald-700 disabled:opacity-50 text-white rounded-lg text-sm font-medium transition-colors"
+                                                    >
+                                                        "Confirm received"
+                                                    </button>
+                                                })}
+                                                {(status != ConfirmationStatus::Disputed).then(|| view! {
+                                                    <button
+                                                        type="button"
+                                                        on:click=move |_| set_disputing.update(|open| *open = !*open)
+                                                        class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors"
+                                                    >
+                                                        "Dispute"
+                                                    </button>
+                                                })}
+                                            </div>
+                                            <Show when=move || disputing.get()>
+                                                <div class="flex flex-col sm:flex-row gap-2">
+                                                    <input
+                                                        type="text"
+                                                        prop:value=move || dispute_reason.get()
+                                                        on:input=move |ev| set_dispute_reason.set(event_target_value(&ev))
+                                                        placeholder="Why didn't the payment arrive?"
+                                                        class="flex-1 px-3 py-1.5 text-sm bg-white dark:bg-gray-800 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white"
+                                                    />
+                                                    <button
+                                                        type="button"
+                                                        on:click=move |_| dispute()
+                                                        disabled=move || dispute_action.pending().get()
+                                                        class="px-3 py-1.5 bg-red-600 hover:bg-red-700 disabled:opacity-50 text-white rounded-lg text-sm font-medium transition-colors"
+                                                    >
+                                                        {move || if dispute_action.pending().get() { "Sending..." } else { "Send dispute" }}
+                                                    </button>
+                                                </div>
+                                            </Show>
+                                            {move || status_error.get().map(|e| view! {
+                                                <p class="text-xs text-red-600 dark:text-red-400">{e}</p>
+                                            })}
+                                        </div>
+                                    })}
+                                </div>
+                                {is_payer.then(|| view! {
+                                    <div class="flex flex-wrap gap-2">
+                                        <button
+                                            type="button"
+                                            title="Quick edit amount and description"
+                                            on:click=move |_| start_editing()
+                                            class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
+                                        >
+                                            <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15.232 5.232l3.536 3.536m-2.036-5.036a2.5 2.5 0 113.536 3.536L6.5 21.036H3v-3.572L16.732 3.732z"/>
+                                            </svg>
+                                        </button>
+                                        <a
+                                            href=format!("/groups/{}/transactions/{}/edit", gid, trans_id)
+                                            class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
+                                        >
+                                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M11 5H6a2 2 0 00-2 2v11a2 2 0 002 2h11a2 2 0 002-2v-5m-1.414-9.414a2 2 0 112.828 2.828L11.828 15H9v-2.828l8.586-8.586z"/>
+                                            </svg>
+                                            "Edit"
+                                        </a>
+                                        <button
+                                            on:click=move |_| {
+                                                confirm_then("Are you sure you want to delete this transaction?", || {
+                                                    delete_action.dispatch((gid, trans_id));
+                                                });
+                                            }
+                                            class="px-3 py-1.5 bg-red-600 hover:bg-red-700 text-white rounded-lg text-sm font-medium transition-colors inline-flex items-center"
+                                        >
+                                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16"/>
+                                            </svg>
+                                            "Delete"
+                                        </button>
+                                    </div>
+                                })}
+                            </div>
+                        }
+                    }
+                }
+            >
+                <div class="space-y-3" on:keydown=on_keydown>
+                    <div>
+                        <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">"Description"</label>
+                        <input
+                            type="text"
+                            prop:value=move || description.get()
+                            on:input=move |ev| set_description.set(event_target_value(&ev))
+                            placeholder="Payment"
+                            class="w-full px-3 py-1.5 text-sm bg-white dark:bg-gray-800 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white"
+                        />
+                    </div>
+                    <div>
+                        <label class="block text-xs font-medium text-gray-600 dark:text-gray-400 mb-1">{move || format!("Amount ({})", currency.get().trim())}</label>
+                        <input
+                            type="number"
+                            step="0.01"
+                            min="0.01"
+                            prop:value=move || amount.get()
+                            on:input=move |ev| set_amount.set(event_target_value(&ev))
+                            class=move || format!(
+                                "w-full px-3 py-1.5 text-sm bg-white dark:bg-gray-800 border rounded-lg text-gray-900 dark:text-white {}",
+                                if amount_error.get().is_some() {
+                                    "border-red-500 dark:border-red-500"
+                                } else {
+                                    "border-gray-300 dark:border-gray-600"
+                                }
+                            )
+                        />
+                        {move || amount_error.get().map(|e| view! {
+                            <p class="mt-1 text-xs text-red-600 dark:text-red-400">{e}</p>
+                        })}
+                    </div>
+                    <EditErrorAlert message=form_error on_reload=on_reload />
+                    <div class="flex gap-2">
+                        <button
+                            type="button"
+                            on:click=move |_| save()
+                            disabled=move || update_action.pending().get()
+                            class="px-3 py-1.5 bg-emerald-600 hover:bg-emerald-700 disabled:opacity-50 text-white rounded-lg text-sm font-medium transition-colors"
+                        >
+                            {move || if update_action.pending().get() { "Saving..." } else { "Save" }}
+                        </button>
+                        <button
+                            type="button"
+                            on:click=move |_| cancel()
+                            class="px-3 py-1.5 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 text-gray-900 dark:text-white rounded-lg text-sm font-medium transition-colors"
+                        >
+                            "Cancel"
+                        </button>
+                    </div>
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,464 @@
+use leptos::prelude::*;
+use leptos_router::{components::A, hooks::use_params_map};
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+
+use crate::{
+    components::{AppLayout, CategoryBadge, FormDateInput, Money, Navigation},
+    features::{
+        auth::{UserSession, use_logout, use_redirect_to_login},
+        groups::{
+            handlers::{get_group, get_group_statistics, get_member_contribution_summary},
+            models::{CategorySpend, MemberContribution, MemberContributionSummary, MonthlySpend},
+        },
+    },
+};
+
+/// Periods the page offers, in months
+const PERIOD_OPTIONS: [i64; 3] = [3, 6, 12];
+
+/// Width of one month's slot in the chart, in SVG units
+const BAR_SLOT_WIDTH: f64 = 40.0;
+
+/// Height of the tallest bar, in SVG units
+const BAR_MAX_HEIGHT: f64 = 140.0;
+
+/// Space below the bars for the month labels
+const LABEL_HEIGHT: f64 = 20.0;
+
+/// Bar height for a total, relative to the largest one
+fn bar_height(total: Decimal, max: Decimal) -> f64 {
+    if max <= Decimal::ZERO {
+        return 0.0;
+    }
+    (total / max).to_f64().unwrap_or(0.0) * BAR_MAX_HEIGHT
+}
+
+/// Bar chart of the total spent per month
+#[component]
+fn MonthlySpendChart(months: Vec<MonthlySpend>, max: Decimal) -> impl IntoView {
+    let width = BAR_SLOT_WIDTH * months.len() as f64;
+    let height = BAR_MAX_HEIGHT + LABEL_HEIGHT;
+
+    view! {
+        <svg
+            viewBox=format!("0 0 {} {}", width, height)
+            class="w-full h-48 text-indigo-500 dark:text-indigo-400"
+            role="img"
+            aria-label="Monthly spending"
+        >
+            {months.into_iter().enumerate().map(|(index, month)| {
+                let bar = bar_height(month.total, max);
+                let x = index as f64 * BAR_SLOT_WIDTH;
+                let label = month.label();
+                view! {
+                    <g>
+                        <title>{label.clone()} ": " <Money amount=month.total /> {format!(" ({} debts)", month.debt_count)}</title>
+                        <rect
+                            x=x + 6.0
+                            y=BAR_MAX_HEIGHT - bar
+                            width=BAR_SLOT_WIDTH - 12.0
+                            height=bar
+                            rx="2"
+                            fill="currentColor"
+                        />
+                        <text
+                            x=x + BAR_SLOT_WIDTH / 2.0
+                            y=height - 6.0
+                            text-anchor="middle"
+                            font-size="9"
+                            class="fill-gray-500 dark:fill-gray-400"
+                        >
+                            {label.get(..3).unwrap_or_default().to_string()}
+                        </text>
+                    </g>
+                }
+            }).collect_view()}
+            <line
+                x1="0"
+                y1=BAR_MAX_HEIGHT
+                x2=width
+                y2=BAR_MAX_HEIGHT
+                stroke-width="1"
+                class="stroke-gray-300 dark:stroke-gray-600"
+            />
+        </svg>
+    }
+}
+
+/// Members ranked by how much they paid
+#[component]
+fn ContributionTable(members: Vec<MemberContribution>, total: Decimal) -> impl IntoView {
+    view! {
+        <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
+            <thead>
+                <tr>
+                    <th class="px-3 py-2 text-left text-xs font-medium text-gray-500 dark:text-gray-400 uppercase">"#"</th>
+                    <th class="px-3 py-2 text-left text-xs font-medium text-gray-500 dark:text-gray-400 uppercase">"Member"</th>
+                    <th class="px-3 py-2 text-right text-xs font-medium text-gray-500 dark:text-gray-400 uppercase">"Debts"</th>
+                    <th class="px-3 py-2 text-right text-xs font-medium text-gray-500 dark:text-gray-400 uppercase">"Paid"</th>
+                    <th class="px-3 py-2 text-right text-xs font-medium text-gray-500 dark:text-gray-400 uppercase">"Share"</th>
+                </tr>
+            </thead>
+            <tbody class="divide-y divide-gray-200 dark:divide-gray-700">
+                {members.into_iter().enumerate().map(|(index, member)| {
+                    let share = if total > Decimal::ZERO {
+                        format!("{:.0}%", member.total / total * Decimal::ONE_HUNDRED)
+                    } else {
+                        "-".to_string()
+                    };
+                    view! {
+                        <tr>
+                            <td class="px-3 py-2 text-sm text-gray-500 dark:text-gray-400">{index + 1}</td>
+                            <td class="px-3 py-2 text-sm font-medium text-gray-900 dark:text-white">{member.username}</td>
+                            <td class="px-3 py-2 text-sm text-right text-gray-700 dark:text-gray-300">{member.debt_count}</td>
+                            <td class="px-3 py-2 text-sm text-right text-gray-900 dark:text-white"><Money amount=member.total /></td>
+                            <td class="px-3 py-2 text-sm text-right text-gray-700 dark:text-gray-300">{share}</td>
+                        </tr>
+                    }
+                }).collect_view()}
+            </tbody>
+        </table>
+    }
+}
+
+/// Column the contribution summary is sorted by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SummaryColumn {
+    Member,
+    DebtsPaid,
+    Paid,
+    Shares,
+    Sent,
+    Received,
+}
+
+impl SummaryColumn {
+    const ALL: [Self; 6] = [
+        Self::Member,
+        Self::DebtsPaid,
+        Self::Paid,
+        Self::Shares,
+        Self::Sent,
+        Self::Received,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Member => "Member",
+            Self::DebtsPaid => "Debts",
+            Self::Paid => "Paid",
+            Self::Shares => "Owes",
+            Self::Sent => "Paid back",
+            Self::Received => "Received",
+        }
+    }
+
+    /// Order of two members by this column, smallest first
+    fn compare(
+        self,
+        a: &MemberContributionSummary,
+        b: &MemberContributionSummary,
+    ) -> std::cmp::Ordering {
+        match self {
+            Self::Member => a.username.to_lowercase().cmp(&b.username.to_lowercase()),
+            Self::DebtsPaid => a.debts_paid.cmp(&b.debts_paid),
+            Self::Paid => a.paid_total.cmp(&b.paid_total),
+            Self::Shares => a.share_total.cmp(&b.share_total),
+            Self::Sent => a.payments_sent.cmp(&b.payments_sent),
+            Self::Received => a.payments_received.cmp(&b.payments_received),
+        }
+    }
+}
+
+/// Per member: debts paid, shares owed and payments made and received,
+/// optionally between two dates, sortable by clicking a column header
+#[component]
+fn ContributionSummaryCard(group_id: Memo<i64>) -> impl IntoView {
+    let from_date = RwSignal::new(String::new());
+    let to_date = RwSignal::new(String::new());
+    // Column and whether it is sorted in descending order
+    let sort = RwSignal::new((SummaryColumn::Paid, true));
+
+    let summary_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        let from = Some(from_date.get()).filter(|d| !d.is_empty());
+        let to = Some(to_date.get()).filter(|d| !d.is_empty());
+        async move { get_member_contribution_summary(id, from, to).await }
+    });
+
+    let sort_by = move |column: SummaryColumn| {
+        sort.update(|(current, descending)| {
+            if *current == column {
+                *descending = !*descending;
+            } else {
+                *current = column;
+                // Names read best A to Z, amounts largest first
+                *descending = column != SummaryColumn::Member;
+            }
+        });
+    };
+
+    view! {
+        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mt-6">
+            <div class="flex flex-wrap gap-4 justify-between items-end mb-4">
+                <h2 class="text-lg font-semibold text-gray-900 dark:text-white">"Who Spends and Who Owes"</h2>
+                <div class="flex gap-3">
+                    <div>
+                        <label for="summary-from" class="block text-xs text-gray-500 dark:text-gray-400 mb-1">"From"</label>
+                        <FormDateInput
+                            id="summary-from"
+                            value=Signal::derive(move || from_date.get())
+                            on_input=Callback::new(move |val| from_date.set(val))
+                        />
+                    </div>
+                    <div>
+                        <label for="summary-to" class="block text-xs text-gray-500 dark:text-gray-400 mb-1">"To"</label>
+                        <FormDateInput
+                            id="summary-to"
+                            value=Signal::derive(move || to_date.get())
+                            on_input=Callback::new(move |val| to_date.set(val))
+                        />
+                    </div>
+                </div>
+            </div>
+            <Suspense fallback=move || view! { <div>"Loading contributions..."</div> }>
+                {move || summary_resource.get().map(|result| match result {
+                    Ok(mut members) => {
+                        let (column, descending) = sort.get();
+                        members.sort_by(|a, b| {
+                            let order = column.compare(a, b);
+                            if descending { order.reverse() } else { order }
+                        });
+                        view! {
+                            <div class="overflow-x-auto">
+                                <table class="min-w-full divide-y divide-gray-200 dark:divide-gray-700">
+                                    <thead>
+                                        <tr>
+                                            {SummaryColumn::ALL.into_iter().map(|header| {
+                                                let align = if header == SummaryColumn::Member { "text-left" } else { "text-right" };
+                                                let arrow = (header == column).then_some(if descending { " ↓" } else { " ↑" });
+                                                view! {
+                                                    <th
+                                                        class=format!("px-3 py-2 {} text-xs font-medium text-gray-500 dark:text-gray-400 uppercase", align)
+                                                        aria-sort=if header != column { "none" } else if descending { "descending" } else { "ascending" }
+                                                    >
+                                                        <button
+                                                            type="button"
+                                                            on:click=move |_| sort_by(header)
+                                                            class="uppercase hover:text-gray-900 dark:hover:text-white"
+                                                        >
+                                                            {header.label()} {arrow}
+                                                        </button>
+                                                    </th>
+                                                }
+                                            }).collect_view()}
+                                        </tr>
+                                    </thead>
+                                    <tbody class="divide-y divide-gray-200 dark:divide-gray-700">
+                                        {members.into_iter().map(|member| view! {
+                                            <tr>
+                                                <td class="px-3 py-2 text-sm font-medium text-gray-900 dark:text-white">{member.username}</td>
+                                                <td class="px-3 py-2 text-sm text-right text-gray-700 dark:text-gray-300">{member.debts_paid}</td>
+                                                <td class="px-3 py-2 text-sm text-right text-gray-900 dark:text-white"><Money amount=member.paid_total /></td>
+                                                <td class="px-3 py-2 text-sm text-right text-gray-700 dark:text-gray-300"><Money amount=member.share_total /></td>
+                                                <td class="px-3 py-2 text-sm text-right text-gray-700 dark:text-gray-300"><Money amount=member.payments_sent /></td>
+                                                <td class="px-3 py-2 text-sm text-right text-gray-700 dark:text-gray-300"><Money amount=member.payments_received /></td>
+                                            </tr>
+                                        }).collect_view()}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }.into_any()
+                    }
+                    Err(e) => view! {
+                        <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
+                            <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
+                        </div>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+/// Spending per category with a bar relative to the whole period
+#[component]
+fn CategoryBreakdown(categories: Vec<CategorySpend>, total: Decimal) -> impl IntoView {
+    if categories.is_empty() {
+        return view! {
+            <p class="text-sm text-gray-500 dark:text-gray-400">"No debts in this period."</p>
+        }
+        .into_any();
+    }
+
+    view! {
+        <ul class="space-y-3">
+            {categories.into_iter().map(|spend| {
+                let percent = if total > Decimal::ZERO {
+                    (spend.total / total * Decimal::ONE_HUNDRED).to_f64().unwrap_or(0.0)
+                } else {
+                    0.0
+                };
+                view! {
+                    <li>
+                        <div class="flex justify-between items-center mb-1">
+                            {match spend.category {
+                                Some(category) => view! { <CategoryBadge category=category /> }.into_any(),
+                                None => view! {
+                                    <span class="text-xs font-medium text-gray-500 dark:text-gray-400">"Uncategorized"</span>
+                                }.into_any(),
+                            }}
+                            <span class="text-sm text-gray-700 dark:text-gray-300">
+                                <Money amount=spend.total />
+                                <span class="text-gray-500 dark:text-gray-400">{format!(" · {} debts", spend.debt_count)}</span>
+                            </span>
+                        </div>
+                        <div class="h-2 bg-gray-100 dark:bg-gray-700 rounded-full overflow-hidden">
+                            <div
+                                class="h-full bg-indigo-500 dark:bg-indigo-400 rounded-full"
+                                style=format!("width: {:.1}%", percent)
+                            ></div>
+                        </div>
+                    </li>
+                }
+            }).collect_view()}
+        </ul>
+    }
+    .into_any()
+}
+
+/// Groups statistics page - monthly spending and member contributions
+#[must_use]
+#[component]
+pub fn GroupsStats() -> impl IntoView {
+    let user_resource =
+        expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
+    let on_logout = use_logout();
+    let params = use_params_map();
+
+    let group_id = Memo::new(move |_| {
+        params
+            .read()
+            .get("id")
+            .and_then(|id| id.parse::<i64>().ok())
+            .unwrap_or(0)
+    });
+
+    let (months, set_months) = signal(6_i64);
+
+    let group_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { get_group(id).await }
+    });
+
+    let stats_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        let months = months.get();
+        async move { get_group_statistics(id, months).await }
+    });
+
+    // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(Ok(None)) = user_resource.get() {
+            redirect_to_login();
+        }
+    });
+
+    view! {
+        <Suspense fallback=move || view! {
+            <div class="flex justify-center items-center min-h-screen bg-gray-100 dark:bg-gray-900">
+                <div class="animate-spin rounded-full h-12 w-12 border-b-2 border-indigo-600"></div>
+            </div>
+        }>
+            {move || {
+                match user_resource.get() {
+                    Some(Ok(Some(user))) => view! {
+                        <div class="min-h-screen bg-gray-100 dark:bg-gray-900">
+                            <Navigation username=user.username.clone() on_logout=on_logout />
+                            <AppLayout>
+                                <div class="py-6">
+                                    <div class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8">
+                                        <A href=move || format!("/groups/{}", group_id.get()) attr:class="text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 text-sm inline-flex items-center mb-3">
+                                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15 19l-7-7 7-7"/>
+                                            </svg>
+                                            "Back to Group"
+                                        </A>
+
+                                        // Header
+                                        <div class="mb-8 flex flex-wrap gap-4 justify-between items-center">
+                                            <h1 class="text-2xl sm:text-3xl font-bold text-gray-900 dark:text-white">
+                                                "Statistics"
+                                                {move || group_resource.get().and_then(Result::ok).map(|group| format!(" for {}", group.name))}
+                                            </h1>
+                                            <div class="inline-flex rounded-lg border border-gray-300 dark:border-gray-600 overflow-hidden">
+                                                {PERIOD_OPTIONS.into_iter().map(|option| view! {
+                                                    <button
+                                                        type="button"
+                                                        on:click=move |_| set_months.set(option)
+                                                        class=move || if months.get() == option {
+                                                            "px-3 py-1.5 text-sm font-medium bg-indigo-600 text-white"
+                                                        } else {
+                                                            "px-3 py-1.5 text-sm font-medium bg-white dark:bg-gray-800 text-gray-700 dark:text-gray-300 hover:bg-gray-50 dark:hover:bg-gray-700"
+                                                        }
+                                                    >
+                                                        {format!("{} months", option)}
+                                                    </button>
+                                                }).collect_view()}
+                                            </div>
+                                        </div>
+
+                                        <Suspense fallback=move || view! { <div>"Loading statistics..."</div> }>
+                                            {move || stats_resource.get().map(|result| match result {
+                                                Ok(stats) => {
+                                                    let total = stats.total();
+                                                    let max = stats.max_monthly_total();
+                                                    view! {
+                                                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
+                                                            <div class="flex justify-between items-baseline mb-4">
+                                                                <h2 class="text-lg font-semibold text-gray-900 dark:text-white">"Monthly Spending"</h2>
+                                                                <span class="text-sm text-gray-500 dark:text-gray-400">
+                                                                    "Total " <Money amount=total />
+                                                                </span>
+                                                            </div>
+                                                            <MonthlySpendChart months=stats.months max=max />
+                                                        </div>
+
+                                                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
+                                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"By Category"</h2>
+                                                            <CategoryBreakdown categories=stats.categories total=total />
+                                                        </div>
+
+                                                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6">
+                                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Who Paid"</h2>
+                                                            <div class="overflow-x-auto">
+                                                                <ContributionTable members=stats.members total=total />
+                                                            </div>
+                                                        </div>
+                                                    }.into_any()
+                                                }
+                                                Err(e) => view! {
+                                                    <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
+                                                        <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
+                                                    </div>
+                                                }.into_any(),
+                                            })}
+                                        </Suspense>
+
+                                        <ContributionSummaryCard group_id=group_id />
+                                    </div>
+                                </div>
+                            </AppLayout>
+                        </div>
+                    }.into_any(),
+                    _ => view! {
+                        <div class="flex justify-center items-center min-h-screen bg-gray-100 dark:bg-gray-900">
+                            <div class="animate-spin rounded-full h-12 w-12 border-b-2 border-indigo-600"></div>
+                        </div>
+                    }.into_any(),
+                }
+            }}
+        </Suspense>
+    }
+}
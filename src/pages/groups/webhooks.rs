@@ -0,0 +1,233 @@
+use leptos::prelude::*;
+
+use crate::{
+    components::forms::{ErrorAlert, FormCard},
+    features::webhooks::{
+        CreateGroupWebhook, DeleteGroupWebhook, GroupWebhook, UpdateGroupWebhook, WebhookEvent,
+        get_group_webhooks,
+    },
+};
+
+/// Event names as submitted to the webhook server functions
+fn event_names(events: &[WebhookEvent]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| event.as_str().to_string())
+        .collect()
+}
+
+/// Status line of a webhook's last delivery with its text color
+fn delivery_status(webhook: &GroupWebhook) -> (&'static str, String) {
+    let Some(delivered_at) = webhook.last_delivery_at else {
+        return (
+            "text-gray-500 dark:text-gray-400",
+            "Not delivered yet".to_string(),
+        );
+    };
+    let when = format!(
+        "{} {:02}:{:02} UTC",
+        delivered_at.date(),
+        delivered_at.hour(),
+        delivered_at.minute()
+    );
+    match (&webhook.last_error, webhook.last_status_code) {
+        (None, Some(code)) => (
+            "text-green-600 dark:text-green-400",
+            format!("Delivered {} (HTTP {})", when, code),
+        ),
+        (None, None) => (
+            "text-green-600 dark:text-green-400",
+            format!("Delivered {}", when),
+        ),
+        (Some(error), _) => (
+            "text-red-600 dark:text-red-400",
+            format!("Failed {}: {}", when, error),
+        ),
+    }
+}
+
+/// Card on the group settings page for managing the group's webhooks
+#[must_use]
+#[component]
+pub fn GroupWebhooks(group_id: Memo<i64>) -> impl IntoView {
+    let create_action = ServerAction::<CreateGroupWebhook>::new();
+    let update_action = ServerAction::<UpdateGroupWebhook>::new();
+    let delete_action = ServerAction::<DeleteGroupWebhook>::new();
+
+    let webhooks_resource = LocalResource::new(move || {
+        let _ = create_action.version().get();
+        let _ = update_action.version().get();
+        let _ = delete_action.version().get();
+        let id = group_id.get();
+        async move { get_group_webhooks(id).await }
+    });
+
+    let url_signal = RwSignal::new(String::new());
+    let new_events = RwSignal::new(WebhookEvent::ALL.to_vec());
+    let (webhook_error, set_webhook_error) = signal(None::<String>);
+
+    // Clear the form after adding a webhook
+    Effect::new(move |_| {
+        if let Some(result) = create_action.value().get() {
+            match result {
+                Ok(_) => {
+                    url_signal.set(String::new());
+                    new_events.set(WebhookEvent::ALL.to_vec());
+                    set_webhook_error.set(None);
+                }
+                Err(e) => set_webhook_error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(result) = update_action.value().get() {
+            set_webhook_error.set(result.err().map(|e| e.to_string()));
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(result) = delete_action.value().get() {
+            set_webhook_error.set(result.err().map(|e| e.to_string()));
+        }
+    });
+
+    let on_add = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        create_action.dispatch(CreateGroupWebhook {
+            group_id: group_id.get(),
+            url: url_signal.get(),
+            events: event_names(&new_events.get()),
+        });
+    };
+
+    view! {
+        <FormCard>
+            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Webhooks"</h2>
+            <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">
+                "Send a signed JSON request to another service whenever something happens in this group. Verify the "
+                <code class="text-xs">"X-Splitify-Signature"</code>
+                " header with the webhook's secret (HMAC-SHA256)."
+            </p>
+
+            <Suspense fallback=move || view! { <div>"Loading..."</div> }>
+                {move || webhooks_resource.get().map(|result| match result {
+                    Ok(webhooks) if webhooks.is_empty() => view! {
+                        <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">"No webhooks yet."</p>
+                    }.into_any(),
+                    Ok(webhooks) => view! {
+                        <ul class="divide-y divide-gray-200 dark:divide-gray-700 mb-4">
+                            {webhooks.into_iter().map(|webhook| {
+                                let webhook_id = webhook.id;
+                                let url = StoredValue::new(webhook.url.clone());
+                                let events = StoredValue::new(webhook.events.clone());
+                                let (status_class, status) = delivery_status(&webhook);
+                                view! {
+                                    <li class="py-3">
+                                        <div class="flex justify-between items-start gap-3">
+                                            <div class="min-w-0">
+                                                <p class="text-sm font-medium text-gray-900 dark:text-white break-all">{webhook.url.clone()}</p>
+                                                <p class=format!("text-xs {}", status_class)>{status}</p>
+                                            </div>
+                                            <button
+                                                type="button"
+                                                on:click=move |_| {
+                                                    delete_action.dispatch(DeleteGroupWebhook { webhook_id });
+                                                }
+                                                disabled=move || delete_action.pending().get()
+                                                class="text-sm text-red-600 hover:text-red-500 dark:text-red-400 dark:hover:text-red-300 disabled:opacity-50"
+                                            >
+                                                "Delete"
+                                            </button>
+                                        </div>
+                                        <div class="mt-2 flex flex-wrap gap-x-4 gap-y-1">
+                                            {WebhookEvent::ALL.into_iter().map(|event| {
+                                                let subscribed = webhook.events.contains(&event);
+                                                view! {
+                                                    <label class="inline-flex items-center text-xs text-gray-700 dark:text-gray-300 cursor-pointer">
+                                                        <input
+                                                            type="checkbox"
+                                                            class="w-3 h-3 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                            prop:checked=subscribed
+                                                            disabled=move || update_action.pending().get()
+                                                            on:change=move |ev| {
+                                                                let mut events = events.get_value();
+                                                                if event_target_checked(&ev) {
+                                                                    events.push(event);
+                                                                } else {
+                                                                    events.retain(|e| *e != event);
+                                                                }
+                                                                update_action.dispatch(UpdateGroupWebhook {
+                                                                    webhook_id,
+                                                                    url: url.get_value(),
+                                                                    events: event_names(&events),
+                                                                });
+                                                            }
+                                                        />
+                                                        <span class="ml-1">{event.label().to_string()}</span>
+                                                    </label>
+                                                }
+                                            }).collect_view()}
+                                        </div>
+                                        <details class="mt-2">
+                                            <summary class="text-xs text-gray-500 dark:text-gray-400 cursor-pointer">"Show secret"</summary>
+                                            <code class="text-xs text-gray-700 dark:text-gray-300 break-all">{webhook.secret.clone()}</code>
+                                        </details>
+                                    </li>
+                                }
+                            }).collect_view()}
+                        </ul>
+                    }.into_any(),
+                    Err(e) => view! {
+                        <div class="text-red-600 dark:text-red-400 mb-4">"Error: " {e.to_string()}</div>
+                    }.into_any(),
+                })}
+            </Suspense>
+
+            <form on:submit=on_add class="space-y-3">
+                <div class="flex flex-col sm:flex-row gap-2">
+                    <input
+                        type="url"
+                        placeholder="https://example.com/webhook"
+                        required=true
+                        prop:value=move || url_signal.get()
+                        on:input=move |ev| url_signal.set(event_target_value(&ev))
+                        class="flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md shadow-sm text-sm bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:outline-none focus:ring-indigo-500 focus:border-indigo-500"
+                    />
+                    <button
+                        type="submit"
+                        disabled=move || create_action.pending().get() || new_events.with(Vec::is_empty)
+                        class="px-4 py-2 rounded-md text-sm font-medium text-white bg-indigo-600 hover:bg-indigo-700 disabled:opacity-50"
+                    >
+                        "Add Webhook"
+                    </button>
+                </div>
+                <div class="flex flex-wrap gap-x-4 gap-y-1">
+                    {WebhookEvent::ALL.into_iter().map(|event| view! {
+                        <label class="inline-flex items-center text-sm text-gray-700 dark:text-gray-300 cursor-pointer">
+                            <input
+                                type="checkbox"
+                                class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                prop:checked=move || new_events.with(|events| events.contains(&event))
+                                on:change=move |ev| {
+                                    let checked = event_target_checked(&ev);
+                                    new_events.update(|events| {
+                                        events.retain(|e| *e != event);
+                                        if checked {
+                                            events.push(event);
+                                        }
+                                    });
+                                }
+                            />
+                            <span class="ml-2">{event.label().to_string()}</span>
+                        </label>
+                    }).collect_view()}
+                </div>
+            </form>
+
+            <div class="mt-4">
+                <ErrorAlert message=webhook_error />
+            </div>
+        </FormCard>
+    }
+}
@@ -0,0 +1,129 @@
+use leptos::prelude::*;
+use leptos_router::hooks::{use_navigate, use_query_map};
+
+use crate::{
+    components::{
+        AppLayout, ErrorAlert, FormActions, FormCard, FormField, FormInput, LoadingSpinner,
+        Navigation, PageHeader,
+    },
+    error::handle_app_error,
+    features::{
+        auth::{UserSession, use_logout, use_redirect_to_login},
+        invites::{join_requests::RequestToJoin, models::JoinRequestOutcome},
+    },
+};
+
+/// Page for asking to join a group with its join code
+///
+/// The code can be passed as `?code=`, e.g. in a shared link.
+#[must_use]
+#[component]
+pub fn JoinGroup() -> impl IntoView {
+    let user_resource =
+        expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
+    let navigate = use_navigate();
+    let on_logout = use_logout();
+    let query = use_query_map();
+
+    let request_action = ServerAction::<RequestToJoin>::new();
+    let (code, set_code) = signal(query.read_untracked().get("code").unwrap_or_default());
+    let (error_message, set_error_message) = signal(Option::<String>::None);
+    let (sent_message, set_sent_message) = signal(Option::<String>::None);
+
+    // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
+    let redirect_on_error = redirect_to_login.clone();
+    Effect::new(move |_| {
+        if let Some(Ok(None)) = user_resource.get() {
+            redirect_to_login();
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(result) = request_action.value().get() {
+            match result {
+                Ok(JoinRequestOutcome::Requested { group_name }) => {
+                    set_code.set(String::new());
+                    set_sent_message.set(Some(format!(
+                        "Asked to join {}. You'll be in the group once an admin approves.",
+                        group_name
+                    )));
+                }
+                Ok(JoinRequestOutcome::AlreadyRequested { group_name }) => {
+                    set_sent_message.set(Some(format!(
+                        "You already asked to join {}; an admin has yet to answer.",
+                        group_name
+                    )));
+                }
+                Ok(JoinRequestOutcome::AlreadyMember { group_id }) => {
+                    navigate(&format!("/groups/{}", group_id), Default::default());
+                }
+                Err(e) => {
+                    set_error_message.set(handle_app_error(&e, &redirect_on_error));
+                }
+            }
+        }
+    });
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        set_error_message.set(None);
+        set_sent_message.set(None);
+
+        request_action.dispatch(RequestToJoin { code: code.get() });
+    };
+
+    view! {
+        <Suspense fallback=LoadingSpinner>
+            {move || {
+                match user_resource.get() {
+                    Some(Ok(Some(user))) => view! {
+                        <div class="min-h-screen bg-gray-100 dark:bg-gray-900">
+                            <Navigation username=user.username.clone() on_logout=on_logout />
+                            <AppLayout>
+                                <div class="py-6">
+                                    <div class="max-w-3xl mx-auto px-4 sm:px-6 lg:px-8">
+                                        <PageHeader title="Join a Group".to_string() />
+
+                                        <FormCard>
+                                            <form on:submit=on_submit class="space-y-6">
+                                                <ErrorAlert message=error_message />
+                                                {move || sent_message.get().map(|message| view! {
+                                                    <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4">
+                                                        <p class="text-sm text-green-700 dark:text-green-300">{message}</p>
+                                                    </div>
+                                                })}
+
+                                                <FormField
+                                                    label="Join Code"
+                                                    for_id="join_code"
+                                                    helper_text="Ask an admin of the group for its code."
+                                                >
+                                                    <FormInput
+                                                        id="join_code"
+                                                        required=true
+                                                        placeholder="e.g., 3F9A0C1B"
+                                                        value=Signal::derive(move || code.get())
+                                                        on_input=Callback::new(move |val| set_code.set(val))
+                                                    />
+                                                </FormField>
+
+                                                <FormActions
+                                                    submit_text="Ask to Join"
+                                                    loading_text="Sending..."
+                                                    loading=Signal::derive(move || request_action.pending().get())
+                                                    cancel_href="/groups".to_string()
+                                                />
+                                            </form>
+                                        </FormCard>
+                                    </div>
+                                </div>
+                            </AppLayout>
+                        </div>
+                    }.into_any(),
+                    _ => LoadingSpinner().into_any()
+                }
+            }}
+        </Suspense>
+    }
+}
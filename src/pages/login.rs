@@ -1,9 +1,12 @@
-use leptos::prelude::*;
-use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos::{prelude::*, task::spawn_local};
+use leptos_router::hooks::use_navigate;
 
 use crate::{
     components::{GuestLayout, InputLabel, PrimaryButton, TextInput},
-    features::auth::{LoginUser, UserSession},
+    features::{
+        auth::{LoginUser, UserSession, use_redirect_target},
+        groups::handlers::get_default_group,
+    },
 };
 
 /// Login page component
@@ -14,17 +17,11 @@ pub fn LoginPage() -> impl IntoView {
     let username_signal = RwSignal::new(String::new());
     let password_signal = RwSignal::new(String::new());
     let navigate = use_navigate();
-    let query_map = use_query_map();
     let user_resource =
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
 
-    // Get redirect_to query parameter
-    let redirect_to = Memo::new(move |_| {
-        query_map
-            .read()
-            .get("redirect_to")
-            .and_then(|encoded| urlencoding::decode(&encoded).ok().map(|s| s.into_owned()))
-    });
+    // Validated redirect target from the query string
+    let redirect_to = use_redirect_target();
 
     // Redirect logged-in users
     let navigate_clone = navigate.clone();
@@ -54,13 +51,23 @@ pub fn LoginPage() -> impl IntoView {
         }
     });
 
-    // Separate effect to navigate after resource is updated
+    // Separate effect to navigate after resource is updated; without a
+    // redirect target, go to the user's default group if they have one
     Effect::new(move |_| {
         if has_refetched.get()
             && let Some(Ok(Some(_))) = user_resource.get()
         {
-            let target = redirect_to.get().unwrap_or_else(|| "/groups".to_string());
-            navigate(&target, Default::default());
+            let navigate = navigate.clone();
+            match redirect_to.get() {
+                Some(target) => navigate(&target, Default::default()),
+                None => spawn_local(async move {
+                    let target = match get_default_group().await {
+                        Ok(Some(group_id)) => format!("/groups/{}", group_id),
+                        _ => "/groups".to_string(),
+                    };
+                    navigate(&target, Default::default());
+                }),
+            }
         }
     });
 
@@ -106,7 +113,13 @@ pub fn LoginPage() -> impl IntoView {
                         />
                     </div>
 
-                    <div class="flex items-center justify-end">
+                    <div class="flex items-center justify-between">
+                        <a
+                            href="/reset-password"
+                            class="text-sm text-gray-600 hover:text-gray-900 dark:text-gray-400 dark:hover:text-gray-100 underline"
+                        >
+                            "Forgot your password?"
+                        </a>
                         <PrimaryButton
                             button_type="submit"
                             disabled=login_action.pending()
@@ -141,7 +154,7 @@ pub fn LoginPage() -> impl IntoView {
                         <a
                             href={move || {
                                 redirect_to.get()
-                                    .map(|path| format!("/register?redirect_to={}", urlencoding::encode(&path)))
+                                    .map(|path| format!("/register?redirect={}", urlencoding::encode(&path)))
                                     .unwrap_or_else(|| "/register".to_string())
                             }}
                             class="font-medium text-indigo-600 hover:text-indigo-500 dark:text-indigo-400 dark:hover:text-indigo-300"
@@ -1,20 +1,31 @@
 pub mod groups;
 pub mod home;
 pub mod invite_accept;
+pub mod join_group;
 pub mod login;
+pub mod profile;
+pub mod recovery_codes;
 pub mod recurring_debts;
 pub mod register;
+pub mod reset_password;
 pub mod shared_debts;
 pub mod shopping_lists;
 pub mod transactions;
 
 // Re-export page components
-pub use groups::{GroupsCreate, GroupsEdit, GroupsIndex, GroupsInvites, GroupsShow};
+pub use groups::{
+    GroupScope, GroupsCreate, GroupsDigest, GroupsEdit, GroupsIndex, GroupsInvites, GroupsShow,
+    GroupsStats,
+};
 pub use home::HomePage;
 pub use invite_accept::InviteAccept;
+pub use join_group::JoinGroup;
 pub use login::LoginPage;
+pub use profile::ProfilePage;
+pub use recovery_codes::RecoveryCodesPage;
 pub use recurring_debts::{RecurringDebtsCreate, RecurringDebtsEdit, RecurringDebtsShow};
 pub use register::RegisterPage;
+pub use reset_password::ResetPasswordPage;
 pub use shared_debts::{SharedDebtsCreate, SharedDebtsEdit};
 pub use shopping_lists::{ShoppingListCreate, ShoppingListEdit, ShoppingListShow};
 pub use transactions::{TransactionsCreate, TransactionsEdit};
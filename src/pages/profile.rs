@@ -0,0 +1,433 @@
+use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
+
+use crate::{
+    components::{
+        AppLayout, ErrorAlert, FormCard, FormField, FormInput, FormSelect, LoadingSpinner,
+        Navigation, PageHeader, SubmitButton, clear_resource_cache, use_number_format,
+    },
+    error::{AppError, handle_app_error},
+    features::auth::{
+        ChangePassword, CreateApiToken, DeleteAccount, LogoutAllSessions, NumberFormat,
+        RevokeApiToken, SetNumberFormat, UserSession, get_api_tokens, use_logout,
+        use_redirect_to_login,
+    },
+};
+
+/// Profile page - change password, log out other devices and delete account
+#[must_use]
+#[component]
+pub fn ProfilePage() -> impl IntoView {
+    let user_resource =
+        expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
+    let change_password_action = ServerAction::<ChangePassword>::new();
+    let delete_account_action = ServerAction::<DeleteAccount>::new();
+    let logout_all_action = ServerAction::<LogoutAllSessions>::new();
+    let number_format_resource = expect_context::<LocalResource<Result<NumberFormat, AppError>>>();
+    let number_format = use_number_format();
+    let set_number_format_action = ServerAction::<SetNumberFormat>::new();
+    let navigate = use_navigate();
+    let on_logout = use_logout();
+
+    let current_password = RwSignal::new(String::new());
+    let new_password = RwSignal::new(String::new());
+    let delete_password = RwSignal::new(String::new());
+    let show_delete_modal = RwSignal::new(false);
+    let (password_error, set_password_error) = signal(None::<String>);
+    let (password_changed, set_password_changed) = signal(false);
+    let (delete_error, set_delete_error) = signal(None::<String>);
+    let (logout_all_error, set_logout_all_error) = signal(None::<String>);
+    let (logged_out_all, set_logged_out_all) = signal(false);
+    let (number_format_error, set_number_format_error) = signal(None::<String>);
+
+    // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(Ok(None)) = user_resource.get() {
+            redirect_to_login();
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(result) = change_password_action.value().get() {
+            match result {
+                Ok(()) => {
+                    set_password_error.set(None);
+                    set_password_changed.set(true);
+                    current_password.set(String::new());
+                    new_password.set(String::new());
+                }
+                Err(e) => {
+                    set_password_changed.set(false);
+                    set_password_error.set(Some(e.to_string()));
+                }
+            }
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(result) = logout_all_action.value().get() {
+            match result {
+                Ok(()) => {
+                    set_logout_all_error.set(None);
+                    set_logged_out_all.set(true);
+                }
+                Err(e) => {
+                    set_logged_out_all.set(false);
+                    set_logout_all_error.set(Some(e.to_string()));
+                }
+            }
+        }
+    });
+
+    // Reload the format either way, so amounts and the select show what is
+    // actually saved
+    let redirect_on_number_format_error = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = set_number_format_action.value().get() {
+            match result {
+                Ok(()) => set_number_format_error.set(None),
+                Err(e) => set_number_format_error
+                    .set(handle_app_error(&e, &redirect_on_number_format_error)),
+            }
+            number_format_resource.refetch();
+        }
+    });
+
+    // The session is gone once the account is deleted, so drop cached data
+    // and leave the authenticated pages
+    Effect::new(move |_| {
+        if let Some(result) = delete_account_action.value().get() {
+            match result {
+                Ok(()) => {
+                    clear_resource_cache();
+                    user_resource.refetch();
+                    navigate("/", Default::default());
+                }
+                Err(e) => set_delete_error.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    let on_change_password = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        set_password_changed.set(false);
+        change_password_action.dispatch(ChangePassword {
+            current_password: current_password.get(),
+            new_password: new_password.get(),
+        });
+    };
+
+    view! {
+        <Suspense fallback=move || view! { <LoadingSpinner /> }>
+            {move || {
+                match user_resource.get() {
+                    Some(Ok(Some(user))) => view! {
+                        <div class="min-h-screen bg-gray-100 dark:bg-gray-900">
+                            <Navigation username=user.username.clone() on_logout=on_logout />
+                            <AppLayout>
+                                <div class="py-6">
+                                    <div class="max-w-3xl mx-auto px-4 sm:px-6 lg:px-8 space-y-6">
+                                        <PageHeader
+                                            title="Profile".to_string()
+                                            subtitle=format!("Signed in as {}", user.username)
+                                        />
+
+                                        <FormCard>
+                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Change Password"</h2>
+                                            <form on:submit=on_change_password class="space-y-6">
+                                                <FormField label="Current Password" for_id="current-password">
+                                                    <FormInput
+                                                        id="current-password"
+                                                        input_type="password"
+                                                        value=Signal::derive(move || current_password.get())
+                                                        on_input=Callback::new(move |val| current_password.set(val))
+                                                        required=true
+                                                    />
+                                                </FormField>
+
+                                                <FormField
+                                                    label="New Password"
+                                                    for_id="new-password"
+                                                    helper_text="Must be at least 8 characters long"
+                                                >
+                                                    <FormInput
+                                                        id="new-password"
+                                                        input_type="password"
+                                                        value=Signal::derive(move || new_password.get())
+                                                        on_input=Callback::new(move |val| new_password.set(val))
+                                                        required=true
+                                                    />
+                                                </FormField>
+
+                                                <ErrorAlert message=password_error />
+                                                <Show when=move || password_changed.get()>
+                                                    <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4">
+                                                        <p class="text-sm text-green-700 dark:text-green-300">"Your password has been changed."</p>
+                                                    </div>
+                                                </Show>
+
+                                                <SubmitButton
+                                                    text="Change Password"
+                                                    loading_text="Saving..."
+                                                    loading=Signal::derive(move || change_password_action.pending().get())
+                                                />
+                                            </form>
+                                        </FormCard>
+
+                                        <FormCard>
+                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Number Format"</h2>
+                                            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                                                "How amounts are written throughout your groups."
+                                            </p>
+                                            <FormField label="Amounts" for_id="number_format">
+                                                <FormSelect
+                                                    id="number_format"
+                                                    value=Signal::derive(move || number_format.get().as_str().to_string())
+                                                    on_change=Callback::new(move |val: String| {
+                                                        set_number_format_action.dispatch(SetNumberFormat { number_format: val });
+                                                    })
+                                                >
+                                                    {NumberFormat::ALL.into_iter().map(|format| view! {
+                                                        <option value=format.as_str()>{format.label()}</option>
+                                                    }).collect_view()}
+                                                </FormSelect>
+                                            </FormField>
+                                            <div class="mt-4">
+                                                <ErrorAlert message=number_format_error />
+                                            </div>
+                                        </FormCard>
+
+                                        <FormCard>
+                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Recovery Codes"</h2>
+                                            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                                                "Recovery codes let you reset a forgotten password."
+                                            </p>
+                                            <a
+                                                href="/recovery-codes"
+                                                class="text-sm font-medium text-indigo-600 hover:text-indigo-500 dark:text-indigo-400 dark:hover:text-indigo-300"
+                                            >
+                                                "Manage recovery codes"
+                                            </a>
+                                        </FormCard>
+
+                                        <FormCard>
+                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Sessions"</h2>
+                                            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                                                "Log out everywhere else you are signed in, e.g. on a lost phone. Changing your password does this too."
+                                            </p>
+                                            <ErrorAlert message=logout_all_error />
+                                            <Show when=move || logged_out_all.get()>
+                                                <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4 mb-4">
+                                                    <p class="text-sm text-green-700 dark:text-green-300">"All other devices have been logged out."</p>
+                                                </div>
+                                            </Show>
+                                            <button
+                                                type="button"
+                                                disabled=move || logout_all_action.pending().get()
+                                                on:click=move |_| {
+                                                    set_logged_out_all.set(false);
+                                                    logout_all_action.dispatch(LogoutAllSessions {});
+                                                }
+                                                class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors disabled:opacity-50"
+                                            >
+                                                {move || if logout_all_action.pending().get() { "Logging out..." } else { "Log out of all devices" }}
+                                            </button>
+                                        </FormCard>
+
+                                        <ApiTokensCard />
+
+                                        <FormCard>
+                                            <h2 class="text-lg font-semibold text-red-600 dark:text-red-400 mb-2">"Delete Account"</h2>
+                                            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                                                "Deleting your account is permanent. You must settle all balances and delete the groups you created first."
+                                            </p>
+                                            <button
+                                                type="button"
+                                                on:click=move |_| {
+                                                    set_delete_error.set(None);
+                                                    delete_password.set(String::new());
+                                                    show_delete_modal.set(true);
+                                                }
+                                                class="px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded-lg font-medium transition-colors"
+                                            >
+                                                "Delete Account"
+                                            </button>
+                                        </FormCard>
+                                    </div>
+                                </div>
+                            </AppLayout>
+
+                            <Show when=move || show_delete_modal.get()>
+                                <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50">
+                                    <div class="bg-white dark:bg-gray-800 rounded-xl shadow-2xl max-w-md w-full p-6">
+                                        <h3 class="text-xl font-bold text-gray-900 dark:text-white mb-3">"Delete your account?"</h3>
+                                        <p class="text-gray-700 dark:text-gray-300 mb-4">
+                                            "Enter your password to confirm. This cannot be undone."
+                                        </p>
+                                        <FormInput
+                                            id="delete-password"
+                                            input_type="password"
+                                            value=Signal::derive(move || delete_password.get())
+                                            on_input=Callback::new(move |val| delete_password.set(val))
+                                            required=true
+                                        />
+                                        <div class="mt-4">
+                                            <ErrorAlert message=delete_error />
+                                        </div>
+                                        <div class="flex justify-end gap-3 mt-4">
+                                            <button
+                                                type="button"
+                                                on:click=move |_| show_delete_modal.set(false)
+                                                class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                            >
+                                                "Cancel"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                disabled=move || delete_account_action.pending().get()
+                                                on:click=move |_| {
+                                                    delete_account_action.dispatch(DeleteAccount {
+                                                        password: delete_password.get(),
+                                                    });
+                                                }
+                                                class="px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded-lg font-medium transition-colors disabled:opacity-50"
+                                            >
+                                                {move || if delete_account_action.pending().get() { "Deleting..." } else { "Delete Account" }}
+                                            </button>
+                                        </div>
+                                    </div>
+                                </div>
+                            </Show>
+                        </div>
+                    }.into_any(),
+                    _ => view! { <LoadingSpinner /> }.into_any()
+                }
+            }}
+        </Suspense>
+    }
+}
+
+/// Create and revoke the tokens scripts use for the JSON API
+#[must_use]
+#[component]
+fn ApiTokensCard() -> impl IntoView {
+    let create_action = ServerAction::<CreateApiToken>::new();
+    let revoke_action = ServerAction::<RevokeApiToken>::new();
+    let tokens_resource = LocalResource::new(move || async move { get_api_tokens().await });
+
+    let name = RwSignal::new(String::new());
+    let (error_message, set_error_message) = signal(None::<String>);
+    // The new token in plain text, only shown until the page is left
+    let (new_secret, set_new_secret) = signal(None::<String>);
+
+    let redirect_to_login = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = create_action.value().get() {
+            match result {
+                Ok(created) => {
+                    set_error_message.set(None);
+                    set_new_secret.set(Some(created.secret));
+                    name.set(String::new());
+                    tokens_resource.refetch();
+                }
+                Err(e) => set_error_message.set(handle_app_error(&e, &redirect_to_login)),
+            }
+        }
+    });
+
+    let redirect_on_revoke_error = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = revoke_action.value().get() {
+            if let Err(e) = result {
+                set_error_message.set(handle_app_error(&e, &redirect_on_revoke_error));
+            }
+            tokens_resource.refetch();
+        }
+    });
+
+    let on_create = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        set_error_message.set(None);
+        set_new_secret.set(None);
+        create_action.dispatch(CreateApiToken { name: name.get() });
+    };
+
+    view! {
+        <FormCard>
+            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"API Tokens"</h2>
+            <p class="text-sm text-gray-600 dark:text-gray-400 mb-4">
+                "Scripts can read your groups and add debts and payments through the JSON API at /api/v1 by sending a token as "
+                <code class="font-mono">"Authorization: Bearer <token>"</code>
+                ". A token can do everything you can, so revoke it when it's no longer needed."
+            </p>
+            <ErrorAlert message=error_message />
+            {move || new_secret.get().map(|secret| view! {
+                <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4 mb-4">
+                    <p class="text-sm text-green-700 dark:text-green-300 mb-2">
+                        "Copy your new token now. It will not be shown again."
+                    </p>
+                    <p class="font-mono text-sm break-all text-gray-900 dark:text-white">{secret}</p>
+                </div>
+            })}
+            <Suspense fallback=move || view! { <div>"Loading tokens..."</div> }>
+                {move || {
+                    tokens_resource.get().and_then(Result::ok).map(|tokens| {
+                        if tokens.is_empty() {
+                            return view! {
+                                <p class="text-sm text-gray-500 dark:text-gray-400 mb-4">"You have no API tokens."</p>
+                            }.into_any();
+                        }
+                        view! {
+                            <ul class="divide-y divide-gray-200 dark:divide-gray-700 mb-4">
+                                {tokens.into_iter().map(|token| {
+                                    let token_id = token.id;
+                                    let last_used = token
+                                        .last_used_at
+                                        .map(|at| format!("last used {}", at.date()))
+                                        .unwrap_or_else(|| "never used".to_string());
+                                    view! {
+                                        <li class="flex items-center justify-between py-2">
+                                            <div>
+                                                <p class="text-sm font-medium text-gray-900 dark:text-white">{token.name}</p>
+                                                <p class="text-xs text-gray-500 dark:text-gray-400">
+                                                    {format!("Created {}, {}", token.created_at.date(), last_used)}
+                                                </p>
+                                            </div>
+                                            <button
+                                                type="button"
+                                                disabled=move || revoke_action.pending().get()
+                                                on:click=move |_| {
+                                                    set_error_message.set(None);
+                                                    revoke_action.dispatch(RevokeApiToken { token_id });
+                                                }
+                                                class="text-sm font-medium text-red-600 hover:text-red-500 dark:text-red-400 disabled:opacity-50"
+                                            >
+                                                "Revoke"
+                                            </button>
+                                        </li>
+                                    }
+                                }).collect_view()}
+                            </ul>
+                        }.into_any()
+                    })
+                }}
+            </Suspense>
+            <form on:submit=on_create class="space-y-4">
+                <FormField label="Token name" for_id="api-token-name" helper_text="What the token is for, e.g. Bank import">
+                    <FormInput
+                        id="api-token-name"
+                        value=Signal::derive(move || name.get())
+                        on_input=Callback::new(move |val| name.set(val))
+                        required=true
+                    />
+                </FormField>
+                <SubmitButton
+                    text="Create Token"
+                    loading_text="Creating..."
+                    loading=Signal::derive(move || create_action.pending().get())
+                />
+            </form>
+        </FormCard>
+    }
+}
@@ -0,0 +1,111 @@
+use leptos::prelude::*;
+
+use crate::{
+    components::{AppLayout, ErrorAlert, LoadingSpinner, Navigation, PageHeader},
+    features::auth::{RegenerateRecoveryCodes, UserSession, use_logout, use_redirect_to_login},
+};
+
+/// Plain-text recovery codes with a hint to store them safely
+#[must_use]
+#[component]
+pub fn RecoveryCodesList(codes: Vec<String>) -> impl IntoView {
+    view! {
+        <div>
+            <p class="text-sm text-gray-700 dark:text-gray-300 mb-3">
+                "Store these recovery codes somewhere safe. Each one can reset your password once, and they will not be shown again."
+            </p>
+            <ul class="grid grid-cols-2 gap-2 font-mono text-sm">
+                {codes.into_iter().map(|code| view! {
+                    <li class="px-3 py-2 rounded-md bg-gray-100 dark:bg-gray-700 text-gray-900 dark:text-white text-center">
+                        {code}
+                    </li>
+                }).collect_view()}
+            </ul>
+        </div>
+    }
+}
+
+/// Page for replacing the current user's recovery codes
+#[must_use]
+#[component]
+pub fn RecoveryCodesPage() -> impl IntoView {
+    let user_resource =
+        expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
+    let regenerate_action = ServerAction::<RegenerateRecoveryCodes>::new();
+    let on_logout = use_logout();
+
+    let (error_message, set_error_message) = signal(None::<String>);
+
+    // Effect to redirect if not authenticated
+    let redirect_to_login = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(Ok(None)) = user_resource.get() {
+            redirect_to_login();
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Err(e)) = regenerate_action.value().get() {
+            set_error_message.set(Some(e.to_string()));
+        }
+    });
+
+    let on_regenerate = move |_| {
+        let confirmed = window()
+            .confirm_with_message(
+                "Generate new recovery codes? Your current codes will stop working.",
+            )
+            .unwrap_or(false);
+        if confirmed {
+            set_error_message.set(None);
+            regenerate_action.dispatch(RegenerateRecoveryCodes {});
+        }
+    };
+
+    view! {
+        <Suspense fallback=move || view! { <LoadingSpinner /> }>
+            {move || {
+                match user_resource.get() {
+                    Some(Ok(Some(user))) => view! {
+                        <div class="min-h-screen bg-gray-100 dark:bg-gray-900">
+                            <Navigation username=user.username.clone() on_logout=on_logout />
+                            <AppLayout>
+                                <div class="py-6">
+                                    <div class="max-w-3xl mx-auto px-4 sm:px-6 lg:px-8">
+                                        <PageHeader
+                                            title="Recovery Codes".to_string()
+                                            subtitle="Reset your password without email".to_string()
+                                        />
+
+                                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 space-y-4">
+                                            {move || match regenerate_action.value().get() {
+                                                Some(Ok(codes)) => view! { <RecoveryCodesList codes=codes /> }.into_any(),
+                                                _ => view! {
+                                                    <p class="text-sm text-gray-700 dark:text-gray-300">
+                                                        "Your recovery codes were shown when you registered. If you lost them or used most of them, generate a new set."
+                                                    </p>
+                                                }.into_any(),
+                                            }}
+
+                                            <ErrorAlert message=error_message />
+
+                                            <button
+                                                type="button"
+                                                on:click=on_regenerate
+                                                disabled=move || regenerate_action.pending().get()
+                                                class="px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white rounded-lg font-medium transition-colors disabled:opacity-50"
+                                            >
+                                                {move || if regenerate_action.pending().get() { "Generating..." } else { "Generate New Codes" }}
+                                            </button>
+                                        </div>
+                                    </div>
+                                </div>
+                            </AppLayout>
+                        </div>
+                    }.into_any(),
+                    _ => view! { <LoadingSpinner /> }.into_any()
+                }
+            }}
+        </Suspense>
+    }
+}
@@ -0,0 +1,69 @@
+use leptos::prelude::*;
+use time::OffsetDateTime;
+
+use crate::features::recurring_debts::handlers::get_scheduler_status;
+
+fn format_timestamp(at: OffsetDateTime) -> String {
+    format!("{} {:02}:{:02} UTC", at.date(), at.hour(), at.minute())
+}
+
+/// Status of the recurring debts scheduler, warning when the last run failed
+/// or is overdue
+#[must_use]
+#[component]
+pub fn AutomationCard() -> impl IntoView {
+    let status_resource = LocalResource::new(get_scheduler_status);
+
+    move || {
+        let status = status_resource.get()?.ok()?;
+        let warning = status.needs_attention();
+
+        let last_run = match &status.last_run {
+            Some(run) => match (&run.error, run.finished_at) {
+                (Some(error), _) => format!(
+                    "Last run {} failed: {}",
+                    format_timestamp(run.started_at),
+                    error
+                ),
+                (None, Some(_)) => format!(
+                    "Last run {} — {} debt(s) generated",
+                    format_timestamp(run.started_at),
+                    run.generated_count
+                ),
+                (None, None) => format!(
+                    "Run started {} has not finished",
+                    format_timestamp(run.started_at)
+                ),
+            },
+            None => "The scheduler has not run yet".to_string(),
+        };
+
+        Some(view! {
+            <div class=if warning {
+                "bg-yellow-50 dark:bg-yellow-900/20 rounded-xl border border-yellow-300 dark:border-yellow-700 p-6 mb-6"
+            } else {
+                "bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6"
+            }>
+                <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-2">"Automation"</h2>
+                <p class=if warning {
+                    "text-sm text-yellow-800 dark:text-yellow-300"
+                } else {
+                    "text-sm text-gray-700 dark:text-gray-300"
+                }>
+                    {last_run}
+                </p>
+                {status.is_overdue.then(|| view! {
+                    <p class="text-sm text-yellow-800 dark:text-yellow-300 mt-1">
+                        "The scheduler is overdue by more than a day. Debts may not be generating."
+                    </p>
+                })}
+                <p class="text-xs text-gray-500 dark:text-gray-400 mt-2">
+                    {match status.next_run {
+                        Some(next) => format!("Next run expected {}", format_timestamp(next)),
+                        None => "Next run unknown — the cron expression could not be parsed".to_string(),
+                    }}
+                </p>
+            </div>
+        })
+    }
+}
@@ -0,0 +1,132 @@
+use leptos::prelude::*;
+
+use crate::features::recurring_debts::handlers::{
+    CreateCalendarToken, DeleteCalendarToken, calendar_feed_path, get_calendar_token,
+};
+
+/// Subscribe link for the token-authenticated iCal feed of upcoming debts
+#[must_use]
+#[component]
+pub fn CalendarSubscription(group_id: Memo<i64>) -> impl IntoView {
+    let create_action = ServerAction::<CreateCalendarToken>::new();
+    let delete_action = ServerAction::<DeleteCalendarToken>::new();
+    let (copied, _set_copied) = signal(false);
+
+    let token_resource = LocalResource::new(move || {
+        let _ = create_action.version().get();
+        let _ = delete_action.version().get();
+        get_calendar_token(group_id.get())
+    });
+
+    // The feed URL must be absolute for calendar apps; the origin is only
+    // known in the browser
+    let (origin, set_origin) = signal(String::new());
+    Effect::new(move |_| {
+        if let Ok(o) = window().location().origin() {
+            set_origin.set(o);
+        }
+    });
+
+    let feed_url = move |token: &str| {
+        format!(
+            "{}{}?token={}",
+            origin.get(),
+            calendar_feed_path(group_id.get()),
+            token
+        )
+    };
+
+    view! {
+        <Suspense fallback=|| ()>
+            {move || match token_resource.get() {
+                Some(Ok(Some(token))) => {
+                    let url = feed_url(&token);
+                    let url_for_copy = url.clone();
+                    view! {
+                        <div class="mb-4 p-3 bg-purple-50 dark:bg-purple-900/20 border border-purple-100 dark:border-purple-800 rounded-lg">
+                            <p class="text-sm text-gray-700 dark:text-gray-300 mb-2">
+                                "Add this URL to your calendar app to see upcoming recurring debts. Anyone with the link can view them."
+                            </p>
+                            <div class="flex flex-col sm:flex-row gap-2">
+                                <input
+                                    type="text"
+                                    readonly
+                                    prop:value=url
+                                    class="flex-1 px-3 py-1.5 text-sm font-mono bg-white dark:bg-gray-800 border border-gray-300 dark:border-gray-600 rounded-lg text-gray-900 dark:text-white"
+                                />
+                                <button
+                                    type="button"
+                                    class="px-3 py-1.5 bg-purple-600 hover:bg-purple-700 text-white rounded-lg text-sm font-medium transition-colors"
+                                    on:click=move |_| {
+                                        #[cfg(target_arch = "wasm32")]
+                                        {
+                                            if let Some(window) = web_sys::window() {
+                                                let _ = window.navigator().clipboard().write_text(&url_for_copy);
+
+                                                // Set copied state and reset after 2 seconds
+                                                _set_copied.set(true);
+                                                set_timeout(
+                                                    move || _set_copied.set(false),
+                                                    std::time::Duration::from_secs(2),
+                                                );
+                                            }
+                                        }
+                                        #[cfg(not(target_arch = "wasm32"))]
+                                        let _ = &url_for_copy;
+                                    }
+                                >
+                                    {move || if copied.get() { "Copied!" } else { "Copy" }}
+                                </button>
+                                <button
+                                    type="button"
+                                    class="px-3 py-1.5 bg-white hover:bg-gray-50 dark:bg-gray-800 dark:hover:bg-gray-700 border border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 rounded-lg text-sm font-medium transition-colors"
+                                    title="Issue a new link; the current one stops working"
+                                    disabled=move || create_action.pending().get()
+                                    on:click=move |_| {
+                                        create_action.dispatch(CreateCalendarToken {
+                                            group_id: group_id.get_untracked(),
+                                        });
+                                    }
+                                >
+                                    "Regenerate token"
+                                </button>
+                                <button
+                                    type="button"
+                                    class="px-3 py-1.5 bg-red-100 hover:bg-red-200 dark:bg-red-900/30 dark:hover:bg-red-900/50 text-red-700 dark:text-red-300 rounded-lg text-sm font-medium transition-colors"
+                                    disabled=move || delete_action.pending().get()
+                                    on:click=move |_| {
+                                        delete_action.dispatch(DeleteCalendarToken {
+                                            group_id: group_id.get_untracked(),
+                                        });
+                                    }
+                                >
+                                    "Revoke"
+                                </button>
+                            </div>
+                        </div>
+                    }.into_any()
+                }
+                Some(Ok(None)) => view! {
+                    <div class="mb-4">
+                        <button
+                            type="button"
+                            class="text-sm text-purple-600 hover:text-purple-700 dark:text-purple-400 dark:hover:text-purple-300 font-medium inline-flex items-center"
+                            disabled=move || create_action.pending().get()
+                            on:click=move |_| {
+                                create_action.dispatch(CreateCalendarToken {
+                                    group_id: group_id.get_untracked(),
+                                });
+                            }
+                        >
+                            <svg class="w-4 h-4 mr-1" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 7V3m8 4V3m-9 8h10M5 21h14a2 2 0 002-2V7a2 2 0 00-2-2H5a2 2 0 00-2 2v12a2 2 0 002 2z"/>
+                            </svg>
+                            "Subscribe in calendar"
+                        </button>
+                    </div>
+                }.into_any(),
+                _ => ().into_any(),
+            }}
+        </Suspense>
+    }
+}
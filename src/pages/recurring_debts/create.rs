@@ -6,13 +6,23 @@ use leptos_router::{
 
 use crate::{
     components::{
-        AppLayout, ErrorAlert, FormActions, FormCard, FormDateInput, FormField, FormInput,
-        FormNumberInput, FormSelect, LoadingSpinner, MemberCheckboxItem, Navigation, PageHeader,
+        AppLayout, CategorySelect, ErrorAlert, FieldCheck, FormActions, FormCard, FormDateInput,
+        FormField, FormInput, FormNumberInput, FormSelect, LoadingSpinner, MemberPicker,
+        Navigation, PageHeader, share_entries, use_currency_symbol,
     },
     features::{
-        auth::{UserSession, use_logout},
+        auth::{UserSession, use_logout, use_redirect_to_login},
         groups::handlers::{get_group, get_group_members},
-        recurring_debts::handlers::CreateRecurringDebt,
+        recurring_debts::{
+            handlers::CreateRecurringDebt,
+            utils::{
+                CUSTOM_FREQUENCY_CHOICES, browser_utc_offset_minutes, form_frequency,
+                parse_form_date,
+            },
+        },
+    },
+    validation::rules::{
+        MAX_NAME_LENGTH, check_amount, check_date_order, check_interval, check_name, check_weight,
     },
 };
 
@@ -24,6 +34,7 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let navigate = use_navigate();
     let on_logout = use_logout();
+    let currency = use_currency_symbol();
     let params = use_params_map();
 
     let group_id = Memo::new(move |_| {
@@ -47,17 +58,71 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
     let create_action = ServerAction::<CreateRecurringDebt>::new();
     let (name, set_name) = signal(String::new());
     let (amount, set_amount) = signal(String::new());
+    let (category, set_category) = signal(String::new());
     let (frequency, set_frequency) = signal("monthly".to_string());
+    let (frequency_interval, set_frequency_interval) = signal(String::new());
     let (start_date, set_start_date) = signal(String::new());
     let (end_date, set_end_date) = signal(String::new());
-    let (selected_members, set_selected_members) = signal(Vec::<i64>::new());
+    let selected_members = RwSignal::new(Vec::<i64>::new());
+    let split_weights = RwSignal::new(Vec::<(i64, String)>::new());
+    let auto_include_new_members = RwSignal::new(false);
     let (error_message, set_error_message) = signal(Option::<String>::None);
 
+    // Client-side mirrors of the server's rules; the server still checks everything
+    let name_check =
+        FieldCheck::new(move || check_name(&name.get(), 1, MAX_NAME_LENGTH, "Name").err());
+    let amount_check = FieldCheck::new(move || check_amount(&amount.get()).err());
+    let interval_check = FieldCheck::new(move || {
+        if !CUSTOM_FREQUENCY_CHOICES.contains(&frequency.get().as_str()) {
+            return None;
+        }
+        check_interval(&frequency_interval.get()).err()
+    });
+    let start_date_check =
+        FieldCheck::new(move || parse_form_date(&start_date.get(), "start date").err());
+    let end_date_check = FieldCheck::new(move || {
+        let end_date = end_date.get();
+        if end_date.is_empty() {
+            return None;
+        }
+        let end = match parse_form_date(&end_date, "end date") {
+            Ok(end) => end,
+            Err(e) => return Some(e),
+        };
+        let start = parse_form_date(&start_date.get(), "start date").ok()?;
+        check_date_order(start, end).err()
+    });
+    let members_check = FieldCheck::new(move || {
+        selected_members
+            .with(Vec::is_empty)
+            .then(|| "At least one member must be selected".to_string())
+    });
+    let weights_check = FieldCheck::new(move || {
+        split_weights.with(|weights| {
+            share_entries(&selected_members.get(), weights)
+                .iter()
+                .find_map(|(_, entry)| check_weight(entry).err())
+        })
+    });
+    let has_errors = Signal::derive(move || {
+        [
+            name_check,
+            amount_check,
+            interval_check,
+            start_date_check,
+            end_date_check,
+            members_check,
+            weights_check,
+        ]
+        .iter()
+        .any(FieldCheck::is_invalid)
+    });
+
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -66,7 +131,7 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
         if let Some(Ok(members)) = members_resource.get()
             && selected_members.get().is_empty()
         {
-            set_selected_members.set(members.iter().map(|m| m.id).collect());
+            selected_members.set(members.iter().map(|m| m.id).collect());
         }
     });
 
@@ -100,10 +165,16 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
             group_id: group_id.get(),
             name: name.get(),
             amount: amount.get(),
-            frequency: frequency.get(),
+            frequency: form_frequency(&frequency.get(), &frequency_interval.get()),
             start_date: start_date.get(),
             end_date: end_date_opt,
             member_ids: selected_members.get(),
+            weights: Some(
+                split_weights.with(|weights| share_entries(&selected_members.get(), weights)),
+            ),
+            category: Some(category.get()).filter(|category| !category.is_empty()),
+            utc_offset_minutes: browser_utc_offset_minutes(),
+            auto_include_new_members: auto_include_new_members.get(),
         });
     };
 
@@ -136,17 +207,18 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
                                                             <form on:submit=on_submit class="space-y-6">
                                                                 <ErrorAlert message=error_message />
 
-                                                                <FormField label="Name" for_id="name">
+                                                                <FormField label="Name" for_id="name" error=name_check.message()>
                                                                     <FormInput
                                                                         id="name"
                                                                         placeholder="e.g., Monthly rent"
                                                                         required=true
                                                                         value=Signal::derive(move || name.get())
                                                                         on_input=Callback::new(move |val| set_name.set(val))
+                                                                        on_blur=name_check.on_blur()
                                                                     />
                                                                 </FormField>
 
-                                                                <FormField label="Amount (€)" for_id="amount">
+                                                                <FormField label=move || format!("Amount ({})", currency.get()) for_id="amount" error=amount_check.message()>
                                                                     <FormNumberInput
                                                                         id="amount"
                                                                         placeholder="0.00"
@@ -155,6 +227,15 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
                                                                         required=true
                                                                         value=Signal::derive(move || amount.get())
                                                                         on_input=Callback::new(move |val| set_amount.set(val))
+                                                                        on_blur=amount_check.on_blur()
+                                                                    />
+                                                                </FormField>
+
+                                                                <FormField label="Category" for_id="category" helper_text="Generated debts get the same category.">
+                                                                    <CategorySelect
+                                                                        id="category"
+                                                                        value=Signal::derive(move || category.get())
+                                                                        on_change=Callback::new(move |val| set_category.set(val))
                                                                     />
                                                                 </FormField>
 
@@ -169,58 +250,69 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
                                                                         <option value="weekly">"Weekly"</option>
                                                                         <option value="monthly" selected>"Monthly"</option>
                                                                         <option value="yearly">"Yearly"</option>
+                                                                        <option value="every_n_days">"Every N days"</option>
+                                                                        <option value="every_n_weeks">"Every N weeks"</option>
                                                                     </FormSelect>
                                                                 </FormField>
 
+                                                                <Show when=move || CUSTOM_FREQUENCY_CHOICES.contains(&frequency.get().as_str())>
+                                                                    <FormField
+                                                                        label="Repeat every"
+                                                                        for_id="frequency_interval"
+                                                                        helper_text="Number of days or weeks between instances"
+                                                                        error=interval_check.message()
+                                                                    >
+                                                                        <FormNumberInput
+                                                                            id="frequency_interval"
+                                                                            placeholder="e.g. 3"
+                                                                            min="1"
+                                                                            step="1"
+                                                                            required=true
+                                                                            value=Signal::derive(move || frequency_interval.get())
+                                                                            on_input=Callback::new(move |val| set_frequency_interval.set(val))
+                                                                            on_blur=interval_check.on_blur()
+                                                                        />
+                                                                    </FormField>
+                                                                </Show>
+
                                                                 <div class="grid grid-cols-1 sm:grid-cols-2 gap-4">
-                                                                    <FormField label="Start Date" for_id="start_date">
+                                                                    <FormField label="Start Date" for_id="start_date" error=start_date_check.message()>
                                                                         <FormDateInput
                                                                             id="start_date"
                                                                             required=true
                                                                             value=Signal::derive(move || start_date.get())
                                                                             on_input=Callback::new(move |val| set_start_date.set(val))
+                                                                            on_blur=start_date_check.on_blur()
                                                                         />
                                                                     </FormField>
 
-                                                                    <FormField label="End Date (Optional)" for_id="end_date">
+                                                                    <FormField label="End Date (Optional)" for_id="end_date" error=end_date_check.message()>
                                                                         <FormDateInput
                                                                             id="end_date"
                                                                             value=Signal::derive(move || end_date.get())
                                                                             on_input=Callback::new(move |val| set_end_date.set(val))
+                                                                            on_blur=end_date_check.on_blur()
                                                                         />
                                                                     </FormField>
                                                                 </div>
 
-                                                                <FormField label="Split Between">
+                                                                <FormField
+                                                                    label="Split Between"
+                                                                    helper_text="Members owe in proportion to their weight, e.g. weight 2 pays twice as much as weight 1"
+                                                                    error=Signal::derive(move || {
+                                                                        members_check.message().get().or_else(|| weights_check.message().get())
+                                                                    })
+                                                                >
                                                                     <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
                                                                         {move || {
                                                                             match members_resource.get() {
                                                                                 Some(Ok(members)) => {
-                                                                                    let selected = selected_members.get();
                                                                                     view! {
-                                                                                        <div class="space-y-2 max-h-64 overflow-y-auto border border-gray-200 dark:border-gray-600 rounded-lg p-4">
-                                                                                            {members.into_iter().map(|member| {
-                                                                                                let member_id = member.id;
-                                                                                                let is_checked = selected.contains(&member_id);
-                                                                                                view! {
-                                                                                                    <MemberCheckboxItem
-                                                                                                        member_id=member_id
-                                                                                                        username=member.username
-                                                                                                        is_checked=is_checked
-                                                                                                        on_change=Callback::new(move |checked| {
-                                                                                                            set_selected_members.update(|members| {
-                                                                                                                if checked {
-                                                                                                                    if !members.contains(&member_id) {
-                                                                                                                        members.push(member_id);
-                                                                                                                    }
-                                                                                                                } else {
-                                                                                                                    members.retain(|&id| id != member_id);
-                                                                                                                }
-                                                                                                            });
-                                                                                                        })
-                                                                                                    />
-                                                                                                }
-                                                                                            }).collect_view()}
+                                                                                        <div
+                                                                                            on:click=move |_| members_check.touch()
+                                                                                            on:focusout=move |_| weights_check.touch()
+                                                                                        >
+                                                                                            <MemberPicker members=members selected=selected_members weights=split_weights />
                                                                                         </div>
                                                                                     }.into_any()
                                                                                 },
@@ -233,10 +325,21 @@ pub fn RecurringDebtsCreate() -> impl IntoView {
                                                                     </Suspense>
                                                                 </FormField>
 
+                                                                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                        prop:checked=move || auto_include_new_members.get()
+                                                                        on:change=move |ev| auto_include_new_members.set(event_target_checked(&ev))
+                                                                    />
+                                                                    "Add members who join the group later"
+                                                                </label>
+
                                                                 <FormActions
                                                                     submit_text="Create Recurring Debt"
                                                                     loading_text="Creating..."
                                                                     loading=Signal::derive(move || create_action.pending().get())
+                                                                    disabled=has_errors
                                                                     cancel_href=format!("/groups/{}", gid)
                                                                 />
                                                             </form>
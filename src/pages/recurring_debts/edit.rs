@@ -3,16 +3,20 @@ use leptos_router::hooks::{use_navigate, use_params_map};
 
 use crate::{
     components::{
-        AppLayout, ErrorAlert, FormActions, FormCard, FormDateInput, FormField, FormInput,
-        FormNumberInput, FormSelect, LoadingSpinner, MemberCheckboxItem, Navigation, PageHeader,
+        AppLayout, CategorySelect, EditErrorAlert, FieldCheck, FormActions, FormCard,
+        FormDateInput, FormField, FormInput, FormNumberInput, FormSelect, LoadingSpinner,
+        MemberPicker, Navigation, PageHeader, share_entries, use_currency_symbol,
     },
     features::{
-        auth::{UserSession, use_logout},
+        auth::{UserSession, use_logout, use_redirect_to_login},
         groups::handlers::get_group_members,
-        recurring_debts::handlers::{
-            UpdateRecurringDebt, get_recurring_debt, get_recurring_debt_members,
+        recurring_debts::{
+            handlers::{UpdateRecurringDebt, get_recurring_debt, get_recurring_debt_shares},
+            utils::{CUSTOM_FREQUENCY_CHOICES, form_frequency, frequency_form_values},
         },
     },
+    pages::shared_debts::DuplicateNotice,
+    validation::rules::check_interval,
 };
 
 /// Edit recurring debt page
@@ -23,6 +27,7 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let navigate = use_navigate();
     let on_logout = use_logout();
+    let currency = use_currency_symbol();
     let params = use_params_map();
 
     let group_id = Memo::new(move |_| {
@@ -53,23 +58,34 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
 
     let debt_members_resource = LocalResource::new(move || {
         let id = recurring_id.get();
-        async move { get_recurring_debt_members(id).await }
+        async move { get_recurring_debt_shares(id).await }
     });
 
     let update_action = ServerAction::<UpdateRecurringDebt>::new();
     let (name, set_name) = signal(String::new());
     let (amount, set_amount) = signal(String::new());
+    let (category, set_category) = signal(String::new());
     let (frequency, set_frequency) = signal("monthly".to_string());
+    let (frequency_interval, set_frequency_interval) = signal(String::new());
     let (start_date, set_start_date) = signal(String::new());
     let (end_date, set_end_date) = signal(String::new());
-    let (selected_members, set_selected_members) = signal(Vec::<i64>::new());
+    let selected_members = RwSignal::new(Vec::<i64>::new());
+    let split_weights = RwSignal::new(Vec::<(i64, String)>::new());
+    let auto_include_new_members = RwSignal::new(false);
     let (error_message, set_error_message) = signal(Option::<String>::None);
 
+    let interval_check = FieldCheck::new(move || {
+        if !CUSTOM_FREQUENCY_CHOICES.contains(&frequency.get().as_str()) {
+            return None;
+        }
+        check_interval(&frequency_interval.get()).err()
+    });
+
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -78,16 +94,26 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
         if let Some(Ok(debt)) = debt_resource.get() {
             set_name.set(debt.name.clone());
             set_amount.set(debt.amount.to_string());
-            set_frequency.set(debt.frequency.to_string());
+            set_category.set(debt.category.map(|c| c.to_string()).unwrap_or_default());
+            let (choice, interval) = frequency_form_values(&debt.frequency);
+            set_frequency.set(choice);
+            set_frequency_interval.set(interval);
             set_start_date.set(debt.start_date.to_string());
             set_end_date.set(debt.end_date.map(|d| d.to_string()).unwrap_or_default());
+            auto_include_new_members.set(debt.auto_include_new_members);
         }
     });
 
-    // Effect to populate selected members
+    // Effect to populate selected members and their weights
     Effect::new(move |_| {
-        if let Some(Ok(member_ids)) = debt_members_resource.get() {
-            set_selected_members.set(member_ids);
+        if let Some(Ok(members)) = debt_members_resource.get() {
+            selected_members.set(members.iter().map(|m| m.user_id).collect());
+            split_weights.set(
+                members
+                    .iter()
+                    .map(|m| (m.user_id, m.weight.to_string()))
+                    .collect(),
+            );
         }
     });
 
@@ -124,24 +150,35 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
             Some(end_date_value)
         };
 
-        // Get current is_active state from debt_resource
-        let is_active = debt_resource
-            .get()
-            .and_then(|r| r.ok())
-            .map(|d| d.is_active)
-            .unwrap_or(true);
+        // Get current is_active state and version from debt_resource
+        let loaded = debt_resource.get().and_then(|r| r.ok());
+        let is_active = loaded.as_ref().map(|d| d.is_active).unwrap_or(true);
+        let version = loaded.map(|d| d.version).unwrap_or_default();
 
         update_action.dispatch(UpdateRecurringDebt {
             recurring_debt_id: recurring_id.get(),
             name: name.get(),
             amount: amount.get(),
-            frequency: frequency.get(),
+            frequency: form_frequency(&frequency.get(), &frequency_interval.get()),
             end_date: end_date_opt,
             is_active,
             member_ids: selected_members.get(),
+            weights: Some(
+                split_weights.with(|weights| share_entries(&selected_members.get(), weights)),
+            ),
+            category: Some(category.get()).filter(|category| !category.is_empty()),
+            auto_include_new_members: auto_include_new_members.get(),
+            version,
         });
     };
 
+    // Load the debt again after someone else saved it
+    let on_reload = Callback::new(move |()| {
+        set_error_message.set(None);
+        debt_resource.refetch();
+        debt_members_resource.refetch();
+    });
+
     let gid = group_id.get_untracked();
     let rid = recurring_id.get_untracked();
 
@@ -174,9 +211,11 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
                                                     Some(Ok(_debt)) => view! {
                                                         <PageHeader title="Edit Recurring Debt".to_string() />
 
+                                                        <DuplicateNotice />
+
                                                         <FormCard>
                                                             <form on:submit=on_submit class="space-y-6">
-                                                                <ErrorAlert message=error_message />
+                                                                <EditErrorAlert message=error_message on_reload=on_reload />
 
                                                                 <FormField label="Name" for_id="name">
                                                                     <FormInput
@@ -187,7 +226,7 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
                                                                     />
                                                                 </FormField>
 
-                                                                <FormField label="Amount (€)" for_id="amount">
+                                                                <FormField label=move || format!("Amount ({})", currency.get()) for_id="amount">
                                                                     <FormNumberInput
                                                                         id="amount"
                                                                         min="0.01"
@@ -198,6 +237,14 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
                                                                     />
                                                                 </FormField>
 
+                                                                <FormField label="Category" for_id="category" helper_text="Generated debts get the same category.">
+                                                                    <CategorySelect
+                                                                        id="category"
+                                                                        value=Signal::derive(move || category.get())
+                                                                        on_change=Callback::new(move |val| set_category.set(val))
+                                                                    />
+                                                                </FormField>
+
                                                                 <FormField label="Frequency" for_id="frequency">
                                                                     <FormSelect
                                                                         id="frequency"
@@ -209,9 +256,31 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
                                                                         <option value="weekly">"Weekly"</option>
                                                                         <option value="monthly">"Monthly"</option>
                                                                         <option value="yearly">"Yearly"</option>
+                                                                        <option value="every_n_days">"Every N days"</option>
+                                                                        <option value="every_n_weeks">"Every N weeks"</option>
                                                                     </FormSelect>
                                                                 </FormField>
 
+                                                                <Show when=move || CUSTOM_FREQUENCY_CHOICES.contains(&frequency.get().as_str())>
+                                                                    <FormField
+                                                                        label="Repeat every"
+                                                                        for_id="frequency_interval"
+                                                                        helper_text="Number of days or weeks between instances"
+                                                                        error=interval_check.message()
+                                                                    >
+                                                                        <FormNumberInput
+                                                                            id="frequency_interval"
+                                                                            placeholder="e.g. 3"
+                                                                            min="1"
+                                                                            step="1"
+                                                                            required=true
+                                                                            value=Signal::derive(move || frequency_interval.get())
+                                                                            on_input=Callback::new(move |val| set_frequency_interval.set(val))
+                                                                            on_blur=interval_check.on_blur()
+                                                                        />
+                                                                    </FormField>
+                                                                </Show>
+
                                                                 <div class="grid grid-cols-1 sm:grid-cols-2 gap-4">
                                                                     <FormField label="Start Date (Cannot be changed)" for_id="start_date">
                                                                         <FormDateInput
@@ -231,37 +300,16 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
                                                                     </FormField>
                                                                 </div>
 
-                                                                <FormField label="Split Between">
+                                                                <FormField
+                                                                    label="Split Between"
+                                                                    helper_text="Members owe in proportion to their weight, e.g. weight 2 pays twice as much as weight 1"
+                                                                >
                                                                     <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
                                                                         {move || {
                                                                             match members_resource.get() {
                                                                                 Some(Ok(members)) => {
-                                                                                    let selected = selected_members.get();
                                                                                     view! {
-                                                                                        <div class="space-y-2 max-h-64 overflow-y-auto border border-gray-200 dark:border-gray-600 rounded-lg p-4">
-                                                                                            {members.into_iter().map(|member| {
-                                                                                                let member_id = member.id;
-                                                                                                let is_checked = selected.contains(&member_id);
-                                                                                                view! {
-                                                                                                    <MemberCheckboxItem
-                                                                                                        member_id=member_id
-                                                                                                        username=member.username
-                                                                                                        is_checked=is_checked
-                                                                                                        on_change=Callback::new(move |checked| {
-                                                                                                            set_selected_members.update(|members| {
-                                                                                                                if checked {
-                                                                                                                    if !members.contains(&member_id) {
-                                                                                                                        members.push(member_id);
-                                                                                                                    }
-                                                                                                                } else {
-                                                                                                                    members.retain(|&id| id != member_id);
-                                                                                                                }
-                                                                                                            });
-                                                                                                        })
-                                                                                                    />
-                                                                                                }
-                                                                                            }).collect_view()}
-                                                                                        </div>
+                                                                                        <MemberPicker members=members selected=selected_members weights=split_weights />
                                                                                     }.into_any()
                                                                                 },
                                                                                 Some(Err(e)) => view! {
@@ -273,6 +321,16 @@ pub fn RecurringDebtsEdit() -> impl IntoView {
                                                                     </Suspense>
                                                                 </FormField>
 
+                                                                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                        prop:checked=move || auto_include_new_members.get()
+                                                                        on:change=move |ev| auto_include_new_members.set(event_target_checked(&ev))
+                                                                    />
+                                                                    "Add members who join the group later"
+                                                                </label>
+
                                                                 <FormActions
                                                                     submit_text="Update Recurring Debt"
                                                                     loading_text="Updating..."
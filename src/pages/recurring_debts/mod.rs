@@ -1,7 +1,10 @@
+mod automation;
+mod calendar;
 mod create;
 mod edit;
 mod show;
 
+pub use calendar::CalendarSubscription;
 pub use create::RecurringDebtsCreate;
 pub use edit::RecurringDebtsEdit;
 pub use show::RecurringDebtsShow;
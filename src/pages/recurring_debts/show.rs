@@ -1,15 +1,24 @@
 use leptos::prelude::*;
 use leptos_router::hooks::{use_navigate, use_params_map};
 
+use super::{automation::AutomationCard, calendar::CalendarSubscription};
 use crate::{
-    components::{AppLayout, Navigation},
+    components::{
+        AppLayout, CategoryBadge, ErrorCard, Money, Navigation, format_money, forms::ErrorAlert,
+        use_currency_symbol, use_number_format,
+    },
+    error::{AppError, handle_app_error},
     features::{
-        auth::{UserSession, use_logout},
+        auth::{UserSession, use_logout, use_redirect_to_login},
         recurring_debts::handlers::{
-            DeleteRecurringDebt, GenerateNow, ToggleRecurringDebtActive, get_generated_instances,
-            get_recurring_debt, get_recurring_debt_shares,
+            DeleteRecurringDebt, DuplicateRecurringDebt, GenerateNow, MAX_PREVIEW_OCCURRENCES,
+            SkipNextOccurrence, ToggleRecurringDebtActive, UnskipOccurrence,
+            get_generated_instances, get_recurring_debt, get_recurring_debt_shares,
+            get_skipped_occurrences, preview_occurrences,
         },
+        recurring_debts::utils::calculate_next_occurrence,
     },
+    pages::shared_debts::duplicate_edit_path,
 };
 
 /// Show recurring debt details page
@@ -53,15 +62,32 @@ pub fn RecurringDebtsShow() -> impl IntoView {
         async move { get_generated_instances(id).await }
     });
 
+    let preview_resource = LocalResource::new(move || {
+        let id = recurring_id.get();
+        async move { preview_occurrences(id, MAX_PREVIEW_OCCURRENCES).await }
+    });
+
+    let skips_resource = LocalResource::new(move || {
+        let id = recurring_id.get();
+        async move { get_skipped_occurrences(id).await }
+    });
+
     let toggle_action = ServerAction::<ToggleRecurringDebtActive>::new();
     let generate_action = ServerAction::<GenerateNow>::new();
     let delete_action = ServerAction::<DeleteRecurringDebt>::new();
+    let duplicate_action = ServerAction::<DuplicateRecurringDebt>::new();
+    let skip_action = ServerAction::<SkipNextOccurrence>::new();
+    let unskip_action = ServerAction::<UnskipOccurrence>::new();
+    let number_format = use_number_format();
+    let currency = use_currency_symbol();
+    let (duplicate_error, set_duplicate_error) = signal(None::<String>);
+    let (skip_error, set_skip_error) = signal(None::<String>);
 
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -69,6 +95,7 @@ pub fn RecurringDebtsShow() -> impl IntoView {
     Effect::new(move |_| {
         if let Some(Ok(_)) = toggle_action.value().get() {
             debt_resource.refetch();
+            preview_resource.refetch();
         }
     });
 
@@ -77,6 +104,42 @@ pub fn RecurringDebtsShow() -> impl IntoView {
         if let Some(Ok(_)) = generate_action.value().get() {
             instances_resource.refetch();
             debt_resource.refetch();
+            preview_resource.refetch();
+        }
+    });
+
+    // Effect to reload the schedule when a skip or its undo completes
+    let on_skip_result = move |result: Option<Result<time::Date, ServerFnError>>| {
+        let Some(result) = result else {
+            return;
+        };
+        set_skip_error.set(result.err().map(|e| e.to_string()));
+        debt_resource.refetch();
+        preview_resource.refetch();
+        skips_resource.refetch();
+    };
+    Effect::new(move |_| on_skip_result(skip_action.value().get()));
+    Effect::new(move |_| on_skip_result(unskip_action.value().get()));
+
+    // Effect to open the paused copy in the editor
+    let navigate_to_copy = navigate.clone();
+    let redirect_on_error = use_redirect_to_login();
+    Effect::new(move |_| {
+        if let Some(result) = duplicate_action.value().get() {
+            match result {
+                Ok(duplicate) => {
+                    let edit_path = format!(
+                        "/groups/{}/recurring-debts/{}/edit",
+                        group_id.get_untracked(),
+                        duplicate.id
+                    );
+                    navigate_to_copy(
+                        &duplicate_edit_path(edit_path, &duplicate),
+                        Default::default(),
+                    );
+                }
+                Err(e) => set_duplicate_error.set(handle_app_error(&e, &redirect_on_error)),
+            }
         }
     });
 
@@ -99,6 +162,20 @@ pub fn RecurringDebtsShow() -> impl IntoView {
         });
     };
 
+    let on_unskip = move |_| {
+        set_skip_error.set(None);
+        unskip_action.dispatch(UnskipOccurrence {
+            recurring_debt_id: recurring_id.get(),
+        });
+    };
+
+    let on_duplicate = move |_| {
+        set_duplicate_error.set(None);
+        duplicate_action.dispatch(DuplicateRecurringDebt {
+            recurring_debt_id: recurring_id.get(),
+        });
+    };
+
     let on_delete = move |_| {
         if window()
             .confirm_with_message("Are you sure you want to delete this recurring debt? Generated debts will remain, but no new ones will be created.")
@@ -133,9 +210,12 @@ pub fn RecurringDebtsShow() -> impl IntoView {
                                                     Some(Ok(debt)) => view! {
                                                         <div class="mb-8">
                                                             <div class="flex items-center justify-between">
-                                                                <h1 class="text-2xl sm:text-3xl font-bold text-gray-900 dark:text-white">
-                                                                    {debt.name.clone()}
-                                                                </h1>
+                                                                <div class="flex items-center gap-3 min-w-0">
+                                                                    <h1 class="text-2xl sm:text-3xl font-bold text-gray-900 dark:text-white">
+                                                                        {debt.name.clone()}
+                                                                    </h1>
+                                                                    {debt.category.map(|category| view! { <CategoryBadge category=category /> })}
+                                                                </div>
                                                                 <span class={format!(
                                                                     "px-3 py-1 rounded-full text-sm font-medium {}",
                                                                     if debt.is_active {
@@ -164,13 +244,13 @@ pub fn RecurringDebtsShow() -> impl IntoView {
                                                                 <div>
                                                                     <p class="text-sm text-gray-500 dark:text-gray-400">"Amount"</p>
                                                                     <p class="text-xl font-semibold text-gray-900 dark:text-white">
-                                                                        "€" {debt.amount.to_string()}
+                                                                        <Money amount=debt.amount />
                                                                     </p>
                                                                 </div>
                                                                 <div>
                                                                     <p class="text-sm text-gray-500 dark:text-gray-400">"Frequency"</p>
                                                                     <p class="text-xl font-semibold text-gray-900 dark:text-white capitalize">
-                                                                        {debt.frequency.to_string()}
+                                                                        {debt.frequency.label()}
                                                                     </p>
                                                                 </div>
                                                                 <div>
@@ -215,9 +295,76 @@ pub fn RecurringDebtsShow() -> impl IntoView {
                                                                                         <div class="flex justify-between items-center py-2 border-b border-gray-100 dark:border-gray-700 last:border-0">
                                                                                             <span class="text-gray-900 dark:text-white">
                                                                                                 {share.username}
+                                                                                                {(share.weight != 1).then(|| view! {
+                                                                                                    <span class="ml-2 text-sm text-gray-500 dark:text-gray-400">
+                                                                                                        "weight " {share.weight}
+                                                                                                    </span>
+                                                                                                })}
+                                                                                                {share.absorbs_remainder.then(|| view! {
+                                                                                                    <span
+                                                                                                        class="ml-2 text-sm text-gray-500 dark:text-gray-400"
+                                                                                                        title="Includes the cents left over by the split"
+                                                                                                    >
+                                                                                                        "+rounding"
+                                                                                                    </span>
+                                                                                                })}
                                                                                             </span>
                                                                                             <span class="font-medium text-gray-900 dark:text-white">
-                                                                                                "€" {share.share_amount.to_string()}
+                                                                                                <Money amount=share.share_amount />
+                                                                                            </span>
+                                                                                        </div>
+                                                                                    }
+                                                                                }).collect_view()}
+                                                                            </div>
+                                                                        }.into_any(),
+                                                                        Some(Err(e)) => view! {
+                                                                            <div class="text-red-600 dark:text-red-400">"Error: " {e.to_string()}</div>
+                                                                        }.into_any(),
+                                                                        None => view! { <div>"Loading..."</div> }.into_any()
+                                                                    }
+                                                                }}
+                                                            </Suspense>
+                                                        </div>
+
+                                                        <AutomationCard />
+
+                                                        // Calendar Feed Card
+                                                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
+                                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">
+                                                                "Calendar Feed"
+                                                            </h2>
+                                                            <CalendarSubscription group_id=group_id />
+                                                        </div>
+
+                                                        // Upcoming Occurrences Card
+                                                        <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 mb-6">
+                                                            <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">
+                                                                "Upcoming"
+                                                            </h2>
+                                                            <Suspense fallback=move || view! { <div>"Loading upcoming debts..."</div> }>
+                                                                {move || {
+                                                                    match preview_resource.get() {
+                                                                        Some(Ok(preview)) if preview.occurrences.is_empty() => view! {
+                                                                            <p class="text-gray-500 dark:text-gray-400 text-sm">
+                                                                                {preview.reason.unwrap_or_else(|| "No debts are coming up.".to_string())}
+                                                                            </p>
+                                                                        }.into_any(),
+                                                                        Some(Ok(preview)) => view! {
+                                                                            <div class="space-y-2">
+                                                                                {preview.occurrences.into_iter().map(|occurrence| {
+                                                                                    let shares = occurrence
+                                                                                        .shares
+                                                                                        .into_iter()
+                                                                                        .map(|share| format!("{} {}", share.username, format_money(share.share_amount, currency.get(), number_format.get())))
+                                                                                        .collect::<Vec<_>>()
+                                                                                        .join(" · ");
+                                                                                    view! {
+                                                                                        <div class="flex justify-between items-center gap-4 py-2 border-b border-gray-100 dark:border-gray-700 last:border-0">
+                                                                                            <span class="font-medium text-gray-900 dark:text-white whitespace-nowrap">
+                                                                                                {occurrence.date.to_string()}
+                                                                                            </span>
+                                                                                            <span class="text-sm text-gray-500 dark:text-gray-400 text-right">
+                                                                                                {shares}
                                                                                             </span>
                                                                                         </div>
                                                                                     }
@@ -260,7 +407,7 @@ pub fn RecurringDebtsShow() -> impl IntoView {
                                                                                                 </p>
                                                                                             </div>
                                                                                             <span class="font-medium text-gray-900 dark:text-white">
-                                                                                                "€" {instance.amount.to_string()}
+                                                                                                <Money amount=instance.amount />
                                                                                             </span>
                                                                                         </div>
                                                                                     }
@@ -274,10 +421,48 @@ pub fn RecurringDebtsShow() -> impl IntoView {
                                                                     }
                                                                 }}
                                                             </Suspense>
+                                                            {move || skips_resource.get().and_then(Result::ok).filter(|skips| !skips.is_empty()).map(|skips| {
+                                                                view! {
+                                                                    <div class="mt-4 pt-4 border-t border-gray-200 dark:border-gray-700">
+                                                                        <h3 class="text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">"Skipped"</h3>
+                                                                        <div class="space-y-2">
+                                                                            {skips.into_iter().enumerate().map(|(index, skip)| {
+                                                                                // Only the latest skip can be undone, and only
+                                                                                // while its date is still the next one
+                                                                                let can_undo = index == 0
+                                                                                    && debt.is_creator
+                                                                                    && skip.next_date == debt.next_generation_date;
+                                                                                view! {
+                                                                                    <div class="flex justify-between items-center gap-4 py-2 border-b border-gray-100 dark:border-gray-700 last:border-0">
+                                                                                        <div>
+                                                                                            <p class="text-gray-900 dark:text-white font-medium">
+                                                                                                {skip.skipped_date.to_string()}
+                                                                                            </p>
+                                                                                            <p class="text-sm text-gray-500 dark:text-gray-400">
+                                                                                                "Skipped by " {skip.skipped_by_username} " on " {skip.created_at.date().to_string()}
+                                                                                            </p>
+                                                                                        </div>
+                                                                                        {can_undo.then(|| view! {
+                                                                                            <button
+                                                                                                on:click=on_unskip
+                                                                                                disabled=move || unskip_action.pending().get()
+                                                                                                class="px-3 py-1.5 text-sm text-indigo-700 dark:text-indigo-300 bg-indigo-50 dark:bg-indigo-900/30 hover:bg-indigo-100 dark:hover:bg-indigo-900/50 disabled:opacity-50 rounded-lg font-medium"
+                                                                                            >
+                                                                                                "Undo"
+                                                                                            </button>
+                                                                                        })}
+                                                                                    </div>
+                                                                                }
+                                                                            }).collect_view()}
+                                                                        </div>
+                                                                    </div>
+                                                                }
+                                                            })}
                                                         </div>
 
                                                         // Actions
                                                         {if debt.is_creator {
+                                                            let skipped_next_date = calculate_next_occurrence(debt.next_generation_date, &debt.frequency);
                                                             view! {
                                                                 <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6">
                                                                     <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">
@@ -306,12 +491,44 @@ pub fn RecurringDebtsShow() -> impl IntoView {
                                                                                 }
                                                                             }}
                                                                         </button>
+                                                                        <button
+                                                                            on:click=move |_| {
+                                                                                let confirmed = window()
+                                                                                    .confirm_with_message(&format!(
+                                                                                        "Skip the debt due on {}? The next one will be generated on {}.",
+                                                                                        debt.next_generation_date, skipped_next_date
+                                                                                    ))
+                                                                                    .unwrap_or(false);
+                                                                                if confirmed {
+                                                                                    set_skip_error.set(None);
+                                                                                    skip_action.dispatch(SkipNextOccurrence {
+                                                                                        recurring_debt_id: rid,
+                                                                                    });
+                                                                                }
+                                                                            }
+                                                                            disabled=move || skip_action.pending().get()
+                                                                            title=format!("Next debt would then be generated on {}", skipped_next_date)
+                                                                            class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 disabled:bg-gray-400 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                                                        >
+                                                                            {move || if skip_action.pending().get() {
+                                                                                "Skipping...".to_string()
+                                                                            } else {
+                                                                                format!("Skip next ({} → {})", debt.next_generation_date, skipped_next_date)
+                                                                            }}
+                                                                        </button>
                                                                         <a
                                                                             href=format!("/groups/{}/recurring-debts/{}/edit", gid, rid)
                                                                             class="px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white rounded-lg font-medium transition-colors"
                                                                         >
                                                                             "Edit"
                                                                         </a>
+                                                                        <button
+                                                                            on:click=on_duplicate
+                                                                            disabled=move || duplicate_action.pending().get()
+                                                                            class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 disabled:bg-gray-400 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                                                        >
+                                                                            {move || if duplicate_action.pending().get() { "Duplicating..." } else { "Duplicate" }}
+                                                                        </button>
                                                                         <button
                                                                             on:click=on_delete
                                                                             disabled=move || delete_action.pending().get()
@@ -320,22 +537,36 @@ pub fn RecurringDebtsShow() -> impl IntoView {
                                                                             {move || if delete_action.pending().get() { "Deleting..." } else { "Delete" }}
                                                                         </button>
                                                                     </div>
+                                                                    <div class="mt-4 space-y-2">
+                                                                        <ErrorAlert message=duplicate_error />
+                                                                        <ErrorAlert message=skip_error />
+                                                                    </div>
                                                                 </div>
                                                             }.into_any()
                                                         } else {
                                                             view! {
-                                                                <div class="bg-yellow-50 dark:bg-yellow-900/30 rounded-lg p-4">
+                                                                <div class="bg-yellow-50 dark:bg-yellow-900/30 rounded-lg p-4 space-y-3">
                                                                     <p class="text-sm text-yellow-700 dark:text-yellow-300">
-                                                                        "Only the creator can modify this recurring debt."
+                                                                        "Only the creator can modify this recurring debt, but you can start your own copy of it."
                                                                     </p>
+                                                                    <button
+                                                                        on:click=on_duplicate
+                                                                        disabled=move || duplicate_action.pending().get()
+                                                                        class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-600 dark:hover:bg-gray-500 disabled:bg-gray-400 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                                                                    >
+                                                                        {move || if duplicate_action.pending().get() { "Duplicating..." } else { "Duplicate" }}
+                                                                    </button>
+                                                                    <ErrorAlert message=duplicate_error />
                                                                 </div>
                                                             }.into_any()
                                                         }}
                                                     }.into_any(),
                                                     Some(Err(e)) => view! {
-                                                        <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
-                                                            <p class="text-sm text-red-700 dark:text-red-300">"Error: " {e.to_string()}</p>
-                                                        </div>
+                                                        <ErrorCard
+                                                            error=AppError::from(e)
+                                                            back_label="Back to group"
+                                                            back_href=format!("/groups/{}", group_id.get())
+                                                        />
                                                     }.into_any(),
                                                     None => view! { <div>"Loading..."</div> }.into_any()
                                                 }
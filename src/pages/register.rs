@@ -1,9 +1,10 @@
 use leptos::prelude::*;
-use leptos_router::hooks::{use_navigate, use_query_map};
+use leptos_router::hooks::use_navigate;
 
 use crate::{
     components::{GuestLayout, InputLabel, PrimaryButton, TextInput},
-    features::auth::{RegisterUser, UserSession},
+    features::auth::{RegisterUser, UserSession, use_redirect_target},
+    pages::recovery_codes::RecoveryCodesList,
 };
 
 /// Registration page component
@@ -15,17 +16,11 @@ pub fn RegisterPage() -> impl IntoView {
     let password_signal = RwSignal::new(String::new());
     let email_signal = RwSignal::new(String::new());
     let navigate = use_navigate();
-    let query_map = use_query_map();
     let user_resource =
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
 
-    // Get redirect_to query parameter
-    let redirect_to = Memo::new(move |_| {
-        query_map
-            .read()
-            .get("redirect_to")
-            .and_then(|encoded| urlencoding::decode(&encoded).ok().map(|s| s.into_owned()))
-    });
+    // Validated redirect target from the query string
+    let redirect_to = use_redirect_target();
 
     // Redirect logged-in users
     let navigate_clone = navigate.clone();
@@ -53,17 +48,16 @@ pub fn RegisterPage() -> impl IntoView {
         });
     };
 
-    // Effect to handle navigation after successful registration
+    // The recovery codes are only shown here, so the user moves on once they
+    // confirm having saved them
     let has_refetched = RwSignal::new(false);
-    Effect::new(move |_| {
-        if let Some(Ok(_)) = register_action.value().get()
-            && !has_refetched.get()
-        {
+    let on_continue = move |_| {
+        if !has_refetched.get() {
             // Refetch user resource to update the global user context
             user_resource.refetch();
             has_refetched.set(true);
         }
-    });
+    };
 
     // Separate effect to navigate after resource is updated
     Effect::new(move |_| {
@@ -143,10 +137,18 @@ pub fn RegisterPage() -> impl IntoView {
                     {move || {
                         register_action.value().get().map(|result| {
                             match result {
-                                Ok(_) => {
+                                Ok(registration) => {
                                     view! {
-                                        <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4">
-                                            <p class="text-sm text-green-700 dark:text-green-300">"Registration successful! Redirecting..."</p>
+                                        <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4 space-y-3">
+                                            <p class="text-sm text-green-700 dark:text-green-300">"Registration successful!"</p>
+                                            <RecoveryCodesList codes=registration.recovery_codes />
+                                            <button
+                                                type="button"
+                                                on:click=on_continue
+                                                class="w-full px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white rounded-md text-sm font-medium transition-colors"
+                                            >
+                                                "I have saved my codes"
+                                            </button>
                                         </div>
                                     }.into_any()
                                 },
@@ -166,7 +168,7 @@ pub fn RegisterPage() -> impl IntoView {
                         <a
                             href={move || {
                                 redirect_to.get()
-                                    .map(|path| format!("/login?redirect_to={}", urlencoding::encode(&path)))
+                                    .map(|path| format!("/login?redirect={}", urlencoding::encode(&path)))
                                     .unwrap_or_else(|| "/login".to_string())
                             }}
                             class="font-medium text-indigo-600 hover:text-indigo-500 dark:text-indigo-400 dark:hover:text-indigo-300"
@@ -0,0 +1,109 @@
+use leptos::prelude::*;
+
+use crate::{
+    components::{GuestLayout, InputLabel, PrimaryButton, TextInput},
+    features::auth::ResetPasswordWithCode,
+};
+
+/// Password reset page using a recovery code
+#[must_use]
+#[component]
+pub fn ResetPasswordPage() -> impl IntoView {
+    let reset_action = ServerAction::<ResetPasswordWithCode>::new();
+    let username_signal = RwSignal::new(String::new());
+    let code_signal = RwSignal::new(String::new());
+    let password_signal = RwSignal::new(String::new());
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        reset_action.dispatch(ResetPasswordWithCode {
+            username: username_signal.get(),
+            code: code_signal.get(),
+            new_password: password_signal.get(),
+        });
+    };
+
+    view! {
+        <GuestLayout>
+            <div>
+                <h2 class="text-2xl font-bold text-gray-900 dark:text-white mb-2">"Reset Password"</h2>
+                <p class="text-sm text-gray-600 dark:text-gray-400 mb-6">
+                    "Enter one of the recovery codes you saved when registering. Each code works only once."
+                </p>
+
+                <form on:submit=on_submit class="space-y-6">
+                    <div>
+                        <InputLabel for_input="username">"Username"</InputLabel>
+                        <TextInput
+                            input_type="text"
+                            class="block mt-1 w-full"
+                            required=true
+                            value=username_signal
+                        />
+                    </div>
+
+                    <div>
+                        <InputLabel for_input="code">"Recovery Code"</InputLabel>
+                        <TextInput
+                            input_type="text"
+                            placeholder="XXXXX-XXXXX"
+                            class="block mt-1 w-full font-mono"
+                            required=true
+                            value=code_signal
+                        />
+                    </div>
+
+                    <div>
+                        <InputLabel for_input="password">"New Password"</InputLabel>
+                        <TextInput
+                            input_type="password"
+                            placeholder="Minimum 8 characters"
+                            class="block mt-1 w-full"
+                            required=true
+                            value=password_signal
+                        />
+                    </div>
+
+                    <div class="flex items-center justify-end">
+                        <PrimaryButton
+                            button_type="submit"
+                            disabled=reset_action.pending()
+                        >
+                            {move || if reset_action.pending().get() { "Resetting..." } else { "Reset Password" }}
+                        </PrimaryButton>
+                    </div>
+
+                    {move || {
+                        reset_action.value().get().map(|result| {
+                            match result {
+                                Ok(()) => view! {
+                                    <div class="rounded-md bg-green-50 dark:bg-green-900/30 p-4">
+                                        <p class="text-sm text-green-700 dark:text-green-300">
+                                            "Your password has been reset. "
+                                            <a href="/login" class="font-medium underline">"Log in"</a>
+                                            " with your new password."
+                                        </p>
+                                    </div>
+                                }.into_any(),
+                                Err(e) => view! {
+                                    <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
+                                        <p class="text-sm text-red-700 dark:text-red-300">{e.to_string()}</p>
+                                    </div>
+                                }.into_any()
+                            }
+                        })
+                    }}
+                </form>
+
+                <div class="mt-6 text-center">
+                    <a
+                        href="/login"
+                        class="text-sm font-medium text-indigo-600 hover:text-indigo-500 dark:text-indigo-400 dark:hover:text-indigo-300"
+                    >
+                        "Back to login"
+                    </a>
+                </div>
+            </div>
+        </GuestLayout>
+    }
+}
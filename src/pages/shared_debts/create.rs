@@ -6,13 +6,19 @@ use leptos_router::{
 
 use crate::{
     components::{
-        AppLayout, ErrorAlert, FormActions, FormCard, FormField, FormNumberInput, LoadingSpinner,
-        MemberCheckboxItem, Navigation, PageHeader,
+        AppLayout, CategorySelect, ErrorAlert, FieldCheck, ForeignAmountEntry, ForeignAmountInputs,
+        FormActions, FormCard, FormDateInput, FormField, FormNumberInput, FormSelect,
+        LoadingSpinner, MemberPicker, Navigation, PageHeader, ShareInputs, share_entries,
+        use_currency_symbol,
     },
+    error::handle_app_error,
     features::{
-        auth::{UserSession, use_logout},
+        auth::{UserSession, use_logout, use_redirect_to_login},
         groups::handlers::{get_group, get_group_members},
-        shared_debts::handlers::CreateSharedDebt,
+        shared_debts::handlers::{CreateSharedDebt, SaveDebtPreset, get_debt_presets},
+    },
+    validation::rules::{
+        MAX_NAME_LENGTH, check_amount, check_custom_shares, check_date_filter, check_name,
     },
 };
 
@@ -24,6 +30,7 @@ pub fn SharedDebtsCreate() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let navigate = use_navigate();
     let on_logout = use_logout();
+    let currency = use_currency_symbol();
     let params = use_params_map();
 
     let group_id = Memo::new(move |_| {
@@ -44,17 +51,100 @@ pub fn SharedDebtsCreate() -> impl IntoView {
         async move { get_group_members(id).await }
     });
 
+    let presets_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { get_debt_presets(id).await }
+    });
+
     let create_action = ServerAction::<CreateSharedDebt>::new();
+    let save_preset_action = ServerAction::<SaveDebtPreset>::new();
     let (name, set_name) = signal(String::new());
     let (amount, set_amount) = signal(String::new());
-    let (selected_members, set_selected_members) = signal(Vec::<i64>::new());
+    let (category, set_category) = signal(String::new());
+    let (due_date, set_due_date) = signal(String::new());
+    let (incurred_on, set_incurred_on) = signal(String::new());
+    let selected_members = RwSignal::new(Vec::<i64>::new());
+    let custom_split = RwSignal::new(false);
+    let shares = RwSignal::new(Vec::<(i64, String)>::new());
+    let paid_abroad = RwSignal::new(false);
+    let foreign_entry = ForeignAmountEntry::new();
+    // Empty until the user loads, then defaults to the current user
+    let (payer_id, set_payer_id) = signal(String::new());
     let (error_message, set_error_message) = signal(Option::<String>::None);
+    let (preset_name, set_preset_name) = signal(String::new());
+    // Members and category as last filled in, by default or from a preset
+    let preset_baseline = RwSignal::new(None::<(Vec<i64>, String)>);
+    let preset_select = NodeRef::<leptos::html::Select>::new();
+
+    // Client-side mirrors of the server's rules; the server still checks everything
+    let name_check =
+        FieldCheck::new(move || check_name(&name.get(), 1, MAX_NAME_LENGTH, "Name").err());
+    let amount_check = FieldCheck::new(move || {
+        if paid_abroad.get() {
+            return None;
+        }
+        check_amount(&amount.get()).err()
+    });
+    // Amount in the group's currency to split; converted when paid in another
+    // currency
+    let booked_amount = Signal::derive(move || {
+        if paid_abroad.get() {
+            foreign_entry
+                .check()
+                .map(|original| original.converted().to_string())
+                .unwrap_or_default()
+        } else {
+            amount.get()
+        }
+    });
+    let foreign_check =
+        FieldCheck::new(move || paid_abroad.get().then(|| foreign_entry.check().err())?);
+    let due_date_check =
+        FieldCheck::new(move || check_date_filter(Some(&due_date.get()), "due date").err());
+    let incurred_on_check =
+        FieldCheck::new(move || check_date_filter(Some(&incurred_on.get()), "date").err());
+    let members_check = FieldCheck::new(move || {
+        selected_members
+            .with(Vec::is_empty)
+            .then(|| "At least one member must be selected to split the debt".to_string())
+    });
+    let shares_check = FieldCheck::new(move || {
+        if !custom_split.get() {
+            return None;
+        }
+        let total = check_amount(&booked_amount.get()).ok()?;
+        let entries = shares.with(|shares| share_entries(&selected_members.get(), shares));
+        check_custom_shares(total, &entries).err()
+    });
+    let has_errors = Signal::derive(move || {
+        [
+            name_check,
+            amount_check,
+            foreign_check,
+            due_date_check,
+            incurred_on_check,
+            members_check,
+            shares_check,
+        ]
+        .iter()
+        .any(FieldCheck::is_invalid)
+    });
 
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
+    let redirect_on_error = redirect_to_login.clone();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
+        }
+    });
+
+    // Effect to default the payer to the current user
+    Effect::new(move |_| {
+        if let Some(Ok(Some(user))) = user_resource.get()
+            && payer_id.get_untracked().is_empty()
+        {
+            set_payer_id.set(user.id.to_string());
         }
     });
 
@@ -63,10 +153,32 @@ pub fn SharedDebtsCreate() -> impl IntoView {
         if let Some(Ok(members)) = members_resource.get()
             && selected_members.get().is_empty()
         {
-            set_selected_members.set(members.iter().map(|m| m.id).collect());
+            selected_members.set(members.iter().map(|m| m.id).collect());
+        }
+    });
+
+    // Effect to remember the default selection, to tell when it was changed
+    Effect::new(move |_| {
+        let members = selected_members.get();
+        if preset_baseline.get_untracked().is_none() && !members.is_empty() {
+            preset_baseline.set(Some((sorted(members), category.get_untracked())));
         }
     });
 
+    let redirect_on_preset_error = redirect_on_error.clone();
+    Effect::new(move |_| match save_preset_action.value().get() {
+        Some(Ok(preset)) => {
+            set_preset_name.set(String::new());
+            preset_baseline.set(Some((
+                preset.member_ids,
+                preset.category.unwrap_or_default(),
+            )));
+            presets_resource.refetch();
+        }
+        Some(Err(e)) => set_error_message.set(handle_app_error(&e, &redirect_on_preset_error)),
+        None => {}
+    });
+
     // Effect to handle submission result
     Effect::new(move |_| {
         if let Some(result) = create_action.value().get() {
@@ -78,12 +190,61 @@ pub fn SharedDebtsCreate() -> impl IntoView {
                     );
                 }
                 Err(e) => {
-                    set_error_message.set(Some(e.to_string()));
+                    set_error_message.set(handle_app_error(&e, &redirect_on_error));
                 }
             }
         }
     });
 
+    let on_preset_change = move |ev| {
+        let preset = event_target_value(&ev).parse::<i64>().ok().and_then(|id| {
+            presets_resource
+                .get_untracked()
+                .and_then(Result::ok)?
+                .into_iter()
+                .find(|preset| preset.id == id)
+        });
+        // The dropdown only picks a preset to fill in, so it always goes back
+        if let Some(select) = preset_select.get_untracked() {
+            select.set_value("");
+        }
+        let Some(preset) = preset else {
+            return;
+        };
+
+        let edited = preset_baseline
+            .get_untracked()
+            .is_some_and(|(members, cat)| {
+                sorted(selected_members.get_untracked()) != members
+                    || category.get_untracked() != cat
+            });
+        if edited
+            && !window()
+                .confirm_with_message(&format!(
+                    "Replace the selected members and category with the preset \"{}\"?",
+                    preset.name
+                ))
+                .unwrap_or(false)
+        {
+            return;
+        }
+
+        let preset_category = preset.category.unwrap_or_default();
+        selected_members.set(preset.member_ids.clone());
+        set_category.set(preset_category.clone());
+        preset_baseline.set(Some((sorted(preset.member_ids), preset_category)));
+    };
+
+    let on_save_preset = move |_| {
+        set_error_message.set(None);
+        save_preset_action.dispatch(SaveDebtPreset {
+            group_id: group_id.get(),
+            name: preset_name.get(),
+            member_ids: selected_members.get(),
+            category: Some(category.get()).filter(|category| !category.is_empty()),
+        });
+    };
+
     let on_submit = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
         set_error_message.set(None);
@@ -91,8 +252,18 @@ pub fn SharedDebtsCreate() -> impl IntoView {
         create_action.dispatch(CreateSharedDebt {
             group_id: group_id.get(),
             name: name.get(),
-            amount: amount.get(),
+            amount: booked_amount.get(),
             member_ids: selected_members.get(),
+            payer_id: payer_id.get().parse::<i64>().ok(),
+            custom_shares: custom_split
+                .get()
+                .then(|| shares.with(|shares| share_entries(&selected_members.get(), shares))),
+            category: Some(category.get()).filter(|category| !category.is_empty()),
+            due_date: Some(due_date.get()).filter(|due_date| !due_date.is_empty()),
+            original_amount: paid_abroad.get().then(|| foreign_entry.amount.get()),
+            original_currency: paid_abroad.get().then(|| foreign_entry.currency.get()),
+            exchange_rate: paid_abroad.get().then(|| foreign_entry.exchange_rate.get()),
+            incurred_on: Some(incurred_on.get()).filter(|incurred_on| !incurred_on.is_empty()),
         });
     };
 
@@ -125,7 +296,33 @@ pub fn SharedDebtsCreate() -> impl IntoView {
                                                             <form on:submit=on_submit class="space-y-6">
                                                                 <ErrorAlert message=error_message />
 
-                                                                <FormField label="Name" for_id="name">
+                                                                {move || {
+                                                                    let presets = presets_resource
+                                                                        .get()
+                                                                        .and_then(Result::ok)
+                                                                        .unwrap_or_default();
+                                                                    (!presets.is_empty()).then(|| view! {
+                                                                        <FormField
+                                                                            label="Preset"
+                                                                            for_id="preset"
+                                                                            helper_text="Fills in the members and category you saved"
+                                                                        >
+                                                                            <select
+                                                                                id="preset"
+                                                                                node_ref=preset_select
+                                                                                class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white"
+                                                                                on:change=on_preset_change
+                                                                            >
+                                                                                <option value="" selected>"Choose a preset..."</option>
+                                                                                {presets.into_iter().map(|preset| view! {
+                                                                                    <option value=preset.id.to_string()>{preset.name}</option>
+                                                                                }).collect_view()}
+                                                                            </select>
+                                                                        </FormField>
+                                                                    })
+                                                                }}
+
+                                                                <FormField label="Name" for_id="name" error=name_check.message()>
                                                                     <input
                                                                         type="text"
                                                                         id="name"
@@ -134,51 +331,118 @@ pub fn SharedDebtsCreate() -> impl IntoView {
                                                                         placeholder="e.g., Dinner at restaurant"
                                                                         value=name.get_untracked()
                                                                         on:input=move |ev| set_name.set(event_target_value(&ev))
+                                                                        on:blur=move |_| name_check.touch()
                                                                     />
                                                                 </FormField>
 
-                                                                <FormField label="Amount (€)" for_id="amount">
-                                                                    <FormNumberInput
-                                                                        id="amount"
-                                                                        placeholder="0.00"
-                                                                        min="0.01"
-                                                                        step="0.01"
-                                                                        required=true
-                                                                        value=Signal::derive(move || amount.get())
-                                                                        on_input=Callback::new(move |val| set_amount.set(val))
+                                                                {move || if paid_abroad.get() {
+                                                                    view! {
+                                                                        <FormField label="Amount paid" error=foreign_check.message()>
+                                                                            <ForeignAmountInputs entry=foreign_entry on_blur=foreign_check.on_blur() />
+                                                                        </FormField>
+                                                                    }.into_any()
+                                                                } else {
+                                                                    view! {
+                                                                        <FormField label=move || format!("Amount ({})", currency.get()) for_id="amount" error=amount_check.message()>
+                                                                            <FormNumberInput
+                                                                                id="amount"
+                                                                                placeholder="0.00"
+                                                                                min="0.01"
+                                                                                step="0.01"
+                                                                                required=true
+                                                                                value=Signal::derive(move || amount.get())
+                                                                                on_input=Callback::new(move |val| set_amount.set(val))
+                                                                                on_blur=amount_check.on_blur()
+                                                                            />
+                                                                        </FormField>
+                                                                    }.into_any()
+                                                                }}
+
+                                                                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                        prop:checked=move || paid_abroad.get()
+                                                                        on:change=move |ev| paid_abroad.set(event_target_checked(&ev))
                                                                     />
+                                                                    "Paid in another currency"
+                                                                </label>
+
+                                                                <FormField label="Category" for_id="category">
+                                                                    <CategorySelect
+                                                                        id="category"
+                                                                        value=Signal::derive(move || category.get())
+                                                                        on_change=Callback::new(move |val| set_category.set(val))
+                                                                    />
+                                                                </FormField>
+
+                                                                <FormField
+                                                                    label="Date"
+                                                                    for_id="incurred_on"
+                                                                    helper_text="When the expense happened; leave empty for today."
+                                                                    error=incurred_on_check.message()
+                                                                >
+                                                                    <FormDateInput
+                                                                        id="incurred_on"
+                                                                        value=Signal::derive(move || incurred_on.get())
+                                                                        on_input=Callback::new(move |val| set_incurred_on.set(val))
+                                                                        on_blur=incurred_on_check.on_blur()
+                                                                    />
+                                                                </FormField>
+
+                                                                <FormField
+                                                                    label="Due date"
+                                                                    for_id="due_date"
+                                                                    helper_text="Optional deadline for paying the debt back."
+                                                                    error=due_date_check.message()
+                                                                >
+                                                                    <FormDateInput
+                                                                        id="due_date"
+                                                                        value=Signal::derive(move || due_date.get())
+                                                                        on_input=Callback::new(move |val| set_due_date.set(val))
+                                                                        on_blur=due_date_check.on_blur()
+                                                                    />
+                                                                </FormField>
+
+                                                                <FormField label="Paid by" for_id="payer_id">
+                                                                    <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
+                                                                        {move || {
+                                                                            members_resource.get().and_then(Result::ok).map(|members| {
+                                                                                let current_payer = payer_id.get_untracked();
+                                                                                view! {
+                                                                                    <FormSelect
+                                                                                        id="payer_id"
+                                                                                        required=true
+                                                                                        value=Signal::derive(move || payer_id.get())
+                                                                                        on_change=Callback::new(move |val| set_payer_id.set(val))
+                                                                                    >
+                                                                                        {members
+                                                                                            .into_iter()
+                                                                                            .map(|member| {
+                                                                                                let value = member.id.to_string();
+                                                                                                let selected = value == current_payer;
+                                                                                                view! {
+                                                                                                    <option value=value selected=selected>
+                                                                                                        {member.username}
+                                                                                                    </option>
+                                                                                                }
+                                                                                            })
+                                                                                            .collect_view()}
+                                                                                    </FormSelect>
+                                                                                }
+                                                                            })
+                                                                        }}
+                                                                    </Suspense>
                                                                 </FormField>
 
-                                                                <FormField label="Split Between">
+                                                                <FormField label="Split Between" error=members_check.message()>
                                                                     <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
                                                                         {move || {
                                                                             match members_resource.get() {
                                                                                 Some(Ok(members)) => {
-                                                                                    let selected = selected_members.get();
                                                                                     view! {
-                                                                                        <div class="space-y-2 max-h-64 overflow-y-auto border border-gray-200 dark:border-gray-600 rounded-lg p-4">
-                                                                                            {members.into_iter().map(|member| {
-                                                                                                let member_id = member.id;
-                                                                                                let is_checked = selected.contains(&member_id);
-                                                                                                view! {
-                                                                                                    <MemberCheckboxItem
-                                                                                                        member_id=member_id
-                                                                                                        username=member.username
-                                                                                                        is_checked=is_checked
-                                                                                                        on_change=Callback::new(move |checked| {
-                                                                                                            set_selected_members.update(|members| {
-                                                                                                                if checked {
-                                                                                                                    if !members.contains(&member_id) {
-                                                                                                                        members.push(member_id);
-                                                                                                                    }
-                                                                                                                } else {
-                                                                                                                    members.retain(|&id| id != member_id);
-                                                                                                                }
-                                                                                                            });
-                                                                                                        })
-                                                                                                    />
-                                                                                                }
-                                                                                            }).collect_view()}
+                                                                                        <div on:click=move |_| members_check.touch()>
+                                                                                            <MemberPicker members=members selected=selected_members />
                                                                                         </div>
                                                                                     }.into_any()
                                                                                 },
@@ -191,10 +455,66 @@ pub fn SharedDebtsCreate() -> impl IntoView {
                                                                     </Suspense>
                                                                 </FormField>
 
+                                                                <FormField
+                                                                    label="Save as preset"
+                                                                    for_id="preset_name"
+                                                                    helper_text="Only you see your presets; each group keeps up to 10."
+                                                                >
+                                                                    <div class="flex gap-2">
+                                                                        <input
+                                                                            type="text"
+                                                                            id="preset_name"
+                                                                            maxlength="255"
+                                                                            class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white"
+                                                                            placeholder="e.g., Flatmates"
+                                                                            prop:value=move || preset_name.get()
+                                                                            on:input=move |ev| set_preset_name.set(event_target_value(&ev))
+                                                                        />
+                                                                        <button
+                                                                            type="button"
+                                                                            class="px-4 py-2 text-sm font-medium text-indigo-700 dark:text-indigo-300 bg-indigo-50 dark:bg-indigo-900/30 rounded-lg hover:bg-indigo-100 dark:hover:bg-indigo-900/50 disabled:opacity-50 whitespace-nowrap"
+                                                                            disabled=move || preset_name.with(|name| name.trim().is_empty()) || save_preset_action.pending().get()
+                                                                            on:click=on_save_preset
+                                                                        >
+                                                                            "Save"
+                                                                        </button>
+                                                                    </div>
+                                                                </FormField>
+
+                                                                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                        prop:checked=move || custom_split.get()
+                                                                        on:change=move |ev| custom_split.set(event_target_checked(&ev))
+                                                                    />
+                                                                    "Split by custom amounts"
+                                                                </label>
+
+                                                                {move || custom_split.get().then(|| view! {
+                                                                    <FormField
+                                                                        label=move || format!("Shares ({} or %)", currency.get())
+                                                                        helper_text="Members left empty split the rest equally."
+                                                                        error=shares_check.message()
+                                                                    >
+                                                                        {move || members_resource.get().and_then(Result::ok).map(|members| view! {
+                                                                            <div on:focusout=move |_| shares_check.touch()>
+                                                                                <ShareInputs
+                                                                                    members=members
+                                                                                    selected=selected_members
+                                                                                    shares=shares
+                                                                                    total=booked_amount
+                                                                                />
+                                                                            </div>
+                                                                        })}
+                                                                    </FormField>
+                                                                })}
+
                                                                 <FormActions
                                                                     submit_text="Add Debt"
                                                                     loading_text="Adding..."
                                                                     loading=Signal::derive(move || create_action.pending().get())
+                                                                    disabled=has_errors
                                                                     cancel_href=format!("/groups/{}", gid)
                                                                 />
                                                             </form>
@@ -220,3 +540,9 @@ pub fn SharedDebtsCreate() -> impl IntoView {
         </Suspense>
     }
 }
+
+/// Member ids in a fixed order, to compare selections
+fn sorted(mut member_ids: Vec<i64>) -> Vec<i64> {
+    member_ids.sort_unstable();
+    member_ids
+}
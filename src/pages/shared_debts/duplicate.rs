@@ -0,0 +1,69 @@
+use leptos::prelude::*;
+use leptos_router::hooks::use_query_map;
+
+use crate::features::shared_debts::models::DuplicatedDebt;
+
+/// Edit page of a fresh duplicate, telling it which members were left out
+///
+/// `edit_path` is the page's path without a query.
+pub fn duplicate_edit_path(edit_path: String, duplicate: &DuplicatedDebt) -> String {
+    let mut path = format!("{}?copied=1", edit_path);
+    if !duplicate.dropped_members.is_empty() {
+        path.push_str("&dropped=");
+        path.push_str(&duplicate.dropped_members.join(","));
+    }
+    path
+}
+
+/// Note on an edit page opened right after duplicating, see
+/// [`duplicate_edit_path`]
+#[must_use]
+#[component]
+pub fn DuplicateNotice() -> impl IntoView {
+    let query = use_query_map();
+    let is_copy = move || query.read().get("copied").is_some();
+    let dropped = move || {
+        query
+            .read()
+            .get("dropped")
+            .filter(|dropped| !dropped.is_empty())
+    };
+
+    view! {
+        <Show when=is_copy>
+            <div class="mb-6 rounded-md bg-indigo-50 dark:bg-indigo-900/30 p-4">
+                <p class="text-sm text-indigo-700 dark:text-indigo-300">
+                    "This is a new copy; adjust anything that differs this time."
+                </p>
+                {move || dropped().map(|dropped| view! {
+                    <p class="mt-1 text-sm text-indigo-700 dark:text-indigo-300">
+                        {format!("Left out since they are no longer in the group: {}", dropped.replace(',', ", "))}
+                    </p>
+                })}
+            </div>
+        </Show>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_edit_path() {
+        let mut duplicate = DuplicatedDebt {
+            id: 7,
+            dropped_members: Vec::new(),
+        };
+        assert_eq!(
+            duplicate_edit_path("/groups/1/debts/7/edit".to_string(), &duplicate),
+            "/groups/1/debts/7/edit?copied=1"
+        );
+
+        duplicate.dropped_members = vec!["carol".to_string(), "dave".to_string()];
+        assert_eq!(
+            duplicate_edit_path("/groups/1/debts/7/edit".to_string(), &duplicate),
+            "/groups/1/debts/7/edit?copied=1&dropped=carol,dave"
+        );
+    }
+}
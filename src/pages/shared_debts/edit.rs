@@ -4,16 +4,23 @@ use leptos_router::{
     hooks::{use_navigate, use_params_map},
 };
 
+use super::{DuplicateNotice, SharedDebtHistory};
 use crate::{
     components::{
-        AppLayout, ErrorAlert, FormActions, FormCard, FormField, FormNumberInput, LoadingSpinner,
-        MemberCheckboxItem, Navigation, PageHeader,
+        AppLayout, CategorySelect, EditErrorAlert, FieldCheck, ForeignAmountEntry,
+        ForeignAmountInputs, FormActions, FormCard, FormDateInput, FormField, FormNumberInput,
+        FormSelect, LoadingSpinner, MemberPicker, Navigation, PageHeader, ShareInputs,
+        share_entries, use_currency_symbol,
     },
+    error::handle_app_error,
     features::{
-        auth::{UserSession, use_logout},
+        auth::{UserSession, use_logout, use_redirect_to_login},
         groups::handlers::get_group_members,
-        shared_debts::handlers::{UpdateSharedDebt, get_shared_debt, get_shared_debt_members},
+        shared_debts::handlers::{
+            UpdateSharedDebt, get_shared_debt, get_shared_debt_members, get_shared_debt_shares,
+        },
     },
+    validation::rules::{check_amount, check_custom_shares},
 };
 
 /// Edit shared debt page
@@ -24,6 +31,7 @@ pub fn SharedDebtsEdit() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let navigate = use_navigate();
     let on_logout = use_logout();
+    let currency = use_currency_symbol();
     let params = use_params_map();
 
     let group_id = Memo::new(move |_| {
@@ -57,17 +65,58 @@ pub fn SharedDebtsEdit() -> impl IntoView {
         async move { get_shared_debt_members(id).await }
     });
 
+    let debt_shares_resource = LocalResource::new(move || {
+        let id = debt_id.get();
+        async move { get_shared_debt_shares(id).await }
+    });
+
     let update_action = ServerAction::<UpdateSharedDebt>::new();
     let (name, set_name) = signal(String::new());
     let (amount, set_amount) = signal(String::new());
-    let (selected_members, set_selected_members) = signal(Vec::<i64>::new());
+    let (category, set_category) = signal(String::new());
+    let (due_date, set_due_date) = signal(String::new());
+    let (incurred_on, set_incurred_on) = signal(String::new());
+    // Version of the debt the form was loaded from
+    let (version, set_version) = signal(0i64);
+    let selected_members = RwSignal::new(Vec::<i64>::new());
+    let (payer_id, set_payer_id) = signal(String::new());
+    let custom_split = RwSignal::new(false);
+    let shares = RwSignal::new(Vec::<(i64, String)>::new());
+    let paid_abroad = RwSignal::new(false);
+    let foreign_entry = ForeignAmountEntry::new();
     let (error_message, set_error_message) = signal(Option::<String>::None);
 
+    // Amount in the group's currency to split; converted when paid in another
+    // currency
+    let booked_amount = Signal::derive(move || {
+        if paid_abroad.get() {
+            foreign_entry
+                .check()
+                .map(|original| original.converted().to_string())
+                .unwrap_or_default()
+        } else {
+            amount.get()
+        }
+    });
+    let foreign_check =
+        FieldCheck::new(move || paid_abroad.get().then(|| foreign_entry.check().err())?);
+    let shares_check = FieldCheck::new(move || {
+        if !custom_split.get() {
+            return None;
+        }
+        let total = check_amount(&booked_amount.get()).ok()?;
+        let entries = shares.with(|shares| share_entries(&selected_members.get(), shares));
+        check_custom_shares(total, &entries).err()
+    });
+    let has_errors =
+        Signal::derive(move || shares_check.is_invalid() || foreign_check.is_invalid());
+
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
+    let redirect_on_error = redirect_to_login.clone();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -76,13 +125,40 @@ pub fn SharedDebtsEdit() -> impl IntoView {
         if let Some(Ok(debt)) = debt_resource.get() {
             set_name.set(debt.name.clone());
             set_amount.set(debt.amount.to_string());
+            set_category.set(debt.category.map(|c| c.to_string()).unwrap_or_default());
+            set_due_date.set(debt.due_date.map(|d| d.to_string()).unwrap_or_default());
+            set_incurred_on.set(debt.incurred_on.to_string());
+            set_payer_id.set(debt.payer_id.to_string());
+            set_version.set(debt.version);
+            match &debt.original {
+                Some(original) => {
+                    foreign_entry.set(original);
+                    paid_abroad.set(true);
+                }
+                None => paid_abroad.set(false),
+            }
         }
     });
 
     // Effect to populate selected members
     Effect::new(move |_| {
         if let Some(Ok(member_ids)) = debt_members_resource.get() {
-            set_selected_members.set(member_ids);
+            selected_members.set(member_ids);
+        }
+    });
+
+    // Effect to populate custom shares, if the debt has them
+    Effect::new(move |_| {
+        if let Some(Ok(debt_shares)) = debt_shares_resource.get()
+            && debt_shares.iter().any(|share| share.is_custom)
+        {
+            custom_split.set(true);
+            shares.set(
+                debt_shares
+                    .into_iter()
+                    .map(|share| (share.user_id, share.share_amount.to_string()))
+                    .collect(),
+            );
         }
     });
 
@@ -94,7 +170,7 @@ pub fn SharedDebtsEdit() -> impl IntoView {
                     navigate(&format!("/groups/{}", group_id.get()), Default::default());
                 }
                 Err(e) => {
-                    set_error_message.set(Some(e.to_string()));
+                    set_error_message.set(handle_app_error(&e, &redirect_on_error));
                 }
             }
         }
@@ -107,11 +183,30 @@ pub fn SharedDebtsEdit() -> impl IntoView {
         update_action.dispatch(UpdateSharedDebt {
             debt_id: debt_id.get(),
             name: name.get(),
-            amount: amount.get(),
+            amount: booked_amount.get(),
             member_ids: selected_members.get(),
+            payer_id: payer_id.get().parse::<i64>().ok(),
+            custom_shares: custom_split
+                .get()
+                .then(|| shares.with(|shares| share_entries(&selected_members.get(), shares))),
+            category: Some(category.get()).filter(|category| !category.is_empty()),
+            due_date: Some(due_date.get()).filter(|due_date| !due_date.is_empty()),
+            version: version.get(),
+            original_amount: paid_abroad.get().then(|| foreign_entry.amount.get()),
+            original_currency: paid_abroad.get().then(|| foreign_entry.currency.get()),
+            exchange_rate: paid_abroad.get().then(|| foreign_entry.exchange_rate.get()),
+            incurred_on: Some(incurred_on.get()).filter(|incurred_on| !incurred_on.is_empty()),
         });
     };
 
+    // Load the debt again after someone else saved it
+    let on_reload = Callback::new(move |()| {
+        set_error_message.set(None);
+        debt_resource.refetch();
+        debt_members_resource.refetch();
+        debt_shares_resource.refetch();
+    });
+
     let gid = group_id.get_untracked();
 
     view! {
@@ -127,7 +222,7 @@ pub fn SharedDebtsEdit() -> impl IntoView {
                                         <Suspense fallback=move || view! { <div>"Loading..."</div> }>
                                             {move || {
                                                 match debt_resource.get() {
-                                                    Some(Ok(_debt)) if !_debt.is_creator => view! {
+                                                    Some(Ok(_debt)) if !_debt.can_manage => view! {
                                                         <div class="rounded-md bg-red-50 dark:bg-red-900/30 p-4">
                                                             <p class="text-sm text-red-700 dark:text-red-300">
                                                                 "You do not have permission to edit this debt."
@@ -150,9 +245,11 @@ pub fn SharedDebtsEdit() -> impl IntoView {
 
                                                         <PageHeader title="Edit Debt".to_string() />
 
+                                                        <DuplicateNotice />
+
                                                         <FormCard>
                                                             <form on:submit=on_submit class="space-y-6">
-                                                                <ErrorAlert message=error_message />
+                                                                <EditErrorAlert message=error_message on_reload=on_reload />
 
                                                                 <FormField label="Name" for_id="name">
                                                                     <input
@@ -165,48 +262,107 @@ pub fn SharedDebtsEdit() -> impl IntoView {
                                                                     />
                                                                 </FormField>
 
-                                                                <FormField label="Amount (€)" for_id="amount">
-                                                                    <FormNumberInput
-                                                                        id="amount"
-                                                                        min="0.01"
-                                                                        step="0.01"
-                                                                        required=true
-                                                                        value=Signal::derive(move || amount.get())
-                                                                        on_input=Callback::new(move |val| set_amount.set(val))
+                                                                {move || if paid_abroad.get() {
+                                                                    view! {
+                                                                        <FormField label="Amount paid" error=foreign_check.message()>
+                                                                            <ForeignAmountInputs entry=foreign_entry on_blur=foreign_check.on_blur() />
+                                                                        </FormField>
+                                                                    }.into_any()
+                                                                } else {
+                                                                    view! {
+                                                                        <FormField label=move || format!("Amount ({})", currency.get()) for_id="amount">
+                                                                            <FormNumberInput
+                                                                                id="amount"
+                                                                                min="0.01"
+                                                                                step="0.01"
+                                                                                required=true
+                                                                                value=Signal::derive(move || amount.get())
+                                                                                on_input=Callback::new(move |val| set_amount.set(val))
+                                                                            />
+                                                                        </FormField>
+                                                                    }.into_any()
+                                                                }}
+
+                                                                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                        prop:checked=move || paid_abroad.get()
+                                                                        on:change=move |ev| paid_abroad.set(event_target_checked(&ev))
+                                                                    />
+                                                                    "Paid in another currency"
+                                                                </label>
+
+                                                                <FormField label="Category" for_id="category">
+                                                                    <CategorySelect
+                                                                        id="category"
+                                                                        value=Signal::derive(move || category.get())
+                                                                        on_change=Callback::new(move |val| set_category.set(val))
+                                                                    />
+                                                                </FormField>
+
+                                                                <FormField
+                                                                    label="Date"
+                                                                    for_id="incurred_on"
+                                                                    helper_text="When the expense happened."
+                                                                >
+                                                                    <FormDateInput
+                                                                        id="incurred_on"
+                                                                        value=Signal::derive(move || incurred_on.get())
+                                                                        on_input=Callback::new(move |val| set_incurred_on.set(val))
                                                                     />
                                                                 </FormField>
 
+                                                                <FormField
+                                                                    label="Due date"
+                                                                    for_id="due_date"
+                                                                    helper_text="Optional deadline for paying the debt back."
+                                                                >
+                                                                    <FormDateInput
+                                                                        id="due_date"
+                                                                        value=Signal::derive(move || due_date.get())
+                                                                        on_input=Callback::new(move |val| set_due_date.set(val))
+                                                                    />
+                                                                </FormField>
+
+                                                                <FormField label="Paid by" for_id="payer_id">
+                                                                    <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
+                                                                        {move || {
+                                                                            members_resource.get().and_then(Result::ok).map(|members| {
+                                                                                let current_payer = payer_id.get_untracked();
+                                                                                view! {
+                                                                                    <FormSelect
+                                                                                        id="payer_id"
+                                                                                        required=true
+                                                                                        value=Signal::derive(move || payer_id.get())
+                                                                                        on_change=Callback::new(move |val| set_payer_id.set(val))
+                                                                                    >
+                                                                                        {members
+                                                                                            .into_iter()
+                                                                                            .map(|member| {
+                                                                                                let value = member.id.to_string();
+                                                                                                let selected = value == current_payer;
+                                                                                                view! {
+                                                                                                    <option value=value selected=selected>
+                                                                                                        {member.username}
+                                                                                                    </option>
+                                                                                                }
+                                                                                            })
+                                                                                            .collect_view()}
+                                                                                    </FormSelect>
+                                                                                }
+                                                                            })
+                                                                        }}
+                                                                    </Suspense>
+                                                                </FormField>
+
                                                                 <FormField label="Split Between">
                                                                     <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
                                                                         {move || {
                                                                             match members_resource.get() {
                                                                                 Some(Ok(members)) => {
-                                                                                    let selected = selected_members.get();
                                                                                     view! {
-                                                                                        <div class="space-y-2 max-h-64 overflow-y-auto border border-gray-200 dark:border-gray-600 rounded-lg p-4">
-                                                                                            {members.into_iter().map(|member| {
-                                                                                                let member_id = member.id;
-                                                                                                let is_checked = selected.contains(&member_id);
-                                                                                                view! {
-                                                                                                    <MemberCheckboxItem
-                                                                                                        member_id=member_id
-                                                                                                        username=member.username
-                                                                                                        is_checked=is_checked
-                                                                                                        on_change=Callback::new(move |checked| {
-                                                                                                            set_selected_members.update(|members| {
-                                                                                                                if checked {
-                                                                                                                    if !members.contains(&member_id) {
-                                                                                                                        members.push(member_id);
-                                                                                                                    }
-                                                                                                                } else {
-                                                                                                                    members.retain(|&id| id != member_id);
-                                                                                                                }
-                                                                                                            });
-                                                                                                        })
-                                                                                                    />
-                                                                                                }
-                                                                                            }).collect_view()}
-                                                                                        </div>
+                                                                                        <MemberPicker members=members selected=selected_members />
                                                                                     }.into_any()
                                                                                 },
                                                                                 Some(Err(e)) => view! {
@@ -218,13 +374,46 @@ pub fn SharedDebtsEdit() -> impl IntoView {
                                                                     </Suspense>
                                                                 </FormField>
 
+                                                                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                                                                    <input
+                                                                        type="checkbox"
+                                                                        class="w-4 h-4 text-indigo-600 border-gray-300 rounded focus:ring-indigo-500"
+                                                                        prop:checked=move || custom_split.get()
+                                                                        on:change=move |ev| custom_split.set(event_target_checked(&ev))
+                                                                    />
+                                                                    "Split by custom amounts"
+                                                                </label>
+
+                                                                {move || custom_split.get().then(|| view! {
+                                                                    <FormField
+                                                                        label=move || format!("Shares ({} or %)", currency.get())
+                                                                        helper_text="Members left empty split the rest equally."
+                                                                        error=shares_check.message()
+                                                                    >
+                                                                        {move || members_resource.get().and_then(Result::ok).map(|members| view! {
+                                                                            <div on:focusout=move |_| shares_check.touch()>
+                                                                                <ShareInputs
+                                                                                    members=members
+                                                                                    selected=selected_members
+                                                                                    shares=shares
+                                                                                    total=booked_amount
+                                                                                />
+                                                                            </div>
+                                                                        })}
+                                                                    </FormField>
+                                                                })}
+
                                                                 <FormActions
                                                                     submit_text="Update Debt"
                                                                     loading_text="Updating..."
                                                                     loading=Signal::derive(move || update_action.pending().get())
+                                                                    disabled=has_errors
                                                                     cancel_href=format!("/groups/{}", gid)
                                                                 />
                                                             </form>
+                                                            <div class="mt-6 border-t border-gray-200 dark:border-gray-700 pt-4">
+                                                                <SharedDebtHistory debt_id=debt_id.get_untracked() />
+                                                            </div>
                                                         </FormCard>
                                                     }.into_any(),
                                                     Some(Err(e)) => view! {
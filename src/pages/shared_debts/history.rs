@@ -0,0 +1,99 @@
+use leptos::prelude::*;
+
+use crate::{
+    components::use_currency_symbol,
+    features::shared_debts::{
+        handlers::{MAX_HISTORY_PAGE_SIZE, get_shared_debt_history},
+        models::SharedDebtRevision,
+    },
+};
+
+/// Collapsible edit history of a shared debt, loaded on first expand
+#[must_use]
+#[component]
+pub fn SharedDebtHistory(debt_id: i64) -> impl IntoView {
+    let open = RwSignal::new(false);
+    let revisions = RwSignal::new(Vec::<SharedDebtRevision>::new());
+    let has_more = RwSignal::new(true);
+    let (error, set_error) = signal(None::<String>);
+    let currency = use_currency_symbol();
+
+    let load_page = Action::new(move |offset: &i64| {
+        let offset = *offset;
+        async move { get_shared_debt_history(debt_id, MAX_HISTORY_PAGE_SIZE, offset).await }
+    });
+
+    Effect::new(move |_| match load_page.value().get() {
+        Some(Ok(page)) => {
+            has_more.set(page.len() as i64 == MAX_HISTORY_PAGE_SIZE);
+            revisions.update(|all| all.extend(page));
+        }
+        Some(Err(e)) => set_error.set(Some(e.to_string())),
+        None => {}
+    });
+
+    let on_toggle = move |_| {
+        let now_open = !open.get();
+        open.set(now_open);
+        if now_open && revisions.with(Vec::is_empty) && load_page.value().get().is_none() {
+            load_page.dispatch(0);
+        }
+    };
+
+    view! {
+        <div class="mt-3">
+            <button
+                type="button"
+                on:click=on_toggle
+                class="text-xs font-medium text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300"
+            >
+                {move || if open.get() { "Hide history" } else { "History" }}
+            </button>
+            <Show when=move || open.get()>
+                <div class="mt-2 space-y-1">
+                    {move || error.get().map(|msg| view! {
+                        <p class="text-xs text-red-500">{msg}</p>
+                    })}
+                    {move || {
+                        let list = revisions.get();
+                        if list.is_empty() && !load_page.pending().get() && error.get().is_none() {
+                            return view! {
+                                <p class="text-xs text-gray-500 dark:text-gray-400">"No edits yet."</p>
+                            }.into_any();
+                        }
+                        view! {
+                            <ul class="space-y-1">
+                                {list.into_iter().map(|revision| {
+                                    let editor = revision
+                                        .editor_username
+                                        .clone()
+                                        .unwrap_or_else(|| "A former member".to_string());
+                                    let changes = revision.changes(currency.get()).join(", ");
+                                    view! {
+                                        <li class="text-xs text-gray-600 dark:text-gray-300">
+                                            {editor} " " {changes} " on " {revision.created_at.date().to_string()}
+                                        </li>
+                                    }
+                                }).collect_view()}
+                            </ul>
+                        }.into_any()
+                    }}
+                    {move || load_page.pending().get().then(|| view! {
+                        <p class="text-xs text-gray-500 dark:text-gray-400">"Loading..."</p>
+                    })}
+                    {move || (has_more.get() && !revisions.with(Vec::is_empty) && !load_page.pending().get()).then(|| view! {
+                        <button
+                            type="button"
+                            on:click=move |_| {
+                                load_page.dispatch(revisions.with(Vec::len) as i64);
+                            }
+                            class="text-xs font-medium text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300"
+                        >
+                            "Load more"
+                        </button>
+                    })}
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -1,5 +1,9 @@
 mod create;
+mod duplicate;
 mod edit;
+mod history;
 
 pub use create::SharedDebtsCreate;
+pub use duplicate::{DuplicateNotice, duplicate_edit_path};
 pub use edit::SharedDebtsEdit;
+pub use history::SharedDebtHistory;
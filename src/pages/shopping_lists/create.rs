@@ -10,9 +10,12 @@ use crate::{
         PageHeader,
     },
     features::{
-        auth::{UserSession, use_logout},
+        auth::{UserSession, use_logout, use_redirect_to_login},
         groups::handlers::get_group,
-        shopping_lists::CreateShoppingList,
+        shopping_lists::{
+            CreateListFromTemplate, CreateShoppingList, DeleteShoppingListTemplate,
+            get_shopping_list_templates,
+        },
     },
 };
 
@@ -39,32 +42,56 @@ pub fn ShoppingListCreate() -> impl IntoView {
         async move { get_group(id).await }
     });
 
+    let templates_resource = LocalResource::new(move || {
+        let id = group_id.get();
+        async move { get_shopping_list_templates(id).await }
+    });
+
     let create_action = ServerAction::<CreateShoppingList>::new();
+    let from_template_action = ServerAction::<CreateListFromTemplate>::new();
+    let delete_template_action = ServerAction::<DeleteShoppingListTemplate>::new();
     let (name, set_name) = signal(String::new());
+    let selected_template = RwSignal::new(None::<i64>);
     let (error_message, set_error_message) = signal(Option::<String>::None);
 
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
+        }
+    });
+
+    Effect::new(move |_| match delete_template_action.value().get() {
+        Some(Ok(())) => {
+            selected_template.set(None);
+            templates_resource.refetch();
         }
+        Some(Err(e)) => set_error_message.set(Some(e.to_string())),
+        None => {}
     });
 
-    // Effect to handle submission result
+    // Handle submission results, whether created from scratch or from a template
+    let handle_result = move |result: Result<i64, ServerFnError>| match result {
+        Ok(list_id) => {
+            navigate(
+                &format!("/groups/{}/shopping-lists/{}", group_id.get(), list_id),
+                Default::default(),
+            );
+        }
+        Err(e) => {
+            set_error_message.set(Some(e.to_string()));
+        }
+    };
+    let handle_template_result = handle_result.clone();
     Effect::new(move |_| {
         if let Some(result) = create_action.value().get() {
-            match result {
-                Ok(list_id) => {
-                    navigate(
-                        &format!("/groups/{}/shopping-lists/{}", group_id.get(), list_id),
-                        Default::default(),
-                    );
-                }
-                Err(e) => {
-                    set_error_message.set(Some(e.to_string()));
-                }
-            }
+            handle_result(result);
+        }
+    });
+    Effect::new(move |_| {
+        if let Some(result) = from_template_action.value().get() {
+            handle_template_result(result);
         }
     });
 
@@ -72,10 +99,33 @@ pub fn ShoppingListCreate() -> impl IntoView {
         ev.prevent_default();
         set_error_message.set(None);
 
-        create_action.dispatch(CreateShoppingList {
-            group_id: group_id.get(),
-            name: name.get(),
-        });
+        match selected_template.get() {
+            Some(template_id) => {
+                from_template_action.dispatch(CreateListFromTemplate {
+                    group_id: group_id.get(),
+                    template_id,
+                    name: name.get(),
+                });
+            }
+            None => {
+                create_action.dispatch(CreateShoppingList {
+                    group_id: group_id.get(),
+                    name: name.get(),
+                });
+            }
+        }
+    };
+
+    let on_delete_template = move |_| {
+        let Some(template_id) = selected_template.get() else {
+            return;
+        };
+        let confirmed = window()
+            .confirm_with_message("Delete this template? Lists created from it are kept.")
+            .unwrap_or(false);
+        if confirmed {
+            delete_template_action.dispatch(DeleteShoppingListTemplate { template_id });
+        }
     };
 
     let gid = group_id.get_untracked();
@@ -120,10 +170,62 @@ pub fn ShoppingListCreate() -> impl IntoView {
                                                                     />
                                                                 </FormField>
 
+                                                                {move || {
+                                                                    let templates = templates_resource
+                                                                        .get()
+                                                                        .and_then(Result::ok)
+                                                                        .unwrap_or_default();
+                                                                    (!templates.is_empty()).then(|| {
+                                                                        let can_delete_selected = {
+                                                                            let templates = templates.clone();
+                                                                            move || {
+                                                                                selected_template.get().is_some_and(|id| {
+                                                                                    templates.iter().any(|t| t.id == id && t.can_delete)
+                                                                                })
+                                                                            }
+                                                                        };
+                                                                        view! {
+                                                                            <FormField
+                                                                                label="Start from template"
+                                                                                for_id="template"
+                                                                                helper_text="The new list gets a copy of the template's items"
+                                                                            >
+                                                                                <div class="flex gap-2">
+                                                                                    <select
+                                                                                        id="template"
+                                                                                        class="w-full px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white"
+                                                                                        on:change=move |ev| selected_template.set(event_target_value(&ev).parse::<i64>().ok())
+                                                                                    >
+                                                                                        <option value="" selected=move || selected_template.get().is_none()>"Empty list"</option>
+                                                                                        {templates.into_iter().map(|template| {
+                                                                                            let id = template.id;
+                                                                                            view! {
+                                                                                                <option value=id.to_string() selected=move || selected_template.get() == Some(id)>
+                                                                                                    {format!("{} ({} items, by {})", template.name, template.item_count, template.creator_username)}
+                                                                                                </option>
+                                                                                            }
+                                                                                        }).collect_view()}
+                                                                                    </select>
+                                                                                    <Show when=can_delete_selected>
+                                                                                        <button
+                                                                                            type="button"
+                                                                                            on:click=on_delete_template
+                                                                                            disabled=move || delete_template_action.pending().get()
+                                                                                            class="px-3 py-2 text-sm text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/20 rounded-lg font-medium whitespace-nowrap disabled:opacity-50"
+                                                                                        >
+                                                                                            "Delete template"
+                                                                                        </button>
+                                                                                    </Show>
+                                                                                </div>
+                                                                            </FormField>
+                                                                        }
+                                                                    })
+                                                                }}
+
                                                                 <FormActions
                                                                     submit_text="Create List"
                                                                     loading_text="Creating..."
-                                                                    loading=Signal::derive(move || create_action.pending().get())
+                                                                    loading=Signal::derive(move || create_action.pending().get() || from_template_action.pending().get())
                                                                     cancel_href=format!("/groups/{}", gid)
                                                                 />
                                                             </form>
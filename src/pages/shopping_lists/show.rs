@@ -1,19 +1,42 @@
-use leptos::prelude::{ServerFnError, *};
+use std::collections::{HashMap, HashSet};
+
+use leptos::{
+    prelude::{ServerFnError, *},
+    task::spawn_local,
+};
 use leptos_router::{components::A, hooks::use_params_map};
 
+#[cfg(feature = "hydrate")]
+use crate::features::shopping_lists::{
+    offline_queue,
+    utils::{load_collapsed_categories, save_collapsed_categories},
+};
 use crate::{
-    components::{InputLabel, PrimaryButton, TextInput},
-    features::shopping_lists::{
-        AddShoppingListItem, DeleteShoppingList, DeleteShoppingListItem, ShoppingListActivity,
-        ShoppingListItem, ToggleShoppingListItem, get_shopping_list, get_shopping_list_activity,
-        get_shopping_list_items,
+    components::{ErrorAlert, ErrorCard, InputLabel, PrimaryButton, SuggestInput, TextInput},
+    error::AppError,
+    features::{
+        auth::UserSession,
+        groups::{GroupMemberInfo, handlers::get_group_members},
+        shopping_lists::{
+            AddShoppingListItem, AddShoppingListItems, ArchiveShoppingList, AssignShoppingListItem,
+            DeleteShoppingList, DeleteShoppingListItem, MergeShoppingListItems, QuantityUnit,
+            ReorderShoppingListItem, SaveListAsTemplate, SetCategoryOrder, ShoppingListActivity,
+            ShoppingListItem, ToggleShoppingListItem, UnarchiveShoppingList,
+            UpdateShoppingListItem, get_category_order, get_list_categories, get_shopping_list,
+            get_shopping_list_activity, get_shopping_list_items,
+            offline_queue::{OfflineQueue, QueuedChange, is_network_error},
+            toggle_shopping_list_item,
+            utils::{group_items_by_category, merge_targets, parse_item_lines},
+        },
     },
+    validation::rules::{MAX_NAME_LENGTH, check_name},
 };
 
 #[component]
 pub fn ShoppingListShow() -> impl IntoView {
     let params = use_params_map();
-    #[cfg(feature = "hydrate")]
+    let user_resource =
+        expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let group_id = move || {
         params
             .read()
@@ -47,6 +70,50 @@ pub fn ShoppingListShow() -> impl IntoView {
         }
     });
 
+    let category_order_resource = LocalResource::new(move || {
+        let id = list_id();
+        async move {
+            match id {
+                Some(id) => get_category_order(id).await,
+                None => Err(ServerFnError::new("Missing list_id")),
+            }
+        }
+    });
+
+    // Categories used before in the group, suggested while adding items
+    let categories_resource = LocalResource::new(move || {
+        let id = list_id();
+        async move {
+            match id {
+                Some(id) => get_list_categories(id).await,
+                None => Err(ServerFnError::new("Missing list_id")),
+            }
+        }
+    });
+
+    let members_resource = LocalResource::new(move || {
+        let id = group_id();
+        async move {
+            match id {
+                Some(id) => get_group_members(id).await,
+                None => Err(AppError::from(ServerFnError::new("Missing group_id"))),
+            }
+        }
+    });
+    let members = Signal::derive(move || {
+        members_resource
+            .get()
+            .and_then(Result::ok)
+            .unwrap_or_default()
+    });
+    let current_user_id = Signal::derive(move || {
+        user_resource
+            .get()
+            .and_then(Result::ok)
+            .flatten()
+            .map(|user| user.id)
+    });
+
     let activity_resource = LocalResource::new(move || {
         let id = list_id();
         async move {
@@ -68,11 +135,15 @@ pub fn ShoppingListShow() -> impl IntoView {
                 let url = format!("/api/groups/{}/shopping-lists/{}/events", gid, lid);
 
                 if let Ok(es) = EventSource::new(&url) {
+                    let list_resource_clone = list_resource;
                     let items_resource_clone = items_resource;
                     let activity_resource_clone = activity_resource;
+                    let category_order_resource_clone = category_order_resource;
 
                     let on_message = Closure::wrap(Box::new(move |_: MessageEvent| {
+                        list_resource_clone.refetch();
                         items_resource_clone.refetch();
+                        category_order_resource_clone.refetch();
                         activity_resource_clone.refetch();
                     })
                         as Box<dyn FnMut(MessageEvent)>);
@@ -88,42 +159,266 @@ pub fn ShoppingListShow() -> impl IntoView {
     }
 
     let (show_completed, set_show_completed) = signal(true);
+    let (only_assigned_to_me, set_only_assigned_to_me) = signal(false);
+    // Keyed by category name ("" for uncategorized) so it survives refetches
+    let collapsed_categories = RwSignal::new(HashSet::<String>::new());
+    // Collapsed sections are remembered per list in this browser: load them
+    // when the list changes and save every change after that
+    #[cfg(feature = "hydrate")]
+    Effect::new(move |loaded_list: Option<Option<i64>>| {
+        let lid = list_id();
+        match lid {
+            Some(lid) if loaded_list == Some(Some(lid)) => {
+                collapsed_categories.with(|collapsed| save_collapsed_categories(lid, collapsed))
+            }
+            Some(lid) => {
+                collapsed_categories.track();
+                collapsed_categories.set(load_collapsed_categories(lid));
+            }
+            None => {}
+        }
+        lid
+    });
     let item_name = RwSignal::new(String::new());
     let item_quantity = RwSignal::new(String::new());
+    // Abbreviation of a common unit, "other" for a custom one, or "" for none
+    let item_unit = RwSignal::new(String::new());
+    let item_custom_unit = RwSignal::new(String::new());
+    let (add_item_error, set_add_item_error) = signal(None::<String>);
     let item_category = RwSignal::new(String::new());
+    let item_name_input = NodeRef::<leptos::html::Input>::new();
     let (show_delete_modal, set_show_delete_modal) = signal(false);
+    let show_template_modal = RwSignal::new(false);
+    let paste_mode = RwSignal::new(false);
+    let paste_text = RwSignal::new(String::new());
+    let (paste_error, set_paste_error) = signal(None::<String>);
 
     let add_item_action = ServerAction::<AddShoppingListItem>::new();
-    let toggle_item_action = ServerAction::<ToggleShoppingListItem>::new();
+    let add_items_action = ServerAction::<AddShoppingListItems>::new();
+    let assign_item_action = ServerAction::<AssignShoppingListItem>::new();
     let delete_item_action = ServerAction::<DeleteShoppingListItem>::new();
+    let merge_item_action = ServerAction::<MergeShoppingListItems>::new();
+    let reorder_item_action = ServerAction::<ReorderShoppingListItem>::new();
     let delete_list_action = ServerAction::<DeleteShoppingList>::new();
+    let archive_list_action = ServerAction::<ArchiveShoppingList>::new();
+    let unarchive_list_action = ServerAction::<UnarchiveShoppingList>::new();
+    let set_category_order_action = ServerAction::<SetCategoryOrder>::new();
+
+    Effect::new(move |_| {
+        if let Some(Ok(_)) = set_category_order_action.value().get() {
+            category_order_resource.refetch();
+        }
+    });
+
+    Effect::new(move |_| {
+        let archived = archive_list_action.value().get();
+        let unarchived = unarchive_list_action.value().get();
+        if matches!(archived, Some(Ok(_))) || matches!(unarchived, Some(Ok(_))) {
+            list_resource.refetch();
+        }
+    });
+
+    // Changes made while offline, shown on top of the fetched items until
+    // they are sent
+    let queued_changes = RwSignal::new(OfflineQueue::default());
+    // Changes the server refused when the queue was sent
+    let offline_notices = RwSignal::new(Vec::<String>::new());
+    let queue_change = move |change: QueuedChange| {
+        queued_changes.update(|queue| queue.push(change));
+        #[cfg(feature = "hydrate")]
+        queued_changes.with_untracked(offline_queue::save);
+    };
+
+    #[cfg(feature = "hydrate")]
+    {
+        let replay_queue = move || {
+            let queue = queued_changes.get_untracked();
+            if queue.is_empty() {
+                return;
+            }
+            spawn_local(async move {
+                let outcome = offline_queue::replay(queue, offline_queue::send_change).await;
+                queued_changes.set(outcome.remaining);
+                queued_changes.with_untracked(offline_queue::save);
+                if !outcome.dropped.is_empty() {
+                    offline_notices.update(|notices| notices.extend(outcome.dropped));
+                }
+                items_resource.refetch();
+            });
+        };
+
+        // Send anything left over from an earlier visit
+        Effect::new(move |_| {
+            queued_changes.set(offline_queue::load());
+            replay_queue();
+        });
+        let online_listener = window_event_listener_untyped("online", move |_| replay_queue());
+        on_cleanup(move || online_listener.remove());
+    }
+
+    let toggle_item = Callback::new(move |input: ToggleShoppingListItem| {
+        spawn_local(async move {
+            let result =
+                toggle_shopping_list_item(input.item_id, input.completed, input.version).await;
+            match result {
+                Ok(_) => {}
+                Err(e) if is_network_error(&e) => queue_change(QueuedChange::Toggle {
+                    item_id: input.item_id,
+                    completed: input.completed,
+                    version: input.version,
+                }),
+                // A failed toggle means our copy of the item is stale (someone
+                // else changed it first), so refetch instead of retrying blindly
+                Err(_) => items_resource.refetch(),
+            }
+        });
+    });
+
+    Effect::new(move |_| {
+        if let Some(Err(_)) = assign_item_action.value().get() {
+            items_resource.refetch();
+        }
+    });
+
+    Effect::new(move |_| {
+        if let Some(Err(_)) = merge_item_action.value().get() {
+            items_resource.refetch();
+        }
+    });
 
-    // Reset form after successful add
     Effect::new(move |_| {
-        if let Some(Ok(_)) = add_item_action.value().get() {
-            item_name.set(String::new());
-            item_quantity.set(String::new());
-            item_category.set(String::new());
+        if let Some(Err(_)) = reorder_item_action.value().get() {
+            items_resource.refetch();
         }
     });
 
+    // Item being added, kept so it can be queued if the network is down. The
+    // add button is disabled while one is pending, so there is only ever one.
+    let last_added_item = StoredValue::new(None::<AddShoppingListItem>);
+    let reset_item_form = move || {
+        item_name.set(String::new());
+        item_quantity.set(String::new());
+        item_unit.set(String::new());
+        item_custom_unit.set(String::new());
+        item_category.set(String::new());
+        set_add_item_error.set(None);
+        // Ready for the next item without reaching for the mouse
+        if let Some(input) = item_name_input.get_untracked() {
+            let _ = input.focus();
+        }
+    };
+
+    // Reset form after successful add
+    Effect::new(move |_| match add_item_action.value().get() {
+        Some(Ok(_)) => {
+            reset_item_form();
+            categories_resource.refetch();
+        }
+        Some(Err(e)) if is_network_error(&e) => {
+            if let Some(input) = last_added_item.get_value() {
+                queue_change(QueuedChange::Add {
+                    list_id: input.list_id,
+                    name: input.name,
+                    quantity_value: input.quantity_value,
+                    quantity_unit: input.quantity_unit,
+                    category: input.category,
+                });
+                reset_item_form();
+            }
+        }
+        Some(Err(e)) => set_add_item_error.set(Some(e.to_string())),
+        None => {}
+    });
+
+    // Reset paste box after a successful batch add
+    Effect::new(move |_| match add_items_action.value().get() {
+        Some(Ok(_)) => {
+            paste_text.set(String::new());
+            set_paste_error.set(None);
+        }
+        Some(Err(e)) => set_paste_error.set(Some(e.to_string())),
+        None => {}
+    });
+
+    let parsed_items = Memo::new(move |_| parse_item_lines(&paste_text.get()));
+
+    let on_add_items = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let Some(lid) = list_id() else {
+            return;
+        };
+
+        let parsed = parsed_items.get();
+        if let Some(err) = parsed
+            .iter()
+            .find_map(|(line, item)| {
+                check_name(&item.name, 1, MAX_NAME_LENGTH, "Name")
+                    .err()
+                    .map(|e| (line, e))
+            })
+            .map(|(line, e)| format!("Line {}: {}", line, e))
+        {
+            set_paste_error.set(Some(err));
+            return;
+        }
+
+        set_paste_error.set(None);
+        add_items_action.dispatch(AddShoppingListItems {
+            list_id: lid,
+            items: parsed.into_iter().map(|(_, item)| item).collect(),
+        });
+    };
+
     let on_add_item = move |ev: leptos::ev::SubmitEvent| {
         ev.prevent_default();
         if let Some(lid) = list_id() {
             let qty = item_quantity.get();
+            let unit = match item_unit.get().as_str() {
+                "other" => item_custom_unit.get(),
+                unit => unit.to_string(),
+            };
             let cat = item_category.get();
-            add_item_action.dispatch(AddShoppingListItem {
+            let input = AddShoppingListItem {
                 list_id: lid,
                 name: item_name.get(),
-                quantity: if qty.is_empty() { None } else { Some(qty) },
+                quantity_value: if qty.is_empty() { None } else { Some(qty) },
+                quantity_unit: if unit.is_empty() { None } else { Some(unit) },
                 category: if cat.is_empty() { None } else { Some(cat) },
-            });
+            };
+            last_added_item.set_value(Some(input.clone()));
+            add_item_action.dispatch(input);
         }
     };
 
     view! {
         <div class="min-h-screen bg-gray-100 dark:bg-gray-900">
             <div class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8 py-8">
+                <Show when=move || queued_changes.with(|queue| !queue.is_empty())>
+                    <div class="mb-4 rounded-lg bg-yellow-50 dark:bg-yellow-900/30 border border-yellow-200 dark:border-yellow-800 px-4 py-3 text-sm text-yellow-800 dark:text-yellow-300">
+                        "You're offline. " {move || queued_changes.with(OfflineQueue::len)}
+                        " change(s) will be sent when the connection is back."
+                    </div>
+                </Show>
+                // Notices for offline changes the server refused
+                <div class="fixed bottom-4 right-4 z-50 space-y-2 max-w-sm">
+                    {move || offline_notices.get().into_iter().enumerate().map(|(index, notice)| view! {
+                        <div class="flex items-start gap-3 rounded-lg bg-gray-900 text-white shadow-lg px-4 py-3 text-sm">
+                            <p class="flex-1">{notice}</p>
+                            <button
+                                type="button"
+                                aria-label="Dismiss"
+                                on:click=move |_| offline_notices.update(|notices| {
+                                    notices.remove(index);
+                                })
+                                class="text-gray-400 hover:text-white"
+                            >
+                                <svg class="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                    <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M6 18L18 6M6 6l12 12"/>
+                                </svg>
+                            </button>
+                        </div>
+                    }).collect_view()}
+                </div>
                 <Suspense fallback=move || view! {
                     <div class="flex justify-center items-center py-12">
                         <div class="animate-spin rounded-full h-12 w-12 border-b-2 border-indigo-600"></div>
@@ -134,6 +429,7 @@ pub fn ShoppingListShow() -> impl IntoView {
                             Some(Ok(list)) => {
                                 let gid = list.group_id;
                                 let list_name = list.name.clone();
+                                let is_archived = list.is_archived;
 
                                 view! {
                                     <div>
@@ -150,6 +446,7 @@ pub fn ShoppingListShow() -> impl IntoView {
                                                     <p class="text-sm text-gray-600 dark:text-gray-400 mt-1">"Shopping List Details"</p>
                                                 </div>
                                                 <div class="flex gap-2">
+                                                    <Show when=move || !is_archived>
                                                     <A
                                                         href=format!("/groups/{}/shopping-lists/{}/edit", gid, list.id)
                                                         attr:class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors inline-flex items-center"
@@ -159,6 +456,34 @@ pub fn ShoppingListShow() -> impl IntoView {
                                                         </svg>
                                                         "Edit"
                                                     </A>
+                                                    </Show>
+                                                    <button
+                                                        on:click=move |_| {
+                                                            if let Some(lid) = list_id() {
+                                                                if is_archived {
+                                                                    unarchive_list_action.dispatch(UnarchiveShoppingList { list_id: lid });
+                                                                } else {
+                                                                    archive_list_action.dispatch(ArchiveShoppingList { list_id: lid });
+                                                                }
+                                                            }
+                                                        }
+                                                        class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors inline-flex items-center"
+                                                    >
+                                                        <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M5 8h14M5 8a2 2 0 110-4h14a2 2 0 110 4M5 8v10a2 2 0 002 2h10a2 2 0 002-2V8m-9 4h4"/>
+                                                        </svg>
+                                                        {if is_archived { "Unarchive" } else { "Archive" }}
+                                                    </button>
+                                                    <button
+                                                        on:click=move |_| show_template_modal.set(true)
+                                                        class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors inline-flex items-center"
+                                                    >
+                                                        <svg class="w-4 h-4 mr-2" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M8 7v8a2 2 0 002 2h6M8 7V5a2 2 0 012-2h4.586a1 1 0 01.707.293l4.414 4.414a1 1 0 01.293.707V15a2 2 0 01-2 2h-2M8 7H6a2 2 0 00-2 2v10a2 2 0 002 2h8a2 2 0 002-2v-2"/>
+                                                        </svg>
+                                                        "Save as template"
+                                                    </button>
+                                                    <Show when=move || is_archived>
                                                     <button
                                                         on:click=move |_| set_show_delete_modal.set(true)
                                                         class="px-4 py-2 bg-red-600 hover:bg-red-700 text-white rounded-lg font-medium transition-colors inline-flex items-center"
@@ -168,64 +493,173 @@ pub fn ShoppingListShow() -> impl IntoView {
                                                         </svg>
                                                         "Delete"
                                                     </button>
+                                                    </Show>
                                                 </div>
                                             </div>
                                         </div>
 
+                                        <Show when=move || is_archived>
+                                            <div class="mb-6 rounded-lg border border-amber-200 dark:border-amber-800 bg-amber-50 dark:bg-amber-900/30 p-4">
+                                                <p class="text-sm text-amber-800 dark:text-amber-300">
+                                                    "This list is archived. Its items are kept for reference, but no new items can be added. Unarchive it to keep shopping, or delete it for good."
+                                                </p>
+                                            </div>
+                                        </Show>
+
                                         <div class="grid grid-cols-1 lg:grid-cols-3 gap-8">
                                             <div class="lg:col-span-2">
                                                 <div class="bg-white dark:bg-gray-800 rounded-lg shadow p-6 mb-6">
-                                                    <h2 class="text-lg font-semibold text-gray-900 dark:text-white mb-4">"Add Item"</h2>
-                                                    <form on:submit=on_add_item class="space-y-4">
-                                                        <div>
-                                                            <InputLabel for_input="item_name">"Item Name"</InputLabel>
-                                                            <TextInput
-                                                                input_type="text"
-                                                                placeholder="Enter item name"
-                                                                required=true
-                                                                class="w-full mt-1"
-                                                                value=item_name
-                                                            />
-                                                        </div>
-                                                        <div class="grid grid-cols-2 gap-4">
+                                                    <div class="flex items-center justify-between mb-4">
+                                                        <h2 class="text-lg font-semibold text-gray-900 dark:text-white">
+                                                            {move || if paste_mode.get() { "Paste List" } else { "Add Item" }}
+                                                        </h2>
+                                                        <button
+                                                            type="button"
+                                                            on:click=move |_| paste_mode.update(|v| *v = !*v)
+                                                            class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
+                                                        >
+                                                            {move || if paste_mode.get() { "Add single item" } else { "Paste list" }}
+                                                        </button>
+                                                    </div>
+                                                    <fieldset disabled=is_archived>
+                                                        <Show when=move || paste_mode.get()>
+                                                        <form on:submit=on_add_items class="space-y-4">
                                                             <div>
-                                                                <InputLabel for_input="item_quantity">"Quantity (optional)"</InputLabel>
-                                                                <TextInput
-                                                                    input_type="text"
-                                                                    placeholder="2 kg"
-                                                                    class="w-full mt-1"
-                                                                    value=item_quantity
-                                                                />
+                                                                <InputLabel for_input="paste_items">"One item per line"</InputLabel>
+                                                                <textarea
+                                                                    id="paste_items"
+                                                                    rows="6"
+                                                                    placeholder="2kg potatoes\nmilk\n6 eggs"
+                                                                    class="w-full mt-1 px-3 py-2 border border-gray-300 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-300 focus:border-indigo-500 dark:focus:border-indigo-600 focus:ring-indigo-500 dark:focus:ring-indigo-600 rounded-md shadow-sm"
+                                                                    prop:value=move || paste_text.get()
+                                                                    on:input=move |ev| paste_text.set(event_target_value(&ev))
+                                                                ></textarea>
                                                             </div>
+                                                            {move || {
+                                                                let parsed = parsed_items.get();
+                                                                (!parsed.is_empty()).then(|| view! {
+                                                                    <ul class="divide-y divide-gray-200 dark:divide-gray-700 border border-gray-200 dark:border-gray-700 rounded-md max-h-48 overflow-y-auto">
+                                                                        {parsed.into_iter().map(|(line, item)| {
+                                                                            let quantity = item.quantity_label();
+                                                                            view! {
+                                                                                <li class="flex items-center px-3 py-2 text-sm">
+                                                                                    <span class="w-10 text-xs text-gray-400">{line}</span>
+                                                                                    <span class="flex-1 text-gray-900 dark:text-white">{item.name}</span>
+                                                                                    {quantity.map(|q| view! {
+                                                                                        <span class="text-gray-500 dark:text-gray-400">{q}</span>
+                                                                                    })}
+                                                                                </li>
+                                                                            }
+                                                                        }).collect_view()}
+                                                                    </ul>
+                                                                })
+                                                            }}
+                                                            <ErrorAlert message=paste_error />
+                                                            <PrimaryButton
+                                                                button_type="submit"
+                                                                disabled=Signal::derive(move || add_items_action.pending().get() || parsed_items.get().is_empty())
+                                                            >
+                                                                {move || if add_items_action.pending().get() {
+                                                                    "Adding...".to_string()
+                                                                } else {
+                                                                    format!("Add {} items", parsed_items.get().len())
+                                                                }}
+                                                            </PrimaryButton>
+                                                        </form>
+                                                        </Show>
+                                                        <Show when=move || !paste_mode.get()>
+                                                        <form on:submit=on_add_item class="space-y-4">
                                                             <div>
-                                                                <InputLabel for_input="item_category">"Category (optional)"</InputLabel>
+                                                                <InputLabel for_input="item_name">"Item Name"</InputLabel>
                                                                 <TextInput
                                                                     input_type="text"
-                                                                    placeholder="e.g. Produce"
+                                                                    placeholder="Enter item name"
+                                                                    required=true
                                                                     class="w-full mt-1"
-                                                                    value=item_category
+                                                                    node_ref=item_name_input
+                                                                    value=item_name
                                                                 />
                                                             </div>
-                                                        </div>
-                                                        <PrimaryButton
-                                                            button_type="submit"
-                                                            disabled=Signal::derive(move || add_item_action.pending().get())
-                                                        >
-                                                            {move || if add_item_action.pending().get() { "Adding..." } else { "Add Item" }}
-                                                        </PrimaryButton>
-                                                    </form>
+                                                            <div class="grid grid-cols-2 gap-4">
+                                                                <div>
+                                                                    <InputLabel for_input="item_quantity">"Quantity (optional)"</InputLabel>
+                                                                    <div class="flex gap-2 mt-1">
+                                                                        <input
+                                                                            id="item_quantity"
+                                                                            type="number"
+                                                                            min="0"
+                                                                            step="any"
+                                                                            placeholder="2"
+                                                                            class="w-full px-3 py-2 border border-gray-300 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-300 focus:border-indigo-500 dark:focus:border-indigo-600 focus:ring-indigo-500 dark:focus:ring-indigo-600 rounded-md shadow-sm"
+                                                                            prop:value=move || item_quantity.get()
+                                                                            on:input=move |ev| item_quantity.set(event_target_value(&ev))
+                                                                        />
+                                                                        <select
+                                                                            id="item_unit"
+                                                                            class="px-3 py-2 border border-gray-300 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-300 focus:border-indigo-500 dark:focus:border-indigo-600 focus:ring-indigo-500 dark:focus:ring-indigo-600 rounded-md shadow-sm"
+                                                                            prop:value=move || item_unit.get()
+                                                                            on:change=move |ev| item_unit.set(event_target_value(&ev))
+                                                                        >
+                                                                            <option value="">"no unit"</option>
+                                                                            {QuantityUnit::ALL.into_iter().map(|unit| view! {
+                                                                                <option value=unit.abbreviation().unwrap_or("other")>{unit.label()}</option>
+                                                                            }).collect_view()}
+                                                                        </select>
+                                                                    </div>
+                                                                    <Show when=move || item_unit.get() == "other">
+                                                                        <TextInput
+                                                                            input_type="text"
+                                                                            placeholder="e.g. bunch"
+                                                                            required=true
+                                                                            class="w-full mt-2"
+                                                                            value=item_custom_unit
+                                                                        />
+                                                                    </Show>
+                                                                </div>
+                                                                <div>
+                                                                    <InputLabel for_input="item_category">"Category (optional)"</InputLabel>
+                                                                    <SuggestInput
+                                                                        id="item_category"
+                                                                        placeholder="e.g. Produce"
+                                                                        class="mt-1"
+                                                                        value=item_category
+                                                                        suggestions=Signal::derive(move || {
+                                                                            categories_resource.get().and_then(Result::ok).unwrap_or_default()
+                                                                        })
+                                                                    />
+                                                                </div>
+                                                            </div>
+                                                            <ErrorAlert message=add_item_error />
+                                                            <PrimaryButton
+                                                                button_type="submit"
+                                                                disabled=Signal::derive(move || add_item_action.pending().get())
+                                                            >
+                                                                {move || if add_item_action.pending().get() { "Adding..." } else { "Add Item" }}
+                                                            </PrimaryButton>
+                                                        </form>
+                                                        </Show>
+                                                    </fieldset>
                                                 </div>
 
                                                 <div class="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700">
                                                     <div class="p-6 border-b border-gray-200 dark:border-gray-700">
                                                         <div class="flex items-center justify-between">
                                                             <h2 class="text-lg font-semibold text-gray-900 dark:text-white">"Items"</h2>
-                                                            <button
-                                                                on:click=move |_| set_show_completed.update(|v| *v = !*v)
-                                                                class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
-                                                            >
-                                                                {move || if show_completed.get() { "Hide completed" } else { "Show completed" }}
-                                                            </button>
+                                                            <div class="flex gap-4">
+                                                                <button
+                                                                    on:click=move |_| set_only_assigned_to_me.update(|v| *v = !*v)
+                                                                    aria-pressed=move || only_assigned_to_me.get().to_string()
+                                                                    class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
+                                                                >
+                                                                    {move || if only_assigned_to_me.get() { "Show all items" } else { "Assigned to me" }}
+                                                                </button>
+                                                                <button
+                                                                    on:click=move |_| set_show_completed.update(|v| *v = !*v)
+                                                                    class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
+                                                                >
+                                                                    {move || if show_completed.get() { "Hide completed" } else { "Show completed" }}
+                                                                </button>
+                                                            </div>
                                                         </div>
                                                     </div>
 
@@ -233,11 +667,21 @@ pub fn ShoppingListShow() -> impl IntoView {
                                                         {move || {
                                                             match items_resource.get() {
                                                                 Some(Ok(items)) => {
-                                                                    let filtered_items: Vec<_> = items.into_iter()
-                                                                        .filter(|item| show_completed.get() || !item.is_completed)
-                                                                        .collect();
-
-                                                                    if filtered_items.is_empty() {
+                                                                    let items = queued_changes.with(|queue| {
+                                                                        queue.apply(list_id().unwrap_or_default(), items)
+                                                                    });
+                                                                    let has_items = !items.is_empty();
+                                                                    let items: Vec<_> = if only_assigned_to_me.get() {
+                                                                        let me = current_user_id.get();
+                                                                        items.into_iter().filter(|item| me.is_some() && item.assigned_to == me).collect()
+                                                                    } else {
+                                                                        items
+                                                                    };
+                                                                    if has_items && items.is_empty() {
+                                                                        view! {
+                                                                            <p class="p-12 text-center text-gray-500 dark:text-gray-400">"No items are assigned to you."</p>
+                                                                        }.into_any()
+                                                                    } else if items.is_empty() {
                                                                         view! {
                                                                             <div class="p-12 text-center">
                                                                                 <div class="w-16 h-16 mx-auto mb-4 bg-gray-100 dark:bg-gray-700 rounded-full flex items-center justify-center">
@@ -249,12 +693,61 @@ pub fn ShoppingListShow() -> impl IntoView {
                                                                             </div>
                                                                         }.into_any()
                                                                     } else {
+                                                                        let order = category_order_resource
+                                                                            .get()
+                                                                            .and_then(Result::ok)
+                                                                            .unwrap_or_default();
+                                                                        let merge_targets = merge_targets(&items);
+                                                                        // Items arrive in list order, so the index is what reordering expects
+                                                                        let ranks: HashMap<i64, i64> = items
+                                                                            .iter()
+                                                                            .enumerate()
+                                                                            .map(|(rank, item)| (item.id, rank as i64))
+                                                                            .collect();
+                                                                        let groups = group_items_by_category(items, &order);
+                                                                        let displayed_order: Vec<String> = groups
+                                                                            .iter()
+                                                                            .filter_map(|(category, _)| category.clone())
+                                                                            .collect();
+                                                                        let named_count = displayed_order.len();
+                                                                        let displayed_order = StoredValue::new(displayed_order);
+                                                                        let move_category = move |index: usize, up: bool| {
+                                                                            let Some(lid) = list_id() else { return };
+                                                                            let mut categories = displayed_order.get_value();
+                                                                            let target = if up { index.checked_sub(1) } else { Some(index + 1) };
+                                                                            if let Some(target) = target.filter(|t| *t < categories.len()) {
+                                                                                categories.swap(index, target);
+                                                                                set_category_order_action.dispatch(SetCategoryOrder { list_id: lid, categories });
+                                                                            }
+                                                                        };
+
                                                                         view! {
-                                                                            <ul class="divide-y divide-gray-200 dark:divide-gray-700">
-                                                                                {filtered_items.into_iter().map(|item| {
-                                                                                    view! { <ItemRow item=item toggle_action=toggle_item_action delete_action=delete_item_action /> }
+                                                                            <div class="divide-y divide-gray-200 dark:divide-gray-700">
+                                                                                {groups.into_iter().enumerate().map(|(index, (category, items))| {
+                                                                                    let movable = category.is_some();
+                                                                                    let can_move_up = movable && index > 0;
+                                                                                    let can_move_down = movable && index + 1 < named_count;
+                                                                                    view! {
+                                                                                        <CategorySection
+                                                                                            category=category
+                                                                                            items=items
+                                                                                            show_completed=show_completed
+                                                                                            collapsed=collapsed_categories
+                                                                                            can_move_up=can_move_up
+                                                                                            can_move_down=can_move_down
+                                                                                            on_move=Callback::new(move |up: bool| move_category(index, up))
+                                                                                            on_toggle=toggle_item
+                                                                                            assign_action=assign_item_action
+                                                                                            merge_action=merge_item_action
+                                                                                            delete_action=delete_item_action
+                                                                                            members=members
+                                                                                            merge_targets=merge_targets.clone()
+                                                                                            reorder_action=reorder_item_action
+                                                                                            ranks=ranks.clone()
+                                                                                        />
+                                                                                    }
                                                                                 }).collect_view()}
-                                                                            </ul>
+                                                                            </div>
                                                                         }.into_any()
                                                                     }
                                                                 }
@@ -322,21 +815,21 @@ pub fn ShoppingListShow() -> impl IntoView {
                                             on_cancel=Callback::new(move |_| set_show_delete_modal.set(false))
                                             list_name=list_name.clone()
                                         />
+
+                                        <SaveTemplateModal
+                                            show=show_template_modal
+                                            list_id=list.id
+                                            list_name=list_name.clone()
+                                        />
                                     </div>
                                 }.into_any()
                             }
                             Some(Err(e)) => view! {
-                                <div class="bg-red-50 dark:bg-red-900/30 border border-red-200 dark:border-red-800 rounded-xl p-6">
-                                    <div class="flex items-start">
-                                        <svg class="w-5 h-5 text-red-600 dark:text-red-400 mt-0.5 mr-3" fill="none" stroke="currentColor" viewBox="0 0 24 24">
-                                            <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M12 8v4m0 4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z"/>
-                                        </svg>
-                                        <div>
-                                            <h3 class="text-sm font-medium text-red-800 dark:text-red-300">"Error loading shopping list"</h3>
-                                            <p class="mt-1 text-sm text-red-700 dark:text-red-400">{e.to_string()}</p>
-                                        </div>
-                                    </div>
-                                </div>
+                                <ErrorCard
+                                    error=AppError::from(e)
+                                    back_label="Back to group"
+                                    back_href=format!("/groups/{}", group_id().unwrap_or_default())
+                                />
                             }.into_any(),
                             None => view! {
                                 <div class="flex justify-center items-center py-12">
@@ -351,26 +844,270 @@ pub fn ShoppingListShow() -> impl IntoView {
     }
 }
 
+/// Collapsible block of items sharing a category
+#[component]
+#[allow(clippy::too_many_arguments)]
+fn CategorySection(
+    category: Option<String>,
+    items: Vec<ShoppingListItem>,
+    show_completed: ReadSignal<bool>,
+    collapsed: RwSignal<HashSet<String>>,
+    can_move_up: bool,
+    can_move_down: bool,
+    /// Called with `true` to move the category up, `false` to move it down
+    on_move: Callback<bool>,
+    on_toggle: Callback<ToggleShoppingListItem>,
+    assign_action: ServerAction<AssignShoppingListItem>,
+    merge_action: ServerAction<MergeShoppingListItems>,
+    delete_action: ServerAction<DeleteShoppingListItem>,
+    /// Group members items can be assigned to
+    members: Signal<Vec<GroupMemberInfo>>,
+    /// Duplicate items and the item each can be merged into
+    merge_targets: HashMap<i64, i64>,
+    reorder_action: ServerAction<ReorderShoppingListItem>,
+    /// Index of each item in the whole list's order
+    ranks: HashMap<i64, i64>,
+) -> impl IntoView {
+    let key = category.clone().unwrap_or_default();
+    let title = category.unwrap_or_else(|| "Uncategorized".to_string());
+    let total = items.len();
+    let completed = items.iter().filter(|item| item.is_completed).count();
+    let items = StoredValue::new(items);
+    let merge_targets = StoredValue::new(merge_targets);
+    let ranks = StoredValue::new(ranks);
+    let is_collapsed = {
+        let key = key.clone();
+        Memo::new(move |_| collapsed.with(|c| c.contains(&key)))
+    };
+    let toggle_collapsed = move |_| {
+        collapsed.update(|c| {
+            if !c.remove(&key) {
+                c.insert(key.clone());
+            }
+        })
+    };
+
+    view! {
+        <section>
+            <div class="flex items-center justify-between px-4 py-2 bg-gray-50 dark:bg-gray-900/40">
+                <button
+                    type="button"
+                    on:click=toggle_collapsed
+                    aria-expanded=move || (!is_collapsed.get()).to_string()
+                    class="flex items-center gap-2 text-sm font-semibold text-gray-700 dark:text-gray-300"
+                >
+                    <svg
+                        class=move || if is_collapsed.get() { "w-4 h-4 -rotate-90 transition-transform" } else { "w-4 h-4 transition-transform" }
+                        fill="none" stroke="currentColor" viewBox="0 0 24 24"
+                    >
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M19 9l-7 7-7-7"/>
+                    </svg>
+                    {title}
+                    <span class="font-normal text-gray-500 dark:text-gray-400">{format!("{}/{}", completed, total)}</span>
+                </button>
+                <div class="flex gap-1">
+                    <Show when=move || can_move_up>
+                        <button
+                            type="button"
+                            on:click=move |_| on_move.run(true)
+                            class="p-1 text-gray-500 hover:text-indigo-600 dark:text-gray-400 dark:hover:text-indigo-400"
+                            title="Move category up"
+                        >
+                            "↑"
+                        </button>
+                    </Show>
+                    <Show when=move || can_move_down>
+                        <button
+                            type="button"
+                            on:click=move |_| on_move.run(false)
+                            class="p-1 text-gray-500 hover:text-indigo-600 dark:text-gray-400 dark:hover:text-indigo-400"
+                            title="Move category down"
+                        >
+                            "↓"
+                        </button>
+                    </Show>
+                </div>
+            </div>
+            <Show when=move || !is_collapsed.get()>
+                {move || {
+                    let visible: Vec<_> = items
+                        .get_value()
+                        .into_iter()
+                        .filter(|item| show_completed.get() || !item.is_completed)
+                        .collect();
+                    if visible.is_empty() {
+                        view! {
+                            <p class="px-4 py-3 text-sm text-gray-500 dark:text-gray-400 italic">"All items completed"</p>
+                        }.into_any()
+                    } else {
+                        view! {
+                            <ul class="divide-y divide-gray-200 dark:divide-gray-700">
+                                {visible.iter().enumerate().map(|(index, item)| {
+                                    let merge_into = merge_targets.with_value(|t| t.get(&item.id).copied());
+                                    // Items only move past visible neighbours with the same
+                                    // completion state, since open items always come first
+                                    let neighbour_rank = |neighbour: Option<&ShoppingListItem>| {
+                                        neighbour
+                                            .filter(|n| n.is_completed == item.is_completed)
+                                            .and_then(|n| ranks.with_value(|r| r.get(&n.id).copied()))
+                                    };
+                                    let move_up_to = neighbour_rank(index.checked_sub(1).and_then(|i| visible.get(i)));
+                                    let move_down_to = neighbour_rank(visible.get(index + 1));
+                                    let item = item.clone();
+                                    view! {
+                                        <ItemRow
+                                            item=item
+                                            on_toggle=on_toggle
+                                            assign_action=assign_action
+                                            merge_action=merge_action
+                                            delete_action=delete_action
+                                            members=members
+                                            merge_into=merge_into
+                                            reorder_action=reorder_action
+                                            move_up_to=move_up_to
+                                            move_down_to=move_down_to
+                                        />
+                                    }
+                                }).collect_view()}
+                            </ul>
+                        }.into_any()
+                    }
+                }}
+            </Show>
+        </section>
+    }
+}
+
 #[component]
+#[allow(clippy::too_many_arguments)]
 fn ItemRow(
     item: ShoppingListItem,
-    toggle_action: ServerAction<ToggleShoppingListItem>,
+    on_toggle: Callback<ToggleShoppingListItem>,
+    assign_action: ServerAction<AssignShoppingListItem>,
+    merge_action: ServerAction<MergeShoppingListItems>,
     delete_action: ServerAction<DeleteShoppingListItem>,
+    members: Signal<Vec<GroupMemberInfo>>,
+    /// Earlier item with the same name and unit, if any
+    merge_into: Option<i64>,
+    reorder_action: ServerAction<ReorderShoppingListItem>,
+    /// List index to move to when moving up, if the item can move up
+    move_up_to: Option<i64>,
+    /// List index to move to when moving down, if the item can move down
+    move_down_to: Option<i64>,
 ) -> impl IntoView {
     let item_id = item.id;
     let name = item.name.clone();
-    let quantity = item.quantity.clone();
+    let quantity = item.quantity_label();
     let category = item.category.clone();
     let completed_by_username = item.completed_by_username.clone();
+    let assigned_to = item.assigned_to;
+    let assigned_to_username = item.assigned_to_username.clone();
     let is_completed = item.is_completed;
+    let version = item.version;
+
+    let update_action = ServerAction::<UpdateShoppingListItem>::new();
+    let editing = RwSignal::new(false);
+    let edit_name = RwSignal::new(String::new());
+    let edit_quantity = RwSignal::new(String::new());
+    let edit_unit = RwSignal::new(String::new());
+    let (edit_error, set_edit_error) = signal(None::<String>);
+    let edit_name_input = NodeRef::<leptos::html::Input>::new();
+
+    let start_editing = {
+        let item = item.clone();
+        move |_| {
+            edit_name.set(item.name.clone());
+            edit_quantity.set(
+                item.quantity_value
+                    .map(|value| value.normalize().to_string())
+                    .unwrap_or_default(),
+            );
+            edit_unit.set(item.quantity_unit.clone().unwrap_or_default());
+            set_edit_error.set(None);
+            editing.set(true);
+        }
+    };
+    let stored_category = StoredValue::new(category.clone());
+    let save_edit = move || {
+        update_action.dispatch(UpdateShoppingListItem {
+            item_id,
+            name: edit_name.get_untracked(),
+            quantity_value: Some(edit_quantity.get_untracked()),
+            quantity_unit: Some(edit_unit.get_untracked()),
+            category: stored_category.get_value(),
+        });
+    };
+    let on_edit_keydown = move |ev: leptos::ev::KeyboardEvent| match ev.key().as_str() {
+        "Enter" => {
+            ev.prevent_default();
+            save_edit();
+        }
+        "Escape" => editing.set(false),
+        _ => {}
+    };
+
+    // The list refetches on the item's update event, which ends editing for good
+    Effect::new(move |_| match update_action.value().get() {
+        Some(Ok(())) => editing.set(false),
+        Some(Err(e)) => set_edit_error.set(Some(e.to_string())),
+        None => {}
+    });
+
+    Effect::new(move |_| {
+        if editing.get()
+            && let Some(input) = edit_name_input.get()
+        {
+            let _ = input.focus();
+        }
+    });
 
     view! {
         <li class="p-4 hover:bg-gray-50 dark:hover:bg-gray-700/50 transition-colors">
-            <div class="flex items-center gap-4">
+            <Show when=move || editing.get()>
+                <div class="space-y-2" on:keydown=on_edit_keydown>
+                    <div class="flex gap-2">
+                        <input
+                            type="text"
+                            node_ref=edit_name_input
+                            aria-label="Item name"
+                            maxlength="255"
+                            prop:value=move || edit_name.get()
+                            on:input=move |ev| edit_name.set(event_target_value(&ev))
+                            class="flex-1 min-w-0 px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white"
+                        />
+                        <input
+                            type="text"
+                            inputmode="decimal"
+                            aria-label="Quantity"
+                            placeholder="Qty"
+                            prop:value=move || edit_quantity.get()
+                            on:input=move |ev| edit_quantity.set(event_target_value(&ev))
+                            class="w-20 px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white"
+                        />
+                        <input
+                            type="text"
+                            aria-label="Unit"
+                            placeholder="Unit"
+                            prop:value=move || edit_unit.get()
+                            on:input=move |ev| edit_unit.set(event_target_value(&ev))
+                            class="w-20 px-3 py-1.5 border border-gray-300 dark:border-gray-600 rounded-lg focus:ring-2 focus:ring-indigo-500 focus:border-indigo-500 dark:bg-gray-700 dark:text-white"
+                        />
+                    </div>
+                    <p class="text-xs text-gray-500 dark:text-gray-400">"Enter to save, Esc to cancel"</p>
+                    <ErrorAlert message=edit_error />
+                </div>
+            </Show>
+            <div class="flex items-center gap-4" class:hidden=move || editing.get()>
                 <input
                     type="checkbox"
                     prop:checked=is_completed
-                    on:change=move |_| { toggle_action.dispatch(ToggleShoppingListItem { item_id }); }
+                    on:change=move |_| {
+                        on_toggle.run(ToggleShoppingListItem {
+                            item_id,
+                            completed: !is_completed,
+                            version,
+                        });
+                    }
                     class="h-5 w-5 rounded border-gray-300 dark:border-gray-600 text-indigo-600 focus:ring-indigo-500 dark:bg-gray-800"
                 />
                 <div class="flex-1 min-w-0">
@@ -392,7 +1129,74 @@ fn ItemRow(
                             "Completed by " {username.clone()}
                         </p>
                     })}
+                    {assigned_to_username.as_ref().map(|username| view! {
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            "Assigned to " {username.clone()}
+                        </p>
+                    })}
+                </div>
+                <div class="flex flex-col">
+                    <button
+                        type="button"
+                        disabled=move_up_to.is_none()
+                        on:click=move |_| {
+                            if let Some(new_position) = move_up_to {
+                                reorder_action.dispatch(ReorderShoppingListItem { item_id, new_position });
+                            }
+                        }
+                        class="px-1 text-gray-500 hover:text-indigo-600 dark:text-gray-400 dark:hover:text-indigo-400 disabled:opacity-30"
+                        title="Move item up"
+                    >
+                        "↑"
+                    </button>
+                    <button
+                        type="button"
+                        disabled=move_down_to.is_none()
+                        on:click=move |_| {
+                            if let Some(new_position) = move_down_to {
+                                reorder_action.dispatch(ReorderShoppingListItem { item_id, new_position });
+                            }
+                        }
+                        class="px-1 text-gray-500 hover:text-indigo-600 dark:text-gray-400 dark:hover:text-indigo-400 disabled:opacity-30"
+                        title="Move item down"
+                    >
+                        "↓"
+                    </button>
                 </div>
+                <select
+                    on:change=move |ev| {
+                        let user_id = event_target_value(&ev).parse::<i64>().ok();
+                        assign_action.dispatch(AssignShoppingListItem { item_id, user_id });
+                    }
+                    class="text-sm border-gray-300 dark:border-gray-700 dark:bg-gray-900 dark:text-gray-300 focus:border-indigo-500 focus:ring-indigo-500 rounded-md shadow-sm"
+                    title="Assign item"
+                >
+                    <option value="" selected=assigned_to.is_none()>"Unassigned"</option>
+                    {move || members.get().into_iter().map(|member| view! {
+                        <option value=member.id.to_string() selected=assigned_to == Some(member.id)>
+                            {member.username}
+                        </option>
+                    }).collect_view()}
+                </select>
+                {merge_into.map(|target_id| view! {
+                    <button
+                        on:click=move |_| { merge_action.dispatch(MergeShoppingListItems { source_id: item_id, target_id }); }
+                        class="text-sm text-indigo-600 dark:text-indigo-400 hover:text-indigo-900 dark:hover:text-indigo-300 font-medium"
+                        title="Add this quantity to the earlier item with the same name and unit"
+                    >
+                        "Merge"
+                    </button>
+                })}
+                <button
+                    type="button"
+                    on:click=start_editing
+                    class="text-gray-500 dark:text-gray-400 hover:text-indigo-600 dark:hover:text-indigo-400 p-2 rounded-lg hover:bg-indigo-50 dark:hover:bg-indigo-900/20 transition-colors"
+                    title="Edit item"
+                >
+                    <svg class="h-5 w-5" fill="none" stroke="currentColor" viewBox="0 0 24 24">
+                        <path stroke-linecap="round" stroke-linejoin="round" stroke-width="2" d="M15.232 5.232l3.536 3.536m-2.036-5.036a2.5 2.5 0 113.536 3.536L6.5 21.036H3v-3.572L16.732 3.732z" />
+                    </svg>
+                </button>
                 <button
                     on:click=move |_| { delete_action.dispatch(DeleteShoppingListItem { item_id }); }
                     class="text-red-600 dark:text-red-400 hover:text-red-900 dark:hover:text-red-300 p-2 rounded-lg hover:bg-red-50 dark:hover:bg-red-900/20 transition-colors"
@@ -417,6 +1221,65 @@ fn ActivityItem(activity: ShoppingListActivity) -> impl IntoView {
     }
 }
 
+/// Dialog for saving the list's items as a reusable template
+#[component]
+fn SaveTemplateModal(show: RwSignal<bool>, list_id: i64, list_name: String) -> impl IntoView {
+    let save_action = ServerAction::<SaveListAsTemplate>::new();
+    let template_name = RwSignal::new(list_name);
+    let (error, set_error) = signal(None::<String>);
+
+    Effect::new(move |_| match save_action.value().get() {
+        Some(Ok(_)) => {
+            set_error.set(None);
+            show.set(false);
+        }
+        Some(Err(e)) => set_error.set(Some(e.to_string())),
+        None => {}
+    });
+
+    let on_submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        save_action.dispatch(SaveListAsTemplate {
+            list_id,
+            template_name: template_name.get(),
+        });
+    };
+
+    view! {
+        <Show when=move || show.get()>
+            <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center p-4 z-50">
+                <form on:submit=on_submit class="bg-white dark:bg-gray-800 rounded-xl shadow-2xl max-w-md w-full p-6 space-y-4">
+                    <h3 class="text-xl font-bold text-gray-900 dark:text-white">"Save as template"</h3>
+                    <p class="text-sm text-gray-600 dark:text-gray-400">
+                        "Everyone in the group can start new lists from this template. It keeps the items as they are now."
+                    </p>
+                    <div>
+                        <InputLabel for_input="template_name">"Template Name"</InputLabel>
+                        <TextInput input_type="text" required=true class="w-full mt-1" value=template_name />
+                    </div>
+                    <ErrorAlert message=error />
+                    <div class="flex justify-end gap-3">
+                        <button
+                            type="button"
+                            on:click=move |_| show.set(false)
+                            class="px-4 py-2 bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white rounded-lg font-medium transition-colors"
+                        >
+                            "Cancel"
+                        </button>
+                        <button
+                            type="submit"
+                            disabled=move || save_action.pending().get()
+                            class="px-4 py-2 bg-indigo-600 hover:bg-indigo-700 text-white rounded-lg font-medium transition-colors disabled:opacity-50"
+                        >
+                            {move || if save_action.pending().get() { "Saving..." } else { "Save Template" }}
+                        </button>
+                    </div>
+                </form>
+            </div>
+        </Show>
+    }
+}
+
 #[component]
 fn DeleteModal(
     show: ReadSignal<bool>,
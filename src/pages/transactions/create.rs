@@ -1,19 +1,21 @@
 use leptos::{prelude::*, task::spawn_local};
 use leptos_router::{
     components::A,
-    hooks::{use_navigate, use_params_map},
+    hooks::{use_navigate, use_params_map, use_query_map},
 };
 
 use crate::{
     components::{
-        AppLayout, ErrorAlert, FormActions, FormCard, FormField, FormInput, FormNumberInput,
-        FormSelect, LoadingSpinner, Navigation, PageHeader,
+        AppLayout, ErrorAlert, FieldCheck, FormActions, FormCard, FormField, FormInput,
+        FormNumberInput, FormSelect, LoadingSpinner, Navigation, PageHeader, use_currency_symbol,
     },
+    error::handle_app_error,
     features::{
-        auth::{models::UserSession, use_logout},
+        auth::{models::UserSession, use_logout, use_redirect_to_login},
         groups::handlers::get_group_members,
-        transactions::handlers::create_transaction,
+        transactions::handlers::{create_transaction, get_my_debt_to},
     },
+    validation::rules::check_amount,
 };
 
 #[must_use]
@@ -23,7 +25,9 @@ pub fn TransactionsCreate() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let navigate = use_navigate();
     let on_logout = use_logout();
+    let currency = use_currency_symbol();
     let params = use_params_map();
+    let query = use_query_map();
 
     let group_id = Memo::new(move |_| {
         params
@@ -38,13 +42,59 @@ pub fn TransactionsCreate() -> impl IntoView {
         async move { get_group_members(id).await }
     });
 
-    let (recipient_id, set_recipient_id) = signal(String::from("0"));
-    let (amount, set_amount) = signal(String::new());
+    // Recipient and amount can be prefilled, e.g. from a suggested settlement payment
+    let (recipient_id, set_recipient_id) = signal(
+        query
+            .read_untracked()
+            .get("recipient")
+            .filter(|id| id.parse::<i64>().is_ok())
+            .unwrap_or_else(|| String::from("0")),
+    );
+    let (amount, set_amount) = signal(query.read_untracked().get("amount").unwrap_or_default());
     let (description, set_description) = signal(String::new());
     let (error_message, set_error_message) = signal(Option::<String>::None);
     let (current_user_id, set_current_user_id) = signal(0i64);
     let (is_submitting, set_is_submitting) = signal(false);
 
+    // Client-side mirrors of the server's rules; the server still checks everything
+    let recipient_check = FieldCheck::new(move || match recipient_id.get().parse::<i64>() {
+        Ok(0) | Err(_) => Some("Please select a recipient".to_string()),
+        Ok(id) if id == current_user_id.get() => {
+            Some("The payer and recipient must be different people".to_string())
+        }
+        Ok(_) => None,
+    });
+    let amount_check = FieldCheck::new(move || check_amount(&amount.get()).err());
+    let has_errors =
+        Signal::derive(move || recipient_check.is_invalid() || amount_check.is_invalid());
+
+    // What the current user owes the selected recipient, used as a suggestion
+    let suggestion_resource = LocalResource::new(move || {
+        let gid = group_id.get();
+        let rid = recipient_id.get().parse::<i64>().unwrap_or(0);
+        async move {
+            if rid == 0 {
+                return None;
+            }
+            get_my_debt_to(gid, rid).await.ok().flatten()
+        }
+    });
+    let (suggested_amount, set_suggested_amount) = signal(Option::<String>::None);
+
+    // Prefill the amount unless the user has typed their own
+    Effect::new(move |_| {
+        let Some(suggestion) = suggestion_resource.get() else {
+            return;
+        };
+        let suggestion = suggestion.map(|s| format!("{:.2}", s));
+        let previous = suggested_amount.get_untracked();
+        let current = amount.get_untracked();
+        if current.is_empty() || previous.as_ref() == Some(&current) {
+            set_amount.set(suggestion.clone().unwrap_or_default());
+        }
+        set_suggested_amount.set(suggestion);
+    });
+
     // Set current_user_id when user loads
     Effect::new(move |_| {
         if let Some(Ok(Some(user))) = user_resource.get() {
@@ -53,10 +103,11 @@ pub fn TransactionsCreate() -> impl IntoView {
     });
 
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
+    let redirect_on_error = redirect_to_login.clone();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -73,6 +124,7 @@ pub fn TransactionsCreate() -> impl IntoView {
         let amt = amount.get();
         let desc = description.get();
         let nav = navigate_for_submit.clone();
+        let redirect_on_error = redirect_on_error.clone();
 
         spawn_local(async move {
             let rid = match rid_str.parse::<i64>() {
@@ -97,7 +149,7 @@ pub fn TransactionsCreate() -> impl IntoView {
                     nav(&format!("/groups/{}", gid), Default::default());
                 }
                 Err(e) => {
-                    set_error_message.set(Some(e.to_string()));
+                    set_error_message.set(handle_app_error(&e, &redirect_on_error));
                     set_is_submitting.set(false);
                 }
             }
@@ -129,7 +181,7 @@ pub fn TransactionsCreate() -> impl IntoView {
                                             <form on:submit=move |ev| on_submit.with_value(|f| f(ev)) class="space-y-6">
                                                 <ErrorAlert message=error_message />
 
-                                                <FormField label="Recipient" for_id="recipient_id">
+                                                <FormField label="Recipient" for_id="recipient_id" error=recipient_check.message()>
                                                     <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
                                                         {move || {
                                                             match members_resource.get() {
@@ -143,14 +195,19 @@ pub fn TransactionsCreate() -> impl IntoView {
                                                                             id="recipient_id"
                                                                             required=true
                                                                             value=Signal::derive(move || recipient_id.get())
-                                                                            on_change=Callback::new(move |val| set_recipient_id.set(val))
+                                                                            on_change=Callback::new(move |val| {
+                                                                                recipient_check.touch();
+                                                                                set_recipient_id.set(val);
+                                                                            })
                                                                         >
                                                                             <option value="0">"Select Recipient"</option>
                                                                             {other_members
                                                                                 .into_iter()
                                                                                 .map(|member| {
+                                                                                    let value = member.id.to_string();
+                                                                                    let selected = value == recipient_id.get_untracked();
                                                                                     view! {
-                                                                                        <option value=member.id.to_string()>
+                                                                                        <option value=value selected=selected>
                                                                                             {member.username}
                                                                                         </option>
                                                                                     }
@@ -168,7 +225,7 @@ pub fn TransactionsCreate() -> impl IntoView {
                                                     </Suspense>
                                                 </FormField>
 
-                                                <FormField label="Amount (€)" for_id="amount">
+                                                <FormField label=move || format!("Amount ({})", currency.get()) for_id="amount" error=amount_check.message()>
                                                     <FormNumberInput
                                                         id="amount"
                                                         placeholder="0.00"
@@ -177,7 +234,20 @@ pub fn TransactionsCreate() -> impl IntoView {
                                                         required=true
                                                         value=Signal::derive(move || amount.get())
                                                         on_input=Callback::new(move |val| set_amount.set(val))
+                                                        on_blur=amount_check.on_blur()
                                                     />
+                                                    {move || suggested_amount.get().map(|suggestion| {
+                                                        let is_prefilled = amount.get() == suggestion;
+                                                        view! {
+                                                            <p class="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                                                                {if is_prefilled {
+                                                                    format!("Suggested: you currently owe {}{}. You can change this amount.", currency.get(), suggestion)
+                                                                } else {
+                                                                    format!("You currently owe {}{} to this person.", currency.get(), suggestion)
+                                                                }}
+                                                            </p>
+                                                        }
+                                                    })}
                                                 </FormField>
 
                                                 <FormField label="Description (optional)" for_id="description">
@@ -193,6 +263,7 @@ pub fn TransactionsCreate() -> impl IntoView {
                                                     submit_text="Add Transaction"
                                                     loading_text="Adding..."
                                                     loading=Signal::derive(move || is_submitting.get())
+                                                    disabled=has_errors
                                                     cancel_href=format!("/groups/{}", gid)
                                                 />
                                             </form>
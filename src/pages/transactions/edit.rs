@@ -6,11 +6,12 @@ use leptos_router::{
 
 use crate::{
     components::{
-        AppLayout, ErrorAlert, FormActions, FormCard, FormField, FormInput, FormNumberInput,
-        FormSelect, LoadingSpinner, Navigation, PageHeader,
+        AppLayout, EditErrorAlert, FormActions, FormCard, FormField, FormInput, FormNumberInput,
+        FormSelect, LoadingSpinner, Navigation, PageHeader, use_currency_symbol,
     },
+    error::handle_app_error,
     features::{
-        auth::{models::UserSession, use_logout},
+        auth::{models::UserSession, use_logout, use_redirect_to_login},
         groups::handlers::get_group_members,
         transactions::handlers::{get_transaction, update_transaction},
     },
@@ -23,6 +24,7 @@ pub fn TransactionsEdit() -> impl IntoView {
         expect_context::<LocalResource<Result<Option<UserSession>, ServerFnError>>>();
     let navigate = use_navigate();
     let on_logout = use_logout();
+    let currency = use_currency_symbol();
     let params = use_params_map();
 
     let group_id = Memo::new(move |_| {
@@ -70,16 +72,17 @@ pub fn TransactionsEdit() -> impl IntoView {
     Effect::new(move |_| {
         if let Some(Ok(transaction)) = transaction_resource.get() {
             set_recipient_id.set(transaction.recipient_id.to_string());
-            set_amount.set(transaction.amount.clone());
+            set_amount.set(format!("{:.2}", transaction.amount));
             set_description.set(transaction.description.clone().unwrap_or_default());
         }
     });
 
     // Effect to redirect if not authenticated
-    let navigate_clone = navigate.clone();
+    let redirect_to_login = use_redirect_to_login();
+    let redirect_on_error = redirect_to_login.clone();
     Effect::new(move |_| {
         if let Some(Ok(None)) = user_resource.get() {
-            navigate_clone("/login", Default::default());
+            redirect_to_login();
         }
     });
 
@@ -96,7 +99,14 @@ pub fn TransactionsEdit() -> impl IntoView {
         let rid_str = recipient_id.get();
         let amt = amount.get();
         let desc = description.get();
+        // Version of the transaction the form was loaded from
+        let version = transaction_resource
+            .get()
+            .and_then(Result::ok)
+            .map(|transaction| transaction.version)
+            .unwrap_or_default();
         let nav = navigate_for_submit.clone();
+        let redirect_on_error = redirect_on_error.clone();
 
         spawn_local(async move {
             let rid = match rid_str.parse::<i64>() {
@@ -116,18 +126,24 @@ pub fn TransactionsEdit() -> impl IntoView {
 
             let desc_opt = if desc.is_empty() { None } else { Some(desc) };
 
-            match update_transaction(gid, tid, rid, amt, desc_opt).await {
+            match update_transaction(gid, tid, rid, amt, desc_opt, version).await {
                 Ok(_) => {
                     nav(&format!("/groups/{}", gid), Default::default());
                 }
                 Err(e) => {
-                    set_error_message.set(Some(e.to_string()));
+                    set_error_message.set(handle_app_error(&e, &redirect_on_error));
                     set_is_submitting.set(false);
                 }
             }
         });
     });
 
+    // Load the transaction again after someone else saved it
+    let on_reload = Callback::new(move |()| {
+        set_error_message.set(None);
+        transaction_resource.refetch();
+    });
+
     let gid = group_id.get_untracked();
 
     view! {
@@ -155,7 +171,7 @@ pub fn TransactionsEdit() -> impl IntoView {
 
                                                         <FormCard>
                                                             <form on:submit=move |ev| on_submit.with_value(|f| f(ev)) class="space-y-6">
-                                                                <ErrorAlert message=error_message />
+                                                                <EditErrorAlert message=error_message on_reload=on_reload />
 
                                                                 <FormField label="Recipient" for_id="recipient_id">
                                                                     <Suspense fallback=move || view! { <div>"Loading members..."</div> }>
@@ -196,7 +212,7 @@ pub fn TransactionsEdit() -> impl IntoView {
                                                                     </Suspense>
                                                                 </FormField>
 
-                                                                <FormField label="Amount (€)" for_id="amount">
+                                                                <FormField label=move || format!("Amount ({})", currency.get()) for_id="amount">
                                                                     <FormNumberInput
                                                                         id="amount"
                                                                         min="0.01"
@@ -6,57 +6,29 @@ use leptos::prelude::*;
 use rust_decimal::Decimal;
 
 #[cfg(feature = "ssr")]
-use super::sanitize_string;
+use super::rules::check_amount;
 
-/// Validate amount (decimal string) for financial transactions
+/// Validate a money amount (decimal string) for debts, payments and budgets
 ///
 /// Ensures amounts are:
-/// - Valid decimal numbers
+/// - Plain decimal numbers: no decimal commas, thousands separators or plus
+///   signs
 /// - Greater than zero
 /// - Have at most 2 decimal places
-/// - Not excessively large (max 999,999,999.99)
+/// - Not above [`MAX_AMOUNT`](super::rules::MAX_AMOUNT)
 ///
 /// # Examples
 /// ```
-/// use rustify_app::validation::validate_amount;
+/// use rustify_app::validation::validate_money;
 ///
-/// assert!(validate_amount("10.50").is_ok());
-/// assert!(validate_amount("100").is_ok());
-/// assert!(validate_amount("0").is_err()); // Must be > 0
-/// assert!(validate_amount("10.999").is_err()); // Too many decimals
+/// assert!(validate_money("10.50").is_ok());
+/// assert!(validate_money("100").is_ok());
+/// assert!(validate_money("0").is_err()); // Must be > 0
+/// assert!(validate_money("10.999").is_err()); // Too many decimals
 /// ```
 #[cfg(feature = "ssr")]
-pub fn validate_amount(amount: &str) -> Result<Decimal, ServerFnError> {
-    let sanitized = sanitize_string(amount);
-
-    if sanitized.is_empty() {
-        return Err(ServerFnError::new("Amount is required"));
-    }
-
-    let amount_decimal = sanitized.parse::<Decimal>().map_err(|_| {
-        ServerFnError::new(
-            "Invalid amount format. Please use numbers and a decimal point (e.g., 10.50)",
-        )
-    })?;
-
-    if amount_decimal <= Decimal::ZERO {
-        return Err(ServerFnError::new("Amount must be greater than zero"));
-    }
-
-    if amount_decimal.scale() > 2 {
-        return Err(ServerFnError::new(
-            "Amount can have at most 2 decimal places",
-        ));
-    }
-
-    // Prevent overflow
-    if amount_decimal > Decimal::from(999_999_999) {
-        return Err(ServerFnError::new(
-            "Amount is too large. Maximum is 999,999,999.99",
-        ));
-    }
-
-    Ok(amount_decimal)
+pub fn validate_money(input: &str) -> Result<Decimal, ServerFnError> {
+    check_amount(input).map_err(ServerFnError::new)
 }
 
 #[cfg(all(test, feature = "ssr"))]
@@ -66,16 +38,46 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_validate_amount() {
-        assert_eq!(validate_amount("10.50").unwrap(), Decimal::new(1050, 2));
-        assert_eq!(validate_amount("100").unwrap(), Decimal::from(100));
-        assert_eq!(validate_amount("0.01").unwrap(), Decimal::new(1, 2));
+    fn test_validate_money() {
+        assert_eq!(validate_money("10.50").unwrap(), Decimal::new(1050, 2));
+        assert_eq!(validate_money("100").unwrap(), Decimal::from(100));
+        assert_eq!(validate_money("0.01").unwrap(), Decimal::new(1, 2));
+        assert_eq!(validate_money("1000000").unwrap(), Decimal::from(1_000_000));
 
-        assert!(validate_amount("0").is_err()); // Zero
-        assert!(validate_amount("-5").is_err()); // Negative
-        assert!(validate_amount("10.999").is_err()); // Too many decimals
-        assert!(validate_amount("abc").is_err()); // Invalid format
-        assert!(validate_amount("").is_err()); // Empty
-        assert!(validate_amount("1000000000").is_err()); // Too large
+        for (input, expected) in [
+            ("", "Amount is required"),
+            ("   ", "Amount is required"),
+            ("0", "greater than zero"),
+            ("0.00", "greater than zero"),
+            ("-5", "greater than zero"),
+            ("-0.01", "greater than zero"),
+            ("0.001", "at most 2 decimal places"),
+            ("10.999", "at most 2 decimal places"),
+            ("1000000.01", "too large"),
+            ("1000000000", "too large"),
+            ("+5", "plus sign"),
+            ("1,000", "thousands separators"),
+            ("1,000.50", "thousands separators"),
+            ("1 000", "thousands separators"),
+            ("1'000", "thousands separators"),
+            ("1_000", "thousands separators"),
+            ("1,50", "decimal point instead of a comma"),
+            ("abc", "Invalid amount format"),
+            ("1e3", "Invalid amount format"),
+            ("10_0", "Invalid amount format"),
+            ("€10", "Invalid amount format"),
+            ("1.2.3", "Invalid amount format"),
+            ("5-", "Invalid amount format"),
+            ("NaN", "Invalid amount format"),
+        ] {
+            let message = validate_money(input).unwrap_err().to_string();
+            assert!(
+                message.contains(expected),
+                "{:?} gave {:?}, expected {:?}",
+                input,
+                message,
+                expected
+            );
+        }
     }
 }
@@ -5,6 +5,7 @@
 
 pub mod auth;
 pub mod financial;
+pub mod rules;
 
 #[cfg(feature = "ssr")]
 pub use auth::*;
@@ -54,25 +55,7 @@ pub fn validate_name(
     max_len: usize,
     field_name: &str,
 ) -> Result<String, ServerFnError> {
-    let sanitized = sanitize_string(input);
-
-    if sanitized.is_empty() || sanitized.len() < min_len {
-        return Err(ServerFnError::new(format!(
-            "{} must be at least {} character{}",
-            field_name,
-            min_len,
-            if min_len == 1 { "" } else { "s" }
-        )));
-    }
-
-    if sanitized.len() > max_len {
-        return Err(ServerFnError::new(format!(
-            "{} must be {} characters or less",
-            field_name, max_len
-        )));
-    }
-
-    Ok(sanitized)
+    rules::check_name(input, min_len, max_len, field_name).map_err(ServerFnError::new)
 }
 
 /// Validate a description field (allows empty, enforces max length)
@@ -90,6 +73,45 @@ pub fn validate_description(description: &str, max_len: usize) -> Result<String,
     Ok(sanitized)
 }
 
+/// Validate a post-login redirect target, accepting only same-origin relative
+/// paths
+///
+/// # Examples
+/// ```
+/// use rustify_app::validation::safe_redirect_path;
+///
+/// assert_eq!(safe_redirect_path("/groups/1"), Some("/groups/1"));
+/// assert_eq!(safe_redirect_path("//evil.example"), None);
+/// ```
+pub fn safe_redirect_path(path: &str) -> Option<&str> {
+    let is_safe = path.starts_with('/')
+        && !path.starts_with("//")
+        && !path.contains('\\')
+        && !path.chars().any(char::is_control);
+
+    is_safe.then_some(path)
+}
+
+/// Escape `%`, `_` and `\` so a search term matches literally in a
+/// `LIKE ... ESCAPE '\'` pattern
+///
+/// # Examples
+/// ```
+/// use rustify_app::validation::escape_like;
+///
+/// assert_eq!(escape_like("50%_off"), "50\\%\\_off");
+/// ```
+pub fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,4 +132,27 @@ mod tests {
         assert!(validate_name(&"a".repeat(300), 1, 255, "Group name").is_err());
         assert!(validate_name("  Valid  ", 1, 255, "Group name").is_ok());
     }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("ikea"), "ikea");
+        assert_eq!(escape_like("100%"), "100\\%");
+        assert_eq!(escape_like("a_b"), "a\\_b");
+        assert_eq!(escape_like("c:\\x"), "c:\\\\x");
+    }
+
+    #[test]
+    fn test_safe_redirect_path() {
+        assert_eq!(safe_redirect_path("/groups"), Some("/groups"));
+        assert_eq!(
+            safe_redirect_path("/groups/1/shopping-lists/2?tab=open"),
+            Some("/groups/1/shopping-lists/2?tab=open")
+        );
+        assert_eq!(safe_redirect_path(""), None);
+        assert_eq!(safe_redirect_path("groups"), None);
+        assert_eq!(safe_redirect_path("https://evil.example"), None);
+        assert_eq!(safe_redirect_path("//evil.example"), None);
+        assert_eq!(safe_redirect_path("/\\evil.example"), None);
+        assert_eq!(safe_redirect_path("/groups\n"), None);
+    }
 }
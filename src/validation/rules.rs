@@ -0,0 +1,962 @@
+//! Validation rules that don't need the database
+//!
+//! Compiled for both server and browser so forms can show the same messages
+//! inline before submitting. Server functions still run these checks and
+//! remain authoritative.
+
+use std::net::IpAddr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use time::Date;
+
+use super::sanitize_string;
+
+/// Maximum length of names: groups, shared and recurring debts, shopping
+/// lists, their items and templates all share this limit
+pub const MAX_NAME_LENGTH: usize = 255;
+
+/// Maximum length of payment descriptions
+pub const MAX_DESCRIPTION_LENGTH: usize = 500;
+
+/// Largest amount accepted for a debt, payment or budget, in whole euros
+pub const MAX_AMOUNT: i64 = 1_000_000;
+
+/// Largest weight a member can have in a weighted split
+pub const MAX_SPLIT_WEIGHT: i64 = 100;
+
+/// Longest custom interval of a recurring debt, in days or weeks
+pub const MAX_FREQUENCY_INTERVAL: u32 = 365;
+
+/// Most decimal places an exchange rate may have, e.g. 0.02635412
+pub const MAX_RATE_DECIMALS: u32 = 8;
+
+/// Rows returned per page of a group's debts or transactions
+pub const DEFAULT_PAGE_SIZE: i64 = 25;
+
+/// Most rows a single page may request
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+const INVALID_AMOUNT: &str =
+    "Invalid amount format. Please use numbers and a decimal point (e.g., 10.50)";
+
+/// Whether the whole-euro part is split into groups of three, as in
+/// "1,000", "1 000", "1'000" or "1_000"
+fn has_digit_grouping(amount: &str) -> bool {
+    let whole = amount.split('.').next().unwrap_or_default();
+    [',', ' ', '\'', '_'].into_iter().any(|separator| {
+        let mut groups = whole.trim_start_matches('-').split(separator);
+        let first = groups.next().unwrap_or_default();
+        let rest: Vec<&str> = groups.collect();
+        !rest.is_empty()
+            && (1..=3).contains(&first.len())
+            && first.chars().all(|c| c.is_ascii_digit())
+            && rest
+                .iter()
+                .all(|group| group.len() == 3 && group.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Check an amount string and parse it
+///
+/// The amount must be a number greater than zero with at most 2 decimal
+/// places and not above [`MAX_AMOUNT`]. Only digits and a decimal point are
+/// accepted: decimal commas, thousands separators and plus signs are
+/// rejected with their own message instead of being guessed at.
+pub fn check_amount(amount: &str) -> Result<Decimal, String> {
+    let amount_decimal = parse_amount_entry(amount)?;
+
+    // Prevent overflow
+    if amount_decimal > Decimal::from(MAX_AMOUNT) {
+        return Err(format!("Amount is too large. Maximum is {}", MAX_AMOUNT));
+    }
+
+    Ok(amount_decimal)
+}
+
+/// Check an amount paid in another currency and parse it
+///
+/// Written like [`check_amount`], but not capped: the limit applies to the
+/// amount once converted to euros.
+pub fn check_original_amount(amount: &str) -> Result<Decimal, String> {
+    parse_amount_entry(amount)
+}
+
+/// [`check_amount`] without the cap
+fn parse_amount_entry(amount: &str) -> Result<Decimal, String> {
+    let sanitized = sanitize_string(amount);
+
+    if sanitized.is_empty() {
+        return Err("Amount is required".to_string());
+    }
+
+    if sanitized.starts_with('+') {
+        return Err("Leave out the plus sign (e.g., 10.50)".to_string());
+    }
+
+    if has_digit_grouping(&sanitized) {
+        return Err("Leave out thousands separators (e.g., 1500.50)".to_string());
+    }
+
+    // "1,50" is a common way to write 1.50, but could also be read as 150;
+    // reject it rather than guess
+    if sanitized.contains(',') {
+        return Err("Use a decimal point instead of a comma (e.g., 1.50)".to_string());
+    }
+
+    // Decimal parsing skips underscores, so only digits and a point get
+    // through; a minus sign is left to be reported as not greater than zero
+    if !sanitized
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c == '-')
+    {
+        return Err(INVALID_AMOUNT.to_string());
+    }
+
+    let amount_decimal = sanitized
+        .parse::<Decimal>()
+        .map_err(|_| INVALID_AMOUNT.to_string())?;
+
+    if amount_decimal <= Decimal::ZERO {
+        return Err("Amount must be greater than zero".to_string());
+    }
+
+    if amount_decimal.scale() > 2 {
+        return Err("Amount can have at most 2 decimal places".to_string());
+    }
+
+    Ok(amount_decimal)
+}
+
+/// Check a three-letter currency code such as "THB" and return it in capitals
+pub fn check_currency_code(code: &str) -> Result<String, String> {
+    let code = sanitize_string(code);
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("Currency must be a three-letter code (e.g., THB)".to_string());
+    }
+    Ok(code.to_ascii_uppercase())
+}
+
+/// Check the rate from another currency to euros and parse it
+///
+/// Rates are entered by hand, e.g. 0.026 for Thai baht, with a decimal point
+/// and at most [`MAX_RATE_DECIMALS`] decimal places.
+pub fn check_exchange_rate(rate: &str) -> Result<Decimal, String> {
+    let rate = sanitize_string(rate);
+
+    if rate.is_empty() {
+        return Err("Exchange rate is required".to_string());
+    }
+
+    if rate.contains(',') {
+        return Err("Use a decimal point in the exchange rate (e.g., 0.026)".to_string());
+    }
+
+    let invalid = || "Invalid exchange rate. Please use a number like 0.026".to_string();
+    if !rate
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c == '-')
+    {
+        return Err(invalid());
+    }
+    let rate = rate.parse::<Decimal>().map_err(|_| invalid())?;
+
+    if rate <= Decimal::ZERO {
+        return Err("Exchange rate must be greater than zero".to_string());
+    }
+
+    if rate.scale() > MAX_RATE_DECIMALS {
+        return Err(format!(
+            "Exchange rate can have at most {} decimal places",
+            MAX_RATE_DECIMALS
+        ));
+    }
+
+    Ok(rate.normalize())
+}
+
+/// Check a name's length after sanitizing it and return the sanitized name
+pub fn check_name(
+    input: &str,
+    min_len: usize,
+    max_len: usize,
+    field_name: &str,
+) -> Result<String, String> {
+    let sanitized = sanitize_string(input);
+
+    if sanitized.is_empty() || sanitized.len() < min_len {
+        return Err(format!(
+            "{} must be at least {} character{}",
+            field_name,
+            min_len,
+            if min_len == 1 { "" } else { "s" }
+        ));
+    }
+
+    if sanitized.len() > max_len {
+        return Err(format!(
+            "{} must be {} characters or less",
+            field_name, max_len
+        ));
+    }
+
+    Ok(sanitized)
+}
+
+/// Check that an end date comes strictly after the start date
+pub fn check_date_order(start: Date, end: Date) -> Result<(), String> {
+    if end <= start {
+        return Err("End date must be after start date".to_string());
+    }
+    Ok(())
+}
+
+/// Check pagination parameters and fill in the defaults
+///
+/// Returns `(limit, offset)`, starting at the first row with
+/// [`DEFAULT_PAGE_SIZE`] rows when omitted.
+pub fn check_page(limit: Option<i64>, offset: Option<i64>) -> Result<(i64, i64), String> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+        return Err(format!("Page size must be between 1 and {}", MAX_PAGE_SIZE));
+    }
+
+    let offset = offset.unwrap_or(0);
+    if offset < 0 {
+        return Err("Page offset can't be negative".to_string());
+    }
+
+    Ok((limit, offset))
+}
+
+/// Check an optional YYYY-MM-DD filter date
+///
+/// Empty values mean no filter.
+pub fn check_date_filter(value: Option<&str>, field: &str) -> Result<Option<Date>, String> {
+    let Some(value) = value.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+
+    Date::parse(
+        value,
+        &time::format_description::well_known::Iso8601::DEFAULT,
+    )
+    .map(Some)
+    .map_err(|_| format!("Invalid {} format (expected YYYY-MM-DD)", field))
+}
+
+/// Check an optional YYYY-MM-DD start and end date
+///
+/// Either may be empty for an open end, but the start can't come after the end.
+pub fn check_date_range(
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<(Option<Date>, Option<Date>), String> {
+    let from = check_date_filter(from, "start date")?;
+    let to = check_date_filter(to, "end date")?;
+    if let (Some(from), Some(to)) = (from, to)
+        && from > to
+    {
+        return Err("The start date can't be after the end date".to_string());
+    }
+    Ok((from, to))
+}
+
+/// Check an optional YYYY-MM-DD due date
+///
+/// Empty values mean no due date. A due date can't lie before `today`.
+pub fn check_due_date(value: Option<&str>, today: Date) -> Result<Option<Date>, String> {
+    let due_date = check_date_filter(value, "due date")?;
+    if due_date.is_some_and(|due_date| due_date < today) {
+        return Err("Due date must be today or later".to_string());
+    }
+    Ok(due_date)
+}
+
+/// How many years back the day a debt was incurred on may lie
+pub const MAX_INCURRED_YEARS_AGO: i32 = 5;
+
+/// Check the optional YYYY-MM-DD day a debt was incurred on
+///
+/// Empty values mean `today`. The day may lie a day ahead of `today`, for
+/// members ahead of the application timezone, and at most
+/// [`MAX_INCURRED_YEARS_AGO`] years back.
+pub fn check_incurred_on(value: Option<&str>, today: Date) -> Result<Date, String> {
+    let Some(incurred_on) = check_date_filter(value, "date")? else {
+        return Ok(today);
+    };
+    if today
+        .next_day()
+        .is_some_and(|tomorrow| incurred_on > tomorrow)
+    {
+        return Err("Date can't be more than a day in the future".to_string());
+    }
+    let earliest_year = today.year() - MAX_INCURRED_YEARS_AGO;
+    let earliest = today
+        .replace_year(earliest_year)
+        // February 29th in a year that has none
+        .or_else(|_| Date::from_calendar_date(earliest_year, today.month(), 28))
+        .unwrap_or(Date::MIN);
+    if incurred_on < earliest {
+        return Err(format!(
+            "Date can't be more than {} years ago",
+            MAX_INCURRED_YEARS_AGO
+        ));
+    }
+    Ok(incurred_on)
+}
+
+/// Amount of one custom share entry, if it is fixed
+///
+/// An entry is either an exact amount ("12.50") or a percentage of the total
+/// ("70%"). Empty entries return `Ok(None)` and take an equal part of what the
+/// fixed entries leave over.
+pub fn check_share_entry(total: Decimal, entry: &str) -> Result<Option<Decimal>, String> {
+    let entry = sanitize_string(entry);
+
+    if entry.is_empty() {
+        return Ok(None);
+    }
+
+    match entry.strip_suffix('%') {
+        Some(percent) => {
+            let percent = percent
+                .trim()
+                .parse::<Decimal>()
+                .map_err(|_| format!("Invalid percentage \"{}\"", entry))?;
+            if percent <= Decimal::ZERO || percent > Decimal::ONE_HUNDRED {
+                return Err("Percentages must be greater than 0 and at most 100".to_string());
+            }
+            Ok(Some((total * percent / Decimal::ONE_HUNDRED).round_dp(2)))
+        }
+        None => check_amount(&entry).map(Some),
+    }
+}
+
+/// Check a member's split weight entry and parse it
+///
+/// Weights are whole numbers from 1 to [`MAX_SPLIT_WEIGHT`]; an empty entry
+/// counts as the default weight of 1.
+pub fn check_weight(entry: &str) -> Result<i64, String> {
+    let entry = sanitize_string(entry);
+
+    if entry.is_empty() {
+        return Ok(1);
+    }
+
+    let weight = entry
+        .parse::<i64>()
+        .map_err(|_| format!("Invalid weight \"{}\", use a whole number", entry))?;
+    if !(1..=MAX_SPLIT_WEIGHT).contains(&weight) {
+        return Err(format!(
+            "Weights must be between 1 and {}",
+            MAX_SPLIT_WEIGHT
+        ));
+    }
+    Ok(weight)
+}
+
+/// Check the interval of a custom "every N days/weeks" frequency and parse it
+pub fn check_interval(entry: &str) -> Result<u32, String> {
+    let entry = sanitize_string(entry);
+
+    if entry.is_empty() {
+        return Err("Interval is required".to_string());
+    }
+
+    let interval = entry
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid interval \"{}\", use a whole number", entry))?;
+    if !(1..=MAX_FREQUENCY_INTERVAL).contains(&interval) {
+        return Err(format!(
+            "Interval must be between 1 and {}",
+            MAX_FREQUENCY_INTERVAL
+        ));
+    }
+    Ok(interval)
+}
+
+/// Resolve custom share entries per member against a debt's total
+///
+/// Fixed entries may not add up to more than the total. Without empty entries
+/// they must match it exactly; otherwise the rest is split equally between the
+/// empty entries, with leftover cents going to the first of them.
+pub fn check_custom_shares(
+    total: Decimal,
+    entries: &[(i64, String)],
+) -> Result<Vec<(i64, Decimal)>, String> {
+    if entries.is_empty() {
+        return Err("At least one member must have a share".to_string());
+    }
+
+    let mut fixed = Vec::with_capacity(entries.len());
+    for (index, (user_id, entry)) in entries.iter().enumerate() {
+        if entries[..index].iter().any(|(other, _)| other == user_id) {
+            return Err("Each member can only have one share".to_string());
+        }
+        fixed.push((*user_id, check_share_entry(total, entry)?));
+    }
+
+    let assigned: Decimal = fixed.iter().filter_map(|(_, share)| *share).sum();
+    let open = fixed.iter().filter(|(_, share)| share.is_none()).count();
+
+    if assigned > total {
+        return Err(format!(
+            "Shares add up to {:.2}, which is more than the total of {:.2}",
+            assigned, total
+        ));
+    }
+
+    if open == 0 {
+        if assigned != total {
+            return Err(format!(
+                "Shares add up to {:.2} but the total is {:.2}",
+                assigned, total
+            ));
+        }
+        return Ok(fixed
+            .into_iter()
+            .map(|(user_id, share)| (user_id, share.unwrap_or_default()))
+            .collect());
+    }
+
+    let remainder = total - assigned;
+    let cent = Decimal::new(1, 2);
+    let base =
+        (remainder / Decimal::from(open)).round_dp_with_strategy(2, RoundingStrategy::ToZero);
+    if base < cent {
+        return Err("Members without an amount would owe nothing".to_string());
+    }
+    let mut leftover = remainder - base * Decimal::from(open);
+
+    Ok(fixed
+        .into_iter()
+        .map(|(user_id, share)| {
+            let share = share.unwrap_or_else(|| {
+                if leftover >= cent {
+                    leftover -= cent;
+                    base + cent
+                } else {
+                    base
+                }
+            });
+            (user_id, share)
+        })
+        .collect())
+}
+
+/// Longest webhook URL accepted
+pub const MAX_WEBHOOK_URL_LENGTH: usize = 2048;
+
+/// Error for webhook URLs pointing at the server itself or its network
+pub const PRIVATE_WEBHOOK_HOST: &str = "Webhook URL must point to a public host";
+
+/// Check a webhook URL and return it trimmed
+///
+/// Only absolute http(s) URLs without whitespace are accepted, and not ones
+/// naming localhost or a private IP address. Host names are resolved and
+/// checked again on the server before anything is sent.
+pub fn check_webhook_url(input: &str) -> Result<String, String> {
+    let url = input.trim();
+
+    if url.is_empty() {
+        return Err("Webhook URL is required".to_string());
+    }
+
+    if url.len() > MAX_WEBHOOK_URL_LENGTH {
+        return Err(format!(
+            "Webhook URL must be at most {} characters",
+            MAX_WEBHOOK_URL_LENGTH
+        ));
+    }
+
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| "Webhook URL must start with http:// or https://".to_string())?;
+
+    if host.is_empty() || host.starts_with('/') || url.chars().any(char::is_whitespace) {
+        return Err("Webhook URL is not valid".to_string());
+    }
+
+    let (host, _) = webhook_url_host(url).ok_or_else(|| "Webhook URL is not valid".to_string())?;
+    let is_private = match host.parse::<IpAddr>() {
+        Ok(ip) => !is_public_ip(ip),
+        Err(_) => host == "localhost" || host.ends_with(".localhost"),
+    };
+    if is_private {
+        return Err(PRIVATE_WEBHOOK_HOST.to_string());
+    }
+
+    Ok(url.to_string())
+}
+
+/// Lowercase host and port an http(s) URL points at, e.g.
+/// `("example.com", 443)`
+///
+/// IPv6 addresses are returned without their brackets.
+pub fn webhook_url_host(url: &str) -> Option<(String, u16)> {
+    let (rest, default_port) = match url.strip_prefix("https://") {
+        Some(rest) => (rest, 443),
+        None => (url.strip_prefix("http://")?, 80),
+    };
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, after) = bracketed.split_once(']')?;
+            if after.is_empty() {
+                (host, None)
+            } else {
+                (host, Some(after.strip_prefix(':')?))
+            }
+        }
+        None => match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port,
+    };
+
+    Some((host.to_ascii_lowercase(), port))
+}
+
+/// Whether an address is on the public internet: not loopback, private,
+/// link-local, shared (100.64.0.0/10), unspecified, broadcast, multicast or
+/// reserved for documentation
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || first == 0
+                || (first == 100 && (64..128).contains(&second)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(ip));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // Unique local fc00::/7 and link-local fe80::/10
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80
+                // Documentation 2001:db8::/32
+                || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+        }
+    }
+}
+
+/// Error message returned when an edit was based on an outdated version of
+/// a group, debt or payment
+pub const EDIT_CONFLICT_ERROR: &str = "This record was modified by someone else, please reload";
+
+/// Whether an error message reports an edit conflict
+///
+/// Server function errors carry a prefix, so the message is matched as a
+/// substring.
+pub fn is_edit_conflict(message: &str) -> bool {
+    message.contains(EDIT_CONFLICT_ERROR)
+}
+
+/// Start of the error returned when a group update would remove members who
+/// still have open debts in the group
+pub const MEMBER_REMOVAL_BLOCKED: &str = "Can't remove members with open debts";
+
+/// Whether an error message reports members that couldn't be removed
+pub fn is_member_removal_blocked(message: &str) -> bool {
+    message.contains(MEMBER_REMOVAL_BLOCKED)
+}
+
+/// Error for viewers trying to change something in a group
+pub const VIEWER_READ_ONLY: &str = "Viewers can look at this group but not change it";
+
+/// Error for changes to a group that was archived
+pub const GROUP_ARCHIVED: &str = "Group is archived";
+
+#[cfg(test)]
+mod tests {
+    use time::Month;
+
+    use super::*;
+
+    #[test]
+    fn test_check_amount() {
+        assert_eq!(check_amount(" 10.50 ").unwrap(), Decimal::new(1050, 2));
+        assert_eq!(check_amount("1000000").unwrap(), Decimal::from(MAX_AMOUNT));
+
+        assert_eq!(check_amount("").unwrap_err(), "Amount is required");
+        assert_eq!(
+            check_amount("10.999").unwrap_err(),
+            "Amount can have at most 2 decimal places"
+        );
+        assert_eq!(
+            check_amount("-1").unwrap_err(),
+            "Amount must be greater than zero"
+        );
+        assert_eq!(
+            check_amount("1,50").unwrap_err(),
+            "Use a decimal point instead of a comma (e.g., 1.50)"
+        );
+        assert_eq!(
+            check_amount("1,000.50").unwrap_err(),
+            "Leave out thousands separators (e.g., 1500.50)"
+        );
+        assert_eq!(
+            check_amount("+5").unwrap_err(),
+            "Leave out the plus sign (e.g., 10.50)"
+        );
+        assert!(check_amount("1000000.01").is_err());
+    }
+
+    #[test]
+    fn test_check_original_amount() {
+        assert_eq!(
+            check_original_amount("1500000").unwrap(),
+            Decimal::from(1_500_000)
+        );
+        assert_eq!(
+            check_original_amount("0").unwrap_err(),
+            "Amount must be greater than zero"
+        );
+    }
+
+    #[test]
+    fn test_check_currency_code() {
+        assert_eq!(check_currency_code(" thb ").unwrap(), "THB");
+        for code in ["", "TH", "THBX", "T1B", "€"] {
+            assert_eq!(
+                check_currency_code(code).unwrap_err(),
+                "Currency must be a three-letter code (e.g., THB)"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_exchange_rate() {
+        assert_eq!(check_exchange_rate("0.0260").unwrap(), Decimal::new(26, 3));
+        assert_eq!(check_exchange_rate("1.1").unwrap(), Decimal::new(11, 1));
+
+        assert_eq!(
+            check_exchange_rate("").unwrap_err(),
+            "Exchange rate is required"
+        );
+        assert_eq!(
+            check_exchange_rate("0").unwrap_err(),
+            "Exchange rate must be greater than zero"
+        );
+        assert_eq!(
+            check_exchange_rate("-0.5").unwrap_err(),
+            "Exchange rate must be greater than zero"
+        );
+        assert_eq!(
+            check_exchange_rate("0,026").unwrap_err(),
+            "Use a decimal point in the exchange rate (e.g., 0.026)"
+        );
+        assert_eq!(
+            check_exchange_rate("0.000000001").unwrap_err(),
+            "Exchange rate can have at most 8 decimal places"
+        );
+        assert!(check_exchange_rate("abc").is_err());
+    }
+
+    #[test]
+    fn test_check_name() {
+        assert_eq!(
+            check_name("  Rent ", 1, MAX_NAME_LENGTH, "Debt name").unwrap(),
+            "Rent"
+        );
+        assert_eq!(
+            check_name("   ", 1, MAX_NAME_LENGTH, "Debt name").unwrap_err(),
+            "Debt name must be at least 1 character"
+        );
+        assert_eq!(
+            check_name(&"a".repeat(256), 1, MAX_NAME_LENGTH, "Debt name").unwrap_err(),
+            "Debt name must be 255 characters or less"
+        );
+    }
+
+    #[test]
+    fn test_check_date_order() {
+        let start = Date::from_calendar_date(2026, Month::March, 1).unwrap();
+        let end = Date::from_calendar_date(2026, Month::March, 2).unwrap();
+
+        assert!(check_date_order(start, end).is_ok());
+        assert!(check_date_order(start, start).is_err());
+        assert!(check_date_order(end, start).is_err());
+    }
+
+    #[test]
+    fn test_check_page() {
+        assert_eq!(check_page(None, None), Ok((DEFAULT_PAGE_SIZE, 0)));
+        assert_eq!(check_page(Some(50), Some(25)), Ok((50, 25)));
+        assert!(check_page(Some(0), None).is_err());
+        assert!(check_page(Some(MAX_PAGE_SIZE + 1), None).is_err());
+        assert!(check_page(None, Some(-1)).is_err());
+    }
+
+    #[test]
+    fn test_check_date_filter() {
+        let date = Date::from_calendar_date(2026, Month::March, 1).unwrap();
+
+        assert_eq!(check_date_filter(None, "start date"), Ok(None));
+        assert_eq!(check_date_filter(Some(" "), "start date"), Ok(None));
+        assert_eq!(
+            check_date_filter(Some("2026-03-01"), "start date"),
+            Ok(Some(date))
+        );
+        assert!(check_date_filter(Some("01.03.2026"), "start date").is_err());
+    }
+
+    #[test]
+    fn test_check_date_range() {
+        let first = Date::from_calendar_date(2026, Month::March, 1).unwrap();
+        let last = Date::from_calendar_date(2026, Month::March, 31).unwrap();
+
+        assert_eq!(check_date_range(None, Some("")), Ok((None, None)));
+        assert_eq!(
+            check_date_range(Some("2026-03-01"), Some("2026-03-31")),
+            Ok((Some(first), Some(last)))
+        );
+        assert_eq!(
+            check_date_range(Some("2026-03-01"), Some("2026-03-01")),
+            Ok((Some(first), Some(first)))
+        );
+        assert!(check_date_range(Some("2026-03-31"), Some("2026-03-01")).is_err());
+    }
+
+    #[test]
+    fn test_check_due_date() {
+        let today = Date::from_calendar_date(2026, Month::March, 1).unwrap();
+
+        assert_eq!(check_due_date(Some(""), today), Ok(None));
+        assert_eq!(check_due_date(Some("2026-03-01"), today), Ok(Some(today)));
+        assert_eq!(
+            check_due_date(Some("2026-02-28"), today),
+            Err("Due date must be today or later".to_string())
+        );
+        assert!(check_due_date(Some("next week"), today).is_err());
+    }
+
+    #[test]
+    fn test_check_incurred_on() {
+        let today = Date::from_calendar_date(2026, Month::March, 1).unwrap();
+        let date = |month, day| Date::from_calendar_date(2026, month, day).unwrap();
+
+        assert_eq!(check_incurred_on(None, today), Ok(today));
+        assert_eq!(check_incurred_on(Some(" "), today), Ok(today));
+        assert_eq!(
+            check_incurred_on(Some("2026-02-21"), today),
+            Ok(date(Month::February, 21))
+        );
+        assert_eq!(
+            check_incurred_on(Some("2026-03-02"), today),
+            Ok(date(Month::March, 2))
+        );
+        assert_eq!(
+            check_incurred_on(Some("2026-03-03"), today),
+            Err("Date can't be more than a day in the future".to_string())
+        );
+        assert!(check_incurred_on(Some("2021-03-01"), today).is_ok());
+        assert_eq!(
+            check_incurred_on(Some("2021-02-28"), today),
+            Err("Date can't be more than 5 years ago".to_string())
+        );
+
+        // Five years before a leap day
+        let leap_day = Date::from_calendar_date(2028, Month::February, 29).unwrap();
+        assert!(check_incurred_on(Some("2023-02-28"), leap_day).is_ok());
+        assert!(check_incurred_on(Some("2023-02-27"), leap_day).is_err());
+    }
+
+    #[test]
+    fn test_check_share_entry() {
+        let total = Decimal::from(50);
+
+        assert_eq!(check_share_entry(total, " ").unwrap(), None);
+        assert_eq!(
+            check_share_entry(total, "12.5").unwrap(),
+            Some(Decimal::new(125, 1))
+        );
+        assert_eq!(
+            check_share_entry(total, "70%").unwrap(),
+            Some(Decimal::from(35))
+        );
+        assert!(check_share_entry(total, "0%").is_err());
+        assert!(check_share_entry(total, "120%").is_err());
+        assert!(check_share_entry(total, "abc%").is_err());
+        assert!(check_share_entry(total, "-3").is_err());
+    }
+
+    #[test]
+    fn test_check_weight() {
+        assert_eq!(check_weight("").unwrap(), 1);
+        assert_eq!(check_weight(" 3 ").unwrap(), 3);
+        assert_eq!(check_weight("100").unwrap(), MAX_SPLIT_WEIGHT);
+        assert_eq!(
+            check_weight("0").unwrap_err(),
+            "Weights must be between 1 and 100"
+        );
+        assert!(check_weight("101").is_err());
+        assert!(check_weight("1.5").is_err());
+        assert!(check_weight("abc").is_err());
+    }
+
+    #[test]
+    fn test_check_interval() {
+        assert_eq!(check_interval(" 3 ").unwrap(), 3);
+        assert_eq!(check_interval("365").unwrap(), MAX_FREQUENCY_INTERVAL);
+        assert_eq!(check_interval("").unwrap_err(), "Interval is required");
+        assert!(check_interval("0").is_err());
+        assert!(check_interval("366").is_err());
+        assert!(check_interval("-2").is_err());
+        assert!(check_interval("2.5").is_err());
+    }
+
+    #[test]
+    fn test_check_custom_shares() {
+        let total = Decimal::from(100);
+        let entries = |values: &[&str]| -> Vec<(i64, String)> {
+            values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (index as i64 + 1, value.to_string()))
+                .collect()
+        };
+
+        // One member pays 70%, the others split the rest
+        assert_eq!(
+            check_custom_shares(total, &entries(&["70%", "", ""])).unwrap(),
+            vec![
+                (1, Decimal::from(70)),
+                (2, Decimal::from(15)),
+                (3, Decimal::from(15))
+            ]
+        );
+
+        // Leftover cents go to the first open entries
+        assert_eq!(
+            check_custom_shares(Decimal::from(10), &entries(&["", "", ""])).unwrap(),
+            vec![
+                (1, Decimal::new(334, 2)),
+                (2, Decimal::new(333, 2)),
+                (3, Decimal::new(333, 2))
+            ]
+        );
+
+        assert!(check_custom_shares(total, &entries(&["60", "40"])).is_ok());
+        assert_eq!(
+            check_custom_shares(total, &entries(&["60", "30"])).unwrap_err(),
+            "Shares add up to 90.00 but the total is 100.00"
+        );
+        // Totals a cent short are rejected rather than rounded away
+        assert_eq!(
+            check_custom_shares(Decimal::new(3000, 2), &entries(&["15", "14.99"])).unwrap_err(),
+            "Shares add up to 29.99 but the total is 30.00"
+        );
+        assert_eq!(
+            check_custom_shares(total, &entries(&["80", "30", ""])).unwrap_err(),
+            "Shares add up to 110.00, which is more than the total of 100.00"
+        );
+        assert_eq!(
+            check_custom_shares(total, &entries(&["100", ""])).unwrap_err(),
+            "Members without an amount would owe nothing"
+        );
+        assert_eq!(
+            check_custom_shares(total, &[(1, "50".to_string()), (1, "50".to_string())])
+                .unwrap_err(),
+            "Each member can only have one share"
+        );
+        assert!(check_custom_shares(total, &[]).is_err());
+    }
+
+    #[test]
+    fn test_check_webhook_url() {
+        assert_eq!(
+            check_webhook_url(" https://example.com/hook ").unwrap(),
+            "https://example.com/hook"
+        );
+        assert!(check_webhook_url("http://example.com:8080/hook").is_ok());
+
+        assert_eq!(
+            check_webhook_url("").unwrap_err(),
+            "Webhook URL is required"
+        );
+        assert!(check_webhook_url("ftp://example.com").is_err());
+        assert!(check_webhook_url("https://").is_err());
+        assert!(check_webhook_url("https://exa mple.com").is_err());
+    }
+
+    #[test]
+    fn test_check_webhook_url_rejects_private_hosts() {
+        for url in [
+            "http://localhost:8080",
+            "http://api.localhost/hook",
+            "http://127.0.0.1/hook",
+            "http://127.0.0.1:9000/hook",
+            "http://[::1]:8080/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+            "http://169.254.169.254/latest/meta-data",
+            "http://10.0.0.5/hook",
+            "https://192.168.1.10/hook",
+            "http://172.16.0.1/hook",
+            "http://user@10.1.2.3/hook",
+            "http://0.0.0.0/hook",
+            "http://[fd00::1]/hook",
+            "http://[fe80::1]/hook",
+        ] {
+            assert_eq!(
+                check_webhook_url(url).unwrap_err(),
+                PRIVATE_WEBHOOK_HOST,
+                "{url}"
+            );
+        }
+
+        assert!(check_webhook_url("https://93.184.216.34/hook").is_ok());
+        assert!(check_webhook_url("https://[2606:4700::1111]/hook").is_ok());
+    }
+
+    #[test]
+    fn test_webhook_url_host() {
+        assert_eq!(
+            webhook_url_host("https://Example.com/hook?x=1"),
+            Some(("example.com".to_string(), 443))
+        );
+        assert_eq!(
+            webhook_url_host("http://user:pw@example.com:8080"),
+            Some(("example.com".to_string(), 8080))
+        );
+        assert_eq!(
+            webhook_url_host("http://[::1]:9000/"),
+            Some(("::1".to_string(), 9000))
+        );
+        assert_eq!(webhook_url_host("http://example.com:port/"), None);
+        assert_eq!(webhook_url_host("ftp://example.com"), None);
+    }
+
+    #[test]
+    fn test_is_edit_conflict() {
+        assert!(is_edit_conflict(EDIT_CONFLICT_ERROR));
+        assert!(is_edit_conflict(&format!(
+            "error running server function: {}",
+            EDIT_CONFLICT_ERROR
+        )));
+        assert!(!is_edit_conflict("Amount is required"));
+    }
+}